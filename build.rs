@@ -7,6 +7,16 @@ fn main() -> anyhow::Result<()> {
     println!("cargo:rerun-if-changed=src/ui/vite.config.ts");
     println!("cargo:rerun-if-changed=src/ui/svelte.config.js");
 
+    // Reported by the /health endpoint.
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=DUCKHUB_GIT_COMMIT={commit}");
+
     if Command::new("pnpm").arg("--version").output().is_err() {
         anyhow::bail!("pnpm is not installed. Please install it to build the UI.");
     }
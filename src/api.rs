@@ -1,33 +1,232 @@
-use crate::core::{config::Config, graph::Graph};
+use crate::{
+    api::auth::Principal,
+    core::{
+        audit::{AuditChange, AuditLog},
+        config::{
+            Config, api_token::ApiToken, project::ServerConfig, secret::InvalidSecretReference,
+            validate_name,
+        },
+        ducklake::{CatalogUnavailable, DuckLake, DuckLakeCache, PoolExhausted, QueryTimeout},
+        graph::Graph,
+        masking::Masks,
+        pipeline::ActivePipeline,
+        query_cache::QueryCache,
+        query_history::QueryHistory,
+        template::rename_references,
+    },
+};
 use anyhow::Result;
 pub use axum::http::StatusCode;
 use axum::{
     Extension, Router,
+    http::{HeaderValue, header},
+    middleware::{from_fn, from_fn_with_state},
     response::{IntoResponse, Response},
 };
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tower_http::cors::{Any, CorsLayer};
+use tokio::sync::{Mutex, MutexGuard};
+use tokio_util::sync::CancellationToken;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use utoipa::{IntoParams, ToSchema};
+
+// The bundled UI is served from its own port, so it always needs CORS access.
+const UI_ORIGINS: [&str; 2] = ["http://localhost:8015", "http://127.0.0.1:8015"];
 
 mod adapter;
+mod audit;
+mod auth;
+mod bulk;
+mod cache;
 mod connection;
 mod dashboard;
+mod engine;
 mod graph;
+mod health;
+mod limits;
+mod maintenance;
+mod metrics;
 mod model;
+mod notification;
+mod openapi;
 mod pipeline;
+mod project;
 mod query;
+mod schedule;
+mod search;
+mod seed;
+mod share;
+mod snapshot;
+mod status;
+mod table;
+
+pub use limits::QueryLimiter;
+
+#[derive(Deserialize, ToSchema)]
+pub struct RenameRequest {
+    pub new_name: String,
+}
+
+// `?tag=` and `?group=` on list endpoints; both must match when given.
+#[derive(Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LabelFilter {
+    pub tag: Option<String>,
+    pub group: Option<String>,
+}
+
+impl LabelFilter {
+    pub fn matches(&self, tags: &[String], group: Option<&str>) -> bool {
+        self.tag.as_ref().is_none_or(|tag| tags.contains(tag))
+            && self
+                .group
+                .as_deref()
+                .is_none_or(|wanted| group == Some(wanted))
+    }
+}
+
+// `?unmasked=true` returns masked columns in the clear, to admin tokens only.
+#[derive(Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MaskingParams {
+    #[serde(default)]
+    pub unmasked: bool,
+}
+
+impl MaskingParams {
+    pub fn masks(
+        &self,
+        principal: Option<&Principal>,
+        masks: impl FnOnce() -> Result<Masks, Error>,
+    ) -> Result<Masks, Error> {
+        if !self.unmasked {
+            return masks();
+        }
+        match principal {
+            Some(principal) if principal.is_admin() => Ok(Masks::new()),
+            _ => Error::forbidden()
+                .with_message("'unmasked' is only allowed with an admin API token")
+                .build(),
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteParams {
+    // Also drops the table the adapter or model built.
+    #[serde(default)]
+    pub drop_table: bool,
+}
+
+impl DeleteParams {
+    // Locks the config for the delete, along with the DuckLake to drop the table from
+    // when asked to. The DuckLake is built before the lock is taken, since building
+    // one needs the config lock too.
+    pub async fn lock_config<'a>(
+        &self,
+        config: &'a Mutex<Config>,
+        ducklakes: &DuckLakeCache,
+    ) -> Result<(MutexGuard<'a, Config>, Option<Arc<DuckLake>>), Error> {
+        let ducklake = if self.drop_table {
+            Some(ducklakes.get(config).await?)
+        } else {
+            None
+        };
+        Ok((config.lock().await, ducklake))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    InternalError,
+    ServiceUnavailable,
+    ServerBusy,
+    TooManyQueries,
+    ValidationFailed,
+    InvalidName,
+    ConnectionNotFound,
+    ConnectionAlreadyExists,
+    ConnectionInUse,
+    ConnectionFailed,
+    AdapterNotFound,
+    AdapterAlreadyExists,
+    TargetTableConflict,
+    TableInUse,
+    SchemaMismatch,
+    ModelNotFound,
+    ModelAlreadyExists,
+    InvalidSql,
+    DependencyCycle,
+    QueryNotFound,
+    QueryAlreadyExists,
+    QueryInUse,
+    QueryFailed,
+    QueryTimeout,
+    ReadOnlyQuery,
+    MaskedColumn,
+    InvalidParameter,
+    DashboardNotFound,
+    DashboardAlreadyExists,
+    InvalidChart,
+    TableNotFound,
+    DiffTooLarge,
+    PipelineNotFound,
+    PipelineRunning,
+    CatalogUnavailable,
+    InvalidBundle,
+    ImportConflict,
+    InvalidPattern,
+    InvalidKind,
+}
+
+impl ErrorCode {
+    fn from_status(status_code: StatusCode) -> Self {
+        match status_code {
+            StatusCode::BAD_REQUEST => Self::BadRequest,
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::CONFLICT => Self::Conflict,
+            StatusCode::SERVICE_UNAVAILABLE => Self::ServiceUnavailable,
+            _ => Self::InternalError,
+        }
+    }
+}
+
+// The JSON body of every error response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub message: String,
+    #[schema(value_type = Object)]
+    pub details: Map<String, Value>,
+}
 
 #[derive(Debug)]
 pub struct Error {
     status_code: StatusCode,
+    code: ErrorCode,
     message: Option<String>,
+    details: Map<String, Value>,
+    retry_after: Option<u64>,
 }
 
 impl Error {
     pub fn new(status_code: StatusCode) -> Self {
         Self {
             status_code,
+            code: ErrorCode::from_status(status_code),
             message: None,
+            details: Map::new(),
+            retry_after: None,
         }
     }
 
@@ -36,6 +235,22 @@ impl Error {
         self
     }
 
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+
+    // Sent as a `Retry-After` header, in seconds.
+    pub fn with_retry_after(mut self, secs: u64) -> Self {
+        self.retry_after = Some(secs);
+        self
+    }
+
     pub fn bad_request() -> Self {
         Self::new(StatusCode::BAD_REQUEST)
     }
@@ -48,10 +263,30 @@ impl Error {
         Self::new(StatusCode::INTERNAL_SERVER_ERROR)
     }
 
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED)
+    }
+
+    pub fn forbidden() -> Self {
+        Self::new(StatusCode::FORBIDDEN)
+    }
+
     pub fn conflict() -> Self {
         Self::new(StatusCode::CONFLICT)
     }
 
+    pub fn too_many_requests() -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    pub fn request_timeout() -> Self {
+        Self::new(StatusCode::REQUEST_TIMEOUT)
+    }
+
+    pub fn service_unavailable() -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE)
+    }
+
     pub fn build<T>(self) -> Result<T, Self> {
         Err(self)
     }
@@ -60,16 +295,26 @@ impl Error {
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
         println!("Error: {:?}", self);
-        let body = serde_json::json!({
-            "message": self.message.unwrap_or_else(|| "An error occurred".to_string())
-        });
+        let body = ErrorResponse {
+            code: self.code,
+            message: self
+                .message
+                .unwrap_or_else(|| "An error occurred".to_string()),
+            details: self.details,
+        };
         let body_string = serde_json::to_string(&body).expect("failed parse response");
-        (
+        let mut response = (
             self.status_code,
             [("content-type", "application/json")],
             body_string,
         )
-            .into_response()
+            .into_response();
+        if let Some(secs) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -79,40 +324,300 @@ where
 {
     fn from(err: E) -> Self {
         let err: anyhow::Error = err.into();
-        Self {
-            status_code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: Some(err.to_string()),
+        // An exhausted DuckDB pool is temporary; tell the client to retry.
+        if let Some(busy) = err.chain().find_map(|e| e.downcast_ref::<PoolExhausted>()) {
+            return Self::service_unavailable()
+                .with_code(ErrorCode::ServerBusy)
+                .with_message(busy.to_string());
+        }
+        // The server keeps serving config endpoints without a catalog; data endpoints
+        // answer 503 until it is reachable again.
+        if let Some(unavailable) = err
+            .chain()
+            .find_map(|e| e.downcast_ref::<CatalogUnavailable>())
+        {
+            let mut error = Self::service_unavailable()
+                .with_code(ErrorCode::CatalogUnavailable)
+                .with_message(unavailable.to_string())
+                .with_detail("catalog", unavailable.catalog.as_str());
+            if let Some(host) = &unavailable.host {
+                error = error.with_detail("host", host.as_str());
+            }
+            return error;
+        }
+        // References are checked before they are saved; the caller has to fix them.
+        if err
+            .chain()
+            .any(|e| e.downcast_ref::<InvalidSecretReference>().is_some())
+        {
+            return Self::bad_request().with_message(format!("{err:#}"));
         }
+        if let Some(timeout) = err.chain().find_map(|e| e.downcast_ref::<QueryTimeout>()) {
+            return Self::request_timeout()
+                .with_code(ErrorCode::QueryTimeout)
+                .with_message(timeout.to_string());
+        }
+        Self::internal_server_error().with_message(err.to_string())
+    }
+}
+
+// Renaming an adapter or model rewrites the source() or ref() calls to it in other
+// models. Models that read its table by name have to be changed by hand first,
+// since the rename would break them.
+pub fn renamed_references(
+    config: &Config,
+    function: &str,
+    from: &str,
+    to: &str,
+    table: Option<&str>,
+) -> Result<Vec<(String, String)>, Error> {
+    let renamed = rename_references(config, function, from, to, table);
+    if !renamed.by_table.is_empty() {
+        return Error::conflict()
+            .with_code(ErrorCode::TableInUse)
+            .with_message(format!(
+                "'{from}' is read by table name in models: {}; use {{{{ {function}('{from}') }}}} there before renaming it",
+                renamed.by_table.join(", ")
+            ))
+            .with_detail("models", renamed.by_table)
+            .build();
+    }
+    Ok(renamed.rewritten)
+}
+
+// Returns the audit changes for the models it rewrote.
+pub fn save_renamed_references(
+    config: &mut Config,
+    rewritten: Vec<(String, String)>,
+) -> Result<Vec<AuditChange>, Error> {
+    let mut changes = Vec::new();
+    for (name, sql) in rewritten {
+        let before = config.models[&name].clone();
+        let mut model = before.clone();
+        model.sql = sql;
+        config.upsert_model(&name, &model)?.save()?;
+        let change = AuditChange::new("models", &name, Some(&before), Some(&model));
+        changes.push(change);
     }
+    Ok(changes)
 }
 
-pub async fn main(config: Config) -> Result<()> {
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
+// Called by every handler that creates or renames a named resource.
+pub fn ensure_valid_name(name: &str) -> Result<(), Error> {
+    validate_name(name).map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::InvalidName)
+            .with_message(e.to_string())
+            .with_detail("name", name)
+    })
+}
+
+fn cors_layer(server: &ServerConfig) -> CorsLayer {
+    let origins: Vec<HeaderValue> = UI_ORIGINS
+        .into_iter()
+        .chain(server.allowed_origins.iter().map(String::as_str))
+        .filter_map(|origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        // Embedding pages read it to tell whether the snapshot changed.
+        .expose_headers([header::ETAG])
+}
+
+// `token` is `None` when the server was started with `--no-auth`.
+pub fn app(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+    active: Arc<ActivePipeline>,
+    ducklakes: Arc<DuckLakeCache>,
+    token: Option<ApiToken>,
+    server: &ServerConfig,
+) -> Router {
+    let cors = cors_layer(server);
+    let base_path = server.base_path();
 
     let routes = Router::new()
         .merge(adapter::routes())
+        .merge(audit::routes())
+        .merge(bulk::routes())
+        .merge(cache::routes())
         .merge(connection::routes())
         .merge(dashboard::router())
+        .merge(engine::routes())
+        .merge(maintenance::routes())
         .merge(model::routes())
+        .merge(notification::routes())
         .merge(query::routes())
         .merge(graph::routes())
-        .merge(pipeline::routes());
+        .merge(pipeline::routes())
+        .merge(project::routes())
+        .merge(schedule::routes())
+        .merge(search::routes())
+        .merge(seed::routes())
+        .merge(share::routes())
+        .merge(snapshot::routes())
+        .merge(status::routes())
+        .merge(table::routes());
 
-    let graph = Graph::load(&config.project_dir).await?;
+    let routes = match token {
+        Some(token) => routes.layer(from_fn_with_state(Arc::new(token), auth::require_token)),
+        None => routes,
+    };
+    // Share links are the only API routes reachable without the token.
+    let routes = routes
+        .merge(share::public_routes())
+        .route_layer(from_fn(metrics::track_requests));
 
-    let app = Router::new()
-        .nest("/api", routes)
+    Router::new()
+        .merge(health::routes(&base_path))
+        .merge(metrics::routes(&base_path))
+        .merge(openapi::routes(&base_path))
+        .nest(&format!("{base_path}/api"), routes)
         .layer(cors)
-        .layer(Extension(Arc::new(Mutex::new(graph))))
-        .layer(Extension(Arc::new(Mutex::new(config))));
+        .layer(Extension(graph))
+        .layer(Extension(config))
+        .layer(Extension(active))
+        .layer(Extension(ducklakes))
+        .layer(Extension(Arc::new(QueryCache::default())))
+        .layer(Extension(Arc::new(QueryHistory::default())))
+        .layer(Extension(Arc::new(AuditLog::default())))
+        .layer(Extension(Arc::new(QueryLimiter::default())))
+        .layer(Extension(Arc::new(health::Health::default())))
+}
 
-    let port = 3015;
-    let listener = tokio::net::TcpListener::bind(format!("localhost:{port}")).await?;
+pub async fn main(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+    active: Arc<ActivePipeline>,
+    ducklakes: Arc<DuckLakeCache>,
+    token: Option<ApiToken>,
+    host: &str,
+    port: u16,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    let server = config.lock().await.project.server.clone();
+    let app = app(config, graph, active, ducklakes, token, &server);
 
-    axum::serve(listener, app).await?;
+    let listener = tokio::net::TcpListener::bind((host, port)).await?;
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{Method, header};
+    use axum_test::TestServer;
+
+    fn setup_server(server: &ServerConfig) -> (tempfile::TempDir, TestServer) {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(
+            Arc::new(Mutex::new(Config::new(dir.path().to_path_buf()))),
+            Arc::new(Mutex::new(Graph::new(dir.path()))),
+            Arc::default(),
+            Arc::default(),
+            None,
+            server,
+        );
+        (dir, TestServer::new(app).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight() {
+        let server_config = ServerConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..Default::default()
+        };
+        let (_dir, server) = setup_server(&server_config);
+
+        let preflight = |origin: &'static str| {
+            server
+                .method(Method::OPTIONS, "/api/pipelines")
+                .add_header(header::ORIGIN, HeaderValue::from_static(origin))
+                .add_header(
+                    header::ACCESS_CONTROL_REQUEST_METHOD,
+                    HeaderValue::from_static("GET"),
+                )
+        };
+
+        let response = preflight("https://app.example.com").await;
+        response.assert_status_ok();
+        assert_eq!(
+            response.header(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            "https://app.example.com"
+        );
+
+        let response = preflight("http://localhost:8015").await;
+        assert_eq!(
+            response.header(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            "http://localhost:8015"
+        );
+
+        let response = preflight("https://evil.example.com").await;
+        assert!(
+            response
+                .headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_metrics() {
+        crate::core::metrics::handle();
+        let (_dir, server) = setup_server(&ServerConfig::default());
+
+        server.get("/api/pipelines").await.assert_status_ok();
+
+        let response = server.get("/metrics").await;
+        response.assert_status_ok();
+        let scraped = response.text();
+        assert!(scraped.contains(
+            "duckhub_http_requests_total{method=\"GET\",path=\"/api/pipelines\",status=\"200\"}"
+        ));
+        assert!(scraped.contains("duckhub_http_request_duration_seconds_bucket"));
+    }
+
+    #[tokio::test]
+    async fn test_routes_under_base_path() {
+        let server_config = ServerConfig {
+            base_path: Some("/duckhub/".to_string()),
+            ..Default::default()
+        };
+        let (_dir, server) = setup_server(&server_config);
+
+        server.get("/duckhub/health").await.assert_status_ok();
+        server
+            .get("/duckhub/api/pipelines")
+            .await
+            .assert_status_ok();
+        server
+            .get("/api/pipelines")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_error_body_shape() {
+        let (_dir, server) = setup_server(&ServerConfig::default());
+
+        let response = server.get("/api/connections/missing").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONNECTION_NOT_FOUND");
+        assert!(body["message"].is_string());
+        assert!(body["details"].is_object());
+
+        let response = server.get("/api/pipelines/current/events").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "PIPELINE_NOT_FOUND");
+    }
+}
@@ -1,18 +1,35 @@
 use crate::{
-    api::Error,
+    api::{
+        DeleteParams, Error, ErrorCode, LabelFilter, RenameRequest,
+        audit::record_changes,
+        auth::Principal,
+        ensure_valid_name,
+        graph::LastRun,
+        model::{ensure_relationships_resolve, reject_cycle},
+        renamed_references, save_renamed_references,
+    },
     core::{
-        adapter::Adapter,
+        adapter::{
+            Adapter, SchemaMismatch,
+            database::ColumnInfo,
+            inference::{DEFAULT_SAMPLE_ROWS, InferenceReport},
+        },
+        audit::{AuditChange, AuditLog},
         config::{
             Config,
-            adapter::{AdapterConfig, AdapterSource, ColumnConfig},
+            adapter::{AdapterConfig, AdapterSource, ColumnConfig, FormatConfig},
+            project::ConnectionConfig,
         },
+        ducklake::{DuckLakeCache, TypedResult},
         graph::Graph,
+        impact::{ImpactReport, column_impact},
+        maintenance::drop_unowned_table,
     },
 };
 use anyhow::Result;
 use axum::{
     Extension, Router,
-    extract::Path,
+    extract::{Path, Query},
     http::StatusCode,
     response::Json,
     routing::{get, post},
@@ -20,32 +37,110 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct AdapterSummary {
     pub name: String,
     pub description: Option<String>,
     pub connection: String,
     pub source_type: String,
+    pub tags: Vec<String>,
+    pub group: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AdapterResponse {
+    #[serde(flatten)]
+    pub config: AdapterConfig,
+    pub last_run: Option<LastRun>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateAdapterRequest {
     pub name: String,
     pub config: AdapterConfig,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct TestSchemaRequest {
     pub connection: String,
     pub source: AdapterSource,
     pub columns: Vec<ColumnConfig>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+pub struct PreviewRequest {
+    pub connection: String,
+    pub source: AdapterSource,
+    #[serde(default)]
+    pub columns: Vec<ColumnConfig>,
+    #[serde(default = "default_preview_limit")]
+    pub limit: usize,
+}
+
+fn default_preview_limit() -> usize {
+    20
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImpactParams {
+    pub column: String,
+}
+
+// Files listed by `/adapters/{name}/resolved`; `total` counts every match.
+const MAX_RESOLVED_FILES: usize = 100;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ResolvedAdapter {
+    pub name: String,
+    pub config: AdapterConfig,
+    pub connection: ConnectionConfig,
+    pub location: String,
+    pub target_table: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    // The file format with the project's `defaults.file_format` filled in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<FormatConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<MatchedFiles>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MatchedFiles {
+    pub files: Vec<String>,
+    pub total: usize,
+    // Set instead of failing the request, so the rest still shows when listing fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct GetSchemaRequest {
     pub connection: String,
     pub source: AdapterSource,
+    // Also report how the column types were inferred.
+    #[serde(default)]
+    pub inference: bool,
+    // Rows read for the inference report; defaults to 1000.
+    #[serde(default)]
+    pub sample_rows: Option<usize>,
+}
+
+// The bare column list unless the request asks for `inference`.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum GetSchemaResponse {
+    Columns(Vec<ColumnInfo>),
+    WithInference {
+        columns: Vec<ColumnInfo>,
+        // Only for file sources, whose column types are detected rather than declared.
+        inference: Option<InferenceReport>,
+    },
 }
 
 pub fn routes() -> Router {
@@ -55,12 +150,40 @@ pub fn routes() -> Router {
             "/adapters/{name}",
             get(get_adapter).put(update_adapter).delete(delete_adapter),
         )
+        .route("/adapters/{name}/rename", post(rename_adapter))
+        .route("/adapters/{name}/impact", get(get_impact))
+        .route("/adapters/{name}/resolved", get(get_resolved_adapter))
         .route("/adapters/test-schema", post(test_schema))
         .route("/adapters/get-schema", post(get_schema))
+        .route("/adapters/preview", post(preview))
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_adapters,
+    create_adapter,
+    get_adapter,
+    update_adapter,
+    delete_adapter,
+    rename_adapter,
+    get_impact,
+    get_resolved_adapter,
+    test_schema,
+    get_schema,
+    preview
+))]
+pub struct AdapterApi;
+
+#[utoipa::path(
+    get,
+    path = "/adapters",
+    tag = "adapters",
+    params(LabelFilter),
+    responses((status = 200, description = "Adapters, sorted by name", body = Vec<AdapterSummary>))
+)]
 async fn list_adapters(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Query(filter): Query<LabelFilter>,
 ) -> Result<Json<Vec<AdapterSummary>>, Error> {
     let mut adapters: Vec<AdapterSummary> = config
         .lock()
@@ -68,6 +191,7 @@ async fn list_adapters(
         .adapters
         .clone()
         .into_iter()
+        .filter(|(_, config)| filter.matches(&config.tags, config.group.as_deref()))
         .map(|(name, config)| {
             let source_type = match &config.source {
                 AdapterSource::File { .. } => "file".to_string(),
@@ -78,6 +202,8 @@ async fn list_adapters(
                 description: config.description,
                 connection: config.connection,
                 source_type,
+                tags: config.tags,
+                group: config.group,
             }
         })
         .collect();
@@ -86,70 +212,360 @@ async fn list_adapters(
     Ok(Json(adapters))
 }
 
+#[utoipa::path(
+    get,
+    path = "/adapters/{name}",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name")),
+    responses((status = 200, description = "Adapter config and last run", body = AdapterResponse))
+)]
 async fn get_adapter(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
     Path(name): Path<String>,
-) -> Result<Json<AdapterConfig>, Error> {
-    if let Some(adapter_config) = config.lock().await.adapters.get(&name) {
-        Ok(Json(adapter_config.clone()))
+) -> Result<Json<AdapterResponse>, Error> {
+    let adapter_config = config.lock().await.adapters.get(&name).cloned();
+    if let Some(adapter_config) = adapter_config {
+        Ok(Json(AdapterResponse {
+            config: adapter_config,
+            last_run: LastRun::of(&*graph.lock().await, &name),
+        }))
     } else {
-        Error::not_found().build()
+        Error::not_found()
+            .with_code(ErrorCode::AdapterNotFound)
+            .build()
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/adapters/{name}/impact",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name"), ImpactParams),
+    responses((status = 200, description = "Resources that read the column", body = ImpactReport))
+)]
+async fn get_impact(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Path(name): Path<String>,
+    Query(params): Query<ImpactParams>,
+) -> Result<Json<ImpactReport>, Error> {
+    let config = config.lock().await;
+    let Some(adapter) = config.adapters.get(&name) else {
+        return Error::not_found()
+            .with_code(ErrorCode::AdapterNotFound)
+            .build();
+    };
+
+    let graph = graph.lock().await;
+    let table = adapter.table_name(&name);
+    Ok(Json(column_impact(&config, &graph, table, &params.column)))
+}
+
+// The adapter as an import sees it: connection inlined with secrets masked, paths
+// resolved against the project, and the files its pattern matches right now.
+#[utoipa::path(
+    get,
+    path = "/adapters/{name}/resolved",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name")),
+    responses((status = 200, description = "Adapter as an import sees it", body = ResolvedAdapter))
+)]
+async fn get_resolved_adapter(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Path(name): Path<String>,
+) -> Result<Json<ResolvedAdapter>, Error> {
+    let (adapter_config, effective, connections) = {
+        let config = config.lock().await;
+        let Some(adapter_config) = config.adapters.get(&name).cloned() else {
+            return Error::not_found()
+                .with_code(ErrorCode::AdapterNotFound)
+                .build();
+        };
+        ensure_connection_exists(&config, &adapter_config)?;
+        let effective = config.with_format_defaults(adapter_config.clone());
+        (
+            adapter_config,
+            effective,
+            config.project.connections.clone(),
+        )
+    };
+    let connection = &connections[&adapter_config.connection];
+
+    let mut resolved = ResolvedAdapter {
+        name: name.clone(),
+        config: adapter_config.clone(),
+        connection: connection.masked(),
+        location: connection.location(),
+        target_table: adapter_config.table_name(&name).to_string(),
+        source_table: None,
+        pattern: None,
+        format: None,
+        files: None,
+    };
+
+    match &effective.source {
+        AdapterSource::Database { table_name, .. } => {
+            resolved.source_table = Some(table_name.clone());
+        }
+        AdapterSource::File { file, format } => {
+            resolved.format = Some(format.clone());
+            let ducklake = ducklakes.get(&config).await?;
+            let adapter = Adapter::new(effective.clone(), ducklake);
+            let file_adapter = adapter.file_adapter(&adapter_config.connection, &connections)?;
+            resolved.pattern = Some(file_adapter.resolve_pattern(&file.path));
+            resolved.files = Some(match file_adapter.list_files(&file.path).await {
+                Ok(mut files) => {
+                    let total = files.len();
+                    files.sort();
+                    files.truncate(MAX_RESOLVED_FILES);
+                    MatchedFiles {
+                        files,
+                        total,
+                        error: None,
+                    }
+                }
+                Err(e) => MatchedFiles {
+                    files: vec![],
+                    total: 0,
+                    error: Some(format!("{e:#}")),
+                },
+            });
+        }
+    }
+
+    Ok(Json(resolved))
+}
+
+#[utoipa::path(
+    post,
+    path = "/adapters",
+    tag = "adapters",
+    request_body = CreateAdapterRequest,
+    responses((status = 200, description = "Adapter created"))
+)]
 async fn create_adapter(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Json(adapter): Json<CreateAdapterRequest>,
 ) -> Result<(), Error> {
+    ensure_valid_name(&adapter.name)?;
+    ensure_selected_columns_exist(&config, &ducklakes, &adapter.name, &adapter.config).await?;
+
     let mut config = config.lock().await;
 
     if config.adapters.contains_key(&adapter.name) {
-        return Error::conflict().build();
+        return Error::conflict()
+            .with_code(ErrorCode::AdapterAlreadyExists)
+            .build();
     }
+    ensure_connection_exists(&config, &adapter.config)?;
+    ensure_valid_adapter(&adapter.name, &adapter.config)?;
+    ensure_target_table_free(&config, &adapter.name, &adapter.config)?;
+    ensure_relationships_resolve(&config, &adapter.config.relationships)?;
 
+    let dependencies = config.relationship_nodes(&adapter.name, &adapter.config.relationships);
+    let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
     let mut graph = graph.lock().await;
-    graph.create_node(&adapter.name, &[]);
+    let mut candidate = graph.clone();
+    candidate.create_node(&adapter.name, &deps);
+    reject_cycle(&candidate)?;
+    *graph = candidate;
     graph.save().await?;
 
     let adapter_file = config.upsert_adapter(&adapter.name, &adapter.config)?;
     adapter_file.save()?;
+    let change = AuditChange::new("adapters", &adapter.name, None, Some(&adapter.config));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
+#[utoipa::path(
+    put,
+    path = "/adapters/{name}",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name")),
+    request_body = AdapterConfig,
+    responses((status = 200, description = "Adapter updated"))
+)]
 async fn update_adapter(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
     Json(adapter): Json<AdapterConfig>,
 ) -> Result<(), Error> {
+    ensure_selected_columns_exist(&config, &ducklakes, &name, &adapter).await?;
+
     let mut config = config.lock().await;
 
-    if !config.adapters.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.adapters.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::AdapterNotFound)
+            .build();
     };
+    ensure_connection_exists(&config, &adapter)?;
+    ensure_valid_adapter(&name, &adapter)?;
+    ensure_target_table_free(&config, &name, &adapter)?;
+    ensure_relationships_resolve(&config, &adapter.relationships)?;
 
+    let dependencies = config.relationship_nodes(&name, &adapter.relationships);
+    let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
     let mut graph = graph.lock().await;
+    let mut candidate = graph.clone();
+    candidate.update_dependencies(&name, &deps);
+    reject_cycle(&candidate)?;
+    *graph = candidate;
     graph.update_node(&name);
     graph.save().await?;
 
     let adapter_file = config.upsert_adapter(&name, &adapter)?;
     adapter_file.save()?;
+    let change = AuditChange::new("adapters", &name, Some(&before), Some(&adapter));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
+pub fn ensure_connection_exists(config: &Config, adapter: &AdapterConfig) -> Result<(), Error> {
+    if !config.project.connections.contains_key(&adapter.connection) {
+        return Error::bad_request()
+            .with_code(ErrorCode::ConnectionNotFound)
+            .with_message(format!(
+                "Connection '{}' does not exist",
+                adapter.connection
+            ))
+            .with_detail("connection", adapter.connection.as_str())
+            .build();
+    }
+
+    Ok(())
+}
+
+pub fn ensure_valid_adapter(name: &str, adapter: &AdapterConfig) -> Result<(), Error> {
+    adapter.validate().map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(e.to_string())
+            .with_detail("adapter", name)
+    })
+}
+
+// Two adapters importing into one table would overwrite each other.
+pub fn ensure_target_table_free(
+    config: &Config,
+    name: &str,
+    adapter: &AdapterConfig,
+) -> Result<(), Error> {
+    let table = adapter.table_name(name);
+    let owner = config
+        .adapters
+        .iter()
+        .find(|(other, other_adapter)| *other != name && other_adapter.table_name(other) == table);
+
+    if let Some((owner, _)) = owner {
+        return Error::conflict()
+            .with_code(ErrorCode::TargetTableConflict)
+            .with_message(format!(
+                "Adapter '{owner}' already imports into table '{table}'"
+            ))
+            .with_detail("adapter", owner.as_str())
+            .with_detail("target_table", table)
+            .build();
+    }
+
+    Ok(())
+}
+
+// Schema mismatches carry the offending columns so the UI can point at them.
+fn schema_error(e: anyhow::Error) -> Error {
+    let error = Error::bad_request().with_message(e.to_string());
+    match e.downcast_ref::<SchemaMismatch>() {
+        Some(SchemaMismatch::MissingColumns { columns, .. }) => error
+            .with_code(ErrorCode::SchemaMismatch)
+            .with_detail("columns", columns.clone()),
+        Some(SchemaMismatch::TypeMismatch {
+            column,
+            expected,
+            found,
+        }) => error
+            .with_code(ErrorCode::SchemaMismatch)
+            .with_detail("column", column.as_str())
+            .with_detail("expected", expected.as_str())
+            .with_detail("found", found.as_str()),
+        None => error.with_code(ErrorCode::ValidationFailed),
+    }
+}
+
+pub async fn ensure_selected_columns_exist(
+    config: &Arc<Mutex<Config>>,
+    ducklakes: &DuckLakeCache,
+    name: &str,
+    adapter: &AdapterConfig,
+) -> Result<(), Error> {
+    let selects_columns = matches!(
+        &adapter.source,
+        AdapterSource::Database { columns: Some(columns), .. } if !columns.is_empty()
+    );
+    if !selects_columns && adapter.dedupe.is_none() {
+        return Ok(());
+    }
+    // Checked as the adapter will run, with the project's format defaults applied.
+    let (connections, effective) = {
+        let config = config.lock().await;
+        let effective = config.with_format_defaults(adapter.clone());
+        (config.project.connections.clone(), effective)
+    };
+    if !connections.contains_key(&adapter.connection) {
+        return Ok(());
+    }
+
+    // Without a catalog the columns can't be checked; saving the adapter still works
+    // so the project can be edited while the catalog is down.
+    let Ok(ducklake) = ducklakes.get(config).await else {
+        return Ok(());
+    };
+    let adapter = Adapter::new(effective, ducklake);
+    adapter
+        .validate_selection(&connections)
+        .map_err(|e| schema_error(e).with_detail("adapter", name))?;
+    adapter
+        .validate_dedupe(&connections)
+        .await
+        .map_err(|e| schema_error(e).with_detail("adapter", name))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/adapters/{name}",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name"), DeleteParams),
+    responses((status = 204, description = "Adapter deleted"))
+)]
 async fn delete_adapter(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
+    Query(params): Query<DeleteParams>,
 ) -> Result<StatusCode, Error> {
-    let mut config = config.lock().await;
+    let (mut config, ducklake) = params.lock_config(&config, &ducklakes).await?;
 
-    if !config.adapters.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.adapters.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::AdapterNotFound)
+            .build();
     };
+    let table = before.table_name(&name).to_string();
 
     let mut graph = graph.lock().await;
     graph.delete_node(&name);
@@ -157,90 +573,270 @@ async fn delete_adapter(
 
     let adapter_file = config.delete_adapter(&name)?;
     adapter_file.save()?;
+    let change = AuditChange::new("adapters", &name, Some(&before), None);
+    record_changes(&audit, &config, principal.as_deref(), [change]);
+
+    if let Some(ducklake) = ducklake {
+        drop_unowned_table(&ducklake, &config, &table)?;
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/adapters/{name}/rename",
+    tag = "adapters",
+    params(("name" = String, Path, description = "Adapter name")),
+    request_body = RenameRequest,
+    responses((status = 200, description = "Adapter renamed"))
+)]
+async fn rename_adapter(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    Json(request): Json<RenameRequest>,
+) -> Result<(), Error> {
+    ensure_valid_name(&request.new_name)?;
+    let mut config = config.lock().await;
+
+    let Some(adapter) = config.adapters.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::AdapterNotFound)
+            .build();
+    };
+    if config.adapters.contains_key(&request.new_name)
+        || config.models.contains_key(&request.new_name)
+    {
+        return Error::conflict()
+            .with_code(ErrorCode::AdapterAlreadyExists)
+            .build();
+    }
+    // Without a target table, renaming the adapter also renames its table.
+    let renames_table = adapter.target_table.is_none();
+    if renames_table {
+        ensure_target_table_free(&config, &request.new_name, &adapter)?;
+    }
+    let rewritten = renamed_references(
+        &config,
+        "source",
+        &name,
+        &request.new_name,
+        renames_table.then_some(name.as_str()),
+    )?;
+
+    // The file moves first, so a failed move leaves the graph and models as they were.
+    let adapter_file = config.rename_adapter(&name, &request.new_name)?;
+    if let Err(e) = adapter_file.save() {
+        config.rename_adapter(&request.new_name, &name)?;
+        return Err(e.into());
+    }
+    let changes = AuditChange::renamed("adapters", &name, &request.new_name, &adapter);
+    record_changes(&audit, &config, principal.as_deref(), changes);
+    let changes = save_renamed_references(&mut config, rewritten)?;
+    record_changes(&audit, &config, principal.as_deref(), changes);
+
+    let mut graph = graph.lock().await;
+    graph.rename_node(&name, &request.new_name);
+    graph.save().await?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/adapters/test-schema",
+    tag = "adapters",
+    request_body = TestSchemaRequest,
+    responses((status = 200, description = "Source matches the columns"))
+)]
 async fn test_schema(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
     Json(request): Json<TestSchemaRequest>,
 ) -> Result<(), Error> {
-    let config = config.lock().await;
+    let connections = config.lock().await.project.connections.clone();
 
-    if !config.project.connections.contains_key(&request.connection) {
+    if !connections.contains_key(&request.connection) {
         return Err(Error::bad_request()
-            .with_message(format!("Connection '{}' not found", request.connection)));
+            .with_code(ErrorCode::ConnectionNotFound)
+            .with_message(format!("Connection '{}' not found", request.connection))
+            .with_detail("connection", request.connection.as_str()));
     }
 
     let test_adapter_config = AdapterConfig {
         connection: request.connection.clone(),
+        target_table: None,
+        dedupe: None,
         description: Some("Test schema validation".to_string()),
         source: request.source.clone(),
         columns: request.columns.clone(),
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
 
-    let ducklake = Arc::new(
-        crate::core::ducklake::DuckLake::from_config(&config)
-            .await
-            .map_err(|e| {
-                Error::internal_server_error()
-                    .with_message(format!("Failed to initialize DuckLake: {}", e))
-            })?,
-    );
+    let ducklake = ducklakes.get(&config).await?;
+    let test_adapter_config = config
+        .lock()
+        .await
+        .with_format_defaults(test_adapter_config);
     let test_adapter = Adapter::new(test_adapter_config, ducklake);
 
     test_adapter
-        .validate_schema(&config.project.connections)
+        .validate_schema(&connections)
         .await
-        .map_err(|e| Error::bad_request().with_message(e.to_string()))?;
+        .map_err(schema_error)?;
 
     Ok(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/adapters/get-schema",
+    tag = "adapters",
+    request_body = GetSchemaRequest,
+    responses((status = 200, description = "Source columns and how their types were inferred", body = GetSchemaResponse))
+)]
 async fn get_schema(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
     Json(request): Json<GetSchemaRequest>,
-) -> Result<Json<Vec<crate::core::adapter::database::ColumnInfo>>, Error> {
-    let config = config.lock().await;
+) -> Result<Json<GetSchemaResponse>, Error> {
+    let connections = config.lock().await.project.connections.clone();
 
-    if !config.project.connections.contains_key(&request.connection) {
+    if !connections.contains_key(&request.connection) {
         return Err(Error::bad_request()
-            .with_message(format!("Connection '{}' not found", request.connection)));
+            .with_code(ErrorCode::ConnectionNotFound)
+            .with_message(format!("Connection '{}' not found", request.connection))
+            .with_detail("connection", request.connection.as_str()));
     }
 
     let test_adapter_config = AdapterConfig {
         connection: request.connection.clone(),
+        target_table: None,
+        dedupe: None,
         description: Some("Get schema".to_string()),
         source: request.source.clone(),
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
 
-    let ducklake = Arc::new(
-        crate::core::ducklake::DuckLake::from_config(&config)
-            .await
-            .map_err(|e| {
-                Error::internal_server_error()
-                    .with_message(format!("Failed to initialize DuckLake: {}", e))
-            })?,
-    );
+    let ducklake = ducklakes.get(&config).await?;
+    let test_adapter_config = config
+        .lock()
+        .await
+        .with_format_defaults(test_adapter_config);
     let test_adapter = Adapter::new(test_adapter_config, ducklake);
 
-    let schema = test_adapter
-        .get_schema(&config.project.connections)
+    let read_failed = |e: anyhow::Error| {
+        Error::bad_request()
+            .with_code(ErrorCode::ConnectionFailed)
+            .with_message(e.to_string())
+    };
+    let columns = test_adapter
+        .get_schema(&connections)
+        .await
+        .map_err(read_failed)?;
+    if !request.inference {
+        return Ok(Json(GetSchemaResponse::Columns(columns)));
+    }
+
+    let inference = test_adapter
+        .inference_report(
+            &connections,
+            request.sample_rows.unwrap_or(DEFAULT_SAMPLE_ROWS),
+        )
+        .await
+        .map_err(read_failed)?;
+
+    Ok(Json(GetSchemaResponse::WithInference {
+        columns,
+        inference,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/adapters/preview",
+    tag = "adapters",
+    request_body = PreviewRequest,
+    responses((status = 200, description = "First rows of the source", body = TypedResult))
+)]
+async fn preview(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Json(request): Json<PreviewRequest>,
+) -> Result<Json<TypedResult>, Error> {
+    let connections = config.lock().await.project.connections.clone();
+
+    if !connections.contains_key(&request.connection) {
+        return Err(Error::bad_request()
+            .with_code(ErrorCode::ConnectionNotFound)
+            .with_message(format!("Connection '{}' not found", request.connection))
+            .with_detail("connection", request.connection.as_str()));
+    }
+
+    let preview_adapter_config = AdapterConfig {
+        connection: request.connection.clone(),
+        target_table: None,
+        dedupe: None,
+        description: Some("Preview".to_string()),
+        source: request.source.clone(),
+        columns: request.columns.clone(),
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
+    };
+
+    let ducklake = ducklakes.get(&config).await?;
+    let preview_adapter_config = config
+        .lock()
         .await
-        .map_err(|e| Error::bad_request().with_message(e.to_string()))?;
+        .with_format_defaults(preview_adapter_config);
+    let preview_adapter = Adapter::new(preview_adapter_config, ducklake);
 
-    Ok(Json(schema))
+    let result = preview_adapter
+        .preview(&connections, request.limit)
+        .await
+        .map_err(|e| {
+            Error::bad_request()
+                .with_code(ErrorCode::ConnectionFailed)
+                .with_message(format!("{e:#}"))
+        })?;
+
+    Ok(Json(result))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::adapter::inference::InferenceIssue;
     use crate::{
         core::{
-            adapter::test_helpers::write_test_file,
+            adapter::test_helpers::{create_test_sqlite_db, write_test_file},
             config::{
-                adapter::{FileConfig, FormatConfig},
+                adapter::{FileConfig, FormatConfig, FormatDefaults},
+                model::{Materialization, ModelConfig},
                 project::ConnectionConfig,
             },
         },
@@ -249,10 +845,25 @@ mod tests {
     use anyhow::Result;
     use serde_json::json;
 
+    async fn save_test_connection(test: &TestManager) -> Result<()> {
+        let mut config = test.config().await;
+        let mut project_config = config.project.clone();
+        project_config.connections.insert(
+            "test_connection".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: test.directory().to_string_lossy().to_string(),
+            },
+        );
+        config.add_project_setting(&project_config)?.save()?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_adapter() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
 
         // Create request
         let new_adapter = json!({
@@ -304,28 +915,176 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_import_with_project_format_defaults() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| {
+            routes()
+                .merge(crate::api::graph::routes())
+                .merge(crate::api::pipeline::routes())
+        });
+        write_test_file(
+            test.directory(),
+            "people.csv",
+            "id;name\n1;ann\n1;ann\n2;bob\n",
+        )?;
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.project.clone();
+            project_config.connections.insert(
+                "test_connection".to_string(),
+                ConnectionConfig::LocalFile {
+                    base_path: test.directory().to_string_lossy().to_string(),
+                },
+            );
+            project_config.defaults.file_format.insert(
+                "csv".to_string(),
+                FormatDefaults {
+                    delimiter: Some(";".to_string()),
+                    ..Default::default()
+                },
+            );
+            config.save_project(project_config)?;
+        }
+
+        // The dedupe key is only a column once the default delimiter splits the header.
+        let response = server
+            .post("/adapters")
+            .json(&json!({
+                "name": "people",
+                "config": {
+                    "connection": "test_connection",
+                    "dedupe": { "unique_key": ["id"], "order_by": "name" },
+                    "source": {
+                        "type": "file",
+                        "file": { "path": "people.csv" },
+                        "format": { "type": "csv", "has_header": true }
+                    }
+                }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        server
+            .post("/pipeline/run-node")
+            .json(&json!({ "node_name": "people" }))
+            .await
+            .assert_status_ok();
+
+        let mut row_count = None;
+        for _ in 0..60 {
+            let response = server.get("/tables/people/stats").await;
+            response.assert_status_ok();
+            let stats: crate::api::graph::TableStatsResponse = response.json();
+            if stats.row_count.is_some() {
+                row_count = stats.row_count;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+        assert_eq!(row_count, Some(2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_adapter_target_table_conflict() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+
+        let adapter = |name: &str, target_table: Option<&str>| {
+            json!({
+                "name": name,
+                "config": {
+                    "connection": "test_connection",
+                    "target_table": target_table,
+                    "source": {
+                        "type": "file",
+                        "file": { "path": "users.csv" },
+                        "format": { "type": "csv" }
+                    },
+                    "columns": []
+                }
+            })
+        };
+
+        server
+            .post("/adapters")
+            .json(&adapter("users", Some("raw_users")))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post("/adapters")
+            .json(&adapter("users_copy", Some("raw_users")))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "TARGET_TABLE_CONFLICT");
+        assert_eq!(body["details"]["adapter"], "users");
+        assert_eq!(body["details"]["target_table"], "raw_users");
+
+        // An adapter without a target table imports into a table of its own name.
+        server
+            .post("/adapters")
+            .json(&adapter("raw_users", None))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        server
+            .post("/adapters")
+            .json(&adapter("users_copy", Some("users")))
+            .await
+            .assert_status_ok();
+        let adapter_config: AdapterConfig = server.get("/adapters/users_copy").await.json();
+        assert_eq!(adapter_config.target_table, Some("users".to_string()));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_update_adapter() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
 
         let original_adapter = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Original adapter".to_string()),
             source: AdapterSource::File {
                 file: FileConfig {
                     path: "original.csv".to_string(),
                     compression: None,
                     max_batch_size: None,
+                    max_batch_files: None,
+                    incremental: false,
+                    import_mode: None,
                 },
                 format: FormatConfig {
                     ty: "csv".to_string(),
                     delimiter: Some(",".to_string()),
                     null_value: None,
                     has_header: Some(true),
+                    sheet: None,
+                    columns_spec: vec![],
+                    quote: None,
+                    escape: None,
+                    date_format: None,
+                    timestamp_format: None,
                 },
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         // Create adapter directly
@@ -394,6 +1153,188 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_adapter_nonexistent_connection() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let new_adapter = json!({
+            "name": "orphan_adapter",
+            "config": {
+                "connection": "missing_connection",
+                "source": {
+                    "type": "database",
+                    "table_name": "users"
+                },
+                "columns": []
+            }
+        });
+
+        let response = server.post("/adapters").json(&new_adapter).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONNECTION_NOT_FOUND");
+        assert_eq!(body["details"]["connection"], "missing_connection");
+
+        assert!(!test.graph().await.has_node("orphan_adapter"));
+        assert!(!test.config().await.adapters.contains_key("orphan_adapter"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_adapter_unsupported_format() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+
+        let new_adapter = json!({
+            "name": "xml_adapter",
+            "config": {
+                "connection": "test_connection",
+                "source": {
+                    "type": "file",
+                    "file": { "path": "data.xml" },
+                    "format": { "type": "xml" }
+                },
+                "columns": []
+            }
+        });
+
+        let response = server.post("/adapters").json(&new_adapter).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"]["adapter"], "xml_adapter");
+
+        assert!(!test.config().await.adapters.contains_key("xml_adapter"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_adapter_database_selection() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let db_path = test.directory().join("source.db");
+        create_test_sqlite_db(&db_path).await?;
+        let mut config = test.config().await;
+        let mut project_config = config.project.clone();
+        project_config.connections.insert(
+            "sqlite".to_string(),
+            ConnectionConfig::Sqlite {
+                path: db_path.to_string_lossy().to_string(),
+            },
+        );
+        config.add_project_setting(&project_config)?.save()?;
+        drop(config);
+
+        let adapter = |columns: serde_json::Value, filter: &str| {
+            json!({
+                "name": "users",
+                "config": {
+                    "connection": "sqlite",
+                    "source": {
+                        "type": "database",
+                        "table_name": "test_table",
+                        "columns": columns,
+                        "filter": filter
+                    },
+                    "columns": []
+                }
+            })
+        };
+
+        let response = server
+            .post("/adapters")
+            .json(&adapter(json!(["id", "email", "phone"]), "age > 20"))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "SCHEMA_MISMATCH");
+        assert_eq!(body["details"]["adapter"], "users");
+        assert_eq!(body["details"]["columns"], json!(["email", "phone"]));
+
+        let response = server
+            .post("/adapters")
+            .json(&adapter(json!(["id", "name"]), "age >"))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+
+        let response = server
+            .post("/adapters")
+            .json(&adapter(json!(["id", "name"]), "age > 20"))
+            .await;
+        response.assert_status_ok();
+
+        let config = test.config().await;
+        assert_eq!(
+            config.adapters["users"].source,
+            AdapterSource::Database {
+                table_name: "test_table".to_string(),
+                columns: Some(vec!["id".to_string(), "name".to_string()]),
+                filter: Some("age > 20".to_string()),
+                incremental: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_adapter_nonexistent_connection() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let adapter_config = AdapterConfig {
+            connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
+            description: None,
+            source: AdapterSource::Database {
+                table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        test.config()
+            .await
+            .upsert_adapter("test_adapter", &adapter_config)?
+            .save()?;
+
+        let updated_config = json!({
+            "connection": "missing_connection",
+            "source": {
+                "type": "database",
+                "table_name": "users"
+            },
+            "columns": []
+        });
+
+        let response = server
+            .put("/adapters/test_adapter")
+            .json(&updated_config)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONNECTION_NOT_FOUND");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_schema() -> Result<()> {
         let test = TestManager::new();
@@ -423,21 +1364,40 @@ mod tests {
 
         let adapter_config = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Test adapter".to_string()),
             source: AdapterSource::File {
                 file: FileConfig {
                     path: "test_data.csv".to_string(),
                     compression: None,
                     max_batch_size: None,
+                    max_batch_files: None,
+                    incremental: false,
+                    import_mode: None,
                 },
                 format: FormatConfig {
                     ty: "csv".to_string(),
                     delimiter: Some(",".to_string()),
                     null_value: None,
                     has_header: Some(true),
+                    sheet: None,
+                    columns_spec: vec![],
+                    quote: None,
+                    escape: None,
+                    date_format: None,
+                    timestamp_format: None,
                 },
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         let get_schema_request = json!({
@@ -451,12 +1411,178 @@ mod tests {
             .await;
         response.assert_status_ok();
 
-        let schema: Vec<crate::core::adapter::database::ColumnInfo> = response.json();
+        let schema: Vec<ColumnInfo> = response.json();
         assert_eq!(schema.len(), 3);
         assert_eq!(schema[0].name, "id");
         assert_eq!(schema[1].name, "name");
         assert_eq!(schema[2].name, "age");
 
+        let response = server
+            .post("/adapters/get-schema")
+            .json(&json!({
+                "connection": "test_connection",
+                "source": adapter_config.source,
+                "inference": true
+            }))
+            .await;
+        response.assert_status_ok();
+        let GetSchemaResponse::WithInference { columns, inference } =
+            response.json::<GetSchemaResponse>()
+        else {
+            panic!("Expected the inference report");
+        };
+        assert_eq!(columns, schema);
+        let inference = inference.unwrap();
+        assert_eq!(inference.sampled_rows, 2);
+        assert!(inference.findings.is_empty());
+
+        Ok(())
+    }
+
+    fn csv_source(path: &str) -> AdapterSource {
+        AdapterSource::File {
+            file: FileConfig {
+                path: path.to_string(),
+                compression: None,
+                max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
+            },
+            format: FormatConfig {
+                ty: "csv".to_string(),
+                delimiter: Some(",".to_string()),
+                null_value: None,
+                has_header: Some(true),
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_schema_inference_report() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+        write_test_file(
+            test.directory(),
+            "stores.csv",
+            "id,zip,opened\n1,01234,2024-01-15\n2,12345,2024-02-01\n3,00501,2024-03-10\n4,98765,2024-04-22\n5,10001,unknown",
+        )?;
+
+        let response = server
+            .post("/adapters/get-schema")
+            .json(&json!({
+                "connection": "test_connection",
+                "source": csv_source("stores.csv"),
+                "inference": true
+            }))
+            .await;
+        response.assert_status_ok();
+        let GetSchemaResponse::WithInference { inference, .. } =
+            response.json::<GetSchemaResponse>()
+        else {
+            panic!("Expected the inference report");
+        };
+        let report = inference.unwrap();
+        assert_eq!(report.sampled_rows, 5);
+
+        let zip = report.findings.iter().find(|f| f.column == "zip").unwrap();
+        assert_eq!(zip.issue, InferenceIssue::LeadingZeros);
+        assert_ne!(zip.inferred_type, "VARCHAR");
+        assert_eq!(zip.suggestion.as_ref().unwrap().ty, "VARCHAR");
+
+        let opened = report
+            .findings
+            .iter()
+            .find(|f| f.column == "opened")
+            .unwrap();
+        assert_eq!(opened.issue, InferenceIssue::DateAsText);
+        assert_eq!(opened.inferred_type, "VARCHAR");
+        let suggestion = opened.suggestion.as_ref().unwrap();
+        assert_eq!((suggestion.ty.as_str(), suggestion.lenient), ("DATE", true));
+
+        let names: Vec<&str> = report
+            .suggested_columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["id", "zip", "opened"]);
+        assert!(report.suggested_columns[0].ty.contains("INT"));
+        assert!(!report.suggested_columns[0].enforce);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_adapter() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+        write_test_file(
+            test.directory(),
+            "users_1.csv",
+            "id,name\n1,Alice\n2,Bob\n3,Carol",
+        )?;
+        write_test_file(test.directory(), "users_2.csv", "id,name\n4,Dave")?;
+
+        let response = server
+            .post("/adapters/preview")
+            .json(&json!({
+                "connection": "test_connection",
+                "source": csv_source("users_*.csv"),
+                "columns": []
+            }))
+            .await;
+        response.assert_status_ok();
+        let preview: TypedResult = response.json();
+        assert_eq!(preview.columns, vec!["id", "name"]);
+        assert_eq!(preview.row_count(), 3);
+        assert_eq!(preview.data["name"][0], "Alice");
+
+        let response = server
+            .post("/adapters/preview")
+            .json(&json!({
+                "connection": "test_connection",
+                "source": csv_source("users_1.csv"),
+                "limit": 2
+            }))
+            .await;
+        response.assert_status_ok();
+        let preview: TypedResult = response.json();
+        assert_eq!(preview.row_count(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_adapter_missing_file() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+
+        let response = server
+            .post("/adapters/preview")
+            .json(&json!({
+                "connection": "test_connection",
+                "source": csv_source("missing.csv")
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONNECTION_FAILED");
+        assert!(
+            body["message"]
+                .as_str()
+                .unwrap()
+                .contains("No files found matching pattern: missing.csv")
+        );
+
         Ok(())
     }
 
@@ -467,11 +1593,24 @@ mod tests {
 
         let adapter_config = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Adapter to delete".to_string()),
             source: AdapterSource::Database {
                 table_name: "test_table".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         {
@@ -498,4 +1637,352 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_create_adapter_invalid_name() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+
+        let adapter = |name: &str| {
+            json!({
+                "name": name,
+                "config": {
+                    "connection": "test_connection",
+                    "source": {
+                        "type": "file",
+                        "file": { "path": "test.csv" },
+                        "format": { "type": "csv" }
+                    },
+                    "columns": []
+                }
+            })
+        };
+
+        for name in [
+            "../../etc/cron.d/x",
+            "foo/bar",
+            "2024_orders",
+            "ユーザー",
+            "my users",
+        ] {
+            let response = server.post("/adapters").json(&adapter(name)).await;
+            response.assert_status(StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = response.json();
+            assert_eq!(body["code"], "INVALID_NAME");
+            assert_eq!(body["details"]["name"], name);
+        }
+        assert!(test.config().await.adapters.is_empty());
+        assert!(!test.directory().join("etc").exists());
+
+        server
+            .post("/adapters")
+            .json(&adapter("users_2024"))
+            .await
+            .assert_status_ok();
+        server
+            .post("/adapters/users_2024/rename")
+            .json(&json!({ "new_name": "../users" }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        assert!(test.config().await.adapters.contains_key("users_2024"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_adapter() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let adapter_config = AdapterConfig {
+            connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
+            description: None,
+            source: AdapterSource::Database {
+                table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+
+        test.config()
+            .await
+            .upsert_adapter("users", &adapter_config)?
+            .save()?;
+
+        {
+            let mut graph = test.graph().await;
+            graph.create_node("users", &[]);
+            graph.set_current_time("users");
+            graph.create_node("active_users", &["users"]);
+        }
+
+        let response = server
+            .post("/adapters/users/rename")
+            .json(&json!({ "new_name": "customers" }))
+            .await;
+        response.assert_status_ok();
+
+        let graph = Graph::load(test.directory()).await?;
+        assert!(!graph.has_node("users"));
+        assert!(graph.get_node("customers").unwrap().updated_at.is_some());
+        assert_eq!(
+            graph.get_node("active_users").unwrap().dependencies,
+            vec!["customers".to_string()]
+        );
+
+        let config = test.config().await;
+        assert!(config.adapters.contains_key("customers"));
+        assert!(!config.adapters.contains_key("users"));
+        assert!(test.directory().join("adapters/customers.yml").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_mismatch_details() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let db_path = test.directory().join("source.db");
+        create_test_sqlite_db(&db_path).await?;
+        let mut config = test.config().await;
+        let mut project_config = config.project.clone();
+        project_config.connections.insert(
+            "sqlite".to_string(),
+            ConnectionConfig::Sqlite {
+                path: db_path.to_string_lossy().to_string(),
+            },
+        );
+        config.add_project_setting(&project_config)?.save()?;
+        drop(config);
+
+        let request = |columns: serde_json::Value| {
+            json!({
+                "connection": "sqlite",
+                "source": { "type": "database", "table_name": "test_table" },
+                "columns": columns
+            })
+        };
+
+        let response = server
+            .post("/adapters/test-schema")
+            .json(&request(json!([{ "name": "name", "type": "INTEGER" }])))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "SCHEMA_MISMATCH");
+        assert_eq!(body["details"]["column"], "name");
+        assert_eq!(body["details"]["expected"], "INTEGER");
+        assert_eq!(body["details"]["found"], "TEXT");
+
+        let response = server
+            .post("/adapters/test-schema")
+            .json(&request(json!([{ "name": "email", "type": "TEXT" }])))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "SCHEMA_MISMATCH");
+        assert_eq!(body["details"]["columns"], json!(["email"]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adapter_impact() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let mut config = test.config().await;
+            let adapter = AdapterConfig {
+                connection: "test_connection".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: "products".to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: None,
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_adapter("products", &adapter)?.save()?;
+            for (name, sql) in [
+                (
+                    "discounted",
+                    "SELECT price * 0.9 AS sale_price FROM products",
+                ),
+                ("converted", "SELECT price_usd FROM products"),
+            ] {
+                let model = ModelConfig {
+                    description: None,
+                    sql: sql.to_string(),
+                    materialization: Materialization::Table,
+                    unique_key: None,
+                    updated_at_column: None,
+                    tests: vec![],
+                    relationships: vec![],
+                    tags: vec![],
+                    group: None,
+                    weight: None,
+                    exclusive: false,
+                    masking: vec![],
+                };
+                config.upsert_model(name, &model)?.save()?;
+            }
+            test.graph().await.rebuild(&config);
+        }
+        let server = test.setup_server(routes);
+
+        let response = server
+            .get("/adapters/products/impact")
+            .add_query_param("column", "price")
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(
+            body["models"],
+            json!([{
+                "name": "discounted",
+                "references": [{ "identifier": "price", "line": 1, "column": 8 }]
+            }])
+        );
+        assert_eq!(body["queries"], json!([]));
+
+        let response = server
+            .get("/adapters/missing/impact")
+            .add_query_param("column", "price")
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_resolved_adapter() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        let data_dir = test.directory().join("data");
+        std::fs::create_dir_all(&data_dir)?;
+        for file in ["b.csv", "a.csv", "notes.txt"] {
+            write_test_file(&data_dir, file, "id\n1\n")?;
+        }
+
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.base_project()?;
+            project_config.connections.insert(
+                "local".to_string(),
+                ConnectionConfig::LocalFile {
+                    base_path: "./data".to_string(),
+                },
+            );
+            project_config.defaults.file_format.insert(
+                "csv".to_string(),
+                FormatDefaults {
+                    delimiter: Some(";".to_string()),
+                    null_value: Some("NA".to_string()),
+                    ..Default::default()
+                },
+            );
+            config.save_project(project_config)?;
+
+            let adapter_config = AdapterConfig {
+                connection: "local".to_string(),
+                target_table: Some("raw_events".to_string()),
+                dedupe: None,
+                description: None,
+                source: AdapterSource::File {
+                    file: FileConfig {
+                        path: "*.csv".to_string(),
+                        compression: None,
+                        max_batch_size: None,
+                        max_batch_files: None,
+                        incremental: false,
+                        import_mode: None,
+                    },
+                    format: FormatConfig {
+                        ty: "csv".to_string(),
+                        delimiter: None,
+                        null_value: Some("-".to_string()),
+                        has_header: Some(true),
+                        sheet: None,
+                        columns_spec: vec![],
+                        quote: None,
+                        escape: None,
+                        date_format: None,
+                        timestamp_format: None,
+                    },
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_adapter("events", &adapter_config)?.save()?;
+        }
+
+        let response = server.get("/adapters/events/resolved").await;
+        response.assert_status_ok();
+        let resolved: ResolvedAdapter = response.json();
+        let base_path = test.directory().join("data");
+        assert_eq!(resolved.location, base_path.to_string_lossy());
+        assert_eq!(resolved.target_table, "raw_events");
+        assert_eq!(
+            resolved.pattern,
+            Some(base_path.join("*.csv").to_string_lossy().to_string())
+        );
+        let files = resolved.files.unwrap();
+        assert_eq!(files.total, 2);
+        assert_eq!(
+            files.files,
+            vec![
+                base_path.join("a.csv").to_string_lossy().to_string(),
+                base_path.join("b.csv").to_string_lossy().to_string(),
+            ]
+        );
+        assert!(files.error.is_none());
+
+        // The project default fills the unset delimiter; the adapter's own null value
+        // wins. The stored config is left as written.
+        let format = resolved.format.unwrap();
+        assert_eq!(format.delimiter.as_deref(), Some(";"));
+        assert_eq!(format.null_value.as_deref(), Some("-"));
+        let AdapterSource::File { format, .. } = resolved.config.source else {
+            panic!("Expected file source");
+        };
+        assert_eq!(format.delimiter, None);
+
+        server
+            .get("/adapters/missing/resolved")
+            .await
+            .assert_status_not_found();
+
+        Ok(())
+    }
 }
@@ -0,0 +1,245 @@
+use crate::{
+    api::{Error, auth::Principal},
+    core::{
+        audit::{AuditChange, AuditEntry, AuditLog, AuditOperation},
+        config::Config,
+    },
+};
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    response::Json,
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Deserialize)]
+pub struct AuditParams {
+    pub resource: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditDiff {
+    pub id: String,
+    pub resource: String,
+    pub operation: AuditOperation,
+    pub diff: String,
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/audit", get(list_audit))
+        .route("/audit/{id}/diff", get(get_audit_diff))
+}
+
+// Appends an entry for each change a handler saved, made with the request's token.
+// Handlers call it while still holding the config lock, so entries keep the order of
+// the writes.
+pub fn record_changes(
+    audit: &Arc<AuditLog>,
+    config: &Config,
+    principal: Option<&Principal>,
+    changes: impl IntoIterator<Item = AuditChange>,
+) {
+    let principal = principal.map(|principal| principal.0.as_str());
+    audit.record(&config.project_dir, principal, changes);
+}
+
+async fn list_audit(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    Query(params): Query<AuditParams>,
+) -> Result<Json<Vec<AuditEntry>>, Error> {
+    let project_dir = config.lock().await.project_dir.clone();
+    let entries = audit
+        .list(&project_dir, params.resource.as_deref(), params.limit)
+        .await?;
+    Ok(Json(entries))
+}
+
+async fn get_audit_diff(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    Path(id): Path<String>,
+) -> Result<Json<AuditDiff>, Error> {
+    let project_dir = config.lock().await.project_dir.clone();
+    let Some(entry) = audit.get(&project_dir, &id).await? else {
+        return Error::not_found()
+            .with_message(format!("No audit entry '{id}'"))
+            .build();
+    };
+
+    Ok(Json(AuditDiff {
+        diff: entry.diff(),
+        id: entry.id,
+        resource: entry.resource,
+        operation: entry.operation,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{StatusCode, model},
+        core::config::model::ModelConfig,
+        test_helpers::TestManager,
+    };
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_audit_records_updates_with_diff() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| model::routes().merge(routes()));
+
+        let response = server
+            .post("/models")
+            .json(&json!({ "name": "orders", "config": { "sql": "SELECT 1 AS id" } }))
+            .await;
+        response.assert_status_ok();
+
+        let mut updated: ModelConfig = test.config().await.models["orders"].clone();
+        updated.sql = "SELECT 2 AS id".to_string();
+        let response = server.put("/models/orders").json(&updated).await;
+        response.assert_status_ok();
+
+        // Reads and failed writes leave no entry.
+        server.get("/models").await.assert_status_ok();
+        server
+            .put("/models/missing")
+            .json(&updated)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let response = server
+            .get("/audit")
+            .add_query_param("resource", "models/orders")
+            .await;
+        response.assert_status_ok();
+        let entries: Vec<AuditEntry> = response.json();
+        let operations: Vec<AuditOperation> = entries.iter().map(|entry| entry.operation).collect();
+        assert_eq!(
+            operations,
+            vec![AuditOperation::Update, AuditOperation::Create]
+        );
+        assert!(entries[0].before.as_deref().unwrap().contains("SELECT 1"));
+        assert!(entries[0].after.as_deref().unwrap().contains("SELECT 2"));
+        assert_eq!(entries[0].principal, None);
+
+        let response = server
+            .get("/audit")
+            .add_query_param("resource", "models")
+            .add_query_param("limit", "1")
+            .await;
+        let entries: Vec<AuditEntry> = response.json();
+        assert_eq!(entries.len(), 1);
+
+        let response = server.get(&format!("/audit/{}/diff", entries[0].id)).await;
+        response.assert_status_ok();
+        let diff: AuditDiff = response.json();
+        assert_eq!(diff.resource, "models/orders");
+        assert!(
+            diff.diff
+                .starts_with("--- a/models/orders\n+++ b/models/orders\n@@ ")
+        );
+        assert!(
+            diff.diff
+                .contains("\n-sql: SELECT 1 AS id\n+sql: SELECT 2 AS id\n")
+        );
+
+        server
+            .get("/audit/unknown/diff")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_rename_and_rewritten_references() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| model::routes().merge(routes()));
+
+        for (name, sql) in [
+            ("orders", "SELECT 1 AS id"),
+            ("report", "SELECT * FROM {{ ref('orders') }}"),
+        ] {
+            server
+                .post("/models")
+                .json(&json!({ "name": name, "config": { "sql": sql } }))
+                .await
+                .assert_status_ok();
+        }
+        server
+            .post("/models/orders/rename")
+            .json(&json!({ "new_name": "sales" }))
+            .await
+            .assert_status_ok();
+
+        let entries: Vec<AuditEntry> = server
+            .get("/audit")
+            .add_query_param("resource", "models")
+            .add_query_param("limit", "3")
+            .await
+            .json();
+        let summary: Vec<(&str, AuditOperation)> = entries
+            .iter()
+            .map(|entry| (entry.resource.as_str(), entry.operation))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("models/report", AuditOperation::Update),
+                ("models/sales", AuditOperation::Create),
+                ("models/orders", AuditOperation::Delete),
+            ]
+        );
+        assert!(entries[0].before.as_deref().unwrap().contains("orders"));
+        assert!(entries[0].after.as_deref().unwrap().contains("sales"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_audit_concurrent_writes() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| model::routes().merge(routes()));
+
+        let create = |name: &'static str| {
+            let server = &server;
+            async move {
+                server
+                    .post("/models")
+                    .json(&json!({ "name": name, "config": { "sql": "SELECT 1 AS id" } }))
+                    .await
+            }
+        };
+        let (first, second) = tokio::join!(create("first"), create("second"));
+        first.assert_status_ok();
+        second.assert_status_ok();
+
+        // Each request records only the model it created.
+        let entries: Vec<AuditEntry> = server
+            .get("/audit")
+            .add_query_param("resource", "models")
+            .await
+            .json();
+        let mut resources: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.resource.as_str())
+            .collect();
+        resources.sort();
+        assert_eq!(resources, vec!["models/first", "models/second"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{api::Error, core::config::api_token::ApiToken};
+use axum::{
+    extract::{Query, Request, State},
+    http::{Uri, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+const PROJECT_TOKEN: &str = "api-token";
+const ADMIN_TOKEN: &str = "admin-token";
+
+// Who made an authenticated request: the project token, or the admin token when
+// the project has one.
+#[derive(Debug, Clone)]
+pub struct Principal(pub String);
+
+impl Principal {
+    // Only the admin token may see masked columns unmasked.
+    pub fn is_admin(&self) -> bool {
+        self.0 == ADMIN_TOKEN
+    }
+}
+
+pub async fn require_token(
+    State(token): State<Arc<ApiToken>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, Error> {
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| query_token(request.uri()));
+
+    match provided {
+        Some(provided) if token.verify_admin(&provided) => {
+            request
+                .extensions_mut()
+                .insert(Principal(ADMIN_TOKEN.to_string()));
+            Ok(next.run(request).await)
+        }
+        Some(provided) if token.verify(&provided) => {
+            request
+                .extensions_mut()
+                .insert(Principal(PROJECT_TOKEN.to_string()));
+            Ok(next.run(request).await)
+        }
+        Some(_) => Error::unauthorized()
+            .with_message("Invalid API token")
+            .build(),
+        None => Error::unauthorized()
+            .with_message("Missing 'Authorization: Bearer <token>' header")
+            .build(),
+    }
+}
+
+// EventSource cannot send headers, so event streams take the token in the query.
+// No other route does, since URLs end up in access logs, history and Referer headers.
+const EVENT_STREAMS: [&str; 1] = ["/pipelines/current/events"];
+
+#[derive(Deserialize)]
+struct TokenParams {
+    access_token: Option<String>,
+}
+
+fn query_token(uri: &Uri) -> Option<String> {
+    if !EVENT_STREAMS.contains(&uri.path()) {
+        return None;
+    }
+    Query::<TokenParams>::try_from_uri(uri).ok()?.0.access_token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::query_token;
+    use crate::{
+        api::{StatusCode, app, query::QueryResult},
+        core::{
+            config::{
+                Config,
+                api_token::{ApiToken, generate_admin_token, generate_api_token},
+                model::ModelConfig,
+                project::{ProjectConfig, ServerConfig},
+            },
+            graph::Graph,
+        },
+    };
+    use anyhow::Result;
+    use axum_test::TestServer;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn setup_server(token: Option<ApiToken>, dir: &std::path::Path) -> TestServer {
+        setup_server_with(token, Config::new(dir.to_path_buf()), dir)
+    }
+
+    fn setup_server_with(
+        token: Option<ApiToken>,
+        config: Config,
+        dir: &std::path::Path,
+    ) -> TestServer {
+        let app = app(
+            Arc::new(Mutex::new(config)),
+            Arc::new(Mutex::new(Graph::new(dir))),
+            Arc::default(),
+            Arc::default(),
+            token,
+            &ServerConfig::default(),
+        );
+        TestServer::new(app).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_require_token() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let token = generate_api_token(dir.path())?;
+        let server = setup_server(Some(ApiToken::load(dir.path())?), dir.path());
+
+        server
+            .get("/api/pipelines")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get("/api/pipelines")
+            .authorization_bearer("wrong")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get("/api/pipelines")
+            .authorization_bearer(&token)
+            .await
+            .assert_status_ok();
+        server
+            .get("/api/pipelines")
+            .add_query_param("access_token", &token)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        // Past the token check; no pipeline has run yet.
+        server
+            .get("/api/pipelines/current/events")
+            .add_query_param("access_token", &token)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        server.get("/health").await.assert_status_ok();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_token() -> Result<()> {
+        let uri = "/pipelines/current/events?tail=1&access_token=a%2Bb%2F%3D".parse()?;
+        assert_eq!(query_token(&uri).as_deref(), Some("a+b/="));
+
+        assert_eq!(query_token(&"/pipelines?access_token=abc".parse()?), None);
+        assert_eq!(query_token(&"/pipelines/current/events".parse()?), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unmasked_requires_admin_token() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let token = generate_api_token(dir.path())?;
+        let admin_token = generate_admin_token(dir.path())?;
+
+        let mut config = Config::new(dir.path().to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        let users: ModelConfig =
+            serde_yml::from_str("sql: SELECT 1\nmasking:\n  - column: email\n    mask: full\n")?;
+        config.models.insert("users".to_string(), users);
+        let server = setup_server_with(Some(ApiToken::load(dir.path())?), config, dir.path());
+
+        // The CTE can't be resolved to a table, so every mask in the project applies.
+        let query = json!({
+            "sql": "WITH t AS (SELECT 'alice@example.com' AS email) SELECT email FROM t"
+        });
+
+        let masked: QueryResult = server
+            .post("/api/query")
+            .authorization_bearer(&token)
+            .json(&query)
+            .await
+            .json();
+        assert_eq!(masked.data["email"], vec![json!("***")]);
+        server
+            .post("/api/query")
+            .add_query_param("unmasked", true)
+            .authorization_bearer(&token)
+            .json(&query)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        let unmasked: QueryResult = server
+            .post("/api/query")
+            .add_query_param("unmasked", true)
+            .authorization_bearer(&admin_token)
+            .json(&query)
+            .await
+            .json();
+        assert_eq!(unmasked.data["email"], vec![json!("alice@example.com")]);
+
+        let export = json!({ "sql": query["sql"], "format": "csv" });
+        let csv = server
+            .post("/api/query/export")
+            .authorization_bearer(&token)
+            .json(&export)
+            .await
+            .text();
+        assert!(csv.contains("***"));
+        assert!(!csv.contains("alice"));
+        server
+            .post("/api/query/export")
+            .add_query_param("unmasked", true)
+            .authorization_bearer(&token)
+            .json(&export)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_auth() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let server = setup_server(None, dir.path());
+
+        server.get("/api/pipelines").await.assert_status_ok();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,441 @@
+use crate::{
+    api::{
+        Error, ErrorCode,
+        adapter::{
+            ensure_connection_exists, ensure_selected_columns_exist, ensure_target_table_free,
+            ensure_valid_adapter,
+        },
+        audit::record_changes,
+        auth::Principal,
+        ensure_valid_name,
+        model::{ensure_valid_model, model_dependencies, reject_cycle},
+    },
+    core::{
+        audit::{AuditChange, AuditLog},
+        config::{Config, Journal, adapter::AdapterConfig, model::ModelConfig},
+        ducklake::DuckLakeCache,
+        graph::Graph,
+    },
+};
+use anyhow::Result;
+use axum::{Extension, Router, response::Json, routing::post};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Model,
+    Adapter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkOp {
+    Upsert,
+    Delete,
+}
+
+#[derive(Deserialize)]
+pub struct BulkOperation {
+    pub kind: ResourceKind,
+    pub op: BulkOp,
+    pub name: String,
+    // Parsed per item so one malformed config is reported instead of rejecting the body.
+    #[serde(default)]
+    pub config: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Applied,
+    // Passed validation but was not applied because another item failed.
+    Valid,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    pub kind: ResourceKind,
+    pub op: BulkOp,
+    pub name: String,
+    pub status: ItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkResponse {
+    pub results: Vec<BulkItemResult>,
+}
+
+enum Change {
+    UpsertAdapter(AdapterConfig),
+    UpsertModel(ModelConfig),
+    DeleteAdapter,
+    DeleteModel,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/bulk", post(bulk))
+}
+
+// Applies every operation or none. All items are validated against a staged copy of
+// the config and graph first, so a model may depend on an adapter created in the same
+// batch; only then are files written, and a failed write restores the files already
+// written. A rejected batch lists each item's outcome under `details.results`.
+async fn bulk(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Json(operations): Json<Vec<BulkOperation>>,
+) -> Result<Json<BulkResponse>, Error> {
+    let mut changes: Vec<Result<Change, Error>> = operations.iter().map(parse_operation).collect();
+
+    // Checking selected columns reaches the source and builds a DuckLake, which needs
+    // the config lock, so it runs before taking it.
+    for (operation, change) in operations.iter().zip(changes.iter_mut()) {
+        let Ok(Change::UpsertAdapter(adapter)) = change else {
+            continue;
+        };
+        let checked = ensure_selected_columns_exist(&config, &ducklakes, &operation.name, adapter);
+        if let Err(e) = checked.await {
+            *change = Err(e);
+        }
+    }
+
+    let mut config = config.lock().await;
+    let mut graph = graph.lock().await;
+    let mut staged = config.clone();
+    let mut candidate = graph.clone();
+
+    for (operation, change) in operations.iter().zip(changes.iter_mut()) {
+        let staged_change = match change {
+            Ok(applied) => stage_config(&mut staged, &operation.name, applied),
+            Err(_) => continue,
+        };
+        if let Err(e) = staged_change {
+            *change = Err(e);
+        }
+    }
+    // Dependencies are resolved once every item is staged, so the order of items
+    // within the batch doesn't matter.
+    for (operation, change) in operations.iter().zip(changes.iter_mut()) {
+        let staged_change = match change {
+            Ok(applied) => stage_graph(&staged, &mut candidate, &operation.name, applied),
+            Err(_) => continue,
+        };
+        if let Err(e) = staged_change {
+            *change = Err(e);
+        }
+    }
+    if changes.iter().all(Result::is_ok)
+        && let Some(cycle) = candidate.detect_cycle()
+    {
+        for (operation, change) in operations.iter().zip(changes.iter_mut()) {
+            if cycle.contains(&operation.name)
+                && let Err(e) = reject_cycle(&candidate)
+            {
+                *change = Err(e);
+            }
+        }
+    }
+
+    let failed = changes.iter().filter(|change| change.is_err()).count();
+    if failed > 0 {
+        let results: Vec<BulkItemResult> = operations
+            .iter()
+            .zip(&changes)
+            .map(|(operation, change)| {
+                let status = match change {
+                    Ok(_) => ItemStatus::Valid,
+                    Err(_) => ItemStatus::Failed,
+                };
+                item_result(operation, status, change.as_ref().err())
+            })
+            .collect();
+        return Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(format!(
+                "{failed} of {} operations failed validation; nothing was applied",
+                operations.len()
+            ))
+            .with_detail("results", serde_json::to_value(results)?)
+            .build();
+    }
+    let changes: Vec<Change> = changes.into_iter().filter_map(Result::ok).collect();
+
+    let mut journal = Journal::default();
+    let persisted = match persist(&mut staged, &operations, &changes, &mut journal) {
+        Ok(()) => candidate.save().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = persisted {
+        journal.rollback();
+        return Err(e.into());
+    }
+
+    let changes = operations.iter().map(|operation| {
+        let kind = match operation.kind {
+            ResourceKind::Adapter => "adapters",
+            ResourceKind::Model => "models",
+        };
+        AuditChange::between(&config, &staged, kind, &operation.name)
+    });
+    record_changes(&audit, &staged, principal.as_deref(), changes);
+
+    *config = staged;
+    *graph = candidate;
+
+    let results = operations
+        .iter()
+        .map(|operation| item_result(operation, ItemStatus::Applied, None))
+        .collect();
+    Ok(Json(BulkResponse { results }))
+}
+
+fn parse_operation(operation: &BulkOperation) -> Result<Change, Error> {
+    ensure_valid_name(&operation.name)?;
+
+    let config = || {
+        operation.config.clone().ok_or_else(|| {
+            Error::bad_request()
+                .with_code(ErrorCode::ValidationFailed)
+                .with_message("Upsert requires 'config'")
+        })
+    };
+    let invalid = |e: serde_json::Error| {
+        Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(format!("Invalid config: {e}"))
+    };
+
+    match (operation.kind, operation.op) {
+        (ResourceKind::Adapter, BulkOp::Upsert) => {
+            let adapter: AdapterConfig = serde_json::from_value(config()?).map_err(invalid)?;
+            ensure_valid_adapter(&operation.name, &adapter)?;
+            Ok(Change::UpsertAdapter(adapter))
+        }
+        (ResourceKind::Model, BulkOp::Upsert) => {
+            let model: ModelConfig = serde_json::from_value(config()?).map_err(invalid)?;
+            ensure_valid_model(&operation.name, &model)?;
+            Ok(Change::UpsertModel(model))
+        }
+        (ResourceKind::Adapter, BulkOp::Delete) => Ok(Change::DeleteAdapter),
+        (ResourceKind::Model, BulkOp::Delete) => Ok(Change::DeleteModel),
+    }
+}
+
+fn stage_config(staged: &mut Config, name: &str, change: &Change) -> Result<(), Error> {
+    match change {
+        Change::UpsertAdapter(adapter) => {
+            if staged.models.contains_key(name) {
+                return Error::conflict()
+                    .with_code(ErrorCode::ModelAlreadyExists)
+                    .with_message(format!("'{name}' is already a model"))
+                    .build();
+            }
+            ensure_connection_exists(staged, adapter)?;
+            staged.adapters.insert(name.to_string(), adapter.clone());
+        }
+        Change::UpsertModel(model) => {
+            if staged.adapters.contains_key(name) {
+                return Error::conflict()
+                    .with_code(ErrorCode::AdapterAlreadyExists)
+                    .with_message(format!("'{name}' is already an adapter"))
+                    .build();
+            }
+            staged.models.insert(name.to_string(), model.clone());
+        }
+        Change::DeleteAdapter => {
+            if staged.adapters.remove(name).is_none() {
+                return Error::not_found()
+                    .with_code(ErrorCode::AdapterNotFound)
+                    .build();
+            }
+        }
+        Change::DeleteModel => {
+            if staged.models.remove(name).is_none() {
+                return Error::not_found()
+                    .with_code(ErrorCode::ModelNotFound)
+                    .build();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn stage_graph(
+    staged: &Config,
+    candidate: &mut Graph,
+    name: &str,
+    change: &Change,
+) -> Result<(), Error> {
+    match change {
+        Change::UpsertAdapter(adapter) => {
+            ensure_target_table_free(staged, name, adapter)?;
+            if candidate.has_node(name) {
+                candidate.update_node(name);
+            } else {
+                candidate.create_node(name, &[]);
+            }
+        }
+        Change::UpsertModel(model) => {
+            let (dependencies, _) = model_dependencies(staged, &model.sql)?;
+            let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
+            if candidate.has_node(name) {
+                candidate.update_dependencies(name, &deps);
+                candidate.update_node(name);
+            } else {
+                candidate.create_node(name, &deps);
+            }
+        }
+        Change::DeleteAdapter | Change::DeleteModel => candidate.delete_node(name),
+    }
+    Ok(())
+}
+
+// Records what each file held before it was written, so the journal can restore it.
+fn persist(
+    staged: &mut Config,
+    operations: &[BulkOperation],
+    changes: &[Change],
+    journal: &mut Journal,
+) -> Result<()> {
+    for (operation, change) in operations.iter().zip(changes) {
+        let name = operation.name.as_str();
+        match change {
+            Change::UpsertAdapter(adapter) => {
+                let handle = staged.upsert_adapter(name, adapter)?;
+                journal.record(handle.path());
+                handle.save()?;
+            }
+            Change::UpsertModel(model) => {
+                let handle = staged.upsert_model(name, model)?;
+                journal.record(handle.path());
+                handle.save()?;
+            }
+            Change::DeleteAdapter => {
+                let handle = staged.delete_adapter(name)?;
+                journal.record(handle.path());
+                handle.save()?;
+            }
+            Change::DeleteModel => {
+                let handle = staged.delete_model(name)?;
+                journal.record(handle.path());
+                handle.save()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn item_result(
+    operation: &BulkOperation,
+    status: ItemStatus,
+    error: Option<&Error>,
+) -> BulkItemResult {
+    BulkItemResult {
+        kind: operation.kind,
+        op: operation.op,
+        name: operation.name.clone(),
+        status,
+        code: error.map(|e| e.code),
+        message: error.and_then(|e| e.message.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::StatusCode, test_helpers::TestManager};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_bulk_upsert_resolves_dependencies_across_items() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        // `daily` depends on `orders`, which only appears later in the batch.
+        let response = server
+            .post("/bulk")
+            .json(&json!([
+                {
+                    "kind": "model",
+                    "op": "upsert",
+                    "name": "daily",
+                    "config": { "description": null, "sql": "SELECT count(*) FROM orders" }
+                },
+                {
+                    "kind": "model",
+                    "op": "upsert",
+                    "name": "orders",
+                    "config": { "description": null, "sql": "SELECT 1 AS id" }
+                }
+            ]))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["results"][0]["status"], "applied");
+        assert_eq!(body["results"][1]["status"], "applied");
+
+        let graph = Graph::load(test.directory()).await?;
+        assert_eq!(graph.upstream("daily"), vec!["orders"]);
+        assert!(test.directory().join("models/orders.yml").exists());
+        assert!(test.config().await.models.contains_key("daily"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bulk_invalid_item_persists_nothing() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/bulk")
+            .json(&json!([
+                {
+                    "kind": "model",
+                    "op": "upsert",
+                    "name": "orders",
+                    "config": { "description": null, "sql": "SELECT 1 AS id" }
+                },
+                {
+                    "kind": "model",
+                    "op": "delete",
+                    "name": "missing"
+                },
+                {
+                    "kind": "model",
+                    "op": "upsert",
+                    "name": "broken",
+                    "config": { "description": null, "sql": "SELEC FROM" }
+                }
+            ]))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        let results = &body["details"]["results"];
+        assert_eq!(results[0]["status"], "valid");
+        assert_eq!(results[1]["status"], "failed");
+        assert_eq!(results[1]["code"], "MODEL_NOT_FOUND");
+        assert_eq!(results[2]["status"], "failed");
+        assert_eq!(results[2]["code"], "INVALID_SQL");
+
+        assert!(!test.directory().join("models/orders.yml").exists());
+        assert!(test.config().await.models.is_empty());
+        assert!(!test.graph().await.has_node("orders"));
+        assert!(!Graph::load(test.directory()).await?.has_node("orders"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    api::Error,
+    core::{
+        config::project::CacheConfig,
+        query_cache::{CacheKey, CachedResult, QueryCache},
+    },
+};
+use anyhow::Result;
+use axum::{
+    Extension, Router,
+    http::{HeaderName, HeaderValue},
+    response::Json,
+    routing::delete,
+};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, sync::Arc};
+
+pub const CACHE_HEADER: HeaderName = HeaderName::from_static("x-cache");
+
+// Sent back as a response header: `x-cache: HIT` or `x-cache: MISS`.
+pub type CacheStatus = [(HeaderName, HeaderValue); 1];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClearCacheResponse {
+    pub cleared: usize,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/cache", delete(clear_cache))
+}
+
+async fn clear_cache(
+    Extension(cache): Extension<Arc<QueryCache>>,
+) -> Result<Json<ClearCacheResponse>, Error> {
+    Ok(Json(ClearCacheResponse {
+        cleared: cache.clear(),
+    }))
+}
+
+// Serves `key` from the cache, or runs the query and stores what it returns. Failed
+// queries are not cached.
+pub async fn cached<F>(
+    cache: &QueryCache,
+    config: &CacheConfig,
+    key: CacheKey,
+    run: F,
+) -> Result<(Arc<CachedResult>, CacheStatus), Error>
+where
+    F: Future<Output = Result<CachedResult, Error>>,
+{
+    if let Some(result) = cache.get(&key, config) {
+        return Ok((result, [(CACHE_HEADER, HeaderValue::from_static("HIT"))]));
+    }
+
+    let result = run.await?;
+    cache.insert(key, result.clone(), config);
+    Ok((
+        Arc::new(result),
+        [(CACHE_HEADER, HeaderValue::from_static("MISS"))],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::query, core::config::query::QueryConfig, test_helpers::TestManager};
+
+    #[tokio::test]
+    async fn test_clear_cache() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| routes().merge(query::routes()));
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT 42 AS answer".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+        test.config()
+            .await
+            .upsert_query("answer", &query_config)?
+            .save()?;
+
+        let response = server.post("/queries/answer/run").await;
+        response.assert_status_ok();
+        assert_eq!(response.header(CACHE_HEADER), "MISS");
+        let response = server.post("/queries/answer/run").await;
+        assert_eq!(response.header(CACHE_HEADER), "HIT");
+
+        let response = server.delete("/cache").await;
+        response.assert_status_ok();
+        let body: ClearCacheResponse = response.json();
+        assert_eq!(body.cleared, 1);
+        assert_eq!(test.query_cache().entry_count(), 0);
+
+        let response = server.post("/queries/answer/run").await;
+        assert_eq!(response.header(CACHE_HEADER), "MISS");
+
+        Ok(())
+    }
+}
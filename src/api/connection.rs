@@ -1,32 +1,53 @@
 use crate::{
-    api::Error,
-    core::config::{Config, project::ConnectionConfig, secret::SecretField},
+    api::{Error, ErrorCode, audit::record_changes, auth::Principal, ensure_valid_name},
+    core::{
+        adapter::file::{gcs::gcs_client, http::http_client},
+        audit::{AuditChange, AuditLog},
+        config::{
+            Config,
+            adapter::AdapterSource,
+            project::{ConnectionConfig, GcsConfig, HttpConfig},
+            secret::SecretField,
+        },
+    },
 };
 use anyhow::Result;
 use axum::{
     Extension, Router,
-    extract::Path,
+    extract::{Path, Query},
     response::Json,
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
-use std::{path::Path as StdPath, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path as StdPath,
+    sync::Arc,
+};
 use tokio::sync::Mutex;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ConnectionSummary {
     pub name: String,
     pub connection_type: String,
     pub details: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateConnectionRequest {
     pub name: String,
     pub config: ConnectionConfig,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteConnectionParams {
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(tag = "type")]
 pub enum TestConnectionConfig {
     #[serde(rename = "sqlite")]
@@ -59,6 +80,101 @@ pub enum TestConnectionConfig {
         secret_access_key: Option<String>,
         path_style_access: Option<bool>,
     },
+    #[serde(rename = "http")]
+    Http {
+        base_url: String,
+        headers: Option<HashMap<String, String>>,
+        auth_token: Option<String>,
+    },
+    #[serde(rename = "gcs")]
+    Gcs {
+        bucket: String,
+        hmac_key_id: String,
+        hmac_secret: String,
+        endpoint_url: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct TestConnectionRequest {
+    #[serde(flatten)]
+    pub connection: TestConnectionConfig,
+    // The table, or S3 path, to check read access on. Defaults to the source of the
+    // first adapter bound to the connection `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    // The credentials work but lack a grant; the message says which.
+    Denied,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CapabilityCheck {
+    pub status: CheckStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl CapabilityCheck {
+    fn ok() -> Self {
+        Self {
+            status: CheckStatus::Ok,
+            message: None,
+        }
+    }
+
+    fn denied(message: String) -> Self {
+        Self {
+            status: CheckStatus::Denied,
+            message: Some(message),
+        }
+    }
+
+    fn failed(message: String) -> Self {
+        Self {
+            status: CheckStatus::Failed,
+            message: Some(message),
+        }
+    }
+
+    fn skipped(message: &str) -> Self {
+        Self {
+            status: CheckStatus::Skipped,
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+// `success` is false when any capability was denied or failed; skipped checks don't
+// count against it.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub checks: BTreeMap<String, CapabilityCheck>,
+}
+
+impl ConnectionTestResult {
+    fn connected(capabilities: Vec<(&str, CapabilityCheck)>) -> Self {
+        let mut checks = BTreeMap::from([("connectivity".to_string(), CapabilityCheck::ok())]);
+        checks.extend(
+            capabilities
+                .into_iter()
+                .map(|(name, check)| (name.to_string(), check)),
+        );
+        let success = checks
+            .values()
+            .all(|check| matches!(check.status, CheckStatus::Ok | CheckStatus::Skipped));
+        Self { success, checks }
+    }
 }
 
 #[derive(Debug)]
@@ -96,82 +212,44 @@ pub fn routes() -> Router {
         .route("/connections/test", post(test_connection))
 }
 
-fn encrypt_connection_secrets(
-    mut connection: ConnectionConfig,
-    project_dir: &StdPath,
-) -> Result<ConnectionConfig, Error> {
-    match &mut connection {
-        ConnectionConfig::MySql(config) => {
-            if let SecretField::PlainText { value } = &config.password {
-                let key_path = project_dir.join(".secret.key");
-                config.password = SecretField::encrypt(value, &key_path).map_err(|e| {
-                    Error::internal_server_error()
-                        .with_message(format!("Failed to encrypt password: {}", e))
-                })?;
-            }
-        }
-        ConnectionConfig::PostgreSql(config) => {
-            if let SecretField::PlainText { value } = &config.password {
-                let key_path = project_dir.join(".secret.key");
-                config.password = SecretField::encrypt(value, &key_path).map_err(|e| {
-                    Error::internal_server_error()
-                        .with_message(format!("Failed to encrypt password: {}", e))
-                })?;
-            }
-        }
-        ConnectionConfig::S3(config) => {
-            if let Some(SecretField::PlainText { value }) = &config.secret_access_key {
-                let key_path = project_dir.join(".secret.key");
-                config.secret_access_key =
-                    Some(SecretField::encrypt(value, &key_path).map_err(|e| {
-                        Error::internal_server_error()
-                            .with_message(format!("Failed to encrypt secret access key: {}", e))
-                    })?);
-            }
-        }
-        _ => {}
-    }
-    Ok(connection)
-}
-
-fn decrypt_connection_secrets(
-    mut connection: ConnectionConfig,
-    project_dir: &StdPath,
-) -> Result<ConnectionConfig, Error> {
-    match &mut connection {
-        ConnectionConfig::MySql(config) => {
-            config.password.load(project_dir).map_err(|e| {
-                Error::internal_server_error()
-                    .with_message(format!("Failed to decrypt password: {}", e))
-            })?;
-        }
-        ConnectionConfig::PostgreSql(config) => {
-            config.password.load(project_dir).map_err(|e| {
-                Error::internal_server_error()
-                    .with_message(format!("Failed to decrypt password: {}", e))
-            })?;
-        }
-        ConnectionConfig::S3(config) => {
-            if let Some(ref mut secret) = config.secret_access_key {
-                secret.load(project_dir)
-            } else {
-                Ok(())
-            }
-            .map_err(|e| {
-                Error::internal_server_error()
-                    .with_message(format!("Failed to decrypt secret access key: {}", e))
-            })?;
-        }
-        _ => {}
-    }
-    Ok(connection)
-}
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_connections,
+    create_connection,
+    get_connection,
+    update_connection,
+    delete_connection,
+    test_connection
+))]
+pub struct ConnectionApi;
+
+#[utoipa::path(
+    post,
+    path = "/connections/test",
+    tag = "connections",
+    request_body = TestConnectionRequest,
+    responses(
+        (status = 200, description = "Connected; `checks` reports each capability", body = ConnectionTestResult),
+        (status = 400, description = "Could not connect")
+    )
+)]
+async fn test_connection(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Json(request): Json<TestConnectionRequest>,
+) -> Result<Json<ConnectionTestResult>, Error> {
+    let source = match (request.table, &request.name) {
+        (Some(table), _) => Some(table),
+        (None, Some(name)) => bound_source(&*config.lock().await, name),
+        (None, None) => None,
+    };
+    let source = source.as_deref();
 
-async fn test_connection(Json(connection): Json<TestConnectionConfig>) -> Result<(), Error> {
-    match connection {
-        TestConnectionConfig::SQLite { path } => test_sqlite_connection(&path).await,
+    let checks = match request.connection {
+        TestConnectionConfig::SQLite { path } => test_sqlite_connection(&path)
+            .await
+            .map(|()| vec![("read", check_sqlite_read(&path, source))]),
         TestConnectionConfig::LocalFile { base_path } => {
-            test_localfile_connection(&base_path).await
+            test_localfile_connection(&base_path).await.map(|()| vec![])
         }
         TestConnectionConfig::MySQL {
             host,
@@ -187,7 +265,9 @@ async fn test_connection(Json(connection): Json<TestConnectionConfig>) -> Result
                 username,
                 password,
             };
-            test_mysql_connection(&config).await
+            test_mysql_connection(&config)
+                .await
+                .map(|()| vec![("read", check_mysql_read(&config, source))])
         }
         TestConnectionConfig::PostgreSQL {
             host,
@@ -203,7 +283,10 @@ async fn test_connection(Json(connection): Json<TestConnectionConfig>) -> Result
                 username,
                 password,
             };
-            test_postgresql_connection(&config).await
+            match test_postgresql_connection(&config).await {
+                Ok(()) => Ok(vec![("read", check_postgresql_read(&config, source).await)]),
+                Err(e) => Err(e),
+            }
         }
         TestConnectionConfig::S3 {
             bucket,
@@ -223,13 +306,240 @@ async fn test_connection(Json(connection): Json<TestConnectionConfig>) -> Result
                 secret_access_key,
                 path_style_access: path_style_access.unwrap_or(false),
             };
-            test_s3_connection(&s3_config).await
+            match test_s3_connection(&s3_config).await {
+                Ok(()) => {
+                    let (list, read) = check_s3_access(&s3_config, source).await;
+                    Ok(vec![("list", list), ("read", read)])
+                }
+                Err(e) => Err(e),
+            }
+        }
+        TestConnectionConfig::Http {
+            base_url,
+            headers,
+            auth_token,
+        } => {
+            let http_config = HttpConfig {
+                base_url,
+                headers,
+                auth_token: auth_token.map(|value| SecretField::PlainText { value }),
+            };
+            test_http_connection(&http_config).await.map(|()| vec![])
         }
-    }?;
+        TestConnectionConfig::Gcs {
+            bucket,
+            hmac_key_id,
+            hmac_secret,
+            endpoint_url,
+        } => {
+            let gcs_config = GcsConfig {
+                bucket,
+                hmac_key_id,
+                hmac_secret: SecretField::PlainText { value: hmac_secret },
+                endpoint_url,
+            };
+            test_gcs_connection(&gcs_config).await.map(|()| vec![])
+        }
+    }
+    .map_err(|e| e.with_code(ErrorCode::ConnectionFailed))?;
 
-    Ok(())
+    Ok(Json(ConnectionTestResult::connected(checks)))
 }
 
+// The table or file path of the first adapter, by name, that reads from `connection`.
+fn bound_source(config: &Config, connection: &str) -> Option<String> {
+    config
+        .adapters
+        .iter()
+        .filter(|(_, adapter)| adapter.connection == connection)
+        .min_by_key(|(name, _)| *name)
+        .map(|(_, adapter)| match &adapter.source {
+            AdapterSource::Database { table_name, .. } => table_name.clone(),
+            AdapterSource::File { file, .. } => file.path.clone(),
+        })
+}
+
+// Errors meaning the credentials work but lack a grant, rather than a missing object
+// or a broken connection.
+fn is_permission_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "permission denied",
+        "access denied",
+        "command denied",
+        "accessdenied",
+        "insufficient privilege",
+        "not authorized",
+        "forbidden",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+fn failed_read(message: String, hint: &str) -> CapabilityCheck {
+    if is_permission_error(&message) {
+        CapabilityCheck::denied(format!("{message}. {hint}"))
+    } else {
+        CapabilityCheck::failed(message)
+    }
+}
+
+// `schema.table` is quoted part by part with the dialect's identifier quote.
+fn quote_table(table: &str, quote: char) -> String {
+    table
+        .split('.')
+        .map(|part| {
+            let escaped = part.replace(quote, &format!("{quote}{quote}"));
+            format!("{quote}{escaped}{quote}")
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn check_sqlite_read(path: &str, table: Option<&str>) -> CapabilityCheck {
+    let hint = "Make the file readable by the user running DuckHub";
+    if let Err(e) = std::fs::File::open(path) {
+        return failed_read(format!("Cannot read '{path}': {e}"), hint);
+    }
+
+    // Without a table, reading the schema still proves the file itself is readable.
+    let sql = match table {
+        Some(table) => format!("SELECT * FROM {} LIMIT 1", quote_table(table, '"')),
+        None => "SELECT count(*) FROM sqlite_master".to_string(),
+    };
+    let result =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(|connection| connection.prepare(&sql)?.query([])?.next().map(|_| ()));
+    match result {
+        Ok(()) => CapabilityCheck::ok(),
+        Err(e) => failed_read(format!("Cannot read '{path}': {e}"), hint),
+    }
+}
+
+fn check_mysql_read(config: &TestRemoteDatabaseConfig, table: Option<&str>) -> CapabilityCheck {
+    use mysql::prelude::*;
+
+    let Some(table) = table else {
+        return CapabilityCheck::skipped(NO_TABLE);
+    };
+    let url = format!(
+        "mysql://{}:{}@{}:{}/{}",
+        config.username, config.password, config.host, config.port, config.database
+    );
+    let result = mysql::Pool::new(url.as_str())
+        .and_then(|pool| pool.get_conn())
+        .and_then(|mut conn| {
+            conn.query_drop(format!("SELECT * FROM {} LIMIT 1", quote_table(table, '`')))
+        });
+    match result {
+        Ok(()) => CapabilityCheck::ok(),
+        Err(e) => failed_read(
+            format!("Cannot read '{table}': {e}"),
+            &format!("Grant SELECT on '{table}' to '{}'", config.username),
+        ),
+    }
+}
+
+async fn check_postgresql_read(
+    config: &TestRemoteDatabaseConfig,
+    table: Option<&str>,
+) -> CapabilityCheck {
+    let Some(table) = table else {
+        return CapabilityCheck::skipped(NO_TABLE);
+    };
+    let connection_string = format!(
+        "host={} port={} dbname={} user={} password={}",
+        config.host, config.port, config.database, config.username, config.password
+    );
+    let result = match tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await {
+        Ok((client, connection)) => {
+            let handle = tokio::spawn(connection);
+            let sql = format!("SELECT * FROM {} LIMIT 1", quote_table(table, '"'));
+            let result = client.query(sql.as_str(), &[]).await;
+            handle.abort();
+            result.map(|_| ())
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => CapabilityCheck::ok(),
+        Err(e) => failed_read(
+            format!("Cannot read '{table}': {e}"),
+            &format!("Grant SELECT on '{table}' to '{}'", config.username),
+        ),
+    }
+}
+
+// Lists the configured prefix, then reads the first byte of one listed object.
+async fn check_s3_access(
+    s3_config: &TestS3Config,
+    path: Option<&str>,
+) -> (CapabilityCheck, CapabilityCheck) {
+    use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata};
+
+    let bucket = &s3_config.bucket;
+    let path = path.unwrap_or_default();
+    let path = path
+        .strip_prefix(&format!("s3://{bucket}/"))
+        .unwrap_or(path);
+    let prefix = &path[..path.find(['*', '?']).unwrap_or(path.len())];
+
+    let client = test_s3_client(s3_config).await;
+    let listed = client
+        .list_objects_v2()
+        .bucket(bucket)
+        .prefix(prefix)
+        .max_keys(1)
+        .send()
+        .await;
+    let key = match listed {
+        Ok(output) => output
+            .contents()
+            .first()
+            .and_then(|object| object.key())
+            .map(str::to_string),
+        Err(e) => {
+            let list = failed_read(
+                format!(
+                    "Cannot list 's3://{bucket}/{prefix}': {} {}",
+                    e.code().unwrap_or_default(),
+                    DisplayErrorContext(&e)
+                ),
+                "Grant s3:ListBucket on the bucket",
+            );
+            return (list, CapabilityCheck::skipped("Listing failed"));
+        }
+    };
+    let Some(key) = key else {
+        return (
+            CapabilityCheck::ok(),
+            CapabilityCheck::skipped(&format!("No objects under 's3://{bucket}/{prefix}'")),
+        );
+    };
+
+    let read = match client
+        .get_object()
+        .bucket(bucket)
+        .key(&key)
+        .range("bytes=0-0")
+        .send()
+        .await
+    {
+        Ok(_) => CapabilityCheck::ok(),
+        Err(e) => failed_read(
+            format!(
+                "Cannot read 's3://{bucket}/{key}': {} {}",
+                e.code().unwrap_or_default(),
+                DisplayErrorContext(&e)
+            ),
+            "Grant s3:GetObject on the objects",
+        ),
+    };
+    (CapabilityCheck::ok(), read)
+}
+
+const NO_TABLE: &str = "No table to read; pass `table` or bind an adapter to this connection";
+
 async fn test_localfile_connection(base_path: &str) -> Result<(), Error> {
     let path = StdPath::new(base_path);
 
@@ -254,7 +564,7 @@ async fn test_localfile_connection(base_path: &str) -> Result<(), Error> {
     }
 }
 
-async fn test_s3_connection(s3_config: &TestS3Config) -> Result<(), Error> {
+async fn test_s3_client(s3_config: &TestS3Config) -> aws_sdk_s3::Client {
     use aws_config::Region;
     use aws_sdk_s3::{
         Client,
@@ -284,7 +594,11 @@ async fn test_s3_connection(s3_config: &TestS3Config) -> Result<(), Error> {
         s3_config_builder.build()
     };
 
-    let client = Client::from_conf(aws_s3_config);
+    Client::from_conf(aws_s3_config)
+}
+
+async fn test_s3_connection(s3_config: &TestS3Config) -> Result<(), Error> {
+    let client = test_s3_client(s3_config).await;
 
     match client.head_bucket().bucket(&s3_config.bucket).send().await {
         Ok(_) => Ok(()),
@@ -295,6 +609,36 @@ async fn test_s3_connection(s3_config: &TestS3Config) -> Result<(), Error> {
     }
 }
 
+async fn test_gcs_connection(gcs_config: &GcsConfig) -> Result<(), Error> {
+    let client = gcs_client(gcs_config)
+        .await
+        .map_err(|e| Error::bad_request().with_message(format!("{:#}", e)))?;
+
+    match client.head_bucket().bucket(&gcs_config.bucket).send().await {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::bad_request().with_message(format!(
+            "Failed to access GCS bucket '{}': {}",
+            gcs_config.bucket, e
+        ))),
+    }
+}
+
+async fn test_http_connection(http_config: &HttpConfig) -> Result<(), Error> {
+    let client = http_client(http_config)
+        .map_err(|e| Error::bad_request().with_message(format!("{:#}", e)))?;
+
+    match client
+        .head(&http_config.base_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::bad_request()
+            .with_message(format!("Failed to reach '{}': {}", http_config.base_url, e))),
+    }
+}
+
 async fn test_sqlite_connection(path: &str) -> Result<(), Error> {
     let sqlite_path = StdPath::new(path);
 
@@ -372,6 +716,12 @@ async fn test_postgresql_connection(config: &TestRemoteDatabaseConfig) -> Result
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/connections",
+    tag = "connections",
+    responses((status = 200, description = "Connections, sorted by name", body = Vec<ConnectionSummary>))
+)]
 async fn list_connections(
     Extension(config): Extension<Arc<Mutex<Config>>>,
 ) -> Result<Json<Vec<ConnectionSummary>>, Error> {
@@ -421,6 +771,26 @@ async fn list_connections(
                 }
                 ("s3".to_string(), s3_config.bucket.clone())
             }
+            ConnectionConfig::Http(http_config) => {
+                if let Some(ref auth_token) = http_config.auth_token
+                    && let Err(e) = auth_token.plaintext()
+                {
+                    return Err(Error::internal_server_error().with_message(format!(
+                        "Failed to access HTTP auth token for connection '{}': {}",
+                        name, e
+                    )));
+                }
+                ("http".to_string(), http_config.base_url.clone())
+            }
+            ConnectionConfig::Gcs(gcs_config) => {
+                if let Err(e) = gcs_config.hmac_secret.plaintext() {
+                    return Err(Error::internal_server_error().with_message(format!(
+                        "Failed to access GCS HMAC secret for connection '{}': {}",
+                        name, e
+                    )));
+                }
+                ("gcs".to_string(), gcs_config.bucket.clone())
+            }
         };
 
         let summary = ConnectionSummary {
@@ -436,6 +806,13 @@ async fn list_connections(
     Ok(Json(connections))
 }
 
+#[utoipa::path(
+    get,
+    path = "/connections/{name}",
+    tag = "connections",
+    params(("name" = String, Path, description = "Connection name")),
+    responses((status = 200, description = "Connection config", body = ConnectionConfig))
+)]
 async fn get_connection(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Path(name): Path<String>,
@@ -443,79 +820,124 @@ async fn get_connection(
     let config = config.lock().await;
     match config.project.connections.get(&name) {
         Some(conn_config) => Ok(Json(conn_config.clone())),
-        None => Error::not_found().build(),
+        None => Error::not_found()
+            .with_code(ErrorCode::ConnectionNotFound)
+            .build(),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/connections",
+    tag = "connections",
+    request_body = CreateConnectionRequest,
+    responses((status = 200, description = "Connection created"))
+)]
 async fn create_connection(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Json(req): Json<CreateConnectionRequest>,
 ) -> Result<(), Error> {
+    ensure_valid_name(&req.name)?;
     let mut config = config.lock().await;
-    let mut project_config = config.project.clone();
-
-    if project_config.connections.contains_key(&req.name) {
-        return Error::conflict().build();
-    }
-
-    let encrypted_config = encrypt_connection_secrets(req.config.clone(), &config.project_dir)?;
-    project_config
-        .connections
-        .insert(req.name.clone(), encrypted_config);
-    let project_file = config.add_project_setting(&project_config)?;
-    project_file.save()?;
+    config.update_project(|project_config| {
+        if project_config.connections.contains_key(&req.name) {
+            return Error::conflict()
+                .with_code(ErrorCode::ConnectionAlreadyExists)
+                .build();
+        }
 
-    let decrypted_config = decrypt_connection_secrets(req.config, &config.project_dir)?;
-    project_config
-        .connections
-        .insert(req.name.clone(), decrypted_config);
-    config.add_project_setting(&project_config)?;
+        project_config
+            .connections
+            .insert(req.name.clone(), req.config.clone());
+        Ok(())
+    })?;
+    let change = AuditChange::connection(&req.name, None, Some(&req.config));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
+#[utoipa::path(
+    put,
+    path = "/connections/{name}",
+    tag = "connections",
+    params(("name" = String, Path, description = "Connection name")),
+    request_body = ConnectionConfig,
+    responses((status = 200, description = "Connection updated"))
+)]
 async fn update_connection(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
     Json(connection): Json<ConnectionConfig>,
 ) -> Result<(), Error> {
     let mut config = config.lock().await;
-    let mut project_config = config.project.clone();
-
-    if !project_config.connections.contains_key(&name) {
-        return Error::not_found().build();
-    }
-
-    let encrypted_connection = encrypt_connection_secrets(connection.clone(), &config.project_dir)?;
-    project_config
-        .connections
-        .insert(name.clone(), encrypted_connection);
-    let project_file = config.add_project_setting(&project_config)?;
-    project_file.save()?;
+    let before = config.update_project(|project_config| {
+        let Some(before) = project_config.connections.get(&name).cloned() else {
+            return Error::not_found()
+                .with_code(ErrorCode::ConnectionNotFound)
+                .build();
+        };
 
-    let decrypted_connection = decrypt_connection_secrets(connection, &config.project_dir)?;
-    project_config
-        .connections
-        .insert(name, decrypted_connection);
-    config.add_project_setting(&project_config)?;
+        project_config
+            .connections
+            .insert(name.clone(), connection.clone());
+        Ok(before)
+    })?;
+    let change = AuditChange::connection(&name, Some(&before), Some(&connection));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
+#[utoipa::path(
+    delete,
+    path = "/connections/{name}",
+    tag = "connections",
+    params(("name" = String, Path, description = "Connection name"), DeleteConnectionParams),
+    responses((status = 200, description = "Connection deleted"))
+)]
 async fn delete_connection(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
+    Query(params): Query<DeleteConnectionParams>,
 ) -> Result<(), Error> {
     let mut config = config.lock().await;
-    let mut project_config = config.project.clone();
+    let mut dependents: Vec<String> = config
+        .adapters
+        .iter()
+        .filter(|(_, adapter)| adapter.connection == name)
+        .map(|(adapter_name, _)| adapter_name.clone())
+        .collect();
+
+    let before = config.update_project(|project_config| {
+        let Some(before) = project_config.connections.remove(&name) else {
+            return Error::not_found()
+                .with_code(ErrorCode::ConnectionNotFound)
+                .build();
+        };
 
-    if !project_config.connections.contains_key(&name) {
-        return Error::not_found().build();
-    }
+        if !dependents.is_empty() && !params.force {
+            dependents.sort();
+            return Error::conflict()
+                .with_code(ErrorCode::ConnectionInUse)
+                .with_message(format!(
+                    "Connection '{name}' is used by adapters: {}",
+                    dependents.join(", ")
+                ))
+                .with_detail("adapters", dependents)
+                .build();
+        }
 
-    project_config.connections.remove(&name);
-    let project_file = config.add_project_setting(&project_config)?;
-    project_file.save()?;
+        Ok(before)
+    })?;
+    let change = AuditChange::connection(&name, Some(&before), None);
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
@@ -527,9 +949,13 @@ mod tests {
         api::StatusCode,
         core::{
             adapter::test_helpers::{
-                create_test_s3_config, setup_minio_test_data, test_encrypted_field,
+                create_csv_adapter_config, create_test_s3_config, setup_minio_test_data,
+                test_encrypted_field,
+            },
+            config::{
+                adapter::{AdapterConfig, AdapterSource},
+                project::{RemoteDatabaseConfig, parse_project_config},
             },
-            config::project::RemoteDatabaseConfig,
         },
         test_helpers::TestManager,
     };
@@ -568,6 +994,176 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_concurrent_connection_creation() -> Result<()> {
+        const CONNECTIONS: usize = 16;
+
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let requests = (0..CONNECTIONS).map(|i| {
+            let server = &server;
+            async move {
+                server
+                    .post("/connections")
+                    .json(&json!({
+                        "name": format!("warehouse_{i}"),
+                        "config": {
+                            "type": "mysql",
+                            "config": {
+                                "host": "localhost",
+                                "port": 3306,
+                                "database": "testdb",
+                                "username": "user",
+                                "password": { "type": "plain", "value": format!("secret_{i}") }
+                            }
+                        }
+                    }))
+                    .await
+            }
+        });
+        for response in futures::future::join_all(requests).await {
+            response.assert_status_ok();
+        }
+
+        let content = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(!content.contains("secret_"));
+        let project = parse_project_config(&content)?;
+        assert_eq!(project.connections.len(), CONNECTIONS);
+        for (name, connection) in &project.connections {
+            let ConnectionConfig::MySql(mysql) = connection else {
+                panic!("Expected MySQL connection for {name}");
+            };
+            assert!(matches!(mysql.password, SecretField::Encrypted { .. }));
+        }
+
+        let config = test.config().await;
+        assert_eq!(config.project.connections.len(), CONNECTIONS);
+        let ConnectionConfig::MySql(mysql) = &config.project.connections["warehouse_3"] else {
+            panic!("Expected MySQL connection");
+        };
+        assert_eq!(mysql.password.plaintext()?, "secret_3");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_connection_keeps_edits_made_on_disk() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        // Another process adds a connection after the server has loaded the project.
+        let project_file = test.directory().join("project.yml");
+        let mut project = parse_project_config(&std::fs::read_to_string(&project_file)?)?;
+        project.connections.insert(
+            "external".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: "/tmp/external".to_string(),
+            },
+        );
+        std::fs::write(&project_file, serde_yml::to_string(&project)?)?;
+
+        server
+            .post("/connections")
+            .json(&json!({
+                "name": "local",
+                "config": { "type": "localfile", "config": { "base_path": "/tmp/local" } }
+            }))
+            .await
+            .assert_status_ok();
+
+        let project = parse_project_config(&std::fs::read_to_string(&project_file)?)?;
+        assert!(project.connections.contains_key("external"));
+        assert!(project.connections.contains_key("local"));
+
+        let config = test.config().await;
+        assert!(config.project.connections.contains_key("external"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_connection_secret_reference() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        std::fs::write(test.directory().join("db_password"), "file_password\n")?;
+        let password = json!({ "type": "file", "path": "db_password" });
+        server
+            .post("/connections")
+            .json(&json!({
+                "name": "warehouse",
+                "config": {
+                    "type": "postgresql",
+                    "config": {
+                        "host": "localhost",
+                        "port": 5432,
+                        "database": "testdb",
+                        "username": "user",
+                        "password": password
+                    }
+                }
+            }))
+            .await
+            .assert_status_ok();
+
+        let content = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        let project = parse_project_config(&content)?;
+        let ConnectionConfig::PostgreSql(postgres) = &project.connections["warehouse"] else {
+            panic!("Expected PostgreSQL connection");
+        };
+        assert_eq!(
+            postgres.password,
+            SecretField::File {
+                path: "db_password".to_string(),
+                value: None,
+            }
+        );
+
+        {
+            let config = test.config().await;
+            let ConnectionConfig::PostgreSql(postgres) = &config.project.connections["warehouse"]
+            else {
+                panic!("Expected PostgreSQL connection");
+            };
+            assert_eq!(postgres.password.plaintext()?, "file_password");
+        }
+
+        let response = server.get("/connections/warehouse").await;
+        response.assert_status_ok();
+        assert_eq!(
+            response.json::<serde_json::Value>()["config"]["password"],
+            password
+        );
+
+        // Other variables could hand the server's own credentials to any host.
+        server
+            .post("/connections")
+            .json(&json!({
+                "name": "leaky",
+                "config": {
+                    "type": "postgresql",
+                    "config": {
+                        "host": "attacker.example.com",
+                        "port": 5432,
+                        "database": "testdb",
+                        "username": "user",
+                        "password": { "type": "env", "name": "AWS_SECRET_ACCESS_KEY" }
+                    }
+                }
+            }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        let content = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(
+            !parse_project_config(&content)?
+                .connections
+                .contains_key("leaky")
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_connection_sqlite() -> Result<()> {
         let test = TestManager::new();
@@ -719,6 +1315,77 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_delete_connection_referenced_by_adapter() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.project.clone();
+            project_config.connections.insert(
+                "shared_connection".to_string(),
+                ConnectionConfig::LocalFile {
+                    base_path: "/tmp/test".to_string(),
+                },
+            );
+            config.add_project_setting(&project_config)?.save()?;
+
+            let adapter_config = AdapterConfig {
+                connection: "shared_connection".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: "users".to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: None,
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config
+                .upsert_adapter("users_adapter", &adapter_config)?
+                .save()?;
+            config
+                .upsert_adapter("orders_adapter", &adapter_config)?
+                .save()?;
+        }
+
+        let response = server.delete("/connections/shared_connection").await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONNECTION_IN_USE");
+        assert_eq!(
+            body["details"]["adapters"],
+            json!(["orders_adapter", "users_adapter"])
+        );
+        server
+            .get("/connections/shared_connection")
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .delete("/connections/shared_connection")
+            .add_query_param("force", "true")
+            .await;
+        response.assert_status_ok();
+        server
+            .get("/connections/shared_connection")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_list_connections() -> Result<()> {
         let test = TestManager::new();
@@ -875,12 +1542,7 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
 
         let body: serde_json::Value = response.json();
-        assert!(
-            body["message"]
-                .as_str()
-                .unwrap()
-                .contains("Path does not exist")
-        );
+        assert_eq!(body["code"], "CONNECTION_FAILED");
 
         Ok(())
     }
@@ -910,6 +1572,82 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_connection_sqlite_read_checks() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let connection = rusqlite::Connection::open(&db_path)?;
+        connection.execute("CREATE TABLE events (id INTEGER)", [])?;
+        drop(connection);
+        let path = db_path.to_string_lossy().to_string();
+
+        let response = server
+            .post("/connections/test")
+            .json(&json!({ "type": "sqlite", "path": path, "table": "events" }))
+            .await;
+        response.assert_status_ok();
+        let result: ConnectionTestResult = response.json();
+        assert!(result.success);
+        assert_eq!(result.checks["connectivity"].status, CheckStatus::Ok);
+        assert_eq!(result.checks["read"].status, CheckStatus::Ok);
+
+        // Without a table, the first adapter bound to the connection supplies one.
+        let mut adapter = create_csv_adapter_config();
+        adapter.connection = "warehouse".to_string();
+        adapter.source = AdapterSource::Database {
+            table_name: "missing".to_string(),
+            columns: None,
+            filter: None,
+            incremental: None,
+        };
+        test.config()
+            .lock()
+            .await
+            .adapters
+            .insert("orders".to_string(), adapter);
+
+        let response = server
+            .post("/connections/test")
+            .json(&json!({ "type": "sqlite", "path": path, "name": "warehouse" }))
+            .await;
+        response.assert_status_ok();
+        let result: ConnectionTestResult = response.json();
+        assert!(!result.success);
+        assert_eq!(result.checks["connectivity"].status, CheckStatus::Ok);
+        assert_eq!(result.checks["read"].status, CheckStatus::Failed);
+        assert!(
+            result.checks["read"]
+                .message
+                .as_deref()
+                .unwrap()
+                .contains("missing")
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(&db_path, std::fs::Permissions::from_mode(0o000))?;
+            // Permission bits don't stop root, so there is nothing to observe there.
+            if std::fs::File::open(&db_path).is_err() {
+                let response = server
+                    .post("/connections/test")
+                    .json(&json!({ "type": "sqlite", "path": path, "table": "events" }))
+                    .await;
+                response.assert_status_ok();
+                let result: ConnectionTestResult = response.json();
+                assert!(!result.success);
+                assert_eq!(result.checks["connectivity"].status, CheckStatus::Ok);
+                assert_eq!(result.checks["read"].status, CheckStatus::Denied);
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_connection_sqlite_path_not_found() -> Result<()> {
         let test = TestManager::new();
@@ -927,7 +1665,7 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
 
         let body: serde_json::Value = response.json();
-        assert!(body["message"].as_str().unwrap().contains("does not exist"));
+        assert_eq!(body["code"], "CONNECTION_FAILED");
 
         Ok(())
     }
@@ -980,11 +1718,7 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
 
         let body: serde_json::Value = response.json();
-        assert!(
-            body["message"].as_str().unwrap().contains("MySQL")
-                || body["message"].as_str().unwrap().contains("connection")
-                || body["message"].as_str().unwrap().contains("password")
-        );
+        assert_eq!(body["code"], "CONNECTION_FAILED");
 
         Ok(())
     }
@@ -1037,11 +1771,7 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
 
         let body: serde_json::Value = response.json();
-        assert!(
-            body["message"].as_str().unwrap().contains("PostgreSQL")
-                || body["message"].as_str().unwrap().contains("database")
-                || body["message"].as_str().unwrap().contains("connection")
-        );
+        assert_eq!(body["code"], "CONNECTION_FAILED");
 
         Ok(())
     }
@@ -1075,6 +1805,132 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_connection_s3_capabilities() -> Result<()> {
+        setup_minio_test_data().await?;
+
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let s3_config = |bucket: &str| TestConnectionConfig::S3 {
+            bucket: bucket.to_string(),
+            region: "us-east-1".to_string(),
+            endpoint_url: Some("http://localhost:9010".to_string()),
+            auth_method: "explicit".to_string(),
+            access_key_id: Some("user".to_string()),
+            secret_access_key: Some("password".to_string()),
+            path_style_access: Some(true),
+        };
+
+        let request = TestConnectionRequest {
+            connection: s3_config("test-bucket"),
+            table: Some("test-data/*.csv".to_string()),
+            name: None,
+        };
+        let response = server.post("/connections/test").json(&request).await;
+        response.assert_status_ok();
+        let result: ConnectionTestResult = response.json();
+        assert!(result.success);
+        assert_eq!(result.checks["list"].status, CheckStatus::Ok);
+        assert_eq!(result.checks["read"].status, CheckStatus::Ok);
+
+        // An empty bucket can be listed but has nothing to read.
+        let client = test_s3_client(&TestS3Config {
+            bucket: "empty-bucket".to_string(),
+            region: "us-east-1".to_string(),
+            endpoint_url: Some("http://localhost:9010".to_string()),
+            auth_method: "explicit".to_string(),
+            access_key_id: Some("user".to_string()),
+            secret_access_key: Some("password".to_string()),
+            path_style_access: true,
+        })
+        .await;
+        let _ = client.create_bucket().bucket("empty-bucket").send().await;
+
+        let request = TestConnectionRequest {
+            connection: s3_config("empty-bucket"),
+            table: None,
+            name: None,
+        };
+        let response = server.post("/connections/test").json(&request).await;
+        response.assert_status_ok();
+        let result: ConnectionTestResult = response.json();
+        assert!(result.success);
+        assert_eq!(result.checks["list"].status, CheckStatus::Ok);
+        assert_eq!(result.checks["read"].status, CheckStatus::Skipped);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_connection_gcs_encrypts_secret() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let new_connection = json!({
+            "name": "lake",
+            "config": {
+                "type": "gcs",
+                "config": {
+                    "bucket": "analytics",
+                    "hmac_key_id": "GOOG1EXAMPLE",
+                    "hmac_secret": {
+                        "type": "plain",
+                        "value": "hmac-secret"
+                    }
+                }
+            }
+        });
+
+        let response = server.post("/connections").json(&new_connection).await;
+        response.assert_status_ok();
+
+        let project_yaml = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(!project_yaml.contains("hmac-secret"));
+
+        let response = server.get("/connections").await;
+        response.assert_status_ok();
+        let connections: Vec<ConnectionSummary> = response.json();
+        assert_eq!(connections[0].connection_type, "gcs");
+        assert_eq!(connections[0].details, "analytics");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_gcs_emulator() -> Result<()> {
+        setup_minio_test_data().await?;
+
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let connection_config = TestConnectionConfig::Gcs {
+            bucket: "test-bucket".to_string(),
+            hmac_key_id: "user".to_string(),
+            hmac_secret: "password".to_string(),
+            endpoint_url: Some("http://localhost:9010".to_string()),
+        };
+        let response = server
+            .post("/connections/test")
+            .json(&connection_config)
+            .await;
+        response.assert_status_ok();
+
+        let connection_config = TestConnectionConfig::Gcs {
+            bucket: "nonexistent-bucket".to_string(),
+            hmac_key_id: "user".to_string(),
+            hmac_secret: "password".to_string(),
+            endpoint_url: Some("http://localhost:9010".to_string()),
+        };
+        let response = server
+            .post("/connections/test")
+            .json(&connection_config)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_connection_s3_bucket_not_found() -> Result<()> {
         let test = TestManager::new();
@@ -1097,10 +1953,7 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
 
         let body: serde_json::Value = response.json();
-        assert!(
-            body["message"].as_str().unwrap().contains("S3")
-                || body["message"].as_str().unwrap().contains("bucket")
-        );
+        assert_eq!(body["code"], "CONNECTION_FAILED");
 
         Ok(())
     }
@@ -1,19 +1,38 @@
 use crate::{
-    api::Error,
+    api::{
+        Error, ErrorCode, LabelFilter, MaskingParams, QueryLimiter, RenameRequest,
+        audit::record_changes,
+        auth::Principal,
+        cache::{CacheStatus, cached},
+        ensure_valid_name,
+        query::{SnapshotParams, default_sql, masked_reference, param_failed, query_failed},
+    },
     core::{
+        audit::{AuditChange, AuditLog},
         config::{
             Config,
-            dashboard::{ChartType, DashboardConfig},
+            dashboard::{ChartType, DashboardConfig, PanelConfig},
         },
-        ducklake::DuckLake,
+        ducklake::DuckLakeCache,
+        graph::Graph,
+        masking::{mask_values, query_masks},
+        query_cache::{CacheKey, CachedResult, QueryCache},
+        query_params::bind_query,
+        share::Shares,
     },
 };
-use axum::{Extension, Router, extract::Path, response::Json, routing::get};
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    response::Json,
+    routing::{get, post},
+};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateDashboardRequest {
     pub name: String,
     pub config: DashboardConfig,
@@ -29,12 +48,38 @@ pub fn router() -> Router {
                 .delete(delete_dashboard),
         )
         .route("/dashboards/{name}/data", get(get_dashboard_data))
+        .route(
+            "/dashboards/{name}/panels/{index}/data",
+            get(get_panel_data),
+        )
+        .route("/dashboards/{name}/rename", post(rename_dashboard))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_dashboards,
+    create_dashboard,
+    get_dashboard,
+    update_dashboard,
+    delete_dashboard,
+    get_dashboard_data,
+    get_panel_data,
+    rename_dashboard
+))]
+pub struct DashboardApi;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DashboardListItem {
     pub name: String,
     pub description: Option<String>,
+    pub panels: Vec<PanelSummary>,
+    pub tags: Vec<String>,
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PanelSummary {
+    pub title: Option<String>,
     pub query: String,
     pub chart_type: String,
 }
@@ -55,32 +100,62 @@ pub struct ChartRequest {
     pub y_column: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DashboardDataResponse {
     pub labels: Vec<serde_json::Value>,
     pub values: Vec<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<ScatterPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScatterPoint {
+    pub x: f64,
+    pub y: f64,
 }
 
+#[utoipa::path(
+    get,
+    path = "/dashboards",
+    tag = "dashboards",
+    params(LabelFilter),
+    responses((status = 200, description = "Dashboards", body = Vec<DashboardListItem>))
+)]
 async fn list_dashboards(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Query(filter): Query<LabelFilter>,
 ) -> Result<Json<Vec<DashboardListItem>>, Error> {
     let config = config.lock().await;
     let dashboards: Vec<DashboardListItem> = config
         .dashboards
         .iter()
+        .filter(|(_, dashboard)| filter.matches(&dashboard.tags, dashboard.group.as_deref()))
         .map(|(name, dashboard_config)| DashboardListItem {
             name: name.clone(),
             description: dashboard_config.description.clone(),
-            query: dashboard_config.query.clone(),
-            chart_type: match dashboard_config.chart.chart_type {
-                ChartType::Line => "line".to_string(),
-                ChartType::Bar => "bar".to_string(),
-            },
+            panels: dashboard_config
+                .panels
+                .iter()
+                .map(|panel| PanelSummary {
+                    title: panel.title.clone(),
+                    query: panel.query.clone(),
+                    chart_type: panel.chart.chart_type.as_str().to_string(),
+                })
+                .collect(),
+            tags: dashboard_config.tags.clone(),
+            group: dashboard_config.group.clone(),
         })
         .collect();
     Ok(Json(dashboards))
 }
 
+#[utoipa::path(
+    get,
+    path = "/dashboards/{name}",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name")),
+    responses((status = 200, description = "Dashboard config", body = DashboardConfig))
+)]
 async fn get_dashboard(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Path(name): Path<String>,
@@ -89,140 +164,469 @@ async fn get_dashboard(
     if let Some(dashboard_config) = config.dashboards.get(&name) {
         Ok(Json(dashboard_config.clone()))
     } else {
-        Error::not_found().build()
+        Error::not_found()
+            .with_code(ErrorCode::DashboardNotFound)
+            .build()
     }
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ValidationParams {
+    #[serde(default)]
+    skip_validation: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/dashboards",
+    tag = "dashboards",
+    params(ValidationParams),
+    request_body = CreateDashboardRequest,
+    responses((status = 200, description = "Dashboard created"))
+)]
 async fn create_dashboard(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<ValidationParams>,
     Json(request): Json<CreateDashboardRequest>,
 ) -> Result<(), Error> {
-    let mut config = config.lock().await;
-    if config.dashboards.contains_key(&request.name) {
-        return Error::conflict().build();
+    ensure_valid_name(&request.name)?;
+    let sqls = {
+        let config = config.lock().await;
+        ensure_dashboard_absent(&config, &request.name)?;
+        panel_sqls(&config, &request.config)?
+    };
+    if !params.skip_validation {
+        validate_panels(&config, &ducklakes, &request.config, &sqls).await?;
     }
 
+    let mut config = config.lock().await;
+    ensure_dashboard_absent(&config, &request.name)?;
     let dashboard_file = config.upsert_dashboard(&request.name, &request.config)?;
     dashboard_file.save()?;
+    let change = AuditChange::new("dashboards", &request.name, None, Some(&request.config));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
+#[utoipa::path(
+    put,
+    path = "/dashboards/{name}",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name"), ValidationParams),
+    request_body = DashboardConfig,
+    responses((status = 200, description = "Dashboard updated"))
+)]
 async fn update_dashboard(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
+    Query(params): Query<ValidationParams>,
     Json(dashboard): Json<DashboardConfig>,
 ) -> Result<(), Error> {
-    let mut config = config.lock().await;
-    if !config.dashboards.contains_key(&name) {
-        return Error::not_found().build();
+    let sqls = {
+        let config = config.lock().await;
+        ensure_dashboard_exists(&config, &name)?;
+        panel_sqls(&config, &dashboard)?
+    };
+    if !params.skip_validation {
+        validate_panels(&config, &ducklakes, &dashboard, &sqls).await?;
     }
 
+    let mut config = config.lock().await;
+    ensure_dashboard_exists(&config, &name)?;
+    let before = config.dashboards[&name].clone();
     let dashboard_file = config.upsert_dashboard(&name, &dashboard)?;
     dashboard_file.save()?;
+    let change = AuditChange::new("dashboards", &name, Some(&before), Some(&dashboard));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
-async fn delete_dashboard(
+#[utoipa::path(
+    post,
+    path = "/dashboards/{name}/rename",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name")),
+    request_body = RenameRequest,
+    responses((status = 200, description = "Dashboard renamed"))
+)]
+async fn rename_dashboard(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
+    Json(request): Json<RenameRequest>,
 ) -> Result<(), Error> {
+    ensure_valid_name(&request.new_name)?;
     let mut config = config.lock().await;
-    if !config.dashboards.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(dashboard) = config.dashboards.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::DashboardNotFound)
+            .build();
+    };
+    if config.dashboards.contains_key(&request.new_name) {
+        return Error::conflict()
+            .with_code(ErrorCode::DashboardAlreadyExists)
+            .build();
     }
 
-    let dashboard_file = config.delete_dashboard(&name)?;
+    let dashboard_file = config.rename_dashboard(&name, &request.new_name)?;
     dashboard_file.save()?;
+    let changes = AuditChange::renamed("dashboards", &name, &request.new_name, &dashboard);
+    record_changes(&audit, &config, principal.as_deref(), changes);
+
+    let mut shares = Shares::load(&config.project_dir)?;
+    if shares.rename_dashboard(&name, &request.new_name) {
+        shares.save()?;
+    }
+
+    Ok(())
+}
+
+fn ensure_dashboard_absent(config: &Config, name: &str) -> Result<(), Error> {
+    if config.dashboards.contains_key(name) {
+        return Error::conflict()
+            .with_code(ErrorCode::DashboardAlreadyExists)
+            .build();
+    }
+
+    Ok(())
+}
+
+fn ensure_dashboard_exists(config: &Config, name: &str) -> Result<(), Error> {
+    if !config.dashboards.contains_key(name) {
+        return Error::not_found()
+            .with_code(ErrorCode::DashboardNotFound)
+            .build();
+    }
+
+    Ok(())
+}
+
+fn panel_sqls(config: &Config, dashboard: &DashboardConfig) -> Result<Vec<String>, Error> {
+    dashboard
+        .panels
+        .iter()
+        .map(|panel| match config.queries.get(&panel.query) {
+            Some(query) => default_sql(query),
+            None => Error::bad_request()
+                .with_code(ErrorCode::QueryNotFound)
+                .with_message(format!("Query '{}' does not exist", panel.query))
+                .with_detail("query", panel.query.as_str())
+                .build(),
+        })
+        .collect()
+}
+
+async fn validate_panels(
+    config: &Mutex<Config>,
+    ducklakes: &DuckLakeCache,
+    dashboard: &DashboardConfig,
+    sqls: &[String],
+) -> Result<(), Error> {
+    for (index, (panel, sql)) in dashboard.panels.iter().zip(sqls).enumerate() {
+        let columns = describe_query(config, ducklakes, sql).await?;
+        ensure_chart_columns(panel, &columns).map_err(|e| e.with_detail("panel", index))?;
+    }
 
     Ok(())
 }
 
-async fn get_dashboard_data(
+async fn describe_query(
+    config: &Mutex<Config>,
+    ducklakes: &DuckLakeCache,
+    sql: &str,
+) -> Result<Vec<String>, Error> {
+    let ducklake = ducklakes.get(config).await?;
+    let sql = sql.trim().trim_end_matches(';');
+    let schema = ducklake.query(&format!("DESCRIBE ({sql})")).map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::QueryFailed)
+            .with_message(format!("{e:#}"))
+    })?;
+
+    Ok(schema.into_iter().map(|row| row[0].clone()).collect())
+}
+
+// Both chart columns must be among the columns the query returns.
+fn ensure_chart_columns(panel: &PanelConfig, columns: &[String]) -> Result<(), Error> {
+    for column in [&panel.chart.x_column, &panel.chart.y_column] {
+        if !columns.contains(column) {
+            return missing_column(column)
+                .with_detail("available_columns", columns.to_vec())
+                .build();
+        }
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/dashboards/{name}",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name")),
+    responses((status = 200, description = "Dashboard deleted"))
+)]
+async fn delete_dashboard(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
-) -> Result<Json<DashboardDataResponse>, Error> {
-    let config = config.lock().await;
-    let dashboard_config = match config.dashboards.get(&name) {
-        Some(c) => c,
-        None => return Error::not_found().build(),
+) -> Result<(), Error> {
+    let mut config = config.lock().await;
+    let Some(before) = config.dashboards.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::DashboardNotFound)
+            .build();
     };
 
-    let query_config = match config.queries.get(&dashboard_config.query) {
-        Some(q) => q,
-        None => return Error::not_found().build(),
-    };
+    let dashboard_file = config.delete_dashboard(&name)?;
+    dashboard_file.save()?;
+    let change = AuditChange::new("dashboards", &name, Some(&before), None);
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
-    let ducklake = DuckLake::from_config(&config).await?;
+    Ok(())
+}
 
-    let describe_sql = format!("DESCRIBE ({})", query_config.sql);
-    let describe_results = ducklake.query(&describe_sql)?;
+// Predates panels; only answers for dashboards with a single panel.
+#[utoipa::path(
+    get,
+    path = "/dashboards/{name}/data",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name"), SnapshotParams, MaskingParams),
+    responses((status = 200, description = "Chart data of a single-panel dashboard", body = DashboardDataResponse, headers(("x-cache" = String, description = "`HIT` or `MISS`"))))
+)]
+pub(super) async fn get_dashboard_data(
+    config: Extension<Arc<Mutex<Config>>>,
+    graph: Extension<Arc<Mutex<Graph>>>,
+    ducklakes: Extension<Arc<DuckLakeCache>>,
+    query_cache: Extension<Arc<QueryCache>>,
+    limiter: Extension<Arc<QueryLimiter>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    params: Query<SnapshotParams>,
+    masking: Query<MaskingParams>,
+) -> Result<(CacheStatus, Json<DashboardDataResponse>), Error> {
+    let panels = match config.lock().await.dashboards.get(&name) {
+        Some(dashboard) => dashboard.panels.len(),
+        None => {
+            return Error::not_found()
+                .with_code(ErrorCode::DashboardNotFound)
+                .build();
+        }
+    };
+    if panels != 1 {
+        return Error::bad_request()
+            .with_message(format!(
+                "Dashboard '{name}' has {panels} panels; use /dashboards/{name}/panels/{{index}}/data"
+            ))
+            .with_detail("panels", panels)
+            .build();
+    }
 
-    let mut x_column_index = None;
-    let mut y_column_index = None;
+    get_panel_data(
+        config,
+        graph,
+        ducklakes,
+        query_cache,
+        limiter,
+        principal,
+        Path((name, 0)),
+        params,
+        masking,
+    )
+    .await
+}
 
-    for (idx, describe_row) in describe_results.iter().enumerate() {
-        if !describe_row.is_empty() {
-            let column_name = &describe_row[0];
-            if column_name == &dashboard_config.chart.x_column {
-                x_column_index = Some(idx);
+#[utoipa::path(
+    get,
+    path = "/dashboards/{name}/panels/{index}/data",
+    tag = "dashboards",
+    params(("name" = String, Path, description = "Dashboard name"), ("index" = usize, Path, description = "Zero-based panel index"), SnapshotParams, MaskingParams),
+    responses((status = 200, description = "Chart data of the panel", body = DashboardDataResponse, headers(("x-cache" = String, description = "`HIT` or `MISS`"))))
+)]
+pub(super) async fn get_panel_data(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(query_cache): Extension<Arc<QueryCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    principal: Option<Extension<Principal>>,
+    Path((name, index)): Path<(String, usize)>,
+    Query(params): Query<SnapshotParams>,
+    Query(masking): Query<MaskingParams>,
+) -> Result<(CacheStatus, Json<DashboardDataResponse>), Error> {
+    let snapshot = params.snapshot()?;
+    let (panel, bound, masks, engine, cache_config) = {
+        let config = config.lock().await;
+        let panel = match config.dashboards.get(&name) {
+            Some(dashboard) => match dashboard.panels.get(index) {
+                Some(panel) => panel.clone(),
+                None => {
+                    return Error::not_found()
+                        .with_message(format!("Dashboard '{name}' has no panel {index}"))
+                        .with_detail("panels", dashboard.panels.len())
+                        .build();
+                }
+            },
+            None => {
+                return Error::not_found()
+                    .with_code(ErrorCode::DashboardNotFound)
+                    .build();
             }
-            if column_name == &dashboard_config.chart.y_column {
-                y_column_index = Some(idx);
+        };
+
+        // Dashboards run parameterized queries with their defaults.
+        let bound = match config.queries.get(&panel.query) {
+            Some(q) => bind_query(q, &HashMap::new()).map_err(param_failed)?,
+            None => {
+                return Error::not_found()
+                    .with_code(ErrorCode::QueryNotFound)
+                    .build();
             }
-        }
-    }
+        };
 
-    let x_idx = match x_column_index {
-        Some(idx) => idx,
-        None => return Error::bad_request().build(),
+        let masks = masking.masks(principal.as_deref(), || {
+            query_masks(&config, &bound.inlined).map_err(masked_reference)
+        })?;
+
+        (
+            panel,
+            bound,
+            masks,
+            config.project.engine.clone(),
+            config.project.cache.clone(),
+        )
     };
-    let y_idx = match y_column_index {
-        Some(idx) => idx,
-        None => return Error::bad_request().build(),
+    let key = CacheKey::new(
+        &*graph.lock().await,
+        &panel.query,
+        &bound.inlined,
+        None,
+        snapshot.as_ref(),
+    );
+
+    let (cached_result, status) = cached(&query_cache, &cache_config, key, async {
+        let _permit = limiter.acquire(&engine).await?;
+        let ducklake = ducklakes.get(&config).await?;
+        let result = ducklake
+            .query_bound_with_timeout(&bound, snapshot.as_ref(), engine.dashboard_timeout())
+            .await
+            .map_err(query_failed)?;
+        Ok(CachedResult {
+            result,
+            truncated: false,
+        })
+    })
+    .await?;
+    let result = &cached_result.result;
+
+    ensure_chart_columns(&panel, &result.columns)?;
+    let mut labels = result.data[&panel.chart.x_column].clone();
+    let mut values = result.data[&panel.chart.y_column].clone();
+    mask_values(&masks, &panel.chart.x_column, &mut labels);
+    mask_values(&masks, &panel.chart.y_column, &mut values);
+
+    let points = match panel.chart.chart_type {
+        ChartType::Scatter => {
+            let xs = numeric_column(&labels, &panel.chart.x_column)?;
+            let ys = numeric_column(&values, &panel.chart.y_column)?;
+            xs.into_iter()
+                .zip(ys)
+                .map(|(x, y)| ScatterPoint { x, y })
+                .collect()
+        }
+        ChartType::Line | ChartType::Bar | ChartType::Pie | ChartType::Area => Vec::new(),
     };
 
-    let query_results = ducklake.query(&query_config.sql)?;
-
-    let mut labels = Vec::new();
-    let mut values = Vec::new();
-
-    for row in query_results {
-        if row.len() > x_idx && row.len() > y_idx {
-            labels.push(serde_json::Value::String(row[x_idx].clone()));
-            if let Ok(num) = row[y_idx].parse::<f64>() {
-                values.push(serde_json::Value::Number(
-                    serde_json::Number::from_f64(num)
-                        .unwrap_or_else(|| serde_json::Number::from(0)),
-                ));
-            } else {
-                values.push(serde_json::Value::String(row[y_idx].clone()));
-            }
-        }
-    }
+    Ok((
+        status,
+        Json(DashboardDataResponse {
+            labels,
+            values,
+            points,
+        }),
+    ))
+}
 
-    Ok(Json(DashboardDataResponse { labels, values }))
+fn missing_column(column: &str) -> Error {
+    Error::bad_request()
+        .with_code(ErrorCode::InvalidChart)
+        .with_message(format!("Column '{column}' is not returned by the query"))
+        .with_detail("column", column)
+}
+
+fn numeric_column(values: &[serde_json::Value], column: &str) -> Result<Vec<f64>, Error> {
+    values
+        .iter()
+        .map(|value| match value {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.parse::<f64>().ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<f64>>>()
+        .ok_or_else(|| {
+            Error::bad_request()
+                .with_code(ErrorCode::InvalidChart)
+                .with_message(format!(
+                    "Column '{column}' must be numeric for scatter charts"
+                ))
+                .with_detail("column", column)
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        api::StatusCode,
-        core::config::{
-            dashboard::{ChartConfig, ChartType},
-            query::QueryConfig,
+        api::{StatusCode, cache::CACHE_HEADER},
+        core::{
+            config::{
+                dashboard::{ChartConfig, ChartType, PanelConfig},
+                query::QueryConfig,
+            },
+            ducklake::DuckLake,
         },
         test_helpers::TestManager,
     };
     use anyhow::Result;
     use serde_json::json;
 
+    async fn save_query(test: &TestManager, name: &str, sql: &str) -> Result<()> {
+        let query_config = QueryConfig {
+            description: None,
+            sql: sql.to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+        test.config()
+            .await
+            .upsert_query(name, &query_config)?
+            .save()?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_dashboard_line_chart() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(router);
+        save_query(
+            &test,
+            "monthly_sales",
+            "SELECT 'Jan' AS month, 1000 AS revenue",
+        )
+        .await?;
 
         let new_dashboard = json!({
             "name": "test_dashboard",
@@ -248,10 +652,10 @@ mod tests {
             dashboard_config.description,
             Some("Monthly sales overview".to_string())
         );
-        assert_eq!(dashboard_config.query, "monthly_sales");
-        assert_eq!(dashboard_config.chart.chart_type, ChartType::Line);
-        assert_eq!(dashboard_config.chart.x_column, "month");
-        assert_eq!(dashboard_config.chart.y_column, "revenue");
+        assert_eq!(dashboard_config.panels[0].query, "monthly_sales");
+        assert_eq!(dashboard_config.panels[0].chart.chart_type, ChartType::Line);
+        assert_eq!(dashboard_config.panels[0].chart.x_column, "month");
+        assert_eq!(dashboard_config.panels[0].chart.y_column, "revenue");
 
         Ok(())
     }
@@ -260,6 +664,12 @@ mod tests {
     async fn test_create_dashboard_bar_chart() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(router);
+        save_query(
+            &test,
+            "category_breakdown",
+            "SELECT 'A' AS category, 10 AS total_amount",
+        )
+        .await?;
 
         let new_dashboard = json!({
             "name": "category_dashboard",
@@ -281,10 +691,10 @@ mod tests {
 
         let dashboard_config: DashboardConfig = get_response.json();
         assert_eq!(dashboard_config.description, None);
-        assert_eq!(dashboard_config.query, "category_breakdown");
-        assert_eq!(dashboard_config.chart.chart_type, ChartType::Bar);
-        assert_eq!(dashboard_config.chart.x_column, "category");
-        assert_eq!(dashboard_config.chart.y_column, "total_amount");
+        assert_eq!(dashboard_config.panels[0].query, "category_breakdown");
+        assert_eq!(dashboard_config.panels[0].chart.chart_type, ChartType::Bar);
+        assert_eq!(dashboard_config.panels[0].chart.x_column, "category");
+        assert_eq!(dashboard_config.panels[0].chart.y_column, "total_amount");
 
         Ok(())
     }
@@ -293,15 +703,20 @@ mod tests {
     async fn test_update_dashboard() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(router);
+        save_query(&test, "updated_query", "SELECT 1 AS new_x, 2 AS new_y").await?;
 
         let original_dashboard = DashboardConfig {
             description: Some("Original description".to_string()),
-            query: "original_query".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "x".to_string(),
-                y_column: "y".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "original_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x".to_string(),
+                    y_column: "y".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -334,10 +749,10 @@ mod tests {
             dashboard_config.description,
             Some("Updated description".to_string())
         );
-        assert_eq!(dashboard_config.query, "updated_query");
-        assert_eq!(dashboard_config.chart.chart_type, ChartType::Bar);
-        assert_eq!(dashboard_config.chart.x_column, "new_x");
-        assert_eq!(dashboard_config.chart.y_column, "new_y");
+        assert_eq!(dashboard_config.panels[0].query, "updated_query");
+        assert_eq!(dashboard_config.panels[0].chart.chart_type, ChartType::Bar);
+        assert_eq!(dashboard_config.panels[0].chart.x_column, "new_x");
+        assert_eq!(dashboard_config.panels[0].chart.y_column, "new_y");
 
         Ok(())
     }
@@ -349,12 +764,16 @@ mod tests {
 
         let dashboard_config = DashboardConfig {
             description: Some("This will be deleted".to_string()),
-            query: "test_query".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "x".to_string(),
-                y_column: "y".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "test_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x".to_string(),
+                    y_column: "y".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -383,22 +802,30 @@ mod tests {
 
         let dashboard1 = DashboardConfig {
             description: Some("First description".to_string()),
-            query: "query1".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "x1".to_string(),
-                y_column: "y1".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "query1",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x1".to_string(),
+                    y_column: "y1".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         let dashboard2 = DashboardConfig {
             description: None,
-            query: "query2".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Bar,
-                x_column: "x2".to_string(),
-                y_column: "y2".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "query2",
+                ChartConfig {
+                    chart_type: ChartType::Bar,
+                    x_column: "x2".to_string(),
+                    y_column: "y2".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -418,13 +845,13 @@ mod tests {
             dashboard1_item.description,
             Some("First description".to_string())
         );
-        assert_eq!(dashboard1_item.query, "query1");
-        assert_eq!(dashboard1_item.chart_type, "line");
+        assert_eq!(dashboard1_item.panels[0].query, "query1");
+        assert_eq!(dashboard1_item.panels[0].chart_type, "line");
 
         let dashboard2_item = dashboards.iter().find(|d| d.name == "dashboard2").unwrap();
         assert_eq!(dashboard2_item.description, None);
-        assert_eq!(dashboard2_item.query, "query2");
-        assert_eq!(dashboard2_item.chart_type, "bar");
+        assert_eq!(dashboard2_item.panels[0].query, "query2");
+        assert_eq!(dashboard2_item.panels[0].chart_type, "bar");
 
         Ok(())
     }
@@ -437,16 +864,23 @@ mod tests {
         let query_config = QueryConfig {
             description: Some("Query for dashboard data".to_string()),
             sql: "SELECT 'Jan' as month, 1000 as revenue UNION SELECT 'Feb' as month, 1500 as revenue".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         let dashboard_config = DashboardConfig {
             description: Some("Dashboard for testing data".to_string()),
-            query: "test_data_query".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "month".to_string(),
-                y_column: "revenue".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "test_data_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "month".to_string(),
+                    y_column: "revenue".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -466,6 +900,242 @@ mod tests {
         assert!(!data_response.labels.is_empty());
         assert!(!data_response.values.is_empty());
         assert_eq!(data_response.labels.len(), data_response.values.len());
+        assert!(data_response.labels.iter().all(|label| label.is_string()));
+        assert!(data_response.values.iter().all(|value| value.is_i64()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_data_cache() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        {
+            let mut config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query("sales", "SELECT 'Jan' AS month, 100 AS total")?;
+            test.graph().await.create_node("sales", &[]);
+
+            let query_config = QueryConfig {
+                description: None,
+                sql: "SELECT month, total FROM sales ORDER BY month".to_string(),
+                tags: vec![],
+                group: None,
+                parameters: vec![],
+            };
+            config
+                .upsert_query("sales_by_month", &query_config)?
+                .save()?;
+            let dashboard_config = DashboardConfig {
+                description: None,
+                panels: vec![PanelConfig::new(
+                    "sales_by_month",
+                    ChartConfig {
+                        chart_type: ChartType::Bar,
+                        x_column: "month".to_string(),
+                        y_column: "total".to_string(),
+                    },
+                )],
+                tags: vec![],
+                group: None,
+            };
+            config
+                .upsert_dashboard("sales", &dashboard_config)?
+                .save()?;
+        }
+
+        let response = server.get("/dashboards/sales/data").await;
+        response.assert_status_ok();
+        assert_eq!(response.header(CACHE_HEADER), "MISS");
+
+        let response = server.get("/dashboards/sales/data").await;
+        assert_eq!(response.header(CACHE_HEADER), "HIT");
+        let data: DashboardDataResponse = response.json();
+        assert_eq!(data.labels, vec![json!("Jan")]);
+
+        // A rebuild of `sales` bumps its timestamp in the graph.
+        {
+            let config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query(
+                "sales",
+                "SELECT 'Jan' AS month, 100 AS total UNION ALL SELECT 'Feb', 150",
+            )?;
+            test.graph().await.set_current_time("sales");
+        }
+
+        let response = server.get("/dashboards/sales/data").await;
+        assert_eq!(response.header(CACHE_HEADER), "MISS");
+        let data: DashboardDataResponse = response.json();
+        assert_eq!(data.labels, vec![json!("Feb"), json!("Jan")]);
+
+        Ok(())
+    }
+
+    async fn setup_chart_dashboard(
+        test: &TestManager,
+        chart_type: ChartType,
+        x_column: &str,
+        y_column: &str,
+    ) -> Result<()> {
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT 'A' AS category, 1.5 AS x, 10 AS y \
+                  UNION ALL SELECT 'B', 2.5, 20 ORDER BY category"
+                .to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        let dashboard_config = DashboardConfig {
+            description: None,
+            panels: vec![PanelConfig::new(
+                "chart_query",
+                ChartConfig {
+                    chart_type,
+                    x_column: x_column.to_string(),
+                    y_column: y_column.to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
+        };
+
+        let mut config = test.config().await;
+        config.upsert_query("chart_query", &query_config)?.save()?;
+        config
+            .upsert_dashboard("chart_dashboard", &dashboard_config)?
+            .save()?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_panel_data() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        save_query(
+            &test,
+            "by_category",
+            "SELECT 'A' AS category, 10 AS total UNION ALL SELECT 'B', 20 ORDER BY category",
+        )
+        .await?;
+        save_query(&test, "by_month", "SELECT 'Jan' AS month, 5 AS orders").await?;
+
+        let dashboard_config = DashboardConfig {
+            description: None,
+            panels: vec![
+                PanelConfig::new(
+                    "by_category",
+                    ChartConfig {
+                        chart_type: ChartType::Bar,
+                        x_column: "category".to_string(),
+                        y_column: "total".to_string(),
+                    },
+                ),
+                PanelConfig::new(
+                    "by_month",
+                    ChartConfig {
+                        chart_type: ChartType::Line,
+                        x_column: "month".to_string(),
+                        y_column: "orders".to_string(),
+                    },
+                ),
+            ],
+            tags: vec![],
+            group: None,
+        };
+        test.config()
+            .await
+            .upsert_dashboard("kpis", &dashboard_config)?
+            .save()?;
+
+        let data: DashboardDataResponse = server.get("/dashboards/kpis/panels/0/data").await.json();
+        assert_eq!(data.labels, vec![json!("A"), json!("B")]);
+        assert_eq!(data.values, vec![json!(10), json!(20)]);
+
+        let data: DashboardDataResponse = server.get("/dashboards/kpis/panels/1/data").await.json();
+        assert_eq!(data.labels, vec![json!("Jan")]);
+        assert_eq!(data.values, vec![json!(5)]);
+
+        let response = server.get("/dashboards/kpis/panels/2/data").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["details"]["panels"], 2);
+
+        server
+            .get("/dashboards/kpis/data")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_data_pie() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Pie, "category", "y").await?;
+
+        let response = server.get("/dashboards/chart_dashboard/data").await;
+        response.assert_status_ok();
+
+        let data_response: DashboardDataResponse = response.json();
+        assert_eq!(data_response.labels, vec![json!("A"), json!("B")]);
+        assert_eq!(data_response.values, vec![json!(10), json!(20)]);
+        assert!(data_response.points.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_data_scatter() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Scatter, "x", "y").await?;
+
+        let response = server.get("/dashboards/chart_dashboard/data").await;
+        response.assert_status_ok();
+
+        let data_response: DashboardDataResponse = response.json();
+        assert_eq!(
+            data_response.points,
+            vec![
+                ScatterPoint { x: 1.5, y: 10.0 },
+                ScatterPoint { x: 2.5, y: 20.0 },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_data_scatter_non_numeric_x() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Scatter, "category", "y").await?;
+
+        let response = server.get("/dashboards/chart_dashboard/data").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_CHART");
+        assert_eq!(body["details"]["column"], "category");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_dashboards_new_chart_types() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Area, "category", "y").await?;
+
+        let response = server.get("/dashboards").await;
+        response.assert_status_ok();
+
+        let dashboards: Vec<DashboardListItem> = response.json();
+        assert_eq!(dashboards[0].panels[0].chart_type, "area");
 
         Ok(())
     }
@@ -477,12 +1147,16 @@ mod tests {
 
         let existing_dashboard = DashboardConfig {
             description: None,
-            query: "test_query".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "x".to_string(),
-                y_column: "y".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "test_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x".to_string(),
+                    y_column: "y".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -564,12 +1238,16 @@ mod tests {
 
         let dashboard_config = DashboardConfig {
             description: None,
-            query: "missing_query".to_string(),
-            chart: ChartConfig {
-                chart_type: ChartType::Line,
-                x_column: "x".to_string(),
-                y_column: "y".to_string(),
-            },
+            panels: vec![PanelConfig::new(
+                "missing_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x".to_string(),
+                    y_column: "y".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
         };
 
         {
@@ -586,4 +1264,180 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_create_dashboard_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+
+        let new_dashboard = json!({
+            "name": "dangling_dashboard",
+            "config": {
+                "query": "missing_query",
+                "chart": {
+                    "type": "line",
+                    "x_column": "x",
+                    "y_column": "y"
+                }
+            }
+        });
+
+        let response = server.post("/dashboards").json(&new_dashboard).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_NOT_FOUND");
+        assert_eq!(body["details"]["query"], "missing_query");
+
+        let get_response = server.get("/dashboards/dangling_dashboard").await;
+        get_response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_dashboard_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Line, "x", "y").await?;
+
+        let updated_config = json!({
+            "query": "missing_query",
+            "chart": {
+                "type": "line",
+                "x_column": "x",
+                "y_column": "y"
+            }
+        });
+
+        let response = server
+            .put("/dashboards/chart_dashboard")
+            .json(&updated_config)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_NOT_FOUND");
+        assert_eq!(body["details"]["query"], "missing_query");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_dashboard_unknown_column() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        save_query(&test, "sales", "SELECT 'Jan' AS month, 1000 AS revenue").await?;
+
+        let new_dashboard = json!({
+            "name": "sales_dashboard",
+            "config": {
+                "query": "sales",
+                "chart": {
+                    "type": "line",
+                    "x_column": "month",
+                    "y_column": "revenu"
+                }
+            }
+        });
+
+        let response = server.post("/dashboards").json(&new_dashboard).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_CHART");
+        assert_eq!(body["details"]["column"], "revenu");
+        assert_eq!(
+            body["details"]["available_columns"],
+            json!(["month", "revenue"])
+        );
+        server
+            .get("/dashboards/sales_dashboard")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let response = server
+            .put("/dashboards/sales_dashboard")
+            .json(&new_dashboard["config"])
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        server
+            .post("/dashboards")
+            .add_query_param("skip_validation", true)
+            .json(&new_dashboard)
+            .await
+            .assert_status_ok();
+
+        let response = server.get("/dashboards/sales_dashboard/data").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["details"]["column"], "revenu");
+        assert_eq!(
+            body["details"]["available_columns"],
+            json!(["month", "revenue"])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_dashboard_unknown_column() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Line, "x", "y").await?;
+
+        let updated_config = json!({
+            "query": "chart_query",
+            "chart": {
+                "type": "line",
+                "x_column": "x",
+                "y_column": "z"
+            }
+        });
+
+        let response = server
+            .put("/dashboards/chart_dashboard")
+            .json(&updated_config)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_CHART");
+        assert_eq!(body["details"]["column"], "z");
+
+        server
+            .put("/dashboards/chart_dashboard")
+            .add_query_param("skip_validation", true)
+            .json(&updated_config)
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_dashboard() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(router);
+        setup_chart_dashboard(&test, ChartType::Line, "x", "y").await?;
+
+        let response = server
+            .post("/dashboards/chart_dashboard/rename")
+            .json(&json!({ "new_name": "renamed_dashboard" }))
+            .await;
+        response.assert_status_ok();
+
+        server
+            .get("/dashboards/chart_dashboard")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get("/dashboards/renamed_dashboard")
+            .await
+            .assert_status_ok();
+        assert!(
+            test.directory()
+                .join("dashboards/renamed_dashboard.yml")
+                .exists()
+        );
+
+        Ok(())
+    }
 }
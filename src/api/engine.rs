@@ -0,0 +1,53 @@
+use crate::{
+    api::Error,
+    core::{
+        config::Config,
+        ducklake::{DuckLakeCache, ExtensionInfo},
+    },
+};
+use anyhow::Result;
+use axum::{Extension, Router, response::Json, routing::get};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub fn routes() -> Router {
+    Router::new().route("/engine/extensions", get(list_extensions))
+}
+
+// Which DuckDB extensions are installed and loaded, to check an offline setup.
+async fn list_extensions(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+) -> Result<Json<Vec<ExtensionInfo>>, Error> {
+    let ducklake = ducklakes.get(&config).await?;
+    Ok(Json(ducklake.list_extensions()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestManager;
+
+    #[tokio::test]
+    async fn test_list_extensions() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server.get("/engine/extensions").await;
+        response.assert_status_ok();
+        let extensions: Vec<ExtensionInfo> = response.json();
+
+        let ducklake = extensions
+            .iter()
+            .find(|extension| extension.name == "ducklake")
+            .expect("ducklake is listed");
+        assert!(ducklake.installed && ducklake.loaded);
+        assert!(
+            extensions
+                .windows(2)
+                .all(|pair| pair[0].name <= pair[1].name)
+        );
+
+        Ok(())
+    }
+}
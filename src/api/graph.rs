@@ -1,23 +1,307 @@
-use crate::{api::Error, core::graph::Graph};
-use axum::{Extension, Json, Router, routing::get};
-use std::sync::Arc;
+use crate::{
+    api::Error,
+    core::{
+        config::Config,
+        graph::{Graph, GraphDiff, Node},
+        pipeline::{Phase, Pipeline},
+        run_history::{NodeRuns, RunHistory},
+    },
+};
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query},
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+const DEFAULT_RUN_LIMIT: usize = 20;
+
+#[derive(Serialize, Deserialize)]
+pub struct TableStatsResponse {
+    pub name: String,
+    pub row_count: Option<u64>,
+    pub last_duration_ms: Option<u64>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LastRun {
+    pub status: Option<Phase>,
+    pub error: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub row_count: Option<u64>,
+    pub duration_ms: Option<u64>,
+}
+
+impl LastRun {
+    pub fn of(graph: &Graph, name: &str) -> Option<Self> {
+        let node = graph.get_node(name)?;
+        Some(Self {
+            status: node.last_status.clone(),
+            error: node.last_error.clone(),
+            updated_at: node.updated_at,
+            row_count: node.row_count,
+            duration_ms: node.last_duration_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Adapter,
+    Model,
+    Seed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub name: String,
+    pub kind: Option<NodeKind>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub dependencies: Vec<String>,
+    pub phase: Option<Phase>,
+    pub last_status: Option<Phase>,
+    pub last_error: Option<String>,
+    // Set when the node has no adapter, model or seed config behind it.
+    pub orphan: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphResponse {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphNodeDetail {
+    #[serde(flatten)]
+    pub node: GraphNode,
+    pub upstream: Vec<String>,
+    pub downstream: Vec<String>,
+}
 
 pub fn routes() -> Router {
-    Router::new().route("/graph", get(get_graph))
+    Router::new()
+        .route("/graph", get(get_graph))
+        .route("/graph/rebuild", post(rebuild_graph))
+        .route("/graph/{name}", get(get_graph_node))
+        .route("/nodes/{name}/runs", get(get_node_runs))
+        .route("/tables/{name}/stats", get(get_table_stats))
+}
+
+async fn latest_phases(config: &Arc<Mutex<Config>>) -> Result<HashMap<String, Phase>, Error> {
+    let project_dir = {
+        let config = config.lock().await;
+        config.project_dir.clone()
+    };
+
+    let phases = Pipeline::load_latest(&project_dir)
+        .await?
+        .map(|pipeline| {
+            pipeline
+                .tasks
+                .into_iter()
+                .map(|(name, task)| (name, task.phase().clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(phases)
+}
+
+fn node_kind(config: &Config, name: &str) -> Option<NodeKind> {
+    if config.adapters.contains_key(name) {
+        Some(NodeKind::Adapter)
+    } else if config.models.contains_key(name) {
+        Some(NodeKind::Model)
+    } else if config.seeds.contains_key(name) {
+        Some(NodeKind::Seed)
+    } else {
+        None
+    }
 }
 
-async fn get_graph(Extension(graph): Extension<Arc<Mutex<Graph>>>) -> Result<Json<Graph>, Error> {
+pub fn graph_node(config: &Config, phases: &HashMap<String, Phase>, node: &Node) -> GraphNode {
+    let kind = node_kind(config, &node.name);
+    let mut dependencies = node.dependencies.clone();
+    dependencies.sort();
+
+    GraphNode {
+        name: node.name.clone(),
+        kind,
+        updated_at: node.updated_at,
+        dependencies,
+        phase: phases.get(&node.name).cloned(),
+        last_status: node.last_status.clone(),
+        last_error: node.last_error.clone(),
+        orphan: kind.is_none(),
+    }
+}
+
+async fn get_graph(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+) -> Result<Json<GraphResponse>, Error> {
+    let phases = latest_phases(&config).await?;
+    let config = config.lock().await;
     let graph = graph.lock().await;
-    Ok(Json(graph.clone()))
+
+    let mut nodes: Vec<GraphNode> = graph
+        .nodes
+        .values()
+        .map(|node| graph_node(&config, &phases, node))
+        .collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let edges = nodes
+        .iter()
+        .flat_map(|node| {
+            node.dependencies.iter().map(|dependency| GraphEdge {
+                from: dependency.clone(),
+                to: node.name.clone(),
+            })
+        })
+        .collect();
+
+    Ok(Json(GraphResponse { nodes, edges }))
+}
+
+async fn get_graph_node(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Path(name): Path<String>,
+) -> Result<Json<GraphNodeDetail>, Error> {
+    let phases = latest_phases(&config).await?;
+    let config = config.lock().await;
+    let graph = graph.lock().await;
+
+    let Some(node) = graph.get_node(&name) else {
+        return Error::not_found()
+            .with_message(format!("Node '{name}' not found"))
+            .build();
+    };
+
+    let mut upstream = graph.upstream(&name);
+    upstream.sort();
+
+    Ok(Json(GraphNodeDetail {
+        node: graph_node(&config, &phases, node),
+        upstream,
+        downstream: graph.downstream(&name),
+    }))
+}
+
+#[derive(Deserialize)]
+struct NodeRunsParams {
+    limit: Option<usize>,
+}
+
+async fn get_node_runs(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Path(name): Path<String>,
+    Query(params): Query<NodeRunsParams>,
+) -> Result<Json<NodeRuns>, Error> {
+    if graph.lock().await.get_node(&name).is_none() {
+        return Error::not_found()
+            .with_message(format!("Node '{name}' not found"))
+            .build();
+    }
+
+    let project_dir = config.lock().await.project_dir.clone();
+    let history = RunHistory::load(&project_dir).await?;
+    Ok(Json(
+        history.runs(&name, params.limit.unwrap_or(DEFAULT_RUN_LIMIT)),
+    ))
+}
+
+// Re-reads the config files so models added or edited outside the API are picked up.
+async fn rebuild_graph(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+) -> Result<Json<GraphDiff>, Error> {
+    let mut config = config.lock().await;
+    config.load()?;
+
+    let mut graph = graph.lock().await;
+    let diff = graph.rebuild(&config);
+    graph.save().await?;
+
+    Ok(Json(diff))
+}
+
+async fn get_table_stats(
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Path(name): Path<String>,
+) -> Result<Json<TableStatsResponse>, Error> {
+    let graph = graph.lock().await;
+    let Some(node) = graph.get_node(&name) else {
+        return Error::not_found()
+            .with_message(format!("Table '{name}' not found"))
+            .build();
+    };
+
+    Ok(Json(TableStatsResponse {
+        name,
+        row_count: node.row_count,
+        last_duration_ms: node.last_duration_ms,
+        updated_at: node.updated_at,
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helpers::TestManager;
+    use crate::{
+        api::StatusCode,
+        core::{
+            adapter::test_helpers::{
+                create_csv_adapter_config, create_test_csv_data, write_test_file,
+            },
+            config::project::ConnectionConfig,
+        },
+        test_helpers::TestManager,
+    };
     use anyhow::Result;
     use serde_json::json;
+    use std::time::Duration;
+
+    async fn save_test_connection(test: &TestManager) -> Result<()> {
+        let mut config = test.config().await;
+        let mut project_config = config.project.clone();
+        project_config.connections.insert(
+            "test_connection".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: test.directory().to_string_lossy().to_string(),
+            },
+        );
+        config.add_project_setting(&project_config)?.save()?;
+
+        Ok(())
+    }
+
+    fn database_adapter(table_name: &str) -> serde_json::Value {
+        json!({
+            "description": "Test adapter",
+            "connection": "test_connection",
+            "source": {
+                "type": "database",
+                "table_name": table_name
+            },
+            "columns": []
+        })
+    }
 
     #[tokio::test]
     async fn test_graph_updates_after_adapter_creation() -> Result<()> {
@@ -27,36 +311,33 @@ mod tests {
 
         let server =
             test.setup_server(move || Router::new().merge(adapter_routes).merge(graph_routes));
+        save_test_connection(&test).await?;
 
         // Initially graph should be empty
         let graph_response = server.get("/graph").await;
         graph_response.assert_status_ok();
-        let graph: Graph = graph_response.json();
-        assert_eq!(graph.nodes.len(), 0);
-
-        // Create an adapter
-        let new_adapter = json!({
-            "name": "test_adapter",
-            "config": {
-                "description": "Test adapter",
-                "connection": "test_connection",
-                "source": {
-                    "type": "database",
-                    "table_name": "test_table"
-                },
-                "columns": []
-            }
-        });
+        let graph: GraphResponse = graph_response.json();
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
 
-        let create_response = server.post("/adapters").json(&new_adapter).await;
-        create_response.assert_status_ok();
+        server
+            .post("/adapters")
+            .json(&json!({
+                "name": "test_adapter",
+                "config": database_adapter("test_table")
+            }))
+            .await
+            .assert_status_ok();
 
         // Check that graph now contains the adapter
         let graph_response = server.get("/graph").await;
         graph_response.assert_status_ok();
-        let graph: Graph = graph_response.json();
+        let graph: GraphResponse = graph_response.json();
         assert_eq!(graph.nodes.len(), 1);
-        assert!(graph.nodes.contains_key("test_adapter"));
+        assert_eq!(graph.nodes[0].name, "test_adapter");
+        assert_eq!(graph.nodes[0].kind, Some(NodeKind::Adapter));
+        assert!(!graph.nodes[0].orphan);
+        assert!(graph.nodes[0].phase.is_none());
 
         Ok(())
     }
@@ -74,53 +355,234 @@ mod tests {
                 .merge(model_routes)
                 .merge(graph_routes)
         });
-
-        // Create an adapter first
-        let new_adapter = json!({
-            "name": "users",
-            "config": {
-                "description": "Users table",
-                "connection": "test_connection",
-                "source": {
-                    "type": "database",
-                    "table_name": "users"
-                },
-                "columns": []
-            }
-        });
+        save_test_connection(&test).await?;
 
         server
             .post("/adapters")
-            .json(&new_adapter)
+            .json(&json!({ "name": "users", "config": database_adapter("users") }))
+            .await
+            .assert_status_ok();
+        server
+            .post("/adapters")
+            .json(&json!({ "name": "orders", "config": database_adapter("orders") }))
             .await
             .assert_status_ok();
 
-        // Create a model that depends on the adapter
-        let new_model = json!({
-            "name": "active_users",
-            "config": {
-                "description": "Active users model",
-                "sql": "SELECT * FROM users WHERE active = true"
-            }
-        });
-
         server
             .post("/models")
-            .json(&new_model)
+            .json(&json!({
+                "name": "active_users",
+                "config": {
+                    "description": "Active users model",
+                    "sql": "SELECT * FROM users WHERE active = true"
+                }
+            }))
+            .await
+            .assert_status_ok();
+        server
+            .post("/models")
+            .json(&json!({
+                "name": "user_orders",
+                "config": {
+                    "sql": "SELECT * FROM active_users JOIN orders ON active_users.id = orders.user_id"
+                }
+            }))
             .await
             .assert_status_ok();
 
-        // Check that graph contains both nodes with correct dependencies
         let graph_response = server.get("/graph").await;
         graph_response.assert_status_ok();
-        let graph: Graph = graph_response.json();
+        let graph: GraphResponse = graph_response.json();
+
+        let names: Vec<&str> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["active_users", "orders", "user_orders", "users"]
+        );
+        assert_eq!(graph.nodes[0].kind, Some(NodeKind::Model));
+        assert_eq!(graph.nodes[0].dependencies, vec!["users"]);
+        assert_eq!(graph.nodes[1].kind, Some(NodeKind::Adapter));
+
+        let edge = |from: &str, to: &str| GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        assert_eq!(
+            graph.edges,
+            vec![
+                edge("users", "active_users"),
+                edge("active_users", "user_orders"),
+                edge("orders", "user_orders"),
+            ]
+        );
+
+        let response = server.get("/graph/active_users").await;
+        response.assert_status_ok();
+        let detail: GraphNodeDetail = response.json();
+        assert_eq!(detail.node.name, "active_users");
+        assert_eq!(detail.upstream, vec!["users"]);
+        assert_eq!(detail.downstream, vec!["user_orders"]);
+
+        let response = server.get("/graph/users").await;
+        response.assert_status_ok();
+        let detail: GraphNodeDetail = response.json();
+        assert!(detail.upstream.is_empty());
+        assert_eq!(detail.downstream, vec!["active_users", "user_orders"]);
+
+        server
+            .get("/graph/missing")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_graph_flags_orphan_nodes() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        test.graph().await.create_node("stale_table", &[]);
+
+        let response = server.get("/graph").await;
+        response.assert_status_ok();
+        let graph: GraphResponse = response.json();
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.nodes[0].name, "stale_table");
+        assert!(graph.nodes[0].kind.is_none());
+        assert!(graph.nodes[0].orphan);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_graph_from_config_files() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_test_connection(&test).await?;
+
+        std::fs::create_dir_all(test.directory().join("adapters"))?;
+        std::fs::create_dir_all(test.directory().join("models"))?;
+        std::fs::write(
+            test.directory().join("adapters/users.yml"),
+            "connection: test_connection\nsource:\n  type: database\n  table_name: users\ncolumns: []\n",
+        )?;
+        std::fs::write(
+            test.directory().join("models/active_users.yml"),
+            "sql: SELECT * FROM users WHERE active = true\n",
+        )?;
+
+        {
+            let mut graph = test.graph().await;
+            graph.create_node("users", &[]);
+            graph.set_current_time("users");
+            graph.create_node("removed_model", &["users"]);
+        }
+
+        let response = server.post("/graph/rebuild").await;
+        response.assert_status_ok();
+        let diff: GraphDiff = response.json();
+        assert_eq!(diff.added, vec!["active_users"]);
+        assert_eq!(diff.removed, vec!["removed_model"]);
+        assert!(diff.changed.is_empty());
+
+        {
+            let graph = test.graph().await;
+            assert_eq!(
+                graph.get_node("active_users").unwrap().dependencies,
+                vec!["users"]
+            );
+            assert!(graph.get_node("users").unwrap().updated_at.is_some());
+            assert!(!graph.has_node("removed_model"));
+        }
+
+        std::fs::write(
+            test.directory().join("models/active_users.yml"),
+            "sql: SELECT * FROM active_users_source\n",
+        )?;
+
+        let response = server.post("/graph/rebuild").await;
+        response.assert_status_ok();
+        let diff: GraphDiff = response.json();
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed, vec!["active_users"]);
+        assert_eq!(
+            test.graph()
+                .await
+                .get_node("active_users")
+                .unwrap()
+                .dependencies,
+            vec!["active_users_source"]
+        );
+
+        let graph = Graph::load(test.directory()).await?;
+        assert!(graph.has_node("active_users"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_table_stats_after_import() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| routes().merge(crate::api::pipeline::routes()));
+
+        write_test_file(test.directory(), "test_data.csv", &create_test_csv_data())?;
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.project.clone();
+            project_config.connections.insert(
+                "local".to_string(),
+                ConnectionConfig::LocalFile {
+                    base_path: test.directory().to_string_lossy().to_string(),
+                },
+            );
+            config.add_project_setting(&project_config)?.save()?;
+            config
+                .upsert_adapter("users", &create_csv_adapter_config())?
+                .save()?;
+        }
+        test.graph().await.create_node("users", &[]);
+
+        server
+            .get("/tables/missing/stats")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        server
+            .post("/pipeline/run-node")
+            .json(&json!({ "node_name": "users" }))
+            .await
+            .assert_status_ok();
+
+        let mut stats = None;
+        for _ in 0..60 {
+            let response = server.get("/tables/users/stats").await;
+            response.assert_status_ok();
+            let body: TableStatsResponse = response.json();
+            if body.row_count.is_some() {
+                stats = Some(body);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        let stats = stats.expect("pipeline did not record table stats");
+        assert_eq!(stats.row_count, Some(3));
+        assert!(stats.last_duration_ms.is_some());
+        assert!(stats.updated_at.is_some());
 
-        assert_eq!(graph.nodes.len(), 2);
-        assert!(graph.nodes.contains_key("users"));
-        assert!(graph.nodes.contains_key("active_users"));
+        let response = server.get("/nodes/users/runs").await;
+        response.assert_status_ok();
+        let history: NodeRuns = response.json();
+        assert_eq!(history.runs.len(), 1);
+        assert_eq!(history.runs[0].phase, Phase::Completed);
+        assert_eq!(history.success_rate, Some(1.0));
 
-        let active_users_node = graph.nodes.get("active_users").unwrap();
-        assert_eq!(active_users_node.dependencies, vec!["users"]);
+        server
+            .get("/nodes/missing/runs")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
 
         Ok(())
     }
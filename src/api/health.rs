@@ -0,0 +1,304 @@
+use crate::core::{
+    adapter::file::{gcs::gcs_client, s3::s3_client},
+    config::{
+        Config,
+        project::{DatabaseConfig, DatabaseType, StorageConfig},
+    },
+    ducklake::DuckLakeCache,
+};
+use anyhow::{Context, Result};
+use axum::{Extension, Router, http::StatusCode, response::Json, routing::get};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+
+// Orchestrators probe frequently; remote systems are only contacted this often.
+const READINESS_TTL: Duration = Duration::from_secs(5);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Health {
+    started_at: Instant,
+    readiness: Mutex<Option<(Instant, Readiness)>>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            readiness: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub status: String,
+    pub version: String,
+    pub commit: String,
+    pub profile: String,
+    pub uptime_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+// Mounted outside `/api`, so probes need no API token.
+pub fn routes(base_path: &str) -> Router {
+    Router::new()
+        .route(&format!("{base_path}/health"), get(health))
+        .route(&format!("{base_path}/ready"), get(ready))
+}
+
+async fn health(Extension(health): Extension<Arc<Health>>) -> Json<HealthStatus> {
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+
+    Json(HealthStatus {
+        status: "ok".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        commit: option_env!("DUCKHUB_GIT_COMMIT")
+            .unwrap_or("unknown")
+            .to_string(),
+        profile: profile.to_string(),
+        uptime_seconds: health.started_at.elapsed().as_secs(),
+    })
+}
+
+async fn ready(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(health): Extension<Arc<Health>>,
+) -> (StatusCode, Json<Readiness>) {
+    // Holding the lock while checking makes concurrent probes share one round of checks.
+    let mut cached = health.readiness.lock().await;
+    let readiness = match cached.as_ref() {
+        Some((checked_at, readiness)) if checked_at.elapsed() < READINESS_TTL => readiness.clone(),
+        _ => {
+            let readiness = check_readiness(&config, &ducklakes).await;
+            *cached = Some((Instant::now(), readiness.clone()));
+            readiness
+        }
+    };
+
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+async fn check_readiness(config: &Mutex<Config>, ducklakes: &DuckLakeCache) -> Readiness {
+    let (database, storage) = {
+        let config = config.lock().await;
+        (
+            config.project.database.clone(),
+            config.project.storage.clone(),
+        )
+    };
+
+    let mut checks = Vec::new();
+    let catalog_reachable = match database.ty {
+        DatabaseType::Sqlite => true,
+        DatabaseType::Mysql | DatabaseType::Postgresql => {
+            let check = run_check("catalog", check_catalog(&database)).await;
+            let ok = check.ok;
+            checks.push(check);
+            ok
+        }
+    };
+
+    checks.push(run_check("storage", check_storage(&storage)).await);
+
+    // Building a DuckLake against an unreachable catalog only repeats the failure slowly.
+    if catalog_reachable {
+        checks.push(
+            run_check("ducklake", async {
+                let ducklake = ducklakes.get(config).await?;
+                ducklake.query("SELECT 1")?;
+                Ok(())
+            })
+            .await,
+        );
+    } else {
+        checks.push(CheckResult {
+            name: "ducklake".to_string(),
+            ok: false,
+            error: Some("Skipped because the catalog is unreachable".to_string()),
+        });
+    }
+
+    Readiness {
+        ready: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+async fn run_check(name: &str, check: impl Future<Output = Result<()>>) -> CheckResult {
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!(
+            "Timed out after {} seconds",
+            CHECK_TIMEOUT.as_secs()
+        )),
+    };
+
+    CheckResult {
+        name: name.to_string(),
+        ok: result.is_ok(),
+        error: result.err().map(|e| format!("{e:#}")),
+    }
+}
+
+async fn check_catalog(database: &DatabaseConfig) -> Result<()> {
+    let host = database.host.as_deref().unwrap_or("localhost");
+    let port = database
+        .port
+        .context("Catalog database port is not configured")?;
+
+    tokio::net::TcpStream::connect((host, port))
+        .await
+        .with_context(|| format!("Cannot reach catalog database at {host}:{port}"))?;
+    Ok(())
+}
+
+async fn check_storage(storage: &StorageConfig) -> Result<()> {
+    match storage {
+        StorageConfig::LocalFile { path } => {
+            let probe = std::path::Path::new(path).join(format!(".ready-{}", uuid::Uuid::new_v4()));
+            tokio::fs::create_dir_all(path)
+                .await
+                .with_context(|| format!("Cannot create storage directory '{path}'"))?;
+            tokio::fs::write(&probe, b"")
+                .await
+                .with_context(|| format!("Storage directory '{path}' is not writable"))?;
+            tokio::fs::remove_file(&probe).await?;
+        }
+        StorageConfig::S3(s3_config) => {
+            s3_client(s3_config)
+                .await?
+                .head_bucket()
+                .bucket(&s3_config.bucket)
+                .send()
+                .await
+                .with_context(|| format!("Cannot access S3 bucket '{}'", s3_config.bucket))?;
+        }
+        StorageConfig::Gcs(gcs_config) => {
+            gcs_client(gcs_config)
+                .await?
+                .head_bucket()
+                .bucket(&gcs_config.bucket)
+                .send()
+                .await
+                .with_context(|| format!("Cannot access GCS bucket '{}'", gcs_config.bucket))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::config::secret::SecretField, test_helpers::TestManager};
+
+    fn server(test: &TestManager) -> axum_test::TestServer {
+        test.setup_server(|| routes("").layer(Extension(Arc::new(Health::default()))))
+    }
+
+    async fn use_local_storage(test: &TestManager) {
+        let directory = test.directory().to_path_buf();
+        let mut config = test.config().await;
+        config.project.storage = StorageConfig::LocalFile {
+            path: directory.join("storage").to_string_lossy().to_string(),
+        };
+        config.project.database.path =
+            Some(directory.join("catalog.db").to_string_lossy().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_health() {
+        let test = TestManager::new();
+        let response = server(&test).get("/health").await;
+
+        response.assert_status_ok();
+        let health: HealthStatus = response.json();
+        assert_eq!(health.status, "ok");
+        assert_eq!(health.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[tokio::test]
+    async fn test_ready() {
+        let test = TestManager::new();
+        use_local_storage(&test).await;
+
+        let response = server(&test).get("/ready").await;
+
+        response.assert_status_ok();
+        let readiness: Readiness = response.json();
+        assert!(readiness.ready);
+        let names: Vec<&str> = readiness
+            .checks
+            .iter()
+            .map(|check| check.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["storage", "ducklake"]);
+    }
+
+    #[tokio::test]
+    async fn test_ready_with_unreachable_catalog() {
+        let test = TestManager::new();
+        use_local_storage(&test).await;
+        test.config().await.project.database = DatabaseConfig {
+            ty: DatabaseType::Postgresql,
+            path: None,
+            host: Some("127.0.0.1".to_string()),
+            port: Some(1),
+            database: Some("catalog".to_string()),
+            username: Some("duckhub".to_string()),
+            password: Some(SecretField::PlainText {
+                value: "password".to_string(),
+            }),
+        };
+
+        let response = server(&test).get("/ready").await;
+
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        let readiness: Readiness = response.json();
+        assert!(!readiness.ready);
+        let catalog = &readiness.checks[0];
+        assert_eq!(catalog.name, "catalog");
+        assert!(!catalog.ok);
+        assert!(
+            catalog
+                .error
+                .as_ref()
+                .unwrap()
+                .contains("Cannot reach catalog database at 127.0.0.1:1")
+        );
+        assert!(
+            readiness
+                .checks
+                .iter()
+                .any(|check| check.name == "storage" && check.ok)
+        );
+    }
+}
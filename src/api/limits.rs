@@ -0,0 +1,82 @@
+use crate::{
+    api::{Error, ErrorCode},
+    core::{config::project::EngineConfig, metrics::API_QUERIES_REJECTED},
+};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Caps how many API queries (ad-hoc, saved and dashboard) run against DuckDB at
+// once. Pipelines build their own DuckLake with its own pool and worker count, so
+// API traffic cannot take their connections and this limit never blocks them.
+#[derive(Default)]
+pub struct QueryLimiter {
+    // Rebuilt when `max_api_queries` changes; queries holding a permit from the
+    // old semaphore finish against it.
+    semaphore: Mutex<Option<(usize, Arc<Semaphore>)>>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+pub struct QueryPermit {
+    _permit: OwnedSemaphorePermit,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for QueryPermit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl QueryLimiter {
+    fn semaphore(&self, max: usize) -> Arc<Semaphore> {
+        let mut semaphore = self.semaphore.lock().unwrap();
+        match semaphore.as_ref() {
+            Some((current, existing)) if *current == max => existing.clone(),
+            _ => {
+                let created = Arc::new(Semaphore::new(max));
+                *semaphore = Some((max, created.clone()));
+                created
+            }
+        }
+    }
+
+    // Waits up to the queue timeout for a slot, then answers 429 with Retry-After.
+    pub async fn acquire(&self, engine: &EngineConfig) -> Result<QueryPermit, Error> {
+        let max = engine.max_api_queries();
+        let wait = engine.api_queue_timeout();
+        let semaphore = self.semaphore(max);
+
+        let permit = match tokio::time::timeout(wait, semaphore.acquire_owned()).await {
+            Ok(Ok(permit)) => permit,
+            _ => {
+                metrics::counter!(API_QUERIES_REJECTED).increment(1);
+                return Error::too_many_requests()
+                    .with_code(ErrorCode::TooManyQueries)
+                    .with_message(format!(
+                        "{max} queries are already running, try again shortly"
+                    ))
+                    .with_detail("max_api_queries", max)
+                    .with_retry_after(wait.as_secs().max(1))
+                    .build();
+            }
+        };
+
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        Ok(QueryPermit {
+            _permit: permit,
+            in_flight: self.in_flight.clone(),
+        })
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    // The limit the last query ran under, if any query has run yet.
+    pub fn max(&self) -> Option<usize> {
+        self.semaphore.lock().unwrap().as_ref().map(|(max, _)| *max)
+    }
+}
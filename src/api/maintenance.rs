@@ -0,0 +1,202 @@
+use crate::{
+    api::Error,
+    core::{
+        config::Config,
+        ducklake::DuckLakeCache,
+        maintenance::{
+            CompactionReport, DEFAULT_SNAPSHOT_RETENTION_DAYS, compact, orphaned_relations,
+        },
+        pipeline::ActivePipeline,
+    },
+};
+use anyhow::Result;
+use axum::{Extension, Router, response::Json, routing::post};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Default, Deserialize)]
+pub struct CleanupRequest {
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CleanupResponse {
+    pub tables: Vec<String>,
+    pub applied: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CompactRequest {
+    #[serde(default = "default_older_than_days")]
+    pub older_than_days: u32,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_older_than_days() -> u32 {
+    DEFAULT_SNAPSHOT_RETENTION_DAYS
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/maintenance/cleanup", post(cleanup))
+        .route("/maintenance/compact", post(compact_storage))
+}
+
+// Lists tables and views no adapter, model or seed builds. Nothing is dropped unless
+// `apply` is set, so the same call previews what a cleanup would remove.
+async fn cleanup(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Json(request): Json<CleanupRequest>,
+) -> Result<Json<CleanupResponse>, Error> {
+    let ducklake = ducklakes.get(&config).await?;
+    // Held throughout so a table can't gain an owner between listing and dropping it.
+    let config = config.lock().await;
+    let tables = orphaned_relations(&ducklake, &config)?;
+
+    if request.apply {
+        for table in &tables {
+            ducklake.drop_relation(table)?;
+            tracing::info!("Dropped orphaned table '{table}'");
+        }
+    }
+
+    Ok(Json(CleanupResponse {
+        tables,
+        applied: request.apply,
+    }))
+}
+
+// Merges small files and expires old snapshots. Refused while a pipeline runs, since
+// compaction rewrites the files its tasks are reading and writing.
+async fn compact_storage(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(active): Extension<Arc<ActivePipeline>>,
+    Json(request): Json<CompactRequest>,
+) -> Result<Json<CompactionReport>, Error> {
+    if active.is_running().await {
+        return Err(
+            Error::conflict().with_message("Cannot compact storage while a pipeline is running")
+        );
+    }
+
+    let ducklake = ducklakes.get(&config).await?;
+    let report = compact(&ducklake, request.older_than_days, request.dry_run)?;
+    if !report.dry_run {
+        tracing::info!(
+            "Compacted storage: expired {} snapshots, reclaimed {} files ({} bytes)",
+            report.expired_snapshots,
+            report.files_reclaimed,
+            report.bytes_reclaimed
+        );
+    }
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::StatusCode,
+        core::{config::query::QueryConfig, ducklake::DuckLake},
+        test_helpers::TestManager,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_cleanup_orphaned_tables() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        {
+            let mut config = test.config().await;
+            // Queries don't build tables, so a table named after one is still orphaned.
+            let query = QueryConfig {
+                description: None,
+                sql: "SELECT 1".to_string(),
+                tags: vec![],
+                group: None,
+                parameters: vec![],
+            };
+            config.upsert_query("leftover", &query)?.save()?;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query("leftover", "SELECT 1 AS id")?;
+        }
+
+        let response = server.post("/maintenance/cleanup").json(&json!({})).await;
+        response.assert_status_ok();
+        let body: CleanupResponse = response.json();
+        assert_eq!(body.tables, vec!["leftover"]);
+        assert!(!body.applied);
+
+        let response = server
+            .post("/maintenance/cleanup")
+            .json(&json!({ "apply": true }))
+            .await;
+        response.assert_status_ok();
+        let body: CleanupResponse = response.json();
+        assert_eq!(body.tables, vec!["leftover"]);
+        assert!(body.applied);
+
+        let response = server.post("/maintenance/cleanup").json(&json!({})).await;
+        let body: CleanupResponse = response.json();
+        assert!(body.tables.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compact_dry_run_report() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        {
+            let config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query("events", "SELECT 1 AS id")?;
+            ducklake
+                .execute_batch("INSERT INTO events VALUES (2); INSERT INTO events VALUES (3);")?;
+        }
+
+        let response = server
+            .post("/maintenance/compact")
+            .json(&json!({ "older_than_days": 30, "dry_run": true }))
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["dry_run"], true);
+        assert_eq!(body["older_than_days"], 30);
+        for key in ["snapshots", "data_files", "data_bytes", "pending_deletion"] {
+            assert!(body["before"][key].is_u64(), "missing before.{key}");
+        }
+        assert_eq!(body["before"], body["after"]);
+        assert_eq!(body["files_reclaimed"], 0);
+        assert_eq!(body["bytes_reclaimed"], 0);
+
+        let report: CompactionReport = response.json();
+        assert!(report.before.data_files >= 3);
+        // Nothing is old enough to expire yet.
+        assert_eq!(report.expired_snapshots, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compact_refused_while_pipeline_running() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        test.active().mark_running(test.directory()).await;
+
+        let response = server
+            .post("/maintenance/compact")
+            .json(&json!({ "dry_run": true }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CONFLICT");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    api::limits::QueryLimiter,
+    core::{
+        ducklake::DuckLakeCache,
+        metrics::{
+            API_QUERIES_IN_FLIGHT, API_QUERIES_MAX, DUCKDB_POOL_CONNECTIONS, DUCKDB_POOL_IDLE,
+            DUCKDB_POOL_MAX, HTTP_REQUEST_DURATION, HTTP_REQUESTS, handle,
+        },
+    },
+};
+use axum::{
+    Extension, Router,
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+    routing::get,
+};
+use std::{sync::Arc, time::Instant};
+
+// Mounted outside `/api` like the health probes, so scrapers need no API token.
+pub fn routes(base_path: &str) -> Router {
+    Router::new().route(&format!("{base_path}/metrics"), get(render_metrics))
+}
+
+async fn render_metrics(
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+) -> String {
+    // Pool gauges are sampled at scrape time instead of on every checkout.
+    if let Some(ducklake) = ducklakes.current().await {
+        let (connections, idle) = ducklake.pool_state();
+        metrics::gauge!(DUCKDB_POOL_MAX).set(ducklake.pool_size());
+        metrics::gauge!(DUCKDB_POOL_CONNECTIONS).set(connections);
+        metrics::gauge!(DUCKDB_POOL_IDLE).set(idle);
+    }
+    metrics::gauge!(API_QUERIES_IN_FLIGHT).set(limiter.in_flight() as f64);
+    if let Some(max) = limiter.max() {
+        metrics::gauge!(API_QUERIES_MAX).set(max as f64);
+    }
+
+    handle().render()
+}
+
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    // The route template keeps label cardinality bounded, unlike the raw path.
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    metrics::counter!(
+        HTTP_REQUESTS,
+        "method" => method.clone(),
+        "path" => path.clone(),
+        "status" => response.status().as_u16().to_string()
+    )
+    .increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION, "method" => method, "path" => path)
+        .record(started.elapsed().as_secs_f64());
+
+    response
+}
@@ -1,39 +1,145 @@
 use crate::{
-    api::Error,
+    api::{
+        DeleteParams, Error, ErrorCode, LabelFilter, QueryLimiter, RenameRequest,
+        audit::record_changes,
+        auth::Principal,
+        ensure_valid_name,
+        graph::LastRun,
+        query::{ExplainOptions, explain_sql},
+        renamed_references, save_renamed_references,
+    },
     core::{
-        config::{Config, model::ModelConfig},
-        graph::{Graph, dependent_tables},
+        adapter::database::ColumnInfo,
+        audit::{AuditChange, AuditLog},
+        config::{Config, data_test::RelationshipConfig, model::ModelConfig},
+        ducklake::DuckLakeCache,
+        explain::QueryPlan,
+        graph::{ColumnLineage, Graph, column_lineage, dependent_tables},
+        maintenance::drop_unowned_table,
+        template::{self, CompiledSql, TemplateError},
     },
 };
 use anyhow::Result;
-use axum::{Extension, Router, extract::Path, http::StatusCode, response::Json, routing::get};
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
+use utoipa::{OpenApi, ToSchema};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ModelSummary {
     pub name: String,
     pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub group: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ModelResponse {
+    #[serde(flatten)]
+    pub config: ModelConfig,
+    pub last_run: Option<LastRun>,
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateModelRequest {
     pub name: String,
     pub config: ModelConfig,
 }
 
+// Tables the SQL reads that no adapter or model defines. They may already be in the
+// catalog, so the model is still saved, but a typo would otherwise surface on run.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SavedModel {
+    pub missing_tables: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ValidateModelRequest {
+    pub sql: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SqlSyntaxError {
+    pub message: String,
+    pub line: Option<u64>,
+    pub column: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModelValidation {
+    pub valid: bool,
+    pub syntax_error: Option<SqlSyntaxError>,
+    pub dependencies: Vec<String>,
+    pub missing_tables: Vec<String>,
+    pub dry_run_error: Option<String>,
+    pub columns: Option<Vec<ColumnInfo>>,
+}
+
+impl ModelValidation {
+    fn new() -> Self {
+        Self {
+            valid: true,
+            syntax_error: None,
+            dependencies: Vec::new(),
+            missing_tables: Vec::new(),
+            dry_run_error: None,
+            columns: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModelLineage {
+    pub model: String,
+    pub columns: Vec<ColumnLineage>,
+}
+
 pub fn routes() -> Router {
     Router::new()
         .route("/models", get(list_models).post(create_model))
+        .route("/models/validate", post(validate_model))
         .route(
             "/models/{name}",
             get(get_model).put(update_model).delete(delete_model),
         )
+        .route("/models/{name}/rename", post(rename_model))
+        .route("/models/{name}/lineage", get(get_model_lineage))
+        .route("/models/{name}/explain", post(explain_model))
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_models,
+    create_model,
+    validate_model,
+    get_model,
+    update_model,
+    delete_model,
+    rename_model,
+    get_model_lineage,
+    explain_model
+))]
+pub struct ModelApi;
+
+#[utoipa::path(
+    get,
+    path = "/models",
+    tag = "models",
+    params(LabelFilter),
+    responses((status = 200, description = "Models", body = Vec<ModelSummary>))
+)]
 async fn list_models(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Query(filter): Query<LabelFilter>,
 ) -> Result<Json<Vec<ModelSummary>>, Error> {
     let config = config.lock().await;
 
@@ -41,88 +147,423 @@ async fn list_models(
         .models
         .clone()
         .into_iter()
+        .filter(|(_, config)| filter.matches(&config.tags, config.group.as_deref()))
         .map(|(name, config)| ModelSummary {
             name,
             description: config.description,
+            tags: config.tags,
+            group: config.group,
         })
         .collect();
 
     Ok(Json(models))
 }
 
+#[utoipa::path(
+    get,
+    path = "/models/{name}",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name")),
+    responses((status = 200, description = "Model config and last run", body = ModelResponse))
+)]
 async fn get_model(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
     Path(name): Path<String>,
-) -> Result<Json<ModelConfig>, Error> {
-    let config = config.lock().await;
-    if let Some(model_config) = config.models.get(&name) {
-        Ok(Json(model_config.clone()))
+) -> Result<Json<ModelResponse>, Error> {
+    let model_config = config.lock().await.models.get(&name).cloned();
+    if let Some(model_config) = model_config {
+        Ok(Json(ModelResponse {
+            config: model_config,
+            last_run: LastRun::of(&*graph.lock().await, &name),
+        }))
     } else {
-        Error::not_found().build()
+        Error::not_found()
+            .with_code(ErrorCode::ModelNotFound)
+            .build()
+    }
+}
+
+// Explains the model's SELECT, not the statement that materializes it.
+#[utoipa::path(
+    post,
+    path = "/models/{name}/explain",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name")),
+    request_body = ExplainOptions,
+    responses((status = 200, description = "Query plan", body = QueryPlan))
+)]
+async fn explain_model(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    Path(name): Path<String>,
+    Json(options): Json<ExplainOptions>,
+) -> Result<Json<QueryPlan>, Error> {
+    let sql = {
+        let config = config.lock().await;
+        let Some(model) = config.models.get(&name) else {
+            return Error::not_found()
+                .with_code(ErrorCode::ModelNotFound)
+                .build();
+        };
+        compile_model_sql(&config, &model.sql)?
+    };
+
+    let plan = explain_sql(&config, &ducklakes, &limiter, &sql, &options).await?;
+    Ok(Json(plan))
+}
+
+#[utoipa::path(
+    get,
+    path = "/models/{name}/lineage",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name")),
+    responses((status = 200, description = "Column lineage", body = ModelLineage))
+)]
+async fn get_model_lineage(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path(name): Path<String>,
+) -> Result<Json<ModelLineage>, Error> {
+    let config = config.lock().await;
+    let Some(model) = config.models.get(&name) else {
+        return Error::not_found()
+            .with_code(ErrorCode::ModelNotFound)
+            .build();
+    };
+
+    let sql = compile_model_sql(&config, &model.sql)?;
+    let columns = column_lineage(&sql).map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::InvalidSql)
+            .with_message(format!("Failed to parse SQL: {e}"))
+    })?;
+
+    Ok(Json(ModelLineage {
+        model: name,
+        columns,
+    }))
+}
+
+// Static checks shared by the validate endpoint and model create/update.
+fn check_model_sql(config: &Config, sql: &str) -> ModelValidation {
+    let mut validation = ModelValidation::new();
+
+    let compiled = match template::compile_for_analysis(config, sql) {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            match e.missing_resource() {
+                Some(resource) => validation.missing_tables.push(resource.to_string()),
+                None => {
+                    validation.syntax_error = Some(SqlSyntaxError {
+                        message: e.to_string(),
+                        line: None,
+                        column: None,
+                    })
+                }
+            }
+            validation.valid = false;
+            return validation;
+        }
+    };
+    check_compiled_sql(config, compiled, validation)
+}
+
+fn check_compiled_sql(
+    config: &Config,
+    compiled: CompiledSql,
+    mut validation: ModelValidation,
+) -> ModelValidation {
+    match dependent_tables(&compiled.sql) {
+        Ok(tables) => {
+            validation.missing_tables = tables
+                .iter()
+                .filter(|table| !config.defines_table(table))
+                .cloned()
+                .collect();
+            validation.dependencies = compiled.dependencies;
+            for table in tables {
+                if !validation.dependencies.contains(&table) {
+                    validation.dependencies.push(table);
+                }
+            }
+        }
+        Err(message) => {
+            let (line, column) = error_position(&message);
+            validation.syntax_error = Some(SqlSyntaxError {
+                message,
+                line,
+                column,
+            });
+        }
+    }
+
+    validation.valid = validation.syntax_error.is_none() && validation.missing_tables.is_empty();
+    validation
+}
+
+// sqlparser reports locations as "... at Line: 1, Column: 15".
+fn error_position(message: &str) -> (Option<u64>, Option<u64>) {
+    let Some(start) = message.rfind("Line: ") else {
+        return (None, None);
+    };
+    let mut parts = message[start + "Line: ".len()..].splitn(2, ", Column: ");
+    let line = parts.next().and_then(|line| line.parse().ok());
+    let column = parts.next().and_then(|column| {
+        let digits: String = column.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    });
+
+    (line, column)
+}
+
+pub fn ensure_valid_model(name: &str, model: &ModelConfig) -> Result<(), Error> {
+    model.validate().map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(e.to_string())
+            .with_detail("model", name)
+    })
+}
+
+pub fn ensure_relationships_resolve(
+    config: &Config,
+    relationships: &[RelationshipConfig],
+) -> Result<(), Error> {
+    config.check_relationships(relationships).map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(e.to_string())
+    })
+}
+
+fn template_error(e: TemplateError) -> Error {
+    let error = Error::bad_request()
+        .with_code(ErrorCode::ValidationFailed)
+        .with_message(e.to_string());
+    match e.missing_resource() {
+        Some(resource) => error.with_detail("resource", resource),
+        None => error,
     }
 }
 
+fn compile_model_sql(config: &Config, sql: &str) -> Result<String, Error> {
+    template::compile(config, sql, &HashMap::new())
+        .map(|compiled| compiled.sql)
+        .map_err(template_error)
+}
+
+// Unknown ref() and source() names are rejected here, so a model can't be saved
+// pointing at something that doesn't exist. Vars may be left for the run to supply.
+// Plain table names nothing defines are returned alongside the dependencies.
+pub fn model_dependencies(config: &Config, sql: &str) -> Result<(Vec<String>, Vec<String>), Error> {
+    let compiled = template::compile_for_analysis(config, sql).map_err(template_error)?;
+    let validation = check_compiled_sql(config, compiled, ModelValidation::new());
+    match validation.syntax_error {
+        Some(error) => Error::bad_request()
+            .with_code(ErrorCode::InvalidSql)
+            .with_message(format!("Failed to parse SQL: {}", error.message))
+            .with_detail("line", error.line)
+            .with_detail("column", error.column)
+            .build(),
+        None => {
+            let mut dependencies = Vec::new();
+            for node in config.table_nodes(validation.dependencies) {
+                if !dependencies.contains(&node) {
+                    dependencies.push(node);
+                }
+            }
+            Ok((dependencies, validation.missing_tables))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/models/validate",
+    tag = "models",
+    request_body = ValidateModelRequest,
+    responses((status = 200, description = "Validation result", body = ModelValidation))
+)]
+async fn validate_model(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Json(request): Json<ValidateModelRequest>,
+) -> Result<Json<ModelValidation>, Error> {
+    let (mut validation, compiled) = {
+        let config = config.lock().await;
+        let compiled = template::compile(&config, &request.sql, &HashMap::new());
+        (check_model_sql(&config, &request.sql), compiled)
+    };
+
+    // A dry run needs real values for the vars, which the static check stood in for.
+    if let (true, None) = (request.dry_run, &validation.syntax_error) {
+        let compiled = match compiled {
+            Ok(compiled) => compiled,
+            // Unknown ref() and source() names are already in `missing_tables`.
+            Err(e) => {
+                if e.missing_resource().is_none() {
+                    validation.dry_run_error = Some(e.to_string());
+                    validation.valid = false;
+                }
+                return Ok(Json(validation));
+            }
+        };
+        let ducklake = ducklakes.get(&config).await?;
+        let sql = compiled.sql.trim().trim_end_matches(';');
+        match ducklake.query(&format!("DESCRIBE ({sql})")) {
+            Ok(schema) => {
+                validation.columns = Some(
+                    schema
+                        .into_iter()
+                        .map(|row| ColumnInfo {
+                            name: row[0].clone(),
+                            data_type: row[1].clone(),
+                        })
+                        .collect(),
+                );
+            }
+            Err(e) => {
+                validation.dry_run_error = Some(e.to_string());
+                validation.valid = false;
+            }
+        }
+    }
+
+    Ok(Json(validation))
+}
+
+#[utoipa::path(
+    post,
+    path = "/models",
+    tag = "models",
+    request_body = CreateModelRequest,
+    responses((status = 200, description = "Model created", body = SavedModel))
+)]
 async fn create_model(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Json(model): Json<CreateModelRequest>,
-) -> Result<(), Error> {
+) -> Result<Json<SavedModel>, Error> {
+    ensure_valid_name(&model.name)?;
     let mut config = config.lock().await;
 
     if config.models.contains_key(&model.name) {
-        return Error::conflict().build();
+        return Error::conflict()
+            .with_code(ErrorCode::ModelAlreadyExists)
+            .build();
     }
 
-    let dependencies = dependent_tables(&model.config.sql)
-        .map_err(|_| Error::bad_request().with_message("Failed to parse SQL"))?;
+    ensure_valid_model(&model.name, &model.config)?;
+    ensure_relationships_resolve(&config, &model.config.relationships)?;
+    let (mut dependencies, missing_tables) = model_dependencies(&config, &model.config.sql)?;
+    for node in config.relationship_nodes(&model.name, &model.config.relationships) {
+        if !dependencies.contains(&node) {
+            dependencies.push(node);
+        }
+    }
 
     let mut graph = graph.lock().await;
     let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
-    graph.create_node(&model.name, &deps);
+    let mut candidate = graph.clone();
+    candidate.create_node(&model.name, &deps);
+    reject_cycle(&candidate)?;
+    *graph = candidate;
     graph.save().await?;
 
     let model_file = config.upsert_model(&model.name, &model.config)?;
     model_file.save()?;
+    let change = AuditChange::new("models", &model.name, None, Some(&model.config));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
-    Ok(())
+    Ok(Json(SavedModel { missing_tables }))
 }
 
+#[utoipa::path(
+    put,
+    path = "/models/{name}",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name")),
+    request_body = ModelConfig,
+    responses((status = 200, description = "Model updated", body = SavedModel))
+)]
 async fn update_model(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
     Json(model): Json<ModelConfig>,
-) -> Result<(), Error> {
+) -> Result<Json<SavedModel>, Error> {
     let mut config = config.lock().await;
 
-    if !config.models.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.models.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::ModelNotFound)
+            .build();
     };
 
-    let dependencies = dependent_tables(&model.sql)
-        .map_err(|_| Error::bad_request().with_message("Failed to parse SQL"))?;
+    ensure_valid_model(&name, &model)?;
+    ensure_relationships_resolve(&config, &model.relationships)?;
+    let (mut dependencies, missing_tables) = model_dependencies(&config, &model.sql)?;
+    for node in config.relationship_nodes(&name, &model.relationships) {
+        if !dependencies.contains(&node) {
+            dependencies.push(node);
+        }
+    }
 
     let mut graph = graph.lock().await;
     let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
-    graph.update_dependencies(&name, &deps);
+    let mut candidate = graph.clone();
+    candidate.update_dependencies(&name, &deps);
+    reject_cycle(&candidate)?;
+    *graph = candidate;
     graph.update_node(&name);
     graph.save().await?;
 
     let model_file = config.upsert_model(&name, &model)?;
     model_file.save()?;
+    let change = AuditChange::new("models", &name, Some(&before), Some(&model));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
-    Ok(())
+    Ok(Json(SavedModel { missing_tables }))
+}
+
+pub fn reject_cycle(graph: &Graph) -> Result<(), Error> {
+    match graph.detect_cycle() {
+        Some(cycle) => Error::bad_request()
+            .with_code(ErrorCode::DependencyCycle)
+            .with_message(format!("Dependency cycle detected: {}", cycle.join(" -> ")))
+            .with_detail("cycle", cycle)
+            .build(),
+        None => Ok(()),
+    }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/models/{name}",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name"), DeleteParams),
+    responses((status = 204, description = "Model deleted"))
+)]
 async fn delete_model(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Path(name): Path<String>,
+    Query(params): Query<DeleteParams>,
 ) -> Result<StatusCode, Error> {
-    let mut config = config.lock().await;
+    let (mut config, ducklake) = params.lock_config(&config, &ducklakes).await?;
 
-    if !config.models.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.models.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::ModelNotFound)
+            .build();
     };
 
     let mut graph = graph.lock().await;
@@ -131,15 +572,80 @@ async fn delete_model(
 
     let model_file = config.delete_model(&name)?;
     model_file.save()?;
+    let change = AuditChange::new("models", &name, Some(&before), None);
+    record_changes(&audit, &config, principal.as_deref(), [change]);
+
+    if let Some(ducklake) = ducklake {
+        drop_unowned_table(&ducklake, &config, &name)?;
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/models/{name}/rename",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name")),
+    request_body = RenameRequest,
+    responses((status = 200, description = "Model renamed"))
+)]
+async fn rename_model(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    Json(request): Json<RenameRequest>,
+) -> Result<(), Error> {
+    ensure_valid_name(&request.new_name)?;
+    let mut config = config.lock().await;
+
+    let Some(model) = config.models.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::ModelNotFound)
+            .build();
+    };
+    if config.adapters.contains_key(&request.new_name)
+        || config.models.contains_key(&request.new_name)
+    {
+        return Error::conflict()
+            .with_code(ErrorCode::ModelAlreadyExists)
+            .build();
+    }
+
+    let rewritten = renamed_references(&config, "ref", &name, &request.new_name, Some(&name))?;
+
+    // The file moves first, so a failed move leaves the graph and other models as they were.
+    let model_file = config.rename_model(&name, &request.new_name)?;
+    if let Err(e) = model_file.save() {
+        config.rename_model(&request.new_name, &name)?;
+        return Err(e.into());
+    }
+    let changes = AuditChange::renamed("models", &name, &request.new_name, &model);
+    record_changes(&audit, &config, principal.as_deref(), changes);
+    let changes = save_renamed_references(&mut config, rewritten)?;
+    record_changes(&audit, &config, principal.as_deref(), changes);
+
+    let mut graph = graph.lock().await;
+    graph.rename_node(&name, &request.new_name);
+    graph.save().await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        core::config::adapter::{AdapterConfig, AdapterSource},
+        core::{
+            config::{
+                adapter::{AdapterConfig, AdapterSource},
+                model::Materialization,
+            },
+            ducklake::DuckLake,
+            pipeline::Phase,
+        },
         test_helpers::TestManager,
     };
     use anyhow::Result;
@@ -152,11 +658,24 @@ mod tests {
 
         let users_adapter = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Users table".to_string()),
             source: AdapterSource::Database {
                 table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         {
@@ -180,6 +699,8 @@ mod tests {
 
         let response = server.post("/models").json(&new_model).await;
         response.assert_status_ok();
+        let saved: SavedModel = response.json();
+        assert!(saved.missing_tables.is_empty());
 
         let graph = Graph::load(test.directory()).await?;
         assert!(graph.has_node("active_users"));
@@ -187,6 +708,134 @@ mod tests {
         let upstream = graph.upstream("active_users");
         assert_eq!(upstream, vec!["users"]);
 
+        // A table nothing defines is saved, but reported back.
+        let response = server
+            .put("/models/active_users")
+            .json(&json!({ "sql": "SELECT * FROM users JOIN raw_events USING (id)" }))
+            .await;
+        response.assert_status_ok();
+        let saved: SavedModel = response.json();
+        assert_eq!(saved.missing_tables, vec!["raw_events"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_models_filtered_by_tag_and_group() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        {
+            let mut config = test.config().await;
+            for (name, tags, group) in [
+                ("stg_orders", vec!["staging"], Some("sales")),
+                ("orders", vec!["marts", "finance"], Some("sales")),
+                ("users", vec!["marts"], None),
+            ] {
+                let model = ModelConfig {
+                    description: None,
+                    sql: "SELECT 1".to_string(),
+                    materialization: Materialization::Table,
+                    unique_key: None,
+                    updated_at_column: None,
+                    tests: vec![],
+                    relationships: vec![],
+                    tags: tags.into_iter().map(String::from).collect(),
+                    group: group.map(String::from),
+                    weight: None,
+                    exclusive: false,
+                    masking: vec![],
+                };
+                config.upsert_model(name, &model)?.save()?;
+            }
+        }
+
+        let names = |models: Vec<ModelSummary>| {
+            let mut names: Vec<String> = models.into_iter().map(|model| model.name).collect();
+            names.sort();
+            names
+        };
+
+        let response = server.get("/models").add_query_param("tag", "marts").await;
+        response.assert_status_ok();
+        assert_eq!(names(response.json()), vec!["orders", "users"]);
+
+        let response = server
+            .get("/models")
+            .add_query_param("group", "sales")
+            .await;
+        assert_eq!(names(response.json()), vec!["orders", "stg_orders"]);
+
+        let response = server
+            .get("/models")
+            .add_query_param("tag", "marts")
+            .add_query_param("group", "sales")
+            .await;
+        let models: Vec<ModelSummary> = response.json();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].tags, vec!["marts", "finance"]);
+        assert_eq!(models[0].group.as_deref(), Some("sales"));
+
+        let response = server.get("/models").await;
+        assert_eq!(names(response.json()).len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_on_adapter_target_table() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let users_adapter = AdapterConfig {
+            connection: "test_connection".to_string(),
+            target_table: Some("raw_users".to_string()),
+            dedupe: None,
+            description: None,
+            source: AdapterSource::Database {
+                table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            config.upsert_adapter("users", &users_adapter)?.save()?;
+        }
+        test.graph().await.create_node("users", &[]);
+
+        let new_model = json!({
+            "name": "active_users",
+            "config": {
+                "sql": "SELECT * FROM raw_users WHERE active = true"
+            }
+        });
+
+        let response = server.post("/models").json(&new_model).await;
+        response.assert_status_ok();
+
+        let graph = Graph::load(test.directory()).await?;
+        assert_eq!(graph.upstream("active_users"), vec!["users"]);
+
+        // The adapter name is no longer a table once a target table is set.
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT * FROM users" }))
+            .await;
+        response.assert_status_ok();
+        let validation: serde_json::Value = response.json();
+        assert_eq!(validation["missing_tables"], json!(["users"]));
+
         Ok(())
     }
 
@@ -197,20 +846,46 @@ mod tests {
 
         let users_adapter = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Users table".to_string()),
             source: AdapterSource::Database {
                 table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         let orders_adapter = AdapterConfig {
             connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Orders table".to_string()),
             source: AdapterSource::Database {
                 table_name: "orders".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
             },
             columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         {
@@ -228,6 +903,16 @@ mod tests {
         let original_model = ModelConfig {
             description: Some("Original model".to_string()),
             sql: "SELECT * FROM users".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         {
@@ -240,8 +925,17 @@ mod tests {
             let mut graph = test.graph().await;
             graph.create_node("test_model", &["users"]);
             graph.set_current_time("test_model");
+            graph.record_failure("test_model", "Table users does not exist");
         }
 
+        let model: ModelResponse = server.get("/models/test_model").await.json();
+        let last_run = model.last_run.unwrap();
+        assert_eq!(last_run.status, Some(Phase::Failed));
+        assert_eq!(
+            last_run.error.as_deref(),
+            Some("Table users does not exist")
+        );
+
         let updated_config = json!({
             "description": "Updated model",
             "sql": "SELECT * FROM users JOIN orders ON users.id = orders.user_id"
@@ -253,9 +947,10 @@ mod tests {
         let get_response = server.get("/models/test_model").await;
         get_response.assert_status_ok();
 
-        let model_config: ModelConfig = get_response.json();
-        assert_eq!(model_config.description, Some("Updated model".to_string()));
-        assert!(model_config.sql.contains("JOIN orders"));
+        let model: ModelResponse = get_response.json();
+        assert_eq!(model.config.description, Some("Updated model".to_string()));
+        assert!(model.config.sql.contains("JOIN orders"));
+        assert_eq!(model.last_run.unwrap().error, None);
 
         {
             let graph = test.graph().await;
@@ -271,6 +966,114 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_model_two_node_cycle() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let model_a = json!({
+            "name": "model_a",
+            "config": { "sql": "SELECT * FROM model_b" }
+        });
+        server
+            .post("/models")
+            .json(&model_a)
+            .await
+            .assert_status_ok();
+
+        let model_b = json!({
+            "name": "model_b",
+            "config": { "sql": "SELECT * FROM model_a" }
+        });
+        let response = server.post("/models").json(&model_b).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "DEPENDENCY_CYCLE");
+        assert_eq!(
+            body["details"]["cycle"],
+            json!(["model_a", "model_b", "model_a"])
+        );
+
+        server
+            .get("/models/model_b")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        assert!(!test.graph().await.has_node("model_b"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_longer_cycle() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        for (name, sql) in [
+            ("model_a", "SELECT * FROM model_c"),
+            ("model_b", "SELECT * FROM model_a"),
+        ] {
+            let model = json!({ "name": name, "config": { "sql": sql } });
+            server.post("/models").json(&model).await.assert_status_ok();
+        }
+
+        let model_c = json!({
+            "name": "model_c",
+            "config": { "sql": "SELECT * FROM model_b" }
+        });
+        let response = server.post("/models").json(&model_c).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "DEPENDENCY_CYCLE");
+        assert_eq!(
+            body["details"]["cycle"],
+            json!(["model_a", "model_c", "model_b", "model_a"])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_self_reference() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let model = json!({
+            "name": "self_ref",
+            "config": { "sql": "SELECT * FROM self_ref" }
+        });
+        let response = server.post("/models").json(&model).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "DEPENDENCY_CYCLE");
+        assert_eq!(body["details"]["cycle"], json!(["self_ref", "self_ref"]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_model_cycle() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        for (name, sql) in [
+            ("model_a", "SELECT 1 AS id"),
+            ("model_b", "SELECT * FROM model_a"),
+        ] {
+            let model = json!({ "name": name, "config": { "sql": sql } });
+            server.post("/models").json(&model).await.assert_status_ok();
+        }
+
+        let updated_config = json!({ "sql": "SELECT * FROM model_b" });
+        let response = server.put("/models/model_a").json(&updated_config).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        let model_config: ModelConfig = server.get("/models/model_a").await.json();
+        assert_eq!(model_config.sql, "SELECT 1 AS id");
+        assert!(test.graph().await.upstream("model_a").is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_model() -> Result<()> {
         let test = TestManager::new();
@@ -279,6 +1082,16 @@ mod tests {
         let model_config = ModelConfig {
             description: Some("Model to delete".to_string()),
             sql: "SELECT * FROM test_table".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         {
@@ -305,4 +1118,607 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_delete_model_drop_table() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        let ducklake = {
+            let mut config = test.config().await;
+            for name in ["kept", "dropped"] {
+                let model = ModelConfig {
+                    description: None,
+                    sql: "SELECT 1 AS id".to_string(),
+                    materialization: Materialization::Table,
+                    unique_key: None,
+                    updated_at_column: None,
+                    tests: vec![],
+                    relationships: vec![],
+                    tags: vec![],
+                    group: None,
+                    weight: None,
+                    exclusive: false,
+                    masking: vec![],
+                };
+                config.upsert_model(name, &model)?.save()?;
+            }
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query("kept", "SELECT 1 AS id")?;
+            ducklake.create_table_from_query("dropped", "SELECT 1 AS id")?;
+            ducklake
+        };
+
+        server
+            .delete("/models/kept")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .delete("/models/dropped")
+            .add_query_param("drop_table", true)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        assert_eq!(ducklake.list_tables()?, vec!["kept"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_model() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let model = ModelConfig {
+            description: None,
+            sql: "SELECT * FROM users".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+
+        let report = ModelConfig {
+            sql: "SELECT * FROM {{ ref('active_users') }}".to_string(),
+            ..model.clone()
+        };
+
+        {
+            let mut config = test.config().await;
+            config.upsert_model("active_users", &model)?.save()?;
+            config.upsert_model("report", &report)?.save()?;
+        }
+
+        {
+            let mut graph = test.graph().await;
+            graph.create_node("users", &[]);
+            graph.create_node("active_users", &["users"]);
+            graph.set_current_time("active_users");
+            graph.create_node("report", &["active_users"]);
+        }
+
+        let response = server
+            .post("/models/active_users/rename")
+            .json(&json!({ "new_name": "engaged_users" }))
+            .await;
+        response.assert_status_ok();
+
+        let graph = Graph::load(test.directory()).await?;
+        assert!(!graph.has_node("active_users"));
+        let renamed = graph.get_node("engaged_users").unwrap();
+        assert!(renamed.updated_at.is_some());
+        assert_eq!(renamed.dependencies, vec!["users".to_string()]);
+        assert_eq!(
+            graph.get_node("report").unwrap().dependencies,
+            vec!["engaged_users".to_string()]
+        );
+
+        server
+            .get("/models/active_users")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server.get("/models/engaged_users").await.assert_status_ok();
+        assert!(test.directory().join("models/engaged_users.yml").exists());
+        assert!(!test.directory().join("models/active_users.yml").exists());
+
+        let report = server
+            .get("/models/report")
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(
+            report["config"]["sql"],
+            "SELECT * FROM {{ ref('engaged_users') }}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_model_conflict() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let model = ModelConfig {
+            description: None,
+            sql: "SELECT 1".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            config.upsert_model("first", &model)?.save()?;
+            config.upsert_model("second", &model)?.save()?;
+        }
+
+        let response = server
+            .post("/models/first/rename")
+            .json(&json!({ "new_name": "second" }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+
+        let response = server
+            .post("/models/missing/rename")
+            .json(&json!({ "new_name": "third" }))
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        // A model reading the table by name would break, so nothing is renamed.
+        let reader = ModelConfig {
+            sql: "SELECT * FROM first".to_string(),
+            ..model.clone()
+        };
+        test.config()
+            .await
+            .upsert_model("reader", &reader)?
+            .save()?;
+        let response = server
+            .post("/models/first/rename")
+            .json(&json!({ "new_name": "third" }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+        assert_eq!(response.json::<serde_json::Value>()["code"], "TABLE_IN_USE");
+        assert!(test.directory().join("models/first.yml").exists());
+
+        Ok(())
+    }
+
+    async fn save_users_adapter(test: &TestManager) -> Result<()> {
+        let users_adapter = AdapterConfig {
+            connection: "test_connection".to_string(),
+            target_table: None,
+            dedupe: None,
+            description: None,
+            source: AdapterSource::Database {
+                table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        test.config()
+            .await
+            .upsert_adapter("users", &users_adapter)?
+            .save()?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_model_syntax_error() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT * FROM users WHERE id = = 1" }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(!validation.valid);
+        let syntax_error = validation.syntax_error.unwrap();
+        assert_eq!(syntax_error.line, Some(1));
+        assert!(syntax_error.column.is_some());
+
+        // Model creation rejects the same SQL.
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "broken",
+                "config": { "sql": "SELECT * FROM users WHERE id = = 1" }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_SQL");
+        assert_eq!(body["details"]["line"], 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_model_missing_table() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_users_adapter(&test).await?;
+
+        let response = server
+            .post("/models/validate")
+            .json(&json!({
+                "sql": "SELECT * FROM users JOIN orders ON users.id = orders.user_id"
+            }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(!validation.valid);
+        assert!(validation.syntax_error.is_none());
+        assert_eq!(validation.dependencies, vec!["users", "orders"]);
+        assert_eq!(validation.missing_tables, vec!["orders"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_with_template_refs() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_users_adapter(&test).await?;
+        test.graph().await.create_node("users", &[]);
+
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "active_users",
+                "config": { "sql": "SELECT * FROM {{ source('users') }} WHERE active" }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        // ref() registers the dependency even where the SQL parser doesn't look.
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "user_count",
+                "config": {
+                    "sql": "SELECT (SELECT count(*) FROM {{ ref('active_users') }}) AS n LIMIT {{ var('limit', '1') }}"
+                }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        // A var the run supplies doesn't hold up saving, nor the model's dependencies.
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "recent_users",
+                "config": {
+                    "sql": "SELECT * FROM {{ ref('active_users') }} WHERE created_at >= {{ var('since') }}"
+                }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        let graph = Graph::load(test.directory()).await?;
+        assert_eq!(graph.upstream("active_users"), vec!["users"]);
+        assert_eq!(
+            graph.get_node("user_count").unwrap().dependencies,
+            vec!["active_users".to_string()]
+        );
+        assert_eq!(
+            graph.get_node("recent_users").unwrap().dependencies,
+            vec!["active_users".to_string()]
+        );
+        let mut rebuilt = Graph::new(test.directory());
+        rebuilt.rebuild(&*test.config().await);
+        assert_eq!(rebuilt.upstream("recent_users"), vec!["active_users"]);
+
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "broken",
+                "config": { "sql": "SELECT * FROM {{ ref('missing_model') }}" }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"]["resource"], "missing_model");
+        assert!(!test.config().await.models.contains_key("broken"));
+
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT * FROM {{ source('missing_adapter') }}" }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(!validation.valid);
+        assert_eq!(validation.missing_tables, vec!["missing_adapter"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_model_dry_run() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_users_adapter(&test).await?;
+        {
+            let config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query("users", "SELECT 1 AS id, 'alice' AS name")?;
+        }
+
+        // The typo is only visible once the query is planned against the catalog.
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT id, nmae FROM users" }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(validation.valid);
+        assert!(validation.columns.is_none());
+
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT id, nmae FROM users", "dry_run": true }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(!validation.valid);
+        assert!(validation.dry_run_error.unwrap().contains("nmae"));
+
+        let response = server
+            .post("/models/validate")
+            .json(&json!({ "sql": "SELECT id, name FROM users;", "dry_run": true }))
+            .await;
+        response.assert_status_ok();
+        let validation: ModelValidation = response.json();
+        assert!(validation.valid);
+        let columns: Vec<String> = validation
+            .columns
+            .unwrap()
+            .into_iter()
+            .map(|column| column.name)
+            .collect();
+        assert_eq!(columns, vec!["id", "name"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_materialization() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "events_latest",
+                "config": {
+                    "sql": "SELECT * FROM events",
+                    "materialization": "incremental",
+                    "updated_at_column": "updated_at"
+                }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"]["model"], "events_latest");
+
+        server
+            .post("/models")
+            .json(&json!({
+                "name": "events_latest",
+                "config": {
+                    "sql": "SELECT * FROM events",
+                    "materialization": "incremental",
+                    "unique_key": "id",
+                    "updated_at_column": "updated_at"
+                }
+            }))
+            .await
+            .assert_status_ok();
+
+        let model: ModelConfig = server.get("/models/events_latest").await.json();
+        assert_eq!(model.materialization, Materialization::Incremental);
+        assert_eq!(model.unique_key, Some("id".to_string()));
+        assert_eq!(model.updated_at_column, Some("updated_at".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_with_data_tests() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/models")
+            .json(&json!({
+                "name": "orders",
+                "config": {
+                    "sql": "SELECT 1 AS id, 'paid' AS status",
+                    "tests": [{ "column": "status", "type": "accepted_values", "values": [] }]
+                }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"]["model"], "orders");
+
+        server
+            .post("/models")
+            .json(&json!({
+                "name": "orders",
+                "config": {
+                    "sql": "SELECT 1 AS id, 'paid' AS status",
+                    "tests": [
+                        { "column": "id", "type": "unique" },
+                        { "column": "status", "type": "accepted_values", "values": ["paid"] }
+                    ]
+                }
+            }))
+            .await
+            .assert_status_ok();
+
+        let model: ModelConfig = server.get("/models/orders").await.json();
+        assert_eq!(model.tests.len(), 2);
+        assert_eq!(model.tests[0].column, "id");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_model_with_relationships() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        server
+            .post("/models")
+            .json(&json!({ "name": "users", "config": { "sql": "SELECT 1 AS id" } }))
+            .await
+            .assert_status_ok();
+
+        let orders = |table: &str| {
+            json!({
+                "name": "orders",
+                "config": {
+                    "sql": "SELECT 1 AS id, 1 AS user_id",
+                    "relationships": [
+                        { "column": "user_id", "references": { "table": table, "column": "id" } }
+                    ]
+                }
+            })
+        };
+
+        let response = server.post("/models").json(&orders("ghosts")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert!(body["message"].as_str().unwrap().contains("ghosts"));
+
+        server
+            .post("/models")
+            .json(&orders("users"))
+            .await
+            .assert_status_ok();
+
+        // The relationship orders the build after `users`, although the SQL reads nothing.
+        let graph = Graph::load(test.directory()).await?;
+        assert_eq!(graph.upstream("orders"), vec!["users"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_model_lineage() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let mut config = test.config().await;
+            let model = ModelConfig {
+                description: None,
+                sql: "SELECT u.id, u.first_name || ' ' || u.last_name AS full_name, o.total \
+                      FROM users u JOIN orders o ON u.id = o.user_id"
+                    .to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("user_orders", &model)?.save()?;
+        }
+        let server = test.setup_server(routes);
+
+        let response = server.get("/models/user_orders/lineage").await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["model"], "user_orders");
+        assert_eq!(body["columns"][1]["output"], "full_name");
+        assert_eq!(
+            body["columns"][1]["inputs"],
+            json!([
+                { "table": "users", "column": "first_name" },
+                { "table": "users", "column": "last_name" }
+            ])
+        );
+        assert_eq!(body["columns"][2]["inputs"][0]["table"], "orders");
+
+        let response = server.get("/models/missing/lineage").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "MODEL_NOT_FOUND");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_model() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        {
+            let mut config = test.config().await;
+            let model = ModelConfig {
+                description: None,
+                sql: "SELECT a.range AS id FROM range(10) a JOIN range(5) b ON a.range = b.range"
+                    .to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("joined", &model)?.save()?;
+        }
+
+        let response = server
+            .post("/models/joined/explain")
+            .json(&json!({ "analyze": true }))
+            .await;
+        response.assert_status_ok();
+        let plan: QueryPlan = response.json();
+        assert!(plan.analyzed);
+        assert!(!plan.plan.trim().is_empty());
+
+        let response = server
+            .post("/models/missing/explain")
+            .json(&json!({}))
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,108 @@
+use crate::{
+    api::Error,
+    core::{
+        config::Config,
+        notification::{self, Notice},
+    },
+};
+use anyhow::Result;
+use axum::{Extension, Router, response::Json, routing::post};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelResult {
+    pub name: String,
+    pub delivered: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestNotificationResponse {
+    pub channels: Vec<ChannelResult>,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/notifications/test", post(test_notifications))
+}
+
+// Sends a test message to every channel. Delivery failures are reported per channel
+// instead of failing the request, so one broken channel doesn't hide the others.
+async fn test_notifications(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+) -> Result<Json<TestNotificationResponse>, Error> {
+    let project = config.lock().await.project.clone();
+    if project.notifications.is_empty() {
+        return Error::bad_request()
+            .with_message("No notification channels are configured")
+            .build();
+    }
+
+    let channels = notification::send_all(&project, &Notice::test())
+        .await
+        .into_iter()
+        .map(|(name, result)| ChannelResult {
+            name,
+            delivered: result.is_ok(),
+            error: result.err().map(|e| format!("{e:#}")),
+        })
+        .collect();
+
+    Ok(Json(TestNotificationResponse { channels }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::StatusCode,
+        core::config::notification::{EmailConfig, NotificationConfig, TlsMode},
+        test_helpers::TestManager,
+    };
+
+    #[tokio::test]
+    async fn test_notifications_without_channels() {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server.post("/notifications/test").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_report_connection_failure() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        // Nothing listens on port 1, so the SMTP connection is refused.
+        test.config().await.project.notifications.insert(
+            "ops".to_string(),
+            NotificationConfig::Email(EmailConfig {
+                host: "127.0.0.1".to_string(),
+                port: Some(1),
+                username: None,
+                password: None,
+                from: "duckhub@example.com".to_string(),
+                to: vec!["ops@example.com".to_string()],
+                tls: TlsMode::None,
+            }),
+        );
+
+        let response = server.post("/notifications/test").await;
+        response.assert_status_ok();
+        let body: TestNotificationResponse = response.json();
+        assert_eq!(body.channels.len(), 1);
+        assert_eq!(body.channels[0].name, "ops");
+        assert!(!body.channels[0].delivered);
+        assert!(
+            body.channels[0]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("Failed to send email through 127.0.0.1:1")
+        );
+
+        Ok(())
+    }
+}
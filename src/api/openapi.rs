@@ -0,0 +1,183 @@
+use crate::api::{ErrorResponse, adapter, connection, dashboard, model, query};
+use axum::Router;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{self, ContentBuilder, Ref, ResponseBuilder, server::Server},
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "DuckHub API"),
+    components(schemas(ErrorResponse)),
+    tags(
+        (name = "connections"),
+        (name = "adapters"),
+        (name = "models"),
+        (name = "queries"),
+        (name = "dashboards"),
+    )
+)]
+struct ApiDoc;
+
+// Handlers only document their success response; errors all share one shape.
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        let response = ResponseBuilder::new()
+            .description("Error")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ErrorResponse")))
+                    .build(),
+            )
+            .build();
+
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation
+                    .responses
+                    .responses
+                    .entry("default".to_string())
+                    .or_insert_with(|| response.clone().into());
+            }
+        }
+    }
+}
+
+// Paths are relative to the `/api` prefix, which the server entry carries.
+pub fn spec(base_path: &str) -> openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+    spec.merge(connection::ConnectionApi::openapi());
+    spec.merge(adapter::AdapterApi::openapi());
+    spec.merge(model::ModelApi::openapi());
+    spec.merge(query::QueryApi::openapi());
+    spec.merge(dashboard::DashboardApi::openapi());
+    ErrorResponses.modify(&mut spec);
+    spec.servers = Some(vec![Server::new(format!("{base_path}/api"))]);
+    spec
+}
+
+// Served without the API token, like the health probes; the spec only
+// describes request and response shapes.
+pub fn routes(base_path: &str) -> Router {
+    SwaggerUi::new(format!("{base_path}/api/docs"))
+        .url(format!("{base_path}/api/openapi.json"), spec(base_path))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::app,
+        core::{
+            config::{
+                Config,
+                api_token::{ApiToken, generate_api_token},
+                project::ServerConfig,
+            },
+            graph::Graph,
+        },
+    };
+    use anyhow::Result;
+    use axum_test::TestServer;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    // `tag` variants as (tag, properties) pairs.
+    fn variants<'a>(spec: &'a Value, schema: &str) -> Vec<(&'a str, &'a Value)> {
+        spec["components"]["schemas"][schema]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| {
+                let properties = &variant["properties"];
+                (properties["type"]["enum"][0].as_str().unwrap(), properties)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spec() -> Result<()> {
+        let spec: Value = serde_json::from_str(&spec("/duckhub").to_json()?)?;
+        assert_eq!(spec["servers"][0]["url"], "/duckhub/api");
+
+        for (path, method) in [
+            ("/connections", "post"),
+            ("/connections/{name}", "put"),
+            ("/adapters", "get"),
+            ("/adapters/{name}", "delete"),
+            ("/models", "post"),
+            ("/models/validate", "post"),
+            ("/query", "post"),
+            ("/queries/{name}/run", "post"),
+            ("/dashboards/{name}/panels/{index}/data", "get"),
+        ] {
+            let operation = &spec["paths"][path][method];
+            assert!(operation.is_object(), "missing {method} {path}");
+            assert!(operation["responses"]["default"].is_object());
+        }
+
+        let connections = variants(&spec, "ConnectionConfig");
+        let tags: Vec<&str> = connections.iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(
+            tags,
+            vec![
+                "localfile",
+                "s3",
+                "sqlite",
+                "mysql",
+                "postgresql",
+                "http",
+                "gcs"
+            ]
+        );
+        for (_, properties) in &connections {
+            assert!(properties["config"].is_object());
+        }
+
+        // Internally tagged: fields sit next to `type`, and skipped ones are left out.
+        let secrets = variants(&spec, "SecretField");
+        let (_, plain) = secrets.iter().find(|(tag, _)| *tag == "plain").unwrap();
+        assert!(plain["value"].is_object());
+        let (_, env) = secrets.iter().find(|(tag, _)| *tag == "env").unwrap();
+        assert!(env["name"].is_object());
+        assert!(env["value"].is_null());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serve_spec() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        generate_api_token(dir.path())?;
+        let app = app(
+            Arc::new(Mutex::new(Config::new(dir.path().to_path_buf()))),
+            Arc::new(Mutex::new(Graph::new(dir.path()))),
+            Arc::default(),
+            Arc::default(),
+            Some(ApiToken::load(dir.path())?),
+            &ServerConfig::default(),
+        );
+        let server = TestServer::new(app)?;
+
+        let response = server.get("/api/openapi.json").await;
+        response.assert_status_ok();
+        let spec: Value = response.json();
+        assert!(spec["paths"]["/connections"].is_object());
+
+        server.get("/api/docs/").await.assert_status_ok();
+
+        Ok(())
+    }
+}
@@ -1,25 +1,41 @@
 use crate::{
-    api::Error,
+    api::{Error, ErrorCode},
     core::{
         config::Config,
         graph::Graph,
-        pipeline::{Pipeline, run_pipeline_all, run_pipeline_node},
+        pipeline::{ActivePipeline, LoadIssue, Pipeline, run_pipeline_all, run_pipeline_node},
+        task_log::read_task_log,
+        template,
     },
 };
 use anyhow::Result;
 use axum::{
     Extension, Router,
-    extract::Json as ExtractJson,
-    response::Json,
+    extract::{Json as ExtractJson, Path, Query},
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
-use serde::Deserialize;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use futures::{Stream, StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex, broadcast::error::RecvError};
+
+// `issues` lists run files that could not be read, so the history can say so
+// instead of silently showing fewer runs.
+#[derive(Serialize)]
+pub struct PipelineList {
+    pub pipelines: Vec<Pipeline>,
+    pub issues: Vec<LoadIssue>,
+}
 
 pub fn routes() -> Router {
     Router::new()
         .route("/pipelines", get(list_pipelines))
+        .route("/pipelines/current/events", get(pipeline_events))
+        .route("/pipelines/{id}/tasks/{name}/logs", get(get_task_log))
         .route("/pipeline", get(get_pipeline))
         .route("/pipeline/run", post(run))
         .route("/pipeline/run-node", post(run_node))
@@ -27,15 +43,15 @@ pub fn routes() -> Router {
 
 async fn list_pipelines(
     Extension(config): Extension<Arc<Mutex<Config>>>,
-) -> Result<Json<Vec<Pipeline>>, Error> {
+) -> Result<Json<PipelineList>, Error> {
     let project_dir = {
         let config = config.lock().await;
         config.project_dir.clone()
     };
 
-    let pipelines = Pipeline::load_all(&project_dir).await?;
+    let (pipelines, issues) = Pipeline::load_all(&project_dir).await?;
 
-    Ok(Json(pipelines))
+    Ok(Json(PipelineList { pipelines, issues }))
 }
 
 async fn get_pipeline(
@@ -51,25 +67,260 @@ async fn get_pipeline(
     Ok(Json(status))
 }
 
+async fn pipeline_events(
+    Extension(active): Extension<Arc<ActivePipeline>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, Error> {
+    let Some(pipeline) = active.get().await else {
+        return Error::not_found()
+            .with_code(ErrorCode::PipelineNotFound)
+            .with_message("No pipeline has been run since the server started")
+            .build();
+    };
+
+    let (snapshot, receiver) = pipeline.lock().await.subscribe();
+    let finished = snapshot.ends_run();
+
+    // The stream ends after the event that ends the run, however it ended. Events
+    // missed while lagging are replaced by a fresh snapshot, which is the final event
+    // when the run ended in the meantime.
+    let updates = stream::unfold(
+        (pipeline, receiver, finished),
+        |(pipeline, mut receiver, finished)| async move {
+            if finished {
+                return None;
+            }
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => {
+                    let (snapshot, fresh) = pipeline.lock().await.subscribe();
+                    receiver = fresh;
+                    snapshot
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            let finished = event.ends_run();
+            Some((event, (pipeline, receiver, finished)))
+        },
+    );
+
+    let events = stream::once(async move { snapshot })
+        .chain(updates)
+        .map(|event| Event::default().json_data(event));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Deserialize)]
+struct TaskLogParams {
+    tail: Option<usize>,
+}
+
+async fn get_task_log(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path((id, name)): Path<(String, String)>,
+    Query(params): Query<TaskLogParams>,
+) -> Result<String, Error> {
+    // Both segments become part of a file path, so keep them to a single component.
+    let is_component = |segment: &str| {
+        !segment.is_empty() && segment != "." && segment != ".." && !segment.contains(['/', '\\'])
+    };
+    if !is_component(&id) || !is_component(&name) {
+        return Error::bad_request()
+            .with_message("Invalid pipeline id or task name")
+            .build();
+    }
+
+    let project_dir = config.lock().await.project_dir.clone();
+    let path = Pipeline::task_log_path(&project_dir, &id, &name);
+
+    match read_task_log(&path, params.tail).await? {
+        Some(log) => Ok(log),
+        None => Error::not_found()
+            .with_message(format!("No log for task '{name}' in pipeline '{id}'"))
+            .build(),
+    }
+}
+
+#[derive(Deserialize)]
+struct RunParams {
+    #[serde(default)]
+    full_refresh: bool,
+    // Overrides `pipeline.max_workers` for this run.
+    max_workers: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+struct RunRequest {
+    // Overrides the project's `vars` for `{{ var() }}` in model SQL.
+    #[serde(default)]
+    vars: HashMap<String, String>,
+}
+
+// The run happens in the background, so templates that can't be filled in with
+// these vars are rejected here rather than failing their tasks later.
+async fn check_templates(
+    config: &Mutex<Config>,
+    vars: &HashMap<String, String>,
+) -> Result<(), Error> {
+    let config = config.lock().await;
+    for (name, model) in &config.models {
+        if let Err(e) = template::compile(&config, &model.sql, vars) {
+            return Error::bad_request()
+                .with_code(ErrorCode::ValidationFailed)
+                .with_message(format!("Model '{name}': {e}"))
+                .with_detail("model", name.as_str())
+                .build();
+        }
+    }
+    Ok(())
+}
+
+// Concurrent triggers get one run between them; the others are refused.
+async fn claim_run(
+    config: &Mutex<Config>,
+    active: &ActivePipeline,
+) -> Result<Arc<Mutex<Pipeline>>, Error> {
+    let project_dir = config.lock().await.project_dir.clone();
+    active.claim(&project_dir).await.map_err(|e| {
+        Error::conflict()
+            .with_code(ErrorCode::PipelineRunning)
+            .with_message(e.to_string())
+    })
+}
+
 async fn run(
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(active): Extension<Arc<ActivePipeline>>,
+    Query(params): Query<RunParams>,
+    payload: Option<ExtractJson<RunRequest>>,
 ) -> Result<(), Error> {
-    tokio::spawn(async move { run_pipeline_all(config, graph).await });
+    let ExtractJson(request) = payload.unwrap_or_default();
+    check_templates(&config, &request.vars).await?;
+    let pipeline = claim_run(&config, &active).await?;
+    tokio::spawn(async move {
+        run_pipeline_all(
+            config,
+            graph,
+            pipeline,
+            params.full_refresh,
+            params.max_workers,
+            request.vars,
+        )
+        .await
+    });
     Ok(())
 }
 
 #[derive(Deserialize)]
 struct RunNodeRequest {
     node_name: String,
+    #[serde(default)]
+    vars: HashMap<String, String>,
 }
 
 async fn run_node(
     Extension(graph): Extension<Arc<Mutex<Graph>>>,
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(active): Extension<Arc<ActivePipeline>>,
+    Query(params): Query<RunParams>,
     ExtractJson(request): ExtractJson<RunNodeRequest>,
 ) -> Result<(), Error> {
-    let node_name = request.node_name;
-    tokio::spawn(async move { run_pipeline_node(config, graph, node_name).await });
+    check_templates(&config, &request.vars).await?;
+    let pipeline = claim_run(&config, &active).await?;
+    tokio::spawn(async move {
+        run_pipeline_node(
+            config,
+            graph,
+            pipeline,
+            request.node_name,
+            params.full_refresh,
+            params.max_workers,
+            request.vars,
+        )
+        .await
+    });
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::StatusCode, test_helpers::TestManager};
+
+    #[tokio::test]
+    async fn test_get_task_log() -> Result<()> {
+        let test = TestManager::new();
+        let path = Pipeline::task_log_path(test.directory(), "2025-01-01-00-00-00", "users");
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, "first\nsecond\nthird\n")?;
+        let server = test.setup_server(routes);
+
+        let response = server
+            .get("/pipelines/2025-01-01-00-00-00/tasks/users/logs")
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.text(), "first\nsecond\nthird\n");
+
+        let response = server
+            .get("/pipelines/2025-01-01-00-00-00/tasks/users/logs")
+            .add_query_param("tail", 2)
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.text(), "second\nthird");
+
+        server
+            .get("/pipelines/2025-01-01-00-00-00/tasks/orders/logs")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_rejects_unresolved_vars() -> Result<()> {
+        let test = TestManager::new();
+        test.config()
+            .await
+            .upsert_model(
+                "recent_orders",
+                &serde_yml::from_str("sql: SELECT * FROM orders WHERE day >= {{ var('since') }}")?,
+            )?
+            .save()?;
+        let server = test.setup_server(routes);
+
+        let response = server.post("/pipeline/run").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"]["model"], "recent_orders");
+
+        let response = server
+            .post("/pipeline/run-node")
+            .json(&serde_json::json!({ "node_name": "recent_orders", "vars": {} }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_rejected_while_running() -> Result<()> {
+        let test = TestManager::new();
+        test.active().mark_running(test.directory()).await;
+        let server = test.setup_server(routes);
+
+        let response = server.post("/pipeline/run").await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "PIPELINE_RUNNING");
+
+        server
+            .post("/pipeline/run-node")
+            .json(&serde_json::json!({ "node_name": "users" }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        Ok(())
+    }
+}
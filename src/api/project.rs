@@ -0,0 +1,1069 @@
+use crate::{
+    api::{Error, ErrorCode, audit::record_changes, auth::Principal},
+    core::{
+        audit::{AuditChange, AuditLog},
+        bundle::{Bundle, ImportSummary, export_bundle},
+        config::{
+            Config,
+            project::{DatabaseConfig, ProjectConfig, StorageConfig},
+            secret::rotate_secret_key,
+        },
+        ducklake::DuckLakeCache,
+        graph::Graph,
+        reload::{ReloadSummary, reload},
+        validation::{ValidationReport, validate_project},
+    },
+};
+use axum::{
+    Extension, Router,
+    body::Bytes,
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectSettings {
+    pub storage: StorageConfig,
+    pub database: DatabaseConfig,
+}
+
+// `effective` returns the settings with the environment overlay applied.
+#[derive(Deserialize)]
+pub struct SettingsParams {
+    #[serde(default)]
+    pub effective: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RotateKeyResponse {
+    pub rotated: usize,
+    // File name of the previous key's backup in the project directory.
+    pub backup: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReconnectResponse {
+    pub connected: bool,
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/project/validate", get(validate))
+        .route("/project/reload", post(reload_config))
+        .route("/project/reconnect", post(reconnect))
+        .route("/project/rotate-key", post(rotate_key))
+        .route("/project/settings", get(get_settings).put(update_settings))
+        .route("/project/storage", get(get_storage).put(update_storage))
+        .route("/project/database", get(get_database).put(update_database))
+        .route("/project/export", get(export))
+        .route("/project/import", post(import))
+}
+
+async fn validate(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+) -> Result<Json<ValidationReport>, Error> {
+    let config = config.lock().await;
+    let graph = graph.lock().await;
+
+    Ok(Json(validate_project(&config, &graph)?))
+}
+
+// For file edits the watcher cannot see, e.g. where inotify is unavailable.
+async fn reload_config(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+) -> Result<Json<ReloadSummary>, Error> {
+    Ok(Json(reload(&config, &graph, None).await?))
+}
+
+// Retries attaching the catalog after a failure. Fails with 503 CATALOG_UNAVAILABLE
+// while it is still unreachable.
+async fn reconnect(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+) -> Result<Json<ReconnectResponse>, Error> {
+    ducklakes.reconnect(&config).await?;
+    Ok(Json(ReconnectResponse { connected: true }))
+}
+
+async fn get_settings(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<SettingsParams>,
+) -> Result<Json<ProjectSettings>, Error> {
+    let project = settings_project(&*config.lock().await, &params, principal.as_deref())?;
+
+    Ok(Json(ProjectSettings {
+        storage: project.storage,
+        database: project.database,
+    }))
+}
+
+async fn update_settings(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Json(settings): Json<ProjectSettings>,
+) -> Result<(), Error> {
+    let mut config = config.lock().await;
+    validate_settings(settings.storage.validate())?;
+    validate_settings(settings.database.validate())?;
+
+    let change = save_project(&mut config, &ducklakes, |project| {
+        project.storage = settings.storage;
+        project.database = settings.database;
+    })
+    .await?;
+    record_changes(&audit, &config, principal.as_deref(), [change]);
+
+    Ok(())
+}
+
+async fn get_storage(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<SettingsParams>,
+) -> Result<Json<StorageConfig>, Error> {
+    let project = settings_project(&*config.lock().await, &params, principal.as_deref())?;
+
+    Ok(Json(project.storage))
+}
+
+async fn update_storage(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Json(storage): Json<StorageConfig>,
+) -> Result<(), Error> {
+    let mut config = config.lock().await;
+    validate_settings(storage.validate())?;
+
+    let change = save_project(&mut config, &ducklakes, |project| {
+        project.storage = storage;
+    })
+    .await?;
+    record_changes(&audit, &config, principal.as_deref(), [change]);
+
+    Ok(())
+}
+
+async fn get_database(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<SettingsParams>,
+) -> Result<Json<DatabaseConfig>, Error> {
+    let project = settings_project(&*config.lock().await, &params, principal.as_deref())?;
+
+    Ok(Json(project.database))
+}
+
+async fn update_database(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Json(database): Json<DatabaseConfig>,
+) -> Result<(), Error> {
+    let mut config = config.lock().await;
+    validate_settings(database.validate())?;
+
+    let change = save_project(&mut config, &ducklakes, |project| {
+        project.database = database;
+    })
+    .await?;
+    record_changes(&audit, &config, principal.as_deref(), [change]);
+
+    Ok(())
+}
+
+// The project as the settings endpoints read it: what project.yml holds unless
+// `effective` is set. Secrets come back decrypted to callers with a token, and
+// masked as "***" when the server runs without one; sending "***" back keeps them.
+fn settings_project(
+    config: &Config,
+    params: &SettingsParams,
+    principal: Option<&Principal>,
+) -> Result<ProjectConfig, Error> {
+    let mut project = if params.effective {
+        config.project.clone()
+    } else {
+        config.base_project()?
+    };
+    if principal.is_none() {
+        project.storage = project.storage.masked();
+        project.database = project.database.masked();
+    }
+    Ok(project)
+}
+
+fn validate_settings(result: anyhow::Result<()>) -> Result<(), Error> {
+    result.map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::ValidationFailed)
+            .with_message(e.to_string())
+    })
+}
+
+// Returns the change to record in the audit log.
+async fn save_project(
+    config: &mut Config,
+    ducklakes: &DuckLakeCache,
+    update: impl FnOnce(&mut ProjectConfig),
+) -> Result<AuditChange, Error> {
+    let project_dir = config.project_dir.clone();
+    let change = config.update_project(|project| {
+        let before = project.clone();
+        update(project);
+        project.storage.restore_masked(before.storage.clone());
+        project.database.restore_masked(before.database.clone());
+        project
+            .load_secrets(&project_dir)
+            .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+        Ok::<_, Error>(AuditChange::project(&before, project))
+    })?;
+
+    // The cached DuckLake still points at the old catalog and storage.
+    ducklakes.invalidate().await;
+
+    Ok(change)
+}
+
+async fn rotate_key(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+) -> Result<Json<RotateKeyResponse>, Error> {
+    let mut config = config.lock().await;
+
+    let rotation = rotate_secret_key(&config.project_dir)?;
+    config.load()?;
+
+    Ok(Json(RotateKeyResponse {
+        rotated: rotation.rotated,
+        backup: rotation
+            .backup
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+    }))
+}
+
+async fn export(Extension(config): Extension<Arc<Mutex<Config>>>) -> Result<Response, Error> {
+    let config = config.lock().await;
+    let export = export_bundle(&config)?;
+
+    let name = config
+        .project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    let content_disposition = format!("attachment; filename=\"{name}.tar.gz\"");
+
+    // Secrets are blanked in the bundle; tell the caller which ones.
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition),
+            (
+                header::HeaderName::from_static("x-duckhub-stripped-secrets"),
+                export.stripped_secrets.join(", "),
+            ),
+        ],
+        export.archive,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    #[serde(default)]
+    overwrite: bool,
+}
+
+async fn import(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<ImportParams>,
+    body: Bytes,
+) -> Result<Json<ImportSummary>, Error> {
+    let bundle = Bundle::read(body.as_ref()).map_err(|e| {
+        Error::bad_request()
+            .with_code(ErrorCode::InvalidBundle)
+            .with_message(format!("{e:#}"))
+    })?;
+
+    let mut config = config.lock().await;
+    let collisions = bundle.collisions(&config);
+    if !collisions.is_empty() && !params.overwrite {
+        return Error::conflict()
+            .with_code(ErrorCode::ImportConflict)
+            .with_message(format!(
+                "{} item(s) in the bundle already exist; pass overwrite=true to replace them",
+                collisions.len()
+            ))
+            .with_detail("collisions", collisions)
+            .build();
+    }
+
+    let before = config.clone();
+    let summary = bundle.apply(&mut config)?;
+    let imported = [
+        ("connections", &summary.connections),
+        ("adapters", &summary.adapters),
+        ("models", &summary.models),
+        ("queries", &summary.queries),
+        ("dashboards", &summary.dashboards),
+    ];
+    let changes = imported.into_iter().flat_map(|(kind, names)| {
+        names
+            .iter()
+            .map(|name| AuditChange::between(&before, &config, kind, name))
+    });
+    record_changes(&audit, &config, principal.as_deref(), changes);
+
+    let mut graph = graph.lock().await;
+    graph.rebuild(&config);
+    graph.save().await?;
+    if !summary.connections.is_empty() {
+        ducklakes.invalidate().await;
+    }
+
+    Ok(Json(summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::StatusCode,
+        core::{
+            config::{
+                adapter::{AdapterConfig, AdapterSource},
+                dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+                model::{Materialization, ModelConfig},
+                project::{
+                    ConnectionConfig, DatabaseType, MASKED_SECRET, RemoteDatabaseConfig,
+                    S3AuthMethod, S3Config,
+                },
+                secret::SecretField,
+            },
+            ducklake::DuckLake,
+            validation::{FindingKind, Severity},
+        },
+        test_helpers::TestManager,
+    };
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_validate_project_endpoint() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        {
+            let mut config = test.config().await;
+            let adapter_config = AdapterConfig {
+                connection: "missing_connection".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: "users".to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: None,
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_adapter("users", &adapter_config)?.save()?;
+
+            let model_config = ModelConfig {
+                description: None,
+                sql: "SELECT * FROM".to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("broken", &model_config)?.save()?;
+
+            let model_config = ModelConfig {
+                description: None,
+                sql: "SELECT * FROM ghosts".to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("dangling", &model_config)?.save()?;
+
+            let dashboard_config = DashboardConfig {
+                description: None,
+                panels: vec![PanelConfig::new(
+                    "missing_query",
+                    ChartConfig {
+                        chart_type: ChartType::Line,
+                        x_column: "x".to_string(),
+                        y_column: "y".to_string(),
+                    },
+                )],
+                tags: vec![],
+                group: None,
+            };
+            config
+                .upsert_dashboard("sales", &dashboard_config)?
+                .save()?;
+        }
+
+        {
+            let mut graph = test.graph().await;
+            graph.create_node("users", &[]);
+            graph.create_node("leftover", &[]);
+        }
+
+        let response = server.get("/project/validate").await;
+        response.assert_status_ok();
+
+        let report: ValidationReport = response.json();
+        let has = |kind: FindingKind, name: &str| {
+            report
+                .findings
+                .iter()
+                .any(|finding| finding.kind == kind && finding.name == name)
+        };
+
+        assert!(has(FindingKind::MissingConnection, "users"));
+        assert!(has(FindingKind::InvalidModelSql, "broken"));
+        assert!(has(FindingKind::UnknownTable, "dangling"));
+        assert!(has(FindingKind::MissingQuery, "sales"));
+        assert!(has(FindingKind::OrphanNode, "leftover"));
+        assert!(
+            report
+                .findings
+                .iter()
+                .any(|finding| finding.severity == Severity::Error)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reload_endpoint() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        let models_dir = test.directory().join("models");
+        std::fs::create_dir_all(&models_dir)?;
+        let model_file = models_dir.join("orders.yml");
+        std::fs::write(&model_file, "sql: SELECT 1 AS id\n")?;
+
+        let summary: ReloadSummary = server.post("/project/reload").await.json();
+        assert!(
+            summary
+                .changes
+                .changed
+                .contains(&"models/orders.yml".to_string())
+        );
+        assert_eq!(summary.graph.added, vec!["orders".to_string()]);
+        assert!(test.config().await.models.contains_key("orders"));
+        assert!(test.graph().await.get_node("orders").is_some());
+
+        // A broken edit keeps the previous version and shows up in validation.
+        std::fs::write(&model_file, "sql: [\n")?;
+        let summary: ReloadSummary = server.post("/project/reload").await.json();
+        assert_eq!(summary.changes.errors.len(), 1);
+        assert_eq!(summary.changes.errors[0].path, "models/orders.yml");
+        assert_eq!(test.config().await.models["orders"].sql, "SELECT 1 AS id");
+
+        let invalid_file = |report: &ValidationReport| {
+            report
+                .findings
+                .iter()
+                .any(|finding| finding.kind == FindingKind::InvalidConfigFile)
+        };
+        let report: ValidationReport = server.get("/project/validate").await.json();
+        assert!(invalid_file(&report));
+
+        std::fs::remove_file(&model_file)?;
+        let summary: ReloadSummary = server.post("/project/reload").await.json();
+        assert_eq!(
+            summary.changes.removed,
+            vec!["models/orders.yml".to_string()]
+        );
+        assert_eq!(summary.graph.removed, vec!["orders".to_string()]);
+        assert!(!test.config().await.models.contains_key("orders"));
+
+        let report: ValidationReport = server.get("/project/validate").await.json();
+        assert!(!invalid_file(&report));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rotate_key_endpoint() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let key_path = test.directory().join(".secret.key");
+        let old_key = std::fs::read(&key_path)?;
+
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.project.clone();
+            project_config.connections.insert(
+                "postgres".to_string(),
+                ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+                    host: "localhost".to_string(),
+                    port: 5432,
+                    database: "app".to_string(),
+                    username: "app".to_string(),
+                    password: SecretField::encrypt("postgres_password", &key_path)?,
+                }),
+            );
+            config.add_project_setting(&project_config)?.save()?;
+        }
+
+        let response = server.post("/project/rotate-key").await;
+        response.assert_status_ok();
+
+        let body: RotateKeyResponse = response.json();
+        assert_eq!(body.rotated, 1);
+        assert_ne!(std::fs::read(&key_path)?, old_key);
+        assert!(body.backup.starts_with(".secret.key."));
+        assert_eq!(std::fs::read(test.directory().join(&body.backup))?, old_key);
+
+        let config = test.config().await;
+        match &config.project.connections["postgres"] {
+            ConnectionConfig::PostgreSql(postgres) => {
+                assert_eq!(postgres.password.plaintext()?, "postgres_password");
+            }
+            _ => panic!("Expected PostgreSQL connection"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_settings_encrypts_secrets() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        {
+            let mut config = test.config().await;
+            let mut project_config = config.project.clone();
+            project_config.connections.insert(
+                "mysql".to_string(),
+                ConnectionConfig::MySql(RemoteDatabaseConfig {
+                    host: "localhost".to_string(),
+                    port: 3306,
+                    database: "app".to_string(),
+                    username: "app".to_string(),
+                    password: SecretField::PlainText {
+                        value: "mysql_password".to_string(),
+                    },
+                }),
+            );
+            config.add_project_setting(&project_config)?;
+        }
+
+        let settings = ProjectSettings {
+            storage: StorageConfig::S3(S3Config {
+                bucket: "lake".to_string(),
+                region: "us-east-1".to_string(),
+                endpoint_url: None,
+                auth_method: S3AuthMethod::Explicit,
+                access_key_id: Some("access".to_string()),
+                secret_access_key: Some(SecretField::PlainText {
+                    value: "storage_secret".to_string(),
+                }),
+                path_style_access: false,
+            }),
+            database: DatabaseConfig {
+                ty: DatabaseType::Postgresql,
+                path: None,
+                host: Some("localhost".to_string()),
+                port: Some(5432),
+                database: Some("catalog".to_string()),
+                username: Some("duckhub".to_string()),
+                password: Some(SecretField::PlainText {
+                    value: "catalog_password".to_string(),
+                }),
+            },
+        };
+
+        let response = server.put("/project/settings").json(&settings).await;
+        response.assert_status_ok();
+
+        let yaml = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(!yaml.contains("catalog_password"));
+        assert!(!yaml.contains("storage_secret"));
+        assert!(!yaml.contains("mysql_password"));
+        assert!(yaml.contains("encrypted"));
+
+        {
+            let config = test.config().await;
+            assert_eq!(
+                config
+                    .project
+                    .database
+                    .password
+                    .as_ref()
+                    .unwrap()
+                    .plaintext()?,
+                "catalog_password"
+            );
+        }
+
+        // Without a token the secrets are masked, and sending them back keeps them.
+        let response = server.get("/project/settings").await;
+        response.assert_status_ok();
+        assert!(!response.text().contains("catalog_password"));
+        assert!(!response.text().contains("storage_secret"));
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["database"]["password"]["value"], MASKED_SECRET);
+        assert_eq!(
+            body["storage"]["config"]["secret_access_key"]["value"],
+            MASKED_SECRET
+        );
+
+        let round_trip: ProjectSettings = response.json();
+        server
+            .put("/project/settings")
+            .json(&round_trip)
+            .await
+            .assert_status_ok();
+
+        {
+            let config = test.config().await;
+            assert_eq!(
+                config
+                    .project
+                    .database
+                    .password
+                    .as_ref()
+                    .unwrap()
+                    .plaintext()?,
+                "catalog_password"
+            );
+            let StorageConfig::S3(storage) = &config.project.storage else {
+                panic!("Expected S3 storage");
+            };
+            assert_eq!(
+                storage.secret_access_key.as_ref().unwrap().plaintext()?,
+                "storage_secret"
+            );
+        }
+
+        // Callers with a token get them decrypted.
+        let server =
+            test.setup_server(|| routes().layer(Extension(Principal("api-token".to_string()))));
+        let response = server.get("/project/settings").await;
+        response.assert_status_ok();
+        let settings: ProjectSettings = response.json();
+        assert_eq!(
+            settings.database.password,
+            Some(SecretField::PlainText {
+                value: "catalog_password".to_string()
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_settings_rejects_undecryptable_secret() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let settings = json!({
+            "storage": { "type": "local", "config": { "path": "./storage" } },
+            "database": {
+                "type": "postgresql",
+                "host": "localhost",
+                "port": 5432,
+                "database": "catalog",
+                "username": "duckhub",
+                "password": { "type": "encrypted", "value": "bm90IHJlYWxseSBlbmNyeXB0ZWQ=" }
+            }
+        });
+
+        let response = server.put("/project/settings").json(&settings).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_settings_with_env_overlay() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        std::fs::write(
+            test.directory().join("project.staging.yml"),
+            "database:\n  path: ./staging.db\n",
+        )?;
+        {
+            let mut config = test.config().await;
+            config.env = Some("staging".to_string());
+            config.load()?;
+        }
+
+        let database_path = |settings: ProjectSettings| settings.database.path.unwrap();
+        let response = server.get("/project/settings").await;
+        response.assert_status_ok();
+        assert!(database_path(response.json()).ends_with("database.db"));
+
+        let response = server
+            .get("/project/settings")
+            .add_query_param("effective", true)
+            .await;
+        response.assert_status_ok();
+        assert!(database_path(response.json()).ends_with("staging.db"));
+
+        // The per-section endpoints read from the same place as the settings.
+        let response = server.get("/project/database").await;
+        response.assert_status_ok();
+        let database: DatabaseConfig = response.json();
+        assert!(database.path.unwrap().ends_with("database.db"));
+        let response = server
+            .get("/project/database")
+            .add_query_param("effective", true)
+            .await;
+        response.assert_status_ok();
+        let database: DatabaseConfig = response.json();
+        assert!(database.path.unwrap().ends_with("staging.db"));
+
+        let storage_path = test.directory().join("new_storage");
+        server
+            .put("/project/storage")
+            .json(&json!({
+                "type": "local",
+                "config": { "path": storage_path.to_string_lossy() }
+            }))
+            .await
+            .assert_status_ok();
+
+        let yaml = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(yaml.contains("new_storage"));
+        assert!(!yaml.contains("staging.db"));
+        let config = test.config().await;
+        assert!(
+            config
+                .project
+                .database
+                .path
+                .as_ref()
+                .unwrap()
+                .ends_with("staging.db")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_storage_switches_ducklake() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let catalog_path = test.directory().join("catalog.db");
+        let response = server
+            .put("/project/database")
+            .json(&json!({
+                "type": "sqlite",
+                "path": catalog_path.to_string_lossy()
+            }))
+            .await;
+        response.assert_status_ok();
+
+        let storage_path = test.directory().join("new_storage");
+        let response = server
+            .put("/project/storage")
+            .json(&json!({
+                "type": "local",
+                "config": { "path": storage_path.to_string_lossy() }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        let response = server.get("/project/storage").await;
+        response.assert_status_ok();
+        let storage: StorageConfig = response.json();
+        assert_eq!(
+            storage,
+            StorageConfig::LocalFile {
+                path: storage_path.to_string_lossy().to_string()
+            }
+        );
+
+        let config = test.config().await;
+        let ducklake = DuckLake::from_config(&config).await?;
+        ducklake.create_table_from_query("switched", "SELECT 1 AS id")?;
+
+        assert!(storage_path.exists());
+        assert!(catalog_path.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_storage_invalidates_cached_ducklake() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| routes().merge(crate::api::query::routes()));
+
+        let query_request = json!({ "sql": "SELECT 1 AS id" });
+        server
+            .post("/query")
+            .json(&query_request)
+            .await
+            .assert_status_ok();
+        assert_eq!(test.ducklakes().builds(), 1);
+
+        let storage_path = test.directory().join("other_storage");
+        let response = server
+            .put("/project/storage")
+            .json(&json!({
+                "type": "local",
+                "config": { "path": storage_path.to_string_lossy() }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        server
+            .post("/query")
+            .json(&query_request)
+            .await
+            .assert_status_ok();
+        assert_eq!(test.ducklakes().builds(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_catalog_degrades_and_recovers() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(|| routes().merge(crate::api::query::routes()));
+
+        let reachable = {
+            let mut config = test.config().await;
+            let reachable = config.project.database.clone();
+            config.project.database = DatabaseConfig {
+                ty: DatabaseType::Postgresql,
+                path: None,
+                host: Some("127.0.0.1".to_string()),
+                port: Some(1),
+                database: Some("catalog".to_string()),
+                username: Some("duckhub".to_string()),
+                password: Some(SecretField::PlainText {
+                    value: "secret".to_string(),
+                }),
+            };
+            reachable
+        };
+
+        let query_request = json!({ "sql": "SELECT 1 AS id" });
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "CATALOG_UNAVAILABLE");
+        assert_eq!(body["details"]["catalog"], "postgresql");
+        assert_eq!(body["details"]["host"], "127.0.0.1:1");
+
+        // The failure is remembered rather than retried on every request.
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(test.ducklakes().builds(), 1);
+
+        // Settings stay reachable so the catalog can be fixed.
+        server.get("/project/settings").await.assert_status_ok();
+
+        let response = server.post("/project/reconnect").await;
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(test.ducklakes().builds(), 2);
+        assert!(test.ducklakes().failure().await.is_some());
+
+        test.config().await.project.database = reachable;
+        let response = server.post("/project/reconnect").await;
+        response.assert_status_ok();
+        let body: ReconnectResponse = response.json();
+        assert!(body.connected);
+        assert!(test.ducklakes().failure().await.is_none());
+
+        server
+            .post("/query")
+            .json(&query_request)
+            .await
+            .assert_status_ok();
+        assert_eq!(test.ducklakes().builds(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_storage_validation() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .put("/project/storage")
+            .json(&json!({
+                "type": "s3",
+                "config": {
+                    "bucket": "lake",
+                    "region": "us-east-1",
+                    "endpoint_url": null,
+                    "auth_method": "explicit",
+                    "access_key_id": null,
+                    "secret_access_key": null,
+                    "path_style_access": false
+                }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+
+        let config = test.config().await;
+        assert!(matches!(
+            config.project.storage,
+            StorageConfig::LocalFile { .. }
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_database_validation() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .put("/project/database")
+            .json(&json!({
+                "type": "postgresql",
+                "host": "localhost",
+                "port": 5432,
+                "database": "catalog",
+                "username": "duckhub"
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+
+        let response = server
+            .put("/project/database")
+            .json(&json!({
+                "type": "postgresql",
+                "host": "localhost",
+                "port": 5432,
+                "database": "catalog",
+                "username": "duckhub",
+                "password": { "type": "plain", "value": "catalog_password" }
+            }))
+            .await;
+        response.assert_status_ok();
+
+        let yaml = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        assert!(!yaml.contains("catalog_password"));
+
+        let response = server.get("/project/database").await;
+        response.assert_status_ok();
+        let database: DatabaseConfig = response.json();
+        assert_eq!(database.ty, DatabaseType::Postgresql);
+        assert_eq!(
+            database.password,
+            Some(SecretField::PlainText {
+                value: MASKED_SECRET.to_string()
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_import_endpoints() -> Result<()> {
+        let source = TestManager::new();
+        {
+            let mut config = source.config().await;
+            let model_config = ModelConfig {
+                description: None,
+                sql: "SELECT 1 AS id".to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("ones", &model_config)?.save()?;
+        }
+        let response = source.setup_server(routes).get("/project/export").await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-type"), "application/gzip");
+        let archive = response.as_bytes().clone();
+
+        let target = TestManager::new();
+        let server = target.setup_server(routes);
+        let response = server.post("/project/import").bytes(archive.clone()).await;
+        response.assert_status_ok();
+        let summary: ImportSummary = response.json();
+        assert_eq!(summary.models, vec!["ones"]);
+        assert!(target.config().await.models.contains_key("ones"));
+        assert!(target.graph().await.has_node("ones"));
+
+        let response = server.post("/project/import").bytes(archive.clone()).await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "IMPORT_CONFLICT");
+        assert_eq!(body["details"]["collisions"], json!(["models/ones"]));
+
+        server
+            .post("/project/import")
+            .add_query_param("overwrite", true)
+            .bytes(archive)
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post("/project/import")
+            .bytes("not a bundle".into())
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_BUNDLE");
+
+        Ok(())
+    }
+}
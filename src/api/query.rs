@@ -1,37 +1,148 @@
 use crate::{
-    api::Error,
+    api::{
+        Error, ErrorCode, LabelFilter, MaskingParams, QueryLimiter, RenameRequest,
+        audit::record_changes,
+        auth::Principal,
+        cache::{CacheStatus, cached},
+        ensure_valid_name,
+    },
     core::{
+        audit::{AuditChange, AuditLog},
         config::{Config, query::QueryConfig},
-        ducklake::DuckLake,
+        ducklake::{
+            DuckLake, DuckLakeCache, ExportFormat, PoolExhausted, QueryTimeout, Snapshot,
+            TypedResult,
+        },
+        explain::{QueryPlan, explain},
+        graph::Graph,
+        masking::{MaskedReference, Masks, mask_values, masked_sql, query_masks},
+        query_cache::{CacheKey, CachedResult, QueryCache},
+        query_history::{QueryHistory, QueryHistoryEntry},
+        query_params::{ParamError, bind_query},
+        read_only::{ReadOnlyError, check_read_only},
     },
 };
 use anyhow::Result;
 use axum::{
     Extension, Router,
-    extract::Path as AxumPath,
-    response::Json,
+    body::Body,
+    extract::{Path as AxumPath, Query},
+    http::header,
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
 };
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
+use tokio_util::io::ReaderStream;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+const DEFAULT_ROW_LIMIT: usize = 10_000;
+const MAX_ROW_LIMIT: usize = 100_000;
+const DEFAULT_HISTORY_LIMIT: usize = 50;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct QueryRequest {
     pub sql: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(flatten)]
+    pub snapshot: SnapshotParams,
+    // Only honoured when the project sets `server.allow_writes`.
+    #[serde(default)]
+    pub allow_writes: bool,
+}
+
+// Pins a read to a DuckLake snapshot, either by id or by timestamp.
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SnapshotParams {
+    pub snapshot_id: Option<u64>,
+    pub as_of: Option<String>,
+}
+
+impl SnapshotParams {
+    pub fn snapshot(&self) -> Result<Option<Snapshot>, Error> {
+        match (self.snapshot_id, &self.as_of) {
+            (Some(_), Some(_)) => Error::bad_request()
+                .with_message("Specify either 'snapshot_id' or 'as_of', not both")
+                .build(),
+            (Some(snapshot_id), None) => Ok(Some(Snapshot::Version(snapshot_id))),
+            (None, Some(as_of)) => Ok(Some(Snapshot::AsOf(as_of.clone()))),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExplainRequest {
+    pub sql: String,
+    #[serde(flatten)]
+    pub options: ExplainOptions,
+}
+
+#[derive(Default, Deserialize, ToSchema)]
+pub struct ExplainOptions {
+    #[serde(default)]
+    pub analyze: bool,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExportRequest {
+    pub format: ExportFormat,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AdhocExportRequest {
+    pub sql: String,
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+pub struct RunQueryRequest {
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryParams {
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    DEFAULT_HISTORY_LIMIT
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteQueryParams {
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateQueryRequest {
     pub name: String,
     pub config: QueryConfig,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct QueryResult {
-    pub data: std::collections::HashMap<String, Vec<String>>,
+    pub columns: Vec<String>,
+    pub types: Vec<String>,
+    pub data: HashMap<String, Vec<serde_json::Value>>,
     pub row_count: usize,
     pub column_count: usize,
+    pub truncated: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -40,7 +151,25 @@ pub struct QuerySummary {
     pub description: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+// Saved queries are checked like ad-hoc ones but only warned about, since
+// existing projects may already rely on them.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SaveQueryResponse {
+    pub warnings: Vec<String>,
+}
+
+impl SaveQueryResponse {
+    fn new(query: &QueryConfig) -> Self {
+        let sql = default_sql(query).unwrap_or_else(|_| query.sql.clone());
+        let warnings = match check_read_only(&sql) {
+            Ok(()) => vec![],
+            Err(e) => vec![e.to_string()],
+        };
+        Self { warnings }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct QueryListResponse {
     pub queries: HashMap<String, QueryConfig>,
 }
@@ -48,70 +177,576 @@ pub struct QueryListResponse {
 pub fn routes() -> Router {
     Router::new()
         .route("/query", post(run_adhoc_query))
+        .route("/query/export", post(export_adhoc_query))
+        .route("/query/explain", post(explain_adhoc_query))
+        .route("/query/history", get(list_query_history))
+        .route("/query/history/{id}/replay", post(replay_query))
         .route("/queries", get(list_queries).post(create_query))
         .route(
             "/queries/{name}",
             get(get_query).put(update_query).delete(delete_query),
         )
         .route("/queries/{name}/run", post(run_query))
+        .route("/queries/{name}/rename", post(rename_query))
+        .route("/queries/{name}/export", post(export_query))
+        .route("/queries/{name}/explain", post(explain_query))
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    run_adhoc_query,
+    export_adhoc_query,
+    explain_adhoc_query,
+    list_query_history,
+    replay_query,
+    list_queries,
+    create_query,
+    get_query,
+    update_query,
+    delete_query,
+    run_query,
+    rename_query,
+    export_query,
+    explain_query
+))]
+pub struct QueryApi;
+
+#[utoipa::path(
+    post,
+    path = "/query",
+    tag = "queries",
+    params(MaskingParams),
+    request_body = QueryRequest,
+    responses((status = 200, description = "Query result", body = QueryResult))
+)]
 async fn run_adhoc_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    Extension(history): Extension<Arc<QueryHistory>>,
+    principal: Option<Extension<Principal>>,
+    Query(masking): Query<MaskingParams>,
     Json(payload): Json<QueryRequest>,
 ) -> Result<Json<QueryResult>, Error> {
-    let config = config.lock().await;
-    let data = execute_query(&config, &payload.sql).await?;
-    let row_count = if data.is_empty() {
-        0
-    } else {
-        data.values().next().map(|v| v.len()).unwrap_or(0)
+    let masks = {
+        let config = config.lock().await;
+        masking.masks(principal.as_deref(), || {
+            query_masks(&config, &payload.sql).map_err(masked_reference)
+        })?
+    };
+    let result = run_recorded(&config, &ducklakes, &limiter, &history, &payload).await?;
+    Ok(Json(result.mask(&masks)))
+}
+
+async fn run_recorded(
+    config: &Arc<Mutex<Config>>,
+    ducklakes: &DuckLakeCache,
+    limiter: &QueryLimiter,
+    history: &Arc<QueryHistory>,
+    payload: &QueryRequest,
+) -> Result<QueryResult, Error> {
+    let snapshot = payload.snapshot.snapshot()?;
+    let limit = row_limit(payload.limit)?;
+    let (engine, project_dir, writes_allowed) = {
+        let config = config.lock().await;
+        (
+            config.project.engine.clone(),
+            config.project_dir.clone(),
+            config.project.server.allow_writes,
+        )
+    };
+
+    let started_at = chrono::Utc::now();
+    let result = match check_read_only(&payload.sql) {
+        Ok(()) => {
+            let timeout = engine.query_timeout(payload.timeout_secs);
+            let _permit = limiter.acquire(&engine).await?;
+            let ducklake = ducklakes.get(config).await?;
+            execute_query(&ducklake, &payload.sql, limit, snapshot.as_ref(), timeout).await
+        }
+        Err(ReadOnlyError::Write(_)) if payload.allow_writes && writes_allowed => {
+            if snapshot.is_some() {
+                return Error::bad_request()
+                    .with_message("Writes cannot be pinned to a snapshot")
+                    .build();
+            }
+            let timeout = engine.query_timeout(payload.timeout_secs);
+            let _permit = limiter.acquire(&engine).await?;
+            let ducklake = ducklakes.get(config).await?;
+            execute_write(&ducklake, &payload.sql, timeout).await
+        }
+        Err(e) if payload.allow_writes && !writes_allowed => Err(read_only_violation(e)
+            .with_message(
+                "Writes are disabled for this project; set 'server.allow_writes' to enable them",
+            )),
+        Err(e) => Err(read_only_violation(e)),
+    };
+    // Refused queries are kept too, so the history shows what was attempted.
+    let outcome = match &result {
+        Ok(result) => Ok(result.row_count),
+        Err(error) => Err(error.message.clone().unwrap_or_default()),
+    };
+    history.record(
+        &project_dir,
+        QueryHistoryEntry::new(&payload.sql, started_at, outcome),
+    );
+
+    result
+}
+
+#[utoipa::path(
+    get,
+    path = "/query/history",
+    tag = "queries",
+    params(HistoryParams),
+    responses((status = 200, description = "Recent ad-hoc queries, newest first", body = Vec<QueryHistoryEntry>))
+)]
+async fn list_query_history(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(history): Extension<Arc<QueryHistory>>,
+    Query(params): Query<HistoryParams>,
+) -> Result<Json<Vec<QueryHistoryEntry>>, Error> {
+    let project_dir = config.lock().await.project_dir.clone();
+    let entries = history.list(&project_dir, params.limit).await?;
+    Ok(Json(entries))
+}
+
+#[utoipa::path(
+    post,
+    path = "/query/history/{id}/replay",
+    tag = "queries",
+    params(("id" = String, Path, description = "History entry id"), MaskingParams),
+    responses((status = 200, description = "Query result", body = QueryResult))
+)]
+async fn replay_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    Extension(history): Extension<Arc<QueryHistory>>,
+    principal: Option<Extension<Principal>>,
+    AxumPath(id): AxumPath<String>,
+    Query(masking): Query<MaskingParams>,
+) -> Result<Json<QueryResult>, Error> {
+    let project_dir = config.lock().await.project_dir.clone();
+    let Some(entry) = history.get(&project_dir, &id).await? else {
+        return Error::not_found()
+            .with_message(format!("No query history entry '{id}'"))
+            .build();
+    };
+    let masks = {
+        let config = config.lock().await;
+        masking.masks(principal.as_deref(), || {
+            query_masks(&config, &entry.sql).map_err(masked_reference)
+        })?
     };
-    let column_count = data.len();
 
-    Ok(Json(QueryResult {
-        data,
-        row_count,
-        column_count,
-    }))
+    let payload = QueryRequest {
+        sql: entry.sql,
+        limit: None,
+        timeout_secs: None,
+        snapshot: SnapshotParams::default(),
+        allow_writes: false,
+    };
+    let result = run_recorded(&config, &ducklakes, &limiter, &history, &payload).await?;
+    Ok(Json(result.mask(&masks)))
+}
+
+fn row_limit(requested: Option<usize>) -> Result<usize, Error> {
+    match requested {
+        Some(limit) if limit > MAX_ROW_LIMIT => Error::bad_request()
+            .with_message(format!(
+                "A query may return at most {MAX_ROW_LIMIT} rows, but {limit} were requested"
+            ))
+            .with_detail("limit", MAX_ROW_LIMIT)
+            .build(),
+        requested => Ok(requested.unwrap_or(DEFAULT_ROW_LIMIT)),
+    }
 }
 
 pub async fn execute_query(
-    config: &Config,
+    ducklake: &DuckLake,
+    sql: &str,
+    limit: usize,
+    snapshot: Option<&Snapshot>,
+    timeout: Duration,
+) -> Result<QueryResult, Error> {
+    let (result, truncated) = ducklake
+        .query_paged_with_timeout(sql, limit, 0, snapshot, timeout)
+        .await
+        .map_err(query_failed)?;
+
+    Ok(QueryResult::new(result, truncated))
+}
+
+// Writes return no rows; the statement is run as-is rather than wrapped for paging.
+async fn execute_write(
+    ducklake: &DuckLake,
+    sql: &str,
+    timeout: Duration,
+) -> Result<QueryResult, Error> {
+    let sql = sql.to_string();
+    ducklake
+        .run_interruptible(timeout, move |connection| {
+            connection.execute_batch(&sql)?;
+            Ok(())
+        })
+        .await
+        .map_err(query_failed)?;
+
+    Ok(QueryResult::new(TypedResult::default(), false))
+}
+
+impl QueryResult {
+    fn new(result: TypedResult, truncated: bool) -> Self {
+        let row_count = result.row_count();
+        let column_count = result.columns.len();
+
+        Self {
+            columns: result.columns,
+            types: result.types,
+            data: result.data,
+            row_count,
+            column_count,
+            truncated,
+        }
+    }
+
+    fn mask(mut self, masks: &Masks) -> Self {
+        for (column, values) in &mut self.data {
+            mask_values(masks, column, values);
+        }
+        self
+    }
+}
+
+// Timeouts and a busy pool keep their own status; anything else is a bad query.
+pub fn query_failed(e: anyhow::Error) -> Error {
+    if e.is::<QueryTimeout>() || e.is::<PoolExhausted>() {
+        return Error::from(e);
+    }
+    Error::bad_request()
+        .with_code(ErrorCode::QueryFailed)
+        .with_message(format!("{e:#}"))
+}
+
+pub fn masked_reference(e: MaskedReference) -> Error {
+    Error::forbidden()
+        .with_code(ErrorCode::MaskedColumn)
+        .with_message(e.to_string())
+        .with_detail("column", e.0)
+}
+
+// Unparseable SQL is a bad query like any other; the rest is refused outright.
+pub fn read_only_violation(e: ReadOnlyError) -> Error {
+    let error = match &e {
+        ReadOnlyError::Unparseable(_) => {
+            return Error::bad_request()
+                .with_code(ErrorCode::QueryFailed)
+                .with_message(e.to_string());
+        }
+        ReadOnlyError::MultipleStatements(count) => {
+            Error::forbidden().with_detail("statements", *count)
+        }
+        ReadOnlyError::Write(kind) => Error::forbidden().with_detail("statement", kind.clone()),
+    };
+    error
+        .with_code(ErrorCode::ReadOnlyQuery)
+        .with_message(e.to_string())
+}
+
+pub fn param_failed(e: ParamError) -> Error {
+    let error = Error::bad_request()
+        .with_code(ErrorCode::InvalidParameter)
+        .with_message(e.to_string());
+    match e {
+        ParamError::Missing(names) => error.with_detail("missing", names),
+        ParamError::Unknown(names) => error.with_detail("unknown", names),
+        ParamError::Invalid { name, .. } => error.with_detail("parameter", name),
+    }
+}
+
+// Statements that cannot bind, such as EXPLAIN and COPY, run with the defaults
+// inlined as literals.
+pub fn default_sql(query: &QueryConfig) -> Result<String, Error> {
+    bind_query(query, &HashMap::new())
+        .map(|bound| bound.inlined)
+        .map_err(param_failed)
+}
+
+#[utoipa::path(
+    post,
+    path = "/query/explain",
+    tag = "queries",
+    request_body = ExplainRequest,
+    responses((status = 200, description = "Query plan", body = QueryPlan))
+)]
+async fn explain_adhoc_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    Json(payload): Json<ExplainRequest>,
+) -> Result<Json<QueryPlan>, Error> {
+    check_read_only(&payload.sql).map_err(read_only_violation)?;
+    let plan = explain_sql(
+        &config,
+        &ducklakes,
+        &limiter,
+        &payload.sql,
+        &payload.options,
+    )
+    .await?;
+    Ok(Json(plan))
+}
+
+#[utoipa::path(
+    post,
+    path = "/queries/{name}/explain",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name")),
+    request_body = ExplainOptions,
+    responses((status = 200, description = "Query plan", body = QueryPlan))
+)]
+async fn explain_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    AxumPath(name): AxumPath<String>,
+    Json(options): Json<ExplainOptions>,
+) -> Result<Json<QueryPlan>, Error> {
+    let sql = match config.lock().await.queries.get(&name) {
+        Some(query) => default_sql(query)?,
+        None => {
+            return Error::not_found()
+                .with_code(ErrorCode::QueryNotFound)
+                .build();
+        }
+    };
+
+    let plan = explain_sql(&config, &ducklakes, &limiter, &sql, &options).await?;
+    Ok(Json(plan))
+}
+
+// Plain EXPLAIN only plans the query, but EXPLAIN ANALYZE runs it, so it waits for a
+// query slot like any other run. Both are bounded by the query timeout.
+pub async fn explain_sql(
+    config: &Mutex<Config>,
+    ducklakes: &DuckLakeCache,
+    limiter: &QueryLimiter,
+    sql: &str,
+    options: &ExplainOptions,
+) -> Result<QueryPlan, Error> {
+    let engine = config.lock().await.project.engine.clone();
+    let timeout = engine.query_timeout(options.timeout_secs);
+    let _permit = if options.analyze {
+        Some(limiter.acquire(&engine).await?)
+    } else {
+        None
+    };
+    let ducklake = ducklakes.get(config).await?;
+    explain(&ducklake, sql, options.analyze, timeout)
+        .await
+        .map_err(query_failed)
+}
+
+#[utoipa::path(
+    post,
+    path = "/query/export",
+    tag = "queries",
+    params(MaskingParams),
+    request_body = AdhocExportRequest,
+    responses((status = 200, description = "Exported file", content_type = "application/octet-stream"))
+)]
+async fn export_adhoc_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    principal: Option<Extension<Principal>>,
+    Query(masking): Query<MaskingParams>,
+    Json(payload): Json<AdhocExportRequest>,
+) -> Result<Response, Error> {
+    check_read_only(&payload.sql).map_err(read_only_violation)?;
+    let (masks, engine) = {
+        let config = config.lock().await;
+        let masks = masking.masks(principal.as_deref(), || {
+            query_masks(&config, &payload.sql).map_err(masked_reference)
+        })?;
+        (masks, config.project.engine.clone())
+    };
+
+    let timeout = engine.query_timeout(payload.timeout_secs);
+    let _permit = limiter.acquire(&engine).await?;
+    let ducklake = ducklakes.get(&config).await?;
+    export_response(
+        &ducklake,
+        &payload.sql,
+        &masks,
+        payload.format,
+        "query",
+        timeout,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/queries/{name}/export",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name"), MaskingParams),
+    request_body = ExportRequest,
+    responses((status = 200, description = "Exported file", content_type = "application/octet-stream"))
+)]
+async fn export_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    principal: Option<Extension<Principal>>,
+    AxumPath(name): AxumPath<String>,
+    Query(masking): Query<MaskingParams>,
+    Json(payload): Json<ExportRequest>,
+) -> Result<Response, Error> {
+    let (sql, masks, engine) = {
+        let config = config.lock().await;
+        let sql = match config.queries.get(&name) {
+            Some(query) => default_sql(query)?,
+            None => {
+                return Error::not_found()
+                    .with_code(ErrorCode::QueryNotFound)
+                    .build();
+            }
+        };
+        let masks = masking.masks(principal.as_deref(), || {
+            query_masks(&config, &sql).map_err(masked_reference)
+        })?;
+        (sql, masks, config.project.engine.clone())
+    };
+    check_read_only(&sql).map_err(read_only_violation)?;
+
+    let timeout = engine.query_timeout(None);
+    let _permit = limiter.acquire(&engine).await?;
+    let ducklake = ducklakes.get(&config).await?;
+    export_response(
+        &ducklake,
+        &sql,
+        &masks,
+        payload.format,
+        &name.replace('/', "_"),
+        timeout,
+    )
+    .await
+}
+
+// Masks are applied by DuckDB as it writes the file, so they hold for every format.
+async fn export_response(
+    ducklake: &DuckLake,
     sql: &str,
-) -> Result<std::collections::HashMap<String, Vec<String>>, Error> {
-    let ducklake = DuckLake::from_config(config).await?;
-    let results = ducklake
-        .query_with_column_names(sql)
-        .map_err(|e| Error::bad_request().with_message(format!("{e}")))?;
+    masks: &Masks,
+    format: ExportFormat,
+    filename: &str,
+    timeout: Duration,
+) -> Result<Response, Error> {
+    let sql = if masks.is_empty() {
+        sql.to_string()
+    } else {
+        let columns: Vec<String> = ducklake
+            .query_schema(&format!("({})", sql.trim().trim_end_matches(';')))
+            .map_err(query_failed)?
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        masked_sql(masks, &columns, sql)
+    };
 
-    Ok(results)
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir
+        .path()
+        .join(format!("export.{}", format.extension()));
+    ducklake
+        .export_query_with_timeout(&sql, format, &path, timeout)
+        .await
+        .map_err(query_failed)?;
+
+    // The file is streamed from disk, so the temporary directory has to live
+    // as long as the response body.
+    let file = tokio::fs::File::open(&path).await?;
+    let stream = ReaderStream::new(file).map(move |chunk| {
+        let _keep_alive = &temp_dir;
+        chunk
+    });
+
+    let content_type = match format {
+        ExportFormat::Csv => "text/csv",
+        ExportFormat::Parquet => "application/vnd.apache.parquet",
+        ExportFormat::Json => "application/x-ndjson",
+    };
+    let content_disposition = format!("attachment; filename=\"{filename}.{}\"", format.extension());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
 }
 
+#[utoipa::path(
+    get,
+    path = "/queries",
+    tag = "queries",
+    params(LabelFilter),
+    responses((status = 200, description = "Saved queries by name", body = QueryListResponse))
+)]
 async fn list_queries(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Query(filter): Query<LabelFilter>,
 ) -> Result<Json<QueryListResponse>, Error> {
     let config = config.lock().await;
-    Ok(Json(QueryListResponse {
-        queries: config.queries.clone(),
-    }))
+    let queries = config
+        .queries
+        .iter()
+        .filter(|(_, query)| filter.matches(&query.tags, query.group.as_deref()))
+        .map(|(name, query)| (name.clone(), query.clone()))
+        .collect();
+    Ok(Json(QueryListResponse { queries }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/queries",
+    tag = "queries",
+    request_body = CreateQueryRequest,
+    responses((status = 200, description = "Query created", body = SaveQueryResponse))
+)]
 async fn create_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     Json(query): Json<CreateQueryRequest>,
-) -> Result<(), Error> {
+) -> Result<Json<SaveQueryResponse>, Error> {
+    ensure_valid_name(&query.name)?;
     let mut config = config.lock().await;
     if config.queries.contains_key(&query.name) {
-        return Error::conflict().build();
+        return Error::conflict()
+            .with_code(ErrorCode::QueryAlreadyExists)
+            .build();
     }
 
     let query_file = config.upsert_query(&query.name, &query.config)?;
     query_file.save()?;
+    let change = AuditChange::new("queries", &query.name, None, Some(&query.config));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
-    Ok(())
+    Ok(Json(SaveQueryResponse::new(&query.config)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/queries/{name}",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name")),
+    responses((status = 200, description = "Query config", body = QueryConfig))
+)]
 async fn get_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
     AxumPath(name): AxumPath<String>,
@@ -119,70 +754,247 @@ async fn get_query(
     let config = config.lock().await;
     match config.queries.get(&name) {
         Some(query) => Ok(Json(query.clone())),
-        None => Error::not_found().build(),
+        None => Error::not_found()
+            .with_code(ErrorCode::QueryNotFound)
+            .build(),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/queries/{name}",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name")),
+    request_body = QueryConfig,
+    responses((status = 200, description = "Query updated", body = SaveQueryResponse))
+)]
 async fn update_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     AxumPath(name): AxumPath<String>,
     Json(query): Json<QueryConfig>,
-) -> Result<(), Error> {
+) -> Result<Json<SaveQueryResponse>, Error> {
     let mut config = config.lock().await;
-    if !config.queries.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.queries.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::QueryNotFound)
+            .build();
     };
 
     let query_file = config.upsert_query(&name, &query)?;
     query_file.save()?;
+    let change = AuditChange::new("queries", &name, Some(&before), Some(&query));
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
-    Ok(())
+    Ok(Json(SaveQueryResponse::new(&query)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/queries/{name}",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name"), DeleteQueryParams),
+    responses((status = 200, description = "Query deleted"))
+)]
 async fn delete_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     AxumPath(name): AxumPath<String>,
+    Query(params): Query<DeleteQueryParams>,
 ) -> Result<(), Error> {
     let mut config = config.lock().await;
-    if !config.queries.contains_key(&name) {
-        return Error::not_found().build();
+    let Some(before) = config.queries.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::QueryNotFound)
+            .build();
+    };
+
+    let dependents = dependent_dashboards(&config, &name);
+    if !dependents.is_empty() {
+        if !params.force {
+            return Error::conflict()
+                .with_code(ErrorCode::QueryInUse)
+                .with_message(format!(
+                    "Query '{name}' is used by dashboards: {}",
+                    dependents.join(", ")
+                ))
+                .with_detail("dashboards", dependents)
+                .build();
+        }
+
+        for dashboard in &dependents {
+            let removed = config.dashboards[dashboard].clone();
+            config.delete_dashboard(dashboard)?.save()?;
+            let change = AuditChange::new("dashboards", dashboard, Some(&removed), None);
+            record_changes(&audit, &config, principal.as_deref(), [change]);
+        }
     }
 
     let query_file = config.delete_query(&name)?;
     query_file.save()?;
+    let change = AuditChange::new("queries", &name, Some(&before), None);
+    record_changes(&audit, &config, principal.as_deref(), [change]);
 
     Ok(())
 }
 
-async fn run_query(
+#[utoipa::path(
+    post,
+    path = "/queries/{name}/rename",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name")),
+    request_body = RenameRequest,
+    responses((status = 200, description = "Query renamed"))
+)]
+async fn rename_query(
     Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(audit): Extension<Arc<AuditLog>>,
+    principal: Option<Extension<Principal>>,
     AxumPath(name): AxumPath<String>,
-) -> Result<Json<QueryResult>, Error> {
-    let config_guard = config.lock().await;
-    let sql = match config_guard.queries.get(&name) {
-        Some(query) => query.sql.clone(),
-        None => return Error::not_found().build(),
+    Json(request): Json<RenameRequest>,
+) -> Result<(), Error> {
+    ensure_valid_name(&request.new_name)?;
+    let mut config = config.lock().await;
+    let Some(query) = config.queries.get(&name).cloned() else {
+        return Error::not_found()
+            .with_code(ErrorCode::QueryNotFound)
+            .build();
     };
+    if config.queries.contains_key(&request.new_name) {
+        return Error::conflict()
+            .with_code(ErrorCode::QueryAlreadyExists)
+            .build();
+    }
 
-    let data = execute_query(&config_guard, &sql).await?;
-    let row_count = if data.is_empty() {
-        0
-    } else {
-        data.values().next().map(|v| v.len()).unwrap_or(0)
-    };
-    let column_count = data.len();
+    let query_file = config.rename_query(&name, &request.new_name)?;
+    query_file.save()?;
+    let changes = AuditChange::renamed("queries", &name, &request.new_name, &query);
+    record_changes(&audit, &config, principal.as_deref(), changes);
+
+    for dashboard_name in dependent_dashboards(&config, &name) {
+        let before = config.dashboards[&dashboard_name].clone();
+        let mut dashboard = before.clone();
+        for panel in dashboard
+            .panels
+            .iter_mut()
+            .filter(|panel| panel.query == name)
+        {
+            panel.query = request.new_name.clone();
+        }
+        config
+            .upsert_dashboard(&dashboard_name, &dashboard)?
+            .save()?;
+        let change = AuditChange::new(
+            "dashboards",
+            &dashboard_name,
+            Some(&before),
+            Some(&dashboard),
+        );
+        record_changes(&audit, &config, principal.as_deref(), [change]);
+    }
+
+    Ok(())
+}
+
+fn dependent_dashboards(config: &Config, query: &str) -> Vec<String> {
+    let mut dashboards: Vec<String> = config
+        .dashboards
+        .iter()
+        .filter(|(_, dashboard)| dashboard.queries().any(|q| q == query))
+        .map(|(name, _)| name.clone())
+        .collect();
+    dashboards.sort();
+    dashboards
+}
 
-    Ok(Json(QueryResult {
-        data,
-        row_count,
-        column_count,
-    }))
+#[utoipa::path(
+    post,
+    path = "/queries/{name}/run",
+    tag = "queries",
+    params(("name" = String, Path, description = "Query name"), SnapshotParams, MaskingParams),
+    request_body = Option<RunQueryRequest>,
+    responses((status = 200, description = "Query result", body = QueryResult, headers(("x-cache" = String, description = "`HIT` or `MISS`"))))
+)]
+async fn run_query(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Extension(query_cache): Extension<Arc<QueryCache>>,
+    Extension(limiter): Extension<Arc<QueryLimiter>>,
+    principal: Option<Extension<Principal>>,
+    AxumPath(name): AxumPath<String>,
+    Query(params): Query<SnapshotParams>,
+    Query(masking): Query<MaskingParams>,
+    payload: Option<Json<RunQueryRequest>>,
+) -> Result<(CacheStatus, Json<QueryResult>), Error> {
+    let snapshot = params.snapshot()?;
+    let Json(payload) = payload.unwrap_or_default();
+    let (bound, masks, engine, cache_config) = {
+        let config = config.lock().await;
+        let bound = match config.queries.get(&name) {
+            Some(query) => bind_query(query, &payload.params).map_err(param_failed)?,
+            None => {
+                return Error::not_found()
+                    .with_code(ErrorCode::QueryNotFound)
+                    .build();
+            }
+        };
+        let masks = masking.masks(principal.as_deref(), || {
+            query_masks(&config, &bound.inlined).map_err(masked_reference)
+        })?;
+        (
+            bound,
+            masks,
+            config.project.engine.clone(),
+            config.project.cache.clone(),
+        )
+    };
+    let key = CacheKey::new(
+        &*graph.lock().await,
+        &name,
+        &bound.inlined,
+        Some(DEFAULT_ROW_LIMIT),
+        snapshot.as_ref(),
+    );
+
+    let (result, status) = cached(&query_cache, &cache_config, key, async {
+        let _permit = limiter.acquire(&engine).await?;
+        let ducklake = ducklakes.get(&config).await?;
+        let timeout = engine.query_timeout(None);
+        let (result, truncated) = ducklake
+            .query_bound_paged_with_timeout(
+                &bound,
+                DEFAULT_ROW_LIMIT,
+                0,
+                snapshot.as_ref(),
+                timeout,
+            )
+            .await
+            .map_err(query_failed)?;
+        Ok(CachedResult { result, truncated })
+    })
+    .await?;
+
+    Ok((
+        status,
+        Json(QueryResult::new(result.result.clone(), result.truncated).mask(&masks)),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{api::StatusCode, test_helpers::TestManager};
+    use crate::{
+        api::StatusCode,
+        core::config::{
+            dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+            project::EngineConfig,
+            query::{ParamDef, ParamType},
+        },
+        test_helpers::TestManager,
+    };
     use anyhow::Result;
     use serde_json::json;
 
@@ -226,6 +1038,9 @@ mod tests {
         let original_query = QueryConfig {
             description: Some("Original description".to_string()),
             sql: "SELECT * FROM users".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         {
@@ -266,6 +1081,9 @@ mod tests {
         let query_config = QueryConfig {
             description: Some("This will be deleted".to_string()),
             sql: "SELECT * FROM test_table".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         {
@@ -286,6 +1104,90 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_delete_query_referenced_by_dashboard() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_query_with_dashboards(&test).await?;
+
+        let response = server.delete("/queries/shared_query").await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_IN_USE");
+        assert_eq!(
+            body["details"]["dashboards"],
+            json!(["dashboard_a", "dashboard_b"])
+        );
+
+        server.get("/queries/shared_query").await.assert_status_ok();
+        assert_eq!(test.config().await.dashboards.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_query_force_cascades() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_query_with_dashboards(&test).await?;
+
+        let response = server
+            .delete("/queries/shared_query")
+            .add_query_param("force", "true")
+            .await;
+        response.assert_status_ok();
+
+        server
+            .get("/queries/shared_query")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let config = test.config().await;
+        assert!(config.dashboards.is_empty());
+        assert!(
+            !config
+                .project_dir
+                .join("dashboards/dashboard_a.yml")
+                .exists()
+        );
+
+        Ok(())
+    }
+
+    async fn save_query_with_dashboards(test: &TestManager) -> Result<()> {
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT 1 AS x, 2 AS y".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+        let dashboard_config = DashboardConfig {
+            description: None,
+            panels: vec![PanelConfig::new(
+                "shared_query",
+                ChartConfig {
+                    chart_type: ChartType::Line,
+                    x_column: "x".to_string(),
+                    y_column: "y".to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
+        };
+
+        let mut config = test.config().await;
+        config.upsert_query("shared_query", &query_config)?.save()?;
+        config
+            .upsert_dashboard("dashboard_a", &dashboard_config)?
+            .save()?;
+        config
+            .upsert_dashboard("dashboard_b", &dashboard_config)?
+            .save()?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_list_queries() -> Result<()> {
         let test = TestManager::new();
@@ -294,11 +1196,17 @@ mod tests {
         let query1 = QueryConfig {
             description: Some("First description".to_string()),
             sql: "SELECT * FROM table1".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         let query2 = QueryConfig {
             description: None,
             sql: "SELECT * FROM table2".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         {
@@ -345,97 +1253,884 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_run_query() -> Result<()> {
+    async fn test_adhoc_query_history_and_replay() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
 
-        let query_config = QueryConfig {
-            description: Some("A test query".to_string()),
-            sql: "SELECT 42 as answer, 'test' as label".to_string(),
-        };
-
-        {
-            let mut config = test.config().await;
-            let query_file = config.upsert_query("saved_query", &query_config)?;
-            query_file.save()?;
+        for sql in ["SELECT 1 AS id", "SELECT * FROM range(3)", "SELEC 1"] {
+            server.post("/query").json(&json!({ "sql": sql })).await;
         }
 
-        let response = server.post("/queries/saved_query/run").await;
-        response.assert_status_ok();
-
-        let query_response: QueryResult = response.json();
-        assert_eq!(query_response.column_count, 2);
-        assert!(query_response.row_count > 0);
+        let entries: Vec<QueryHistoryEntry> = server.get("/query/history").await.json();
+        let sqls: Vec<&str> = entries.iter().map(|entry| entry.sql.as_str()).collect();
+        assert_eq!(
+            sqls,
+            vec!["SELEC 1", "SELECT * FROM range(3)", "SELECT 1 AS id"]
+        );
+        assert!(!entries[0].success);
+        assert!(entries[0].error.is_some());
+        assert_eq!(entries[1].row_count, Some(3));
+
+        let limited: Vec<QueryHistoryEntry> = server
+            .get("/query/history")
+            .add_query_param("limit", 1)
+            .await
+            .json();
+        assert_eq!(limited.len(), 1);
+
+        let replayed: QueryResult = server
+            .post(&format!("/query/history/{}/replay", entries[1].id))
+            .await
+            .json();
+        assert_eq!(replayed.row_count, 3);
+
+        let entries: Vec<QueryHistoryEntry> = server.get("/query/history").await.json();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].sql, "SELECT * FROM range(3)");
+
+        server
+            .post("/query/history/missing/replay")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_create_query_conflict() -> Result<()> {
+    async fn test_adhoc_query_rejects_writes() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
 
-        let existing_query = QueryConfig {
-            description: None,
-            sql: "SELECT 1".to_string(),
-        };
+        for (sql, kind) in [
+            ("CREATE TABLE t AS SELECT 1 AS id", "CREATE"),
+            ("DROP TABLE t", "DROP"),
+            ("INSERT INTO t VALUES (1)", "INSERT"),
+            ("UPDATE t SET id = 2", "UPDATE"),
+            ("DELETE FROM t", "DELETE"),
+            ("PRAGMA database_list", "PRAGMA"),
+            ("ATTACH 'other.db' AS other", "ATTACH"),
+            ("COPY (SELECT 1) TO 'out.csv'", "COPY"),
+        ] {
+            let response = server.post("/query").json(&json!({ "sql": sql })).await;
+            response.assert_status(StatusCode::FORBIDDEN);
+            let body: serde_json::Value = response.json();
+            assert_eq!(body["code"], "READ_ONLY_QUERY", "{sql}");
+            assert_eq!(body["details"]["statement"], kind, "{sql}");
+        }
 
-        {
-            let mut config = test.config().await;
-            let query_file = config.upsert_query("existing_query", &existing_query)?;
-            query_file.save()?;
+        for sql in [
+            "SELECT 1 AS id",
+            "WITH t AS (SELECT 1 AS id) SELECT * FROM t",
+            "DESCRIBE SELECT 1 AS id",
+            "SHOW TABLES",
+            "EXPLAIN SELECT 1 AS id",
+        ] {
+            // Not all of these can be paged, so only the check is asserted here.
+            let response = server.post("/query").json(&json!({ "sql": sql })).await;
+            assert_ne!(response.status_code(), StatusCode::FORBIDDEN, "{sql}");
         }
 
-        let duplicate_query = json!({
-            "name": "existing_query",
-            "config": {
-                "description": "This should conflict",
-                "sql": "SELECT 2"
-            }
-        });
+        let response = server
+            .post("/query/export")
+            .json(&json!({ "sql": "COPY (SELECT 1) TO 'out.csv'", "format": "csv" }))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
 
-        let response = server.post("/queries").json(&duplicate_query).await;
-        response.assert_status(StatusCode::CONFLICT);
+        let entries: Vec<QueryHistoryEntry> = server.get("/query/history").await.json();
+        assert!(
+            entries
+                .iter()
+                .any(|entry| entry.sql == "DROP TABLE t" && !entry.success)
+        );
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_update_nonexistent_query() -> Result<()> {
+    async fn test_adhoc_query_rejects_multiple_statements() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
 
-        let updated_config = json!({
-            "description": "This doesn't exist",
-            "sql": "SELECT * FROM nowhere"
-        });
+        for sql in [
+            "SELECT 1; SELECT 2",
+            "SELECT 1) AS paged; DROP TABLE users; SELECT * FROM (SELECT 1",
+        ] {
+            let response = server.post("/query").json(&json!({ "sql": sql })).await;
+            response.assert_status(StatusCode::FORBIDDEN);
+            let body: serde_json::Value = response.json();
+            assert_eq!(body["code"], "READ_ONLY_QUERY");
+            assert!(body["details"]["statements"].as_u64().unwrap() > 1);
+        }
 
-        let response = server
-            .put("/queries/nonexistent_query")
-            .json(&updated_config)
-            .await;
-        response.assert_status(StatusCode::NOT_FOUND);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_allow_writes() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        let create = json!({ "sql": "CREATE TABLE t AS SELECT 1 AS id", "allow_writes": true });
+
+        // The request alone is not enough.
+        let response = server.post("/query").json(&create).await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        let body: serde_json::Value = response.json();
+        assert!(
+            body["message"]
+                .as_str()
+                .unwrap()
+                .contains("server.allow_writes")
+        );
+
+        test.config().await.project.server.allow_writes = true;
+
+        // Nor is the setting.
+        server
+            .post("/query")
+            .json(&json!({ "sql": "CREATE TABLE t AS SELECT 1 AS id" }))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        let response = server.post("/query").json(&create).await;
+        response.assert_status_ok();
+        let result: QueryResult = response.json();
+        assert_eq!(result.row_count, 0);
+
+        let result: QueryResult = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT id FROM t" }))
+            .await
+            .json();
+        assert_eq!(result.data["id"], json!([1]));
+
+        // One statement at a time still applies.
+        server
+            .post("/query")
+            .json(&json!({ "sql": "DROP TABLE t; DROP TABLE u", "allow_writes": true }))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_delete_nonexistent_query() -> Result<()> {
+    async fn test_save_query_warns_on_writes() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
 
-        let response = server.delete("/queries/nonexistent_query").await;
-        response.assert_status(StatusCode::NOT_FOUND);
+        let response: SaveQueryResponse = server
+            .post("/queries")
+            .json(&json!({ "name": "cleanup", "config": { "sql": "DELETE FROM users" } }))
+            .await
+            .json();
+        assert_eq!(response.warnings.len(), 1);
+        assert!(response.warnings[0].contains("DELETE"));
+        server.get("/queries/cleanup").await.assert_status_ok();
+
+        let response: SaveQueryResponse = server
+            .put("/queries/cleanup")
+            .json(&json!({ "sql": "SELECT * FROM users WHERE id = {{id}}" }))
+            .await
+            .json();
+        assert!(response.warnings.is_empty());
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_run_nonexistent_query() -> Result<()> {
+    async fn test_adhoc_query_reuses_ducklake() -> Result<()> {
         let test = TestManager::new();
         let server = test.setup_server(routes);
 
-        let response = server.post("/queries/nonexistent_query/run").await;
+        let query_request = json!({ "sql": "SELECT 1 AS id" });
+
+        server
+            .post("/query")
+            .json(&query_request)
+            .await
+            .assert_status_ok();
+        server
+            .post("/query")
+            .json(&query_request)
+            .await
+            .assert_status_ok();
+
+        assert_eq!(test.ducklakes().builds(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_timeout() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let started = std::time::Instant::now();
+        let response = server
+            .post("/query")
+            .json(&json!({
+                "sql": "SELECT sum(a.range * b.range) FROM range(1000000000) a, range(1000000000) b",
+                "timeout_secs": 1
+            }))
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        response.assert_status(StatusCode::REQUEST_TIMEOUT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_TIMEOUT");
+        assert!(body["message"].as_str().unwrap().contains("timed out"));
+
+        // The interrupted connection is usable again once the query unwinds.
+        server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT 1 AS id" }))
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_limit() -> Result<()> {
+        let test = TestManager::new();
+        test.config().await.project.engine = EngineConfig {
+            max_api_queries: Some(1),
+            api_queue_timeout_secs: Some(0),
+            ..Default::default()
+        };
+        let server = test.setup_server(routes);
+
+        let slow = json!({
+            "sql": "SELECT sum(a.range * b.range) FROM range(1000000000) a, range(1000000000) b",
+            "timeout_secs": 2
+        });
+        let (first, second) =
+            tokio::join!(async { server.post("/query").json(&slow).await }, async {
+                server.post("/query").json(&slow).await
+            });
+
+        let (slow, rejected) = if first.status_code() == StatusCode::TOO_MANY_REQUESTS {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        slow.assert_status(StatusCode::REQUEST_TIMEOUT);
+        rejected.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(rejected.header(header::RETRY_AFTER), "1");
+        let body: serde_json::Value = rejected.json();
+        assert_eq!(body["code"], "TOO_MANY_QUERIES");
+
+        // The slot frees up once the slow query is cancelled.
+        server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT 1 AS id" }))
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_typed_values() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_request = json!({
+            "sql": "SELECT 7 AS int_col, 2.25::DOUBLE AS float_col, false AS bool_col, \
+                    NULL AS null_col, TIMESTAMP '2024-05-06 07:08:09' AS ts_col"
+        });
+
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status_ok();
+
+        let body: serde_json::Value = response.json();
+        assert_eq!(
+            body["columns"],
+            json!(["int_col", "float_col", "bool_col", "null_col", "ts_col"])
+        );
+        assert_eq!(body["types"][0], json!("INTEGER"));
+        assert_eq!(body["types"][1], json!("DOUBLE"));
+        assert_eq!(body["types"][2], json!("BOOLEAN"));
+        assert_eq!(body["types"][4], json!("TIMESTAMP"));
+
+        assert!(body["data"]["int_col"][0].is_i64());
+        assert_eq!(body["data"]["int_col"][0], json!(7));
+        assert!(body["data"]["float_col"][0].is_f64());
+        assert_eq!(body["data"]["float_col"][0], json!(2.25));
+        assert_eq!(body["data"]["bool_col"][0], json!(false));
+        assert!(body["data"]["null_col"][0].is_null());
+        assert_eq!(body["data"]["ts_col"][0], json!("2024-05-06T07:08:09Z"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_default_limit() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_request = json!({
+            "sql": "SELECT range AS id FROM range(20000)"
+        });
+
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status_ok();
+
+        let query_response: QueryResult = response.json();
+        assert_eq!(query_response.row_count, DEFAULT_ROW_LIMIT);
+        assert_eq!(query_response.data["id"].len(), DEFAULT_ROW_LIMIT);
+        assert!(query_response.truncated);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adhoc_query_custom_limit() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_request = json!({
+            "sql": "SELECT range AS id FROM range(100)",
+            "limit": 25
+        });
+
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status_ok();
+
+        let query_response: QueryResult = response.json();
+        assert_eq!(query_response.row_count, 25);
+        assert!(query_response.truncated);
+
+        let query_request = json!({
+            "sql": "SELECT range AS id FROM range(100)",
+            "limit": 100
+        });
+
+        let response = server.post("/query").json(&query_request).await;
+        response.assert_status_ok();
+
+        let query_response: QueryResult = response.json();
+        assert_eq!(query_response.row_count, 100);
+        assert!(!query_response.truncated);
+
+        for limit in [MAX_ROW_LIMIT + 1, usize::MAX] {
+            let response = server
+                .post("/query")
+                .json(&json!({ "sql": "SELECT 1 AS id", "limit": limit }))
+                .await;
+            response.assert_status(StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = response.json();
+            assert_eq!(body["details"]["limit"], MAX_ROW_LIMIT);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_config = QueryConfig {
+            description: Some("A test query".to_string()),
+            sql: "SELECT 42 as answer, 'test' as label".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            let query_file = config.upsert_query("saved_query", &query_config)?;
+            query_file.save()?;
+        }
+
+        let response = server.post("/queries/saved_query/run").await;
+        response.assert_status_ok();
+
+        let query_response: QueryResult = response.json();
+        assert_eq!(query_response.column_count, 2);
+        assert!(query_response.row_count > 0);
+
+        Ok(())
+    }
+
+    async fn save_parameterized_query(test: &TestManager) -> Result<()> {
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT * FROM (VALUES (DATE '2024-01-01', 1, 1.5, 'a'), (DATE '2024-02-01', 2, 2.5, 'b'), (DATE '2024-03-01', 3, 3.5, 'c')) t(day, n, price, label) WHERE day >= {{start}} AND n >= {{min_n}} AND price <= {{max_price}} AND label <> {{exclude}}".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![
+                ParamDef {
+                    name: "start".to_string(),
+                    param_type: ParamType::Date,
+                    default: None,
+                },
+                ParamDef {
+                    name: "min_n".to_string(),
+                    param_type: ParamType::Int,
+                    default: Some(json!(1)),
+                },
+                ParamDef {
+                    name: "max_price".to_string(),
+                    param_type: ParamType::Float,
+                    default: Some(json!(10.0)),
+                },
+                ParamDef {
+                    name: "exclude".to_string(),
+                    param_type: ParamType::String,
+                    default: Some(json!("")),
+                },
+            ],
+        };
+
+        let mut config = test.config().await;
+        config.upsert_query("orders", &query_config)?.save()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_query_with_params() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_parameterized_query(&test).await?;
+
+        let run = |params: serde_json::Value| {
+            server
+                .post("/queries/orders/run")
+                .json(&json!({ "params": params }))
+        };
+
+        let result: QueryResult = run(json!({ "start": "2024-02-01" })).await.json();
+        assert_eq!(result.data["label"], vec![json!("b"), json!("c")]);
+
+        let result: QueryResult = run(json!({ "start": "2024-01-01", "min_n": 2 }))
+            .await
+            .json();
+        assert_eq!(result.data["label"], vec![json!("b"), json!("c")]);
+
+        let result: QueryResult = run(json!({ "start": "2024-01-01", "max_price": "2.5" }))
+            .await
+            .json();
+        assert_eq!(result.data["label"], vec![json!("a"), json!("b")]);
+
+        let result: QueryResult = run(json!({ "start": "2024-01-01", "exclude": "a" }))
+            .await
+            .json();
+        assert_eq!(result.data["label"], vec![json!("b"), json!("c")]);
+
+        let response = run(json!({ "start": "2024-01-01", "min_n": "two" })).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_PARAMETER");
+        assert_eq!(body["details"]["parameter"], "min_n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_query_missing_param() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_parameterized_query(&test).await?;
+
+        let response = server.post("/queries/orders/run").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_PARAMETER");
+        assert_eq!(body["details"]["missing"], json!(["start"]));
+        assert!(body["message"].as_str().unwrap().contains("start"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_query_string_param_is_not_sql() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_parameterized_query(&test).await?;
+
+        let injection = "x' OR '1'='1";
+        let result: QueryResult = server
+            .post("/queries/orders/run")
+            .json(&json!({ "params": { "start": "2024-01-01", "exclude": injection } }))
+            .await
+            .json();
+        assert_eq!(result.row_count, 3);
+
+        let response = server
+            .post("/queries/orders/run")
+            .json(&json!({
+                "params": { "start": "2024-01-01'); DROP TABLE orders; --" }
+            }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_query_csv_large() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT range AS id, 'row_' || range AS label FROM range(100000)".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            config.upsert_query("large_query", &query_config)?.save()?;
+        }
+
+        let response = server
+            .post("/queries/large_query/export")
+            .json(&json!({ "format": "csv" }))
+            .await;
+        response.assert_status_ok();
+        response.assert_header("content-type", "text/csv");
+        response.assert_header(
+            "content-disposition",
+            "attachment; filename=\"large_query.csv\"",
+        );
+
+        let body = response.text();
+        let mut lines = body.lines();
+        assert_eq!(lines.next(), Some("id,label"));
+        assert_eq!(lines.count(), 100_000);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_query_parquet() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT range AS id FROM range(100000)".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            config.upsert_query("large_query", &query_config)?.save()?;
+        }
+
+        let response = server
+            .post("/queries/large_query/export")
+            .json(&json!({ "format": "parquet" }))
+            .await;
+        response.assert_status_ok();
+        response.assert_header("content-type", "application/vnd.apache.parquet");
+
+        let body = response.as_bytes();
+        assert!(body.starts_with(b"PAR1"));
+        assert!(body.ends_with(b"PAR1"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_adhoc_query_json() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/query/export")
+            .json(&json!({
+                "sql": "SELECT 1 AS id, 'Alice' AS name UNION ALL SELECT 2, 'Bob'",
+                "format": "json"
+            }))
+            .await;
+        response.assert_status_ok();
+        response.assert_header("content-disposition", "attachment; filename=\"query.json\"");
+
+        let body = response.text();
+        let rows: Vec<serde_json::Value> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_adhoc_query_timeout() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let started = std::time::Instant::now();
+        let response = server
+            .post("/query/export")
+            .json(&json!({
+                "sql": "SELECT sum(a.range * b.range) FROM range(1000000000) a, range(1000000000) b",
+                "format": "csv",
+                "timeout_secs": 1
+            }))
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        response.assert_status(StatusCode::REQUEST_TIMEOUT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_TIMEOUT");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_query_read_only() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let query_config = QueryConfig {
+            description: None,
+            sql: "DROP TABLE IF EXISTS t".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+        test.config()
+            .await
+            .upsert_query("drop_query", &query_config)?
+            .save()?;
+
+        let response = server
+            .post("/queries/drop_query/export")
+            .json(&json!({ "format": "csv" }))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "READ_ONLY_QUERY");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/queries/nonexistent_query/export")
+            .json(&json!({ "format": "csv" }))
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_query_conflict() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let existing_query = QueryConfig {
+            description: None,
+            sql: "SELECT 1".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        {
+            let mut config = test.config().await;
+            let query_file = config.upsert_query("existing_query", &existing_query)?;
+            query_file.save()?;
+        }
+
+        let duplicate_query = json!({
+            "name": "existing_query",
+            "config": {
+                "description": "This should conflict",
+                "sql": "SELECT 2"
+            }
+        });
+
+        let response = server.post("/queries").json(&duplicate_query).await;
+        response.assert_status(StatusCode::CONFLICT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let updated_config = json!({
+            "description": "This doesn't exist",
+            "sql": "SELECT * FROM nowhere"
+        });
+
+        let response = server
+            .put("/queries/nonexistent_query")
+            .json(&updated_config)
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server.delete("/queries/nonexistent_query").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_nonexistent_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server.post("/queries/nonexistent_query/run").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_query_updates_dashboards() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_query_with_dashboards(&test).await?;
+
+        let response = server
+            .post("/queries/shared_query/rename")
+            .json(&json!({ "new_name": "renamed_query" }))
+            .await;
+        response.assert_status_ok();
+
+        server
+            .get("/queries/shared_query")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get("/queries/renamed_query")
+            .await
+            .assert_status_ok();
+
+        let config = test.config().await;
+        assert_eq!(config.dashboards["dashboard_a"].query, "renamed_query");
+        assert_eq!(config.dashboards["dashboard_b"].query, "renamed_query");
+
+        let saved = std::fs::read_to_string(test.directory().join("dashboards/dashboard_a.yml"))?;
+        assert!(saved.contains("renamed_query"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_query_conflict() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        save_query_with_dashboards(&test).await?;
+
+        let query_config = QueryConfig {
+            description: None,
+            sql: "SELECT 1".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+        test.config()
+            .await
+            .upsert_query("other_query", &query_config)?
+            .save()?;
+
+        let response = server
+            .post("/queries/shared_query/rename")
+            .json(&json!({ "new_name": "other_query" }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_adhoc_join() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        let sql = "SELECT a.range AS id, b.range * 2 AS doubled FROM range(10) a JOIN range(10) b ON a.range = b.range";
+
+        let response = server
+            .post("/query/explain")
+            .json(&json!({ "sql": sql }))
+            .await;
+        response.assert_status_ok();
+        let plan: QueryPlan = response.json();
+        assert!(!plan.analyzed);
+        assert!(plan.plan.contains("JOIN"));
+        assert!(plan.operators.is_empty());
+
+        let response = server
+            .post("/query/explain")
+            .json(&json!({ "sql": sql, "analyze": true }))
+            .await;
+        response.assert_status_ok();
+        let plan: QueryPlan = response.json();
+        assert!(plan.analyzed);
+        assert!(!plan.plan.trim().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_invalid_sql() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server
+            .post("/query/explain")
+            .json(&json!({ "sql": "SELEC nonsense FROM" }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "QUERY_FAILED");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_saved_query() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        {
+            let mut config = test.config().await;
+            let query_config = QueryConfig {
+                description: None,
+                sql: "SELECT range AS id FROM range(5) WHERE range > 2".to_string(),
+                tags: vec![],
+                group: None,
+                parameters: vec![],
+            };
+            config.upsert_query("saved_query", &query_config)?.save()?;
+        }
+
+        let response = server
+            .post("/queries/saved_query/explain")
+            .json(&json!({}))
+            .await;
+        response.assert_status_ok();
+        let plan: QueryPlan = response.json();
+        assert!(!plan.plan.trim().is_empty());
+
+        let response = server
+            .post("/queries/nonexistent_query/explain")
+            .json(&json!({}))
+            .await;
         response.assert_status(StatusCode::NOT_FOUND);
 
         Ok(())
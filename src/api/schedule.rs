@@ -0,0 +1,134 @@
+use crate::{
+    api::Error,
+    core::config::{Config, project::ScheduleConfig},
+};
+use anyhow::Result;
+use axum::{Extension, Router, response::Json, routing::get};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Deserialize)]
+pub struct ScheduleResponse {
+    pub schedule: Option<ScheduleConfig>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/schedule", get(get_schedule).put(update_schedule))
+}
+
+fn schedule_response(schedule: Option<ScheduleConfig>) -> Result<ScheduleResponse, Error> {
+    let next_run_at = match &schedule {
+        Some(schedule) if schedule.enabled => schedule
+            .next_run_after(Utc::now())
+            .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?,
+        _ => None,
+    };
+
+    Ok(ScheduleResponse {
+        schedule,
+        next_run_at,
+    })
+}
+
+async fn get_schedule(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+) -> Result<Json<ScheduleResponse>, Error> {
+    let config = config.lock().await;
+    Ok(Json(schedule_response(config.project.schedule.clone())?))
+}
+
+async fn update_schedule(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Json(schedule): Json<ScheduleConfig>,
+) -> Result<Json<ScheduleResponse>, Error> {
+    let response = schedule_response(Some(schedule.clone()))?;
+
+    let mut config = config.lock().await;
+    config.update_project(|project_config| {
+        project_config.schedule = Some(schedule);
+        Ok::<_, Error>(())
+    })?;
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::StatusCode, core::config::project::parse_project_config, test_helpers::TestManager,
+    };
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_get_schedule_empty() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let response = server.get("/schedule").await;
+        response.assert_status_ok();
+
+        let schedule: ScheduleResponse = response.json();
+        assert!(schedule.schedule.is_none());
+        assert!(schedule.next_run_at.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let new_schedule = json!({ "cron": "0 3 * * *", "enabled": true });
+        let response = server.put("/schedule").json(&new_schedule).await;
+        response.assert_status_ok();
+
+        let response = server.get("/schedule").await;
+        response.assert_status_ok();
+
+        let schedule: ScheduleResponse = response.json();
+        assert_eq!(schedule.schedule.unwrap().cron, "0 3 * * *");
+        assert!(schedule.next_run_at.unwrap() > Utc::now());
+
+        let content = std::fs::read_to_string(test.directory().join("project.yml"))?;
+        let project_config = parse_project_config(&content)?;
+        assert_eq!(project_config.schedule.unwrap().cron, "0 3 * * *");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_disabled() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let new_schedule = json!({ "cron": "0 3 * * *", "enabled": false });
+        let response = server.put("/schedule").json(&new_schedule).await;
+        response.assert_status_ok();
+
+        let schedule: ScheduleResponse = response.json();
+        assert!(!schedule.schedule.unwrap().enabled);
+        assert!(schedule.next_run_at.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_invalid_cron() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let new_schedule = json!({ "cron": "every day", "enabled": true });
+        let response = server.put("/schedule").json(&new_schedule).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        assert!(test.config().await.project.schedule.is_none());
+
+        Ok(())
+    }
+}
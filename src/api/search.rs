@@ -0,0 +1,368 @@
+use crate::{
+    api::{Error, ErrorCode},
+    core::config::Config,
+};
+use axum::{Extension, Router, extract::Query, response::Json, routing::get};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// Bytes of context kept on each side of the first match in a snippet.
+const SNIPPET_CONTEXT: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Adapters,
+    Models,
+    Queries,
+    Dashboards,
+    Seeds,
+}
+
+impl SearchKind {
+    const ALL: [SearchKind; 5] = [
+        SearchKind::Adapters,
+        SearchKind::Models,
+        SearchKind::Queries,
+        SearchKind::Dashboards,
+        SearchKind::Seeds,
+    ];
+
+    fn parse(kind: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == kind)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchKind::Adapters => "adapters",
+            SearchKind::Models => "models",
+            SearchKind::Queries => "queries",
+            SearchKind::Dashboards => "dashboards",
+            SearchKind::Seeds => "seeds",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub kind: SearchKind,
+    pub name: String,
+    pub field: String,
+    pub snippet: String,
+    // Byte ranges within `snippet`, end-exclusive.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchMatch>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    #[serde(default)]
+    kinds: Option<String>,
+    #[serde(default)]
+    regex: bool,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/search", get(search))
+}
+
+fn parse_kinds(kinds: Option<&str>) -> Result<Vec<SearchKind>, Error> {
+    let Some(kinds) = kinds.filter(|kinds| !kinds.trim().is_empty()) else {
+        return Ok(SearchKind::ALL.to_vec());
+    };
+
+    kinds
+        .split(',')
+        .map(|kind| {
+            SearchKind::parse(kind.trim()).ok_or_else(|| {
+                Error::bad_request()
+                    .with_code(ErrorCode::InvalidKind)
+                    .with_message(format!("Unknown kind '{}'", kind.trim()))
+                    .with_detail("kind", kind.trim())
+            })
+        })
+        .collect()
+}
+
+fn build_pattern(q: &str, regex: bool) -> Result<Regex, Error> {
+    if q.is_empty() {
+        return Error::bad_request()
+            .with_message("Search text must not be empty")
+            .build();
+    }
+
+    let pattern = if regex {
+        q.to_string()
+    } else {
+        regex::escape(q)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| {
+            Error::bad_request()
+                .with_code(ErrorCode::InvalidPattern)
+                .with_message(format!("Invalid search pattern: {e}"))
+                .with_detail("pattern", q)
+        })
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+// Cuts a window around the first non-empty match and reports every match that
+// falls entirely inside it.
+fn snippet(pattern: &Regex, text: &str) -> Option<(String, Vec<(usize, usize)>)> {
+    let matches = pattern
+        .find_iter(text)
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>();
+    let first = matches.first()?;
+
+    let start = floor_char_boundary(text, first.start().saturating_sub(SNIPPET_CONTEXT));
+    let end = ceil_char_boundary(text, first.end() + SNIPPET_CONTEXT);
+    let highlights = matches
+        .iter()
+        .filter(|m| m.start() >= start && m.end() <= end)
+        .map(|m| (m.start() - start, m.end() - start))
+        .collect();
+
+    Some((text[start..end].to_string(), highlights))
+}
+
+fn search_config(config: &Config, kinds: &[SearchKind], pattern: &Regex) -> Vec<SearchMatch> {
+    let mut results = Vec::new();
+
+    for kind in kinds {
+        let mut resources: Vec<(&String, Vec<(&str, &str)>)> = match kind {
+            SearchKind::Adapters => fields(&config.adapters, |adapter| {
+                vec![("description", adapter.description.as_deref())]
+            }),
+            SearchKind::Models => fields(&config.models, |model| {
+                vec![
+                    ("description", model.description.as_deref()),
+                    ("sql", Some(model.sql.as_str())),
+                ]
+            }),
+            SearchKind::Queries => fields(&config.queries, |query| {
+                vec![
+                    ("description", query.description.as_deref()),
+                    ("sql", Some(query.sql.as_str())),
+                ]
+            }),
+            SearchKind::Dashboards => fields(&config.dashboards, |dashboard| {
+                let mut fields = vec![("description", dashboard.description.as_deref())];
+                for panel in &dashboard.panels {
+                    fields.push(("title", panel.title.as_deref()));
+                    fields.push(("query", Some(panel.query.as_str())));
+                }
+                fields
+            }),
+            SearchKind::Seeds => fields(&config.seeds, |_| vec![]),
+        };
+        resources.sort_by_key(|(name, _)| *name);
+
+        for (name, fields) in resources {
+            let name_field = [("name", name.as_str())];
+            for (field, text) in name_field.into_iter().chain(fields) {
+                if let Some((snippet, highlights)) = snippet(pattern, text) {
+                    results.push(SearchMatch {
+                        kind: *kind,
+                        name: name.clone(),
+                        field: field.to_string(),
+                        snippet,
+                        highlights,
+                    });
+                }
+            }
+        }
+    }
+
+    results
+}
+
+fn fields<'a, T>(
+    resources: &'a HashMap<String, T>,
+    extract: impl Fn(&'a T) -> Vec<(&'static str, Option<&'a str>)>,
+) -> Vec<(&'a String, Vec<(&'a str, &'a str)>)> {
+    resources
+        .iter()
+        .map(|(name, resource)| {
+            let fields = extract(resource)
+                .into_iter()
+                .filter_map(|(field, text)| Some((field, text?)))
+                .collect();
+            (name, fields)
+        })
+        .collect()
+}
+
+async fn search(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchResponse>, Error> {
+    let kinds = parse_kinds(params.kinds.as_deref())?;
+    let pattern = build_pattern(&params.q, params.regex)?;
+
+    let config = config.lock().await;
+    Ok(Json(SearchResponse {
+        results: search_config(&config, &kinds, &pattern),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::StatusCode,
+        core::config::{
+            model::{Materialization, ModelConfig},
+            query::QueryConfig,
+        },
+        test_helpers::TestManager,
+    };
+    use anyhow::Result;
+
+    fn highlighted(result: &SearchMatch) -> Vec<&str> {
+        result
+            .highlights
+            .iter()
+            .map(|(start, end)| &result.snippet[*start..*end])
+            .collect()
+    }
+
+    #[test]
+    fn test_snippet_boundaries() {
+        let pattern = build_pattern("orders", false).unwrap();
+
+        let (text, highlights) = snippet(&pattern, "ORDERS").unwrap();
+        assert_eq!(text, "ORDERS");
+        assert_eq!(highlights, vec![(0, 6)]);
+
+        let long = format!("{}orders", "x".repeat(100));
+        let (text, highlights) = snippet(&pattern, &long).unwrap();
+        assert_eq!(text, format!("{}orders", "x".repeat(SNIPPET_CONTEXT)));
+        assert_eq!(highlights, vec![(SNIPPET_CONTEXT, SNIPPET_CONTEXT + 6)]);
+
+        // Multi-byte characters straddling the context window are kept whole.
+        let euros = "€".repeat(30);
+        let (text, highlights) = snippet(&pattern, &format!("{euros}orders{euros}")).unwrap();
+        assert_eq!(&text[highlights[0].0..highlights[0].1], "orders");
+        assert!(text.starts_with('€') && text.ends_with('€'));
+
+        assert!(snippet(&pattern, "customers").is_none());
+        let empty = build_pattern("x*", true).unwrap();
+        assert!(snippet(&empty, "abc").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_endpoint() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let mut config = test.config().await;
+            let model = ModelConfig {
+                description: Some("Daily order totals".to_string()),
+                sql: "SELECT date, SUM(total) FROM orders GROUP BY date".to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model("order_totals", &model)?.save()?;
+            let query = QueryConfig {
+                description: None,
+                sql: "SELECT * FROM customers".to_string(),
+                tags: vec![],
+                group: None,
+                parameters: vec![],
+            };
+            config.upsert_query("customers", &query)?.save()?;
+        }
+        let server = test.setup_server(routes);
+
+        let response = server.get("/search").add_query_param("q", "ORDER").await;
+        response.assert_status_ok();
+        let body: SearchResponse = response.json();
+        let fields = body
+            .results
+            .iter()
+            .map(|result| (result.name.as_str(), result.field.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("order_totals", "name"),
+                ("order_totals", "description"),
+                ("order_totals", "sql"),
+            ]
+        );
+        assert_eq!(highlighted(&body.results[2]), vec!["order"]);
+
+        let response = server
+            .get("/search")
+            .add_query_param("q", "from (orders|customers)")
+            .add_query_param("regex", true)
+            .add_query_param("kinds", "queries")
+            .await;
+        response.assert_status_ok();
+        let body: SearchResponse = response.json();
+        assert_eq!(body.results.len(), 1);
+        assert_eq!(body.results[0].kind, SearchKind::Queries);
+        assert_eq!(highlighted(&body.results[0]), vec!["FROM customers"]);
+
+        let response = server
+            .get("/search")
+            .add_query_param("q", "orders(")
+            .add_query_param("regex", true)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_PATTERN");
+
+        server
+            .get("/search")
+            .add_query_param("q", "orders(")
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .get("/search")
+            .add_query_param("q", "orders")
+            .add_query_param("kinds", "models,widgets")
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "INVALID_KIND");
+        assert_eq!(body["details"]["kind"], "widgets");
+
+        Ok(())
+    }
+}
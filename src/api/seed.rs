@@ -0,0 +1,141 @@
+use crate::{
+    api::Error,
+    core::{config::Config, ducklake::DuckLakeCache, graph::Graph, seed::Seed},
+};
+use axum::{
+    Extension, Router,
+    extract::Path,
+    response::Json,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Deserialize)]
+pub struct SeedSummary {
+    pub name: String,
+    pub path: String,
+    pub row_count: Option<u64>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/seeds", get(list_seeds))
+        .route("/seeds/{name}/refresh", post(refresh_seed))
+}
+
+fn seed_summary(config: &Config, graph: &Graph, name: &str) -> Option<SeedSummary> {
+    let seed = config.seeds.get(name)?;
+    let node = graph.get_node(name);
+
+    Some(SeedSummary {
+        name: name.to_string(),
+        path: seed
+            .path
+            .strip_prefix(&config.project_dir)
+            .unwrap_or(&seed.path)
+            .to_string_lossy()
+            .to_string(),
+        row_count: node.and_then(|node| node.row_count),
+        updated_at: node.and_then(|node| node.updated_at),
+    })
+}
+
+async fn list_seeds(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+) -> Result<Json<Vec<SeedSummary>>, Error> {
+    let config = config.lock().await;
+    let graph = graph.lock().await;
+
+    let mut names: Vec<&String> = config.seeds.keys().collect();
+    names.sort();
+    let seeds = names
+        .into_iter()
+        .filter_map(|name| seed_summary(&config, &graph, name))
+        .collect();
+
+    Ok(Json(seeds))
+}
+
+async fn refresh_seed(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Path(name): Path<String>,
+) -> Result<Json<SeedSummary>, Error> {
+    let Some(seed_config) = config.lock().await.seeds.get(&name).cloned() else {
+        return Error::not_found()
+            .with_message(format!("Seed '{name}' not found"))
+            .build();
+    };
+
+    let ducklake = ducklakes.get(&config).await?;
+    let stats = Seed::new(seed_config, ducklake)
+        .load(&name)
+        .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+
+    let config = config.lock().await;
+    let mut graph = graph.lock().await;
+    if !graph.has_node(&name) {
+        graph.create_node(&name, &[]);
+    }
+    // Models built from the previous contents are now stale.
+    graph.update_node(&name);
+    graph.record_stats(&name, &stats);
+    graph.save().await?;
+
+    match seed_summary(&config, &graph, &name) {
+        Some(summary) => Ok(Json(summary)),
+        None => Error::not_found().build(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::StatusCode, test_helpers::TestManager};
+    use anyhow::Result;
+
+    const COUNTRIES_CSV: &str = "code,name\nJP,Japan\nUS,United States\n";
+
+    async fn write_seed(test: &TestManager, file_name: &str, content: &str) -> Result<()> {
+        std::fs::create_dir_all(test.directory().join("seeds"))?;
+        std::fs::write(test.directory().join("seeds").join(file_name), content)?;
+        test.config().await.load()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_and_refresh_seeds() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+        write_seed(&test, "countries.csv", COUNTRIES_CSV).await?;
+        write_seed(&test, "notes.txt", "not a seed").await?;
+
+        let response = server.get("/seeds").await;
+        response.assert_status_ok();
+        let seeds: Vec<SeedSummary> = response.json();
+        assert_eq!(seeds.len(), 1);
+        assert_eq!(seeds[0].name, "countries");
+        assert_eq!(seeds[0].path, "seeds/countries.csv");
+        assert!(seeds[0].row_count.is_none());
+
+        let response = server.post("/seeds/countries/refresh").await;
+        response.assert_status_ok();
+        let seed: SeedSummary = response.json();
+        assert_eq!(seed.row_count, Some(2));
+        assert!(seed.updated_at.is_some());
+        assert!(test.graph().await.has_node("countries"));
+
+        server
+            .post("/seeds/missing/refresh")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
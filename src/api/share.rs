@@ -0,0 +1,433 @@
+use crate::{
+    api::{
+        Error, ErrorCode, MaskingParams, QueryLimiter,
+        cache::CacheStatus,
+        dashboard::{DashboardDataResponse, get_dashboard_data, get_panel_data},
+        query::SnapshotParams,
+    },
+    core::{
+        config::{
+            Config,
+            dashboard::{ChartConfig, GridPosition},
+        },
+        ducklake::DuckLakeCache,
+        graph::Graph,
+        query_cache::QueryCache,
+        share::{Share, Shares},
+    },
+};
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    response::Json,
+    routing::{delete, get},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub fn routes() -> Router {
+    Router::new()
+        .route(
+            "/dashboards/{name}/share",
+            get(list_shares).post(create_share),
+        )
+        .route("/dashboards/{name}/share/{id}", delete(revoke_share))
+}
+
+// Served without the API token; a share token only ever resolves to its own
+// dashboard.
+pub fn public_routes() -> Router {
+    Router::new()
+        .route("/shared/{token}", get(get_shared))
+        .route("/shared/{token}/data", get(get_shared_data))
+        .route(
+            "/shared/{token}/panels/{index}/data",
+            get(get_shared_panel_data),
+        )
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateShareRequest {
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateShareResponse {
+    #[serde(flatten)]
+    pub share: Share,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedDashboard {
+    pub name: String,
+    pub description: Option<String>,
+    pub panels: Vec<SharedPanel>,
+}
+
+// Leaves out the query name; viewers only get the chart.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedPanel {
+    pub title: Option<String>,
+    pub chart: ChartConfig,
+    pub position: GridPosition,
+}
+
+async fn create_share(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path(name): Path<String>,
+    payload: Option<Json<CreateShareRequest>>,
+) -> Result<Json<CreateShareResponse>, Error> {
+    let Json(request) = payload.unwrap_or_default();
+    if request
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Error::bad_request()
+            .with_message("'expires_at' must be in the future")
+            .build();
+    }
+
+    let config = config.lock().await;
+    ensure_dashboard_exists(&config, &name)?;
+
+    let mut shares = Shares::load(&config.project_dir)?;
+    let (share, token) = shares.create(&name, request.expires_at)?;
+    shares.save()?;
+
+    Ok(Json(CreateShareResponse { share, token }))
+}
+
+async fn list_shares(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<Share>>, Error> {
+    let config = config.lock().await;
+    ensure_dashboard_exists(&config, &name)?;
+
+    let shares = Shares::load(&config.project_dir)?;
+    Ok(Json(
+        shares.for_dashboard(&name).into_iter().cloned().collect(),
+    ))
+}
+
+async fn revoke_share(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path((name, id)): Path<(String, String)>,
+) -> Result<(), Error> {
+    let config = config.lock().await;
+    ensure_dashboard_exists(&config, &name)?;
+
+    let mut shares = Shares::load(&config.project_dir)?;
+    if !shares.revoke(&name, &id) {
+        return Error::not_found()
+            .with_message(format!("Dashboard '{name}' has no share '{id}'"))
+            .build();
+    }
+    shares.save()?;
+
+    Ok(())
+}
+
+fn ensure_dashboard_exists(config: &Config, name: &str) -> Result<(), Error> {
+    if !config.dashboards.contains_key(name) {
+        return Error::not_found()
+            .with_code(ErrorCode::DashboardNotFound)
+            .build();
+    }
+
+    Ok(())
+}
+
+// Unknown, revoked and expired tokens all look the same to the caller.
+fn shared_dashboard_in(config: &Config, token: &str) -> Result<String, Error> {
+    let shares = Shares::load(&config.project_dir)?;
+    match shares.resolve(token, Utc::now()) {
+        Some(share) if config.dashboards.contains_key(&share.dashboard) => {
+            Ok(share.dashboard.clone())
+        }
+        _ => Error::not_found()
+            .with_message("Shared dashboard not found")
+            .build(),
+    }
+}
+
+async fn shared_dashboard(config: &Mutex<Config>, token: &str) -> Result<String, Error> {
+    shared_dashboard_in(&*config.lock().await, token)
+}
+
+async fn get_shared(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedDashboard>, Error> {
+    // Resolved under the same lock it is read with, so a concurrent delete can't
+    // remove the dashboard in between.
+    let config = config.lock().await;
+    let name = shared_dashboard_in(&config, &token)?;
+    let Some(dashboard) = config.dashboards.get(&name) else {
+        return Error::not_found()
+            .with_message("Shared dashboard not found")
+            .build();
+    };
+
+    Ok(Json(SharedDashboard {
+        description: dashboard.description.clone(),
+        panels: dashboard
+            .panels
+            .iter()
+            .map(|panel| SharedPanel {
+                title: panel.title.clone(),
+                chart: panel.chart.clone(),
+                position: panel.position,
+            })
+            .collect(),
+        name,
+    }))
+}
+
+// Shared data is always read from the latest snapshot.
+async fn get_shared_data(
+    config: Extension<Arc<Mutex<Config>>>,
+    graph: Extension<Arc<Mutex<Graph>>>,
+    ducklakes: Extension<Arc<DuckLakeCache>>,
+    query_cache: Extension<Arc<QueryCache>>,
+    limiter: Extension<Arc<QueryLimiter>>,
+    Path(token): Path<String>,
+) -> Result<(CacheStatus, Json<DashboardDataResponse>), Error> {
+    let name = shared_dashboard(&config, &token).await?;
+    get_dashboard_data(
+        config,
+        graph,
+        ducklakes,
+        query_cache,
+        limiter,
+        None,
+        Path(name),
+        Query(SnapshotParams::default()),
+        Query(MaskingParams::default()),
+    )
+    .await
+}
+
+async fn get_shared_panel_data(
+    config: Extension<Arc<Mutex<Config>>>,
+    graph: Extension<Arc<Mutex<Graph>>>,
+    ducklakes: Extension<Arc<DuckLakeCache>>,
+    query_cache: Extension<Arc<QueryCache>>,
+    limiter: Extension<Arc<QueryLimiter>>,
+    Path((token, index)): Path<(String, usize)>,
+) -> Result<(CacheStatus, Json<DashboardDataResponse>), Error> {
+    let name = shared_dashboard(&config, &token).await?;
+    get_panel_data(
+        config,
+        graph,
+        ducklakes,
+        query_cache,
+        limiter,
+        None,
+        Path((name, index)),
+        Query(SnapshotParams::default()),
+        Query(MaskingParams::default()),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{StatusCode, app},
+        core::config::{
+            api_token::{ApiToken, generate_api_token},
+            dashboard::{ChartType, DashboardConfig, PanelConfig},
+            project::{ProjectConfig, ServerConfig},
+            query::QueryConfig,
+        },
+    };
+    use anyhow::Result;
+    use axum_test::TestServer;
+    use serde_json::json;
+
+    fn setup_server(dir: &std::path::Path) -> Result<(TestServer, String)> {
+        let api_token = generate_api_token(dir)?;
+
+        let mut config = Config::new(dir.to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        config
+            .upsert_query(
+                "by_category",
+                &QueryConfig {
+                    description: None,
+                    sql: "SELECT 'A' AS category, 10 AS total UNION ALL SELECT 'B', 20 ORDER BY category"
+                        .to_string(),
+                    tags: vec![],
+                    group: None,
+                    parameters: vec![],
+                },
+            )?
+            .save()?;
+        config
+            .upsert_dashboard(
+                "sales",
+                &DashboardConfig {
+                    description: Some("Sales by category".to_string()),
+                    panels: vec![PanelConfig::new(
+                        "by_category",
+                        ChartConfig {
+                            chart_type: ChartType::Bar,
+                            x_column: "category".to_string(),
+                            y_column: "total".to_string(),
+                        },
+                    )],
+                    tags: vec![],
+                    group: None,
+                },
+            )?
+            .save()?;
+
+        let app = app(
+            Arc::new(Mutex::new(config)),
+            Arc::new(Mutex::new(Graph::new(dir))),
+            Arc::default(),
+            Arc::default(),
+            Some(ApiToken::load(dir)?),
+            &ServerConfig::default(),
+        );
+        Ok((TestServer::new(app)?, api_token))
+    }
+
+    #[tokio::test]
+    async fn test_share_dashboard() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (server, api_token) = setup_server(dir.path())?;
+
+        server
+            .post("/api/dashboards/sales/share")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        let created: CreateShareResponse = server
+            .post("/api/dashboards/sales/share")
+            .authorization_bearer(&api_token)
+            .await
+            .json();
+        let token = created.token;
+
+        let shared: SharedDashboard = server.get(&format!("/api/shared/{token}")).await.json();
+        assert_eq!(shared.name, "sales");
+        assert_eq!(shared.description.as_deref(), Some("Sales by category"));
+        assert_eq!(shared.panels.len(), 1);
+
+        let response = server.get(&format!("/api/shared/{token}/data")).await;
+        response.assert_status_ok();
+        let data: DashboardDataResponse = response.json();
+        assert_eq!(data.labels, vec![json!("A"), json!("B")]);
+        assert_eq!(data.values, vec![json!(10), json!(20)]);
+
+        // The share token is not an API token.
+        server
+            .get("/api/dashboards/sales")
+            .authorization_bearer(&token)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        let mut shares = Shares::load(dir.path())?;
+        shares.set_expires_at(
+            &created.share.id,
+            Some(Utc::now() - chrono::Duration::minutes(1)),
+        );
+        shares.save()?;
+
+        server
+            .get(&format!("/api/shared/{token}"))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get(&format!("/api/shared/{token}/data"))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_share() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (server, api_token) = setup_server(dir.path())?;
+
+        let first: CreateShareResponse = server
+            .post("/api/dashboards/sales/share")
+            .authorization_bearer(&api_token)
+            .await
+            .json();
+        let second: CreateShareResponse = server
+            .post("/api/dashboards/sales/share")
+            .authorization_bearer(&api_token)
+            .await
+            .json();
+
+        server
+            .delete(&format!("/api/dashboards/sales/share/{}", first.share.id))
+            .authorization_bearer(&api_token)
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/shared/{}", first.token))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get(&format!("/api/shared/{}", second.token))
+            .await
+            .assert_status_ok();
+
+        let listed: Vec<Share> = server
+            .get("/api/dashboards/sales/share")
+            .authorization_bearer(&api_token)
+            .await
+            .json();
+        assert_eq!(listed, vec![second.share.clone()]);
+
+        // Deleting the dashboard revokes what is left.
+        server
+            .delete("/api/dashboards/sales")
+            .authorization_bearer(&api_token)
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/shared/{}", second.token))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        assert!(Shares::load(dir.path())?.for_dashboard("sales").is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cascade_delete_revokes_share() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (server, api_token) = setup_server(dir.path())?;
+
+        let created: CreateShareResponse = server
+            .post("/api/dashboards/sales/share")
+            .authorization_bearer(&api_token)
+            .await
+            .json();
+        server
+            .delete("/api/queries/by_category")
+            .add_query_param("force", true)
+            .authorization_bearer(&api_token)
+            .await
+            .assert_status_ok();
+
+        server
+            .get(&format!("/api/shared/{}", created.token))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        assert!(Shares::load(dir.path())?.for_dashboard("sales").is_empty());
+
+        Ok(())
+    }
+}
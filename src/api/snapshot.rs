@@ -0,0 +1,223 @@
+use crate::{
+    api::{
+        Error,
+        graph::{GraphNode, graph_node},
+        status::PipelineSummary,
+    },
+    core::{config::Config, graph::Graph, pipeline::Pipeline},
+};
+use axum::{
+    Extension, Router,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+    routing::get,
+};
+use ring::digest::{SHA256, digest};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSummary {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Resources {
+    pub adapters: Vec<ResourceSummary>,
+    pub models: Vec<ResourceSummary>,
+    pub queries: Vec<ResourceSummary>,
+    pub dashboards: Vec<ResourceSummary>,
+    pub seeds: Vec<ResourceSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotBody {
+    pub resources: Resources,
+    pub nodes: Vec<GraphNode>,
+    pub latest_pipeline: Option<PipelineSummary>,
+}
+
+// Everything an embedding client needs in one read, so it never stitches together
+// responses taken before and after a change. `version` is also sent as the ETag.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: String,
+    #[serde(flatten)]
+    pub body: SnapshotBody,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/snapshot", get(get_snapshot))
+}
+
+fn summaries<'a>(
+    resources: impl Iterator<Item = (&'a String, Option<&'a String>)>,
+) -> Vec<ResourceSummary> {
+    let mut summaries: Vec<ResourceSummary> = resources
+        .map(|(name, description)| ResourceSummary {
+            name: name.clone(),
+            description: description.cloned(),
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    summaries
+}
+
+// Both locks are held for the whole read, including the pipeline file, so every
+// section describes the same moment.
+async fn snapshot(config: &Mutex<Config>, graph: &Mutex<Graph>) -> Result<Snapshot, Error> {
+    let config = config.lock().await;
+    let graph = graph.lock().await;
+
+    let pipeline = Pipeline::load_latest(&config.project_dir).await?;
+    let phases: HashMap<_, _> = pipeline
+        .iter()
+        .flat_map(|pipeline| &pipeline.tasks)
+        .map(|(name, task)| (name.clone(), task.phase().clone()))
+        .collect();
+
+    let resources = Resources {
+        adapters: summaries(
+            config
+                .adapters
+                .iter()
+                .map(|(name, adapter)| (name, adapter.description.as_ref())),
+        ),
+        models: summaries(
+            config
+                .models
+                .iter()
+                .map(|(name, model)| (name, model.description.as_ref())),
+        ),
+        queries: summaries(
+            config
+                .queries
+                .iter()
+                .map(|(name, query)| (name, query.description.as_ref())),
+        ),
+        dashboards: summaries(
+            config
+                .dashboards
+                .iter()
+                .map(|(name, dashboard)| (name, dashboard.description.as_ref())),
+        ),
+        seeds: summaries(config.seeds.keys().map(|name| (name, None))),
+    };
+
+    let mut nodes: Vec<GraphNode> = graph
+        .nodes
+        .values()
+        .map(|node| graph_node(&config, &phases, node))
+        .collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let body = SnapshotBody {
+        resources,
+        nodes,
+        latest_pipeline: pipeline.map(PipelineSummary::from),
+    };
+    let hash = digest(&SHA256, serde_json::to_string(&body)?.as_bytes());
+    let version = hash.as_ref()[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    Ok(Snapshot { version, body })
+}
+
+fn matches_etag(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == etag || tag == "*")
+}
+
+async fn get_snapshot(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    headers: HeaderMap,
+) -> Result<Response, Error> {
+    let snapshot = snapshot(&config, &graph).await?;
+    let etag = format!("\"{}\"", snapshot.version);
+    let etag_header = (header::ETAG, HeaderValue::from_str(&etag)?);
+
+    if matches_etag(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [etag_header]).into_response());
+    }
+    Ok(([etag_header], Json(snapshot)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::config::model::ModelConfig, test_helpers::TestManager};
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_snapshot_etag() -> Result<()> {
+        let test = TestManager::new();
+        let server = test.setup_server(routes);
+
+        let first = server.get("/snapshot").await;
+        first.assert_status_ok();
+        let etag = first.header(header::ETAG);
+        let snapshot: Snapshot = first.json();
+        assert_eq!(etag, format!("\"{}\"", snapshot.version));
+        assert!(snapshot.body.nodes.is_empty());
+
+        let second = server.get("/snapshot").await;
+        assert_eq!(second.header(header::ETAG), etag);
+
+        let unchanged = server
+            .get("/snapshot")
+            .add_header(header::IF_NONE_MATCH, etag.clone())
+            .await;
+        unchanged.assert_status(StatusCode::NOT_MODIFIED);
+        assert_eq!(unchanged.header(header::ETAG), etag);
+
+        {
+            let mut config = test.config().await;
+            config
+                .upsert_model(
+                    "daily_orders",
+                    &ModelConfig {
+                        description: Some("Orders by day".to_string()),
+                        sql: "SELECT 1 AS orders".to_string(),
+                        ..Default::default()
+                    },
+                )?
+                .save()?;
+            test.graph().await.rebuild(&config);
+        }
+
+        let changed = server
+            .get("/snapshot")
+            .add_header(header::IF_NONE_MATCH, etag.clone())
+            .await;
+        changed.assert_status_ok();
+        assert_ne!(changed.header(header::ETAG), etag);
+
+        let snapshot: Snapshot = changed.json();
+        assert_eq!(
+            snapshot.body.resources.models,
+            vec![ResourceSummary {
+                name: "daily_orders".to_string(),
+                description: Some("Orders by day".to_string()),
+            }]
+        );
+        let names: Vec<&str> = snapshot
+            .body
+            .nodes
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["daily_orders"]);
+
+        Ok(())
+    }
+}
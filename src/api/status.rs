@@ -0,0 +1,220 @@
+use crate::{
+    api::Error,
+    core::{
+        config::{
+            Config,
+            project::{DatabaseType, StorageConfig},
+        },
+        graph::Graph,
+        pipeline::{ActivePipeline, Phase, Pipeline},
+        validation::{Severity, validate_project},
+    },
+};
+use axum::{Extension, Router, response::Json, routing::get};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceCounts {
+    pub adapters: usize,
+    pub models: usize,
+    pub queries: usize,
+    pub dashboards: usize,
+    pub seeds: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PipelineSummary {
+    pub id: String,
+    pub phase: Phase,
+    pub started_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub failed_tasks: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectStatus {
+    pub version: String,
+    pub counts: ResourceCounts,
+    pub latest_pipeline: Option<PipelineSummary>,
+    pub pipeline_running: bool,
+    pub validation_warnings: usize,
+    pub storage_type: String,
+    pub catalog_type: DatabaseType,
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/status", get(status))
+}
+
+impl From<Pipeline> for PipelineSummary {
+    fn from(pipeline: Pipeline) -> Self {
+        let duration_ms = pipeline
+            .started_at
+            .zip(pipeline.completed_at)
+            .map(|(started_at, completed_at)| (completed_at - started_at).num_milliseconds());
+        let mut failed_tasks = pipeline
+            .tasks
+            .iter()
+            .filter(|(_, task)| *task.phase() == Phase::Failed)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        failed_tasks.sort();
+
+        Self {
+            id: pipeline.id,
+            phase: pipeline.phase,
+            started_at: pipeline.started_at,
+            duration_ms,
+            failed_tasks,
+        }
+    }
+}
+
+fn storage_type(storage: &StorageConfig) -> &'static str {
+    match storage {
+        StorageConfig::LocalFile { .. } => "local",
+        StorageConfig::S3(_) => "s3",
+        StorageConfig::Gcs(_) => "gcs",
+    }
+}
+
+// Everything here comes from memory or the pipeline files; no DuckLake is built.
+async fn status(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(graph): Extension<Arc<Mutex<Graph>>>,
+    Extension(active): Extension<Arc<ActivePipeline>>,
+) -> Result<Json<ProjectStatus>, Error> {
+    let (project_dir, counts, validation_warnings, storage_type, catalog_type) = {
+        let config = config.lock().await;
+        let graph = graph.lock().await;
+        let report = validate_project(&config, &graph)?;
+
+        (
+            config.project_dir.clone(),
+            ResourceCounts {
+                adapters: config.adapters.len(),
+                models: config.models.len(),
+                queries: config.queries.len(),
+                dashboards: config.dashboards.len(),
+                seeds: config.seeds.len(),
+            },
+            report
+                .findings
+                .iter()
+                .filter(|finding| finding.severity == Severity::Warning)
+                .count(),
+            storage_type(&config.project.storage).to_string(),
+            config.project.database.ty.clone(),
+        )
+    };
+
+    let pipeline_running = active.is_running().await;
+    let latest_pipeline = Pipeline::load_latest(&project_dir)
+        .await?
+        .map(PipelineSummary::from);
+
+    Ok(Json(ProjectStatus {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        counts,
+        latest_pipeline,
+        pipeline_running,
+        validation_warnings,
+        storage_type,
+        catalog_type,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::config::{
+            dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+            query::QueryConfig,
+        },
+        test_helpers::TestManager,
+    };
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_status() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let mut config = test.config().await;
+            let query = QueryConfig {
+                description: None,
+                sql: "SELECT 1 AS x, 2 AS y".to_string(),
+                tags: vec![],
+                group: None,
+                parameters: vec![],
+            };
+            config.upsert_query("numbers", &query)?.save()?;
+            let dashboard = DashboardConfig {
+                description: None,
+                panels: vec![PanelConfig::new(
+                    "numbers",
+                    ChartConfig {
+                        chart_type: ChartType::Bar,
+                        x_column: "x".to_string(),
+                        y_column: "y".to_string(),
+                    },
+                )],
+                tags: vec![],
+                group: None,
+            };
+            config.upsert_dashboard("numbers", &dashboard)?.save()?;
+        }
+
+        let pipeline_dir = test.directory().join(".data").join("pipelines");
+        std::fs::create_dir_all(&pipeline_dir)?;
+        let task = |phase: &str| {
+            json!({
+                "phase": phase,
+                "started_at": "2026-01-02T03:04:05Z",
+                "completed_at": null,
+                "error": null
+            })
+        };
+        std::fs::write(
+            pipeline_dir.join("2026-01-02-03-04-05.json"),
+            serde_json::to_string(&json!({
+                "phase": "failed",
+                "started_at": "2026-01-02T03:04:05Z",
+                "completed_at": "2026-01-02T03:04:07.500Z",
+                "tasks": {
+                    "orders": task("completed"),
+                    "revenue": task("failed"),
+                    "customers": task("failed")
+                }
+            }))?,
+        )?;
+
+        let server = test.setup_server(routes);
+        let response = server.get("/status").await;
+        response.assert_status_ok();
+
+        let status: ProjectStatus = response.json();
+        assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(status.counts.queries, 1);
+        assert_eq!(status.counts.dashboards, 1);
+        assert_eq!(status.counts.models, 0);
+        assert!(!status.pipeline_running);
+        assert_eq!(status.catalog_type, DatabaseType::Sqlite);
+
+        let pipeline = status.latest_pipeline.unwrap();
+        assert_eq!(pipeline.id, "2026-01-02-03-04-05");
+        assert_eq!(pipeline.phase, Phase::Failed);
+        assert_eq!(
+            pipeline.started_at,
+            Some("2026-01-02T03:04:05Z".parse::<DateTime<Utc>>()?)
+        );
+        assert_eq!(pipeline.duration_ms, Some(2500));
+        assert_eq!(pipeline.failed_tasks, vec!["customers", "revenue"]);
+
+        Ok(())
+    }
+}
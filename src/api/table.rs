@@ -0,0 +1,892 @@
+use crate::{
+    api::{Error, ErrorCode, MaskingParams, auth::Principal, query::SnapshotParams},
+    core::{
+        config::Config,
+        ducklake::{DuckLake, DuckLakeCache, Snapshot, SnapshotInfo, TypedResult, quote_ident},
+        masking::{Masks, table_masks},
+    },
+};
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const DEFAULT_PREVIEW_LIMIT: usize = 50;
+const MAX_PREVIEW_LIMIT: usize = 500;
+const PROFILE_SAMPLE_ROWS: u64 = 100_000;
+const DEFAULT_DIFF_SAMPLE: usize = 10;
+const MAX_DIFF_SAMPLE: usize = 100;
+const PREVIOUS_SNAPSHOT: &str = "previous_snapshot";
+
+#[derive(Deserialize)]
+pub struct PreviewParams {
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TablePreview {
+    pub name: String,
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub limit: usize,
+    pub offset: usize,
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub data_type: String,
+    pub null_count: u64,
+    pub distinct_count: u64,
+    pub min: serde_json::Value,
+    pub max: serde_json::Value,
+    pub mean: Option<f64>,
+    pub stddev: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableProfile {
+    pub name: String,
+    pub row_count: u64,
+    // Set when the statistics were computed on a sample of PROFILE_SAMPLE_ROWS rows.
+    pub sampled: bool,
+    pub columns: Vec<ColumnProfile>,
+}
+
+#[derive(Deserialize)]
+pub struct DiffParams {
+    // `previous_snapshot` or a snapshot id.
+    pub against: Option<String>,
+    // Comma-separated key columns; without them rows are compared on every column.
+    pub key: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DiffRows {
+    pub count: u64,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableDiff {
+    pub name: String,
+    pub against_snapshot: u64,
+    pub key: Vec<String>,
+    pub columns: Vec<String>,
+    pub added: DiffRows,
+    pub removed: DiffRows,
+    // Only known when rows can be matched by key.
+    pub changed: Option<DiffRows>,
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/tables", get(list_tables))
+        .route("/tables/{name}/preview", get(preview_table))
+        .route("/tables/{name}/profile", get(profile_table))
+        .route("/tables/{name}/snapshots", get(list_table_snapshots))
+        .route("/tables/{name}/rollback", post(rollback_table))
+        .route("/tables/{name}/diff", get(diff_table))
+}
+
+// Only names that exist in the catalog are ever interpolated into SQL.
+fn ensure_table_exists(ducklake: &DuckLake, name: &str) -> Result<(), Error> {
+    if ducklake.list_tables()?.iter().any(|table| table == name) {
+        Ok(())
+    } else {
+        Error::not_found()
+            .with_code(ErrorCode::TableNotFound)
+            .with_message(format!("Table '{name}' not found"))
+            .with_detail("table", name)
+            .build()
+    }
+}
+
+async fn masks_for(
+    config: &Mutex<Config>,
+    name: &str,
+    principal: Option<Extension<Principal>>,
+    masking: &MaskingParams,
+) -> Result<Masks, Error> {
+    let config = config.lock().await;
+    masking.masks(principal.as_deref(), || Ok(table_masks(&config, name)))
+}
+
+fn masked_rows(result: &TypedResult, masks: &Masks) -> Vec<Vec<serde_json::Value>> {
+    (0..result.row_count())
+        .map(|i| {
+            result
+                .columns
+                .iter()
+                .map(|column| {
+                    let value = &result.data[column][i];
+                    match masks.get(column) {
+                        Some(mask) => mask.apply(value),
+                        None => value.clone(),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+async fn list_tables(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+) -> Result<Json<Vec<String>>, Error> {
+    let ducklake = ducklakes.get(&config).await?;
+    Ok(Json(ducklake.list_tables()?))
+}
+
+async fn preview_table(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    Query(params): Query<PreviewParams>,
+    Query(masking): Query<MaskingParams>,
+) -> Result<Json<TablePreview>, Error> {
+    let masks = masks_for(&config, &name, principal, &masking).await?;
+    let ducklake = ducklakes.get(&config).await?;
+    ensure_table_exists(&ducklake, &name)?;
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PREVIEW_LIMIT)
+        .min(MAX_PREVIEW_LIMIT);
+    let (result, truncated) = ducklake.query_paged(
+        &format!("SELECT * FROM db.main.{}", quote_ident(&name)),
+        limit,
+        params.offset,
+    )?;
+
+    let columns = ducklake
+        .table_schema(&name)?
+        .into_iter()
+        .map(|(name, data_type)| TableColumn { name, data_type })
+        .collect();
+
+    let rows = masked_rows(&result, &masks);
+
+    Ok(Json(TablePreview {
+        name,
+        columns,
+        rows,
+        limit,
+        offset: params.offset,
+        truncated,
+    }))
+}
+
+async fn profile_table(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    Query(masking): Query<MaskingParams>,
+) -> Result<Json<TableProfile>, Error> {
+    let masks = masks_for(&config, &name, principal, &masking).await?;
+    let ducklake = ducklakes.get(&config).await?;
+    ensure_table_exists(&ducklake, &name)?;
+
+    let table = format!("db.main.{}", quote_ident(&name));
+    let row_count = ducklake
+        .query(&format!("SELECT COUNT(*) FROM {table}"))?
+        .first()
+        .and_then(|row| row.first())
+        .and_then(|count| count.parse().ok())
+        .unwrap_or_default();
+    let sampled = row_count > PROFILE_SAMPLE_ROWS;
+
+    let schema = ducklake.table_schema(&name)?;
+    let source = if sampled {
+        format!("(SELECT * FROM {table} USING SAMPLE {PROFILE_SAMPLE_ROWS} ROWS)")
+    } else {
+        table
+    };
+    let result = ducklake.query_typed(&build_profile_query(&source, &schema))?;
+
+    let value = |alias: String| {
+        result
+            .data
+            .get(&alias)
+            .and_then(|values| values.first())
+            .cloned()
+            .unwrap_or_default()
+    };
+    // The extremes are values from the table, so they are masked like rows.
+    let extreme = |alias: String, column: &str| match masks.get(column) {
+        Some(mask) => mask.apply(&value(alias)),
+        None => value(alias),
+    };
+    let columns = schema
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, data_type))| ColumnProfile {
+            null_count: value(format!("nulls_{i}")).as_u64().unwrap_or_default(),
+            distinct_count: value(format!("distinct_{i}")).as_u64().unwrap_or_default(),
+            min: extreme(format!("min_{i}"), &name),
+            max: extreme(format!("max_{i}"), &name),
+            mean: value(format!("mean_{i}")).as_f64(),
+            stddev: value(format!("stddev_{i}")).as_f64(),
+            name,
+            data_type,
+        })
+        .collect();
+
+    Ok(Json(TableProfile {
+        name,
+        row_count,
+        sampled,
+        columns,
+    }))
+}
+
+async fn list_table_snapshots(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<SnapshotInfo>>, Error> {
+    let ducklake = ducklakes.get(&config).await?;
+    ensure_table_exists(&ducklake, &name)?;
+
+    let snapshots = ducklake
+        .list_snapshots(&name)
+        .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+
+    Ok(Json(snapshots))
+}
+
+async fn rollback_table(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    Path(name): Path<String>,
+    Json(params): Json<SnapshotParams>,
+) -> Result<StatusCode, Error> {
+    let Some(snapshot) = params.snapshot()? else {
+        return Error::bad_request()
+            .with_message("Specify 'snapshot_id' or 'as_of' to roll back to")
+            .build();
+    };
+
+    let ducklake = ducklakes.get(&config).await?;
+    ensure_table_exists(&ducklake, &name)?;
+
+    ducklake
+        .rollback_table(&name, &snapshot)
+        .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn diff_table(
+    Extension(config): Extension<Arc<Mutex<Config>>>,
+    Extension(ducklakes): Extension<Arc<DuckLakeCache>>,
+    principal: Option<Extension<Principal>>,
+    Path(name): Path<String>,
+    Query(params): Query<DiffParams>,
+    Query(masking): Query<MaskingParams>,
+) -> Result<Json<TableDiff>, Error> {
+    let masks = masks_for(&config, &name, principal, &masking).await?;
+    let max_rows = config.lock().await.project.engine.max_diff_rows();
+    let ducklake = ducklakes.get(&config).await?;
+    ensure_table_exists(&ducklake, &name)?;
+
+    let against = match params.against.as_deref().unwrap_or(PREVIOUS_SNAPSHOT) {
+        PREVIOUS_SNAPSHOT => {
+            let snapshots = ducklake
+                .list_snapshots(&name)
+                .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+            match snapshots.iter().rev().nth(1) {
+                Some(snapshot) => snapshot.snapshot_id,
+                None => {
+                    return Error::bad_request()
+                        .with_message(format!("Table '{name}' has no previous snapshot"))
+                        .with_detail("table", name)
+                        .build();
+                }
+            }
+        }
+        against => against.parse().map_err(|_| {
+            Error::bad_request()
+                .with_message("'against' must be 'previous_snapshot' or a snapshot id")
+                .with_detail("against", against)
+        })?,
+    };
+
+    let table = format!("db.main.{}", quote_ident(&name));
+    let current = format!("(SELECT * FROM {table})");
+    let previous = format!(
+        "(SELECT * FROM {table} {})",
+        Snapshot::Version(against).at_clause()
+    );
+
+    let schema = ducklake.table_schema(&name)?;
+    let previous_schema = ducklake
+        .query_schema(&format!("SELECT * FROM {previous}"))
+        .map_err(|e| Error::bad_request().with_message(format!("{e:#}")))?;
+    if schema != previous_schema {
+        return Error::bad_request()
+            .with_code(ErrorCode::SchemaMismatch)
+            .with_message(format!(
+                "The columns of '{name}' changed since snapshot {against}; only matching versions can be diffed"
+            ))
+            .build();
+    }
+    let columns: Vec<String> = schema.into_iter().map(|(column, _)| column).collect();
+
+    let key: Vec<String> = params
+        .key
+        .iter()
+        .flat_map(|key| key.split(','))
+        .map(str::trim)
+        .filter(|column| !column.is_empty())
+        .map(str::to_string)
+        .collect();
+    if let Some(column) = key.iter().find(|column| !columns.contains(column)) {
+        return Error::bad_request()
+            .with_message(format!("Key column '{column}' is not in table '{name}'"))
+            .with_detail("column", column.as_str())
+            .build();
+    }
+
+    let counts = ducklake.query(&format!(
+        "SELECT (SELECT COUNT(*) FROM {current}), (SELECT COUNT(*) FROM {previous})"
+    ))?;
+    let rows = counts
+        .first()
+        .into_iter()
+        .flatten()
+        .filter_map(|count| count.parse::<u64>().ok())
+        .max()
+        .unwrap_or_default();
+    if rows > max_rows {
+        return Error::bad_request()
+            .with_code(ErrorCode::DiffTooLarge)
+            .with_message(format!(
+                "Table '{name}' has {rows} rows, more than the {max_rows} a diff may compare; raise engine.max_diff_rows to allow it"
+            ))
+            .with_detail("rows", rows)
+            .with_detail("limit", max_rows)
+            .build();
+    }
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_DIFF_SAMPLE)
+        .min(MAX_DIFF_SAMPLE);
+    let diff_rows = |sql: String| -> Result<DiffRows, Error> {
+        let count = ducklake
+            .query(&format!("SELECT COUNT(*) FROM ({sql})"))?
+            .first()
+            .and_then(|row| row.first())
+            .and_then(|count| count.parse().ok())
+            .unwrap_or_default();
+        let result = ducklake.query_typed(&format!("SELECT * FROM ({sql}) LIMIT {limit}"))?;
+        let rows = masked_rows(&result, &masks);
+        Ok(DiffRows { count, rows })
+    };
+
+    let (added, removed, changed) = if key.is_empty() {
+        (
+            diff_rows(format!(
+                "SELECT * FROM {current} EXCEPT ALL SELECT * FROM {previous}"
+            ))?,
+            diff_rows(format!(
+                "SELECT * FROM {previous} EXCEPT ALL SELECT * FROM {current}"
+            ))?,
+            None,
+        )
+    } else {
+        let on = key_condition(&key, "a", "b");
+        (
+            diff_rows(format!(
+                "SELECT a.* FROM {current} AS a ANTI JOIN {previous} AS b ON {on}"
+            ))?,
+            diff_rows(format!(
+                "SELECT a.* FROM {previous} AS a ANTI JOIN {current} AS b ON {on}"
+            ))?,
+            Some(diff_rows(format!(
+                "SELECT a.* FROM (SELECT * FROM {current} EXCEPT ALL SELECT * FROM {previous}) AS a SEMI JOIN {previous} AS b ON {on}"
+            ))?),
+        )
+    };
+
+    Ok(Json(TableDiff {
+        name,
+        against_snapshot: against,
+        key,
+        columns,
+        added,
+        removed,
+        changed,
+    }))
+}
+
+// NULL keys match each other, as they would in a DISTINCT.
+fn key_condition(key: &[String], left: &str, right: &str) -> String {
+    key.iter()
+        .map(|column| {
+            let column = quote_ident(column);
+            format!("{left}.{column} IS NOT DISTINCT FROM {right}.{column}")
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+// Builds one aggregate query over `source` with the statistics of every column,
+// aliased by column position so that arbitrary column names are safe.
+fn build_profile_query(source: &str, schema: &[(String, String)]) -> String {
+    let mut aggregates = Vec::new();
+    for (i, (name, data_type)) in schema.iter().enumerate() {
+        let column = quote_ident(name);
+        aggregates.push(format!("COUNT(*) - COUNT({column}) AS nulls_{i}"));
+        aggregates.push(format!("COUNT(DISTINCT {column}) AS distinct_{i}"));
+
+        if is_orderable_type(data_type) {
+            aggregates.push(format!("MIN({column}) AS min_{i}"));
+            aggregates.push(format!("MAX({column}) AS max_{i}"));
+        }
+
+        if is_numeric_type(data_type) {
+            aggregates.push(format!("AVG({column})::DOUBLE AS mean_{i}"));
+            aggregates.push(format!("STDDEV_SAMP({column})::DOUBLE AS stddev_{i}"));
+        }
+    }
+
+    if aggregates.is_empty() {
+        return format!("SELECT COUNT(*) AS row_count FROM {source}");
+    }
+    format!("SELECT {} FROM {source}", aggregates.join(", "))
+}
+
+fn is_numeric_type(data_type: &str) -> bool {
+    const NUMERIC_TYPES: &[&str] = &[
+        "TINYINT",
+        "SMALLINT",
+        "INTEGER",
+        "BIGINT",
+        "HUGEINT",
+        "UTINYINT",
+        "USMALLINT",
+        "UINTEGER",
+        "UBIGINT",
+        "UHUGEINT",
+        "FLOAT",
+        "REAL",
+        "DOUBLE",
+    ];
+    let data_type = data_type.to_uppercase();
+    NUMERIC_TYPES.contains(&data_type.as_str()) || data_type.starts_with("DECIMAL")
+}
+
+fn is_orderable_type(data_type: &str) -> bool {
+    let data_type = data_type.to_uppercase();
+    !(data_type.ends_with(']')
+        || ["STRUCT", "MAP", "UNION"]
+            .iter()
+            .any(|nested| data_type.starts_with(nested)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{StatusCode, query::QueryResult},
+        core::{
+            config::{masking::MaskMode, project::EngineConfig},
+            ducklake::DuckLake,
+        },
+        test_helpers::TestManager,
+    };
+    use anyhow::Result;
+    use serde_json::json;
+
+    async fn create_users_table(test: &TestManager) -> Result<()> {
+        let config = test.config().await;
+        let ducklake = DuckLake::from_config(&config).await?;
+        ducklake.create_table_from_query(
+            "users",
+            "SELECT i AS id, 'user_' || i AS name FROM range(1, 601) t(i)",
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_tables() -> Result<()> {
+        let test = TestManager::new();
+        create_users_table(&test).await?;
+        let server = test.setup_server(routes);
+
+        let response = server.get("/tables").await;
+        response.assert_status_ok();
+        let tables: Vec<String> = response.json();
+        assert_eq!(tables, vec!["users".to_string()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_tables_when_pool_exhausted() -> Result<()> {
+        let test = TestManager::new();
+        test.config().await.project.engine = EngineConfig {
+            pool_size: Some(1),
+            acquire_timeout_secs: Some(1),
+            ..Default::default()
+        };
+        let server = test.setup_server(routes);
+        server.get("/tables").await.assert_status_ok();
+
+        let ducklake = test.ducklakes().current().await.unwrap();
+        let _held = ducklake.connection()?;
+        let response = server.get("/tables").await;
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "SERVER_BUSY");
+        assert!(body["message"].as_str().unwrap().starts_with("Server busy"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_table() -> Result<()> {
+        let test = TestManager::new();
+        create_users_table(&test).await?;
+        let server = test.setup_server(routes);
+
+        let response = server
+            .get("/tables/users/preview")
+            .add_query_param("limit", 2)
+            .add_query_param("offset", 1)
+            .await;
+        response.assert_status_ok();
+        let preview: TablePreview = response.json();
+        assert_eq!(
+            preview
+                .columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "name"]
+        );
+        assert_eq!(
+            preview.rows,
+            vec![
+                vec![json!(2), json!("user_2")],
+                vec![json!(3), json!("user_3")]
+            ]
+        );
+        assert!(preview.truncated);
+
+        let response = server
+            .get("/tables/users/preview")
+            .add_query_param("limit", 10_000)
+            .await;
+        response.assert_status_ok();
+        let preview: TablePreview = response.json();
+        assert_eq!(preview.limit, MAX_PREVIEW_LIMIT);
+        assert_eq!(preview.rows.len(), MAX_PREVIEW_LIMIT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_rejects_unknown_table() -> Result<()> {
+        let test = TestManager::new();
+        create_users_table(&test).await?;
+        let server = test.setup_server(routes);
+
+        let response = server
+            .get("/tables/users%22%3B%20DROP%20TABLE%20users%3B%20--/preview")
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        let response = server.get("/tables").await;
+        let tables: Vec<String> = response.json();
+        assert_eq!(tables, vec!["users".to_string()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_masked_columns() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let mut config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query(
+                "contacts",
+                "SELECT * FROM (VALUES (1, 'alice@example.com', '090-1234-5678')) t(id, email, phone)",
+            )?;
+            let model = serde_yml::from_str(
+                "sql: SELECT 1\nmasking:\n  - column: email\n    mask: partial\n  - column: phone\n    mask: hash\n",
+            )?;
+            config.models.insert("contacts".to_string(), model);
+        }
+        let server = test.setup_server(|| routes().merge(crate::api::query::routes()));
+        let phone_hash = MaskMode::Hash.apply(&json!("090-1234-5678"));
+
+        let response = server.get("/tables/contacts/preview").await;
+        response.assert_status_ok();
+        let preview: TablePreview = response.json();
+        assert_eq!(
+            preview.rows,
+            vec![vec![
+                json!(1),
+                json!("a***@example.com"),
+                phone_hash.clone()
+            ]]
+        );
+
+        let response = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT email, phone, id FROM contacts" }))
+            .await;
+        response.assert_status_ok();
+        let result: QueryResult = response.json();
+        assert_eq!(result.data["email"], vec![json!("a***@example.com")]);
+        assert_eq!(result.data["phone"], vec![phone_hash.clone()]);
+        assert_eq!(result.data["id"], vec![json!(1)]);
+
+        // Renamed, the column can't be masked by name, so the query is refused.
+        let response = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT email AS contact FROM contacts" }))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "MASKED_COLUMN");
+        assert_eq!(body["details"]["column"], "email");
+
+        // The hash is stable, so masked values still match across requests.
+        let response = server.get("/tables/contacts/preview").await;
+        let preview: TablePreview = response.json();
+        assert_eq!(preview.rows[0][2], phone_hash);
+
+        let response = server
+            .get("/tables/contacts/preview")
+            .add_query_param("unmasked", true)
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+
+        let server = test.setup_server(|| {
+            routes()
+                .merge(crate::api::query::routes())
+                .layer(Extension(Principal("admin-token".to_string())))
+        });
+        let response = server
+            .post("/query")
+            .add_query_param("unmasked", true)
+            .json(&json!({ "sql": "SELECT email FROM contacts" }))
+            .await;
+        response.assert_status_ok();
+        let result: QueryResult = response.json();
+        assert_eq!(result.data["email"], vec![json!("alice@example.com")]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_profile_table() -> Result<()> {
+        let test = TestManager::new();
+        {
+            let config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.create_table_from_query(
+                "scores",
+                "SELECT * FROM (VALUES (1, 'a', 10.0), (2, 'b', NULL), (3, 'b', 20.0), (4, NULL, 30.0)) t(id, grade, score)",
+            )?;
+        }
+        let server = test.setup_server(routes);
+
+        let response = server.get("/tables/scores/profile").await;
+        response.assert_status_ok();
+        let profile: TableProfile = response.json();
+        assert_eq!(profile.row_count, 4);
+        assert!(!profile.sampled);
+        assert_eq!(profile.columns.len(), 3);
+
+        let id = &profile.columns[0];
+        assert_eq!(id.name, "id");
+        assert_eq!(id.null_count, 0);
+        assert_eq!(id.distinct_count, 4);
+        assert_eq!(id.min, json!(1));
+        assert_eq!(id.max, json!(4));
+        assert_eq!(id.mean, Some(2.5));
+
+        let grade = &profile.columns[1];
+        assert_eq!(grade.null_count, 1);
+        assert_eq!(grade.distinct_count, 2);
+        assert_eq!(grade.min, json!("a"));
+        assert_eq!(grade.max, json!("b"));
+        assert_eq!(grade.mean, None);
+        assert_eq!(grade.stddev, None);
+
+        let score = &profile.columns[2];
+        assert_eq!(score.null_count, 1);
+        assert_eq!(score.distinct_count, 3);
+        assert_eq!(score.min, json!(10.0));
+        assert_eq!(score.max, json!(30.0));
+        assert_eq!(score.mean, Some(20.0));
+        assert_eq!(score.stddev, Some(10.0));
+
+        server
+            .get("/tables/missing/profile")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_table_snapshots_and_rollback() -> Result<()> {
+        let test = TestManager::new();
+        create_users_table(&test).await?;
+        let config = test.config().await;
+        let ducklake = DuckLake::from_config(&config).await?;
+        ducklake.insert_from_query(
+            "users",
+            "SELECT i AS id, 'user_' || i AS name FROM range(601, 701) t(i)",
+        )?;
+        let server = test.setup_server(|| routes().merge(crate::api::query::routes()));
+
+        let response = server.get("/tables/users/snapshots").await;
+        response.assert_status_ok();
+        let snapshots: Vec<SnapshotInfo> = response.json();
+        assert!(snapshots.len() >= 2);
+        let first = snapshots[0].snapshot_id;
+
+        let response = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT COUNT(*) AS n FROM users", "snapshot_id": first }))
+            .await;
+        response.assert_status_ok();
+        let result: QueryResult = response.json();
+        assert_eq!(result.data["n"][0], json!(600));
+
+        let response = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT COUNT(*) AS n FROM users" }))
+            .await;
+        response.assert_status_ok();
+        let result: QueryResult = response.json();
+        assert_eq!(result.data["n"][0], json!(700));
+
+        server
+            .post("/tables/users/rollback")
+            .json(&json!({}))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        server
+            .post("/tables/users/rollback")
+            .json(&json!({ "snapshot_id": first }))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let response = server
+            .post("/query")
+            .json(&json!({ "sql": "SELECT COUNT(*) AS n FROM users" }))
+            .await;
+        let result: QueryResult = response.json();
+        assert_eq!(result.data["n"][0], json!(600));
+
+        server
+            .get("/tables/missing/snapshots")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diff_table() -> Result<()> {
+        let test = TestManager::new();
+        create_users_table(&test).await?;
+        {
+            let config = test.config().await;
+            let ducklake = DuckLake::from_config(&config).await?;
+            ducklake.execute_batch(
+                "BEGIN; \
+                 INSERT INTO db.main.users VALUES (601, 'user_601'); \
+                 DELETE FROM db.main.users WHERE id = 1; \
+                 UPDATE db.main.users SET name = 'renamed' WHERE id = 2; \
+                 COMMIT;",
+            )?;
+        }
+        let server = test.setup_server(routes);
+
+        let response = server
+            .get("/tables/users/diff")
+            .add_query_param("key", "id")
+            .await;
+        response.assert_status_ok();
+        let diff: TableDiff = response.json();
+        assert_eq!(diff.key, vec!["id".to_string()]);
+        assert_eq!(diff.added.count, 1);
+        assert_eq!(diff.added.rows, vec![vec![json!(601), json!("user_601")]]);
+        assert_eq!(diff.removed.count, 1);
+        assert_eq!(diff.removed.rows, vec![vec![json!(1), json!("user_1")]]);
+        let changed = diff.changed.unwrap();
+        assert_eq!(changed.count, 1);
+        assert_eq!(changed.rows, vec![vec![json!(2), json!("renamed")]]);
+
+        // Without a key an update shows up as one row removed and one added.
+        let diff: TableDiff = server
+            .get("/tables/users/diff")
+            .add_query_param("against", "previous_snapshot")
+            .await
+            .json();
+        assert_eq!(diff.added.count, 2);
+        assert_eq!(diff.removed.count, 2);
+        assert!(diff.changed.is_none());
+
+        server
+            .get("/tables/users/diff")
+            .add_query_param("key", "missing")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        test.config().await.project.engine = EngineConfig {
+            max_diff_rows: Some(100),
+            ..Default::default()
+        };
+        let response = server.get("/tables/users/diff").await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["code"], "DIFF_TOO_LARGE");
+        assert_eq!(body["details"]["limit"], 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_profile_query() {
+        let schema = vec![
+            ("id".to_string(), "BIGINT".to_string()),
+            ("tags".to_string(), "VARCHAR[]".to_string()),
+        ];
+        assert_eq!(
+            build_profile_query("t", &schema),
+            "SELECT COUNT(*) - COUNT(\"id\") AS nulls_0, COUNT(DISTINCT \"id\") AS distinct_0, \
+             MIN(\"id\") AS min_0, MAX(\"id\") AS max_0, AVG(\"id\")::DOUBLE AS mean_0, \
+             STDDEV_SAMP(\"id\")::DOUBLE AS stddev_0, COUNT(*) - COUNT(\"tags\") AS nulls_1, \
+             COUNT(DISTINCT \"tags\") AS distinct_1 FROM t"
+        );
+    }
+}
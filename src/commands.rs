@@ -1,3 +1,29 @@
+use crate::core::config::Config;
+use anyhow::Result;
+use std::path::Path;
+
+pub mod admin_token;
+pub mod bundle;
+pub mod init;
 pub mod new;
-pub mod samples;
+pub mod query;
+pub mod rotate_key;
+pub mod run;
 pub mod start;
+pub mod templates;
+
+pub fn ensure_project(project_dir: &Path) -> Result<()> {
+    if !project_dir.join("project.yml").exists() {
+        return Err(anyhow::anyhow!(
+            "The directory is not a valid DuckHub project (missing project.yml)",
+        ));
+    }
+    Ok(())
+}
+
+// Loads the project `config` points at, secrets included.
+pub fn load_project(mut config: Config) -> Result<Config> {
+    ensure_project(&config.project_dir)?;
+    config.load()?;
+    Ok(config)
+}
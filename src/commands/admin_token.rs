@@ -0,0 +1,14 @@
+use super::ensure_project;
+use crate::core::config::api_token::generate_admin_token;
+use anyhow::Result;
+use std::path::Path;
+
+pub fn execute_admin_token(project_dir: &Path) -> Result<()> {
+    ensure_project(project_dir)?;
+
+    let token = generate_admin_token(project_dir)?;
+
+    println!("✓ Admin token generated (shown only once): {token}");
+    println!("  It replaces any previous admin token once the server restarts");
+    Ok(())
+}
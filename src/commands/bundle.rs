@@ -0,0 +1,53 @@
+use super::load_project;
+use crate::core::{
+    bundle::{Bundle, export_bundle},
+    config::Config,
+    graph::Graph,
+};
+use anyhow::Result;
+use std::{fs, path::Path};
+
+pub fn execute_export(project_dir: &Path, output: &Path) -> Result<()> {
+    let config = load_project(Config::new(project_dir.to_path_buf()))?;
+    let export = export_bundle(&config)?;
+    fs::write(output, &export.archive)?;
+
+    println!("✓ Exported project to {}", output.display());
+    for location in &export.stripped_secrets {
+        println!("⚠ The secret for {location} was not exported");
+    }
+    Ok(())
+}
+
+pub async fn execute_import(project_dir: &Path, bundle_path: &Path, overwrite: bool) -> Result<()> {
+    let mut config = load_project(Config::new(project_dir.to_path_buf()))?;
+    let bundle = Bundle::read(fs::File::open(bundle_path)?)?;
+
+    let collisions = bundle.collisions(&config);
+    if !collisions.is_empty() && !overwrite {
+        return Err(anyhow::anyhow!(
+            "These already exist in the project: {}. Use --overwrite to replace them",
+            collisions.join(", ")
+        ));
+    }
+
+    let summary = bundle.apply(&mut config)?;
+
+    let mut graph = Graph::load(project_dir).await?;
+    graph.rebuild(&config);
+    graph.save().await?;
+
+    println!(
+        "✓ Imported {} connection(s), {} adapter(s), {} model(s), {} seed(s), {} query(ies) and {} dashboard(s)",
+        summary.connections.len(),
+        summary.adapters.len(),
+        summary.models.len(),
+        summary.seeds.len(),
+        summary.queries.len(),
+        summary.dashboards.len()
+    );
+    for warning in &summary.warnings {
+        println!("⚠ {warning}");
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use super::{
+    ensure_project,
+    new::{create_gitignore, create_secret_key},
+};
+use crate::core::{
+    config::Config,
+    graph::{Graph, GraphDiff},
+    validation::{Severity, ValidationReport, validate_project},
+};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct InitSummary {
+    pub created_gitignore: bool,
+    pub created_secret_key: bool,
+    // Secrets that were encrypted with a key this machine does not have.
+    pub undecryptable_secrets: bool,
+    pub adapters: usize,
+    pub models: usize,
+    pub queries: usize,
+    pub dashboards: usize,
+    pub seeds: usize,
+    pub graph: GraphDiff,
+    pub report: ValidationReport,
+}
+
+// Adopts an existing project directory, e.g. a fresh git checkout. Only files
+// that are missing are created; YAML definitions are never rewritten.
+pub async fn init_project(project_dir: &Path) -> Result<InitSummary> {
+    ensure_project(project_dir)?;
+
+    let created_gitignore = !project_dir.join(".gitignore").exists();
+    if created_gitignore {
+        create_gitignore(project_dir)?;
+    }
+
+    let created_secret_key = !project_dir.join(".secret.key").exists();
+    create_secret_key(project_dir)?;
+
+    let mut config = Config::new(project_dir.to_path_buf());
+    config.load_without_secrets()?;
+    let undecryptable_secrets = config.project.has_encrypted_secrets()
+        && config.project.clone().load_secrets(project_dir).is_err();
+
+    let mut graph = Graph::load(project_dir).await?;
+    let diff = graph.rebuild(&config);
+    graph.save().await?;
+
+    let report = validate_project(&config, &graph)?;
+
+    Ok(InitSummary {
+        created_gitignore,
+        created_secret_key,
+        undecryptable_secrets,
+        adapters: config.adapters.len(),
+        models: config.models.len(),
+        queries: config.queries.len(),
+        dashboards: config.dashboards.len(),
+        seeds: config.seeds.len(),
+        graph: diff,
+        report,
+    })
+}
+
+pub async fn execute_init(project_dir: &Path) -> Result<()> {
+    let summary = init_project(project_dir).await?;
+
+    if summary.created_gitignore {
+        println!("✓ Created .gitignore");
+    }
+    if summary.created_secret_key {
+        println!("✓ Generated a new .secret.key");
+    }
+    if summary.undecryptable_secrets {
+        println!("⚠ WARNING: project.yml contains secrets encrypted with a different key.");
+        println!("  They will NOT decrypt. Copy the original .secret.key into the project,");
+        println!("  or re-enter the affected passwords and secrets.");
+    }
+
+    println!(
+        "✓ Found {} adapter(s), {} model(s), {} seed(s), {} query(ies) and {} dashboard(s)",
+        summary.adapters, summary.models, summary.seeds, summary.queries, summary.dashboards
+    );
+    println!(
+        "✓ Graph rebuilt ({} added, {} removed, {} changed)",
+        summary.graph.added.len(),
+        summary.graph.removed.len(),
+        summary.graph.changed.len()
+    );
+
+    for finding in &summary.report.findings {
+        let marker = match finding.severity {
+            Severity::Error => "✗",
+            Severity::Warning => "⚠",
+        };
+        println!("{marker} {}", finding.message);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::templates::sample::create_samples,
+        core::config::{
+            project::{ConnectionConfig, ProjectConfig, RemoteDatabaseConfig},
+            secret::SecretField,
+        },
+    };
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn copy_dir(from: &Path, to: &Path, skip: &[&str]) -> Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if skip.iter().any(|skipped| name == *skipped) {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                copy_dir(&entry.path(), &to.join(&name), skip)?;
+            } else {
+                fs::copy(entry.path(), to.join(&name))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn checkout_fixture(dir: &Path) -> Result<std::path::PathBuf> {
+        let original = dir.join("original");
+        fs::create_dir_all(&original)?;
+        let mut config = Config::new(original.clone());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        create_gitignore(&original)?;
+        create_secret_key(&original)?;
+        create_samples(&mut config).await?;
+
+        let checkout = dir.join("checkout");
+        copy_dir(
+            &original,
+            &checkout,
+            &[".data", ".secret.key", ".gitignore"],
+        )?;
+        Ok(checkout)
+    }
+
+    #[tokio::test]
+    async fn test_init_existing_project() -> Result<()> {
+        let dir = tempdir()?;
+        let checkout = checkout_fixture(dir.path()).await?;
+        let project_yml = fs::read_to_string(checkout.join("project.yml"))?;
+        let adapter_yml = fs::read_to_string(checkout.join("adapters/users.yml"))?;
+
+        let summary = init_project(&checkout).await?;
+
+        assert!(summary.created_gitignore);
+        assert!(summary.created_secret_key);
+        assert!(!summary.undecryptable_secrets);
+        assert!(checkout.join(".secret.key").exists());
+        assert!(checkout.join(".gitignore").exists());
+        assert!(summary.adapters > 0 && summary.models > 0);
+
+        let graph = Graph::load(&checkout).await?;
+        for name in ["users", "staging_app_logs", "user_activity_summary"] {
+            assert!(graph.has_node(name), "missing graph node {name}");
+        }
+        let mut dependencies = graph
+            .get_node("user_activity_summary")
+            .unwrap()
+            .dependencies
+            .clone();
+        dependencies.sort();
+        assert_eq!(dependencies, vec!["staging_app_logs", "users"]);
+
+        assert_eq!(
+            fs::read_to_string(checkout.join("project.yml"))?,
+            project_yml
+        );
+        assert_eq!(
+            fs::read_to_string(checkout.join("adapters/users.yml"))?,
+            adapter_yml
+        );
+
+        let summary = init_project(&checkout).await?;
+        assert!(!summary.created_gitignore && !summary.created_secret_key);
+        assert!(summary.graph.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_init_warns_about_foreign_secrets() -> Result<()> {
+        let dir = tempdir()?;
+        let original = dir.path().join("original");
+        fs::create_dir_all(&original)?;
+        create_secret_key(&original)?;
+
+        let mut project = ProjectConfig::default();
+        project.connections.insert(
+            "warehouse".to_string(),
+            ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+                host: "localhost".to_string(),
+                port: 5432,
+                database: "warehouse".to_string(),
+                username: "duckhub".to_string(),
+                password: SecretField::PlainText {
+                    value: "secret".to_string(),
+                },
+            }),
+        );
+        let mut config = Config::new(original.clone());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        config.load()?;
+        config.save_project(project)?;
+
+        let checkout = dir.path().join("checkout");
+        copy_dir(&original, &checkout, &[".secret.key"])?;
+
+        let summary = init_project(&checkout).await?;
+        assert!(summary.created_secret_key);
+        assert!(summary.undecryptable_secrets);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,85 @@
-use crate::core::config::secret::generate_secret_key;
+use super::templates::Template;
+use crate::core::config::{
+    Config,
+    api_token::generate_api_token,
+    project::ProjectConfig,
+    secret::{generate_secret_key, set_secret_key_permissions},
+};
 use anyhow::{Context, Result};
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub struct NewProject {
+    pub project_dir: PathBuf,
+    pub token: String,
+    // Relative to the project, sorted. Internal state under .data is left out.
+    pub files: Vec<PathBuf>,
+}
+
+pub async fn create_project(project_dir: &Path, template: Template) -> Result<NewProject> {
+    if project_dir.exists() {
+        return Err(anyhow::anyhow!(
+            "Directory '{}' already exists",
+            project_dir.display()
+        ));
+    }
+
+    fs::create_dir_all(project_dir)?;
+
+    let mut config = Config::new(project_dir.to_path_buf());
+    config
+        .add_project_setting(&ProjectConfig::default())?
+        .save()?;
+
+    create_gitignore(project_dir)?;
+    create_secret_key(project_dir)?;
+    let token = generate_api_token(project_dir)?;
+    template.template().create(&mut config).await?;
+
+    let mut files = vec![];
+    collect_files(project_dir, project_dir, &mut files)?;
+    files.sort();
+
+    Ok(NewProject {
+        project_dir: project_dir.to_path_buf(),
+        token,
+        files,
+    })
+}
+
+fn collect_files(dir: &Path, project_dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path != project_dir.join(".data") {
+                collect_files(&path, project_dir, files)?;
+            }
+        } else if let Ok(relative) = path.strip_prefix(project_dir) {
+            files.push(relative.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+pub async fn execute_new(project_name: &str, template: Template) -> Result<()> {
+    let project_dir = std::env::current_dir()?.join(project_name);
+    let project = create_project(&project_dir, template).await?;
+
+    println!("✓ Project '{project_name}' created successfully");
+    println!("  Template: {}", template.template().description());
+    for file in &project.files {
+        println!("  + {}", file.display());
+    }
+    println!("  API token (shown only once): {}", project.token);
+    println!("  Run 'duckhub start {project_name}' to open the project");
+    Ok(())
+}
 
 pub fn create_gitignore(project_dir: &Path) -> Result<()> {
-    let gitignore_content = ".secret.key\nstorage/\ndatabase.db\n.data/\nsample_data/\n";
+    let gitignore_content = ".secret.key\n.secret.key.*\n.api_token\n.admin_token\n\
+                             storage/\ndatabase.db\n.data/\nsample_data/\n";
 
     fs::write(project_dir.join(".gitignore"), gitignore_content)
         .context("Failed to write .gitignore")?;
@@ -26,22 +102,3 @@ pub fn create_secret_key(project_dir: &Path) -> Result<()> {
 
     Ok(())
 }
-
-fn set_secret_key_permissions(path: &Path) -> Result<()> {
-    let perms = {
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::Permissions::from_mode(0o600)
-        }
-        #[cfg(not(unix))]
-        {
-            let mut perms = fs::metadata(path)?.permissions();
-            perms.set_readonly(true);
-            perms
-        }
-    };
-
-    fs::set_permissions(path, perms)?;
-    Ok(())
-}
@@ -0,0 +1,217 @@
+use super::load_project;
+use crate::core::{
+    config::Config,
+    ducklake::{DuckLake, TypedResult},
+};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::{io::Write, path::Path};
+
+pub const DEFAULT_MAX_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+    Ndjson,
+}
+
+pub async fn execute_query(
+    project_dir: &Path,
+    sql: &str,
+    format: OutputFormat,
+    max_width: usize,
+    out: &mut impl Write,
+) -> Result<()> {
+    let config = load_project(Config::new(project_dir.to_path_buf()))?;
+    let ducklake = DuckLake::from_config(&config).await?;
+
+    // DuckDB errors already read well; keep them on a single line.
+    let result = ducklake
+        .query_typed(sql)
+        .map_err(|err| anyhow::anyhow!("Query failed: {err}"))?;
+
+    match format {
+        OutputFormat::Table => write_table(out, &result, max_width)?,
+        OutputFormat::Csv => write_csv(out, &result)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, &rows(&result))?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for row in rows(&result) {
+                writeln!(out, "{}", serde_json::to_string(&row)?)?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn rows(result: &TypedResult) -> Vec<serde_json::Map<String, Value>> {
+    (0..result.row_count())
+        .map(|i| {
+            result
+                .columns
+                .iter()
+                .map(|column| (column.clone(), result.data[column][i].clone()))
+                .collect()
+        })
+        .collect()
+}
+
+fn cell(result: &TypedResult, column: &str, row: usize) -> Option<String> {
+    match &result.data[column][row] {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn truncate(value: &str, max_width: usize) -> String {
+    if value.chars().count() <= max_width {
+        return value.to_string();
+    }
+    let mut truncated = value
+        .chars()
+        .take(max_width.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+fn write_table(out: &mut impl Write, result: &TypedResult, max_width: usize) -> Result<()> {
+    let max_width = max_width.max(1);
+    let header = result
+        .columns
+        .iter()
+        .map(|column| truncate(column, max_width))
+        .collect::<Vec<_>>();
+    let body = (0..result.row_count())
+        .map(|i| {
+            result
+                .columns
+                .iter()
+                .map(|column| {
+                    let value = cell(result, column, i).unwrap_or_else(|| "NULL".to_string());
+                    truncate(&value.replace('\n', " "), max_width)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            body.iter()
+                .map(|row| row[i].chars().count())
+                .chain([name.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let line = |values: &[String]| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    writeln!(out, "{}", line(&header))?;
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("-+-");
+    writeln!(out, "{separator}")?;
+    for row in &body {
+        writeln!(out, "{}", line(row))?;
+    }
+    writeln!(out, "({} row(s))", body.len())?;
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv(out: &mut impl Write, result: &TypedResult) -> Result<()> {
+    let header = result
+        .columns
+        .iter()
+        .map(|column| csv_field(column))
+        .collect::<Vec<_>>();
+    writeln!(out, "{}", header.join(","))?;
+    for i in 0..result.row_count() {
+        let row = result
+            .columns
+            .iter()
+            .map(|column| csv_field(&cell(result, column, i).unwrap_or_default()))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::project::ProjectConfig;
+    use tempfile::tempdir;
+
+    async fn run(project_dir: &Path, sql: &str, format: OutputFormat) -> Result<String> {
+        let mut out = Vec::new();
+        execute_query(project_dir, sql, format, 8, &mut out).await?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[tokio::test]
+    async fn test_query_formats() -> Result<()> {
+        let dir = tempdir()?;
+        let mut config = Config::new(dir.path().to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+
+        let sql = "SELECT * FROM (VALUES (1, 'plain', NULL), (2, 'a, \"quoted\" value', 'x')) AS t(id, name, note) ORDER BY id";
+
+        assert_eq!(
+            run(dir.path(), sql, OutputFormat::Csv).await?,
+            "id,name,note\n1,plain,\n2,\"a, \"\"quoted\"\" value\",x\n"
+        );
+
+        assert_eq!(
+            run(dir.path(), sql, OutputFormat::Table).await?,
+            "id | name     | note\n\
+             ---+----------+-----\n\
+             1  | plain    | NULL\n\
+             2  | a, \"quo… | x\n\
+             (2 row(s))\n"
+        );
+
+        let ndjson = run(dir.path(), sql, OutputFormat::Ndjson).await?;
+        let first: Value = serde_json::from_str(ndjson.lines().next().unwrap())?;
+        assert_eq!(first["id"], 1);
+        assert_eq!(first["note"], Value::Null);
+
+        let error = run(dir.path(), "SELECT * FROM missing_table", OutputFormat::Csv)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Query failed: "));
+
+        Ok(())
+    }
+}
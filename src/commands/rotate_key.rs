@@ -0,0 +1,20 @@
+use super::ensure_project;
+use crate::core::config::secret::rotate_secret_key;
+use anyhow::Result;
+use std::path::Path;
+
+pub fn execute_rotate_key(project_dir: &Path) -> Result<()> {
+    ensure_project(project_dir)?;
+
+    let rotation = rotate_secret_key(project_dir)?;
+
+    println!(
+        "✓ Secret key rotated, {} secret(s) re-encrypted",
+        rotation.rotated
+    );
+    println!(
+        "  The previous key was saved to {}",
+        rotation.backup.display()
+    );
+    Ok(())
+}
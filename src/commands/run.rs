@@ -0,0 +1,358 @@
+use super::load_project;
+use crate::core::{
+    config::Config,
+    graph::Graph,
+    pipeline::{
+        ActivePipeline, Phase, PipelineEvent, TaskStatus, run_pipeline_all, run_pipeline_node,
+    },
+};
+use anyhow::Result;
+use std::{collections::HashMap, io::Write, path::Path, sync::Arc};
+use tokio::sync::{Mutex, broadcast::error::RecvError};
+
+#[derive(Default)]
+pub struct RunOptions {
+    pub node: Option<String>,
+    pub incremental: bool,
+    pub json: bool,
+    pub max_workers: Option<usize>,
+    pub env: Option<String>,
+    pub vars: HashMap<String, String>,
+}
+
+// Runs the pipeline without the HTTP server and streams task transitions to
+// `out`. Returns whether every task completed.
+pub async fn execute_run(
+    project_dir: &Path,
+    options: RunOptions,
+    out: &mut impl Write,
+) -> Result<bool> {
+    let config =
+        load_project(Config::new(project_dir.to_path_buf()).with_env(options.env.clone()))?;
+
+    let mut graph = Graph::load(project_dir).await?;
+    graph.rebuild(&config);
+    graph.save().await?;
+
+    if let Some(node) = &options.node
+        && !graph.has_node(node)
+    {
+        return Err(anyhow::anyhow!("Node '{node}' not found"));
+    }
+
+    let RunOptions {
+        node,
+        incremental,
+        json,
+        max_workers,
+        vars,
+        ..
+    } = options;
+    let config = Arc::new(Mutex::new(config));
+    let graph = Arc::new(Mutex::new(graph));
+    let pipeline = ActivePipeline::default().claim(project_dir).await?;
+    let full_refresh = !incremental;
+
+    // Subscribed before the run starts, so no transition is missed.
+    let (snapshot, mut receiver) = pipeline.lock().await.subscribe();
+    let mut run = {
+        let pipeline = pipeline.clone();
+        tokio::spawn(async move {
+            match node {
+                Some(node) => {
+                    run_pipeline_node(
+                        config,
+                        graph,
+                        pipeline,
+                        node,
+                        full_refresh,
+                        max_workers,
+                        vars,
+                    )
+                    .await
+                }
+                None => {
+                    run_pipeline_all(config, graph, pipeline, full_refresh, max_workers, vars).await
+                }
+            }
+        })
+    };
+
+    let mut finished = None;
+    print_event(out, &snapshot, json)?;
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    print_event(out, &event, json)?;
+                    if matches!(event, PipelineEvent::PipelineCompleted) {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            // A run that errors out never publishes `PipelineCompleted`.
+            result = &mut run => {
+                while let Ok(event) = receiver.try_recv() {
+                    print_event(out, &event, json)?;
+                }
+                finished = Some(result);
+                break;
+            }
+        }
+    }
+
+    match finished {
+        Some(result) => result??,
+        None => run.await??,
+    }
+
+    let pipeline = pipeline.lock().await;
+    Ok(pipeline
+        .tasks
+        .values()
+        .all(|task| *task.phase() == Phase::Completed))
+}
+
+fn print_event(out: &mut impl Write, event: &PipelineEvent, json: bool) -> Result<()> {
+    if json {
+        writeln!(out, "{}", serde_json::to_string(event)?)?;
+    } else {
+        print_text(out, event)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn print_text(out: &mut impl Write, event: &PipelineEvent) -> Result<()> {
+    match event {
+        PipelineEvent::Snapshot { tasks, .. } => print_snapshot(out, tasks)?,
+        PipelineEvent::TaskStarted { task } => writeln!(out, "▶ {task} started")?,
+        PipelineEvent::TaskCompleted {
+            task,
+            row_count,
+            duration_ms,
+        } => writeln!(
+            out,
+            "✓ {task} completed ({row_count} row(s) in {duration_ms} ms)"
+        )?,
+        PipelineEvent::TaskFailed { task, error } => writeln!(out, "✗ {task} failed: {error}")?,
+        PipelineEvent::PipelineCompleted => writeln!(out, "Pipeline finished")?,
+    }
+    Ok(())
+}
+
+// Tasks that moved before we subscribed are reported from the snapshot.
+fn print_snapshot(out: &mut impl Write, tasks: &HashMap<String, TaskStatus>) -> Result<()> {
+    let mut names = tasks.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        match tasks[name].phase() {
+            Phase::Waiting => {}
+            Phase::Running => writeln!(out, "▶ {name} started")?,
+            Phase::Completed => writeln!(out, "✓ {name} completed")?,
+            Phase::Failed => writeln!(out, "✗ {name} failed")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{
+            config::{
+                adapter::{AdapterConfig, AdapterSource, FileConfig, FormatConfig},
+                model::ModelConfig,
+                project::{ConnectionConfig, ProjectConfig},
+            },
+            ducklake::DuckLake,
+        },
+        test_helpers::create_project,
+    };
+    use std::fs;
+    use tempfile::tempdir;
+
+    // A project that imports files/users.csv and selects the names from it.
+    fn create_users_project(project_dir: &Path) -> Result<Config> {
+        let mut project = ProjectConfig::default();
+        project.connections.insert(
+            "files".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: "./files".to_string(),
+            },
+        );
+        let mut config = create_project(project_dir, &project)?;
+        fs::create_dir_all(project_dir.join("files"))?;
+        fs::write(
+            project_dir.join("files/users.csv"),
+            "id,name\n1,Alice\n2,Bob\n",
+        )?;
+
+        let adapter = AdapterConfig {
+            connection: "files".to_string(),
+            target_table: None,
+            dedupe: None,
+            description: None,
+            source: AdapterSource::File {
+                file: FileConfig {
+                    path: "users.csv".to_string(),
+                    compression: None,
+                    max_batch_size: None,
+                    max_batch_files: None,
+                    incremental: false,
+                    import_mode: None,
+                },
+                format: FormatConfig {
+                    ty: "csv".to_string(),
+                    delimiter: Some(",".to_string()),
+                    null_value: None,
+                    has_header: Some(true),
+                    sheet: None,
+                    columns_spec: vec![],
+                    quote: None,
+                    escape: None,
+                    date_format: None,
+                    timestamp_format: None,
+                },
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        config.upsert_adapter("users", &adapter)?.save()?;
+        config
+            .upsert_model(
+                "user_names",
+                &ModelConfig {
+                    sql: "SELECT name FROM users".to_string(),
+                    ..Default::default()
+                },
+            )?
+            .save()?;
+        Ok(config)
+    }
+
+    #[tokio::test]
+    async fn test_run_pipeline() -> Result<()> {
+        let dir = tempdir()?;
+        let config = create_users_project(dir.path())?;
+
+        let mut out = Vec::new();
+        let options = RunOptions {
+            json: true,
+            max_workers: Some(1),
+            ..Default::default()
+        };
+        assert!(execute_run(dir.path(), options, &mut out).await?);
+
+        let events = String::from_utf8(out)?
+            .lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(events[0]["type"], "snapshot");
+        assert_eq!(events.last().unwrap()["type"], "pipeline_completed");
+        for task in ["users", "user_names"] {
+            assert!(
+                events
+                    .iter()
+                    .any(|event| event["type"] == "task_completed" && event["task"] == task)
+            );
+        }
+
+        let ducklake = DuckLake::from_config(&config).await?;
+        assert_eq!(
+            ducklake.query("SELECT COUNT(*) FROM user_names")?,
+            vec![vec!["2".to_string()]]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_pipeline_with_vars() -> Result<()> {
+        let dir = tempdir()?;
+        let mut config = create_users_project(dir.path())?;
+        config
+            .upsert_model(
+                "named_users",
+                &ModelConfig {
+                    sql: "SELECT * FROM {{ ref('user_names') }} WHERE name = {{ var('name') }}"
+                        .to_string(),
+                    ..Default::default()
+                },
+            )?
+            .save()?;
+
+        let options = RunOptions {
+            vars: HashMap::from([("name".to_string(), "'Bob'".to_string())]),
+            ..Default::default()
+        };
+        assert!(execute_run(dir.path(), options, &mut Vec::new()).await?);
+
+        let ducklake = DuckLake::from_config(&config).await?;
+        assert_eq!(
+            ducklake.query("SELECT name FROM named_users")?,
+            vec![vec!["Bob".to_string()]]
+        );
+
+        // Without a value the model fails and the rest of the run carries on.
+        let mut out = Vec::new();
+        assert!(!execute_run(dir.path(), RunOptions::default(), &mut out).await?);
+        let output = String::from_utf8(out)?;
+        assert!(output.contains("✓ user_names completed"));
+        assert!(output.contains("✗ named_users failed"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_pipeline_failure() -> Result<()> {
+        let dir = tempdir()?;
+        let mut config = create_users_project(dir.path())?;
+        config
+            .upsert_model(
+                "broken",
+                &ModelConfig {
+                    sql: "SELECT missing_column FROM users".to_string(),
+                    ..Default::default()
+                },
+            )?
+            .save()?;
+
+        let mut out = Vec::new();
+        let options = RunOptions {
+            node: Some("users".to_string()),
+            ..Default::default()
+        };
+        assert!(execute_run(dir.path(), options, &mut out).await?);
+        let output = String::from_utf8(out)?;
+        assert!(output.contains("✓ users completed"));
+        assert!(!output.contains("user_names"));
+
+        let mut out = Vec::new();
+        assert!(!execute_run(dir.path(), RunOptions::default(), &mut out).await?);
+        assert!(String::from_utf8(out)?.contains("✗ broken failed"));
+
+        let result = execute_run(
+            dir.path(),
+            RunOptions {
+                node: Some("unknown".to_string()),
+                ..Default::default()
+            },
+            &mut Vec::new(),
+        )
+        .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
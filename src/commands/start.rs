@@ -1,24 +1,137 @@
-use crate::core::config::Config;
+use super::load_project;
+use crate::core::{
+    config::{
+        Config,
+        api_token::{ApiToken, ensure_api_token},
+    },
+    ducklake::DuckLakeCache,
+    graph::Graph,
+    pipeline::{ActivePipeline, Pipeline},
+    reload::run_config_watcher,
+    schedule::{run_compaction_scheduler, run_scheduler},
+};
 use anyhow::Result;
-use tokio::task::JoinHandle;
+use chrono::Utc;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Mutex, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 
-pub async fn execute_start(mut config: Config, port: u16) -> Result<()> {
-    if !config.project_dir.join("project.yml").exists() {
-        return Err(anyhow::anyhow!(
-            "The directory is not a valid DuckHub project (missing project.yml)",
-        ));
+pub async fn execute_start(
+    config: Config,
+    host: &str,
+    port: u16,
+    no_auth: bool,
+    shutdown_grace: Duration,
+) -> Result<()> {
+    let config = load_project(config)?;
+
+    // Install the recorder up front so metrics from the first runs are kept.
+    crate::core::metrics::handle();
+
+    let recovered = Pipeline::recover_interrupted(&config.project_dir, Utc::now()).await?;
+    if recovered > 0 {
+        println!("✓ Marked {recovered} interrupted pipeline run(s) as failed");
+    }
+
+    let mut graph = Graph::load(&config.project_dir).await?;
+    let diff = graph.rebuild(&config);
+    if !diff.is_empty() {
+        graph.save().await?;
+        println!(
+            "✓ Graph repaired ({} added, {} removed, {} changed)",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+    }
+
+    let base_path = config.project.server.base_path();
+    let mut ui_url = format!("http://{host}:8015{base_path}/");
+    let token = if no_auth {
+        println!("⚠ Authentication is disabled; anyone who can reach the API can use it");
+        None
+    } else {
+        if let Some(token) = ensure_api_token(&config.project_dir)? {
+            println!("✓ API token generated (shown only once): {token}");
+            // The UI picks the token up from the fragment, which never reaches a server.
+            ui_url.push_str(&format!("#token={token}"));
+        }
+        Some(ApiToken::load(&config.project_dir)?)
+    };
+
+    let graph = Arc::new(Mutex::new(graph));
+    let config = Arc::new(Mutex::new(config));
+    let active = Arc::new(ActivePipeline::default());
+
+    // Attach the catalog once up front. When that fails the server still starts so the
+    // settings can be fixed, but data endpoints answer 503 instead of each retrying.
+    let ducklakes = Arc::new(DuckLakeCache::default());
+    if ducklakes.get(&config).await.is_ok() {
+        println!("✓ Catalog attached");
+    } else if let Some(failure) = ducklakes.failure().await {
+        tracing::error!(
+            catalog = %failure.catalog,
+            host = failure.host.as_deref().unwrap_or("-"),
+            error = %failure.error,
+            "Catalog unavailable, starting in degraded mode"
+        );
+        eprintln!("⚠ Catalog unavailable, data endpoints are disabled");
+        eprintln!("   catalog: {}", failure.catalog);
+        if let Some(host) = &failure.host {
+            eprintln!("   host: {host}");
+        }
+        eprintln!("   error: {}", failure.error);
+        eprintln!("   Fix the database settings, then POST {base_path}/api/project/reconnect");
     }
 
-    config.load()?;
+    let scheduler_config = config.clone();
+    let scheduler_graph = graph.clone();
+    let scheduler_active = active.clone();
+    tokio::spawn(async move {
+        run_scheduler(scheduler_config, scheduler_graph, scheduler_active).await
+    });
+
+    let compaction_config = config.clone();
+    let compaction_active = active.clone();
+    tokio::spawn(
+        async move { run_compaction_scheduler(compaction_config, compaction_active).await },
+    );
+
+    let watcher_config = config.clone();
+    let watcher_graph = graph.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run_config_watcher(watcher_config, watcher_graph).await {
+            eprintln!(
+                "⚠ Could not watch config files ({e}); use POST /api/project/reload after editing them"
+            );
+        }
+    });
 
-    let api_handle: JoinHandle<Result<()>> =
-        tokio::spawn(async move { crate::api::main(config).await });
+    let shutdown = CancellationToken::new();
+    let api_host = host.to_string();
+    let api_active = active.clone();
+    let api_shutdown = shutdown.clone();
+    let mut api_handle: JoinHandle<Result<()>> = tokio::spawn(async move {
+        crate::api::main(
+            config,
+            graph,
+            api_active,
+            ducklakes,
+            token,
+            &api_host,
+            port,
+            api_shutdown,
+        )
+        .await
+    });
 
+    let ui_host = host.to_string();
+    let ui_base_path = base_path.clone();
     let ui_handle: JoinHandle<Result<()>> =
-        tokio::spawn(async move { crate::ui::start_ui_server().await });
+        tokio::spawn(async move { crate::ui::start_ui_server(&ui_host, &ui_base_path).await });
 
     if std::env::var("MANUAL_OPEN_BROWSER").is_err() {
-        if open::that("http://localhost:8015").is_ok() {
+        if open::that(&ui_url).is_ok() {
             println!("✓ Browser opened successfully");
         } else {
             println!("⚠ Could not open browser automatically");
@@ -26,20 +139,22 @@ pub async fn execute_start(mut config: Config, port: u16) -> Result<()> {
     }
 
     println!("\n🚀 DuckHub is running!");
-    println!("   API: http://localhost:{port}");
-    println!("   UI: http://localhost:8015");
+    println!("   API: http://{host}:{port}{base_path}/api");
+    println!("   UI: http://{host}:8015{base_path}/");
     println!("\nPress Ctrl+C to stop");
 
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
+    let api_stopped = tokio::select! {
+        _ = shutdown_signal() => {
             println!("\nShutting down...");
+            false
         }
-        api_result = api_handle => {
+        api_result = &mut api_handle => {
             match api_result {
                 Ok(Ok(())) => println!("API server stopped"),
                 Ok(Err(e)) => eprintln!("API server error: {e}"),
                 Err(e) => eprintln!("API server task error: {e}"),
             }
+            true
         }
         ui_result = ui_handle => {
             match ui_result {
@@ -47,8 +162,44 @@ pub async fn execute_start(mut config: Config, port: u16) -> Result<()> {
                 Ok(Err(e)) => eprintln!("UI server error: {e}"),
                 Err(e) => eprintln!("UI server task error: {e}"),
             }
+            false
         }
+    };
+
+    // Stop accepting requests, then let the running pipeline wind down so its
+    // tasks are not cut off mid-import.
+    shutdown.cancel();
+    if let Err(e) = active.shutdown(shutdown_grace).await {
+        eprintln!("Failed to stop the running pipeline: {e}");
+    }
+    // Open event streams would otherwise keep the server alive indefinitely.
+    if !api_stopped {
+        let _ = tokio::time::timeout(shutdown_grace, api_handle).await;
     }
 
     Ok(())
 }
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
@@ -0,0 +1,127 @@
+pub mod empty;
+pub mod sample;
+pub mod warehouse;
+
+use crate::core::{config::Config, graph::Graph};
+use anyhow::Result;
+use clap::ValueEnum;
+
+// What `duckhub new` puts in a project on top of project.yml, .gitignore and the
+// keys. Templates get a loaded config and write their files through it.
+#[async_trait::async_trait]
+pub trait ProjectTemplate: Send + Sync {
+    fn description(&self) -> &'static str;
+
+    async fn create(&self, config: &mut Config) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Template {
+    Empty,
+    #[default]
+    Sample,
+    Warehouse,
+}
+
+impl Template {
+    pub fn template(self) -> &'static dyn ProjectTemplate {
+        match self {
+            Self::Empty => &empty::EmptyTemplate,
+            Self::Sample => &sample::SampleTemplate,
+            Self::Warehouse => &warehouse::WarehouseTemplate,
+        }
+    }
+}
+
+async fn build_graph(config: &Config) -> Result<()> {
+    let mut graph = Graph::load(&config.project_dir).await?;
+    graph.rebuild(config);
+    graph.save().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::new::create_project;
+    use tempfile::{TempDir, tempdir};
+
+    // The created files, relative to the project, and the project loaded back.
+    async fn create(template: Template) -> Result<(TempDir, Vec<String>, Config)> {
+        let dir = tempdir()?;
+        let project = create_project(&dir.path().join("project"), template).await?;
+        let files = project
+            .files
+            .iter()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect();
+
+        let mut config = Config::new(project.project_dir);
+        config.load()?;
+        Ok((dir, files, config))
+    }
+
+    const BASE_FILES: [&str; 4] = [".api_token", ".gitignore", ".secret.key", "project.yml"];
+
+    #[tokio::test]
+    async fn test_empty_template() -> Result<()> {
+        let (_dir, files, config) = create(Template::Empty).await?;
+        assert_eq!(files, BASE_FILES);
+        assert!(config.adapters.is_empty() && config.models.is_empty());
+
+        for subdir in ["adapters", "models", "queries", "dashboards", "seeds"] {
+            assert!(
+                config.project_dir.join(subdir).is_dir(),
+                "missing {subdir}/"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sample_template() -> Result<()> {
+        let (_dir, files, config) = create(Template::Sample).await?;
+
+        for file in BASE_FILES.iter().chain(&[
+            "adapters/users.yml",
+            "adapters/orders.yml",
+            "models/staging_app_logs.yml",
+            "queries/top_products.yml",
+            "dashboards/revenue_trend.yml",
+            "seeds/category_departments.csv",
+            "sample_data/app.db",
+            "sample_data/users.csv",
+        ]) {
+            assert!(files.iter().any(|f| f == file), "missing {file}");
+        }
+        assert_eq!(config.adapters.len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warehouse_template() -> Result<()> {
+        let (_dir, files, config) = create(Template::Warehouse).await?;
+
+        let mut expected: Vec<&str> = BASE_FILES.to_vec();
+        expected.extend([
+            "models/marts_daily_orders.yml",
+            "models/stg_customers.yml",
+            "models/stg_orders.yml",
+        ]);
+        expected.sort();
+        assert_eq!(files, expected);
+        assert!(config.adapters.is_empty());
+        assert_eq!(
+            config.models["stg_orders"].group.as_deref(),
+            Some("staging")
+        );
+        assert_eq!(
+            config.models["marts_daily_orders"].group.as_deref(),
+            Some("marts")
+        );
+
+        Ok(())
+    }
+}
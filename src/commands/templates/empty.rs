@@ -0,0 +1,17 @@
+use super::ProjectTemplate;
+use crate::core::config::Config;
+use anyhow::Result;
+
+// Only the config directories, for starting a real project from scratch.
+pub struct EmptyTemplate;
+
+#[async_trait::async_trait]
+impl ProjectTemplate for EmptyTemplate {
+    fn description(&self) -> &'static str {
+        "Empty config directories only"
+    }
+
+    async fn create(&self, config: &mut Config) -> Result<()> {
+        config.load()
+    }
+}
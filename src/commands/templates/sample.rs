@@ -1,18 +1,32 @@
-use crate::core::{
-    config::{
-        Config,
-        adapter::{AdapterConfig, AdapterSource, FileConfig, FormatConfig},
-        dashboard::{ChartConfig, ChartType, DashboardConfig},
-        model::ModelConfig,
-        project::ConnectionConfig,
-        query::QueryConfig,
-    },
-    graph::{Graph, dependent_tables},
+use super::{ProjectTemplate, build_graph};
+use crate::core::config::{
+    Config,
+    adapter::{AdapterConfig, AdapterSource, FileConfig, FormatConfig},
+    dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+    model::{Materialization, ModelConfig},
+    project::ConnectionConfig,
+    query::QueryConfig,
+    seed::SeedConfig,
 };
 use anyhow::Result;
 use rusqlite::Connection;
 use std::path::Path;
 
+// The full demo: sample files and a SQLite database, with adapters, models,
+// queries and dashboards built on them.
+pub struct SampleTemplate;
+
+#[async_trait::async_trait]
+impl ProjectTemplate for SampleTemplate {
+    fn description(&self) -> &'static str {
+        "Sample data with adapters, models, queries and dashboards"
+    }
+
+    async fn create(&self, config: &mut Config) -> Result<()> {
+        create_samples(config).await
+    }
+}
+
 pub async fn create_samples(config: &mut Config) -> Result<()> {
     config.load()?;
     create_sample_data(&config.project_dir)?;
@@ -21,7 +35,8 @@ pub async fn create_samples(config: &mut Config) -> Result<()> {
     create_sample_models(config)?;
     create_sample_queries(config)?;
     create_sample_dashboards(config)?;
-    create_sample_graph(config).await?;
+    create_sample_seeds(config)?;
+    build_graph(config).await?;
     Ok(())
 }
 
@@ -134,42 +149,80 @@ fn create_sample_adapters(config: &mut Config) -> Result<()> {
     // Users CSV adapter
     let users_config = AdapterConfig {
         connection: "local_files".to_string(),
+        target_table: None,
+        dedupe: None,
         description: Some("User data from CSV file".to_string()),
         source: AdapterSource::File {
             file: FileConfig {
                 path: "users.csv".to_string(),
                 compression: None,
                 max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
             },
             format: FormatConfig {
                 ty: "csv".to_string(),
                 delimiter: Some(",".to_string()),
                 null_value: None,
                 has_header: Some(true),
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
             },
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config.upsert_adapter("users", &users_config)?.save()?;
 
     // App logs JSON adapter
     let app_logs_config = AdapterConfig {
         connection: "local_files".to_string(),
+        target_table: None,
+        dedupe: None,
         description: Some("Application logs from JSON files".to_string()),
         source: AdapterSource::File {
             file: FileConfig {
                 path: "app_log_*.json".to_string(),
                 compression: None,
                 max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
             },
             format: FormatConfig {
                 ty: "json".to_string(),
                 delimiter: None,
                 null_value: None,
                 has_header: None,
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
             },
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config
         .upsert_adapter("app_logs", &app_logs_config)?
@@ -178,11 +231,24 @@ fn create_sample_adapters(config: &mut Config) -> Result<()> {
     // Products database adapter
     let products_config = AdapterConfig {
         connection: "sample_db".to_string(),
+        target_table: None,
+        dedupe: None,
         description: Some("Product data from database".to_string()),
         source: AdapterSource::Database {
             table_name: "products".to_string(),
+            columns: None,
+            filter: None,
+            incremental: None,
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config
         .upsert_adapter("products", &products_config)?
@@ -191,11 +257,24 @@ fn create_sample_adapters(config: &mut Config) -> Result<()> {
     // Orders database adapter
     let orders_config = AdapterConfig {
         connection: "sample_db".to_string(),
+        target_table: None,
+        dedupe: None,
         description: Some("Order data from database".to_string()),
         source: AdapterSource::Database {
             table_name: "orders".to_string(),
+            columns: None,
+            filter: None,
+            incremental: None,
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config.upsert_adapter("orders", &orders_config)?.save()?;
 
@@ -216,6 +295,16 @@ fn create_sample_models(config: &mut Config) -> Result<()> {
 FROM app_logs
 WHERE duration > 0"
             .to_string(),
+        materialization: Materialization::Table,
+        unique_key: None,
+        updated_at_column: None,
+        tests: vec![],
+        relationships: vec![],
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config
         .upsert_model("staging_app_logs", &app_logs_config)?
@@ -236,6 +325,16 @@ FROM users u
 LEFT JOIN staging_app_logs l ON u.user_id = l.user_id
 GROUP BY u.user_id, u.name, u.email"
             .to_string(),
+        materialization: Materialization::Table,
+        unique_key: None,
+        updated_at_column: None,
+        tests: vec![],
+        relationships: vec![],
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config
         .upsert_model("user_activity_summary", &user_activity_config)?
@@ -259,6 +358,16 @@ LEFT JOIN orders o ON p.product_id = o.product_id
 GROUP BY p.product_id, p.name, p.category, p.price, p.stock
 ORDER BY total_revenue DESC"
             .to_string(),
+        materialization: Materialization::Table,
+        unique_key: None,
+        updated_at_column: None,
+        tests: vec![],
+        relationships: vec![],
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     };
     config
         .upsert_model("product_performance", &product_performance_config)?
@@ -280,6 +389,9 @@ FROM product_performance
 ORDER BY total_revenue DESC
 LIMIT 5"
             .to_string(),
+        tags: vec![],
+        group: None,
+        parameters: vec![],
     };
     config
         .upsert_query("top_products", &top_products_config)?
@@ -299,6 +411,9 @@ WHERE total_actions > 0
 ORDER BY total_actions DESC
 LIMIT 10"
             .to_string(),
+        tags: vec![],
+        group: None,
+        parameters: vec![],
     };
     config
         .upsert_query("active_users", &active_users_config)?
@@ -319,6 +434,9 @@ WHERE status = 'completed'
 GROUP BY DATE(order_date)
 ORDER BY date"
             .to_string(),
+        tags: vec![],
+        group: None,
+        parameters: vec![],
     };
     config
         .upsert_query("revenue_trend", &revenue_query)?
@@ -333,6 +451,9 @@ FROM product_performance
 GROUP BY category
 ORDER BY units_sold DESC"
             .to_string(),
+        tags: vec![],
+        group: None,
+        parameters: vec![],
     };
     config
         .upsert_query("category_distribution", &category_query)?
@@ -340,12 +461,16 @@ ORDER BY units_sold DESC"
 
     let revenue_config = DashboardConfig {
         description: Some("Daily Revenue Trend".to_string()),
-        query: "revenue_trend".to_string(),
-        chart: ChartConfig {
-            chart_type: ChartType::Line,
-            x_column: "date".to_string(),
-            y_column: "daily_revenue".to_string(),
-        },
+        panels: vec![PanelConfig::new(
+            "revenue_trend",
+            ChartConfig {
+                chart_type: ChartType::Line,
+                x_column: "date".to_string(),
+                y_column: "daily_revenue".to_string(),
+            },
+        )],
+        tags: vec![],
+        group: None,
     };
     config
         .upsert_dashboard("revenue_trend", &revenue_config)?
@@ -353,12 +478,16 @@ ORDER BY units_sold DESC"
 
     let category_config = DashboardConfig {
         description: Some("Product Sales by Category".to_string()),
-        query: "category_distribution".to_string(),
-        chart: ChartConfig {
-            chart_type: ChartType::Bar,
-            x_column: "category".to_string(),
-            y_column: "units_sold".to_string(),
-        },
+        panels: vec![PanelConfig::new(
+            "category_distribution",
+            ChartConfig {
+                chart_type: ChartType::Bar,
+                x_column: "category".to_string(),
+                y_column: "units_sold".to_string(),
+            },
+        )],
+        tags: vec![],
+        group: None,
     };
     config
         .upsert_dashboard("category_distribution", &category_config)?
@@ -367,20 +496,15 @@ ORDER BY units_sold DESC"
     Ok(())
 }
 
-async fn create_sample_graph(config: &mut Config) -> Result<()> {
-    let mut graph = Graph::load(&config.project_dir).await?;
-
-    for name in config.adapters.keys() {
-        graph.create_node(name, &[]);
-    }
+fn create_sample_seeds(config: &mut Config) -> Result<()> {
+    let seeds_dir = config.project_dir.join("seeds");
+    std::fs::create_dir_all(&seeds_dir)?;
 
-    for (model_name, model_config) in &config.models {
-        let dependencies = dependent_tables(&model_config.sql).unwrap_or_default();
-
-        let dependency_refs: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
-        graph.create_node(model_name, &dependency_refs);
-    }
+    let path = seeds_dir.join("category_departments.csv");
+    std::fs::write(&path, include_str!("./samples/category_departments.csv"))?;
+    config
+        .seeds
+        .insert("category_departments".to_string(), SeedConfig { path });
 
-    graph.save().await?;
     Ok(())
 }
@@ -0,0 +1,84 @@
+use super::{ProjectTemplate, build_graph};
+use crate::core::config::{
+    Config,
+    model::{Materialization, ModelConfig},
+};
+use anyhow::Result;
+
+// Model names carry their layer as a prefix, and `group` matches it so the UI can
+// filter by layer. Table names come from model names, so the layers can't be
+// subdirectories.
+pub struct WarehouseTemplate;
+
+#[async_trait::async_trait]
+impl ProjectTemplate for WarehouseTemplate {
+    fn description(&self) -> &'static str {
+        "Staging and marts models to fill in, without sample data"
+    }
+
+    async fn create(&self, config: &mut Config) -> Result<()> {
+        config.load()?;
+
+        let models = [
+            (
+                "stg_orders",
+                "staging",
+                "Orders, cleaned and renamed from the raw source",
+                Materialization::View,
+                "-- Replace raw_orders with the table an adapter loads.
+SELECT
+    id AS order_id,
+    customer_id,
+    CAST(ordered_at AS TIMESTAMP) AS ordered_at,
+    amount
+FROM raw_orders",
+            ),
+            (
+                "stg_customers",
+                "staging",
+                "Customers, cleaned and renamed from the raw source",
+                Materialization::View,
+                "-- Replace raw_customers with the table an adapter loads.
+SELECT
+    id AS customer_id,
+    name,
+    email
+FROM raw_customers",
+            ),
+            (
+                "marts_daily_orders",
+                "marts",
+                "Orders and revenue per day",
+                Materialization::Table,
+                "SELECT
+    CAST(o.ordered_at AS DATE) AS order_date,
+    COUNT(*) AS orders,
+    COUNT(DISTINCT c.customer_id) AS customers,
+    SUM(o.amount) AS revenue
+FROM stg_orders o
+LEFT JOIN stg_customers c ON o.customer_id = c.customer_id
+GROUP BY 1",
+            ),
+        ];
+
+        for (name, group, description, materialization, sql) in models {
+            let model = ModelConfig {
+                description: Some(description.to_string()),
+                sql: sql.to_string(),
+                materialization,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: Some(group.to_string()),
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_model(name, &model)?.save()?;
+        }
+
+        build_graph(config).await
+    }
+}
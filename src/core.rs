@@ -1,6 +1,27 @@
 pub mod adapter;
+pub mod audit;
+pub mod bundle;
 pub mod config;
+pub mod data_test;
 pub mod ducklake;
+pub mod explain;
 pub mod graph;
+pub mod impact;
+pub mod maintenance;
+pub mod masking;
+pub mod metrics;
 pub mod model;
+pub mod notification;
 pub mod pipeline;
+pub mod query_cache;
+pub mod query_history;
+pub mod query_params;
+pub mod read_only;
+pub mod reload;
+pub mod run_history;
+pub mod schedule;
+pub mod seed;
+pub mod share;
+pub mod task_log;
+pub mod template;
+pub mod validation;
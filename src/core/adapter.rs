@@ -1,28 +1,270 @@
 pub mod database;
 pub mod file;
+pub mod inference;
 
 #[cfg(test)]
 pub mod test_helpers;
 
 use crate::core::{
     config::{
-        adapter::{AdapterConfig, AdapterSource},
+        adapter::{AdapterConfig, AdapterSource, DedupeConfig, SchemaChangePolicy},
         project::ConnectionConfig,
     },
-    ducklake::DuckLake,
+    ducklake::{DuckLake, TypedResult, quote_ident},
+    graph::{FileCounts, TableStats},
 };
 use anyhow::Result;
-use std::{collections::HashMap, sync::Arc};
+use database::{Attachments, ColumnInfo, TableSelection};
+use file::{
+    CsvTypes, build_import_query, build_typed_read_query, explain_read_error, load_format_extension,
+};
+use inference::InferenceReport;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+// Returned by schema validation so callers can report which columns failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaMismatch {
+    MissingColumns {
+        columns: Vec<String>,
+        location: String,
+    },
+    TypeMismatch {
+        column: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl SchemaMismatch {
+    // Fails with every column of `expected` that `available` lacks, so one check
+    // reports them all.
+    pub fn check_missing<'a>(
+        expected: impl IntoIterator<Item = &'a str>,
+        available: &[&str],
+        location: String,
+    ) -> Result<()> {
+        let columns: Vec<String> = expected
+            .into_iter()
+            .filter(|column| !available.contains(column))
+            .map(str::to_string)
+            .collect();
+        if columns.is_empty() {
+            return Ok(());
+        }
+        Err(Self::MissingColumns { columns, location }.into())
+    }
+
+    pub fn type_mismatch(column: &str, expected: &str, found: &str) -> anyhow::Error {
+        Self::TypeMismatch {
+            column: column.to_string(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+        .into()
+    }
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumns { columns, location } => match columns.as_slice() {
+                [column] => write!(f, "Column '{column}' not found in {location}"),
+                columns => write!(
+                    f,
+                    "Columns {} not found in {location}",
+                    columns
+                        .iter()
+                        .map(|column| format!("'{column}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            Self::TypeMismatch {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Column '{column}' type mismatch: expected '{expected}', found '{found}'"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+// How the source's columns differ from the table an earlier run built. Types are
+// compared as DuckDB reports them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<ColumnInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retyped: Vec<RetypedColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetypedColumn {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl SchemaDiff {
+    pub fn between(source: &[ColumnInfo], table: &[(String, String)]) -> Self {
+        let mut diff = Self::default();
+        for column in source {
+            match table.iter().find(|(name, _)| *name == column.name) {
+                None => diff.added.push(column.clone()),
+                Some((_, data_type)) if !data_type.eq_ignore_ascii_case(&column.data_type) => {
+                    diff.retyped.push(RetypedColumn {
+                        name: column.name.clone(),
+                        from: data_type.clone(),
+                        to: column.data_type.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = table
+            .iter()
+            .filter(|(name, _)| !source.iter().any(|column| column.name == *name))
+            .map(|(name, _)| name.clone())
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        if !self.added.is_empty() {
+            let added = self
+                .added
+                .iter()
+                .map(|column| format!("{} {}", column.name, column.data_type))
+                .collect::<Vec<_>>();
+            changes.push(format!("added {}", added.join(", ")));
+        }
+        if !self.removed.is_empty() {
+            changes.push(format!("removed {}", self.removed.join(", ")));
+        }
+        if !self.retyped.is_empty() {
+            let retyped = self
+                .retyped
+                .iter()
+                .map(|column| format!("{} {} -> {}", column.name, column.from, column.to))
+                .collect::<Vec<_>>();
+            changes.push(format!("retyped {}", retyped.join(", ")));
+        }
+        write!(f, "{}", changes.join("; "))
+    }
+}
+
+// Returned when `on_schema_change: fail` finds the source has drifted, so the
+// pipeline can record the diff alongside the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDrift {
+    pub table: String,
+    pub diff: SchemaDiff,
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Source schema of '{}' has changed: {}",
+            self.table, self.diff
+        )
+    }
+}
+
+impl std::error::Error for SchemaDrift {}
+
+// How far a file import has got. Byte counts are only known when every file is
+// local; remote sources report files alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub files_done: usize,
+    pub files_total: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_done: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_total: Option<u64>,
+}
+
+// Receives progress as an import works through its batches. Imports run DuckDB
+// statements synchronously, so the callback is synchronous too.
+#[derive(Clone, Default)]
+pub struct ImportProgress(Option<Arc<dyn Fn(&Progress) + Send + Sync>>);
+
+impl ImportProgress {
+    pub fn new(report: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(report)))
+    }
+
+    pub fn report(&self, progress: &Progress) {
+        if let Some(report) = &self.0 {
+            report(progress);
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Adapter {
     config: AdapterConfig,
     ducklake: Arc<DuckLake>,
+    project_dir: Option<PathBuf>,
+    full_refresh: bool,
+    attachments: Option<Arc<Attachments>>,
+    progress: ImportProgress,
 }
 
 impl Adapter {
     pub fn new(config: AdapterConfig, ducklake: Arc<DuckLake>) -> Self {
-        Self { config, ducklake }
+        Self {
+            config,
+            ducklake,
+            project_dir: None,
+            full_refresh: false,
+            attachments: None,
+            progress: ImportProgress::default(),
+        }
+    }
+
+    // Incremental imports keep their state under the project directory.
+    pub fn with_project_dir(mut self, project_dir: &Path) -> Self {
+        self.project_dir = Some(project_dir.to_path_buf());
+        self
+    }
+
+    pub fn with_full_refresh(mut self, full_refresh: bool) -> Self {
+        self.full_refresh = full_refresh;
+        self
+    }
+
+    // Database imports reuse the pipeline's attachments instead of attaching their own.
+    pub fn with_attachments(mut self, attachments: &Arc<Attachments>) -> Self {
+        self.attachments = Some(attachments.clone());
+        self
+    }
+
+    pub fn with_progress(mut self, progress: ImportProgress) -> Self {
+        self.progress = progress;
+        self
     }
 }
 
@@ -32,7 +274,7 @@ impl Adapter {
         connections: &HashMap<String, ConnectionConfig>,
     ) -> Result<()> {
         match &self.config.source {
-            AdapterSource::Database { table_name } => {
+            AdapterSource::Database { table_name, .. } => {
                 let database_adapter =
                     self.database_adapter(&self.config.connection, connections)?;
                 database_adapter.validate_schema(table_name, &self.config.columns)
@@ -58,7 +300,7 @@ impl Adapter {
         connections: &HashMap<String, ConnectionConfig>,
     ) -> Result<Vec<database::ColumnInfo>> {
         match &self.config.source {
-            AdapterSource::Database { table_name } => {
+            AdapterSource::Database { table_name, .. } => {
                 let database_adapter =
                     self.database_adapter(&self.config.connection, connections)?;
                 database_adapter.get_table_schema(table_name)
@@ -77,24 +319,263 @@ impl Adapter {
         }
     }
 
-    pub async fn import(
+    // Samples the first file twice, once with detected types and once as the text
+    // in the file, and flags the columns where detection looks wrong. Database
+    // sources declare their own types, so there is nothing to report for them.
+    pub async fn inference_report(
+        &self,
+        connections: &HashMap<String, ConnectionConfig>,
+        sample_rows: usize,
+    ) -> Result<Option<InferenceReport>> {
+        let AdapterSource::File { file, format } = &self.config.source else {
+            return Ok(None);
+        };
+
+        let file_adapter = self.file_adapter(&self.config.connection, connections)?;
+        let file_paths = file_adapter.list_files(&file.path).await?;
+        let Some(first) = file_paths.first() else {
+            return Ok(None);
+        };
+
+        file_adapter.configure_access().await?;
+        load_format_extension(&self.ducklake, format)?;
+        let sample = |csv_types: &CsvTypes| -> Result<TypedResult> {
+            let query = build_typed_read_query(format, &format!("'{first}'"), csv_types)?;
+            explain_read_error(
+                format,
+                self.ducklake
+                    .query_typed(&format!("SELECT * FROM ({query}) LIMIT {sample_rows}")),
+            )
+        };
+
+        let typed = sample(&CsvTypes::Detect)?;
+        // Only CSV can be re-read as text; other formats keep the values they store.
+        let raw = if format.ty == "csv" {
+            Some(sample(&CsvTypes::AllText)?)
+        } else {
+            None
+        };
+
+        Ok(Some(inference::inspect(&typed, raw.as_ref())))
+    }
+
+    // Checks that the dedupe columns exist in what the adapter imports: the configured
+    // columns when it projects them, otherwise the source itself. Sources without
+    // files yet are skipped, since the import would fail on them anyway.
+    pub async fn validate_dedupe(
         &self,
-        table_name: &str,
         connections: &HashMap<String, ConnectionConfig>,
     ) -> Result<()> {
+        let Some(dedupe) = &self.config.dedupe else {
+            return Ok(());
+        };
+        dedupe.validate()?;
+
+        let (available, location) = if self.config.projects_columns() {
+            let columns = self
+                .config
+                .columns
+                .iter()
+                .map(|column| column.output_name().to_string())
+                .collect::<Vec<_>>();
+            (columns, "adapter columns".to_string())
+        } else {
+            match &self.config.source {
+                AdapterSource::Database { table_name, .. } => {
+                    let adapter = self.database_adapter(&self.config.connection, connections)?;
+                    adapter.attach()?;
+                    let schema = adapter.get_table_schema(table_name);
+                    let detached = adapter.detach();
+                    let schema = schema?;
+                    detached?;
+
+                    let selection = TableSelection::from_source(&self.config.source);
+                    let columns = schema
+                        .into_iter()
+                        .map(|info| info.name)
+                        .filter(|name| {
+                            selection.columns.is_empty() || selection.columns.contains(name)
+                        })
+                        .collect();
+                    (columns, format!("table '{table_name}'"))
+                }
+                AdapterSource::File { file, .. } => {
+                    let file_adapter = self.file_adapter(&self.config.connection, connections)?;
+                    let file_paths = file_adapter.list_files(&file.path).await?;
+                    let Some(first) = file_paths.first() else {
+                        return Ok(());
+                    };
+                    let schema = file_adapter.get_file_schema(first).await?;
+                    let columns = schema.into_iter().map(|info| info.name).collect();
+                    (columns, format!("file '{first}'"))
+                }
+            }
+        };
+
+        let available: Vec<&str> = available.iter().map(String::as_str).collect();
+        SchemaMismatch::check_missing(dedupe.columns(), &available, location)
+    }
+
+    // Reads the first `limit` rows the adapter would import, without creating a table.
+    // Only the first file matching a glob is read.
+    pub async fn preview(
+        &self,
+        connections: &HashMap<String, ConnectionConfig>,
+        limit: usize,
+    ) -> Result<TypedResult> {
         match &self.config.source {
-            AdapterSource::File { .. } => {
-                let adapter = self.file_adapter(&self.config.connection, connections)?;
-                self.file_import(table_name, adapter).await
+            AdapterSource::File { file, format } => {
+                let file_adapter = self.file_adapter(&self.config.connection, connections)?;
+                let file_paths = file_adapter.list_files(&file.path).await?;
+                if file_paths.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "No files found matching pattern: {}",
+                        file.path
+                    ));
+                }
+
+                file_adapter.configure_access().await?;
+                load_format_extension(&self.ducklake, format)?;
+                let query = build_import_query(&self.config, &file_paths[..1])?;
+                explain_read_error(
+                    format,
+                    self.ducklake
+                        .query_typed(&format!("SELECT * FROM ({query}) LIMIT {limit}")),
+                )
             }
-            AdapterSource::Database { .. } => {
+            AdapterSource::Database { table_name, .. } => {
                 let adapter = self.database_adapter(&self.config.connection, connections)?;
-                self.database_import(table_name, adapter).await
+                let query = TableSelection::from_source(&self.config.source)
+                    .select_from(&adapter.relation(table_name));
+
+                adapter.attach()?;
+                let result = self
+                    .ducklake
+                    .query_typed(&format!("SELECT * FROM ({query}) LIMIT {limit}"));
+                let detached = adapter.detach();
+                let result = result?;
+                detached?;
+                Ok(result)
+            }
+        }
+    }
+
+    // Compares `source` with the table an earlier run built and applies the adapter's
+    // `on_schema_change` policy. A full refresh rebuilds the table from scratch, so
+    // whatever shape the source has now is accepted.
+    fn apply_schema_policy(
+        &self,
+        table_name: &str,
+        source: &[ColumnInfo],
+    ) -> Result<Option<SchemaDiff>> {
+        if self.full_refresh || !self.ducklake.table_exists(table_name)? {
+            return Ok(None);
+        }
+
+        let diff = SchemaDiff::between(source, &self.ducklake.table_schema(table_name)?);
+        if diff.is_empty() {
+            return Ok(None);
+        }
+
+        match self.config.schema_change_policy() {
+            SchemaChangePolicy::Fail => {
+                return Err(SchemaDrift {
+                    table: table_name.to_string(),
+                    diff,
+                }
+                .into());
+            }
+            SchemaChangePolicy::AppendNewColumns => {
+                for column in &diff.added {
+                    self.ducklake
+                        .add_column(table_name, &column.name, &column.data_type)?;
+                }
             }
+            SchemaChangePolicy::Ignore => {}
         }
+
+        tracing::info!("Source schema of '{table_name}' has changed: {diff}");
+        Ok(Some(diff))
+    }
+
+    pub async fn import(
+        &self,
+        table_name: &str,
+        connections: &HashMap<String, ConnectionConfig>,
+    ) -> Result<TableStats> {
+        let started = Instant::now();
+
+        let imported = match &self.config.source {
+            AdapterSource::File { .. } => {
+                let adapter = self.file_adapter(&self.config.connection, connections)?;
+                self.file_import(table_name, adapter).await?
+            }
+            AdapterSource::Database { .. } => match &self.attachments {
+                Some(attachments) => {
+                    self.shared_database_import(table_name, connections, attachments)
+                        .await?
+                }
+                None => {
+                    let adapter = self.database_adapter(&self.config.connection, connections)?;
+                    self.database_import(table_name, adapter).await?
+                }
+            },
+        };
+
+        let mut stats = TableStats::collect(&self.ducklake, table_name, started)?;
+        stats.duplicates_dropped = imported.duplicates_dropped;
+        stats.files = imported.files;
+        stats.schema_changes = imported.schema_changes;
+        Ok(stats)
     }
 }
 
+// What an import did besides loading the rows.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub files: Option<FileCounts>,
+    pub schema_changes: Option<SchemaDiff>,
+    pub duplicates_dropped: u64,
+}
+
+// `relation` is a table or a parenthesized query.
+pub fn dedupe_query(dedupe: &DedupeConfig, relation: &str) -> String {
+    let Some(order_by) = &dedupe.order_by else {
+        return format!("SELECT DISTINCT * FROM {relation}");
+    };
+    format!(
+        "SELECT * FROM {relation} QUALIFY row_number() OVER (PARTITION BY {} ORDER BY {} DESC) = 1",
+        dedupe_key(dedupe),
+        quote_ident(order_by)
+    )
+}
+
+// Counts the rows `dedupe_query` would drop from `query`, in a single scan of it.
+pub fn count_duplicates(ducklake: &DuckLake, dedupe: &DedupeConfig, query: &str) -> Result<u64> {
+    let kept = if dedupe.order_by.is_some() {
+        format!("row({})", dedupe_key(dedupe))
+    } else {
+        "source".to_string()
+    };
+    let result = ducklake.query(&format!(
+        "SELECT count(*) - count(DISTINCT {kept}) FROM ({query}) source"
+    ))?;
+    Ok(result
+        .first()
+        .and_then(|row| row.first())
+        .and_then(|count| count.parse().ok())
+        .unwrap_or_default())
+}
+
+fn dedupe_key(dedupe: &DedupeConfig) -> String {
+    dedupe
+        .unique_key
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub fn adapter_from_connection(
     name: &str,
     connections: &HashMap<String, ConnectionConfig>,
@@ -1,30 +1,102 @@
-use super::{Adapter, adapter_from_connection};
-use crate::core::config::{
-    adapter::{AdapterSource, ColumnConfig},
-    project::ConnectionConfig,
+use super::{
+    Adapter, Imported, SchemaMismatch, adapter_from_connection, count_duplicates, dedupe_query,
 };
-use anyhow::Result;
+use crate::core::{
+    config::{
+        adapter::{AdapterSource, ColumnConfig, DedupeConfig, IncrementalConfig},
+        project::ConnectionConfig,
+    },
+    ducklake::{DuckLake, quote_ident, quote_literal},
+};
+use anyhow::{Context, Result};
 use mysql::MysqlAdapter;
 use postgresql::PostgresqlAdapter;
 use serde::{Deserialize, Serialize};
 use sqlite::SqliteAdapter;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use utoipa::ToSchema;
 
 pub mod mysql;
 pub mod postgresql;
 pub mod sqlite;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct ColumnInfo {
     pub name: String,
     pub data_type: String,
 }
 
+// The projection, row filter and deduplication of a database import.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableSelection {
+    pub columns: Vec<String>,
+    pub filter: Option<String>,
+    pub dedupe: Option<DedupeConfig>,
+}
+
+impl TableSelection {
+    pub fn from_source(source: &AdapterSource) -> Self {
+        match source {
+            AdapterSource::Database {
+                columns, filter, ..
+            } => Self {
+                columns: columns.clone().unwrap_or_default(),
+                filter: filter.clone().filter(|f| !f.trim().is_empty()),
+                dedupe: None,
+            },
+            AdapterSource::File { .. } => Self::default(),
+        }
+    }
+
+    pub fn select_from(&self, relation: &str) -> String {
+        let projection = if self.columns.is_empty() {
+            "*".to_string()
+        } else {
+            self.columns
+                .iter()
+                .map(|column| quote_ident(column))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let query = match &self.filter {
+            Some(filter) => format!("SELECT {projection} FROM {relation} WHERE ({filter})"),
+            None => format!("SELECT {projection} FROM {relation}"),
+        };
+        match &self.dedupe {
+            Some(dedupe) => dedupe_query(dedupe, &format!("({query})")),
+            None => query,
+        }
+    }
+
+    // ANDs `predicate` onto the configured filter.
+    pub fn with_filter(mut self, predicate: String) -> Self {
+        self.filter = Some(match self.filter {
+            Some(filter) => format!("({filter}) AND {predicate}"),
+            None => predicate,
+        });
+        self
+    }
+}
+
 pub trait DatabaseAdapter: Send + Sync {
     fn attach(&self) -> Result<()>;
     fn detach(&self) -> Result<()>;
     fn table_exists(&self, table: &str) -> Result<bool>;
-    fn import_table(&self, source_table: &str, target_table: &str) -> Result<()>;
+    // How `table` is referred to in DuckDB once the database is attached.
+    fn relation(&self, table: &str) -> String;
+    fn import_table(
+        &self,
+        source_table: &str,
+        target_table: &str,
+        selection: &TableSelection,
+    ) -> Result<()>;
     fn get_table_schema(&self, table: &str) -> Result<Vec<ColumnInfo>>;
     fn validate_schema(&self, table: &str, expected_columns: &[ColumnConfig]) -> Result<()>;
 }
@@ -60,23 +132,476 @@ impl Adapter {
         &self,
         table_name: &str,
         adapter: Box<dyn DatabaseAdapter>,
+    ) -> Result<Imported> {
+        adapter.attach()?;
+        let result = self.import_attached(adapter.as_ref(), table_name);
+        // Always detach, even when the import failed, so the alias is released.
+        let detached = adapter.detach();
+        let imported = result?;
+        detached?;
+        Ok(imported)
+    }
+
+    // Imports through a database the pipeline has already attached; the
+    // attachment outlives this import and is released by `Attachments`.
+    pub async fn shared_database_import(
+        &self,
+        table_name: &str,
+        connections: &HashMap<String, ConnectionConfig>,
+        attachments: &Attachments,
+    ) -> Result<Imported> {
+        let adapter = attachments.get_or_attach(&self.config.connection, || {
+            self.database_adapter(&self.config.connection, connections)
+        })?;
+        self.import_attached(adapter.as_ref(), table_name)
+    }
+
+    // Checks that every projected column exists in the source table.
+    pub fn validate_selection(
+        &self,
+        connections: &HashMap<String, ConnectionConfig>,
     ) -> Result<()> {
+        let AdapterSource::Database { table_name, .. } = &self.config.source else {
+            return Ok(());
+        };
+        let selection = TableSelection::from_source(&self.config.source);
+        if selection.columns.is_empty() {
+            return Ok(());
+        }
+
+        let adapter = self.database_adapter(&self.config.connection, connections)?;
         adapter.attach()?;
-        let source_table = if let AdapterSource::Database { table_name, .. } = &self.config.source {
-            table_name
-        } else {
+        let schema = adapter.get_table_schema(table_name);
+        let detached = adapter.detach();
+        let schema = schema?;
+        detached?;
+
+        let names: Vec<&str> = schema.iter().map(|info| info.name.as_str()).collect();
+        SchemaMismatch::check_missing(
+            selection.columns.iter().map(String::as_str),
+            &names,
+            format!("table '{table_name}'"),
+        )
+    }
+
+    fn import_attached(&self, adapter: &dyn DatabaseAdapter, table_name: &str) -> Result<Imported> {
+        let AdapterSource::Database {
+            table_name: source_table,
+            incremental,
+            ..
+        } = &self.config.source
+        else {
             return Err(anyhow::anyhow!("Adapter source is not a database"));
         };
-        if adapter.table_exists(source_table)? {
-            adapter.import_table(source_table, table_name)?;
-            adapter.detach()?;
-            Ok(())
-        } else {
-            adapter.detach()?;
-            Err(anyhow::anyhow!(
+
+        if !adapter.table_exists(source_table)? {
+            return Err(anyhow::anyhow!(
                 "Source table '{}' does not exist in the database",
                 source_table
-            ))
+            ));
         }
+        let selection = TableSelection::from_source(&self.config.source);
+
+        // Described through DuckDB so the types match what the import creates,
+        // rather than the source database's own type names.
+        let schema_changes = if self.config.detects_schema_changes() {
+            let schema = self
+                .ducklake
+                .query_schema(&selection.select_from(&adapter.relation(source_table)))?
+                .into_iter()
+                .map(|(name, data_type)| ColumnInfo { name, data_type })
+                .collect::<Vec<_>>();
+            self.apply_schema_policy(table_name, &schema)?
+        } else {
+            None
+        };
+
+        let relation = adapter.relation(source_table);
+        let duplicates_dropped = match incremental {
+            Some(incremental)
+                if !self.full_refresh && self.ducklake.table_exists(table_name)? =>
+            {
+                self.merge_incremental(&relation, table_name, selection, incremental)?
+            }
+            // A first or full import has nothing to delete, so flagged rows are
+            // simply left out.
+            Some(IncrementalConfig {
+                deleted_flag: Some(flag),
+                ..
+            }) => {
+                let selection = selection.with_filter(format!(
+                    "NOT coalesce(CAST({} AS BOOLEAN), false)",
+                    quote_ident(flag)
+                ));
+                let (selection, dropped) = self.deduplicate(selection, &relation)?;
+                adapter.import_table(source_table, table_name, &selection)?;
+                dropped
+            }
+            _ => {
+                let (selection, dropped) = self.deduplicate(selection, &relation)?;
+                adapter.import_table(source_table, table_name, &selection)?;
+                dropped
+            }
+        };
+        Ok(Imported {
+            files: None,
+            schema_changes,
+            duplicates_dropped,
+        })
+    }
+
+    // Applies the adapter's `dedupe` to `selection`, counting the rows it drops.
+    fn deduplicate(
+        &self,
+        selection: TableSelection,
+        relation: &str,
+    ) -> Result<(TableSelection, u64)> {
+        let Some(dedupe) = &self.config.dedupe else {
+            return Ok((selection, 0));
+        };
+        let dropped = count_duplicates(&self.ducklake, dedupe, &selection.select_from(relation))?;
+        let selection = TableSelection {
+            dedupe: Some(dedupe.clone()),
+            ..selection
+        };
+        Ok((selection, dropped))
+    }
+
+    // Merges the source rows at or after the target's latest `cursor_column` value.
+    // Rows sharing that value may have arrived after the last run, and merging one
+    // again just replaces it by its unique key. The bound is inlined as a literal so
+    // it reaches the source database's scan.
+    // Returns the number of duplicate rows dropped.
+    fn merge_incremental(
+        &self,
+        relation: &str,
+        table_name: &str,
+        selection: TableSelection,
+        incremental: &IncrementalConfig,
+    ) -> Result<u64> {
+        let cursor = quote_ident(&incremental.cursor_column);
+        let Some((_, cursor_type)) = self
+            .ducklake
+            .table_schema(table_name)?
+            .into_iter()
+            .find(|(name, _)| name == &incremental.cursor_column)
+        else {
+            return Err(anyhow::anyhow!(
+                "Cursor column '{}' does not exist in table '{table_name}'",
+                incremental.cursor_column
+            ));
+        };
+
+        let latest = self.ducklake.query(&format!(
+            "SELECT count({cursor}), max({cursor})::VARCHAR FROM {}",
+            quote_ident(table_name)
+        ))?;
+        // With no cursor values yet, every source row is newer.
+        let selection = match latest.first().map(|row| (row[0].as_str(), &row[1])) {
+            Some((count, max)) if count != "0" => selection.with_filter(format!(
+                "{cursor} >= CAST({} AS {cursor_type})",
+                quote_literal(max)
+            )),
+            _ => selection,
+        };
+
+        let (selection, dropped) = self.deduplicate(selection, relation)?;
+        self.ducklake.merge_from_query(
+            table_name,
+            &selection.select_from(relation),
+            &incremental.unique_key,
+            incremental.deleted_flag.as_deref(),
+        )?;
+        Ok(dropped)
+    }
+}
+
+// A remote database attached through one of DuckDB's scanner extensions. Attached
+// databases are shared by every connection in the pool, so each attachment gets an
+// alias of its own and can be attached alongside any other.
+pub struct RemoteAttachment {
+    ducklake: Arc<DuckLake>,
+    // The extension to load, which is also the ATTACH type.
+    extension: &'static str,
+    // How the database is named in errors.
+    label: &'static str,
+    alias: String,
+}
+
+impl RemoteAttachment {
+    pub fn new(ducklake: Arc<DuckLake>, extension: &'static str, label: &'static str) -> Self {
+        let alias = DuckLake::generate_temp_table_name(&format!("{extension}_db"));
+        Self {
+            ducklake,
+            extension,
+            label,
+            alias,
+        }
+    }
+
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
+
+    pub fn attach(&self, connection_params: &str) -> Result<()> {
+        self.ducklake.load_extension(self.extension)?;
+
+        let attach_query = format!(
+            "ATTACH '{}' AS {} (TYPE {});",
+            connection_params, self.alias, self.extension
+        );
+        self.ducklake.execute_batch(&attach_query).with_context(|| {
+            format!(
+                "Failed to attach {} database. Query: {attach_query}",
+                self.label
+            )
+        })
+    }
+
+    pub fn detach(&self) -> Result<()> {
+        let detach_query = format!("DETACH {}", self.alias);
+        self.ducklake
+            .execute_batch(&detach_query)
+            .with_context(|| format!("Failed to detach {} database: {}", self.label, self.alias))
+    }
+
+    pub fn relation(&self, table: &str) -> String {
+        format!("{}.{}", self.alias, table)
+    }
+}
+
+// The external databases a pipeline has attached, keyed by connection name. Each
+// is attached once, on first use, shared by every task importing from that
+// connection, and detached by `detach_all` when the pipeline ends.
+#[derive(Default)]
+pub struct Attachments {
+    attached: Mutex<HashMap<String, Arc<dyn DatabaseAdapter>>>,
+    attach_count: AtomicUsize,
+}
+
+impl Attachments {
+    pub fn get_or_attach(
+        &self,
+        connection: &str,
+        create: impl FnOnce() -> Result<Box<dyn DatabaseAdapter>>,
+    ) -> Result<Arc<dyn DatabaseAdapter>> {
+        // Held while attaching so concurrent tasks cannot attach the same database twice.
+        let mut attached = self.attached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(adapter) = attached.get(connection) {
+            return Ok(adapter.clone());
+        }
+
+        let adapter: Arc<dyn DatabaseAdapter> = Arc::from(create()?);
+        adapter.attach()?;
+        self.attach_count.fetch_add(1, Ordering::Relaxed);
+        attached.insert(connection.to_string(), adapter.clone());
+        Ok(adapter)
+    }
+
+    pub fn attach_count(&self) -> usize {
+        self.attach_count.load(Ordering::Relaxed)
+    }
+
+    // Detaches everything, reporting the first failure after trying them all.
+    pub fn detach_all(&self) -> Result<()> {
+        let attached =
+            std::mem::take(&mut *self.attached.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut result = Ok(());
+        for (connection, adapter) in attached {
+            if let Err(e) = adapter.detach()
+                && result.is_ok()
+            {
+                result = Err(e.context(format!("Failed to detach connection '{connection}'")));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        adapter::test_helpers::{setup_mysql_test_data, setup_test_ducklake, test_encrypted_field},
+        config::{adapter::AdapterConfig, project::RemoteDatabaseConfig},
+        ducklake::DuckLake,
+    };
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn mysql_connections() -> HashMap<String, ConnectionConfig> {
+        let mut connections = HashMap::new();
+        connections.insert(
+            "mysql".to_string(),
+            ConnectionConfig::MySql(RemoteDatabaseConfig {
+                host: "localhost".to_string(),
+                port: 3307,
+                database: "datasource_test".to_string(),
+                username: "datasource".to_string(),
+                password: test_encrypted_field("datasourcepass"),
+            }),
+        );
+        connections
+    }
+
+    fn mysql_adapter(ducklake: Arc<DuckLake>, table_name: &str) -> Adapter {
+        Adapter::new(
+            AdapterConfig {
+                connection: "mysql".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: table_name.to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: None,
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            },
+            ducklake,
+        )
+    }
+
+    fn attached_mysql_databases(ducklake: &DuckLake) -> Result<Vec<String>> {
+        let result = ducklake.query(
+            "SELECT database_name FROM duckdb_databases() WHERE database_name LIKE 'mysql_db%'",
+        )?;
+        Ok(result.into_iter().map(|row| row[0].clone()).collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_database_imports() -> Result<()> {
+        let tempdir = tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+
+        setup_mysql_test_data().await?;
+
+        let tasks: Vec<_> = ["first_import", "second_import"]
+            .into_iter()
+            .map(|target| {
+                let adapter = mysql_adapter(ducklake.clone(), "test_table");
+                let connections = mysql_connections();
+                tokio::spawn(async move { adapter.import(target, &connections).await })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await??;
+        }
+
+        assert!(ducklake.table_exists("first_import")?);
+        assert!(ducklake.table_exists("second_import")?);
+        assert!(attached_mysql_databases(&ducklake)?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_import_detaches_on_failure() -> Result<()> {
+        let tempdir = tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+
+        setup_mysql_test_data().await?;
+
+        let adapter = mysql_adapter(ducklake.clone(), "nonexistent_table");
+        let result = adapter.import("imported", &mysql_connections()).await;
+
+        assert!(result.is_err());
+        assert!(attached_mysql_databases(&ducklake)?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_incremental_database_import() -> Result<()> {
+        let tempdir = tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+
+        let db_path = tempdir.path().join("orders.db");
+        let source = rusqlite::Connection::open(&db_path)?;
+        source.execute_batch(
+            "CREATE TABLE orders (order_id INTEGER, amount INTEGER, updated_at TEXT, deleted INTEGER);
+             INSERT INTO orders VALUES
+                 (1, 10, '2024-01-01 00:00:00', 0),
+                 (2, 20, '2024-01-01 00:00:00', 0),
+                 (3, 30, '2023-12-31 00:00:00', 0),
+                 (4, 40, '2024-01-01 00:00:00', 1);",
+        )?;
+
+        let connections = HashMap::from([(
+            "sqlite".to_string(),
+            ConnectionConfig::Sqlite {
+                path: db_path.to_string_lossy().to_string(),
+            },
+        )]);
+        let adapter = Adapter::new(
+            AdapterConfig {
+                connection: "sqlite".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: "orders".to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: Some(IncrementalConfig {
+                        cursor_column: "updated_at".to_string(),
+                        unique_key: vec!["order_id".to_string()],
+                        deleted_flag: Some("deleted".to_string()),
+                    }),
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            },
+            ducklake.clone(),
+        );
+        let orders = || ducklake.query("SELECT order_id, amount FROM orders ORDER BY order_id");
+
+        // The first run creates the table; rows already deleted are left out.
+        adapter.import("orders", &connections).await?;
+        assert_eq!(
+            orders()?,
+            vec![vec!["1", "10"], vec!["2", "20"], vec!["3", "30"]]
+        );
+
+        source.execute_batch(
+            "UPDATE orders SET amount = 15, updated_at = '2024-01-02 00:00:00' WHERE order_id = 1;
+             UPDATE orders SET deleted = 1, updated_at = '2024-01-02 00:00:00' WHERE order_id = 2;
+             UPDATE orders SET amount = 35 WHERE order_id = 3;
+             INSERT INTO orders VALUES
+                 (5, 50, '2024-01-02 00:00:00', 0),
+                 (6, 60, '2024-01-01 00:00:00', 0);",
+        )?;
+
+        // Order 3 changed without moving its cursor past the last run, so it keeps the
+        // imported value. Order 6 arrived late with the latest imported cursor value.
+        adapter.import("orders", &connections).await?;
+        assert_eq!(
+            orders()?,
+            vec![
+                vec!["1", "15"],
+                vec!["3", "30"],
+                vec!["5", "50"],
+                vec!["6", "60"]
+            ]
+        );
+
+        Ok(())
     }
 }
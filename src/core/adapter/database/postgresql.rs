@@ -1,68 +1,58 @@
 use crate::core::{
+    adapter::SchemaMismatch,
     config::{adapter::ColumnConfig, project::RemoteDatabaseConfig},
     ducklake::DuckLake,
 };
 use anyhow::{Context, Result};
+use duckdb::types::Value;
 use std::sync::Arc;
 
-use super::{ColumnInfo, DatabaseAdapter};
+use super::{ColumnInfo, DatabaseAdapter, RemoteAttachment, TableSelection};
 
 pub struct PostgresqlAdapter {
     ducklake: Arc<DuckLake>,
     config: RemoteDatabaseConfig,
+    attachment: RemoteAttachment,
 }
 
 impl PostgresqlAdapter {
-    const ALIAS: &'static str = "postgres_db";
     pub fn new(ducklake: Arc<DuckLake>, config: RemoteDatabaseConfig) -> Self {
-        Self { ducklake, config }
+        let attachment = RemoteAttachment::new(ducklake.clone(), "postgres", "PostgreSQL");
+        Self {
+            ducklake,
+            config,
+            attachment,
+        }
     }
 }
 
 impl DatabaseAdapter for PostgresqlAdapter {
     fn attach(&self) -> Result<()> {
-        self.ducklake
-            .execute_batch("INSTALL postgres; LOAD postgres;")
-            .with_context(|| "Failed to install/load PostgreSQL extension")?;
-
         let password = self.config.password.plaintext()?;
-        let connection_params = format!(
+        self.attachment.attach(&format!(
             "host={} port={} dbname={} user={} password={}",
             self.config.host,
             self.config.port,
             self.config.database,
             self.config.username,
             password
-        );
-        let attach_query = format!(
-            "ATTACH '{}' AS {} (TYPE postgres);",
-            connection_params,
-            Self::ALIAS
-        );
-
-        self.ducklake
-            .execute_batch(&attach_query)
-            .with_context(|| {
-                format!("Failed to attach PostgreSQL database. Query: {attach_query}")
-            })?;
-        Ok(())
+        ))
     }
 
     fn detach(&self) -> Result<()> {
-        let detach_query = format!("DETACH {}", Self::ALIAS);
-        self.ducklake
-            .execute_batch(&detach_query)
-            .with_context(|| format!("Failed to detach PostgreSQL database: {}", Self::ALIAS))?;
-        Ok(())
+        self.attachment.detach()
     }
 
     fn table_exists(&self, table: &str) -> Result<bool> {
-        let validation_query = format!(
-            "SELECT table_name FROM information_schema.tables WHERE table_name = '{table}'"
-        );
         let validation_result = self
             .ducklake
-            .query(&validation_query)
+            .query_params(
+                "SELECT table_name FROM information_schema.tables WHERE table_catalog = ? AND table_name = ?",
+                &[
+                    Value::Text(self.attachment.alias().to_string()),
+                    Value::Text(table.to_string()),
+                ],
+            )
             .with_context(|| format!("Failed to validate table existence for: {table}"))?;
 
         let table_exists = !validation_result.is_empty()
@@ -72,22 +62,29 @@ impl DatabaseAdapter for PostgresqlAdapter {
         Ok(table_exists)
     }
 
-    fn import_table(&self, source_table: &str, target_table: &str) -> Result<()> {
-        let query = format!("SELECT * FROM {}.{}", Self::ALIAS, source_table);
+    fn relation(&self, table: &str) -> String {
+        self.attachment.relation(table)
+    }
+
+    fn import_table(
+        &self,
+        source_table: &str,
+        target_table: &str,
+        selection: &TableSelection,
+    ) -> Result<()> {
+        let query = selection.select_from(&self.relation(source_table));
         self.ducklake
             .create_table_from_query(target_table, &query)?;
         Ok(())
     }
 
     fn get_table_schema(&self, table: &str) -> Result<Vec<ColumnInfo>> {
-        let schema_query = format!(
-            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = '{}' ORDER BY ordinal_position",
-            table
-        );
-
         let result = self
             .ducklake
-            .query(&schema_query)
+            .query_params(
+                "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = ? ORDER BY ordinal_position",
+                &[Value::Text(table.to_string())],
+            )
             .with_context(|| format!("Failed to get schema for table: {}", table))?;
 
         if result.is_empty() {
@@ -110,28 +107,22 @@ impl DatabaseAdapter for PostgresqlAdapter {
 
     fn validate_schema(&self, table: &str, expected_columns: &[ColumnConfig]) -> Result<()> {
         let actual_columns = self.get_table_schema(table)?;
+        let names: Vec<&str> = actual_columns.iter().map(|col| col.name.as_str()).collect();
+        SchemaMismatch::check_missing(
+            expected_columns.iter().map(|col| col.name.as_str()),
+            &names,
+            format!("table '{table}'"),
+        )?;
 
         for expected in expected_columns {
-            let found = actual_columns.iter().find(|col| col.name == expected.name);
-
-            match found {
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Column '{}' not found in table '{}'",
-                        expected.name,
-                        table
-                    ));
-                }
-                Some(actual) => {
-                    if !self.types_match(&expected.ty, &actual.data_type) {
-                        return Err(anyhow::anyhow!(
-                            "Column '{}' type mismatch: expected '{}', found '{}'",
-                            expected.name,
-                            expected.ty,
-                            actual.data_type
-                        ));
-                    }
-                }
+            if let Some(actual) = actual_columns.iter().find(|col| col.name == expected.name)
+                && !self.types_match(&expected.ty, &actual.data_type)
+            {
+                return Err(SchemaMismatch::type_mismatch(
+                    &expected.name,
+                    &expected.ty,
+                    &actual.data_type,
+                ));
             }
         }
 
@@ -191,7 +182,7 @@ mod tests {
         let not_exists = adapter.table_exists("nonexistent_table")?;
         assert!(!not_exists);
 
-        adapter.import_table("test_table", "imported_table")?;
+        adapter.import_table("test_table", "imported_table", &TableSelection::default())?;
 
         let result = ducklake.query("SELECT COUNT(*) FROM imported_table")?;
         assert_eq!(result[0][0], "3");
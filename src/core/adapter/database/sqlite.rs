@@ -1,8 +1,12 @@
-use crate::core::{config::adapter::ColumnConfig, ducklake::DuckLake};
+use crate::core::{
+    adapter::SchemaMismatch,
+    config::adapter::ColumnConfig,
+    ducklake::{DuckLake, quote_literal},
+};
 use anyhow::{Context, Result};
 use std::sync::Arc;
 
-use super::{ColumnInfo, DatabaseAdapter};
+use super::{ColumnInfo, DatabaseAdapter, TableSelection};
 
 pub struct SqliteAdapter {
     ducklake: Arc<DuckLake>,
@@ -17,11 +21,7 @@ impl SqliteAdapter {
 
 impl DatabaseAdapter for SqliteAdapter {
     fn attach(&self) -> Result<()> {
-        let attach_query = "INSTALL sqlite_scanner; LOAD sqlite_scanner;";
-        self.ducklake
-            .execute_batch(attach_query)
-            .with_context(|| "Failed to install sqlite_scanner".to_string())?;
-        Ok(())
+        self.ducklake.load_extension("sqlite_scanner")
     }
 
     fn detach(&self) -> Result<()> {
@@ -30,8 +30,9 @@ impl DatabaseAdapter for SqliteAdapter {
 
     fn table_exists(&self, table: &str) -> Result<bool> {
         let validation_query = format!(
-            "SELECT COUNT(*) as count FROM sqlite_scan('{}', '{}')",
-            self.path, table
+            "SELECT COUNT(*) as count FROM sqlite_scan({}, {})",
+            quote_literal(&self.path),
+            quote_literal(table)
         );
 
         match self.ducklake.query(&validation_query) {
@@ -45,28 +46,36 @@ impl DatabaseAdapter for SqliteAdapter {
         }
     }
 
-    fn import_table(&self, source_table: &str, target_table: &str) -> Result<()> {
-        let query = format!(
-            "SELECT * FROM sqlite_scan('{}', '{}')",
-            self.path, source_table
-        );
+    fn relation(&self, table: &str) -> String {
+        format!(
+            "sqlite_scan({}, {})",
+            quote_literal(&self.path),
+            quote_literal(table)
+        )
+    }
+
+    fn import_table(
+        &self,
+        source_table: &str,
+        target_table: &str,
+        selection: &TableSelection,
+    ) -> Result<()> {
+        let query = selection.select_from(&self.relation(source_table));
         self.ducklake
             .create_table_from_query(target_table, &query)?;
         Ok(())
     }
 
     fn get_table_schema(&self, table: &str) -> Result<Vec<ColumnInfo>> {
-        let pragma_query = format!("PRAGMA table_info({})", table);
-
         let conn = rusqlite::Connection::open(&self.path)
             .with_context(|| format!("Failed to open SQLite database: {}", self.path))?;
 
         let mut stmt = conn
-            .prepare(&pragma_query)
+            .prepare("SELECT cid, name, type FROM pragma_table_info(?1)")
             .with_context(|| format!("Failed to prepare PRAGMA query for table: {}", table))?;
 
         let column_info_iter = stmt
-            .query_map([], |row| {
+            .query_map([table], |row| {
                 Ok(ColumnInfo {
                     name: row.get::<_, String>(1)?,
                     data_type: row.get::<_, String>(2)?,
@@ -91,28 +100,22 @@ impl DatabaseAdapter for SqliteAdapter {
 
     fn validate_schema(&self, table: &str, expected_columns: &[ColumnConfig]) -> Result<()> {
         let actual_columns = self.get_table_schema(table)?;
+        let names: Vec<&str> = actual_columns.iter().map(|col| col.name.as_str()).collect();
+        SchemaMismatch::check_missing(
+            expected_columns.iter().map(|col| col.name.as_str()),
+            &names,
+            format!("table '{table}'"),
+        )?;
 
         for expected in expected_columns {
-            let found = actual_columns.iter().find(|col| col.name == expected.name);
-
-            match found {
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Column '{}' not found in table '{}'",
-                        expected.name,
-                        table
-                    ));
-                }
-                Some(actual) => {
-                    if !self.types_match(&expected.ty, &actual.data_type) {
-                        return Err(anyhow::anyhow!(
-                            "Column '{}' type mismatch: expected '{}', found '{}'",
-                            expected.name,
-                            expected.ty,
-                            actual.data_type
-                        ));
-                    }
-                }
+            if let Some(actual) = actual_columns.iter().find(|col| col.name == expected.name)
+                && !self.types_match(&expected.ty, &actual.data_type)
+            {
+                return Err(SchemaMismatch::type_mismatch(
+                    &expected.name,
+                    &expected.ty,
+                    &actual.data_type,
+                ));
             }
         }
 
@@ -162,7 +165,7 @@ mod tests {
         assert!(!not_exists);
 
         adapter
-            .import_table("test_table", "imported_table")
+            .import_table("test_table", "imported_table", &TableSelection::default())
             .unwrap();
 
         let result = ducklake
@@ -195,7 +198,60 @@ mod tests {
         let exists = adapter.table_exists("nonexistent_table").unwrap();
         assert!(!exists);
 
-        let result = adapter.import_table("nonexistent_table", "imported_table");
+        let result = adapter.import_table(
+            "nonexistent_table",
+            "imported_table",
+            &TableSelection::default(),
+        );
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_sqlite_adapter_selective_import() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let db_path = tempdir.path().join("test.db");
+        create_test_sqlite_db(&db_path).await.unwrap();
+
+        let adapter = SqliteAdapter::new(ducklake.clone(), db_path.to_string_lossy().to_string());
+        adapter.attach().unwrap();
+
+        let selection = TableSelection {
+            columns: vec!["id".to_string(), "name".to_string()],
+            filter: Some("age >= 30".to_string()),
+            ..Default::default()
+        };
+        adapter
+            .import_table("test_table", "imported_table", &selection)
+            .unwrap();
+
+        let columns = ducklake
+            .query("SELECT column_name FROM (DESCRIBE imported_table)")
+            .unwrap();
+        assert_eq!(columns, vec![vec!["id"], vec!["name"]]);
+
+        let result = ducklake
+            .query("SELECT name FROM imported_table ORDER BY id")
+            .unwrap();
+        assert_eq!(result, vec![vec!["Bob"], vec!["Charlie"]]);
+    }
+
+    #[test]
+    fn test_table_selection_sql() {
+        assert_eq!(
+            TableSelection::default().select_from("src"),
+            "SELECT * FROM src"
+        );
+
+        let selection = TableSelection {
+            columns: vec!["id".to_string(), "first name".to_string()],
+            filter: Some("id > 1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            selection.select_from("src"),
+            "SELECT \"id\", \"first name\" FROM src WHERE (id > 1)"
+        );
+    }
 }
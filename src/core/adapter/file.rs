@@ -1,41 +1,250 @@
+pub mod gcs;
+pub mod http;
 pub mod localfile;
 pub mod s3;
+pub mod state;
 
-use super::{Adapter, adapter_from_connection, database::ColumnInfo};
-use crate::core::config::{
-    adapter::{AdapterConfig, AdapterSource, ColumnConfig},
-    project::ConnectionConfig,
+use super::{
+    Adapter, ImportProgress, Imported, Progress, SchemaDiff, SchemaMismatch,
+    adapter_from_connection, count_duplicates, database::ColumnInfo, dedupe_query,
 };
-use anyhow::Result;
+use crate::core::{
+    config::{
+        adapter::{AdapterConfig, AdapterSource, ColumnConfig, FormatConfig, ImportMode},
+        project::ConnectionConfig,
+    },
+    ducklake::{DuckLake, quote_ident, quote_literal},
+    graph::FileCounts,
+};
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use gcs::GcsFileAdapter;
+use http::HttpFileAdapter;
 use localfile::LocalFileAdapter;
 use s3::S3FileAdapter;
+use state::ImportState;
 use std::collections::HashMap;
 
 #[async_trait]
 pub trait FileAdapter: Send + Sync {
+    // The absolute path or URL a configured pattern refers to, before matching files.
+    fn resolve_pattern(&self, pattern: &str) -> String;
     async fn list_files(&self, pattern: &str) -> Result<Vec<String>>;
-    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<()>;
+    // Like `list_files`, paired with a version string that changes whenever the file does.
+    async fn list_file_versions(&self, pattern: &str) -> Result<Vec<(String, String)>>;
+    // Like `list_file_versions`, but the version only changes with the content. The
+    // ETag object stores report already does, so only local files compute one.
+    async fn list_file_checksums(&self, pattern: &str) -> Result<Vec<(String, String)>> {
+        self.list_file_versions(pattern).await
+    }
+    // Sets up whatever credentials DuckDB needs to read the listed files.
+    async fn configure_access(&self) -> Result<()>;
+    // Both return the number of duplicate rows the adapter's `dedupe` dropped.
+    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<u64>;
+    async fn append_files(&self, table_name: &str, files: &[String]) -> Result<u64>;
     async fn get_file_schema(&self, file_path: &str) -> Result<Vec<ColumnInfo>>;
+
     async fn validate_schema(
         &self,
         file_path: &str,
         expected_columns: &[ColumnConfig],
-    ) -> Result<()>;
+    ) -> Result<()> {
+        let actual_columns = self.get_file_schema(file_path).await?;
+        let names: Vec<&str> = actual_columns.iter().map(|col| col.name.as_str()).collect();
+        SchemaMismatch::check_missing(
+            expected_columns.iter().map(|col| col.name.as_str()),
+            &names,
+            format!("file '{file_path}'"),
+        )?;
+
+        for expected in expected_columns {
+            if let Some(actual) = actual_columns.iter().find(|col| col.name == expected.name)
+                && !expected.enforce
+                && !types_match(&expected.ty, &actual.data_type)
+            {
+                return Err(SchemaMismatch::type_mismatch(
+                    &expected.name,
+                    &expected.ty,
+                    &actual.data_type,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn types_match(expected: &str, actual: &str) -> bool {
+    let normalize_type = |t: &str| -> String {
+        t.to_uppercase()
+            .replace("INTEGER", "BIGINT")
+            .replace("STRING", "VARCHAR")
+            .replace("FLOAT", "DOUBLE")
+    };
+
+    normalize_type(expected) == normalize_type(actual)
+}
+
+// The columns DuckDB reads from the file at `path`, which the caller has already
+// resolved and made readable.
+pub fn describe_file(
+    ducklake: &DuckLake,
+    adapter_config: &AdapterConfig,
+    path: &str,
+) -> Result<Vec<ColumnInfo>> {
+    let AdapterSource::File { format, .. } = &adapter_config.source else {
+        return Err(anyhow::anyhow!("Expected file source"));
+    };
+
+    let temp_table = format!("temp_schema_check_{}", uuid::Uuid::new_v4().simple());
+    load_format_extension(ducklake, format)?;
+    let query = format!(
+        "CREATE TEMP TABLE {temp_table} AS {} LIMIT 0",
+        build_read_query(format, &format!("'{path}'"))?
+    );
+
+    explain_read_error(format, ducklake.execute_batch(&query))?;
+
+    let result = ducklake.query(&format!("DESCRIBE {temp_table}"))?;
+
+    let _ = ducklake.execute_batch(&format!("DROP TABLE {temp_table}"));
+
+    let columns = result
+        .into_iter()
+        .map(|row| ColumnInfo {
+            name: row[0].clone(),
+            data_type: row[1].clone(),
+        })
+        .collect();
+
+    Ok(columns)
 }
 
 impl Adapter {
-    pub async fn file_import(&self, table_name: &str, adapter: Box<dyn FileAdapter>) -> Result<()> {
-        let pattern = if let AdapterSource::File { file, .. } = &self.config.source {
-            &file.path
+    pub async fn file_import(
+        &self,
+        table_name: &str,
+        adapter: Box<dyn FileAdapter>,
+    ) -> Result<Imported> {
+        let file = if let AdapterSource::File { file, .. } = &self.config.source {
+            file
         } else {
             return Err(anyhow::anyhow!("Adapter source is not a file"));
         };
 
-        let files = adapter.list_files(pattern).await?;
-        adapter.import_files(table_name, &files).await?;
+        let mode = file.import_mode();
+        let project_dir = match &self.project_dir {
+            Some(project_dir) if mode != ImportMode::Full => project_dir,
+            _ => {
+                let files = adapter.list_files(&file.path).await?;
+                self.ensure_projected_columns(adapter.as_ref(), &files)
+                    .await?;
+                let schema_changes = self
+                    .file_schema_changes(adapter.as_ref(), table_name, &files)
+                    .await?;
+                let duplicates_dropped = adapter.import_files(table_name, &files).await?;
+                return Ok(Imported {
+                    files: Some(FileCounts {
+                        imported: files.len() as u64,
+                        skipped: 0,
+                    }),
+                    schema_changes,
+                    duplicates_dropped,
+                });
+            }
+        };
+
+        let files = match mode {
+            ImportMode::Content => adapter.list_file_checksums(&file.path).await?,
+            _ => adapter.list_file_versions(&file.path).await?,
+        };
+        let paths: Vec<String> = files.iter().map(|(path, _)| path.clone()).collect();
+        self.ensure_projected_columns(adapter.as_ref(), &paths)
+            .await?;
+        let schema_changes = self
+            .file_schema_changes(adapter.as_ref(), table_name, &paths)
+            .await?;
 
-        Ok(())
+        // Versions recorded under another mode can't be compared with these, so
+        // switching modes reloads everything once. Rows aren't tracked per file, so a
+        // modified or removed file reloads everything too.
+        let state = ImportState::load(project_dir, table_name).await?;
+        let (counts, duplicates_dropped) = if self.full_refresh
+            || state.mode != mode
+            || state.has_stale_files(&files)
+            || !self.ducklake.table_exists(table_name)?
+        {
+            let counts = FileCounts {
+                imported: paths.len() as u64,
+                skipped: 0,
+            };
+            (counts, adapter.import_files(table_name, &paths).await?)
+        } else {
+            let changed = state.changed_files(&files);
+            let counts = FileCounts {
+                imported: changed.len() as u64,
+                skipped: (files.len() - changed.len()) as u64,
+            };
+            (counts, adapter.append_files(table_name, &changed).await?)
+        };
+
+        if counts.skipped > 0 {
+            tracing::info!(
+                "Skipped {} unchanged files for '{table_name}'",
+                counts.skipped
+            );
+        }
+
+        ImportState::from_files(mode, &files)
+            .save(project_dir, table_name)
+            .await?;
+        Ok(Imported {
+            files: Some(counts),
+            schema_changes,
+            duplicates_dropped,
+        })
+    }
+
+    // Only the first file is read: every file of a glob is expected to share a schema.
+    async fn file_schema_changes(
+        &self,
+        adapter: &dyn FileAdapter,
+        table_name: &str,
+        files: &[String],
+    ) -> Result<Option<SchemaDiff>> {
+        let Some(first) = files.first() else {
+            return Ok(None);
+        };
+        if !self.config.detects_schema_changes() {
+            return Ok(None);
+        }
+
+        let schema = adapter.get_file_schema(first).await?;
+        self.apply_schema_policy(table_name, &schema)
+    }
+
+    // A projected column missing from the source would otherwise surface as a
+    // binder error from deep inside the import query.
+    async fn ensure_projected_columns(
+        &self,
+        adapter: &dyn FileAdapter,
+        files: &[String],
+    ) -> Result<()> {
+        let Some(first) = files.first() else {
+            return Ok(());
+        };
+        if !self.config.projects_columns() {
+            return Ok(());
+        }
+
+        let schema = adapter.get_file_schema(first).await?;
+        let names: Vec<&str> = schema.iter().map(|info| info.name.as_str()).collect();
+        let expected = self
+            .config
+            .columns
+            .iter()
+            .map(|column| column.name.as_str());
+        SchemaMismatch::check_missing(expected, &names, format!("file '{first}'"))
     }
 
     pub fn file_adapter(
@@ -52,12 +261,22 @@ impl Adapter {
                     self.config.clone(),
                     Some(base_path),
                 );
-                Ok(Box::new(adapter))
+                Ok(Box::new(adapter.with_progress(self.progress.clone())))
             }
             ConnectionConfig::S3(s3config) => {
                 let adapter =
                     S3FileAdapter::new(self.ducklake.clone(), self.config.clone(), s3config);
-                Ok(Box::new(adapter))
+                Ok(Box::new(adapter.with_progress(self.progress.clone())))
+            }
+            ConnectionConfig::Http(http_config) => {
+                let adapter =
+                    HttpFileAdapter::new(self.ducklake.clone(), self.config.clone(), http_config);
+                Ok(Box::new(adapter.with_progress(self.progress.clone())))
+            }
+            ConnectionConfig::Gcs(gcs_config) => {
+                let adapter =
+                    GcsFileAdapter::new(self.ducklake.clone(), self.config.clone(), gcs_config);
+                Ok(Box::new(adapter.with_progress(self.progress.clone())))
             }
             _ => Err(anyhow::anyhow!(
                 "Unsupported connection type for file adapter"
@@ -66,72 +285,312 @@ impl Adapter {
     }
 }
 
+// Loads `files` into `table_name`, at most `max_batch_files` files per statement.
+// The first batch replaces the table unless `append` is set; later batches insert.
+// Progress is reported before the first batch and after each one. Returns the number
+// of duplicate rows dropped, which are only compared within a batch.
+pub fn import_file_batches(
+    ducklake: &DuckLake,
+    adapter_config: &AdapterConfig,
+    table_name: &str,
+    files: &[String],
+    append: bool,
+    progress: &ImportProgress,
+) -> Result<u64> {
+    let batch_size = max_batch_files(adapter_config)?
+        .unwrap_or(files.len())
+        .max(1);
+    let batches: Vec<&[String]> = files.chunks(batch_size).collect();
+    let total = batches.len();
+
+    if let AdapterSource::File { format, .. } = &adapter_config.source {
+        load_format_extension(ducklake, format)?;
+    }
+
+    let sizes = files
+        .iter()
+        .map(|file| std::fs::metadata(file).ok().map(|metadata| metadata.len()))
+        .collect::<Option<Vec<u64>>>();
+    let mut done = Progress {
+        files_done: 0,
+        files_total: files.len(),
+        bytes_done: sizes.as_ref().map(|_| 0),
+        bytes_total: sizes.as_ref().map(|sizes| sizes.iter().sum()),
+    };
+    progress.report(&done);
+
+    let mut duplicates_dropped = 0;
+    // A full load goes into a staging table that only replaces `table_name` once every
+    // batch is in, so a failed batch leaves the previous table as it was.
+    let mut load = |target: &str| -> Result<()> {
+        for (index, batch) in batches.iter().enumerate() {
+            let query = build_import_query(adapter_config, batch)?;
+            if let Some(dedupe) = &adapter_config.dedupe {
+                duplicates_dropped += count_duplicates(
+                    ducklake,
+                    dedupe,
+                    &build_source_query(adapter_config, batch)?,
+                )?;
+            }
+            let result = if index == 0 && !append {
+                ducklake.create_table_from_query(target, &query)
+            } else {
+                ducklake.insert_from_query(target, &query)
+            };
+            let result = match &adapter_config.source {
+                AdapterSource::File { format, .. } => explain_read_error(format, result),
+                _ => result,
+            };
+            result.with_context(|| {
+                let kept = if append {
+                    format!("{index} batches committed")
+                } else {
+                    "previous table kept".to_string()
+                };
+                format!(
+                    "Failed to import batch {} of {total} into '{table_name}' ({kept})",
+                    index + 1
+                )
+            })?;
+
+            tracing::info!(
+                "Imported batch {}/{total} ({} files) into '{table_name}'",
+                index + 1,
+                batch.len()
+            );
+
+            if let (Some(bytes_done), Some(sizes)) = (done.bytes_done.as_mut(), &sizes) {
+                *bytes_done += sizes[done.files_done..done.files_done + batch.len()]
+                    .iter()
+                    .sum::<u64>();
+            }
+            done.files_done += batch.len();
+            progress.report(&done);
+        }
+        Ok(())
+    };
+
+    if append {
+        load(table_name)?;
+    } else {
+        ducklake.replace_table_with(table_name, load)?;
+    }
+    Ok(duplicates_dropped)
+}
+
+fn max_batch_files(adapter_config: &AdapterConfig) -> Result<Option<usize>> {
+    let AdapterSource::File { file, .. } = &adapter_config.source else {
+        return Ok(None);
+    };
+
+    match file.max_batch_files {
+        Some(0) => Err(anyhow::anyhow!(
+            "max_batch_files must be a positive number of files"
+        )),
+        files => Ok(files),
+    }
+}
+
+// The rows `files` hold, projected to the adapter's columns and with its `dedupe` applied.
 pub fn build_import_query(adapter_config: &AdapterConfig, files: &[String]) -> Result<String> {
+    let query = build_source_query(adapter_config, files)?;
+    Ok(match &adapter_config.dedupe {
+        Some(dedupe) => dedupe_query(dedupe, &format!("({query})")),
+        None => query,
+    })
+}
+
+fn build_source_query(adapter_config: &AdapterConfig, files: &[String]) -> Result<String> {
     if files.is_empty() {
         return Err(anyhow::anyhow!("No files to load"));
     }
 
-    if files.len() == 1 {
-        let file_path = &files[0];
-        return build_import_query_single(adapter_config, file_path);
+    let AdapterSource::File { format, .. } = &adapter_config.source else {
+        return Err(anyhow::anyhow!(
+            "Only file sources are supported in file processing"
+        ));
+    };
+
+    let csv_types = CsvTypes::of(adapter_config);
+    let query = if format.ty == "xlsx" {
+        // read_xlsx only takes a single workbook, so stitch multiple files together.
+        let queries = files
+            .iter()
+            .map(|file| build_read_query(format, &format!("'{file}'")))
+            .collect::<Result<Vec<_>>>()?;
+        queries.join(" UNION ALL BY NAME ")
+    } else if files.len() == 1 {
+        build_typed_read_query(format, &format!("'{}'", files[0]), &csv_types)?
+    } else {
+        let file_paths_str = files
+            .iter()
+            .map(|p| format!("'{p}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        build_typed_read_query(format, &format!("[{file_paths_str}]"), &csv_types)?
+    };
+
+    Ok(project_columns(adapter_config, query))
+}
+
+fn project_columns(adapter_config: &AdapterConfig, query: String) -> String {
+    if !adapter_config.projects_columns() {
+        return query;
     }
 
-    let file_paths_str = files
+    let projection = adapter_config
+        .columns
         .iter()
-        .map(|p| format!("'{p}'"))
+        .map(|column| {
+            let source = quote_ident(&column.name);
+            let value = match (column.enforce, column.lenient) {
+                (true, true) => format!("TRY_CAST({source} AS {})", column.ty),
+                (true, false) => format!("CAST({source} AS {})", column.ty),
+                (false, _) => source,
+            };
+            format!("{value} AS {}", quote_ident(column.output_name()))
+        })
         .collect::<Vec<_>>()
         .join(", ");
+    format!("SELECT {projection} FROM ({query})")
+}
+
+// How CSV columns are typed on read. Other formats carry their own types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CsvTypes {
+    #[default]
+    Detect,
+    // Detect, but keep these columns as the text in the file. Casting a detected
+    // number to VARCHAR afterwards would already have lost its leading zeros.
+    Text(Vec<String>),
+    AllText,
+}
 
-    match &adapter_config.source {
-        crate::core::config::adapter::AdapterSource::File { format, .. } => {
-            match format.ty.as_str() {
-                "csv" => {
-                    let has_header = format.has_header.unwrap_or(true);
-                    let query = format!(
-                        "SELECT * FROM read_csv_auto([{file_paths_str}], header={has_header})"
-                    );
-                    Ok(query)
-                }
-                "parquet" => {
-                    let query = format!("SELECT * FROM read_parquet([{file_paths_str}])");
-                    Ok(query)
-                }
-                "json" => {
-                    let query = format!("SELECT * FROM read_json_auto([{file_paths_str}])");
-                    Ok(query)
-                }
-                _ => Err(anyhow::anyhow!("Unsupported format: {}", format.ty)),
+impl CsvTypes {
+    fn of(adapter_config: &AdapterConfig) -> Self {
+        let text = adapter_config
+            .columns
+            .iter()
+            .filter(|column| column.enforce && column.ty.eq_ignore_ascii_case("VARCHAR"))
+            .map(|column| column.name.clone())
+            .collect::<Vec<_>>();
+        if text.is_empty() {
+            Self::Detect
+        } else {
+            Self::Text(text)
+        }
+    }
+
+    fn option(&self) -> String {
+        match self {
+            Self::Detect => String::new(),
+            Self::Text(columns) => {
+                let types = columns
+                    .iter()
+                    .map(|column| format!("{}: 'VARCHAR'", quote_literal(column)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(", types={{{types}}}")
             }
+            Self::AllText => ", all_varchar=true".to_string(),
         }
-        _ => Err(anyhow::anyhow!(
-            "Only file sources are supported in file processing"
-        )),
     }
 }
 
-fn build_import_query_single(adapter_config: &AdapterConfig, file_path: &str) -> Result<String> {
-    match &adapter_config.source {
-        crate::core::config::adapter::AdapterSource::File { format, .. } => {
-            match format.ty.as_str() {
-                "csv" => {
-                    let has_header = format.has_header.unwrap_or(true);
-                    let query =
-                        format!("SELECT * FROM read_csv_auto('{file_path}', header={has_header})");
-                    Ok(query)
-                }
-                "parquet" => {
-                    let query = format!("SELECT * FROM read_parquet('{file_path}')");
-                    Ok(query)
-                }
-                "json" => {
-                    let query = format!("SELECT * FROM read_json_auto('{file_path}')");
-                    Ok(query)
-                }
-                _ => Err(anyhow::anyhow!("Unsupported format: {}", format.ty)),
-            }
+// `source` is a quoted path or a list of quoted paths.
+pub fn build_read_query(format: &FormatConfig, source: &str) -> Result<String> {
+    build_typed_read_query(format, source, &CsvTypes::Detect)
+}
+
+pub fn build_typed_read_query(
+    format: &FormatConfig,
+    source: &str,
+    csv_types: &CsvTypes,
+) -> Result<String> {
+    match format.ty.as_str() {
+        "csv" => {
+            let has_header = format.has_header.unwrap_or(true);
+            let delimiter = format.delimiter()?.map(|delimiter| delimiter.to_string());
+            let options = [
+                ("delim", &delimiter),
+                ("quote", &format.quote),
+                ("escape", &format.escape),
+                ("nullstr", &format.null_value),
+                ("dateformat", &format.date_format),
+                ("timestampformat", &format.timestamp_format),
+            ]
+            .into_iter()
+            .filter_map(|(option, value)| {
+                value
+                    .as_ref()
+                    .map(|value| format!(", {option}={}", quote_literal(value)))
+            })
+            .collect::<String>();
+            Ok(format!(
+                "SELECT * FROM read_csv_auto({source}, header={has_header}{options}{})",
+                csv_types.option()
+            ))
         }
-        _ => Err(anyhow::anyhow!(
-            "Only file sources are supported in file processing"
+        "parquet" => Ok(format!("SELECT * FROM read_parquet({source})")),
+        "json" => Ok(format!("SELECT * FROM read_json_auto({source})")),
+        "ndjson" => Ok(format!(
+            "SELECT * FROM read_json_auto({source}, format='newline_delimited')"
         )),
+        "xlsx" => match &format.sheet {
+            Some(sheet) => Ok(format!(
+                "SELECT * FROM read_xlsx({source}, sheet={})",
+                quote_literal(sheet)
+            )),
+            None => Ok(format!("SELECT * FROM read_xlsx({source})")),
+        },
+        "fixed_width" => Ok(fixed_width_query(format, source)),
+        _ => Err(anyhow::anyhow!("Unsupported format: {}", format.ty)),
+    }
+}
+
+// Reads every line of the files as a single VARCHAR column and cuts each field out
+// of the line by position. The delimiter is a control character that does not occur
+// in text files, and quoting is off, so a line is never split or unquoted. Fields
+// are trimmed of the padding that fills them to width.
+fn fixed_width_query(format: &FormatConfig, source: &str) -> String {
+    let skip = usize::from(format.has_header.unwrap_or(false));
+    let fields = format
+        .columns_spec
+        .iter()
+        .map(|column| {
+            format!(
+                "trim(substring(line, {}, {})) AS {}",
+                column.start,
+                column.len,
+                quote_ident(&column.name)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "SELECT {fields} FROM read_csv({source}, columns={{'line': 'VARCHAR'}}, header=false, skip={skip}, auto_detect=false, delim='\u{1}', quote='', escape='') WHERE line <> ''"
+    )
+}
+
+pub fn load_format_extension(ducklake: &DuckLake, format: &FormatConfig) -> Result<()> {
+    if format.ty == "xlsx" {
+        ducklake.load_extension("excel")?;
+    }
+    Ok(())
+}
+
+// DuckDB's message for a missing worksheet is not very helpful, so name the sheet instead.
+pub fn explain_read_error<T>(format: &FormatConfig, result: Result<T>) -> Result<T> {
+    match (&format.sheet, result) {
+        (Some(sheet), Err(e))
+            if format.ty == "xlsx"
+                && e.root_cause().to_string().to_lowercase().contains("sheet") =>
+        {
+            Err(anyhow::anyhow!(
+                "Sheet '{sheet}' was not found in the workbook"
+            ))
+        }
+        (_, result) => result,
     }
 }
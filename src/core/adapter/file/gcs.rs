@@ -0,0 +1,227 @@
+use crate::core::{
+    adapter::ImportProgress,
+    config::{adapter::AdapterConfig, project::GcsConfig},
+    ducklake::DuckLake,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_config::Region;
+use aws_sdk_s3::{
+    Client,
+    config::{Builder, Credentials},
+};
+use std::sync::Arc;
+
+use super::{
+    super::database::ColumnInfo,
+    FileAdapter, describe_file, import_file_batches,
+    s3::{compile_pattern, extract_prefix_from_pattern},
+};
+
+pub struct GcsFileAdapter {
+    ducklake: Arc<DuckLake>,
+    adapter_config: AdapterConfig,
+    gcs_config: GcsConfig,
+    progress: ImportProgress,
+}
+
+impl GcsFileAdapter {
+    pub fn new(
+        ducklake: Arc<DuckLake>,
+        adapter_config: AdapterConfig,
+        gcs_config: GcsConfig,
+    ) -> Self {
+        Self {
+            ducklake,
+            adapter_config,
+            gcs_config,
+            progress: ImportProgress::default(),
+        }
+    }
+
+    pub fn with_progress(mut self, progress: ImportProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    async fn list_gcs_files(&self, pattern: &str) -> Result<Vec<(String, String)>> {
+        let client = gcs_client(&self.gcs_config).await?;
+
+        let prefix = extract_prefix_from_pattern(pattern);
+        let mut all_objects = Vec::new();
+        let mut continuation_token = None;
+
+        loop {
+            let mut request = client
+                .list_objects_v2()
+                .bucket(&self.gcs_config.bucket)
+                .prefix(&prefix);
+
+            if let Some(token) = continuation_token {
+                request = request.continuation_token(token);
+            }
+
+            let result = request.send().await.context("Failed to list GCS objects")?;
+
+            if let Some(contents) = result.contents {
+                for object in contents {
+                    if let Some(key) = object.key {
+                        all_objects.push((key, object.e_tag.unwrap_or_default()));
+                    }
+                }
+            }
+
+            continuation_token = result.next_continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        matching_objects(&self.gcs_config.bucket, pattern, all_objects)
+    }
+
+    fn resolve_path(&self, path: &str) -> String {
+        if path.starts_with("gs://") {
+            path.to_string()
+        } else {
+            format!("gs://{}/{}", self.gcs_config.bucket, path)
+        }
+    }
+}
+
+// GCS speaks the S3 protocol through its XML API when authenticated with HMAC keys.
+pub async fn gcs_client(gcs_config: &GcsConfig) -> Result<Client> {
+    let credentials = Credentials::new(
+        &gcs_config.hmac_key_id,
+        gcs_config.hmac_secret.plaintext()?,
+        None,
+        None,
+        "duckhub",
+    );
+
+    let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(Region::new("auto"))
+        .endpoint_url(gcs_config.endpoint())
+        .credentials_provider(credentials)
+        .load()
+        .await;
+
+    Ok(Client::from_conf(
+        Builder::from(&aws_config).force_path_style(true).build(),
+    ))
+}
+
+fn matching_objects(
+    bucket: &str,
+    pattern: &str,
+    objects: Vec<(String, String)>,
+) -> Result<Vec<(String, String)>> {
+    let regex = compile_pattern(pattern)?;
+    Ok(objects
+        .into_iter()
+        .filter(|(key, _)| regex.is_match(key))
+        .map(|(key, etag)| (format!("gs://{bucket}/{key}"), etag))
+        .collect())
+}
+
+#[async_trait]
+impl FileAdapter for GcsFileAdapter {
+    fn resolve_pattern(&self, path: &str) -> String {
+        format!("gs://{}/{path}", self.gcs_config.bucket)
+    }
+
+    async fn list_files(&self, path: &str) -> Result<Vec<String>> {
+        let files = self.list_gcs_files(path).await?;
+        Ok(files.into_iter().map(|(file, _)| file).collect())
+    }
+
+    async fn list_file_versions(&self, path: &str) -> Result<Vec<(String, String)>> {
+        self.list_gcs_files(path).await
+    }
+
+    async fn configure_access(&self) -> Result<()> {
+        self.ducklake
+            .configure_gcs_connection(&self.gcs_config)
+            .await
+    }
+
+    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.ducklake
+            .configure_gcs_connection(&self.gcs_config)
+            .await?;
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            false,
+            &self.progress,
+        )
+    }
+
+    async fn append_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.ducklake
+            .configure_gcs_connection(&self.gcs_config)
+            .await?;
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            true,
+            &self.progress,
+        )
+    }
+
+    async fn get_file_schema(&self, file_path: &str) -> Result<Vec<ColumnInfo>> {
+        let gcs_path = self.resolve_path(file_path);
+
+        self.ducklake
+            .configure_gcs_connection(&self.gcs_config)
+            .await?;
+
+        describe_file(&self.ducklake, &self.adapter_config, &gcs_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_objects() {
+        let objects = vec![
+            ("logs/2024/a.json".to_string(), "\"1\"".to_string()),
+            ("logs/2024/b.csv".to_string(), "\"2\"".to_string()),
+            ("logs/2025/c.json".to_string(), "\"3\"".to_string()),
+            ("other/d.json".to_string(), "\"4\"".to_string()),
+        ];
+
+        assert_eq!(extract_prefix_from_pattern("logs/*/*.json"), "logs");
+
+        let matched = matching_objects("lake", "logs/*/*.json", objects).unwrap();
+        assert_eq!(
+            matched,
+            vec![
+                (
+                    "gs://lake/logs/2024/a.json".to_string(),
+                    "\"1\"".to_string()
+                ),
+                (
+                    "gs://lake/logs/2025/c.json".to_string(),
+                    "\"3\"".to_string()
+                ),
+            ]
+        );
+    }
+}
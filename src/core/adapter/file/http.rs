@@ -0,0 +1,229 @@
+use crate::core::{
+    adapter::ImportProgress,
+    config::{adapter::AdapterConfig, project::HttpConfig},
+    ducklake::DuckLake,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{AUTHORIZATION, ETAG, HeaderMap, HeaderName, HeaderValue, LAST_MODIFIED};
+use std::sync::Arc;
+
+use super::{super::database::ColumnInfo, FileAdapter, describe_file, import_file_batches};
+
+pub struct HttpFileAdapter {
+    ducklake: Arc<DuckLake>,
+    adapter_config: AdapterConfig,
+    http_config: HttpConfig,
+    progress: ImportProgress,
+}
+
+impl HttpFileAdapter {
+    pub fn new(
+        ducklake: Arc<DuckLake>,
+        adapter_config: AdapterConfig,
+        http_config: HttpConfig,
+    ) -> Self {
+        Self {
+            ducklake,
+            adapter_config,
+            http_config,
+            progress: ImportProgress::default(),
+        }
+    }
+
+    pub fn with_progress(mut self, progress: ImportProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    fn resolve_url(&self, path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                self.http_config.base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            )
+        }
+    }
+}
+
+pub fn http_client(http_config: &HttpConfig) -> Result<reqwest::Client> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in http_config.headers.iter().flatten() {
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid HTTP header name '{name}'"))?,
+            HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for HTTP header '{name}'"))?,
+        );
+    }
+    if let Some(auth_token) = &http_config.auth_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", auth_token.plaintext()?))
+            .context("Invalid HTTP auth token")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+#[async_trait]
+impl FileAdapter for HttpFileAdapter {
+    fn resolve_pattern(&self, path: &str) -> String {
+        self.resolve_url(path)
+    }
+
+    async fn list_files(&self, path: &str) -> Result<Vec<String>> {
+        Ok(vec![self.resolve_url(path)])
+    }
+
+    async fn list_file_versions(&self, path: &str) -> Result<Vec<(String, String)>> {
+        let url = self.resolve_url(path);
+        let response = http_client(&self.http_config)?
+            .head(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch '{url}'"))?;
+
+        let version = [ETAG, LAST_MODIFIED]
+            .iter()
+            .find_map(|header| response.headers().get(header))
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(vec![(url, version)])
+    }
+
+    async fn configure_access(&self) -> Result<()> {
+        self.ducklake.configure_http_connection(&self.http_config)
+    }
+
+    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.ducklake.configure_http_connection(&self.http_config)?;
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            false,
+            &self.progress,
+        )
+    }
+
+    async fn append_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.ducklake.configure_http_connection(&self.http_config)?;
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            true,
+            &self.progress,
+        )
+    }
+
+    async fn get_file_schema(&self, file_path: &str) -> Result<Vec<ColumnInfo>> {
+        let url = self.resolve_url(file_path);
+
+        self.ducklake.configure_http_connection(&self.http_config)?;
+
+        describe_file(&self.ducklake, &self.adapter_config, &url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{adapter::test_helpers::*, config::secret::SecretField};
+    use axum::{Router, http::StatusCode, routing::get};
+
+    const TOKEN: &str = "secret-token";
+
+    async fn serve_csv() -> String {
+        let app = Router::new().route(
+            "/data/users.csv",
+            get(|headers: axum::http::HeaderMap| async move {
+                let authorized = headers
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value == format!("Bearer {TOKEN}"));
+                if authorized {
+                    (StatusCode::OK, create_test_csv_data())
+                } else {
+                    (StatusCode::UNAUTHORIZED, String::new())
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        format!("http://{addr}/data")
+    }
+
+    fn http_adapter(ducklake: Arc<DuckLake>, base_url: &str, token: &str) -> HttpFileAdapter {
+        HttpFileAdapter::new(
+            ducklake,
+            create_csv_adapter_config(),
+            HttpConfig {
+                base_url: base_url.to_string(),
+                headers: None,
+                auth_token: Some(SecretField::PlainText {
+                    value: token.to_string(),
+                }),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_http_adapter_csv_import() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        let base_url = serve_csv().await;
+
+        let adapter = http_adapter(ducklake.clone(), &base_url, TOKEN);
+
+        let files = adapter.list_files("users.csv").await.unwrap();
+        assert_eq!(files, vec![format!("{base_url}/users.csv")]);
+
+        let schema = adapter.get_file_schema("users.csv").await.unwrap();
+        assert_eq!(schema.len(), 3);
+
+        adapter.import_files("test_table", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT name FROM test_table ORDER BY id")
+            .unwrap();
+        assert_eq!(result, vec![vec!["Alice"], vec!["Bob"], vec!["Charlie"]]);
+    }
+
+    #[tokio::test]
+    async fn test_http_adapter_rejected_token() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        let base_url = serve_csv().await;
+
+        let adapter = http_adapter(ducklake.clone(), &base_url, "wrong-token");
+
+        assert!(adapter.list_file_versions("users.csv").await.is_err());
+        assert!(adapter.get_file_schema("users.csv").await.is_err());
+    }
+}
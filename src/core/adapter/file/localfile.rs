@@ -1,20 +1,20 @@
-use crate::core::{
-    config::adapter::{AdapterConfig, ColumnConfig},
-    ducklake::DuckLake,
-};
+use crate::core::{adapter::ImportProgress, config::adapter::AdapterConfig, ducklake::DuckLake};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use std::{
+    io::Read,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use xxhash_rust::xxh3::Xxh3;
 
-use super::{super::database::ColumnInfo, FileAdapter, build_import_query};
+use super::{super::database::ColumnInfo, FileAdapter, describe_file, import_file_batches};
 
 pub struct LocalFileAdapter {
     ducklake: Arc<DuckLake>,
     adapter_config: AdapterConfig,
     base_path: Option<String>,
+    progress: ImportProgress,
 }
 
 impl LocalFileAdapter {
@@ -27,22 +27,45 @@ impl LocalFileAdapter {
             ducklake,
             adapter_config,
             base_path,
+            progress: ImportProgress::default(),
+        }
+    }
+
+    pub fn with_progress(mut self, progress: ImportProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+}
+
+fn file_checksum(path: &str) -> Result<String> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open '{path}'"))?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read '{path}'"))?;
+        if read == 0 {
+            break;
         }
+        hasher.update(&buffer[..read]);
     }
+    Ok(format!("{:032x}", hasher.digest128()))
 }
 
 #[async_trait]
 impl FileAdapter for LocalFileAdapter {
-    async fn list_files(&self, path: &str) -> Result<Vec<String>> {
-        let resolved_pattern = if let Some(base) = &self.base_path {
-            if path.starts_with('/') {
-                path.to_string()
-            } else {
+    fn resolve_pattern(&self, path: &str) -> String {
+        match &self.base_path {
+            Some(base) if !path.starts_with('/') => {
                 PathBuf::from(base).join(path).to_string_lossy().to_string()
             }
-        } else {
-            path.to_string()
-        };
+            _ => path.to_string(),
+        }
+    }
+
+    async fn list_files(&self, path: &str) -> Result<Vec<String>> {
+        let resolved_pattern = self.resolve_pattern(path);
 
         let mut existing_paths = Vec::new();
         if resolved_pattern.contains('*') || resolved_pattern.contains('?') {
@@ -59,16 +82,70 @@ impl FileAdapter for LocalFileAdapter {
         Ok(existing_paths)
     }
 
-    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<()> {
-        if files.is_empty() {
-            return Ok(());
+    async fn list_file_versions(&self, path: &str) -> Result<Vec<(String, String)>> {
+        let mut versions = Vec::new();
+        for file in self.list_files(path).await? {
+            let metadata = tokio::fs::metadata(&file)
+                .await
+                .with_context(|| format!("Failed to read metadata for '{file}'"))?;
+            let modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_nanos();
+            versions.push((file, format!("{modified}-{}", metadata.len())));
         }
 
-        let query = build_import_query(&self.adapter_config, files)?;
-        self.ducklake.create_table_from_query(table_name, &query)?;
+        Ok(versions)
+    }
+
+    async fn list_file_checksums(&self, path: &str) -> Result<Vec<(String, String)>> {
+        let files = self.list_files(path).await?;
+        tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .map(|file| {
+                    let checksum = file_checksum(&file)?;
+                    Ok((file, checksum))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .await?
+    }
+
+    async fn configure_access(&self) -> Result<()> {
         Ok(())
     }
 
+    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            false,
+            &self.progress,
+        )
+    }
+
+    async fn append_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            true,
+            &self.progress,
+        )
+    }
+
     async fn get_file_schema(&self, file_path: &str) -> Result<Vec<ColumnInfo>> {
         let resolved_path = if let Some(base) = &self.base_path {
             if file_path.starts_with('/') {
@@ -87,98 +164,7 @@ impl FileAdapter for LocalFileAdapter {
             return Err(anyhow::anyhow!("File '{}' does not exist", resolved_path));
         }
 
-        let temp_table = format!("temp_schema_check_{}", uuid::Uuid::new_v4().simple());
-
-        match &self.adapter_config.source {
-            crate::core::config::adapter::AdapterSource::File { format, .. } => {
-                let query = match format.ty.as_str() {
-                    "csv" => {
-                        let has_header = format.has_header.unwrap_or(true);
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_csv_auto('{resolved_path}', header={has_header}) LIMIT 0"
-                        )
-                    }
-                    "json" => {
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_json_auto('{resolved_path}') LIMIT 0"
-                        )
-                    }
-                    "parquet" => {
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_parquet('{resolved_path}') LIMIT 0"
-                        )
-                    }
-                    _ => return Err(anyhow::anyhow!("Unsupported file format: {}", format.ty)),
-                };
-
-                self.ducklake.execute_batch(&query)?;
-
-                let schema_query = format!("DESCRIBE {temp_table}");
-                let result = self.ducklake.query(&schema_query)?;
-
-                let _ = self
-                    .ducklake
-                    .execute_batch(&format!("DROP TABLE {temp_table}"));
-
-                let columns = result
-                    .into_iter()
-                    .map(|row| ColumnInfo {
-                        name: row[0].clone(),
-                        data_type: row[1].clone(),
-                    })
-                    .collect();
-
-                Ok(columns)
-            }
-            _ => Err(anyhow::anyhow!("Expected file source")),
-        }
-    }
-
-    async fn validate_schema(
-        &self,
-        file_path: &str,
-        expected_columns: &[ColumnConfig],
-    ) -> Result<()> {
-        let actual_columns = self.get_file_schema(file_path).await?;
-
-        for expected in expected_columns {
-            let found = actual_columns.iter().find(|col| col.name == expected.name);
-
-            match found {
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Column '{}' not found in file '{}'",
-                        expected.name,
-                        file_path
-                    ));
-                }
-                Some(actual) => {
-                    if !self.types_match(&expected.ty, &actual.data_type) {
-                        return Err(anyhow::anyhow!(
-                            "Column '{}' type mismatch: expected '{}', found '{}'",
-                            expected.name,
-                            expected.ty,
-                            actual.data_type
-                        ));
-                    }
-                }
-            }
-        }
-
-        Ok(())
-    }
-}
-
-impl LocalFileAdapter {
-    fn types_match(&self, expected: &str, actual: &str) -> bool {
-        let normalize_type = |t: &str| -> String {
-            t.to_uppercase()
-                .replace("INTEGER", "BIGINT")
-                .replace("STRING", "VARCHAR")
-                .replace("FLOAT", "DOUBLE")
-        };
-
-        normalize_type(expected) == normalize_type(actual)
+        describe_file(&self.ducklake, &self.adapter_config, &resolved_path)
     }
 }
 
@@ -188,6 +174,7 @@ mod tests {
     use crate::core::{
         adapter::test_helpers::*,
         config::adapter::{AdapterSource, FileConfig},
+        ducklake::ExportFormat,
     };
     use tempfile::tempdir;
 
@@ -209,6 +196,9 @@ mod tests {
             path: "test_data.csv".to_string(),
             compression: None,
             max_batch_size: None,
+            max_batch_files: None,
+            incremental: false,
+            import_mode: None,
         };
 
         let files = adapter.list_files(&file_config.path).await.unwrap();
@@ -246,6 +236,9 @@ mod tests {
             path: "test_data.json".to_string(),
             compression: None,
             max_batch_size: None,
+            max_batch_files: None,
+            incremental: false,
+            import_mode: None,
         };
 
         let files = adapter.list_files(&file_config.path).await.unwrap();
@@ -257,6 +250,229 @@ mod tests {
         assert_eq!(result[0][0], "3");
     }
 
+    fn file_adapter(ducklake: Arc<DuckLake>, dir: &Path, path: &str, ty: &str) -> LocalFileAdapter {
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, format } = &mut adapter_config.source {
+            file.path = path.to_string();
+            format.ty = ty.to_string();
+        }
+
+        LocalFileAdapter::new(
+            ducklake,
+            adapter_config,
+            Some(dir.to_string_lossy().to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_parquet_import() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let parquet_path = tempdir.path().join("users.parquet");
+        ducklake
+            .export_query(
+                "SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob')) AS t(id, name)",
+                ExportFormat::Parquet,
+                &parquet_path,
+            )
+            .unwrap();
+
+        let adapter = file_adapter(ducklake.clone(), tempdir.path(), "users.parquet", "parquet");
+
+        let schema = adapter.get_file_schema("users.parquet").await.unwrap();
+        assert_eq!(schema[0].name, "id");
+        assert_eq!(schema[1].name, "name");
+
+        let files = adapter.list_files("users.parquet").await.unwrap();
+        adapter.import_files("test_table", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT id, name FROM test_table ORDER BY id")
+            .unwrap();
+        assert_eq!(result, vec![vec!["1", "Alice"], vec!["2", "Bob"]]);
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_ndjson_import() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        write_test_file(
+            tempdir.path(),
+            "events.ndjson",
+            "{\"id\": 1, \"event\": \"login\"}\n{\"id\": 2, \"event\": \"logout\"}\n",
+        )
+        .unwrap();
+
+        let adapter = file_adapter(ducklake.clone(), tempdir.path(), "events.ndjson", "ndjson");
+
+        let schema = adapter.get_file_schema("events.ndjson").await.unwrap();
+        assert_eq!(schema.len(), 2);
+
+        let files = adapter.list_files("events.ndjson").await.unwrap();
+        adapter.import_files("test_table", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT id, event FROM test_table ORDER BY id")
+            .unwrap();
+        assert_eq!(result, vec![vec!["1", "login"], vec!["2", "logout"]]);
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_fixed_width_import() {
+        use crate::core::config::adapter::FixedWidthColumn;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        write_test_file(
+            tempdir.path(),
+            "accounts.txt",
+            "0001Alice     0012.50\r\n0002Bob       0300.00\r\n0003Charlie   0007.25\r\n",
+        )
+        .unwrap();
+
+        let mut adapter = file_adapter(
+            ducklake.clone(),
+            tempdir.path(),
+            "accounts.txt",
+            "fixed_width",
+        );
+        if let AdapterSource::File { format, .. } = &mut adapter.adapter_config.source {
+            format.columns_spec = vec![
+                FixedWidthColumn {
+                    name: "id".to_string(),
+                    start: 1,
+                    len: 4,
+                },
+                FixedWidthColumn {
+                    name: "name".to_string(),
+                    start: 5,
+                    len: 10,
+                },
+                FixedWidthColumn {
+                    name: "balance".to_string(),
+                    start: 15,
+                    len: 7,
+                },
+            ];
+        }
+
+        let schema = adapter.get_file_schema("accounts.txt").await.unwrap();
+        assert_eq!(
+            schema
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "name", "balance"]
+        );
+
+        let files = adapter.list_files("accounts.txt").await.unwrap();
+        adapter.import_files("accounts", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT id, name, balance FROM accounts ORDER BY id")
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                vec!["0001", "Alice", "0012.50"],
+                vec!["0002", "Bob", "0300.00"],
+                vec!["0003", "Charlie", "0007.25"],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_control_character_delimiter() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        write_test_file(
+            tempdir.path(),
+            "extract.dat",
+            "id\x01name\x01note\n1\x01Alice\x01a,b\n2\x01Bob\x01c;d\n",
+        )
+        .unwrap();
+
+        let mut adapter = file_adapter(ducklake.clone(), tempdir.path(), "extract.dat", "csv");
+        if let AdapterSource::File { format, .. } = &mut adapter.adapter_config.source {
+            format.delimiter = Some("\\x01".to_string());
+        }
+
+        let schema = adapter.get_file_schema("extract.dat").await.unwrap();
+        assert_eq!(schema.len(), 3);
+
+        let files = adapter.list_files("extract.dat").await.unwrap();
+        adapter.import_files("extract", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT id, name, note FROM extract ORDER BY id")
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![vec!["1", "Alice", "a,b"], vec!["2", "Bob", "c;d"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_xlsx_import() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        // The excel extension is downloaded on first use; skip when it can't be installed.
+        if ducklake
+            .execute_batch("INSTALL excel; LOAD excel;")
+            .is_err()
+        {
+            eprintln!("Skipping xlsx test: excel extension is not installable");
+            return;
+        }
+
+        let workbook = tempdir.path().join("users.xlsx");
+        ducklake
+            .execute_batch(&format!(
+                "COPY (SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob')) AS t(id, name)) \
+                 TO '{}' (FORMAT xlsx, HEADER true, SHEET 'people');",
+                workbook.display()
+            ))
+            .unwrap();
+
+        let xlsx_adapter = |sheet: &str| {
+            let mut adapter_config = create_csv_adapter_config();
+            if let AdapterSource::File { file, format } = &mut adapter_config.source {
+                file.path = "users.xlsx".to_string();
+                format.ty = "xlsx".to_string();
+                format.sheet = Some(sheet.to_string());
+            }
+            LocalFileAdapter::new(
+                ducklake.clone(),
+                adapter_config,
+                Some(tempdir.path().to_string_lossy().to_string()),
+            )
+        };
+
+        let adapter = xlsx_adapter("people");
+        let schema = adapter.get_file_schema("users.xlsx").await.unwrap();
+        assert_eq!(schema[0].name, "id");
+        assert_eq!(schema[1].name, "name");
+
+        let files = adapter.list_files("users.xlsx").await.unwrap();
+        adapter.import_files("test_table", &files).await.unwrap();
+        let result = ducklake
+            .query("SELECT name FROM test_table ORDER BY name")
+            .unwrap();
+        assert_eq!(result, vec![vec!["Alice"], vec!["Bob"]]);
+
+        let error = xlsx_adapter("missing")
+            .get_file_schema("users.xlsx")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Sheet 'missing' was not found in the workbook"
+        );
+    }
+
     #[tokio::test]
     async fn test_localfile_adapter_multiple_files() {
         let tempdir = tempdir().unwrap();
@@ -280,6 +496,9 @@ mod tests {
             path: "file*.csv".to_string(),
             compression: None,
             max_batch_size: None,
+            max_batch_files: None,
+            incremental: false,
+            import_mode: None,
         };
 
         let files = adapter.list_files(&file_config.path).await.unwrap();
@@ -291,6 +510,135 @@ mod tests {
         assert_eq!(result[0][0], "4");
     }
 
+    #[tokio::test]
+    async fn test_localfile_adapter_batched_import() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        for i in 1..=5 {
+            write_test_file(
+                tempdir.path(),
+                &format!("batch{i}.csv"),
+                &format!("id,name\n{i},user{i}"),
+            )
+            .unwrap();
+        }
+
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.path = "batch*.csv".to_string();
+            file.max_batch_files = Some(2);
+        }
+
+        let adapter = LocalFileAdapter::new(
+            ducklake.clone(),
+            adapter_config,
+            Some(tempdir.path().to_string_lossy().to_string()),
+        );
+
+        let files = adapter.list_files("batch*.csv").await.unwrap();
+        assert_eq!(files.len(), 5);
+
+        adapter.import_files("test_table", &files).await.unwrap();
+
+        let result = ducklake
+            .query("SELECT id FROM test_table ORDER BY id")
+            .unwrap();
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3", "4", "5"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_failed_batch_keeps_previous_table() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let write_batches = |range: std::ops::RangeInclusive<i32>| {
+            for i in range {
+                write_test_file(
+                    tempdir.path(),
+                    &format!("batch{i}.csv"),
+                    &format!("id,name\n{i},user{i}"),
+                )
+                .unwrap();
+            }
+        };
+        write_batches(1..=3);
+
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.max_batch_files = Some(2);
+        }
+
+        let adapter = LocalFileAdapter::new(
+            ducklake.clone(),
+            adapter_config,
+            Some(tempdir.path().to_string_lossy().to_string()),
+        );
+
+        let files = adapter.list_files("batch*.csv").await.unwrap();
+        adapter.import_files("test_table", &files).await.unwrap();
+
+        // The last batch fails after two batches have already been loaded.
+        write_batches(4..=5);
+        write_test_file(tempdir.path(), "batch6.csv", "id,name\nnot_a_number,user6").unwrap();
+        let files = adapter.list_files("batch*.csv").await.unwrap();
+        assert_eq!(files.len(), 6);
+        let error = adapter
+            .import_files("test_table", &files)
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("previous table kept"));
+
+        let result = ducklake
+            .query("SELECT id FROM test_table ORDER BY id")
+            .unwrap();
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3"]
+        );
+        let staging = ducklake
+            .query(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_name LIKE 'staging_%'",
+            )
+            .unwrap();
+        assert_eq!(staging[0][0], "0");
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_invalid_batch_size() {
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        write_test_file(tempdir.path(), "test_data.csv", &create_test_csv_data()).unwrap();
+
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.max_batch_files = Some(0);
+        }
+
+        let adapter = LocalFileAdapter::new(
+            ducklake.clone(),
+            adapter_config,
+            Some(tempdir.path().to_string_lossy().to_string()),
+        );
+
+        let files = adapter.list_files("test_data.csv").await.unwrap();
+        let error = adapter
+            .import_files("test_table", &files)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("max_batch_files"));
+    }
+
     #[tokio::test]
     async fn test_localfile_adapter_empty_files() {
         let tempdir = tempdir().unwrap();
@@ -306,4 +654,467 @@ mod tests {
         let result = adapter.import_files("test_table", &[]).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_incremental_import() {
+        use crate::core::{
+            adapter::{Adapter, file::state::ImportState},
+            config::project::ConnectionConfig,
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        write_test_file(tempdir.path(), "log1.csv", "id,name\n1,Alice\n2,Bob").unwrap();
+        write_test_file(tempdir.path(), "log2.csv", "id,name\n3,Charlie\n4,David").unwrap();
+
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.path = "log*.csv".to_string();
+            file.incremental = true;
+        }
+
+        let mut connections = HashMap::new();
+        connections.insert(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        );
+
+        let adapter =
+            Adapter::new(adapter_config, ducklake.clone()).with_project_dir(tempdir.path());
+
+        adapter.import("logs", &connections).await.unwrap();
+        let result = ducklake.query("SELECT COUNT(*) FROM logs").unwrap();
+        assert_eq!(result[0][0], "4");
+
+        let state = ImportState::load(tempdir.path(), "logs").await.unwrap();
+        assert_eq!(state.files.len(), 2);
+
+        write_test_file(tempdir.path(), "log3.csv", "id,name\n5,Eve").unwrap();
+
+        adapter.import("logs", &connections).await.unwrap();
+        let result = ducklake.query("SELECT id FROM logs ORDER BY id").unwrap();
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3", "4", "5"]
+        );
+
+        // Nothing changed, so a third run must not duplicate rows.
+        adapter.import("logs", &connections).await.unwrap();
+        let result = ducklake.query("SELECT COUNT(*) FROM logs").unwrap();
+        assert_eq!(result[0][0], "5");
+
+        adapter
+            .with_full_refresh(true)
+            .import("logs", &connections)
+            .await
+            .unwrap();
+        let result = ducklake.query("SELECT COUNT(*) FROM logs").unwrap();
+        assert_eq!(result[0][0], "5");
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_content_import_mode() {
+        use crate::core::{
+            adapter::Adapter,
+            config::{adapter::ImportMode, project::ConnectionConfig},
+            graph::FileCounts,
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        write_test_file(tempdir.path(), "log1.csv", "id,name\n1,Alice\n2,Bob").unwrap();
+        write_test_file(tempdir.path(), "log2.csv", "id,name\n3,Charlie").unwrap();
+
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        )]);
+        let adapter_with_mode = |mode| {
+            let mut adapter_config = create_csv_adapter_config();
+            if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+                file.path = "log*.csv".to_string();
+                file.import_mode = Some(mode);
+            }
+            Adapter::new(adapter_config, ducklake.clone()).with_project_dir(tempdir.path())
+        };
+        let reupload = || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            write_test_file(tempdir.path(), "log1.csv", "id,name\n1,Alice\n2,Bob").unwrap();
+        };
+        let count = || ducklake.query("SELECT COUNT(*) FROM logs").unwrap()[0][0].clone();
+
+        let content = adapter_with_mode(ImportMode::Content);
+        let stats = content.import("logs", &connections).await.unwrap();
+        assert_eq!(
+            stats.files,
+            Some(FileCounts {
+                imported: 2,
+                skipped: 0
+            })
+        );
+
+        // Rewriting a file with the same bytes changes its mtime but not its checksum.
+        reupload();
+        let stats = content.import("logs", &connections).await.unwrap();
+        assert_eq!(
+            stats.files,
+            Some(FileCounts {
+                imported: 0,
+                skipped: 2
+            })
+        );
+        assert_eq!(count(), "3");
+
+        write_test_file(tempdir.path(), "log2.csv", "id,name\n3,Charlie\n4,David").unwrap();
+        let stats = content.import("logs", &connections).await.unwrap();
+        assert_eq!(
+            stats.files,
+            Some(FileCounts {
+                imported: 1,
+                skipped: 1
+            })
+        );
+
+        let full = adapter_with_mode(ImportMode::Full);
+        reupload();
+        let stats = full.import("logs", &connections).await.unwrap();
+        assert_eq!(
+            stats.files,
+            Some(FileCounts {
+                imported: 2,
+                skipped: 0
+            })
+        );
+        assert_eq!(count(), "4");
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_schema_change_policies() {
+        use crate::core::{
+            adapter::{Adapter, SchemaDrift, database::ColumnInfo},
+            config::{
+                adapter::{ImportMode, SchemaChangePolicy},
+                project::ConnectionConfig,
+            },
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        )]);
+        let adapter_with_policy = |policy| {
+            let mut adapter_config = create_csv_adapter_config();
+            if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+                file.path = "users.csv".to_string();
+                file.import_mode = Some(ImportMode::Modified);
+            }
+            adapter_config.on_schema_change = Some(policy);
+            Adapter::new(adapter_config, ducklake.clone()).with_project_dir(tempdir.path())
+        };
+        let columns = || {
+            ducklake
+                .table_schema("users")
+                .unwrap()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        let write_users = |content: &str| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            write_test_file(tempdir.path(), "users.csv", content).unwrap();
+        };
+
+        write_users("id,name\n1,Alice\n2,Bob");
+        let stats = adapter_with_policy(SchemaChangePolicy::Fail)
+            .import("users", &connections)
+            .await
+            .unwrap();
+        assert_eq!(stats.schema_changes, None);
+
+        // `fail` leaves the table alone and reports the diff.
+        write_users("id,email\n3,charlie@example.com");
+        let error = adapter_with_policy(SchemaChangePolicy::Fail)
+            .import("users", &connections)
+            .await
+            .unwrap_err();
+        let drift = error.downcast_ref::<SchemaDrift>().unwrap();
+        assert_eq!(
+            drift.diff.added,
+            vec![ColumnInfo {
+                name: "email".to_string(),
+                data_type: "VARCHAR".to_string(),
+            }]
+        );
+        assert_eq!(drift.diff.removed, vec!["name".to_string()]);
+        assert!(drift.diff.retyped.is_empty());
+        assert!(
+            error
+                .to_string()
+                .contains("added email VARCHAR; removed name")
+        );
+        assert_eq!(columns(), vec!["id", "name"]);
+
+        // `append_new_columns` reports the widened schema. The file itself changed,
+        // so its old rows are replaced rather than appended to.
+        write_users("id,name,email\n1,Alice,alice@example.com\n3,Charlie,charlie@example.com");
+        let stats = adapter_with_policy(SchemaChangePolicy::AppendNewColumns)
+            .import("users", &connections)
+            .await
+            .unwrap();
+        let diff = stats.schema_changes.unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name, "email");
+        assert!(diff.removed.is_empty());
+        assert_eq!(columns(), vec!["id", "name", "email"]);
+        let rows = ducklake
+            .query("SELECT name, email FROM users ORDER BY id")
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], vec!["Alice", "alice@example.com"]);
+        assert_eq!(rows[1], vec!["Charlie", "charlie@example.com"]);
+
+        // `ignore` doesn't look; the reload simply takes the file's columns.
+        write_users("id,name,email,phone\n4,David,david@example.com,555-0100");
+        let stats = adapter_with_policy(SchemaChangePolicy::Ignore)
+            .import("users", &connections)
+            .await
+            .unwrap();
+        assert_eq!(stats.schema_changes, None);
+        assert_eq!(columns(), vec!["id", "name", "email", "phone"]);
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_modified_file_replaces_rows() {
+        use crate::core::{
+            adapter::Adapter,
+            config::{adapter::ImportMode, project::ConnectionConfig},
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        )]);
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.path = "part_*.csv".to_string();
+            file.import_mode = Some(ImportMode::Modified);
+        }
+        let adapter =
+            Adapter::new(adapter_config, ducklake.clone()).with_project_dir(tempdir.path());
+        let write_part = |name: &str, content: &str| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            write_test_file(tempdir.path(), name, content).unwrap();
+        };
+        let ids = || {
+            ducklake
+                .query("SELECT id FROM parts ORDER BY id")
+                .unwrap()
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>()
+        };
+
+        write_part("part_1.csv", "id,name\n1,Alice");
+        adapter.import("parts", &connections).await.unwrap();
+
+        // A new file is appended.
+        write_part("part_2.csv", "id,name\n2,Bob");
+        adapter.import("parts", &connections).await.unwrap();
+        assert_eq!(ids(), vec!["1", "2"]);
+
+        // A modified file doesn't keep its old rows next to the new ones.
+        write_part("part_1.csv", "id,name\n1,Alice\n3,Charlie");
+        adapter.import("parts", &connections).await.unwrap();
+        assert_eq!(ids(), vec!["1", "2", "3"]);
+
+        // Nor does a removed one.
+        std::fs::remove_file(tempdir.path().join("part_2.csv")).unwrap();
+        adapter.import("parts", &connections).await.unwrap();
+        assert_eq!(ids(), vec!["1", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_column_casting() {
+        use crate::core::{
+            adapter::Adapter,
+            config::{adapter::ColumnConfig, project::ConnectionConfig},
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        write_test_file(
+            tempdir.path(),
+            "stores.csv",
+            "zip,opened\n12345,2024-01-15\n98765,not a date\n01234,2024-02-01",
+        )
+        .unwrap();
+
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        )]);
+        let column = |name: &str, ty: &str, rename: Option<&str>, lenient: bool| ColumnConfig {
+            name: name.to_string(),
+            ty: ty.to_string(),
+            description: None,
+            rename: rename.map(str::to_string),
+            enforce: true,
+            lenient,
+        };
+        let adapter = |columns: Vec<ColumnConfig>| {
+            let mut adapter_config = create_csv_adapter_config();
+            if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+                file.path = "stores.csv".to_string();
+            }
+            adapter_config.columns = columns;
+            Adapter::new(adapter_config, ducklake.clone())
+        };
+
+        adapter(vec![
+            column("zip", "VARCHAR", Some("zip_code"), false),
+            column("opened", "DATE", None, true),
+        ])
+        .import("stores", &connections)
+        .await
+        .unwrap();
+        let result = ducklake
+            .query(
+                "SELECT zip_code, typeof(zip_code), CAST(opened AS VARCHAR) FROM stores ORDER BY zip_code",
+            )
+            .unwrap();
+        // Read as text, so the leading zero survives.
+        assert_eq!(result[0], vec!["01234", "VARCHAR", "2024-02-01"]);
+        assert_eq!(result[1], vec!["12345", "VARCHAR", "2024-01-15"]);
+        assert_eq!(result[2][0], "98765");
+        assert_eq!(result[2][2], "NULL");
+
+        let error = adapter(vec![column("opened", "DATE", None, false)])
+            .import("strict_stores", &connections)
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("Conversion Error"));
+        assert!(!ducklake.table_exists("strict_stores").unwrap());
+
+        let error = adapter(vec![
+            column("zipcode", "VARCHAR", None, false),
+            column("opened", "DATE", None, false),
+            column("country", "VARCHAR", None, false),
+        ])
+        .import("missing_stores", &connections)
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<crate::core::adapter::SchemaMismatch>(),
+            Some(&crate::core::adapter::SchemaMismatch::MissingColumns {
+                columns: vec!["zipcode".to_string(), "country".to_string()],
+                location: format!("file '{}'", tempdir.path().join("stores.csv").display()),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_localfile_adapter_dedupe() {
+        use crate::core::{
+            adapter::{Adapter, SchemaMismatch},
+            config::{adapter::DedupeConfig, project::ConnectionConfig},
+        };
+        use std::collections::HashMap;
+
+        let tempdir = tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        write_test_file(
+            tempdir.path(),
+            "orders_1.csv",
+            "id,amount,updated_at\n1,10,2024-01-01\n2,20,2024-01-01",
+        )
+        .unwrap();
+        write_test_file(
+            tempdir.path(),
+            "orders_2.csv",
+            "id,amount,updated_at\n2,20,2024-01-01\n3,30,2024-01-02\n1,15,2024-01-03",
+        )
+        .unwrap();
+
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: tempdir.path().to_string_lossy().to_string(),
+            },
+        )]);
+        let adapter = |dedupe: DedupeConfig| {
+            let mut adapter_config = create_csv_adapter_config();
+            if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+                file.path = "orders_*.csv".to_string();
+            }
+            adapter_config.dedupe = Some(dedupe);
+            Adapter::new(adapter_config, ducklake.clone())
+        };
+
+        let stats = adapter(DedupeConfig {
+            distinct: true,
+            unique_key: vec![],
+            order_by: None,
+        })
+        .import("distinct_orders", &connections)
+        .await
+        .unwrap();
+        assert_eq!(stats.row_count, 4);
+        assert_eq!(stats.duplicates_dropped, 1);
+
+        let latest = adapter(DedupeConfig {
+            distinct: false,
+            unique_key: vec!["id".to_string()],
+            order_by: Some("updated_at".to_string()),
+        });
+        let stats = latest.import("latest_orders", &connections).await.unwrap();
+        assert_eq!(stats.row_count, 3);
+        assert_eq!(stats.duplicates_dropped, 2);
+        let result = ducklake
+            .query("SELECT id, amount FROM latest_orders ORDER BY id")
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![vec!["1", "15"], vec!["2", "20"], vec!["3", "30"]]
+        );
+        latest.validate_dedupe(&connections).await.unwrap();
+
+        let error = adapter(DedupeConfig {
+            distinct: false,
+            unique_key: vec!["order_id".to_string()],
+            order_by: Some("modified_at".to_string()),
+        })
+        .validate_dedupe(&connections)
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<SchemaMismatch>(),
+            Some(&SchemaMismatch::MissingColumns {
+                columns: vec!["order_id".to_string(), "modified_at".to_string()],
+                location: format!("file '{}'", tempdir.path().join("orders_1.csv").display()),
+            })
+        );
+    }
 }
@@ -1,8 +1,6 @@
 use crate::core::{
-    config::{
-        adapter::{AdapterConfig, ColumnConfig},
-        project::S3Config,
-    },
+    adapter::ImportProgress,
+    config::{adapter::AdapterConfig, project::S3Config},
     ducklake::DuckLake,
 };
 use anyhow::Result;
@@ -13,6 +11,7 @@ pub struct S3FileAdapter {
     ducklake: Arc<DuckLake>,
     adapter_config: AdapterConfig,
     s3config: S3Config,
+    progress: ImportProgress,
 }
 
 impl S3FileAdapter {
@@ -21,120 +20,135 @@ impl S3FileAdapter {
             ducklake,
             adapter_config,
             s3config,
+            progress: ImportProgress::default(),
         }
     }
 
-    async fn list_s3_files(&self, pattern: &str) -> Result<Vec<String>> {
-        let mut config_loader = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(Region::new(self.s3config.region.clone()));
-
-        if let Some(endpoint) = &self.s3config.endpoint_url {
-            config_loader = config_loader.endpoint_url(endpoint);
-        }
-
-        match &self.s3config.auth_method {
-            S3AuthMethod::CredentialChain => {}
-            S3AuthMethod::Explicit => {
-                let secret_access_key = self
-                    .s3config
-                    .secret_access_key
-                    .as_ref()
-                    .ok_or_else(|| {
-                        anyhow::anyhow!("secret_access_key is required for explicit auth")
-                    })?
-                    .plaintext()?;
-                let session_token = None;
-                config_loader = config_loader.credentials_provider(Credentials::new(
-                    self.s3config.access_key_id.as_ref().ok_or_else(|| {
-                        anyhow::anyhow!("access_key_id is required for explicit auth")
-                    })?,
-                    secret_access_key,
-                    session_token,
-                    None,
-                    "duckhub",
-                ));
-            }
-        }
+    pub fn with_progress(mut self, progress: ImportProgress) -> Self {
+        self.progress = progress;
+        self
+    }
 
-        let aws_config = config_loader.load().await;
-        let s3_config_builder = Builder::from(&aws_config);
-        let aws_s3_config = if self.s3config.path_style_access {
-            s3_config_builder.force_path_style(true).build()
-        } else {
-            s3_config_builder.build()
+    async fn list_s3_files(&self, pattern: &str) -> Result<Vec<(String, String)>> {
+        let client = s3_client(&self.s3config).await?;
+        let bucket = &self.s3config.bucket;
+
+        let objects = match listing_plan(pattern) {
+            ListingPlan::Exact(key) => head_object(&client, bucket, &key)
+                .await?
+                .into_iter()
+                .collect(),
+            ListingPlan::Prefix(prefix) => list_objects(&client, bucket, &prefix).await?,
+            ListingPlan::Expand(prefix) => list_expanded(&client, bucket, &prefix).await?,
         };
 
-        let client = Client::from_conf(aws_s3_config);
-
-        let prefix = extract_prefix_from_pattern(pattern);
-        let all_keys = self.list_all_objects_with_prefix(&client, &prefix).await?;
-
-        let matching_objects: Vec<String> = all_keys
+        let regex = compile_pattern(pattern)?;
+        let mut matching_objects: Vec<(String, String)> = objects
             .into_iter()
-            .filter(|key| matches_pattern(pattern, key))
-            .map(|key| format!("s3://{}/{}", self.s3config.bucket, key))
+            .filter(|(key, _)| regex.is_match(key))
+            .map(|(key, etag)| (format!("s3://{bucket}/{key}"), etag))
             .collect();
+        matching_objects.sort();
 
         Ok(matching_objects)
     }
+}
 
-    async fn list_all_objects_with_prefix(
-        &self,
-        client: &Client,
-        prefix: &str,
-    ) -> Result<Vec<String>> {
-        let mut all_keys = Vec::new();
-        let mut continuation_token = None;
-
-        loop {
-            let mut request = client
-                .list_objects_v2()
-                .bucket(&self.s3config.bucket)
-                .prefix(prefix);
-
-            if let Some(token) = continuation_token {
-                request = request.continuation_token(token);
-            }
-
-            let result = request.send().await.context("Failed to list S3 objects")?;
+pub async fn s3_client(s3config: &S3Config) -> Result<Client> {
+    let mut config_loader = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(Region::new(s3config.region.clone()));
 
-            if let Some(contents) = result.contents {
-                for object in contents {
-                    if let Some(key) = object.key {
-                        all_keys.push(key);
-                    }
-                }
-            }
+    if let Some(endpoint) = &s3config.endpoint_url {
+        config_loader = config_loader.endpoint_url(endpoint);
+    }
 
-            continuation_token = result.next_continuation_token;
-            if continuation_token.is_none() {
-                break;
-            }
+    match &s3config.auth_method {
+        S3AuthMethod::CredentialChain => {}
+        S3AuthMethod::Explicit => {
+            let secret_access_key = s3config
+                .secret_access_key
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("secret_access_key is required for explicit auth"))?
+                .plaintext()?;
+            let session_token = None;
+            config_loader = config_loader.credentials_provider(Credentials::new(
+                s3config.access_key_id.as_ref().ok_or_else(|| {
+                    anyhow::anyhow!("access_key_id is required for explicit auth")
+                })?,
+                secret_access_key,
+                session_token,
+                None,
+                "duckhub",
+            ));
         }
-
-        Ok(all_keys)
     }
+
+    let aws_config = config_loader.load().await;
+    let s3_config_builder = Builder::from(&aws_config);
+    let aws_s3_config = if s3config.path_style_access {
+        s3_config_builder.force_path_style(true).build()
+    } else {
+        s3_config_builder.build()
+    };
+
+    Ok(Client::from_conf(aws_s3_config))
 }
 
 #[async_trait]
 impl FileAdapter for S3FileAdapter {
+    fn resolve_pattern(&self, path: &str) -> String {
+        format!("s3://{}/{path}", self.s3config.bucket)
+    }
+
     async fn list_files(&self, path: &str) -> Result<Vec<String>> {
         let files = self.list_s3_files(path).await?;
-        Ok(files)
+        Ok(files.into_iter().map(|(file, _)| file).collect())
+    }
+
+    async fn list_file_versions(&self, path: &str) -> Result<Vec<(String, String)>> {
+        self.list_s3_files(path).await
+    }
+
+    async fn configure_access(&self) -> Result<()> {
+        self.ducklake.configure_s3_connection(&self.s3config).await
     }
 
-    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<()> {
+    async fn import_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
         if files.is_empty() {
-            return Ok(());
+            return Ok(0);
         }
 
         self.ducklake
             .configure_s3_connection(&self.s3config)
             .await?;
 
-        let query = build_import_query(&self.adapter_config, files)?;
-        self.ducklake.create_table_from_query(table_name, &query)?;
-        Ok(())
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            false,
+            &self.progress,
+        )
+    }
+
+    async fn append_files(&self, table_name: &str, files: &[String]) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        self.ducklake
+            .configure_s3_connection(&self.s3config)
+            .await?;
+
+        import_file_batches(
+            &self.ducklake,
+            &self.adapter_config,
+            table_name,
+            files,
+            true,
+            &self.progress,
+        )
     }
 
     async fn get_file_schema(&self, file_path: &str) -> Result<Vec<ColumnInfo>> {
@@ -148,98 +162,7 @@ impl FileAdapter for S3FileAdapter {
             .configure_s3_connection(&self.s3config)
             .await?;
 
-        let temp_table = format!("temp_schema_check_{}", uuid::Uuid::new_v4().simple());
-
-        match &self.adapter_config.source {
-            crate::core::config::adapter::AdapterSource::File { format, .. } => {
-                let query = match format.ty.as_str() {
-                    "csv" => {
-                        let has_header = format.has_header.unwrap_or(true);
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_csv_auto('{s3_path}', header={has_header}) LIMIT 0"
-                        )
-                    }
-                    "json" => {
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_json_auto('{s3_path}') LIMIT 0"
-                        )
-                    }
-                    "parquet" => {
-                        format!(
-                            "CREATE TEMP TABLE {temp_table} AS SELECT * FROM read_parquet('{s3_path}') LIMIT 0"
-                        )
-                    }
-                    _ => return Err(anyhow::anyhow!("Unsupported file format: {}", format.ty)),
-                };
-
-                self.ducklake.execute_batch(&query)?;
-
-                let schema_query = format!("DESCRIBE {temp_table}");
-                let result = self.ducklake.query(&schema_query)?;
-
-                let _ = self
-                    .ducklake
-                    .execute_batch(&format!("DROP TABLE {temp_table}"));
-
-                let columns = result
-                    .into_iter()
-                    .map(|row| ColumnInfo {
-                        name: row[0].clone(),
-                        data_type: row[1].clone(),
-                    })
-                    .collect();
-
-                Ok(columns)
-            }
-            _ => Err(anyhow::anyhow!("Expected file source")),
-        }
-    }
-
-    async fn validate_schema(
-        &self,
-        file_path: &str,
-        expected_columns: &[ColumnConfig],
-    ) -> Result<()> {
-        let actual_columns = self.get_file_schema(file_path).await?;
-
-        for expected in expected_columns {
-            let found = actual_columns.iter().find(|col| col.name == expected.name);
-
-            match found {
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Column '{}' not found in file '{}'",
-                        expected.name,
-                        file_path
-                    ));
-                }
-                Some(actual) => {
-                    if !self.types_match(&expected.ty, &actual.data_type) {
-                        return Err(anyhow::anyhow!(
-                            "Column '{}' type mismatch: expected '{}', found '{}'",
-                            expected.name,
-                            expected.ty,
-                            actual.data_type
-                        ));
-                    }
-                }
-            }
-        }
-
-        Ok(())
-    }
-}
-
-impl S3FileAdapter {
-    fn types_match(&self, expected: &str, actual: &str) -> bool {
-        let normalize_type = |t: &str| -> String {
-            t.to_uppercase()
-                .replace("INTEGER", "BIGINT")
-                .replace("STRING", "VARCHAR")
-                .replace("FLOAT", "DOUBLE")
-        };
-
-        normalize_type(expected) == normalize_type(actual)
+        describe_file(&self.ducklake, &self.adapter_config, &s3_path)
     }
 }
 
@@ -251,10 +174,40 @@ use aws_sdk_s3::{
     config::{Builder, Credentials},
 };
 use regex::Regex;
+use tokio::task::JoinSet;
+
+use super::{super::database::ColumnInfo, FileAdapter, describe_file, import_file_batches};
+
+// Listings beyond this many prefixes wait for an earlier one to finish.
+const MAX_CONCURRENT_LISTINGS: usize = 8;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum ListingPlan {
+    // No wildcards: the pattern is the key itself.
+    Exact(String),
+    // The wildcard is in the last segment: list everything under the prefix.
+    Prefix(String),
+    // More segments follow the wildcard: expand the directories under the prefix
+    // with a delimiter listing and list each of them.
+    Expand(String),
+}
+
+// The prefix runs up to the first wildcard, including any literal part of its
+// segment, so `logs/2024-*/events_*.json` lists `logs/2024-` rather than `logs/`.
+pub(super) fn listing_plan(pattern: &str) -> ListingPlan {
+    let Some(index) = pattern.find(['*', '?']) else {
+        return ListingPlan::Exact(pattern.to_string());
+    };
 
-use super::{super::database::ColumnInfo, FileAdapter, build_import_query};
+    let prefix = pattern[..index].to_string();
+    if pattern[index..].contains('/') {
+        ListingPlan::Expand(prefix)
+    } else {
+        ListingPlan::Prefix(prefix)
+    }
+}
 
-fn extract_prefix_from_pattern(pattern: &str) -> String {
+pub(super) fn extract_prefix_from_pattern(pattern: &str) -> String {
     let mut prefix = String::new();
     for part in pattern.split('/') {
         if part.contains('*') || part.contains('?') {
@@ -268,15 +221,229 @@ fn extract_prefix_from_pattern(pattern: &str) -> String {
     prefix
 }
 
-fn matches_pattern(pattern: &str, key: &str) -> bool {
-    let pattern_regex = pattern
-        .replace(".", "\\.")
-        .replace("*", ".*")
-        .replace("?", ".");
+// `*` matches any run of characters and `?` any single one; everything else is
+// literal.
+pub(super) fn compile_pattern(pattern: &str) -> Result<Regex> {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).with_context(|| format!("Invalid file pattern '{pattern}'"))
+}
+
+async fn head_object(client: &Client, bucket: &str, key: &str) -> Result<Option<(String, String)>> {
+    match client.head_object().bucket(bucket).key(key).send().await {
+        Ok(object) => Ok(Some((key.to_string(), object.e_tag.unwrap_or_default()))),
+        Err(error) if error.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(None),
+        Err(error) => Err(error).with_context(|| format!("Failed to look up S3 object '{key}'")),
+    }
+}
 
-    if let Ok(regex) = Regex::new(&format!("^{pattern_regex}$")) {
-        regex.is_match(key)
-    } else {
-        false
+async fn list_objects(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<(String, String)>> {
+    let (objects, _) = list_page_by_page(client, bucket, prefix, None).await?;
+    Ok(objects)
+}
+
+// Lists one level of directories under `prefix`, then lists those concurrently.
+// Objects sitting directly under `prefix` come back from the first listing.
+async fn list_expanded(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<(String, String)>> {
+    let (mut objects, directories) = list_page_by_page(client, bucket, prefix, Some("/")).await?;
+
+    let mut listings = JoinSet::new();
+    for directory in directories {
+        if listings.len() >= MAX_CONCURRENT_LISTINGS
+            && let Some(result) = listings.join_next().await
+        {
+            objects.extend(result??);
+        }
+        let client = client.clone();
+        let bucket = bucket.to_string();
+        listings.spawn(async move { list_objects(&client, &bucket, &directory).await });
+    }
+    while let Some(result) = listings.join_next().await {
+        objects.extend(result??);
+    }
+
+    Ok(objects)
+}
+
+// Returns the objects under `prefix` and, when a delimiter is given, the common
+// prefixes it groups the remaining keys into.
+async fn list_page_by_page(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+    delimiter: Option<&str>,
+) -> Result<(Vec<(String, String)>, Vec<String>)> {
+    let mut objects = Vec::new();
+    let mut common_prefixes = Vec::new();
+    let mut continuation_token = None;
+
+    loop {
+        let mut request = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_delimiter(delimiter.map(str::to_string));
+
+        if let Some(token) = continuation_token {
+            request = request.continuation_token(token);
+        }
+
+        let result = request.send().await.context("Failed to list S3 objects")?;
+
+        for object in result.contents.unwrap_or_default() {
+            if let Some(key) = object.key {
+                objects.push((key, object.e_tag.unwrap_or_default()));
+            }
+        }
+        common_prefixes.extend(
+            result
+                .common_prefixes
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|common| common.prefix),
+        );
+
+        continuation_token = result.next_continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    Ok((objects, common_prefixes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::adapter::test_helpers::{
+        create_csv_adapter_config, create_test_s3_config, setup_minio_test_data,
+        setup_test_ducklake,
+    };
+
+    #[test]
+    fn test_listing_plan() {
+        let cases = [
+            (
+                "data/file.csv",
+                ListingPlan::Exact("data/file.csv".to_string()),
+            ),
+            ("data/*.csv", ListingPlan::Prefix("data/".to_string())),
+            (
+                "data/file_?.csv",
+                ListingPlan::Prefix("data/file_".to_string()),
+            ),
+            ("*.csv", ListingPlan::Prefix(String::new())),
+            (
+                "logs/*/events.json",
+                ListingPlan::Expand("logs/".to_string()),
+            ),
+            (
+                "logs/2024-*/events_*.json",
+                ListingPlan::Expand("logs/2024-".to_string()),
+            ),
+            (
+                "logs/2024-0?/a/*.json",
+                ListingPlan::Expand("logs/2024-0".to_string()),
+            ),
+        ];
+
+        for (pattern, expected) in cases {
+            assert_eq!(listing_plan(pattern), expected, "pattern '{pattern}'");
+        }
+    }
+
+    #[test]
+    fn test_compile_pattern() -> Result<()> {
+        let regex = compile_pattern("logs/2024-*/events_?.json")?;
+        assert!(regex.is_match("logs/2024-01/events_1.json"));
+        assert!(!regex.is_match("logs/2024-01/events_10.json"));
+        assert!(!regex.is_match("logs/2024-01/events_1xjson"));
+        assert!(!regex.is_match("logs/2023-12/events_1.json"));
+
+        let regex = compile_pattern("data/(v1)+.csv")?;
+        assert!(regex.is_match("data/(v1)+.csv"));
+        assert!(!regex.is_match("data/v1.csv"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_nested_prefixes() -> Result<()> {
+        setup_minio_test_data().await?;
+        let s3_config = create_test_s3_config();
+        let client = s3_client(&s3_config).await?;
+        let root = format!("listing-{}", uuid::Uuid::new_v4().simple());
+        for key in [
+            "2023-12/events_0.json",
+            "2024-01/events_1.json",
+            "2024-01/other.json",
+            "2024-02/events_2.json",
+            "2024-02/events_3.json",
+            "2024-notes.json",
+        ] {
+            client
+                .put_object()
+                .bucket(&s3_config.bucket)
+                .key(format!("{root}/{key}"))
+                .body(b"{}".to_vec().into())
+                .send()
+                .await?;
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        let adapter = S3FileAdapter::new(ducklake, create_csv_adapter_config(), s3_config);
+        let url = |key: &str| format!("s3://test-bucket/{root}/{key}");
+
+        let files = adapter
+            .list_files(&format!("{root}/2024-*/events_*.json"))
+            .await?;
+        assert_eq!(
+            files,
+            vec![
+                url("2024-01/events_1.json"),
+                url("2024-02/events_2.json"),
+                url("2024-02/events_3.json"),
+            ]
+        );
+
+        let files = adapter.list_files(&format!("{root}/2024-*.json")).await?;
+        assert_eq!(
+            files,
+            vec![
+                url("2024-01/events_1.json"),
+                url("2024-01/other.json"),
+                url("2024-02/events_2.json"),
+                url("2024-02/events_3.json"),
+                url("2024-notes.json")
+            ]
+        );
+
+        let files = adapter
+            .list_files(&format!("{root}/2024-01/other.json"))
+            .await?;
+        assert_eq!(files, vec![url("2024-01/other.json")]);
+
+        let files = adapter
+            .list_files(&format!("{root}/2024-01/missing.json"))
+            .await?;
+        assert!(files.is_empty());
+
+        Ok(())
     }
 }
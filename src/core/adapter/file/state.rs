@@ -0,0 +1,150 @@
+use crate::core::config::{adapter::ImportMode, write_atomic};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportState {
+    // States written before import modes existed were all `modified`.
+    #[serde(default = "modified")]
+    pub mode: ImportMode,
+    // File path -> version (mtime/size for local files, ETag for S3 objects) or, in
+    // `content` mode, checksum.
+    pub files: BTreeMap<String, String>,
+}
+
+fn modified() -> ImportMode {
+    ImportMode::Modified
+}
+
+impl ImportState {
+    pub async fn load(project_dir: &Path, name: &str) -> Result<Self> {
+        let path = Self::get_path(project_dir, name);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path).await?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub async fn save(&self, project_dir: &Path, name: &str) -> Result<()> {
+        let path = Self::get_path(project_dir, name);
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(&path, &content)
+    }
+
+    pub fn changed_files(&self, files: &[(String, String)]) -> Vec<String> {
+        files
+            .iter()
+            .filter(|(path, version)| self.files.get(path) != Some(version))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    // Whether a previously imported file has changed or gone away. Its old rows are
+    // already in the table, so appending alone would duplicate or keep them.
+    pub fn has_stale_files(&self, files: &[(String, String)]) -> bool {
+        let current: BTreeMap<&String, &String> = files
+            .iter()
+            .map(|(path, version)| (path, version))
+            .collect();
+        self.files
+            .iter()
+            .any(|(path, version)| current.get(path) != Some(&version))
+    }
+
+    pub fn from_files(mode: ImportMode, files: &[(String, String)]) -> Self {
+        Self {
+            mode,
+            files: files.iter().cloned().collect(),
+        }
+    }
+
+    fn get_path(project_dir: &Path, name: &str) -> PathBuf {
+        project_dir
+            .join(".data")
+            .join("adapter_state")
+            .join(format!("{name}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_state_roundtrip() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let files = vec![
+            ("a.json".to_string(), "1".to_string()),
+            ("b.json".to_string(), "2".to_string()),
+        ];
+
+        let state = ImportState::from_files(ImportMode::Content, &files);
+        state.save(tempdir.path(), "logs").await?;
+
+        let loaded = ImportState::load(tempdir.path(), "logs").await?;
+        assert_eq!(loaded, state);
+
+        let missing = ImportState::load(tempdir.path(), "other").await?;
+        assert!(missing.files.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_without_mode_is_modified() -> Result<()> {
+        let state: ImportState = serde_json::from_str(r#"{"files": {"a.json": "1"}}"#)?;
+        assert_eq!(state.mode, ImportMode::Modified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let state = ImportState::from_files(
+            ImportMode::Modified,
+            &[
+                ("a.json".to_string(), "1".to_string()),
+                ("b.json".to_string(), "2".to_string()),
+            ],
+        );
+
+        let changed = state.changed_files(&[
+            ("a.json".to_string(), "1".to_string()),
+            ("b.json".to_string(), "3".to_string()),
+            ("c.json".to_string(), "1".to_string()),
+        ]);
+
+        assert_eq!(changed, vec!["b.json".to_string(), "c.json".to_string()]);
+    }
+
+    #[test]
+    fn test_has_stale_files() {
+        let state = ImportState::from_files(
+            ImportMode::Modified,
+            &[
+                ("a.json".to_string(), "1".to_string()),
+                ("b.json".to_string(), "2".to_string()),
+            ],
+        );
+
+        // New files alone can be appended.
+        assert!(!state.has_stale_files(&[
+            ("a.json".to_string(), "1".to_string()),
+            ("b.json".to_string(), "2".to_string()),
+            ("c.json".to_string(), "1".to_string()),
+        ]));
+        // A modified file can't.
+        assert!(state.has_stale_files(&[
+            ("a.json".to_string(), "1".to_string()),
+            ("b.json".to_string(), "3".to_string()),
+        ]));
+        // Nor can a removed one.
+        assert!(state.has_stale_files(&[("a.json".to_string(), "1".to_string())]));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::core::{config::adapter::ColumnConfig, ducklake::TypedResult};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+pub const DEFAULT_SAMPLE_ROWS: usize = 1_000;
+
+// A text column is taken for dates when this share of its values parse as one.
+const DATE_SHARE: f64 = 0.8;
+const MOSTLY_NULL_SHARE: f64 = 0.9;
+
+const DATE_TIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceIssue {
+    // Detected as a number, but the file writes some values with leading zeros.
+    LeadingZeros,
+    // Left as text although most values are dates.
+    DateAsText,
+    MostlyNull,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InferenceFinding {
+    pub column: String,
+    pub inferred_type: String,
+    pub issue: InferenceIssue,
+    pub message: String,
+    pub suggestion: Option<ColumnConfig>,
+}
+
+// `suggested_columns` lists every column, so it can replace an adapter's empty
+// `columns` as is: listing only the flagged ones would drop the rest on import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InferenceReport {
+    pub sampled_rows: usize,
+    pub findings: Vec<InferenceFinding>,
+    pub suggested_columns: Vec<ColumnConfig>,
+}
+
+// `typed` is a sample read with detected types; `raw` the same rows read as text,
+// when the format can be read that way.
+pub fn inspect(typed: &TypedResult, raw: Option<&TypedResult>) -> InferenceReport {
+    let sampled_rows = typed.row_count();
+    let mut findings = vec![];
+
+    for (column, ty) in typed.columns.iter().zip(&typed.types) {
+        let values = typed
+            .data
+            .get(column)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let raw_values = raw
+            .and_then(|raw| raw.data.get(column))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let finding = |issue, message: String, suggestion| InferenceFinding {
+            column: column.clone(),
+            inferred_type: ty.clone(),
+            issue,
+            message,
+            suggestion,
+        };
+
+        if is_numeric(ty)
+            && let Some(example) = texts(raw_values).find(|value| has_leading_zero(value))
+        {
+            findings.push(finding(
+                InferenceIssue::LeadingZeros,
+                format!("'{column}' was read as {ty}, which drops the leading zero of values like '{example}'"),
+                Some(suggested(column, "VARCHAR", false)),
+            ));
+        } else if ty == "VARCHAR"
+            && let Some((date_type, share)) = date_share(values)
+        {
+            findings.push(finding(
+                InferenceIssue::DateAsText,
+                format!(
+                    "'{column}' was read as VARCHAR, but {:.0}% of its values are {date_type} values",
+                    share * 100.0
+                ),
+                Some(suggested(column, date_type, share < 1.0)),
+            ));
+        }
+
+        let nulls = values.iter().filter(|value| value.is_null()).count();
+        if sampled_rows > 0 && nulls as f64 / sampled_rows as f64 >= MOSTLY_NULL_SHARE {
+            findings.push(finding(
+                InferenceIssue::MostlyNull,
+                format!("'{column}' is empty in {nulls} of {sampled_rows} sampled rows, so its type is a guess"),
+                None,
+            ));
+        }
+    }
+
+    let suggested_columns = if findings.iter().any(|f| f.suggestion.is_some()) {
+        typed
+            .columns
+            .iter()
+            .zip(&typed.types)
+            .map(|(column, ty)| {
+                findings
+                    .iter()
+                    .find_map(|f| f.suggestion.clone().filter(|_| f.column == *column))
+                    .unwrap_or_else(|| ColumnConfig {
+                        enforce: false,
+                        ..suggested(column, ty, false)
+                    })
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    InferenceReport {
+        sampled_rows,
+        findings,
+        suggested_columns,
+    }
+}
+
+fn suggested(column: &str, ty: &str, lenient: bool) -> ColumnConfig {
+    ColumnConfig {
+        name: column.to_string(),
+        ty: ty.to_string(),
+        description: None,
+        rename: None,
+        enforce: true,
+        lenient,
+    }
+}
+
+fn texts(values: &[Value]) -> impl Iterator<Item = &str> {
+    values.iter().filter_map(Value::as_str)
+}
+
+fn is_numeric(ty: &str) -> bool {
+    let base = ty.split('(').next().unwrap_or_default();
+    matches!(
+        base,
+        "TINYINT"
+            | "SMALLINT"
+            | "INTEGER"
+            | "BIGINT"
+            | "HUGEINT"
+            | "UTINYINT"
+            | "USMALLINT"
+            | "UINTEGER"
+            | "UBIGINT"
+            | "FLOAT"
+            | "DOUBLE"
+            | "DECIMAL"
+    )
+}
+
+// "0" and "0.5" are ordinary numbers; "0123" is an identifier.
+fn has_leading_zero(value: &str) -> bool {
+    let mut chars = value.trim().chars();
+    chars.next() == Some('0') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+// The type most non-null values parse as and the share that do, if it is high enough.
+fn date_share(values: &[Value]) -> Option<(&'static str, f64)> {
+    let texts: Vec<&str> = texts(values).map(str::trim).collect();
+    if texts.is_empty() {
+        return None;
+    }
+
+    let dates = texts
+        .iter()
+        .filter(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok())
+        .count();
+    let timestamps = texts
+        .iter()
+        .filter(|text| {
+            DATE_TIME_FORMATS
+                .iter()
+                .any(|format| NaiveDateTime::parse_from_str(text, format).is_ok())
+        })
+        .count();
+
+    let (date_type, count) = if timestamps > dates {
+        ("TIMESTAMP", timestamps)
+    } else {
+        ("DATE", dates)
+    };
+    let share = count as f64 / texts.len() as f64;
+    (count > 0 && share >= DATE_SHARE).then_some((date_type, share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn result(columns: &[(&str, &str, Vec<Value>)]) -> TypedResult {
+        TypedResult {
+            columns: columns
+                .iter()
+                .map(|(name, _, _)| name.to_string())
+                .collect(),
+            types: columns.iter().map(|(_, ty, _)| ty.to_string()).collect(),
+            data: columns
+                .iter()
+                .map(|(name, _, values)| (name.to_string(), values.clone()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        let notes = vec![Value::Null; 9]
+            .into_iter()
+            .chain([json!("called back")])
+            .collect::<Vec<_>>();
+        let typed = result(&[
+            ("id", "BIGINT", (1..=10).map(|i| json!(i)).collect()),
+            ("zip", "BIGINT", vec![json!(1234); 10]),
+            ("price", "DOUBLE", vec![json!(0.5); 10]),
+            (
+                "opened",
+                "VARCHAR",
+                (1..=9)
+                    .map(|day| json!(format!("2024-01-0{day}")))
+                    .chain([json!("unknown")])
+                    .collect(),
+            ),
+            ("notes", "VARCHAR", notes),
+        ]);
+        let raw = result(&[
+            (
+                "id",
+                "VARCHAR",
+                (1..=10).map(|i| json!(i.to_string())).collect(),
+            ),
+            ("zip", "VARCHAR", vec![json!("01234"); 10]),
+            ("price", "VARCHAR", vec![json!("0.5"); 10]),
+        ]);
+
+        let report = inspect(&typed, Some(&raw));
+        assert_eq!(report.sampled_rows, 10);
+
+        let issues: Vec<(&str, InferenceIssue)> = report
+            .findings
+            .iter()
+            .map(|f| (f.column.as_str(), f.issue))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("zip", InferenceIssue::LeadingZeros),
+                ("opened", InferenceIssue::DateAsText),
+                ("notes", InferenceIssue::MostlyNull),
+            ]
+        );
+
+        let types: Vec<(&str, &str, bool, bool)> = report
+            .suggested_columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.ty.as_str(), c.enforce, c.lenient))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("id", "BIGINT", false, false),
+                ("zip", "VARCHAR", true, false),
+                ("price", "DOUBLE", false, false),
+                ("opened", "DATE", true, true),
+                ("notes", "VARCHAR", false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inspect_clean_sample() {
+        let typed = result(&[("id", "BIGINT", vec![json!(1), json!(2)])]);
+        let report = inspect(&typed, None);
+        assert!(report.findings.is_empty());
+        assert!(report.suggested_columns.is_empty());
+    }
+}
@@ -11,6 +11,12 @@ use anyhow::Result;
 use std::{fs, path::Path, sync::Arc};
 
 pub async fn setup_test_ducklake(tempdir: &Path) -> Result<Arc<DuckLake>> {
+    let (catalog_config, storage_config) = test_lake_configs(tempdir);
+    let ducklake = Arc::new(DuckLake::new(catalog_config, storage_config).await?);
+    Ok(ducklake)
+}
+
+pub fn test_lake_configs(tempdir: &Path) -> (CatalogConfig, StorageConfig) {
     let catalog_config = CatalogConfig::Sqlite {
         path: tempdir
             .join("test_catalog.sqlite")
@@ -20,9 +26,7 @@ pub async fn setup_test_ducklake(tempdir: &Path) -> Result<Arc<DuckLake>> {
     let storage_config = StorageConfig::LocalFile {
         path: tempdir.to_string_lossy().to_string(),
     };
-
-    let ducklake = Arc::new(DuckLake::new(catalog_config, storage_config).await?);
-    Ok(ducklake)
+    (catalog_config, storage_config)
 }
 
 pub fn create_test_csv_data() -> String {
@@ -41,63 +45,120 @@ pub fn create_test_json_data() -> String {
 pub fn create_csv_adapter_config() -> AdapterConfig {
     AdapterConfig {
         connection: "local".to_string(),
+        target_table: None,
+        dedupe: None,
         description: None,
         source: AdapterSource::File {
             file: FileConfig {
                 path: "test_data.csv".to_string(),
                 compression: None,
                 max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
             },
             format: FormatConfig {
                 ty: "csv".to_string(),
                 delimiter: None,
                 null_value: None,
                 has_header: Some(true),
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
             },
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     }
 }
 
 pub fn create_json_adapter_config() -> AdapterConfig {
     AdapterConfig {
         connection: "local".to_string(),
+        target_table: None,
+        dedupe: None,
         description: None,
         source: AdapterSource::File {
             file: FileConfig {
                 path: "test_data.json".to_string(),
                 compression: None,
                 max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
             },
             format: FormatConfig {
                 ty: "json".to_string(),
                 delimiter: None,
                 null_value: None,
                 has_header: None,
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
             },
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     }
 }
 
 pub fn create_s3_adapter_config() -> AdapterConfig {
     AdapterConfig {
         connection: "s3".to_string(),
+        target_table: None,
+        dedupe: None,
         description: None,
         source: AdapterSource::File {
             file: FileConfig {
                 path: "test-data/*.csv".to_string(),
                 compression: None,
                 max_batch_size: None,
+                max_batch_files: None,
+                incremental: false,
+                import_mode: None,
             },
             format: FormatConfig {
                 ty: "csv".to_string(),
                 delimiter: None,
                 null_value: None,
                 has_header: Some(true),
+                sheet: None,
+                columns_spec: vec![],
+                quote: None,
+                escape: None,
+                date_format: None,
+                timestamp_format: None,
             },
         },
         columns: vec![],
+        tests: vec![],
+        relationships: vec![],
+        on_schema_change: None,
+        tags: vec![],
+        group: None,
+        weight: None,
+        exclusive: false,
+        masking: vec![],
     }
 }
 
@@ -0,0 +1,471 @@
+use crate::core::config::{
+    Config,
+    project::{ConnectionConfig, MASKED_SECRET, ProjectConfig},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex};
+
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    // `<kind>/<name>`, e.g. `models/orders`; project settings are `project`.
+    pub resource: String,
+    pub operation: AuditOperation,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub principal: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn kind(&self) -> &str {
+        self.resource.split('/').next().unwrap_or_default()
+    }
+
+    // A `resource` filter names either one resource or every resource of a kind.
+    pub fn matches(&self, resource: &str) -> bool {
+        self.resource == resource || self.kind() == resource
+    }
+
+    pub fn diff(&self) -> String {
+        let label = |side: &str, body: &Option<String>| match body {
+            Some(_) => format!("{side}/{}", self.resource),
+            None => "/dev/null".to_string(),
+        };
+        unified_diff(
+            self.before.as_deref().unwrap_or_default(),
+            self.after.as_deref().unwrap_or_default(),
+            &label("a", &self.before),
+            &label("b", &self.after),
+        )
+    }
+}
+
+// One resource rendered as YAML with secrets masked, before and after a write;
+// `None` on the side where it doesn't exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditChange {
+    resource: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl AuditChange {
+    // `kind` is the resource's route prefix, e.g. `models`.
+    pub fn new<T: Serialize>(
+        kind: &str,
+        name: &str,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Self {
+        Self {
+            resource: format!("{kind}/{name}"),
+            before: before.and_then(render),
+            after: after.and_then(render),
+        }
+    }
+
+    // A rename is recorded as the old name going away and the new one appearing.
+    pub fn renamed<T: Serialize>(kind: &str, from: &str, to: &str, value: &T) -> [Self; 2] {
+        [
+            Self::new(kind, from, Some(value), None),
+            Self::new(kind, to, None, Some(value)),
+        ]
+    }
+
+    pub fn connection(
+        name: &str,
+        before: Option<&ConnectionConfig>,
+        after: Option<&ConnectionConfig>,
+    ) -> Self {
+        Self::new(
+            "connections",
+            name,
+            before.map(masked_headers).as_ref(),
+            after.map(masked_headers).as_ref(),
+        )
+    }
+
+    // Connections are audited on their own, so a connection edit isn't also a
+    // project edit.
+    pub fn project(before: &ProjectConfig, after: &ProjectConfig) -> Self {
+        let without_connections = |project: &ProjectConfig| {
+            let mut project = project.clone();
+            project.connections.clear();
+            project
+        };
+        Self {
+            resource: "project".to_string(),
+            before: render(&without_connections(before)),
+            after: render(&without_connections(after)),
+        }
+    }
+
+    // `kind/name` as it is in each config, for writes that touch many resources at once.
+    pub fn between(before: &Config, after: &Config, kind: &str, name: &str) -> Self {
+        Self {
+            resource: format!("{kind}/{name}"),
+            before: render_resource(before, kind, name),
+            after: render_resource(after, kind, name),
+        }
+    }
+}
+
+fn render_resource(config: &Config, kind: &str, name: &str) -> Option<String> {
+    match kind {
+        "connections" => render(&masked_headers(config.project.connections.get(name)?)),
+        "adapters" => render(config.adapters.get(name)?),
+        "models" => render(config.models.get(name)?),
+        "queries" => render(config.queries.get(name)?),
+        "dashboards" => render(config.dashboards.get(name)?),
+        _ => None,
+    }
+}
+
+// HTTP headers often carry API keys, so their values are never written to the log.
+fn masked_headers(connection: &ConnectionConfig) -> ConnectionConfig {
+    let mut connection = connection.clone();
+    if let ConnectionConfig::Http(http) = &mut connection {
+        for value in http
+            .headers
+            .iter_mut()
+            .flat_map(|headers| headers.values_mut())
+        {
+            *value = MASKED_SECRET.to_string();
+        }
+    }
+    connection
+}
+
+fn render<T: Serialize>(value: &T) -> Option<String> {
+    let yaml = serde_json::to_value(value).and_then(|mut value| {
+        normalize(&mut value);
+        serde_yml::to_string(&value).map_err(serde::ser::Error::custom)
+    });
+    yaml.inspect_err(|e| tracing::warn!("Failed to render a resource for the audit log: {e}"))
+        .ok()
+}
+
+// Sorts keys so maps render the same way every time, and masks every secret held
+// in the config. Env and file references stay, as they only name the secret.
+fn normalize(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let is_secret = matches!(
+                map.get("type").and_then(|ty| ty.as_str()),
+                Some("plain" | "encrypted")
+            ) && map.contains_key("value");
+            if is_secret {
+                map.insert("value".to_string(), MASKED_SECRET.into());
+            }
+
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                normalize(&mut value);
+                map.insert(key, value);
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+// Entries are queued synchronously and appended by a spawned flush, so a request
+// never waits on the file. Reads flush first and always see earlier changes.
+#[derive(Default)]
+pub struct AuditLog {
+    pending: std::sync::Mutex<Vec<(PathBuf, AuditEntry)>>,
+    file: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".data").join("audit.ndjson")
+    }
+
+    // Called by a handler once its changes are saved; changes that left a resource
+    // as it was are skipped.
+    pub fn record(
+        self: &Arc<Self>,
+        project_dir: &Path,
+        principal: Option<&str>,
+        changes: impl IntoIterator<Item = AuditChange>,
+    ) {
+        let timestamp = Utc::now();
+        let entries: Vec<AuditEntry> = changes
+            .into_iter()
+            .filter_map(|change| {
+                let operation = match (&change.before, &change.after) {
+                    (None, Some(_)) => AuditOperation::Create,
+                    (Some(_), None) => AuditOperation::Delete,
+                    (Some(before), Some(after)) if before != after => AuditOperation::Update,
+                    _ => return None,
+                };
+                Some(AuditEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    timestamp,
+                    resource: change.resource,
+                    operation,
+                    before: change.before,
+                    after: change.after,
+                    principal: principal.map(str::to_string),
+                })
+            })
+            .collect();
+        if entries.is_empty() {
+            return;
+        }
+
+        let path = Self::path(project_dir);
+        self.pending
+            .lock()
+            .unwrap()
+            .extend(entries.into_iter().map(|entry| (path.clone(), entry)));
+
+        let log = self.clone();
+        tokio::spawn(async move {
+            if let Err(error) = log.flush().await {
+                tracing::warn!("Failed to write audit log: {error:#}");
+            }
+        });
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let _file = self.file.lock().await;
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+
+        for (path, entry) in pending {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await?;
+            file.write_all(format!("{}\n", serde_json::to_string(&entry)?).as_bytes())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // Newest first, optionally limited to one resource or kind.
+    pub async fn list(
+        &self,
+        project_dir: &Path,
+        resource: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        self.flush().await?;
+        let _file = self.file.lock().await;
+
+        let path = Self::path(project_dir);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).await?;
+        let mut entries = Vec::new();
+        for line in content.lines().rev().filter(|line| !line.trim().is_empty()) {
+            let entry: AuditEntry = serde_json::from_str(line)?;
+            if resource.is_none_or(|resource| entry.matches(resource)) {
+                entries.push(entry);
+            }
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    pub async fn get(&self, project_dir: &Path, id: &str) -> Result<Option<AuditEntry>> {
+        let entries = self.list(project_dir, None, usize::MAX).await?;
+        Ok(entries.into_iter().find(|entry| entry.id == id))
+    }
+}
+
+// A unified diff of two texts by line. Empty when they are the same.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Each line with its marker and the old and new line counts before it.
+    let mut lines: Vec<(char, &str, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i], i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i], i, j));
+            i += 1;
+        } else {
+            lines.push(('+', new[j], i, j));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|&index| lines[index].0 != ' ')
+        .collect();
+    let Some(&first) = changed.first() else {
+        return String::new();
+    };
+
+    // Changes closer than twice the context share a hunk.
+    let mut hunks = vec![(first, first)];
+    for &index in &changed[1..] {
+        let last = hunks.last_mut().unwrap();
+        if index - last.1 <= 2 * CONTEXT_LINES {
+            last.1 = index;
+        } else {
+            hunks.push((index, index));
+        }
+    }
+
+    let mut diff = format!("--- {old_label}\n+++ {new_label}\n");
+    for (first, last) in hunks {
+        let start = first.saturating_sub(CONTEXT_LINES);
+        let end = (last + CONTEXT_LINES + 1).min(lines.len());
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|line| line.0 != '+').count();
+        let new_count = hunk.iter().filter(|line| line.0 != '-').count();
+        let old_start = hunk[0].2 + usize::from(old_count > 0);
+        let new_start = hunk[0].3 + usize::from(new_count > 0);
+
+        diff.push_str(&format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"
+        ));
+        for (marker, line, _, _) in hunk {
+            diff.push_str(&format!("{marker}{line}\n"));
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::project::{HttpConfig, RemoteDatabaseConfig};
+    use crate::core::config::secret::SecretField;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\ni\nj\nk\n";
+
+        assert_eq!(
+            unified_diff(old, new, "a/x", "b/x"),
+            "--- a/x\n+++ b/x\n\
+             @@ -1,7 +1,7 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n\
+             @@ -8,3 +8,4 @@\n h\n i\n j\n+k\n"
+        );
+        assert_eq!(unified_diff(old, old, "a/x", "b/x"), "");
+        assert_eq!(
+            unified_diff("", "a\n", "/dev/null", "b/x"),
+            "--- /dev/null\n+++ b/x\n@@ -0,0 +1,1 @@\n+a\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_masks_secrets_and_skips_unchanged() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let warehouse = ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+            host: "localhost".to_string(),
+            port: 5432,
+            database: "analytics".to_string(),
+            username: "reader".to_string(),
+            password: SecretField::PlainText {
+                value: "hunter2".to_string(),
+            },
+        });
+        let mut moved = warehouse.clone();
+        if let ConnectionConfig::PostgreSql(moved) = &mut moved {
+            moved.port = 5433;
+        }
+        let files = ConnectionConfig::LocalFile {
+            base_path: "data".to_string(),
+        };
+
+        let log = Arc::new(AuditLog::default());
+        log.record(
+            dir.path(),
+            Some("api-token"),
+            [
+                AuditChange::connection("warehouse", Some(&warehouse), Some(&moved)),
+                AuditChange::connection("files", None, Some(&files)),
+                AuditChange::connection("files", Some(&files), Some(&files)),
+            ],
+        );
+
+        let mut entries = log.list(dir.path(), None, 10).await?;
+        entries.reverse();
+        let summary: Vec<(&str, AuditOperation)> = entries
+            .iter()
+            .map(|entry| (entry.resource.as_str(), entry.operation))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("connections/warehouse", AuditOperation::Update),
+                ("connections/files", AuditOperation::Create),
+            ]
+        );
+        let before = entries[0].before.as_deref().unwrap();
+        assert!(!before.contains("hunter2"));
+        assert!(before.contains(MASKED_SECRET));
+        assert!(entries[0].diff().contains("-  port: 5432\n+  port: 5433\n"));
+        assert_eq!(entries[1].principal.as_deref(), Some("api-token"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_connection_change_masks_http_headers() {
+        let api = ConnectionConfig::Http(HttpConfig {
+            base_url: "https://example.com".to_string(),
+            headers: Some(
+                [("X-Api-Key".to_string(), "sk-live-1234".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            auth_token: None,
+        });
+
+        let change = AuditChange::connection("api", None, Some(&api));
+        let after = change.after.unwrap();
+        assert!(!after.contains("sk-live-1234"));
+        assert!(after.contains("X-Api-Key"));
+        assert!(after.contains(MASKED_SECRET));
+    }
+}
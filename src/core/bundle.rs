@@ -0,0 +1,479 @@
+use crate::core::{
+    config::{
+        Config, Journal,
+        project::{ConnectionConfig, parse_project_config},
+        seed::SeedConfig,
+        validate_name, write_atomic,
+    },
+    share::Shares,
+};
+use anyhow::{Context, Result};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Read, Write},
+};
+use tar::{Archive, Builder, EntryType, Header};
+use tempfile::TempDir;
+
+const MANIFEST_FILE: &str = "bundle.json";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    #[serde(default)]
+    stripped_secrets: Vec<String>,
+}
+
+pub struct Export {
+    pub archive: Vec<u8>,
+    pub stripped_secrets: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub connections: Vec<String>,
+    pub adapters: Vec<String>,
+    pub models: Vec<String>,
+    pub queries: Vec<String>,
+    pub dashboards: Vec<String>,
+    pub seeds: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn sorted<T>(items: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut items = items.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(name, _)| *name);
+    items
+}
+
+fn append<W: Write>(builder: &mut Builder<W>, path: &str, contents: &[u8]) -> Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Regular);
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, contents)?;
+    Ok(())
+}
+
+// Packs the project definitions into a tar.gz. Secrets are blanked rather than
+// exported encrypted: the receiving machine never has this project's key.
+pub fn export_bundle(config: &Config) -> Result<Export> {
+    // The in-memory project has decrypted secrets and absolute paths, so start
+    // from the file on disk.
+    let content = fs::read_to_string(config.project_dir.join("project.yml"))?;
+    let mut project = parse_project_config(&content)?;
+    let stripped_secrets = project.strip_secrets();
+
+    let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let manifest = Manifest {
+        version: BUNDLE_VERSION,
+        stripped_secrets: stripped_secrets.clone(),
+    };
+    append(
+        &mut builder,
+        MANIFEST_FILE,
+        serde_json::to_string_pretty(&manifest)?.as_bytes(),
+    )?;
+    append(
+        &mut builder,
+        "project.yml",
+        serde_yml::to_string(&project)?.as_bytes(),
+    )?;
+
+    for (name, adapter) in sorted(&config.adapters) {
+        let yaml = serde_yml::to_string(adapter)?;
+        append(
+            &mut builder,
+            &format!("adapters/{name}.yml"),
+            yaml.as_bytes(),
+        )?;
+    }
+    for (name, model) in sorted(&config.models) {
+        let yaml = serde_yml::to_string(model)?;
+        append(&mut builder, &format!("models/{name}.yml"), yaml.as_bytes())?;
+    }
+    for (name, query) in sorted(&config.queries) {
+        let yaml = serde_yml::to_string(query)?;
+        append(
+            &mut builder,
+            &format!("queries/{name}.yml"),
+            yaml.as_bytes(),
+        )?;
+    }
+    for (name, dashboard) in sorted(&config.dashboards) {
+        let yaml = serde_yml::to_string(dashboard)?;
+        append(
+            &mut builder,
+            &format!("dashboards/{name}.yml"),
+            yaml.as_bytes(),
+        )?;
+    }
+    for (name, seed) in sorted(&config.seeds) {
+        let data =
+            fs::read(&seed.path).with_context(|| format!("Failed to read seed {:?}", seed.path))?;
+        append(&mut builder, &format!("seeds/{name}.csv"), &data)?;
+    }
+
+    let archive = builder.into_inner()?.finish()?;
+    Ok(Export {
+        archive,
+        stripped_secrets,
+    })
+}
+
+// An unpacked bundle whose configuration has been parsed, ready to be applied.
+pub struct Bundle {
+    config: Config,
+    stripped_secrets: Vec<String>,
+    _dir: TempDir,
+}
+
+impl Bundle {
+    pub fn read(archive: impl Read) -> Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let mut archive = Archive::new(GzDecoder::new(archive));
+        for entry in archive.entries().context("Not a valid project bundle")? {
+            let mut entry = entry.context("Not a valid project bundle")?;
+            let path = entry.path()?.into_owned();
+            if !matches!(
+                entry.header().entry_type(),
+                EntryType::Regular | EntryType::Directory
+            ) {
+                return Err(anyhow::anyhow!(
+                    "Bundle entry {path:?} is not a regular file"
+                ));
+            }
+            if !entry.unpack_in(dir.path())? {
+                return Err(anyhow::anyhow!(
+                    "Bundle entry {path:?} points outside the project"
+                ));
+            }
+        }
+
+        let manifest = fs::read_to_string(dir.path().join(MANIFEST_FILE))
+            .context("Not a valid project bundle (missing bundle.json)")?;
+        let manifest: Manifest = serde_json::from_str(&manifest)?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Bundle version {} is newer than this DuckHub supports",
+                manifest.version
+            ));
+        }
+
+        let mut config = Config::new(dir.path().to_path_buf());
+        config.load_without_secrets()?;
+        // Keep connection paths relative to whichever project they land in.
+        config.project =
+            parse_project_config(&fs::read_to_string(dir.path().join("project.yml"))?)?;
+        validate(&config)?;
+
+        Ok(Self {
+            config,
+            stripped_secrets: manifest.stripped_secrets,
+            _dir: dir,
+        })
+    }
+
+    // Everything in the bundle that already exists in `config`, as "kind/name".
+    pub fn collisions(&self, config: &Config) -> Vec<String> {
+        fn overlap<A, B>(
+            kind: &str,
+            ours: &HashMap<String, A>,
+            theirs: &HashMap<String, B>,
+        ) -> Vec<String> {
+            let mut names = ours
+                .keys()
+                .filter(|name| theirs.contains_key(*name))
+                .map(|name| format!("{kind}/{name}"))
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+
+        let bundle = &self.config;
+        [
+            overlap(
+                "connections",
+                &bundle.project.connections,
+                &config.project.connections,
+            ),
+            overlap("adapters", &bundle.adapters, &config.adapters),
+            overlap("models", &bundle.models, &config.models),
+            overlap("queries", &bundle.queries, &config.queries),
+            overlap("dashboards", &bundle.dashboards, &config.dashboards),
+            overlap("seeds", &bundle.seeds, &config.seeds),
+        ]
+        .concat()
+    }
+
+    // Writes the bundle into `config`, replacing anything with the same name.
+    // Project-level storage and database settings are left untouched. If any
+    // file fails to write, the ones already written are restored and `config`
+    // is left as it was.
+    pub fn apply(self, config: &mut Config) -> Result<ImportSummary> {
+        let mut staged = config.clone();
+        let mut journal = Journal::default();
+        match self.write(&mut staged, &mut journal) {
+            Ok(summary) => {
+                *config = staged;
+                Ok(summary)
+            }
+            Err(e) => {
+                journal.rollback();
+                Err(e)
+            }
+        }
+    }
+
+    fn write(self, config: &mut Config, journal: &mut Journal) -> Result<ImportSummary> {
+        let mut summary = ImportSummary::default();
+        let bundle = self.config;
+
+        let connections = sorted(&bundle.project.connections);
+        if !connections.is_empty() {
+            let mut project = config.project.clone();
+            for (name, connection) in connections {
+                project.connections.insert(name.clone(), connection.clone());
+                summary.connections.push(name.clone());
+                if self
+                    .stripped_secrets
+                    .contains(&format!("connection '{name}'"))
+                {
+                    summary.warnings.push(format!(
+                        "Connection '{name}' was exported without its {}; set it again before use",
+                        secret_kind(connection)
+                    ));
+                }
+            }
+            journal.record(&config.project_config_file()?);
+            config.save_project(project)?;
+        }
+
+        for (name, adapter) in sorted(&bundle.adapters) {
+            let handle = config.upsert_adapter(name, adapter)?;
+            journal.record(handle.path());
+            handle.save()?;
+            summary.adapters.push(name.clone());
+        }
+        for (name, model) in sorted(&bundle.models) {
+            let handle = config.upsert_model(name, model)?;
+            journal.record(handle.path());
+            handle.save()?;
+            summary.models.push(name.clone());
+        }
+        for (name, query) in sorted(&bundle.queries) {
+            let handle = config.upsert_query(name, query)?;
+            journal.record(handle.path());
+            handle.save()?;
+            summary.queries.push(name.clone());
+        }
+        if bundle
+            .dashboards
+            .keys()
+            .any(|name| config.dashboards.contains_key(name))
+        {
+            journal.record(&Shares::path(&config.project_dir));
+        }
+        for (name, dashboard) in sorted(&bundle.dashboards) {
+            if config.dashboards.contains_key(name) {
+                config.revoke_shares(name)?;
+            }
+            let handle = config.upsert_dashboard(name, dashboard)?;
+            journal.record(handle.path());
+            handle.save()?;
+            summary.dashboards.push(name.clone());
+        }
+
+        let seeds_dir = config.project_dir.join("seeds");
+        for (name, seed) in sorted(&bundle.seeds) {
+            let path = seeds_dir.join(format!("{name}.csv"));
+            let data = fs::read(&seed.path)
+                .with_context(|| format!("Failed to read seed {:?}", seed.path))?;
+            journal.record(&path);
+            write_atomic(&path, data)?;
+            config.seeds.insert(name.clone(), SeedConfig { path });
+            summary.seeds.push(name.clone());
+        }
+
+        Ok(summary)
+    }
+}
+
+// Checks everything the bundle would write before any of it is, so a bad entry
+// can't leave the project half imported. Names come from file paths, and a
+// grouped resource keeps its subdirectory, so each part of the path is checked.
+fn validate(bundle: &Config) -> Result<()> {
+    for name in bundle.project.connections.keys() {
+        validate_name(name).with_context(|| format!("Invalid connection name '{name}'"))?;
+    }
+    let resources = [
+        ("adapter", bundle.adapters.keys().collect::<Vec<_>>()),
+        ("model", bundle.models.keys().collect()),
+        ("query", bundle.queries.keys().collect()),
+        ("dashboard", bundle.dashboards.keys().collect()),
+        ("seed", bundle.seeds.keys().collect()),
+    ];
+    for (kind, names) in resources {
+        for name in names {
+            name.split('/')
+                .try_for_each(validate_name)
+                .with_context(|| format!("Invalid {kind} name '{name}'"))?;
+        }
+    }
+    bundle.project.check_secret_references()
+}
+
+fn secret_kind(connection: &ConnectionConfig) -> &'static str {
+    match connection {
+        ConnectionConfig::S3(_) => "secret access key",
+        ConnectionConfig::Http(_) => "auth token",
+        ConnectionConfig::Gcs(_) => "HMAC secret",
+        _ => "password",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::templates::sample::create_samples,
+        core::config::{
+            project::{ProjectConfig, RemoteDatabaseConfig},
+            secret::SecretField,
+        },
+        test_helpers::create_project,
+    };
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_export_import_roundtrip() -> Result<()> {
+        let dir = tempdir()?;
+        let mut source = create_project(&dir.path().join("source"), &ProjectConfig::default())?;
+        create_samples(&mut source).await?;
+        let mut project = source.project.clone();
+        project.connections.insert(
+            "warehouse".to_string(),
+            ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+                host: "localhost".to_string(),
+                port: 5432,
+                database: "warehouse".to_string(),
+                username: "duckhub".to_string(),
+                password: SecretField::PlainText {
+                    value: "hunter2".to_string(),
+                },
+            }),
+        );
+        source.save_project(project)?;
+
+        let export = export_bundle(&source)?;
+        assert_eq!(export.stripped_secrets, vec!["connection 'warehouse'"]);
+
+        let mut target = create_project(&dir.path().join("target"), &ProjectConfig::default())?;
+        let bundle = Bundle::read(export.archive.as_slice())?;
+        assert!(bundle.collisions(&target).is_empty());
+        let summary = bundle.apply(&mut target)?;
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(summary.warnings[0].contains("'warehouse'"));
+
+        let mut reloaded = Config::new(target.project_dir.clone());
+        reloaded.load()?;
+        assert_eq!(reloaded.adapters, source.adapters);
+        assert_eq!(reloaded.models, source.models);
+        assert_eq!(reloaded.queries, source.queries);
+        assert_eq!(reloaded.dashboards, source.dashboards);
+        let mut seeds = reloaded.seeds.keys().collect::<Vec<_>>();
+        let mut expected_seeds = source.seeds.keys().collect::<Vec<_>>();
+        seeds.sort();
+        expected_seeds.sort();
+        assert_eq!(seeds, expected_seeds);
+
+        let mut connections = reloaded.project.connections.keys().collect::<Vec<_>>();
+        connections.sort();
+        assert_eq!(connections, vec!["local_files", "sample_db", "warehouse"]);
+        let ConnectionConfig::PostgreSql(warehouse) = &reloaded.project.connections["warehouse"]
+        else {
+            panic!("warehouse should be a PostgreSQL connection");
+        };
+        assert_eq!(warehouse.password.plaintext()?, "");
+
+        let project_yml = fs::read_to_string(target.project_dir.join("project.yml"))?;
+        assert!(!project_yml.contains("hunter2"));
+
+        let bundle = Bundle::read(export_bundle(&source)?.archive.as_slice())?;
+        let collisions = bundle.collisions(&reloaded);
+        assert!(collisions.contains(&"adapters/users".to_string()));
+        assert!(collisions.contains(&"connections/warehouse".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reject_invalid_names() -> Result<()> {
+        let dir = tempdir()?;
+        let mut source = create_project(&dir.path().join("source"), &ProjectConfig::default())?;
+        let mut project = source.project.clone();
+        project.connections.insert(
+            "Bad Name".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: "data".to_string(),
+            },
+        );
+        source.save_project(project)?;
+
+        let result = Bundle::read(export_bundle(&source)?.archive.as_slice());
+        let error = result.err().unwrap();
+        assert!(format!("{error:#}").contains("Invalid connection name 'Bad Name'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_import_rolls_back() -> Result<()> {
+        let dir = tempdir()?;
+        let mut source = create_project(&dir.path().join("source"), &ProjectConfig::default())?;
+        create_samples(&mut source).await?;
+
+        let mut target = create_project(&dir.path().join("target"), &ProjectConfig::default())?;
+        let project_yml = fs::read_to_string(target.project_dir.join("project.yml"))?;
+        // Queries are written after connections, adapters and models.
+        fs::write(target.project_dir.join("queries"), "not a directory")?;
+
+        let bundle = Bundle::read(export_bundle(&source)?.archive.as_slice())?;
+        assert!(bundle.apply(&mut target).is_err());
+
+        assert_eq!(
+            fs::read_to_string(target.project_dir.join("project.yml"))?,
+            project_yml
+        );
+        assert!(!target.project_dir.join("adapters/users.yml").exists());
+        assert!(target.adapters.is_empty());
+        assert!(target.project.connections.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_entries_outside_project() -> Result<()> {
+        let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = Header::new_gnu();
+        let contents = b"name: x\n";
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        // `append_data` refuses `..`, so write the raw path into the header.
+        header.as_old_mut().name[..17].copy_from_slice(b"../adapters/x.yml");
+        header.set_cksum();
+        builder.append(&header, contents.as_slice())?;
+        let archive = builder.into_inner()?.finish()?;
+
+        let result = Bundle::read(archive.as_slice());
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
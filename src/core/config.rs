@@ -1,23 +1,66 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs,
-    path::{Path, PathBuf},
+    io::Write,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
 };
+use tempfile::NamedTempFile;
 
 pub mod adapter;
+pub mod api_token;
 pub mod dashboard;
+pub mod data_test;
+pub mod masking;
 pub mod model;
+pub mod notification;
 pub mod project;
 pub mod query;
 pub mod secret;
+pub mod seed;
 
-use adapter::AdapterConfig;
+use adapter::{AdapterConfig, AdapterSource};
 use dashboard::DashboardConfig;
+use data_test::RelationshipConfig;
 use model::ModelConfig;
 use project::ProjectConfig;
 use query::QueryConfig;
+use seed::SeedConfig;
+
+use crate::core::share::Shares;
+
+pub const MAX_NAME_LENGTH: usize = 63;
+
+// Selects the environment overlay when `--env` is not given.
+pub const ENV_VAR: &str = "DUCKHUB_ENV";
+
+// Resource names become file names under the project and DuckLake table names,
+// so only plain lowercase identifiers are accepted.
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(anyhow::anyhow!("Name must not be empty"));
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(anyhow::anyhow!(
+            "Name '{name}' is longer than {MAX_NAME_LENGTH} characters"
+        ));
+    }
+
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_');
+    let valid_rest = chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_start || !valid_rest {
+        return Err(anyhow::anyhow!(
+            "Invalid name '{name}': use lowercase letters, digits and underscores, starting with a letter or underscore"
+        ));
+    }
+
+    Ok(())
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -26,7 +69,39 @@ pub struct Config {
     pub models: HashMap<String, ModelConfig>,
     pub queries: HashMap<String, QueryConfig>,
     pub dashboards: HashMap<String, DashboardConfig>,
+    pub seeds: HashMap<String, SeedConfig>,
     pub project_dir: PathBuf,
+    // Overlays project.<env>.yml on top of project.yml when set.
+    pub env: Option<String>,
+    // Files that failed to parse on the last reload, by path relative to the
+    // project; the previous version of each stays in use.
+    pub load_errors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadError {
+    pub path: String,
+    pub message: String,
+}
+
+// Config files touched by a reload, as paths relative to the project.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChanges {
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    pub errors: Vec<LoadError>,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty() && self.errors.is_empty()
+    }
+}
+
+enum FileChange {
+    Unchanged,
+    Changed,
+    Removed,
 }
 
 pub struct UpsertFileHandle<'a, T: Serialize + Deserialize<'a>> {
@@ -35,10 +110,58 @@ pub struct UpsertFileHandle<'a, T: Serialize + Deserialize<'a>> {
 }
 
 impl<'a, T: Serialize + Deserialize<'a>> UpsertFileHandle<'a, T> {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn save(&self) -> Result<()> {
-        fs::create_dir_all(self.path.parent().unwrap())?;
-        fs::write(&self.path, serde_yml::to_string(self.config)?)?;
-        Ok(())
+        write_atomic(&self.path, &serde_yml::to_string(self.config)?)
+    }
+}
+
+// Serializes every config write in this process so concurrent saves of the
+// same file cannot interleave.
+static PERSIST_LOCK: Mutex<()> = Mutex::new(());
+
+// Writes to a temporary file next to `path` and renames it into place, so a
+// crash mid-write leaves the previous version intact.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let directory = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("{path:?} has no parent directory"))?;
+    fs::create_dir_all(directory)?;
+
+    let _guard = PERSIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = NamedTempFile::new_in(directory)?;
+    file.write_all(contents.as_ref())?;
+    file.as_file().sync_all()?;
+    file.persist(path)
+        .with_context(|| format!("Failed to write {path:?}"))?;
+
+    Ok(())
+}
+
+// What each file held before a write spanning several files touched it, so a
+// failure part way through can put every one of them back.
+#[derive(Default)]
+pub struct Journal(Vec<(PathBuf, Option<Vec<u8>>)>);
+
+impl Journal {
+    pub fn record(&mut self, path: &Path) {
+        self.0.push((path.to_path_buf(), fs::read(path).ok()));
+    }
+
+    pub fn rollback(self) {
+        for (path, previous) in self.0.into_iter().rev() {
+            let restored = match previous {
+                Some(contents) => write_atomic(&path, contents),
+                None if path.exists() => fs::remove_file(&path).map_err(Into::into),
+                None => Ok(()),
+            };
+            if let Err(e) = restored {
+                tracing::error!("Failed to roll back {path:?}: {e}");
+            }
+        }
     }
 }
 
@@ -47,11 +170,27 @@ pub struct DeleteFileHandle {
 }
 
 impl DeleteFileHandle {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn save(&self) -> Result<()> {
         Ok(fs::remove_file(&self.path)?)
     }
 }
 
+pub struct RenameFileHandle {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl RenameFileHandle {
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(self.to.parent().unwrap())?;
+        Ok(fs::rename(&self.from, &self.to)?)
+    }
+}
+
 impl Config {
     pub fn new(project_dir: PathBuf) -> Self {
         Self {
@@ -60,12 +199,26 @@ impl Config {
             models: HashMap::new(),
             queries: HashMap::new(),
             dashboards: HashMap::new(),
+            seeds: HashMap::new(),
             project_dir,
+            env: None,
+            load_errors: BTreeMap::new(),
         }
     }
 
+    pub fn with_env(mut self, env: Option<String>) -> Self {
+        self.env = env;
+        self
+    }
+
     pub fn load(&mut self) -> Result<()> {
-        for subdir in ["adapters", "models", "queries", "dashboards"] {
+        self.load_without_secrets()?;
+        self.project.load_secrets(&self.project_dir)
+    }
+
+    // Leaves secrets encrypted, for callers that may not have the right key yet.
+    pub fn load_without_secrets(&mut self) -> Result<()> {
+        for subdir in ["adapters", "models", "queries", "dashboards", "seeds"] {
             fs::create_dir_all(self.project_dir.join(subdir))?
         }
         self.project = self.load_project_config()?;
@@ -73,10 +226,273 @@ impl Config {
         self.models = self.load_models()?;
         self.queries = self.load_queries()?;
         self.dashboards = self.load_dashboards()?;
+        self.seeds = self.load_seeds()?;
+        self.load_errors.clear();
 
         Ok(())
     }
 
+    // Everything `reload_files` understands: the project files and the resource
+    // directories.
+    pub fn config_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.project_dir.join("project.yml")];
+        if let Some(env) = &self.env {
+            paths.push(self.project_dir.join(format!("project.{env}.yml")));
+        }
+        paths.extend(self.config_directories());
+        paths
+    }
+
+    pub fn config_directories(&self) -> Vec<PathBuf> {
+        RELOADABLE_DIRECTORIES
+            .iter()
+            .map(|subdir| self.project_dir.join(subdir))
+            .collect()
+    }
+
+    // Re-reads only the given files. A directory stands for every file in it, and
+    // a missing file removes its resource. A file that fails to parse keeps its
+    // previous version and is recorded in `load_errors` until it parses again.
+    pub fn reload_files(&mut self, paths: &[PathBuf]) -> ConfigChanges {
+        let mut changes = ConfigChanges::default();
+        let mut project_changed = false;
+
+        for path in self.expand_paths(paths) {
+            let Ok(relative) = path.strip_prefix(&self.project_dir) else {
+                continue;
+            };
+            let Some(top) = relative
+                .components()
+                .next()
+                .and_then(|component| component.as_os_str().to_str())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().to_string();
+
+            let result = match top.as_str() {
+                "adapters" => reload_file(
+                    &mut self.adapters,
+                    &self.project_dir.join("adapters"),
+                    &path,
+                    adapter::parse_adapter_config,
+                ),
+                "models" => reload_file(
+                    &mut self.models,
+                    &self.project_dir.join("models"),
+                    &path,
+                    model::parse_model_config,
+                ),
+                "queries" => reload_file(
+                    &mut self.queries,
+                    &self.project_dir.join("queries"),
+                    &path,
+                    query::parse_query_config,
+                ),
+                "dashboards" => reload_file(
+                    &mut self.dashboards,
+                    &self.project_dir.join("dashboards"),
+                    &path,
+                    dashboard::parse_dashboard_config,
+                )
+                .inspect(|change| {
+                    if matches!(change, FileChange::Removed) {
+                        let name = generate_config_key(&self.project_dir.join("dashboards"), &path);
+                        if let Err(e) = self.revoke_shares(&name) {
+                            tracing::warn!("Failed to revoke shares of dashboard '{name}': {e:#}");
+                        }
+                    }
+                }),
+                _ if self.is_project_file(&top) => {
+                    // project.yml and its overlay are parsed together, once.
+                    if project_changed {
+                        continue;
+                    }
+                    project_changed = true;
+                    self.reload_project()
+                }
+                _ => continue,
+            };
+
+            match result {
+                Ok(change) => {
+                    self.load_errors.remove(&relative);
+                    match change {
+                        FileChange::Unchanged => {}
+                        FileChange::Changed => changes.changed.push(relative),
+                        FileChange::Removed => changes.removed.push(relative),
+                    }
+                }
+                Err(e) => {
+                    let message = format!("{e:#}");
+                    self.load_errors.insert(relative.clone(), message.clone());
+                    changes.errors.push(LoadError {
+                        path: relative,
+                        message,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+
+    fn is_project_file(&self, file_name: &str) -> bool {
+        file_name == "project.yml"
+            || self
+                .env
+                .as_ref()
+                .is_some_and(|env| file_name == format!("project.{env}.yml"))
+    }
+
+    // Directories, and anything else that is not a YAML file, are replaced by the
+    // YAML files under the resource directory they belong to plus the files of the
+    // resources loaded from it, so deleted files are noticed too.
+    fn expand_paths(&self, paths: &[PathBuf]) -> BTreeSet<PathBuf> {
+        let mut expanded = BTreeSet::new();
+        for path in paths {
+            let is_yaml = path.extension().and_then(|s| s.to_str()) == Some("yml");
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            if is_yaml || hidden {
+                if is_yaml {
+                    expanded.insert(path.clone());
+                }
+                continue;
+            }
+
+            let Some(subdir) = path
+                .strip_prefix(&self.project_dir)
+                .ok()
+                .and_then(|relative| relative.components().next())
+                .and_then(|component| component.as_os_str().to_str())
+                .filter(|top| RELOADABLE_DIRECTORIES.contains(top))
+            else {
+                continue;
+            };
+            let directory = self.project_dir.join(subdir);
+            let names: Vec<&String> = match subdir {
+                "adapters" => self.adapters.keys().collect(),
+                "models" => self.models.keys().collect(),
+                "queries" => self.queries.keys().collect(),
+                _ => self.dashboards.keys().collect(),
+            };
+            expanded.extend(
+                names
+                    .into_iter()
+                    .map(|name| directory.join(format!("{name}.yml"))),
+            );
+            collect_yaml_files(&directory, &mut expanded);
+        }
+        expanded
+    }
+
+    fn reload_project(&mut self) -> Result<FileChange> {
+        let mut project = self.load_project_config()?;
+        project.load_secrets(&self.project_dir)?;
+        if project == self.project {
+            return Ok(FileChange::Unchanged);
+        }
+        self.project = project;
+        Ok(FileChange::Changed)
+    }
+
+    // Whether `name` is a table built by the pipeline: an adapter, model or seed.
+    pub fn defines_table(&self, name: &str) -> bool {
+        self.table_owner(name).is_some()
+    }
+
+    // Whether `name` is an adapter, model or seed, i.e. a pipeline node.
+    pub fn defines_node(&self, name: &str) -> bool {
+        self.adapters.contains_key(name)
+            || self.models.contains_key(name)
+            || self.seeds.contains_key(name)
+    }
+
+    // The adapter, model or seed that builds `table`. Adapters can import into a
+    // table named differently from themselves.
+    pub fn table_owner(&self, table: &str) -> Option<&str> {
+        if let Some((name, _)) = self
+            .adapters
+            .iter()
+            .find(|(name, adapter)| adapter.table_name(name) == table)
+        {
+            return Some(name);
+        }
+
+        self.models
+            .get_key_value(table)
+            .map(|(name, _)| name)
+            .or_else(|| self.seeds.get_key_value(table).map(|(name, _)| name))
+            .map(String::as_str)
+    }
+
+    // Relationships reference an adapter by name, or any table the pipeline builds.
+    // Returns the node that builds the table, and the table itself.
+    pub fn resolve_table<'a>(&'a self, name: &'a str) -> Option<(&'a str, &'a str)> {
+        if let Some((node, adapter)) = self.adapters.get_key_value(name) {
+            return Some((node, adapter.table_name(node)));
+        }
+        self.table_owner(name).map(|owner| (owner, name))
+    }
+
+    pub fn check_relationships(&self, relationships: &[RelationshipConfig]) -> Result<()> {
+        for relationship in relationships {
+            if self.resolve_table(&relationship.references.table).is_none() {
+                return Err(anyhow::anyhow!(
+                    "Relationship on column '{}' references '{}', which is not an adapter, model or seed",
+                    relationship.column,
+                    relationship.references.table
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The nodes `name` must run after so its relationships can be checked. A table
+    // referencing itself adds no edge.
+    pub fn relationship_nodes(
+        &self,
+        name: &str,
+        relationships: &[RelationshipConfig],
+    ) -> Vec<String> {
+        let mut nodes: Vec<String> = Vec::new();
+        for relationship in relationships {
+            if let Some((node, _)) = self.resolve_table(&relationship.references.table)
+                && node != name
+                && !nodes.iter().any(|other| other == node)
+            {
+                nodes.push(node.to_string());
+            }
+        }
+        nodes
+    }
+
+    // The adapter as it is run: a file format takes the project's
+    // `defaults.file_format` for any option the adapter leaves unset.
+    pub fn with_format_defaults(&self, mut adapter: AdapterConfig) -> AdapterConfig {
+        if let AdapterSource::File { format, .. } = &mut adapter.source
+            && let Some(defaults) = self.project.defaults.file_format.get(&format.ty)
+        {
+            *format = format.clone().with_defaults(defaults);
+        }
+        adapter
+    }
+
+    // Maps tables referenced in SQL to the graph nodes that build them.
+    pub fn table_nodes(&self, tables: Vec<String>) -> Vec<String> {
+        tables
+            .into_iter()
+            .map(|table| match self.table_owner(&table) {
+                Some(owner) => owner.to_string(),
+                None => table,
+            })
+            .collect()
+    }
+
     pub fn add_project_setting<'a>(
         &mut self,
         config: &'a ProjectConfig,
@@ -89,29 +505,111 @@ impl Config {
         })
     }
 
+    // Persists `project` with every secret encrypted and keeps the decrypted,
+    // path-resolved version in memory.
+    pub fn save_project(&mut self, mut project: ProjectConfig) -> Result<()> {
+        project.check_secret_references()?;
+        let mut encrypted = project.clone();
+        encrypted.encrypt_secrets(&self.project_dir)?;
+        write_atomic(
+            &self.project_config_file()?,
+            &serde_yml::to_string(&encrypted)?,
+        )?;
+
+        if self.env.is_some() {
+            project = self.load_project_config()?;
+        }
+        project.load_secrets(&self.project_dir)?;
+        project.resolve_paths(&self.project_dir)?;
+        self.project = project;
+
+        Ok(())
+    }
+
+    // The project as written in project.yml, without the environment overlay, so
+    // edits made through the API never bake overlay values into the base file.
+    pub fn base_project(&self) -> Result<ProjectConfig> {
+        if self.env.is_none() {
+            return Ok(self.project.clone());
+        }
+
+        self.read_base_project()
+    }
+
+    // Re-reads project.yml, applies `update` and saves the result while holding
+    // the project lock, so an edit made by another process in between is kept
+    // rather than overwritten with what this one loaded at startup.
+    pub fn update_project<T, E>(
+        &mut self,
+        update: impl FnOnce(&mut ProjectConfig) -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E>
+    where
+        E: From<anyhow::Error>,
+    {
+        let _lock = self.lock_project()?;
+        let mut project = self.read_base_project()?;
+        let value = update(&mut project)?;
+        self.save_project(project)?;
+        Ok(value)
+    }
+
+    // An advisory lock on a file under .data; it is released when the returned
+    // handle is dropped. Every open takes its own lock, so threads in this
+    // process wait on each other just like the CLI and a running server do.
+    fn lock_project(&self) -> Result<fs::File> {
+        let path = self.project_dir.join(".data").join("project.lock");
+        fs::create_dir_all(self.project_dir.join(".data"))?;
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        file.lock()
+            .with_context(|| format!("Failed to lock {path:?}"))?;
+        Ok(file)
+    }
+
+    fn read_base_project(&self) -> Result<ProjectConfig> {
+        let content = fs::read_to_string(self.project_config_file()?)?;
+        let mut project = project::parse_project_config(&content)?;
+        project.resolve_paths(&self.project_dir)?;
+        project.load_secrets(&self.project_dir)?;
+        Ok(project)
+    }
+
     pub fn upsert_adapter<'a>(
         &mut self,
         path: &str,
         adapter: &'a AdapterConfig,
     ) -> Result<UpsertFileHandle<'a, AdapterConfig>> {
+        let file = config_file(&self.adapters_config_directory()?, path)?;
         self.adapters.insert(path.to_string(), adapter.clone());
 
         Ok(UpsertFileHandle {
             config: adapter,
-            path: self
-                .adapters_config_directory()?
-                .join(format!("{path}.yml")),
+            path: file,
         })
     }
 
     pub fn delete_adapter(&mut self, path: &str) -> Result<DeleteFileHandle> {
+        let file = config_file(&self.adapters_config_directory()?, path)?;
         self.adapters.remove(path);
 
-        Ok(DeleteFileHandle {
-            path: self
-                .adapters_config_directory()?
-                .join(format!("{path}.yml")),
-        })
+        Ok(DeleteFileHandle { path: file })
+    }
+
+    pub fn rename_adapter(&mut self, from: &str, to: &str) -> Result<RenameFileHandle> {
+        let directory = self.adapters_config_directory()?;
+        let handle = RenameFileHandle {
+            from: config_file(&directory, from)?,
+            to: config_file(&directory, to)?,
+        };
+
+        if let Some(adapter) = self.adapters.remove(from) {
+            self.adapters.insert(to.to_string(), adapter);
+        }
+
+        Ok(handle)
     }
 
     pub fn upsert_model<'a>(
@@ -119,20 +617,34 @@ impl Config {
         path: &str,
         model: &'a ModelConfig,
     ) -> Result<UpsertFileHandle<'a, ModelConfig>> {
+        let file = config_file(&self.models_config_directory()?, path)?;
         self.models.insert(path.to_string(), model.clone());
 
         Ok(UpsertFileHandle {
             config: model,
-            path: self.models_config_directory()?.join(format!("{path}.yml")),
+            path: file,
         })
     }
 
     pub fn delete_model(&mut self, path: &str) -> Result<DeleteFileHandle> {
+        let file = config_file(&self.models_config_directory()?, path)?;
         self.models.remove(path);
 
-        Ok(DeleteFileHandle {
-            path: self.models_config_directory()?.join(format!("{path}.yml")),
-        })
+        Ok(DeleteFileHandle { path: file })
+    }
+
+    pub fn rename_model(&mut self, from: &str, to: &str) -> Result<RenameFileHandle> {
+        let directory = self.models_config_directory()?;
+        let handle = RenameFileHandle {
+            from: config_file(&directory, from)?,
+            to: config_file(&directory, to)?,
+        };
+
+        if let Some(model) = self.models.remove(from) {
+            self.models.insert(to.to_string(), model);
+        }
+
+        Ok(handle)
     }
 
     pub fn upsert_query<'a>(
@@ -140,20 +652,34 @@ impl Config {
         path: &str,
         query: &'a QueryConfig,
     ) -> Result<UpsertFileHandle<'a, QueryConfig>> {
+        let file = config_file(&self.queries_config_directory()?, path)?;
         self.queries.insert(path.to_string(), query.clone());
 
         Ok(UpsertFileHandle {
             config: query,
-            path: self.queries_config_directory()?.join(format!("{path}.yml")),
+            path: file,
         })
     }
 
     pub fn delete_query(&mut self, path: &str) -> Result<DeleteFileHandle> {
+        let file = config_file(&self.queries_config_directory()?, path)?;
         self.queries.remove(path);
 
-        Ok(DeleteFileHandle {
-            path: self.queries_config_directory()?.join(format!("{path}.yml")),
-        })
+        Ok(DeleteFileHandle { path: file })
+    }
+
+    pub fn rename_query(&mut self, from: &str, to: &str) -> Result<RenameFileHandle> {
+        let directory = self.queries_config_directory()?;
+        let handle = RenameFileHandle {
+            from: config_file(&directory, from)?,
+            to: config_file(&directory, to)?,
+        };
+
+        if let Some(query) = self.queries.remove(from) {
+            self.queries.insert(to.to_string(), query);
+        }
+
+        Ok(handle)
     }
 
     pub fn upsert_dashboard<'a>(
@@ -161,27 +687,48 @@ impl Config {
         path: &str,
         dashboard: &'a DashboardConfig,
     ) -> Result<UpsertFileHandle<'a, DashboardConfig>> {
+        let file = config_file(&self.dashboards_config_directory()?, path)?;
         self.dashboards.insert(path.to_string(), dashboard.clone());
 
         Ok(UpsertFileHandle {
             config: dashboard,
-            path: self
-                .dashboards_config_directory()?
-                .join(format!("{path}.yml")),
+            path: file,
         })
     }
 
     pub fn delete_dashboard(&mut self, path: &str) -> Result<DeleteFileHandle> {
+        let file = config_file(&self.dashboards_config_directory()?, path)?;
         self.dashboards.remove(path);
+        self.revoke_shares(path)?;
 
-        Ok(DeleteFileHandle {
-            path: self
-                .dashboards_config_directory()?
-                .join(format!("{path}.yml")),
-        })
+        Ok(DeleteFileHandle { path: file })
+    }
+
+    // Share links resolve by dashboard name, so they go with the dashboard instead
+    // of opening one created or imported later under the same name.
+    pub fn revoke_shares(&self, dashboard: &str) -> Result<()> {
+        let mut shares = Shares::load(&self.project_dir)?;
+        if shares.revoke_dashboard(dashboard) {
+            shares.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn rename_dashboard(&mut self, from: &str, to: &str) -> Result<RenameFileHandle> {
+        let directory = self.dashboards_config_directory()?;
+        let handle = RenameFileHandle {
+            from: config_file(&directory, from)?,
+            to: config_file(&directory, to)?,
+        };
+
+        if let Some(dashboard) = self.dashboards.remove(from) {
+            self.dashboards.insert(to.to_string(), dashboard);
+        }
+
+        Ok(handle)
     }
 
-    fn project_config_file(&self) -> Result<PathBuf> {
+    pub fn project_config_file(&self) -> Result<PathBuf> {
         Ok(self.project_dir.join("project.yml"))
     }
 
@@ -201,6 +748,10 @@ impl Config {
         Ok(self.project_dir.join("dashboards"))
     }
 
+    fn seeds_directory(&self) -> Result<PathBuf> {
+        Ok(self.project_dir.join("seeds"))
+    }
+
     fn load_adapters(&self) -> Result<HashMap<String, AdapterConfig>> {
         load_config_files(
             &self.adapters_config_directory()?,
@@ -223,6 +774,25 @@ impl Config {
         )
     }
 
+    fn load_seeds(&self) -> Result<HashMap<String, SeedConfig>> {
+        let mut seeds = HashMap::new();
+
+        for entry in fs::read_dir(self.seeds_directory()?)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("csv") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                // The stem becomes a table name, so it follows the same rules.
+                validate_name(name)
+                    .with_context(|| format!("Invalid seed file '{}'", path.display()))?;
+                seeds.insert(name.to_string(), SeedConfig { path });
+            }
+        }
+
+        Ok(seeds)
+    }
+
     fn load_project_config(&self) -> Result<ProjectConfig> {
         let project_yml_path = self.project_config_file()?;
         if !project_yml_path.exists() {
@@ -232,19 +802,54 @@ impl Config {
         }
 
         let content = fs::read_to_string(&project_yml_path)?;
+        let overlay = match &self.env {
+            Some(env) => {
+                let overlay_path = config_file(&self.project_dir, &format!("project.{env}"))?;
+                if !overlay_path.exists() {
+                    return Err(anyhow::anyhow!(
+                        "project.{env}.yml not found for environment '{env}'"
+                    ));
+                }
+                Some(fs::read_to_string(&overlay_path)?)
+            }
+            None => None,
+        };
 
-        let mut config = project::parse_project_config(&content)?;
+        let mut config = project::parse_project_config_with_overlay(&content, overlay.as_deref())?;
         config.resolve_paths(&self.project_dir)?;
-        config.load_secrets(&self.project_dir)?;
         Ok(config)
     }
 }
 
-fn load_config_files<T>(dir: &Path, parse_fn: fn(&str) -> Result<T>) -> Result<HashMap<String, T>> {
+// Names normally pass `validate_name` first; this only guarantees the file is
+// written directly in `directory`, never above it or in a subdirectory.
+fn config_file(directory: &Path, name: &str) -> Result<PathBuf> {
+    let components: Vec<Component> = Path::new(name).components().collect();
+    let single_file =
+        !name.contains(['/', '\\']) && matches!(components.as_slice(), [Component::Normal(_)]);
+    if !single_file {
+        return Err(anyhow::anyhow!(
+            "Name '{name}' is not a single file name in {directory:?}"
+        ));
+    }
+
+    Ok(directory.join(format!("{name}.yml")))
+}
+
+// Resources that can be grouped; files in a subdirectory fall into the group named
+// after it unless they set one themselves.
+pub trait Labeled {
+    fn group_mut(&mut self) -> &mut Option<String>;
+}
+
+fn load_config_files<T: Labeled>(
+    dir: &Path,
+    parse_fn: fn(&str) -> Result<T>,
+) -> Result<HashMap<String, T>> {
     load_config_files_recursive(dir, dir, parse_fn)
 }
 
-fn load_config_files_recursive<T>(
+fn load_config_files_recursive<T: Labeled>(
     dir: &Path,
     base_dir: &Path,
     parse_fn: fn(&str) -> Result<T>,
@@ -260,13 +865,69 @@ fn load_config_files_recursive<T>(
         } else if path.extension().and_then(|s| s.to_str()) == Some("yml") {
             let content = fs::read_to_string(&path)?;
             let key = generate_config_key(base_dir, &path);
-            config.insert(key, parse_fn(&content)?);
+            config.insert(key, parse_config_file(base_dir, &path, &content, parse_fn)?);
         }
     }
 
     Ok(config)
 }
 
+fn parse_config_file<T: Labeled>(
+    base_dir: &Path,
+    path: &Path,
+    content: &str,
+    parse_fn: fn(&str) -> Result<T>,
+) -> Result<T> {
+    let mut item = parse_fn(content)?;
+    if let Some(group) = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(base_dir).ok())
+        .filter(|group| !group.as_os_str().is_empty())
+    {
+        item.group_mut()
+            .get_or_insert_with(|| group.to_string_lossy().to_string());
+    }
+    Ok(item)
+}
+
+const RELOADABLE_DIRECTORIES: [&str; 4] = ["adapters", "models", "queries", "dashboards"];
+
+fn reload_file<T: Labeled + PartialEq>(
+    items: &mut HashMap<String, T>,
+    base_dir: &Path,
+    path: &Path,
+    parse_fn: fn(&str) -> Result<T>,
+) -> Result<FileChange> {
+    let key = generate_config_key(base_dir, path);
+    if !path.exists() {
+        return Ok(match items.remove(&key) {
+            Some(_) => FileChange::Removed,
+            None => FileChange::Unchanged,
+        });
+    }
+
+    let content = fs::read_to_string(path)?;
+    let item = parse_config_file(base_dir, path, &content, parse_fn)?;
+    if items.get(&key) == Some(&item) {
+        return Ok(FileChange::Unchanged);
+    }
+    items.insert(key, item);
+    Ok(FileChange::Changed)
+}
+
+fn collect_yaml_files(dir: &Path, files: &mut BTreeSet<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_yaml_files(&path, files);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("yml") {
+            files.insert(path);
+        }
+    }
+}
+
 fn generate_config_key(base_dir: &Path, file_path: &Path) -> String {
     file_path
         .strip_prefix(base_dir)
@@ -275,3 +936,99 @@ fn generate_config_key(base_dir: &Path, file_path: &Path) -> String {
         .to_string_lossy()
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        let longest = "a".repeat(MAX_NAME_LENGTH);
+        let too_long = "a".repeat(MAX_NAME_LENGTH + 1);
+
+        for name in ["orders", "_staging", "orders_2024", longest.as_str()] {
+            assert!(validate_name(name).is_ok(), "{name} should be valid");
+        }
+        for name in [
+            "",
+            "../../etc/cron.d/x",
+            "foo/bar",
+            "foo\\bar",
+            "2024_orders",
+            "Orders",
+            "my orders",
+            "注文",
+            "café",
+            too_long.as_str(),
+        ] {
+            assert!(validate_name(name).is_err(), "{name} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_load_seeds_validates_names() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let config = Config::new(tempdir.path().to_path_buf());
+        fs::create_dir(tempdir.path().join("seeds"))?;
+        fs::write(tempdir.path().join("seeds/countries.csv"), "code\nJP\n")?;
+
+        let seeds = config.load_seeds()?;
+        assert_eq!(seeds.keys().collect::<Vec<_>>(), vec!["countries"]);
+
+        fs::write(tempdir.path().join("seeds/Country Codes.csv"), "code\nJP\n")?;
+        let error = config.load_seeds().unwrap_err();
+        assert!(format!("{error:#}").contains("Invalid name 'Country Codes'"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_file_stays_in_directory() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let mut config = Config::new(tempdir.path().to_path_buf());
+        let query = QueryConfig {
+            description: None,
+            sql: "SELECT 1".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        };
+
+        assert!(config.upsert_query("../escape", &query).is_err());
+        assert!(config.upsert_query("/tmp/escape", &query).is_err());
+        assert!(config.upsert_query("nested/query", &query).is_err());
+        assert!(config.upsert_query("nested/", &query).is_err());
+        assert!(config.rename_query("missing", "../escape").is_err());
+        assert!(config.delete_query("..").is_err());
+        assert!(config.queries.is_empty());
+
+        config.upsert_query("daily_sales", &query)?.save()?;
+        assert!(tempdir.path().join("queries/daily_sales.yml").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_assigns_group_from_subdirectory() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let models_dir = tempdir.path().join("models");
+        fs::create_dir_all(models_dir.join("staging"))?;
+        fs::create_dir_all(models_dir.join("marts"))?;
+        fs::write(models_dir.join("top.yml"), "sql: SELECT 1")?;
+        fs::write(models_dir.join("staging/stg_orders.yml"), "sql: SELECT 1")?;
+        fs::write(
+            models_dir.join("marts/orders.yml"),
+            "sql: SELECT 1\ngroup: finance",
+        )?;
+
+        let models = Config::new(tempdir.path().to_path_buf()).load_models()?;
+        assert_eq!(models["top"].group, None);
+        assert_eq!(
+            models["staging/stg_orders"].group.as_deref(),
+            Some("staging")
+        );
+        assert_eq!(models["marts/orders"].group.as_deref(), Some("finance"));
+
+        Ok(())
+    }
+}
@@ -1,22 +1,148 @@
+use super::{
+    Labeled,
+    data_test::{DataTestConfig, RelationshipConfig, validate_data_tests, validate_relationships},
+    masking::MaskingConfig,
+    validate_name,
+};
 use serde::{Deserialize, Serialize};
+use sqlparser::{dialect::DuckDbDialect, parser::Parser, tokenizer::Token};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct AdapterConfig {
     pub connection: String,
+    // The table the adapter imports into; defaults to the adapter name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedupe: Option<DedupeConfig>,
     pub description: Option<String>,
     pub source: AdapterSource,
     pub columns: Vec<ColumnConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<DataTestConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<RelationshipConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub masking: Vec<MaskingConfig>,
+    // What to do when the source's columns no longer match the existing table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_schema_change: Option<SchemaChangePolicy>,
+    // Scheduling hints: `weight` is how many pipeline workers the import counts
+    // as, and an `exclusive` import runs with no other task alongside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive: bool,
+    // Only organize lists in the API and UI; builds ignore them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl Labeled for AdapterConfig {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        &mut self.group
+    }
 }
 
 impl AdapterConfig {
     pub fn has_changed(&self, other: &Self) -> bool {
         self.connection != other.connection
+            || self.target_table != other.target_table
+            || self.dedupe != other.dedupe
             || self.source != other.source
             || self.columns != other.columns
     }
+
+    // Enforced or renamed columns turn the import into an explicit projection of
+    // the configured columns; otherwise every source column is imported as read.
+    pub fn projects_columns(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| column.enforce || column.rename.is_some())
+    }
+
+    pub fn schema_change_policy(&self) -> SchemaChangePolicy {
+        self.on_schema_change.unwrap_or_default()
+    }
+
+    // Projected columns pin the table's shape, so only adapters importing every
+    // source column can drift.
+    pub fn detects_schema_changes(&self) -> bool {
+        self.schema_change_policy() != SchemaChangePolicy::Ignore && !self.projects_columns()
+    }
+
+    pub fn table_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.target_table.as_deref().unwrap_or(name)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(target_table) = &self.target_table {
+            validate_name(target_table)?;
+        }
+        for column in &self.columns {
+            column.validate()?;
+        }
+        if let Some(dedupe) = &self.dedupe {
+            dedupe.validate()?;
+        }
+        self.source.validate()?;
+        validate_relationships(&self.relationships)?;
+        validate_data_tests(&self.tests)
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChangePolicy {
+    Fail,
+    // New source columns are added to the table as nullable columns.
+    AppendNewColumns,
+    #[default]
+    Ignore,
+}
+
+// Drops duplicate rows as they are imported: either exact duplicates (`distinct`), or
+// all but the latest row per `unique_key`, ordered by `order_by`. Rows are compared
+// within one import statement, so not with rows an earlier run or batch loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DedupeConfig {
+    #[serde(default)]
+    pub distinct: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unique_key: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_by: Option<String>,
+}
+
+impl DedupeConfig {
+    pub fn columns(&self) -> Vec<&str> {
+        self.unique_key
+            .iter()
+            .map(String::as_str)
+            .chain(self.order_by.as_deref())
+            .collect()
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match (self.distinct, self.unique_key.is_empty(), &self.order_by) {
+            (true, true, None) | (false, false, Some(_)) => Ok(()),
+            (true, _, _) => Err(anyhow::anyhow!(
+                "dedupe: use either 'distinct' or 'unique_key', not both"
+            )),
+            (false, true, _) => Err(anyhow::anyhow!(
+                "dedupe: set 'distinct: true' or a 'unique_key' with 'order_by'"
+            )),
+            (false, false, None) => Err(anyhow::anyhow!(
+                "dedupe: 'unique_key' requires 'order_by' to pick the row to keep"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type")]
 pub enum AdapterSource {
     #[serde(rename = "file")]
@@ -25,36 +151,375 @@ pub enum AdapterSource {
         format: FormatConfig,
     },
     #[serde(rename = "database")]
-    Database { table_name: String },
+    Database {
+        table_name: String,
+        // Columns to import; an empty or missing projection imports every column.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        columns: Option<Vec<String>>,
+        // SQL predicate applied as the WHERE clause of the import query.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        incremental: Option<IncrementalConfig>,
+    },
+}
+
+impl AdapterSource {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            AdapterSource::File { format, .. } => format.validate(),
+            AdapterSource::Database {
+                columns,
+                filter,
+                incremental,
+                ..
+            } => {
+                if let Some(filter) = filter {
+                    validate_filter(filter)?;
+                }
+                match incremental {
+                    Some(incremental) => incremental.validate(columns.as_deref()),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+// Imports only rows whose `cursor_column` is newer than the target's latest, and
+// merges them in by `unique_key`. Rows with `deleted_flag` set are removed from the
+// target instead of inserted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct IncrementalConfig {
+    pub cursor_column: String,
+    pub unique_key: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_flag: Option<String>,
+}
+
+impl IncrementalConfig {
+    pub fn columns(&self) -> Vec<&str> {
+        std::iter::once(self.cursor_column.as_str())
+            .chain(self.unique_key.iter().map(String::as_str))
+            .chain(self.deleted_flag.as_deref())
+            .collect()
+    }
+
+    pub fn validate(&self, projection: Option<&[String]>) -> anyhow::Result<()> {
+        if self.cursor_column.trim().is_empty() {
+            return Err(anyhow::anyhow!("incremental: 'cursor_column' is required"));
+        }
+        if self.unique_key.is_empty() {
+            return Err(anyhow::anyhow!(
+                "incremental: 'unique_key' needs at least one column"
+            ));
+        }
+
+        // The merge reads these columns from the imported rows.
+        if let Some(projection) = projection.filter(|columns| !columns.is_empty())
+            && let Some(missing) = self
+                .columns()
+                .into_iter()
+                .find(|column| !projection.iter().any(|c| c == column))
+        {
+            return Err(anyhow::anyhow!(
+                "incremental: column '{missing}' must be included in 'columns'"
+            ));
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+// The filter is spliced into the import query, so it must be a single expression.
+fn validate_filter(filter: &str) -> anyhow::Result<()> {
+    let dialect = DuckDbDialect {};
+    let invalid = |e: &dyn std::fmt::Display| anyhow::anyhow!("Invalid filter '{filter}': {e}");
+
+    let mut parser = Parser::new(&dialect)
+        .try_with_sql(filter)
+        .map_err(|e| invalid(&e))?;
+    parser.parse_expr().map_err(|e| invalid(&e))?;
+
+    let next = parser.peek_token();
+    if next.token != Token::EOF {
+        return Err(invalid(&format!("unexpected '{}'", next.token)));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct FileConfig {
     pub path: String,
     pub compression: Option<String>,
     pub max_batch_size: Option<String>,
+    // Caps how many files one import statement reads; `max_batch_size` is a byte size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_files: Option<usize>,
+    #[serde(default)]
+    pub incremental: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_mode: Option<ImportMode>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl FileConfig {
+    // `import_mode` wins over the older `incremental` flag, which means `modified`.
+    pub fn import_mode(&self) -> ImportMode {
+        match self.import_mode {
+            Some(mode) => mode,
+            None if self.incremental => ImportMode::Modified,
+            None => ImportMode::Full,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    // Reload every file on every run.
+    #[default]
+    Full,
+    // Append files whose modification time, size or ETag changed since the last run.
+    Modified,
+    // Append files whose content changed: a checksum for local files, the ETag for
+    // object stores.
+    Content,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct FormatConfig {
     #[serde(rename = "type")]
     pub ty: String,
     pub delimiter: Option<String>,
     pub null_value: Option<String>,
     pub has_header: Option<bool>,
+    #[serde(default)]
+    pub sheet: Option<String>,
+    // Field layout of `fixed_width` files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns_spec: Vec<FixedWidthColumn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape: Option<String>,
+    // strftime patterns such as `%d/%m/%Y`, as DuckDB's CSV reader expects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+}
+
+// Project-wide values for the parsing options of one format type. An adapter's own
+// setting always wins; these only fill the options it leaves unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FormatDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub null_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+}
+
+// One field of a fixed-width record. `start` is 1-based, as in most record layouts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FixedWidthColumn {
+    pub name: String,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl FormatConfig {
+    pub const SUPPORTED_TYPES: &'static [&'static str] =
+        &["csv", "json", "ndjson", "parquet", "xlsx", "fixed_width"];
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !Self::SUPPORTED_TYPES.contains(&self.ty.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Unsupported file format '{}'. Supported formats: {}",
+                self.ty,
+                Self::SUPPORTED_TYPES.join(", ")
+            ));
+        }
+
+        if self.ty == "fixed_width" {
+            self.validate_columns_spec()?;
+        } else if !self.columns_spec.is_empty() {
+            return Err(anyhow::anyhow!(
+                "columns_spec only applies to fixed_width files"
+            ));
+        }
+        self.delimiter()?;
+        check_single_char("quote", self.quote.as_deref())?;
+        check_single_char("escape", self.escape.as_deref())?;
+        Ok(())
+    }
+
+    fn validate_columns_spec(&self) -> anyhow::Result<()> {
+        if self.columns_spec.is_empty() {
+            return Err(anyhow::anyhow!(
+                "fixed_width files need a columns_spec with the name, start and len of each field"
+            ));
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for column in &self.columns_spec {
+            if column.name.trim().is_empty() {
+                return Err(anyhow::anyhow!("Every fixed-width column needs a name"));
+            }
+            if !names.insert(column.name.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Fixed-width column '{}' is defined more than once",
+                    column.name
+                ));
+            }
+            if column.start == 0 || column.len == 0 {
+                return Err(anyhow::anyhow!(
+                    "Fixed-width column '{}' needs a start of at least 1 and a positive len",
+                    column.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The configured delimiter as a single character. Besides a literal character it
+    // accepts the escapes `\t`, `\\` and `\xHH`, so `\x01` selects ^A.
+    pub fn delimiter(&self) -> anyhow::Result<Option<char>> {
+        self.delimiter.as_deref().map(parse_delimiter).transpose()
+    }
+
+    pub fn with_defaults(mut self, defaults: &FormatDefaults) -> Self {
+        fn fill<T: Clone>(value: &mut Option<T>, default: &Option<T>) {
+            if value.is_none() {
+                value.clone_from(default);
+            }
+        }
+        fill(&mut self.delimiter, &defaults.delimiter);
+        fill(&mut self.null_value, &defaults.null_value);
+        fill(&mut self.has_header, &defaults.has_header);
+        fill(&mut self.quote, &defaults.quote);
+        fill(&mut self.escape, &defaults.escape);
+        fill(&mut self.date_format, &defaults.date_format);
+        fill(&mut self.timestamp_format, &defaults.timestamp_format);
+        self
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub fn parse_delimiter(raw: &str) -> anyhow::Result<char> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid delimiter '{}': use a single character or an escape like \\t or \\x01",
+            raw.escape_default()
+        )
+    };
+
+    let delimiter = match raw {
+        "\\t" => '\t',
+        "\\\\" => '\\',
+        _ if raw.starts_with("\\x") => {
+            let hex = &raw[2..];
+            if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            let byte = u8::from_str_radix(hex, 16).map_err(|_| invalid())?;
+            if !byte.is_ascii() {
+                return Err(invalid());
+            }
+            char::from(byte)
+        }
+        _ => {
+            let mut chars = raw.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(invalid()),
+            }
+        }
+    };
+
+    if matches!(delimiter, '\n' | '\r' | '"') {
+        return Err(invalid());
+    }
+    Ok(delimiter)
+}
+
+// DuckDB's CSV reader takes `quote` and `escape` as a single byte.
+pub fn check_single_char(option: &str, value: Option<&str>) -> anyhow::Result<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii() => Ok(()),
+        _ => Err(anyhow::anyhow!(
+            "Invalid {option} '{}': use exactly one ASCII character",
+            value.escape_default()
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct ColumnConfig {
     pub name: String,
     #[serde(rename = "type")]
     pub ty: String,
     pub description: Option<String>,
+    // Name of the column in the imported table, if different from the source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<String>,
+    // Cast the source column to `ty` during import instead of only checking it.
+    #[serde(default)]
+    pub enforce: bool,
+    // With `enforce`, values that fail to cast become NULL instead of failing the import.
+    #[serde(default)]
+    pub lenient: bool,
+}
+
+impl ColumnConfig {
+    pub fn output_name(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+
+    // The type is spliced into a CAST, so it must parse as a single data type.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        let dialect = DuckDbDialect {};
+        let invalid = |e: &dyn std::fmt::Display| {
+            anyhow::anyhow!("Invalid type '{}' for column '{}': {e}", self.ty, self.name)
+        };
+
+        let mut parser = Parser::new(&dialect)
+            .try_with_sql(&self.ty)
+            .map_err(|e| invalid(&e))?;
+        parser.parse_data_type().map_err(|e| invalid(&e))?;
+
+        let next = parser.peek_token();
+        if next.token != Token::EOF {
+            return Err(invalid(&format!("unexpected '{}'", next.token)));
+        }
+
+        Ok(())
+    }
 }
 
 pub fn parse_adapter_config(yaml_str: &str) -> anyhow::Result<AdapterConfig> {
-    serde_yml::from_str(yaml_str)
-        .map_err(|e| anyhow::anyhow!("Failed to parse adapter config: {}", e))
+    let config: AdapterConfig = serde_yml::from_str(yaml_str)
+        .map_err(|e| anyhow::anyhow!("Failed to parse adapter config: {}", e))?;
+    validate_data_tests(&config.tests)?;
+    validate_relationships(&config.relationships)?;
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -85,8 +550,16 @@ mod tests {
         );
 
         match &config.source {
-            AdapterSource::Database { table_name } => {
+            AdapterSource::Database {
+                table_name,
+                columns,
+                filter,
+                incremental,
+            } => {
                 assert_eq!(table_name, "users");
+                assert_eq!(columns, &None);
+                assert_eq!(filter, &None);
+                assert_eq!(incremental, &None);
             }
             _ => panic!("Expected Database source"),
         }
@@ -109,6 +582,7 @@ mod tests {
                 path: data/logs.json
                 compression: gzip
                 max_batch_size: 100MB
+                max_batch_files: 50
               format:
                 type: json
             columns:
@@ -131,6 +605,7 @@ mod tests {
                 assert_eq!(file.path, "data/logs.json");
                 assert_eq!(file.compression, Some("gzip".to_string()));
                 assert_eq!(file.max_batch_size, Some("100MB".to_string()));
+                assert_eq!(file.max_batch_files, Some(50));
                 assert_eq!(format.ty, "json");
             }
             _ => panic!("Expected File source"),
@@ -159,15 +634,31 @@ mod tests {
     fn test_adapter_config_has_changed() {
         let config1 = AdapterConfig {
             connection: "test_db".to_string(),
+            target_table: None,
+            dedupe: None,
             description: Some("Test adapter".to_string()),
             source: AdapterSource::Database {
                 table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
             },
             columns: vec![ColumnConfig {
                 name: "id".to_string(),
                 ty: "INTEGER".to_string(),
                 description: None,
+                rename: None,
+                enforce: false,
+                lenient: false,
             }],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         let config2 = config1.clone();
@@ -184,6 +675,9 @@ mod tests {
         let mut config5 = config1.clone();
         config5.source = AdapterSource::Database {
             table_name: "products".to_string(),
+            columns: None,
+            filter: None,
+            incremental: None,
         };
         assert!(config1.has_changed(&config5));
 
@@ -192,6 +686,9 @@ mod tests {
             name: "name".to_string(),
             ty: "STRING".to_string(),
             description: None,
+            rename: None,
+            enforce: false,
+            lenient: false,
         });
         assert!(config1.has_changed(&config6));
 
@@ -202,5 +699,213 @@ mod tests {
         let mut config8 = config1.clone();
         config8.columns[0].description = Some("Primary key".to_string());
         assert!(config1.has_changed(&config8));
+
+        let mut config9 = config1.clone();
+        config9.target_table = Some("raw_users".to_string());
+        assert!(config1.has_changed(&config9));
+    }
+
+    #[test]
+    fn test_dedupe_config_validate() {
+        let dedupe = |distinct: bool, unique_key: &[&str], order_by: Option<&str>| DedupeConfig {
+            distinct,
+            unique_key: unique_key.iter().map(|key| key.to_string()).collect(),
+            order_by: order_by.map(str::to_string),
+        };
+
+        assert!(dedupe(true, &[], None).validate().is_ok());
+        assert!(
+            dedupe(false, &["id"], Some("updated_at"))
+                .validate()
+                .is_ok()
+        );
+        assert!(dedupe(false, &[], None).validate().is_err());
+        assert!(dedupe(false, &["id"], None).validate().is_err());
+        assert!(
+            dedupe(true, &["id"], Some("updated_at"))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_format_config_validate() {
+        let format = |ty: &str| FormatConfig {
+            ty: ty.to_string(),
+            delimiter: None,
+            null_value: None,
+            has_header: None,
+            sheet: None,
+            columns_spec: vec![],
+            quote: None,
+            escape: None,
+            date_format: None,
+            timestamp_format: None,
+        };
+
+        for ty in FormatConfig::SUPPORTED_TYPES {
+            if *ty != "fixed_width" {
+                assert!(format(ty).validate().is_ok());
+            }
+        }
+
+        let error = format("xml").validate().unwrap_err();
+        assert!(error.to_string().contains("Unsupported file format 'xml'"));
+    }
+
+    #[test]
+    fn test_format_config_delimiter() {
+        let format = |delimiter: &str| FormatConfig {
+            ty: "csv".to_string(),
+            delimiter: Some(delimiter.to_string()),
+            null_value: None,
+            has_header: None,
+            sheet: None,
+            columns_spec: vec![],
+            quote: None,
+            escape: None,
+            date_format: None,
+            timestamp_format: None,
+        };
+
+        assert_eq!(format("|").delimiter().unwrap(), Some('|'));
+        assert_eq!(format("\\t").delimiter().unwrap(), Some('\t'));
+        assert_eq!(format("\t").delimiter().unwrap(), Some('\t'));
+        assert_eq!(format("\\x01").delimiter().unwrap(), Some('\u{1}'));
+        for invalid in ["", ";;", "\\x1", "\\x+1", "\\xff", "\\n", "\n"] {
+            assert!(format(invalid).validate().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_format_config_quote_and_escape() {
+        let format = |quote: &str, escape: &str| FormatConfig {
+            ty: "csv".to_string(),
+            delimiter: None,
+            null_value: None,
+            has_header: None,
+            sheet: None,
+            columns_spec: vec![],
+            quote: Some(quote.to_string()),
+            escape: Some(escape.to_string()),
+            date_format: None,
+            timestamp_format: None,
+        };
+
+        assert!(format("'", "\\").validate().is_ok());
+        for (quote, escape) in [
+            ("", "\\"),
+            ("''", "\\"),
+            ("'", ""),
+            ("'", "\\\\"),
+            ("«", "\\"),
+        ] {
+            assert!(
+                format(quote, escape).validate().is_err(),
+                "{quote:?} {escape:?}"
+            );
+        }
+        let error = format("\"\"", "\\").validate().unwrap_err();
+        assert!(error.to_string().contains("Invalid quote"));
+    }
+
+    #[test]
+    fn test_format_config_with_defaults() {
+        let defaults: FormatDefaults =
+            serde_yml::from_str("delimiter: ';'\nquote: \"'\"\ndate_format: '%d/%m/%Y'\n").unwrap();
+        let format: FormatConfig = serde_yml::from_str("type: csv\nquote: '\"'\n").unwrap();
+
+        let merged = format.with_defaults(&defaults);
+        assert_eq!(merged.delimiter().unwrap(), Some(';'));
+        assert_eq!(merged.quote.as_deref(), Some("\""));
+        assert_eq!(merged.date_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(merged.timestamp_format, None);
+    }
+
+    #[test]
+    fn test_format_config_columns_spec() {
+        let column = |name: &str, start, len| FixedWidthColumn {
+            name: name.to_string(),
+            start,
+            len,
+        };
+        let format = |ty: &str, columns_spec| FormatConfig {
+            ty: ty.to_string(),
+            delimiter: None,
+            null_value: None,
+            has_header: None,
+            sheet: None,
+            columns_spec,
+            quote: None,
+            escape: None,
+            date_format: None,
+            timestamp_format: None,
+        };
+
+        assert!(
+            format("fixed_width", vec![column("id", 1, 4)])
+                .validate()
+                .is_ok()
+        );
+        assert!(format("fixed_width", vec![]).validate().is_err());
+        assert!(
+            format("fixed_width", vec![column("id", 0, 4)])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            format("fixed_width", vec![column("id", 1, 4), column("id", 5, 2)])
+                .validate()
+                .is_err()
+        );
+        assert!(format("csv", vec![column("id", 1, 4)]).validate().is_err());
+    }
+
+    #[test]
+    fn test_database_source_validate_filter() {
+        let source = |filter: &str| AdapterSource::Database {
+            table_name: "orders".to_string(),
+            columns: Some(vec!["id".to_string(), "total".to_string()]),
+            filter: Some(filter.to_string()),
+            incremental: None,
+        };
+
+        assert!(
+            source("created_at >= '2024-01-01' AND status = 'paid'")
+                .validate()
+                .is_ok()
+        );
+        assert!(source("status = ").validate().is_err());
+        assert!(source("1 = 1; DROP TABLE orders").validate().is_err());
+    }
+
+    #[test]
+    fn test_database_source_validate_incremental() {
+        let source = |columns: Option<Vec<&str>>, unique_key: Vec<&str>| AdapterSource::Database {
+            table_name: "orders".to_string(),
+            columns: columns.map(|columns| columns.into_iter().map(String::from).collect()),
+            filter: None,
+            incremental: Some(IncrementalConfig {
+                cursor_column: "updated_at".to_string(),
+                unique_key: unique_key.into_iter().map(String::from).collect(),
+                deleted_flag: Some("deleted".to_string()),
+            }),
+        };
+
+        assert!(source(None, vec!["id"]).validate().is_ok());
+        assert!(
+            source(
+                Some(vec!["id", "total", "updated_at", "deleted"]),
+                vec!["id"]
+            )
+            .validate()
+            .is_ok()
+        );
+        assert!(source(None, vec![]).validate().is_err());
+        assert!(
+            source(Some(vec!["id", "total", "updated_at"]), vec!["id"])
+                .validate()
+                .is_err()
+        );
     }
 }
@@ -0,0 +1,136 @@
+use super::secret::set_secret_key_permissions;
+use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64};
+use ring::{
+    digest::{SHA256, digest},
+    rand::{SecureRandom, SystemRandom},
+};
+use std::{fs, io, path::Path};
+
+const TOKEN_FILE: &str = ".api_token";
+// Issued separately with `duckhub admin-token`; projects need not have one.
+const ADMIN_TOKEN_FILE: &str = ".admin_token";
+
+// Only the SHA-256 digest of the token is kept on disk; the plaintext is shown
+// to the user once, when it is generated.
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    hash: String,
+    admin_hash: Option<String>,
+}
+
+impl ApiToken {
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = project_dir.join(TOKEN_FILE);
+        let hash = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read API token hash from {path:?}"))?;
+
+        let admin_path = project_dir.join(ADMIN_TOKEN_FILE);
+        let admin_hash = match fs::read_to_string(&admin_path) {
+            Ok(hash) => Some(hash.trim().to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read admin token hash from {admin_path:?}")
+                });
+            }
+        };
+
+        Ok(Self {
+            hash: hash.trim().to_string(),
+            admin_hash,
+        })
+    }
+
+    pub fn verify(&self, token: &str) -> bool {
+        hash_token(token) == self.hash
+    }
+
+    pub fn verify_admin(&self, token: &str) -> bool {
+        self.admin_hash
+            .as_ref()
+            .is_some_and(|hash| hash_token(token) == *hash)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    digest(&SHA256, token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+// Creates a new token, replacing any previous one, and returns its plaintext.
+pub fn generate_api_token(project_dir: &Path) -> Result<String> {
+    write_token(&project_dir.join(TOKEN_FILE)).context("Failed to write API token file")
+}
+
+// Like the project token, but it may also lift column masking.
+pub fn generate_admin_token(project_dir: &Path) -> Result<String> {
+    write_token(&project_dir.join(ADMIN_TOKEN_FILE)).context("Failed to write admin token file")
+}
+
+fn write_token(path: &Path) -> Result<String> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow::anyhow!("Failed to generate token"))?;
+    let token = BASE64.encode(bytes);
+
+    fs::write(path, hash_token(&token))?;
+    set_secret_key_permissions(path).context("Failed to set token file permissions")?;
+
+    Ok(token)
+}
+
+// Projects created before tokens existed get one on their first start.
+pub fn ensure_api_token(project_dir: &Path) -> Result<Option<String>> {
+    if project_dir.join(TOKEN_FILE).exists() {
+        return Ok(None);
+    }
+
+    generate_api_token(project_dir).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_api_token_roundtrip() -> Result<()> {
+        let dir = tempdir()?;
+
+        let token = ensure_api_token(dir.path())?.unwrap();
+        assert!(ensure_api_token(dir.path())?.is_none());
+
+        let stored = fs::read_to_string(dir.path().join(TOKEN_FILE))?;
+        assert!(!stored.contains(&token));
+
+        let api_token = ApiToken::load(dir.path())?;
+        assert!(api_token.verify(&token));
+        assert!(!api_token.verify("wrong"));
+
+        let rotated = generate_api_token(dir.path())?;
+        assert_ne!(rotated, token);
+        assert!(!ApiToken::load(dir.path())?.verify(&token));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_admin_token() -> Result<()> {
+        let dir = tempdir()?;
+        let token = generate_api_token(dir.path())?;
+        assert!(!ApiToken::load(dir.path())?.verify_admin(&token));
+
+        let admin = generate_admin_token(dir.path())?;
+        let api_token = ApiToken::load(dir.path())?;
+        assert!(api_token.verify_admin(&admin));
+        assert!(!api_token.verify_admin(&token));
+        assert!(!api_token.verify(&admin));
+
+        Ok(())
+    }
+}
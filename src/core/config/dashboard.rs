@@ -1,13 +1,109 @@
+use super::Labeled;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "DashboardFile")]
 pub struct DashboardConfig {
     pub description: Option<String>,
+    pub panels: Vec<PanelConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl Labeled for DashboardConfig {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        &mut self.group
+    }
+}
+
+impl DashboardConfig {
+    pub fn queries(&self) -> impl Iterator<Item = &str> {
+        self.panels.iter().map(|panel| panel.query.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PanelConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     pub query: String,
     pub chart: ChartConfig,
+    #[serde(default)]
+    pub position: GridPosition,
+}
+
+impl PanelConfig {
+    pub fn new(query: impl Into<String>, chart: ChartConfig) -> Self {
+        Self {
+            title: None,
+            query: query.into(),
+            chart,
+            position: GridPosition::default(),
+        }
+    }
+}
+
+// Cells on a 12-column grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GridPosition {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Default for GridPosition {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            w: 12,
+            h: 4,
+        }
+    }
+}
+
+// Dashboards written before panels had a single top-level `query` and `chart`; they
+// load as a one-panel dashboard.
+#[derive(Deserialize)]
+struct DashboardFile {
+    description: Option<String>,
+    panels: Option<Vec<PanelConfig>>,
+    query: Option<String>,
+    chart: Option<ChartConfig>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    group: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl TryFrom<DashboardFile> for DashboardConfig {
+    type Error = String;
+
+    fn try_from(file: DashboardFile) -> Result<Self, Self::Error> {
+        let panels = match (file.panels, file.query, file.chart) {
+            (Some(panels), None, None) => panels,
+            (None, Some(query), Some(chart)) => vec![PanelConfig::new(query, chart)],
+            (Some(_), _, _) => {
+                return Err("`panels` cannot be combined with `query` or `chart`".to_string());
+            }
+            (None, None, _) => return Err("missing field `query` or `panels`".to_string()),
+            (None, Some(_), None) => return Err("missing field `chart`".to_string()),
+        };
+
+        Ok(Self {
+            description: file.description,
+            panels,
+            tags: file.tags,
+            group: file.group,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct ChartConfig {
     #[serde(rename = "type")]
     pub chart_type: ChartType,
@@ -15,11 +111,26 @@ pub struct ChartConfig {
     pub y_column: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ChartType {
     Line,
     Bar,
+    Pie,
+    Area,
+    Scatter,
+}
+
+impl ChartType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChartType::Line => "line",
+            ChartType::Bar => "bar",
+            ChartType::Pie => "pie",
+            ChartType::Area => "area",
+            ChartType::Scatter => "scatter",
+        }
+    }
 }
 
 pub fn parse_dashboard_config(yaml_str: &str) -> anyhow::Result<DashboardConfig> {
@@ -47,10 +158,10 @@ mod tests {
             config.description,
             Some("Monthly sales overview".to_string())
         );
-        assert_eq!(config.query, "monthly_sales");
-        assert_eq!(config.chart.chart_type, ChartType::Line);
-        assert_eq!(config.chart.x_column, "month");
-        assert_eq!(config.chart.y_column, "revenue");
+        assert_eq!(config.panels[0].query, "monthly_sales");
+        assert_eq!(config.panels[0].chart.chart_type, ChartType::Line);
+        assert_eq!(config.panels[0].chart.x_column, "month");
+        assert_eq!(config.panels[0].chart.y_column, "revenue");
     }
 
     #[test]
@@ -66,10 +177,10 @@ mod tests {
         let config = parse_dashboard_config(yaml_str).unwrap();
 
         assert_eq!(config.description, None);
-        assert_eq!(config.query, "category_breakdown");
-        assert_eq!(config.chart.chart_type, ChartType::Bar);
-        assert_eq!(config.chart.x_column, "category");
-        assert_eq!(config.chart.y_column, "total_amount");
+        assert_eq!(config.panels[0].query, "category_breakdown");
+        assert_eq!(config.panels[0].chart.chart_type, ChartType::Bar);
+        assert_eq!(config.panels[0].chart.x_column, "category");
+        assert_eq!(config.panels[0].chart.y_column, "total_amount");
     }
 
     #[test]
@@ -86,7 +197,7 @@ mod tests {
         assert!(result.is_ok());
         let config = result.unwrap();
         assert_eq!(config.description, None);
-        assert_eq!(config.query, "test_query");
+        assert_eq!(config.panels[0].query, "test_query");
     }
 
     #[test]
@@ -103,12 +214,82 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("query"));
     }
 
+    #[test]
+    fn test_parse_dashboard_config_legacy_is_single_panel() {
+        let yaml_str = r#"
+            query: "monthly_sales"
+            chart:
+              type: line
+              x_column: "month"
+              y_column: "revenue"
+        "#;
+
+        let config = parse_dashboard_config(yaml_str).unwrap();
+        assert_eq!(config.panels.len(), 1);
+        assert_eq!(config.panels[0].title, None);
+        assert_eq!(config.panels[0].position, GridPosition::default());
+
+        // Saved again, it is written in the panels shape.
+        let yaml_str = serde_yml::to_string(&config).unwrap();
+        assert!(yaml_str.contains("panels:"));
+        assert_eq!(parse_dashboard_config(&yaml_str).unwrap(), config);
+    }
+
+    #[test]
+    fn test_parse_dashboard_config_panels() {
+        let yaml_str = r#"
+            description: "KPIs"
+            panels:
+              - title: "Revenue"
+                query: "monthly_sales"
+                chart:
+                  type: line
+                  x_column: "month"
+                  y_column: "revenue"
+                position: { x: 0, y: 0, w: 6, h: 3 }
+              - query: "category_breakdown"
+                chart:
+                  type: bar
+                  x_column: "category"
+                  y_column: "total"
+        "#;
+
+        let config = parse_dashboard_config(yaml_str).unwrap();
+        assert_eq!(config.panels.len(), 2);
+        assert_eq!(config.panels[0].title.as_deref(), Some("Revenue"));
+        assert_eq!(
+            config.panels[0].position,
+            GridPosition {
+                x: 0,
+                y: 0,
+                w: 6,
+                h: 3
+            }
+        );
+        assert_eq!(config.panels[1].position, GridPosition::default());
+        assert_eq!(
+            config.queries().collect::<Vec<_>>(),
+            vec!["monthly_sales", "category_breakdown"]
+        );
+    }
+
+    #[test]
+    fn test_parse_dashboard_config_panels_with_query() {
+        let yaml_str = r#"
+            query: "monthly_sales"
+            panels: []
+        "#;
+
+        let result = parse_dashboard_config(yaml_str);
+        assert!(result.unwrap_err().to_string().contains("panels"));
+    }
+
     #[test]
     fn test_parse_dashboard_config_invalid_chart_type() {
         let yaml_str = r#"
             query: "test_query"
             chart:
-              type: pie
+              type: donut
               x_column: "x"
               y_column: "y"
         "#;
@@ -134,4 +315,57 @@ mod tests {
         assert_eq!(deserialized_line, ChartType::Line);
         assert_eq!(deserialized_bar, ChartType::Bar);
     }
+
+    #[test]
+    fn test_parse_dashboard_config_new_chart_types() {
+        for (name, chart_type) in [
+            ("pie", ChartType::Pie),
+            ("area", ChartType::Area),
+            ("scatter", ChartType::Scatter),
+        ] {
+            let yaml_str = format!(
+                r#"
+            query: "test_query"
+            chart:
+              type: {name}
+              x_column: "x"
+              y_column: "y"
+        "#
+            );
+
+            let config = parse_dashboard_config(&yaml_str).unwrap();
+            assert_eq!(config.panels[0].chart.chart_type, chart_type);
+        }
+    }
+
+    #[test]
+    fn test_dashboard_config_yaml_round_trip() {
+        for chart_type in [
+            ChartType::Line,
+            ChartType::Bar,
+            ChartType::Pie,
+            ChartType::Area,
+            ChartType::Scatter,
+        ] {
+            let config = DashboardConfig {
+                description: Some("Round trip".to_string()),
+                panels: vec![PanelConfig::new(
+                    "test_query",
+                    ChartConfig {
+                        chart_type: chart_type.clone(),
+                        x_column: "x".to_string(),
+                        y_column: "y".to_string(),
+                    },
+                )],
+                tags: vec![],
+                group: None,
+            };
+
+            let yaml_str = serde_yml::to_string(&config).unwrap();
+            assert!(yaml_str.contains(&format!("type: {}", chart_type.as_str())));
+
+            let parsed = parse_dashboard_config(&yaml_str).unwrap();
+            assert_eq!(parsed, config);
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DataTestConfig {
+    pub column: String,
+    #[serde(flatten)]
+    pub kind: DataTestKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DataTestKind {
+    NotNull,
+    Unique,
+    AcceptedValues { values: Vec<String> },
+}
+
+impl DataTestKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataTestKind::NotNull => "not_null",
+            DataTestKind::Unique => "unique",
+            DataTestKind::AcceptedValues { .. } => "accepted_values",
+        }
+    }
+}
+
+// Every non-null `column` value must appear in `references.column` of the referenced
+// adapter, model or seed; checked after the task builds its table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RelationshipConfig {
+    pub column: String,
+    pub references: ColumnReference,
+    #[serde(default, skip_serializing_if = "Severity::is_error")]
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ColumnReference {
+    pub table: String,
+    pub column: String,
+}
+
+impl fmt::Display for ColumnReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.table, self.column)
+    }
+}
+
+// Failing `warn` checks are reported without failing the task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warn,
+}
+
+impl Severity {
+    pub fn is_error(&self) -> bool {
+        *self == Severity::Error
+    }
+}
+
+pub fn validate_relationships(relationships: &[RelationshipConfig]) -> anyhow::Result<()> {
+    for relationship in relationships {
+        if relationship.column.trim().is_empty() {
+            return Err(anyhow::anyhow!("Relationship requires a column"));
+        }
+        if relationship.references.table.trim().is_empty()
+            || relationship.references.column.trim().is_empty()
+        {
+            return Err(anyhow::anyhow!(
+                "Relationship on column '{}' requires 'references.table' and 'references.column'",
+                relationship.column
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_data_tests(tests: &[DataTestConfig]) -> anyhow::Result<()> {
+    for test in tests {
+        if test.column.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Data test '{}' requires a column",
+                test.kind.name()
+            ));
+        }
+        if let DataTestKind::AcceptedValues { values } = &test.kind
+            && values.is_empty()
+        {
+            return Err(anyhow::anyhow!(
+                "Data test 'accepted_values' on column '{}' requires at least one value",
+                test.column
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_tests() {
+        let yaml_str = r#"
+            - column: id
+              type: unique
+            - column: id
+              type: not_null
+            - column: status
+              type: accepted_values
+              values: [a, b, c]
+        "#;
+
+        let tests: Vec<DataTestConfig> = serde_yml::from_str(yaml_str).unwrap();
+        assert_eq!(tests[0].kind, DataTestKind::Unique);
+        assert_eq!(tests[1].kind, DataTestKind::NotNull);
+        assert_eq!(
+            tests[2].kind,
+            DataTestKind::AcceptedValues {
+                values: vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            }
+        );
+        assert!(validate_data_tests(&tests).is_ok());
+
+        let result = serde_yml::from_str::<Vec<DataTestConfig>>("- column: id\n  type: positive\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_data_tests() {
+        let empty_values = vec![DataTestConfig {
+            column: "status".to_string(),
+            kind: DataTestKind::AcceptedValues { values: vec![] },
+        }];
+        let error = validate_data_tests(&empty_values).unwrap_err();
+        assert!(error.to_string().contains("at least one value"));
+
+        let missing_column = vec![DataTestConfig {
+            column: " ".to_string(),
+            kind: DataTestKind::NotNull,
+        }];
+        let error = validate_data_tests(&missing_column).unwrap_err();
+        assert!(error.to_string().contains("requires a column"));
+    }
+
+    #[test]
+    fn test_parse_relationships() {
+        let yaml_str = r#"
+            - column: user_id
+              references:
+                table: users
+                column: id
+            - column: country
+              references: { table: countries, column: code }
+              severity: warn
+        "#;
+
+        let relationships: Vec<RelationshipConfig> = serde_yml::from_str(yaml_str).unwrap();
+        assert_eq!(relationships[0].severity, Severity::Error);
+        assert_eq!(relationships[0].references.to_string(), "users.id");
+        assert_eq!(relationships[1].severity, Severity::Warn);
+        assert!(validate_relationships(&relationships).is_ok());
+
+        let missing_reference = vec![RelationshipConfig {
+            column: "user_id".to_string(),
+            references: ColumnReference {
+                table: "users".to_string(),
+                column: "".to_string(),
+            },
+            severity: Severity::Error,
+        }];
+        let error = validate_relationships(&missing_reference).unwrap_err();
+        assert!(error.to_string().contains("references.column"));
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Masks a column of the adapter's or model's table wherever the API returns it.
+// Pipeline transforms always read the real values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaskingConfig {
+    pub column: String,
+    pub mask: MaskMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskMode {
+    // Replaces the whole value.
+    Full,
+    // Keeps enough to recognise the value: the first letter and domain of an
+    // email, or the last four characters of anything else.
+    Partial,
+    // A stable digest, so masked values can still be grouped and joined on.
+    Hash,
+}
@@ -1,20 +1,88 @@
+use super::{
+    Labeled,
+    data_test::{DataTestConfig, RelationshipConfig, validate_data_tests, validate_relationships},
+    masking::MaskingConfig,
+};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct ModelConfig {
     pub description: Option<String>,
     pub sql: String,
+    #[serde(default, skip_serializing_if = "Materialization::is_table")]
+    pub materialization: Materialization,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unique_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<DataTestConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<RelationshipConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub masking: Vec<MaskingConfig>,
+    // Scheduling hints, as on adapters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl Labeled for ModelConfig {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        &mut self.group
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Materialization {
+    #[default]
+    Table,
+    View,
+    Incremental,
+}
+
+impl Materialization {
+    fn is_table(&self) -> bool {
+        *self == Materialization::Table
+    }
 }
 
 impl ModelConfig {
     pub fn has_changed(&self, other: &Self) -> bool {
         self.sql != other.sql
+            || self.materialization != other.materialization
+            || self.unique_key != other.unique_key
+            || self.updated_at_column != other.updated_at_column
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.materialization == Materialization::Incremental {
+            if self.unique_key.is_none() {
+                return Err(anyhow::anyhow!("Incremental models require 'unique_key'"));
+            }
+            if self.updated_at_column.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Incremental models require 'updated_at_column'"
+                ));
+            }
+        }
+        validate_relationships(&self.relationships)?;
+        validate_data_tests(&self.tests)
     }
 }
 
 pub fn parse_model_config(yaml_str: &str) -> anyhow::Result<ModelConfig> {
-    serde_yml::from_str(yaml_str)
-        .map_err(|e| anyhow::anyhow!("Failed to parse model config: {}", e))
+    let config: ModelConfig = serde_yml::from_str(yaml_str)
+        .map_err(|e| anyhow::anyhow!("Failed to parse model config: {}", e))?;
+    config.validate()?;
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -102,11 +170,72 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("sql"));
     }
 
+    #[test]
+    fn test_parse_model_config_materialization() {
+        let config = parse_model_config("sql: SELECT * FROM users").unwrap();
+        assert_eq!(config.materialization, Materialization::Table);
+
+        let config = parse_model_config(
+            r#"
+            sql: SELECT * FROM users
+            materialization: view
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.materialization, Materialization::View);
+
+        let config = parse_model_config(
+            r#"
+            sql: SELECT * FROM events
+            materialization: incremental
+            unique_key: id
+            updated_at_column: updated_at
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.materialization, Materialization::Incremental);
+        assert_eq!(config.unique_key, Some("id".to_string()));
+        assert_eq!(config.updated_at_column, Some("updated_at".to_string()));
+
+        let result = parse_model_config(
+            r#"
+            sql: SELECT * FROM events
+            materialization: incremental
+            updated_at_column: updated_at
+        "#,
+        );
+        assert!(result.unwrap_err().to_string().contains("unique_key"));
+
+        let result = parse_model_config(
+            r#"
+            sql: SELECT * FROM events
+            materialization: incremental
+            unique_key: id
+        "#,
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("updated_at_column")
+        );
+    }
+
     #[test]
     fn test_model_config_has_changed() {
         let config1 = ModelConfig {
             description: Some("Test model".to_string()),
             sql: "SELECT * FROM users".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         let config2 = config1.clone();
@@ -127,6 +256,16 @@ mod tests {
         let config6 = ModelConfig {
             description: None,
             sql: "SELECT * FROM users".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
         assert!(!config1.has_changed(&config6));
     }
@@ -142,6 +281,16 @@ mod tests {
               FROM users
               WHERE created_at > '2024-01-01'"#
                 .to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -152,4 +301,25 @@ mod tests {
         assert!(json.contains("\"sql\":"));
         assert!(json.contains("WHERE created_at"));
     }
+
+    #[test]
+    fn test_model_config_tags_round_trip() {
+        let yaml_str = r#"
+            sql: "SELECT 1"
+            tags: [marts, finance]
+            group: reporting
+        "#;
+
+        let config = parse_model_config(yaml_str).unwrap();
+        assert_eq!(config.tags, vec!["marts", "finance"]);
+        assert_eq!(config.group.as_deref(), Some("reporting"));
+
+        let yaml_str = serde_yml::to_string(&config).unwrap();
+        assert_eq!(parse_model_config(&yaml_str).unwrap(), config);
+
+        let untagged = parse_model_config("sql: SELECT 1").unwrap();
+        let yaml_str = serde_yml::to_string(&untagged).unwrap();
+        assert!(!yaml_str.contains("tags"));
+        assert!(!yaml_str.contains("group"));
+    }
 }
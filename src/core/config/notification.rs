@@ -0,0 +1,104 @@
+use crate::core::config::secret::SecretField;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+// A channel that is told when a pipeline run fails.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationConfig {
+    Email(EmailConfig),
+}
+
+impl NotificationConfig {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            NotificationConfig::Email(config) => config.validate(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<SecretField>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub tls: TlsMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    // Plain SMTP, for local relays only.
+    None,
+    #[default]
+    Starttls,
+    // Implicit TLS from the first byte (SMTPS).
+    Tls,
+}
+
+impl EmailConfig {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls {
+            TlsMode::None => 25,
+            TlsMode::Starttls => 587,
+            TlsMode::Tls => 465,
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.host.trim().is_empty() {
+            return Err(anyhow::anyhow!("SMTP host is required"));
+        }
+        if self.from.trim().is_empty() {
+            return Err(anyhow::anyhow!("Sender address is required"));
+        }
+        if self.to.is_empty() {
+            return Err(anyhow::anyhow!("At least one recipient is required"));
+        }
+        if self.password.is_some() && self.username.is_none() {
+            return Err(anyhow::anyhow!("SMTP password is set without a username"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_email_channel() -> Result<()> {
+        let config: NotificationConfig = serde_yml::from_str(
+            r#"
+            type: email
+            host: smtp.example.com
+            username: duckhub
+            password:
+              type: env
+              name: DUCKHUB_SMTP_PASSWORD
+            from: duckhub@example.com
+            to: [data-team@example.com]
+            "#,
+        )?;
+
+        let NotificationConfig::Email(email) = &config;
+        assert_eq!(email.tls, TlsMode::Starttls);
+        assert_eq!(email.port(), 587);
+        config.validate()?;
+
+        let missing_recipients = EmailConfig {
+            to: vec![],
+            ..email.clone()
+        };
+        assert!(missing_recipients.validate().is_err());
+
+        Ok(())
+    }
+}
@@ -1,39 +1,139 @@
-use super::secret::SecretField;
+use super::{
+    adapter::{FormatConfig, FormatDefaults, check_single_char, parse_delimiter},
+    notification::{EmailConfig, NotificationConfig},
+    secret::SecretField,
+};
+use crate::core::maintenance::DEFAULT_SNAPSHOT_RETENTION_DAYS;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProjectConfig {
     pub storage: StorageConfig,
     pub database: DatabaseConfig,
     pub connections: HashMap<String, ConnectionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<CompactionSchedule>,
+    #[serde(default, skip_serializing_if = "ServerConfig::is_default")]
+    pub server: ServerConfig,
+    #[serde(default, skip_serializing_if = "EngineConfig::is_default")]
+    pub engine: EngineConfig,
+    #[serde(default, skip_serializing_if = "ExtensionsConfig::is_default")]
+    pub extensions: ExtensionsConfig,
+    #[serde(default, skip_serializing_if = "CacheConfig::is_default")]
+    pub cache: CacheConfig,
+    #[serde(default, skip_serializing_if = "PipelineConfig::is_default")]
+    pub pipeline: PipelineConfig,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub notifications: HashMap<String, NotificationConfig>,
+    // Values for `{{ var('name') }}` in model SQL; a run may override them.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vars: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "DefaultsConfig::is_default")]
+    pub defaults: DefaultsConfig,
 }
 
 impl ProjectConfig {
-    pub fn validate(&self, project_dir: &std::path::Path) -> Result<Vec<String>> {
-        let mut warnings = Vec::new();
+    pub fn has_encrypted_secrets(&self) -> bool {
+        let encrypted = |secret: &SecretField| matches!(secret, SecretField::Encrypted { .. });
 
-        if !project_dir.join(".secret.key").exists() {
-            let has_encrypted_fields = self.connections.values().any(|conn| match conn {
-                ConnectionConfig::MySql(config) => {
-                    matches!(config.password, SecretField::Encrypted { .. })
+        self.database.password.as_ref().is_some_and(encrypted)
+            || self.connections.values().any(|conn| match conn {
+                ConnectionConfig::MySql(config) | ConnectionConfig::PostgreSql(config) => {
+                    encrypted(&config.password)
                 }
-                ConnectionConfig::PostgreSql(config) => {
-                    matches!(config.password, SecretField::Encrypted { .. })
+                ConnectionConfig::S3(config) => {
+                    config.secret_access_key.as_ref().is_some_and(encrypted)
                 }
-                ConnectionConfig::S3(config) => config
-                    .secret_access_key
-                    .as_ref()
-                    .is_some_and(|key| matches!(key, SecretField::Encrypted { .. })),
+                ConnectionConfig::Http(config) => config.auth_token.as_ref().is_some_and(encrypted),
+                ConnectionConfig::Gcs(config) => encrypted(&config.hmac_secret),
                 _ => false,
-            }) || matches!(self.storage, StorageConfig::S3(ref config) if config.secret_access_key.as_ref().is_some_and(|key| matches!(key, SecretField::Encrypted { .. })));
+            })
+            || match &self.storage {
+                StorageConfig::S3(config) => {
+                    config.secret_access_key.as_ref().is_some_and(encrypted)
+                }
+                StorageConfig::Gcs(config) => encrypted(&config.hmac_secret),
+                StorageConfig::LocalFile { .. } => false,
+            }
+            || self.notifications.values().any(|channel| match channel {
+                NotificationConfig::Email(config) => {
+                    config.password.as_ref().is_some_and(encrypted)
+                }
+            })
+    }
+
+    // Every secret in the project with where it lives, e.g. "connection 'warehouse'".
+    fn secrets(&self) -> Vec<(String, &SecretField)> {
+        let mut secrets = Vec::new();
+        if let Some(password) = &self.database.password {
+            secrets.push(("database".to_string(), password));
+        }
 
-            if has_encrypted_fields {
-                warnings.push("Encrypted fields found but .secret.key file is missing. Encrypted fields will fail to decrypt.".to_string());
+        let mut names = self.connections.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let secret = match &self.connections[name] {
+                ConnectionConfig::MySql(config) | ConnectionConfig::PostgreSql(config) => {
+                    Some(&config.password)
+                }
+                ConnectionConfig::S3(config) => config.secret_access_key.as_ref(),
+                ConnectionConfig::Http(config) => config.auth_token.as_ref(),
+                ConnectionConfig::Gcs(config) => Some(&config.hmac_secret),
+                _ => None,
+            };
+            if let Some(secret) = secret {
+                secrets.push((format!("connection '{name}'"), secret));
             }
         }
 
+        match &self.storage {
+            StorageConfig::S3(config) => {
+                if let Some(secret_key) = &config.secret_access_key {
+                    secrets.push(("storage".to_string(), secret_key));
+                }
+            }
+            StorageConfig::Gcs(config) => {
+                secrets.push(("storage".to_string(), &config.hmac_secret))
+            }
+            StorageConfig::LocalFile { .. } => {}
+        }
+
+        let mut names = self.notifications.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let NotificationConfig::Email(config) = &self.notifications[name];
+            if let Some(password) = &config.password {
+                secrets.push((format!("notification '{name}'"), password));
+            }
+        }
+
+        secrets
+    }
+
+    // Checked before the project is written, so a reference that may not be
+    // resolved never reaches project.yml.
+    pub fn check_secret_references(&self) -> Result<()> {
+        for (location, secret) in self.secrets() {
+            secret
+                .check_reference()
+                .with_context(|| format!("Invalid secret for {location}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self, project_dir: &std::path::Path) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+
+        if !project_dir.join(".secret.key").exists() && self.has_encrypted_secrets() {
+            warnings.push("Encrypted fields found but .secret.key file is missing. Encrypted fields will fail to decrypt.".to_string());
+        }
+
         for (name, connection) in &self.connections {
             match connection {
                 ConnectionConfig::LocalFile { base_path } => {
@@ -118,6 +218,72 @@ impl ProjectConfig {
                         }
                     }
                 }
+                ConnectionConfig::Http(config) => {
+                    if !config.base_url.starts_with("http://")
+                        && !config.base_url.starts_with("https://")
+                    {
+                        warnings.push(format!(
+                            "Connection '{}': HTTP base URL must start with http:// or https://",
+                            name
+                        ));
+                    }
+                }
+                ConnectionConfig::Gcs(config) => {
+                    if config.bucket.is_empty() {
+                        warnings.push(format!("Connection '{}': GCS bucket name is empty", name));
+                    }
+                    if config.hmac_key_id.is_empty() {
+                        warnings.push(format!("Connection '{}': GCS HMAC key ID is empty", name));
+                    }
+                }
+            }
+        }
+
+        for (location, secret) in self.secrets() {
+            if let SecretField::EnvVar { name, .. } = secret
+                && std::env::var(name).is_err()
+            {
+                warnings.push(format!(
+                    "Secret for {location} references environment variable '{name}', which is not set"
+                ));
+            }
+        }
+
+        if let Err(e) = self.engine.validate() {
+            warnings.push(e.to_string());
+        }
+        if let Err(e) = self.extensions.validate() {
+            warnings.push(e.to_string());
+        }
+        if let Err(e) = self.defaults.validate() {
+            warnings.push(e.to_string());
+        }
+        if self.cache.max_entries == Some(0) && self.cache.enabled() {
+            warnings.push("Cache: max_entries is 0, so query results are never cached".to_string());
+        }
+
+        if let Some(public_url) = &self.server.public_url
+            && !public_url.starts_with("http://")
+            && !public_url.starts_with("https://")
+        {
+            warnings.push(format!(
+                "Server: public URL '{public_url}' must start with http:// or https://"
+            ));
+        }
+
+        let mut names = self.notifications.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            if let Err(e) = self.notifications[name].validate() {
+                warnings.push(format!("Notification '{name}': {e}"));
+            }
+        }
+
+        for origin in &self.server.allowed_origins {
+            if !origin.starts_with("http://") && !origin.starts_with("https://") {
+                warnings.push(format!(
+                    "Server: allowed origin '{origin}' must start with http:// or https://"
+                ));
             }
         }
 
@@ -140,7 +306,7 @@ impl ProjectConfig {
                         .to_string();
                 }
             }
-            StorageConfig::S3(_) => {}
+            StorageConfig::S3(_) | StorageConfig::Gcs(_) => {}
         }
 
         if let Some(db_path) = &mut self.database.path
@@ -212,6 +378,24 @@ impl ProjectConfig {
                         })?;
                     }
                 }
+                ConnectionConfig::Http(config) => {
+                    if let Some(auth_token) = &mut config.auth_token {
+                        auth_token.load(project_dir).with_context(|| {
+                            format!(
+                                "Failed to load HTTP auth token for connection '{}'",
+                                connection_name
+                            )
+                        })?;
+                    }
+                }
+                ConnectionConfig::Gcs(config) => {
+                    config.hmac_secret.load(project_dir).with_context(|| {
+                        format!(
+                            "Failed to load GCS HMAC secret for connection '{}'",
+                            connection_name
+                        )
+                    })?;
+                }
                 _ => {}
             }
         }
@@ -224,8 +408,131 @@ impl ProjectConfig {
                 .with_context(|| "Failed to load S3 storage secret access key")?;
         }
 
+        if let StorageConfig::Gcs(gcs_config) = &mut self.storage {
+            gcs_config
+                .hmac_secret
+                .load(project_dir)
+                .with_context(|| "Failed to load GCS storage HMAC secret")?;
+        }
+
+        for (name, channel) in self.notifications.iter_mut() {
+            let NotificationConfig::Email(config) = channel;
+            if let Some(password) = &mut config.password {
+                password.load(project_dir).with_context(|| {
+                    format!("Failed to load SMTP password for notification '{name}'")
+                })?;
+            }
+        }
+
         Ok(())
     }
+
+    pub fn encrypt_secrets(&mut self, project_dir: &std::path::Path) -> Result<()> {
+        if let Some(password) = &mut self.database.password {
+            password
+                .encrypt_in_place(project_dir)
+                .with_context(|| "Failed to encrypt database password")?;
+        }
+
+        for (connection_name, connection) in self.connections.iter_mut() {
+            let secret = match connection {
+                ConnectionConfig::MySql(config) | ConnectionConfig::PostgreSql(config) => {
+                    Some(&mut config.password)
+                }
+                ConnectionConfig::S3(config) => config.secret_access_key.as_mut(),
+                ConnectionConfig::Http(config) => config.auth_token.as_mut(),
+                ConnectionConfig::Gcs(config) => Some(&mut config.hmac_secret),
+                _ => None,
+            };
+            if let Some(secret) = secret {
+                secret.encrypt_in_place(project_dir).with_context(|| {
+                    format!("Failed to encrypt secret for connection '{connection_name}'")
+                })?;
+            }
+        }
+
+        if let StorageConfig::S3(s3_config) = &mut self.storage
+            && let Some(secret_key) = &mut s3_config.secret_access_key
+        {
+            secret_key
+                .encrypt_in_place(project_dir)
+                .with_context(|| "Failed to encrypt S3 storage secret access key")?;
+        }
+
+        if let StorageConfig::Gcs(gcs_config) = &mut self.storage {
+            gcs_config
+                .hmac_secret
+                .encrypt_in_place(project_dir)
+                .with_context(|| "Failed to encrypt GCS storage HMAC secret")?;
+        }
+
+        for (name, channel) in self.notifications.iter_mut() {
+            let NotificationConfig::Email(config) = channel;
+            if let Some(password) = &mut config.password {
+                password.encrypt_in_place(project_dir).with_context(|| {
+                    format!("Failed to encrypt SMTP password for notification '{name}'")
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Blanks every secret so the config can leave this machine. Returns where
+    // secrets were removed, e.g. "connection 'warehouse'".
+    pub fn strip_secrets(&mut self) -> Vec<String> {
+        let mut stripped = Vec::new();
+        let mut strip = |secret: &mut SecretField, location: String| {
+            // References hold no secret themselves, so they travel as they are.
+            if secret.is_reference() {
+                return;
+            }
+            *secret = SecretField::PlainText {
+                value: String::new(),
+            };
+            stripped.push(location);
+        };
+
+        if let Some(password) = &mut self.database.password {
+            strip(password, "database".to_string());
+        }
+
+        let mut names = self.connections.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let secret = self
+                .connections
+                .get_mut(&name)
+                .and_then(ConnectionConfig::secret_mut);
+            if let Some(secret) = secret {
+                strip(secret, format!("connection '{name}'"));
+            }
+        }
+
+        match &mut self.storage {
+            StorageConfig::S3(config) => {
+                if let Some(secret_key) = &mut config.secret_access_key {
+                    strip(secret_key, "storage".to_string());
+                }
+            }
+            StorageConfig::Gcs(config) => strip(&mut config.hmac_secret, "storage".to_string()),
+            StorageConfig::LocalFile { .. } => {}
+        }
+
+        let mut names = self.notifications.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            if let Some(NotificationConfig::Email(EmailConfig {
+                password: Some(password),
+                ..
+            })) = self.notifications.get_mut(&name)
+            {
+                strip(password, format!("notification '{name}'"));
+            }
+        }
+
+        stripped
+    }
 }
 
 impl Default for ProjectConfig {
@@ -244,10 +551,376 @@ impl Default for ProjectConfig {
                 password: None,
             },
             connections: HashMap::new(),
+            schedule: None,
+            compaction: None,
+            server: ServerConfig::default(),
+            engine: EngineConfig::default(),
+            extensions: ExtensionsConfig::default(),
+            cache: CacheConfig::default(),
+            pipeline: PipelineConfig::default(),
+            notifications: HashMap::new(),
+            vars: HashMap::new(),
+            defaults: DefaultsConfig::default(),
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    pub cron: String,
+    #[serde(default = "default_schedule_enabled")]
+    pub enabled: bool,
+}
+
+fn default_schedule_enabled() -> bool {
+    true
+}
+
+impl ScheduleConfig {
+    pub fn parse(&self) -> Result<cron::Schedule> {
+        parse_cron(&self.cron)
+    }
+
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        next_cron_run(&self.cron, after)
+    }
+}
+
+// Storage compaction runs on its own cron, usually far less often than the pipeline,
+// and whether or not the pipeline is scheduled at all.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionSchedule {
+    pub cron: String,
+    #[serde(default = "default_schedule_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u32>,
+}
+
+impl CompactionSchedule {
+    pub fn older_than_days(&self) -> u32 {
+        self.older_than_days
+            .unwrap_or(DEFAULT_SNAPSHOT_RETENTION_DAYS)
+    }
+
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        next_cron_run(&self.cron, after)
+    }
+}
+
+pub fn next_cron_run(cron: &str, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    Ok(parse_cron(cron)?.after(&after).next())
+}
+
+fn parse_cron(cron: &str) -> Result<cron::Schedule> {
+    // Accept the common 5-field crontab syntax as well as the 6/7-field
+    // syntax (with seconds) understood by the cron crate.
+    let expression = if cron.split_whitespace().count() == 5 {
+        format!("0 {cron}")
+    } else {
+        cron.to_string()
+    };
+
+    cron::Schedule::from_str(&expression)
+        .with_context(|| format!("Invalid cron expression '{cron}'"))
+}
+
+// How the API server is exposed when it runs behind a proxy or serves a
+// separately hosted frontend.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    // Where users reach the server, e.g. "https://duckhub.example.com". Used to link
+    // back from notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
+    // Lets ad-hoc queries that ask for it run DDL and DML. Only for setups where
+    // everyone holding the API token may change the data.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_writes: bool,
+}
+
+pub const DEFAULT_MAX_POOL_SIZE: u32 = 8;
+pub const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_QUERY_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_MAX_QUERY_TIMEOUT_SECS: u64 = 600;
+pub const DEFAULT_DASHBOARD_TIMEOUT_SECS: u64 = 15;
+pub const DEFAULT_MAX_API_QUERIES: usize = 4;
+pub const DEFAULT_API_QUEUE_TIMEOUT_SECS: u64 = 5;
+pub const DEFAULT_MAX_DIFF_ROWS: u64 = 1_000_000;
+
+// Tuning for the embedded DuckDB engine. `memory_limit` and `threads` are applied
+// to every pooled connection; unset, DuckDB's own defaults are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquire_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_query_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dashboard_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_api_queries: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_queue_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_diff_rows: Option<u64>,
+}
+
+impl EngineConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    // One connection per CPU, capped so large machines do not open dozens of
+    // connections against the same database file.
+    pub fn pool_size(&self) -> u32 {
+        self.pool_size
+            .unwrap_or_else(|| (num_cpus::get() as u32).min(DEFAULT_MAX_POOL_SIZE))
+            .max(1)
+    }
+
+    pub fn acquire_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.acquire_timeout_secs
+                .unwrap_or(DEFAULT_ACQUIRE_TIMEOUT_SECS),
+        )
+    }
+
+    // Ad-hoc queries use the configured default unless the request asks for its own
+    // timeout, which is capped at `max_query_timeout_secs`.
+    pub fn query_timeout(&self, requested_secs: Option<u64>) -> std::time::Duration {
+        let max = self
+            .max_query_timeout_secs
+            .unwrap_or(DEFAULT_MAX_QUERY_TIMEOUT_SECS);
+        let secs = requested_secs
+            .or(self.query_timeout_secs)
+            .unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS);
+        std::time::Duration::from_secs(secs.clamp(1, max.max(1)))
+    }
+
+    pub fn dashboard_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.dashboard_timeout_secs
+                .unwrap_or(DEFAULT_DASHBOARD_TIMEOUT_SECS),
+        )
+    }
+
+    pub fn max_api_queries(&self) -> usize {
+        self.max_api_queries
+            .unwrap_or(DEFAULT_MAX_API_QUERIES)
+            .max(1)
+    }
+
+    // How long an API query waits for a free slot before it is turned away; 0 rejects
+    // immediately.
+    pub fn api_queue_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.api_queue_timeout_secs
+                .unwrap_or(DEFAULT_API_QUEUE_TIMEOUT_SECS),
+        )
+    }
+
+    // Table diffs refuse to compare versions larger than this many rows.
+    pub fn max_diff_rows(&self) -> u64 {
+        self.max_diff_rows.unwrap_or(DEFAULT_MAX_DIFF_ROWS)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.pool_size == Some(0) {
+            return Err(anyhow::anyhow!("Engine pool_size must be at least 1"));
+        }
+        if self.acquire_timeout_secs == Some(0) {
+            return Err(anyhow::anyhow!(
+                "Engine acquire_timeout_secs must be at least 1"
+            ));
+        }
+        if self.threads == Some(0) {
+            return Err(anyhow::anyhow!("Engine threads must be at least 1"));
+        }
+        if self.max_api_queries == Some(0) {
+            return Err(anyhow::anyhow!("Engine max_api_queries must be at least 1"));
+        }
+        if self.max_diff_rows == Some(0) {
+            return Err(anyhow::anyhow!("Engine max_diff_rows must be at least 1"));
+        }
+        for (name, secs) in [
+            ("query_timeout_secs", self.query_timeout_secs),
+            ("max_query_timeout_secs", self.max_query_timeout_secs),
+            ("dashboard_timeout_secs", self.dashboard_timeout_secs),
+        ] {
+            if secs == Some(0) {
+                return Err(anyhow::anyhow!("Engine {name} must be at least 1"));
+            }
+        }
+        if self
+            .memory_limit
+            .as_deref()
+            .is_some_and(|limit| limit.trim().is_empty())
+        {
+            return Err(anyhow::anyhow!("Engine memory_limit must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+// Where DuckDB gets its extensions from. `repository` replaces the default download
+// site and `directory` is where installed extensions are kept. With `offline` set
+// nothing is downloaded: extensions must already be in the directory and are only
+// loaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub offline: bool,
+}
+
+impl ExtensionsConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("repository", &self.repository),
+            ("directory", &self.directory),
+        ] {
+            if value
+                .as_deref()
+                .is_some_and(|value| value.trim().is_empty())
+            {
+                return Err(anyhow::anyhow!("Extensions {name} must not be empty"));
+            }
+        }
+        if self.offline && self.repository.is_some() {
+            return Err(anyhow::anyhow!(
+                "Extensions repository is never used in offline mode"
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 100;
+
+// Results of saved queries and dashboards are kept in memory until the tables they
+// read are rebuilt, the TTL passes, or the least recently used entry is evicted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+}
+
+impl CacheConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn ttl(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.ttl_secs.unwrap_or(DEFAULT_CACHE_TTL_SECS))
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries.unwrap_or(DEFAULT_CACHE_MAX_ENTRIES)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    // Defaults to one worker per CPU; `duckhub run --max-workers` and the run API
+    // override it for a single run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_workers: Option<usize>,
+}
+
+impl PipelineConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultsConfig {
+    // Parsing options keyed by format type, e.g. `csv`, applied to every adapter of
+    // that type when it is loaded or run. The adapter files are never rewritten.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_format: HashMap<String, FormatDefaults>,
+}
+
+impl DefaultsConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (ty, defaults) in &self.file_format {
+            if !FormatConfig::SUPPORTED_TYPES.contains(&ty.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Defaults: unsupported file format '{ty}'. Supported formats: {}",
+                    FormatConfig::SUPPORTED_TYPES.join(", ")
+                ));
+            }
+            if let Some(delimiter) = &defaults.delimiter {
+                parse_delimiter(delimiter).with_context(|| format!("Defaults for {ty} files"))?;
+            }
+            check_single_char("quote", defaults.quote.as_deref())
+                .and_then(|()| check_single_char("escape", defaults.escape.as_deref()))
+                .with_context(|| format!("Defaults for {ty} files"))?;
+        }
+        Ok(())
+    }
+}
+
+impl ServerConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    // Normalized to either "" or "/prefix", without a trailing slash.
+    pub fn base_path(&self) -> String {
+        let trimmed = self
+            .base_path
+            .as_deref()
+            .unwrap_or_default()
+            .trim_matches('/');
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("/{trimmed}")
+        }
+    }
+
+    // `public_url` joined with the base path, without a trailing slash.
+    pub fn public_base_url(&self) -> Option<String> {
+        self.public_url
+            .as_deref()
+            .map(|url| format!("{}{}", url.trim_end_matches('/'), self.base_path()))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
 pub enum StorageConfig {
@@ -255,9 +928,132 @@ pub enum StorageConfig {
     LocalFile { path: String },
     #[serde(rename = "s3")]
     S3(S3Config),
+    #[serde(rename = "gcs")]
+    Gcs(GcsConfig),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub const MASKED_SECRET: &str = "***";
+
+fn mask_secret(secret: Option<&mut SecretField>) {
+    if let Some(secret) = secret
+        && !secret.is_reference()
+    {
+        *secret = SecretField::PlainText {
+            value: MASKED_SECRET.to_string(),
+        };
+    }
+}
+
+// A masked secret sent back unchanged keeps the value it stood for.
+fn restore_secret(secret: Option<&mut SecretField>, before: Option<&mut SecretField>) {
+    if let (Some(secret), Some(before)) = (secret, before)
+        && matches!(secret, SecretField::PlainText { value } if value == MASKED_SECRET)
+    {
+        *secret = before.clone();
+    }
+}
+
+impl ConnectionConfig {
+    fn secret_mut(&mut self) -> Option<&mut SecretField> {
+        match self {
+            ConnectionConfig::MySql(config) | ConnectionConfig::PostgreSql(config) => {
+                Some(&mut config.password)
+            }
+            ConnectionConfig::S3(config) => config.secret_access_key.as_mut(),
+            ConnectionConfig::Http(config) => config.auth_token.as_mut(),
+            ConnectionConfig::Gcs(config) => Some(&mut config.hmac_secret),
+            ConnectionConfig::LocalFile { .. } | ConnectionConfig::Sqlite { .. } => None,
+        }
+    }
+
+    // Safe to show: a secret held in the config reads as "***", while env and file
+    // references are kept since they name where the secret comes from.
+    pub fn masked(&self) -> Self {
+        let mut masked = self.clone();
+        mask_secret(masked.secret_mut());
+        masked
+    }
+
+    // Where the connection reads from once paths are resolved.
+    pub fn location(&self) -> String {
+        match self {
+            ConnectionConfig::LocalFile { base_path } => base_path.clone(),
+            ConnectionConfig::Sqlite { path } => path.clone(),
+            ConnectionConfig::MySql(config) | ConnectionConfig::PostgreSql(config) => {
+                format!("{}:{}/{}", config.host, config.port, config.database)
+            }
+            ConnectionConfig::S3(config) => format!("s3://{}", config.bucket),
+            ConnectionConfig::Gcs(config) => format!("gs://{}", config.bucket),
+            ConnectionConfig::Http(config) => config.base_url.clone(),
+        }
+    }
+}
+
+impl StorageConfig {
+    fn secret_mut(&mut self) -> Option<&mut SecretField> {
+        match self {
+            StorageConfig::S3(config) => config.secret_access_key.as_mut(),
+            StorageConfig::Gcs(config) => Some(&mut config.hmac_secret),
+            StorageConfig::LocalFile { .. } => None,
+        }
+    }
+
+    // Masked the same way as connections.
+    pub fn masked(&self) -> Self {
+        let mut masked = self.clone();
+        mask_secret(masked.secret_mut());
+        masked
+    }
+
+    // Only a secret of the same kind of storage is kept.
+    pub fn restore_masked(&mut self, mut before: StorageConfig) {
+        if std::mem::discriminant(self) == std::mem::discriminant(&before) {
+            restore_secret(self.secret_mut(), before.secret_mut());
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            StorageConfig::LocalFile { path } => {
+                if path.trim().is_empty() {
+                    return Err(anyhow::anyhow!("Local storage path is required"));
+                }
+            }
+            StorageConfig::S3(config) => {
+                if config.bucket.trim().is_empty() {
+                    return Err(anyhow::anyhow!("S3 bucket name is required"));
+                }
+                if config.region.trim().is_empty() {
+                    return Err(anyhow::anyhow!("S3 region is required"));
+                }
+                if matches!(config.auth_method, S3AuthMethod::Explicit) {
+                    if config.access_key_id.as_deref().is_none_or(str::is_empty) {
+                        return Err(anyhow::anyhow!(
+                            "S3 access key ID is required for explicit auth"
+                        ));
+                    }
+                    if config.secret_access_key.is_none() {
+                        return Err(anyhow::anyhow!(
+                            "S3 secret access key is required for explicit auth"
+                        ));
+                    }
+                }
+            }
+            StorageConfig::Gcs(config) => {
+                if config.bucket.trim().is_empty() {
+                    return Err(anyhow::anyhow!("GCS bucket name is required"));
+                }
+                if config.hmac_key_id.trim().is_empty() {
+                    return Err(anyhow::anyhow!("GCS HMAC key ID is required"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct S3Config {
     pub bucket: String,
     pub region: String,
@@ -268,6 +1064,23 @@ pub struct S3Config {
     pub path_style_access: bool,
 }
 
+pub const GCS_DEFAULT_ENDPOINT: &str = "https://storage.googleapis.com";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GcsConfig {
+    pub bucket: String,
+    pub hmac_key_id: String,
+    pub hmac_secret: SecretField,
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+}
+
+impl GcsConfig {
+    pub fn endpoint(&self) -> &str {
+        self.endpoint_url.as_deref().unwrap_or(GCS_DEFAULT_ENDPOINT)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     #[serde(rename = "type")]
@@ -286,7 +1099,51 @@ pub struct DatabaseConfig {
     pub password: Option<SecretField>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+impl DatabaseConfig {
+    pub fn masked(&self) -> Self {
+        let mut masked = self.clone();
+        mask_secret(masked.password.as_mut());
+        masked
+    }
+
+    pub fn restore_masked(&mut self, mut before: DatabaseConfig) {
+        restore_secret(self.password.as_mut(), before.password.as_mut());
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let is_blank =
+            |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+
+        match self.ty {
+            DatabaseType::Sqlite => {
+                if is_blank(&self.path) {
+                    return Err(anyhow::anyhow!("SQLite catalog path is required"));
+                }
+            }
+            DatabaseType::Mysql | DatabaseType::Postgresql => {
+                if is_blank(&self.host) {
+                    return Err(anyhow::anyhow!("Database host is required"));
+                }
+                if self.port.is_none() {
+                    return Err(anyhow::anyhow!("Database port is required"));
+                }
+                if is_blank(&self.database) {
+                    return Err(anyhow::anyhow!("Database name is required"));
+                }
+                if is_blank(&self.username) {
+                    return Err(anyhow::anyhow!("Database username is required"));
+                }
+                if self.password.is_none() {
+                    return Err(anyhow::anyhow!("Database password is required"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
 pub enum S3AuthMethod {
     #[serde(rename = "credential_chain")]
     CredentialChain,
@@ -295,7 +1152,7 @@ pub enum S3AuthMethod {
     Explicit,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct RemoteDatabaseConfig {
     pub host: String,
     pub port: u16,
@@ -314,7 +1171,7 @@ pub enum DatabaseType {
     Postgresql,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type", content = "config")]
 pub enum ConnectionConfig {
     #[serde(rename = "localfile")]
@@ -327,6 +1184,19 @@ pub enum ConnectionConfig {
     MySql(RemoteDatabaseConfig),
     #[serde(rename = "postgresql")]
     PostgreSql(RemoteDatabaseConfig),
+    #[serde(rename = "http")]
+    Http(HttpConfig),
+    #[serde(rename = "gcs")]
+    Gcs(GcsConfig),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct HttpConfig {
+    pub base_url: String,
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub auth_token: Option<SecretField>,
 }
 
 fn expand_env_vars(value: &str) -> Result<String, anyhow::Error> {
@@ -365,3 +1235,460 @@ pub fn parse_project_config(yaml_str: &str) -> anyhow::Result<ProjectConfig> {
     serde_yml::from_str(&expanded_yaml)
         .map_err(|e| anyhow::anyhow!("Failed to parse project config: {}", e))
 }
+
+// Deep-merges an environment overlay (e.g. project.staging.yml) over project.yml:
+// mappings merge key by key, anything else in the overlay replaces the base value.
+pub fn parse_project_config_with_overlay(
+    yaml_str: &str,
+    overlay: Option<&str>,
+) -> anyhow::Result<ProjectConfig> {
+    let Some(overlay) = overlay else {
+        return parse_project_config(yaml_str);
+    };
+
+    let mut value = parse_yaml_value(yaml_str)?;
+    let overlay = parse_yaml_value(overlay)?;
+    if !overlay.is_null() {
+        merge_yaml(&mut value, overlay);
+    }
+    serde_yml::from_value(value)
+        .map_err(|e| anyhow::anyhow!("Failed to parse project config: {}", e))
+}
+
+fn parse_yaml_value(yaml_str: &str) -> anyhow::Result<serde_yml::Value> {
+    let expanded_yaml = expand_env_vars(yaml_str)?;
+    serde_yml::from_str(&expanded_yaml)
+        .map_err(|e| anyhow::anyhow!("Failed to parse project config: {}", e))
+}
+
+fn merge_yaml(base: &mut serde_yml::Value, overlay: serde_yml::Value) {
+    match (base, overlay) {
+        (serde_yml::Value::Mapping(base), serde_yml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_yaml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_project_config_with_schedule() {
+        let yaml_str = r#"
+            storage:
+              type: local
+              config:
+                path: ./storage
+            database:
+              type: sqlite
+              path: ./database.db
+            connections: {}
+            schedule:
+              cron: "0 3 * * *"
+        "#;
+
+        let config = parse_project_config(yaml_str).unwrap();
+        assert_eq!(
+            config.schedule,
+            Some(ScheduleConfig {
+                cron: "0 3 * * *".to_string(),
+                enabled: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_project_config_with_server() {
+        let yaml_str = r#"
+            storage:
+              type: local
+              config:
+                path: ./storage
+            database:
+              type: sqlite
+              path: ./database.db
+            connections: {}
+            server:
+              allowed_origins:
+                - https://app.example.com
+              base_path: /duckhub/
+        "#;
+
+        let config = parse_project_config(yaml_str).unwrap();
+        assert_eq!(
+            config.server.allowed_origins,
+            vec!["https://app.example.com".to_string()]
+        );
+        assert_eq!(config.server.base_path(), "/duckhub");
+        assert_eq!(ServerConfig::default().base_path(), "");
+
+        let yaml_str = serde_yml::to_string(&ProjectConfig::default()).unwrap();
+        assert!(!yaml_str.contains("server"));
+    }
+
+    #[test]
+    fn test_parse_project_config_with_extensions() {
+        let yaml_str = r#"
+            storage:
+              type: local
+              config:
+                path: ./storage
+            database:
+              type: sqlite
+              path: ./database.db
+            connections: {}
+            extensions:
+              directory: /opt/duckdb/extensions
+              offline: true
+        "#;
+
+        let config = parse_project_config(yaml_str).unwrap();
+        assert_eq!(
+            config.extensions,
+            ExtensionsConfig {
+                repository: None,
+                directory: Some("/opt/duckdb/extensions".to_string()),
+                offline: true,
+            }
+        );
+        assert!(config.extensions.validate().is_ok());
+
+        let mut extensions = config.extensions.clone();
+        extensions.repository = Some("https://mirror.example.com".to_string());
+        assert!(extensions.validate().is_err());
+
+        let yaml_str = serde_yml::to_string(&ProjectConfig::default()).unwrap();
+        assert!(!yaml_str.contains("extensions"));
+    }
+
+    #[test]
+    fn test_parse_project_config_without_schedule() {
+        let yaml_str = serde_yml::to_string(&ProjectConfig::default()).unwrap();
+        assert!(!yaml_str.contains("schedule"));
+
+        let config = parse_project_config(&yaml_str).unwrap();
+        assert_eq!(config.schedule, None);
+    }
+
+    #[test]
+    fn test_schedule_next_run_after() {
+        let schedule = ScheduleConfig {
+            cron: "0 3 * * *".to_string(),
+            enabled: true,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let next_run = schedule.next_run_after(now).unwrap();
+        assert_eq!(
+            next_run,
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 0, 0).unwrap())
+        );
+
+        let schedule = ScheduleConfig {
+            cron: "30 0 3 * * *".to_string(),
+            enabled: true,
+        };
+        let next_run = schedule.next_run_after(now).unwrap();
+        assert_eq!(
+            next_run,
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 0, 30).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_masked_connection() {
+        let connection = ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+            host: "db.internal".to_string(),
+            port: 5432,
+            database: "analytics".to_string(),
+            username: "reader".to_string(),
+            password: SecretField::PlainText {
+                value: "hunter2".to_string(),
+            },
+        });
+        let ConnectionConfig::PostgreSql(masked) = connection.masked() else {
+            panic!("masking changed the connection type");
+        };
+        assert_eq!(
+            masked.password,
+            SecretField::PlainText {
+                value: MASKED_SECRET.to_string()
+            }
+        );
+        assert_eq!(connection.location(), "db.internal:5432/analytics");
+
+        let reference = SecretField::EnvVar {
+            name: "PG_PASSWORD".to_string(),
+            value: Some("hunter2".to_string()),
+        };
+        let connection = ConnectionConfig::PostgreSql(RemoteDatabaseConfig {
+            password: reference.clone(),
+            ..masked
+        });
+        let ConnectionConfig::PostgreSql(masked) = connection.masked() else {
+            panic!("masking changed the connection type");
+        };
+        assert_eq!(masked.password, reference);
+    }
+
+    #[test]
+    fn test_parse_compaction_schedule() {
+        let yaml_str = r#"
+            storage:
+              type: local
+              config:
+                path: ./storage
+            database:
+              type: sqlite
+              path: ./database.db
+            connections: {}
+            compaction:
+              cron: "0 4 * * SUN"
+        "#;
+
+        let config = parse_project_config(yaml_str).unwrap();
+        assert_eq!(config.schedule, None);
+        let compaction = config.compaction.unwrap();
+        assert!(compaction.enabled);
+        assert_eq!(
+            compaction.older_than_days(),
+            DEFAULT_SNAPSHOT_RETENTION_DAYS
+        );
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            compaction.next_run_after(now).unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 7, 4, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_schedule_invalid_cron() {
+        let schedule = ScheduleConfig {
+            cron: "not a cron".to_string(),
+            enabled: true,
+        };
+
+        let result = schedule.parse();
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid cron expression")
+        );
+    }
+
+    #[test]
+    fn test_encrypt_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".secret.key"),
+            crate::core::config::secret::generate_secret_key().unwrap(),
+        )
+        .unwrap();
+
+        let mut config = ProjectConfig {
+            database: DatabaseConfig {
+                ty: DatabaseType::Postgresql,
+                path: None,
+                host: Some("localhost".to_string()),
+                port: Some(5432),
+                database: Some("catalog".to_string()),
+                username: Some("duckhub".to_string()),
+                password: Some(SecretField::PlainText {
+                    value: "catalog_password".to_string(),
+                }),
+            },
+            ..Default::default()
+        };
+        config.connections.insert(
+            "mysql".to_string(),
+            ConnectionConfig::MySql(RemoteDatabaseConfig {
+                host: "localhost".to_string(),
+                port: 3306,
+                database: "app".to_string(),
+                username: "app".to_string(),
+                password: SecretField::PlainText {
+                    value: "mysql_password".to_string(),
+                },
+            }),
+        );
+
+        let mut encrypted = config.clone();
+        encrypted.encrypt_secrets(dir.path()).unwrap();
+
+        let yaml = serde_yml::to_string(&encrypted).unwrap();
+        assert!(!yaml.contains("catalog_password"));
+        assert!(!yaml.contains("mysql_password"));
+
+        encrypted.load_secrets(dir.path()).unwrap();
+        assert_eq!(encrypted, config);
+    }
+
+    #[test]
+    fn test_parse_project_config_with_overlay() {
+        let base = r#"
+            storage:
+              type: s3
+              config:
+                bucket: lake
+                region: us-east-1
+                endpoint_url: http://localhost:9000
+                auth_method: explicit
+                access_key_id: key
+                path_style_access: true
+            database:
+              type: sqlite
+              path: ./database.db
+            connections:
+              warehouse:
+                type: localfile
+                config:
+                  base_path: ./data
+        "#;
+        let overlay = r#"
+            storage:
+              config:
+                bucket: lake-staging
+            database:
+              path: ./staging.db
+            connections:
+              replica:
+                type: localfile
+                config:
+                  base_path: ./replica
+        "#;
+
+        let config = parse_project_config_with_overlay(base, Some(overlay)).unwrap();
+        let StorageConfig::S3(s3) = &config.storage else {
+            panic!("expected S3 storage, got {:?}", config.storage);
+        };
+        assert_eq!(s3.bucket, "lake-staging");
+        assert_eq!(s3.region, "us-east-1");
+        assert_eq!(s3.endpoint_url.as_deref(), Some("http://localhost:9000"));
+        assert_eq!(s3.access_key_id.as_deref(), Some("key"));
+        assert!(s3.path_style_access);
+        assert_eq!(config.database.ty, DatabaseType::Sqlite);
+        assert_eq!(config.database.path.as_deref(), Some("./staging.db"));
+
+        let mut connections = config.connections.keys().collect::<Vec<_>>();
+        connections.sort();
+        assert_eq!(connections, vec!["replica", "warehouse"]);
+
+        assert_eq!(
+            parse_project_config_with_overlay(base, Some("")).unwrap(),
+            parse_project_config(base).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_warns_on_unset_env_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = ProjectConfig::default();
+        config.database.password = Some(SecretField::EnvVar {
+            name: "DUCKHUB_TEST_UNSET_CATALOG_PASSWORD".to_string(),
+            value: None,
+        });
+
+        let warnings = config.validate(dir.path()).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                "Secret for database references environment variable 'DUCKHUB_TEST_UNSET_CATALOG_PASSWORD', which is not set"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_storage_config_validate() {
+        let mut s3 = S3Config {
+            bucket: "lake".to_string(),
+            region: "us-east-1".to_string(),
+            endpoint_url: None,
+            auth_method: S3AuthMethod::Explicit,
+            access_key_id: None,
+            secret_access_key: None,
+            path_style_access: false,
+        };
+        assert!(StorageConfig::S3(s3.clone()).validate().is_err());
+
+        s3.auth_method = S3AuthMethod::CredentialChain;
+        assert!(StorageConfig::S3(s3.clone()).validate().is_ok());
+
+        s3.bucket = String::new();
+        assert!(StorageConfig::S3(s3).validate().is_err());
+
+        let local = StorageConfig::LocalFile {
+            path: String::new(),
+        };
+        assert!(local.validate().is_err());
+
+        let mut gcs = GcsConfig {
+            bucket: "lake".to_string(),
+            hmac_key_id: "GOOG1EXAMPLE".to_string(),
+            hmac_secret: SecretField::PlainText {
+                value: "secret".to_string(),
+            },
+            endpoint_url: None,
+        };
+        assert!(StorageConfig::Gcs(gcs.clone()).validate().is_ok());
+        assert_eq!(gcs.endpoint(), GCS_DEFAULT_ENDPOINT);
+
+        gcs.hmac_key_id = String::new();
+        assert!(StorageConfig::Gcs(gcs).validate().is_err());
+    }
+
+    #[test]
+    fn test_engine_query_timeout() {
+        let secs = std::time::Duration::from_secs;
+        let mut engine = EngineConfig::default();
+        assert_eq!(engine.query_timeout(None), secs(DEFAULT_QUERY_TIMEOUT_SECS));
+        assert_eq!(
+            engine.dashboard_timeout(),
+            secs(DEFAULT_DASHBOARD_TIMEOUT_SECS)
+        );
+
+        engine.query_timeout_secs = Some(20);
+        engine.max_query_timeout_secs = Some(120);
+        assert_eq!(engine.query_timeout(None), secs(20));
+        assert_eq!(engine.query_timeout(Some(5)), secs(5));
+        assert_eq!(engine.query_timeout(Some(3600)), secs(120));
+
+        engine.dashboard_timeout_secs = Some(0);
+        assert!(engine.validate().is_err());
+    }
+
+    #[test]
+    fn test_database_config_validate() {
+        let mut database = DatabaseConfig {
+            ty: DatabaseType::Postgresql,
+            path: None,
+            host: Some("localhost".to_string()),
+            port: Some(5432),
+            database: Some("catalog".to_string()),
+            username: Some("duckhub".to_string()),
+            password: None,
+        };
+        let err = database.validate().unwrap_err();
+        assert!(err.to_string().contains("password"));
+
+        database.password = Some(SecretField::PlainText {
+            value: "secret".to_string(),
+        });
+        assert!(database.validate().is_ok());
+
+        database.ty = DatabaseType::Sqlite;
+        assert!(database.validate().is_err());
+    }
+}
@@ -1,14 +1,47 @@
+use super::Labeled;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct QueryConfig {
     pub description: Option<String>,
     pub sql: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<ParamDef>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    Date,
+    Int,
+    Float,
+    String,
+}
+
+// Referenced from the SQL as `{{name}}`. Without a default the parameter is required.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ParamDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+}
+
+impl Labeled for QueryConfig {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        &mut self.group
+    }
 }
 
 impl QueryConfig {
     pub fn has_changed(&self, other: &Self) -> bool {
-        self.sql != other.sql
+        self.sql != other.sql || self.parameters != other.parameters
     }
 }
 
@@ -86,6 +119,9 @@ mod tests {
         let config1 = QueryConfig {
             description: Some("Test description".to_string()),
             sql: "SELECT * FROM users".to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
         };
 
         let config2 = config1.clone();
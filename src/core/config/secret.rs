@@ -8,7 +8,63 @@ use ring::{
     rand::SecureRandom,
 };
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path};
+use std::{
+    fmt, fs,
+    io::Write,
+    path::{Component, Path, PathBuf},
+};
+use utoipa::ToSchema;
+
+// Connections can be created through the API and pointed at any host, so a
+// reference must not be able to read anything but a secret meant for DuckHub:
+// variables need this prefix, and files must be inside the project but outside
+// its hidden files such as `.secret.key`.
+pub const SECRET_ENV_PREFIX: &str = "DUCKHUB_";
+
+#[derive(Debug)]
+pub struct InvalidSecretReference {
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidSecretReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for InvalidSecretReference {}
+
+fn invalid_reference(reason: String) -> anyhow::Error {
+    anyhow::Error::new(InvalidSecretReference { reason })
+}
+
+// Only plain, non-hidden components, so the path can't leave the project or name
+// one of its keys.
+fn is_contained(path: &Path) -> bool {
+    path.components().all(|component| match component {
+        Component::Normal(part) => !part.to_string_lossy().starts_with('.'),
+        Component::CurDir => true,
+        _ => false,
+    })
+}
+
+// Symlinks are followed before checking, so a link can't point outside either.
+fn secret_file_path(project_dir: &Path, path: &str) -> Result<PathBuf> {
+    let project_dir = project_dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve project directory {project_dir:?}"))?;
+    let file_path = project_dir
+        .join(path)
+        .canonicalize()
+        .with_context(|| format!("Failed to read secret file '{path}'"))?;
+
+    match file_path.strip_prefix(&project_dir) {
+        Ok(relative) if is_contained(relative) => Ok(file_path),
+        _ => Err(invalid_reference(format!(
+            "Secret file '{path}' resolves outside the project"
+        ))),
+    }
+}
 
 struct SingleNonce {
     nonce_bytes: [u8; NONCE_LEN],
@@ -34,17 +90,49 @@ impl NonceSequence for SingleNonce {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+// `EnvVar` and `File` reference a secret injected at runtime. The resolved value
+// only lives in memory, so saving the config writes the reference back, never the
+// secret itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type")]
 pub enum SecretField {
     #[serde(rename = "plain")]
     PlainText { value: String },
     #[serde(rename = "encrypted")]
     Encrypted { value: String },
+    #[serde(rename = "env")]
+    EnvVar {
+        name: String,
+        #[serde(skip)]
+        value: Option<String>,
+    },
+    #[serde(rename = "file")]
+    File {
+        path: String,
+        #[serde(skip)]
+        value: Option<String>,
+    },
 }
 
 impl SecretField {
+    pub fn check_reference(&self) -> Result<()> {
+        match self {
+            SecretField::EnvVar { name, .. } if !name.starts_with(SECRET_ENV_PREFIX) => {
+                Err(invalid_reference(format!(
+                    "Environment variable '{name}' cannot be referenced; secret variables must start with '{SECRET_ENV_PREFIX}'"
+                )))
+            }
+            SecretField::File { path, .. } if !is_contained(Path::new(path)) => {
+                Err(invalid_reference(format!(
+                    "Secret file '{path}' must be a relative path inside the project and not a hidden file"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn load(&mut self, project_dir: &Path) -> Result<()> {
+        self.check_reference()?;
         match self {
             SecretField::PlainText { .. } => Ok(()),
             SecretField::Encrypted { value } => {
@@ -53,15 +141,50 @@ impl SecretField {
                 *self = SecretField::PlainText { value: decrypted };
                 Ok(())
             }
+            SecretField::EnvVar { name, value } => {
+                let resolved = std::env::var(name)
+                    .with_context(|| format!("Environment variable '{name}' is not set"))?;
+                *value = Some(resolved);
+                Ok(())
+            }
+            SecretField::File { path, value } => {
+                let file_path = secret_file_path(project_dir, path)?;
+                let contents = fs::read_to_string(&file_path)
+                    .with_context(|| format!("Failed to read secret file {file_path:?}"))?;
+                // Mounted secret files usually end with a newline that is not part of the value.
+                *value = Some(contents.trim_end_matches(['\n', '\r']).to_string());
+                Ok(())
+            }
         }
     }
 
+    pub fn is_reference(&self) -> bool {
+        matches!(self, SecretField::EnvVar { .. } | SecretField::File { .. })
+    }
+
+    pub fn encrypt_in_place(&mut self, project_dir: &Path) -> Result<()> {
+        if let SecretField::PlainText { value } = self {
+            let key_path = project_dir.join(".secret.key");
+            *self = Self::encrypt(value, &key_path)?;
+        }
+        Ok(())
+    }
+
     pub fn plaintext(&self) -> Result<&str> {
         match self {
             SecretField::PlainText { value } => Ok(value),
+            SecretField::EnvVar {
+                value: Some(value), ..
+            }
+            | SecretField::File {
+                value: Some(value), ..
+            } => Ok(value),
             SecretField::Encrypted { .. } => Err(anyhow::anyhow!(
                 "Field not decrypted yet. Call load() first."
             )),
+            SecretField::EnvVar { value: None, .. } | SecretField::File { value: None, .. } => Err(
+                anyhow::anyhow!("Secret reference not resolved yet. Call load() first."),
+            ),
         }
     }
 
@@ -71,18 +194,12 @@ impl SecretField {
     }
 
     fn encrypt_string(plaintext: &str, key_path: &Path) -> Result<String> {
-        let key_bytes = fs::read(key_path)
-            .with_context(|| format!("Failed to read encryption key from {key_path:?}"))?;
-
-        if key_bytes.len() != 32 {
-            return Err(anyhow::anyhow!(
-                "Invalid key size: expected 32 bytes, got {}",
-                key_bytes.len()
-            ));
-        }
+        Self::encrypt_with_key(plaintext, &read_secret_key(key_path)?)
+    }
 
+    fn encrypt_with_key(plaintext: &str, key_bytes: &[u8]) -> Result<String> {
         let alg = &aead::AES_256_GCM;
-        let unbound_key = UnboundKey::new(alg, &key_bytes)
+        let unbound_key = UnboundKey::new(alg, key_bytes)
             .map_err(|_| anyhow::anyhow!("Failed to create encryption key"))?;
 
         let rng = rand::SystemRandom::new();
@@ -106,16 +223,10 @@ impl SecretField {
     }
 
     fn decrypt_string(encrypted: &str, key_path: &Path) -> Result<String> {
-        let key_bytes = fs::read(key_path)
-            .with_context(|| format!("Failed to read encryption key from {key_path:?}"))?;
-
-        if key_bytes.len() != 32 {
-            return Err(anyhow::anyhow!(
-                "Invalid key size: expected 32 bytes, got {}",
-                key_bytes.len()
-            ));
-        }
+        Self::decrypt_with_key(encrypted, &read_secret_key(key_path)?)
+    }
 
+    fn decrypt_with_key(encrypted: &str, key_bytes: &[u8]) -> Result<String> {
         let combined = BASE64
             .decode(encrypted)
             .context("Failed to decode encrypted data")?;
@@ -130,7 +241,7 @@ impl SecretField {
         let mut nonce_array = [0u8; NONCE_LEN];
         nonce_array.copy_from_slice(nonce_bytes);
 
-        let unbound_key = UnboundKey::new(alg, &key_bytes)
+        let unbound_key = UnboundKey::new(alg, key_bytes)
             .map_err(|_| anyhow::anyhow!("Failed to create decryption key"))?;
 
         let nonce_sequence = SingleNonce::new(nonce_array);
@@ -160,9 +271,168 @@ pub fn generate_secret_key() -> Result<Vec<u8>> {
     Ok(key)
 }
 
+fn read_secret_key(key_path: &Path) -> Result<Vec<u8>> {
+    let key_bytes = fs::read(key_path)
+        .with_context(|| format!("Failed to read encryption key from {key_path:?}"))?;
+
+    if key_bytes.len() != 32 {
+        return Err(anyhow::anyhow!(
+            "Invalid key size: expected 32 bytes, got {}",
+            key_bytes.len()
+        ));
+    }
+
+    Ok(key_bytes)
+}
+
+pub fn set_secret_key_permissions(path: &Path) -> Result<()> {
+    let perms = {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::Permissions::from_mode(0o600)
+        }
+        #[cfg(not(unix))]
+        {
+            let mut perms = fs::metadata(path)?.permissions();
+            perms.set_readonly(true);
+            perms
+        }
+    };
+
+    fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+pub struct KeyRotation {
+    pub rotated: usize,
+    // Where the previous key was kept.
+    pub backup: PathBuf,
+}
+
+pub fn rotate_secret_key(project_dir: &Path) -> Result<KeyRotation> {
+    let key_path = project_dir.join(".secret.key");
+    let project_path = project_dir.join("project.yml");
+
+    let old_key = read_secret_key(&key_path)?;
+    let new_key = generate_secret_key()?;
+
+    let content = fs::read_to_string(&project_path)
+        .with_context(|| format!("Failed to read {}", project_path.display()))?;
+    let mut document: serde_yml::Value =
+        serde_yml::from_str(&content).context("Failed to parse project.yml")?;
+
+    // Re-encrypt everything in memory first so that a single undecryptable
+    // field leaves both project.yml and the key untouched.
+    let mut path = Vec::new();
+    let rotated = reencrypt_secrets(&mut document, &old_key, &new_key, &mut path)?;
+    let rotated_content = serde_yml::to_string(&document)?;
+
+    // The old key stays on disk until project.yml is rewritten, and goes back in
+    // place if that fails.
+    let backup = backup_secret_key(project_dir, &old_key)?;
+    write_secret_key(&key_path, &new_key).context("Failed to replace secret key")?;
+
+    let project_tmp_path = project_dir.join("project.yml.tmp");
+    let written = fs::write(&project_tmp_path, rotated_content)
+        .and_then(|()| fs::rename(&project_tmp_path, &project_path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&project_tmp_path);
+        write_secret_key(&key_path, &old_key).with_context(|| {
+            format!(
+                "Failed to restore the previous secret key; it is kept in {}",
+                backup.display()
+            )
+        })?;
+        return Err(anyhow::Error::new(e).context("Failed to write project.yml"));
+    }
+
+    Ok(KeyRotation { rotated, backup })
+}
+
+// Every rotation keeps its own backup, so rotating twice can't lose a key that
+// an older copy of project.yml still needs.
+fn backup_secret_key(project_dir: &Path, key: &[u8]) -> Result<PathBuf> {
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S");
+    let mut attempt = 0;
+    loop {
+        let name = match attempt {
+            0 => format!(".secret.key.{timestamp}.bak"),
+            n => format!(".secret.key.{timestamp}-{n}.bak"),
+        };
+        let path = project_dir.join(name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(key)
+                    .and_then(|()| file.sync_all())
+                    .context("Failed to back up secret key")?;
+                set_secret_key_permissions(&path)?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(anyhow::Error::new(e).context("Failed to back up secret key")),
+        }
+    }
+}
+
+fn write_secret_key(key_path: &Path, key: &[u8]) -> Result<()> {
+    let new_key_path = key_path.with_extension("key.new");
+    fs::write(&new_key_path, key)?;
+    set_secret_key_permissions(&new_key_path)?;
+    fs::rename(&new_key_path, key_path)?;
+    Ok(())
+}
+
+fn reencrypt_secrets(
+    value: &mut serde_yml::Value,
+    old_key: &[u8],
+    new_key: &[u8],
+    path: &mut Vec<String>,
+) -> Result<usize> {
+    match value {
+        serde_yml::Value::Mapping(mapping) => {
+            if mapping.get("type").and_then(|ty| ty.as_str()) == Some("encrypted")
+                && let Some(serde_yml::Value::String(encrypted)) = mapping.get_mut("value")
+            {
+                let plaintext = SecretField::decrypt_with_key(encrypted, old_key)
+                    .with_context(|| format!("Failed to decrypt secret '{}'", path.join(".")))?;
+                *encrypted = SecretField::encrypt_with_key(&plaintext, new_key)?;
+                return Ok(1);
+            }
+
+            let mut rotated = 0;
+            for (key, child) in mapping.iter_mut() {
+                path.push(key.as_str().unwrap_or_default().to_string());
+                rotated += reencrypt_secrets(child, old_key, new_key, path)?;
+                path.pop();
+            }
+            Ok(rotated)
+        }
+        serde_yml::Value::Sequence(values) => {
+            let mut rotated = 0;
+            for child in values {
+                rotated += reencrypt_secrets(child, old_key, new_key, path)?;
+            }
+            Ok(rotated)
+        }
+        _ => Ok(0),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::config::{
+        Config,
+        project::{
+            ConnectionConfig, ProjectConfig, RemoteDatabaseConfig, S3AuthMethod, S3Config,
+            StorageConfig,
+        },
+    };
     use tempfile::tempdir;
 
     #[test]
@@ -180,7 +450,7 @@ mod tests {
             SecretField::Encrypted { value } => {
                 assert!(!value.is_empty());
             }
-            SecretField::PlainText { .. } => panic!("Expected encrypted field"),
+            _ => panic!("Expected encrypted field"),
         }
 
         let decrypted = SecretField::decrypt_string(
@@ -314,4 +584,259 @@ mod tests {
         assert!(field.load(dir.path()).is_ok());
         assert_eq!(field.plaintext().unwrap(), "plain_password");
     }
+
+    #[test]
+    fn test_load_env_var_field() {
+        let dir = tempdir().unwrap();
+        // Only this test reads the variable.
+        unsafe { std::env::set_var("DUCKHUB_TEST_ENV_SECRET", "env_password") };
+        let mut field = SecretField::EnvVar {
+            name: "DUCKHUB_TEST_ENV_SECRET".to_string(),
+            value: None,
+        };
+        assert!(field.plaintext().is_err());
+
+        field.load(dir.path()).unwrap();
+        assert_eq!(field.plaintext().unwrap(), "env_password");
+        assert_eq!(
+            serde_yml::to_string(&field).unwrap(),
+            "type: env\nname: DUCKHUB_TEST_ENV_SECRET\n"
+        );
+
+        let mut unset = SecretField::EnvVar {
+            name: "DUCKHUB_TEST_UNSET_SECRET".to_string(),
+            value: None,
+        };
+        let error = unset.load(dir.path()).unwrap_err();
+        assert!(error.to_string().contains("DUCKHUB_TEST_UNSET_SECRET"));
+
+        // Variables not meant for DuckHub are never read.
+        let mut other = SecretField::EnvVar {
+            name: "PATH".to_string(),
+            value: None,
+        };
+        let error = other.load(dir.path()).unwrap_err();
+        assert!(error.is::<InvalidSecretReference>());
+        assert!(other.plaintext().is_err());
+    }
+
+    #[test]
+    fn test_load_file_field() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("db_password"), "mounted_password\n").unwrap();
+        let mut field: SecretField =
+            serde_yml::from_str("type: file\npath: db_password\n").unwrap();
+
+        field.load(dir.path()).unwrap();
+        assert_eq!(field.plaintext().unwrap(), "mounted_password");
+
+        // Encrypting a reference leaves it alone, so only the path is ever saved.
+        field.encrypt_in_place(dir.path()).unwrap();
+        assert_eq!(
+            serde_yml::to_string(&field).unwrap(),
+            "type: file\npath: db_password\n"
+        );
+    }
+
+    #[test]
+    fn test_load_file_field_outside_project() {
+        let outer = tempdir().unwrap();
+        let dir = outer.path().join("project");
+        fs::create_dir(&dir).unwrap();
+        fs::write(outer.path().join("outside"), "leaked").unwrap();
+        fs::write(dir.join(".secret.key"), "key").unwrap();
+
+        for path in ["../outside", "/etc/passwd", ".secret.key", "./.secret.key"] {
+            let mut field = SecretField::File {
+                path: path.to_string(),
+                value: None,
+            };
+            let error = field.load(&dir).unwrap_err();
+            assert!(error.is::<InvalidSecretReference>(), "{path}: {error:#}");
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outer.path().join("outside"), dir.join("link")).unwrap();
+            let mut field = SecretField::File {
+                path: "link".to_string(),
+                value: None,
+            };
+            let error = field.load(&dir).unwrap_err();
+            assert!(error.is::<InvalidSecretReference>());
+        }
+    }
+
+    fn write_project_with_secrets(project_dir: &Path) -> ProjectConfig {
+        let key_path = project_dir.join(".secret.key");
+        fs::write(&key_path, generate_secret_key().unwrap()).unwrap();
+
+        let remote = |password: &str| RemoteDatabaseConfig {
+            host: "localhost".to_string(),
+            port: 3306,
+            database: "app".to_string(),
+            username: "app".to_string(),
+            password: SecretField::encrypt(password, &key_path).unwrap(),
+        };
+        let s3 = |secret: &str| S3Config {
+            bucket: "bucket".to_string(),
+            region: "us-east-1".to_string(),
+            endpoint_url: None,
+            auth_method: S3AuthMethod::Explicit,
+            access_key_id: Some("access".to_string()),
+            secret_access_key: Some(SecretField::encrypt(secret, &key_path).unwrap()),
+            path_style_access: false,
+        };
+
+        let mut project = ProjectConfig {
+            storage: StorageConfig::S3(s3("storage_secret")),
+            ..Default::default()
+        };
+        project.connections.insert(
+            "mysql".to_string(),
+            ConnectionConfig::MySql(remote("mysql_password")),
+        );
+        project.connections.insert(
+            "postgres".to_string(),
+            ConnectionConfig::PostgreSql(remote("postgres_password")),
+        );
+        project
+            .connections
+            .insert("s3".to_string(), ConnectionConfig::S3(s3("s3_secret")));
+
+        fs::write(
+            project_dir.join("project.yml"),
+            serde_yml::to_string(&project).unwrap(),
+        )
+        .unwrap();
+
+        project
+    }
+
+    #[test]
+    fn test_rotate_secret_key() {
+        let dir = tempdir().unwrap();
+        write_project_with_secrets(dir.path());
+
+        let old_key = fs::read(dir.path().join(".secret.key")).unwrap();
+        let old_project = fs::read_to_string(dir.path().join("project.yml")).unwrap();
+
+        let rotation = rotate_secret_key(dir.path()).unwrap();
+        assert_eq!(rotation.rotated, 4);
+
+        let new_key = fs::read(dir.path().join(".secret.key")).unwrap();
+        assert_eq!(new_key.len(), 32);
+        assert_ne!(new_key, old_key);
+        assert_eq!(fs::read(&rotation.backup).unwrap(), old_key);
+        assert!(!dir.path().join(".secret.key.new").exists());
+
+        // A second rotation keeps the first backup.
+        let second = rotate_secret_key(dir.path()).unwrap();
+        assert_ne!(second.backup, rotation.backup);
+        assert_eq!(fs::read(&rotation.backup).unwrap(), old_key);
+        assert_eq!(fs::read(&second.backup).unwrap(), new_key);
+
+        let new_project = fs::read_to_string(dir.path().join("project.yml")).unwrap();
+        assert_ne!(new_project, old_project);
+
+        let mut config = Config::new(dir.path().to_path_buf());
+        config.load().unwrap();
+
+        let password = |name: &str| match &config.project.connections[name] {
+            ConnectionConfig::MySql(c) | ConnectionConfig::PostgreSql(c) => {
+                c.password.plaintext().unwrap().to_string()
+            }
+            ConnectionConfig::S3(c) => c
+                .secret_access_key
+                .as_ref()
+                .unwrap()
+                .plaintext()
+                .unwrap()
+                .to_string(),
+            _ => panic!("Unexpected connection type"),
+        };
+        assert_eq!(password("mysql"), "mysql_password");
+        assert_eq!(password("postgres"), "postgres_password");
+        assert_eq!(password("s3"), "s3_secret");
+
+        match &config.project.storage {
+            StorageConfig::S3(s3) => assert_eq!(
+                s3.secret_access_key.as_ref().unwrap().plaintext().unwrap(),
+                "storage_secret"
+            ),
+            _ => panic!("Expected S3 storage"),
+        }
+    }
+
+    #[test]
+    fn test_rotate_secret_key_is_all_or_nothing() {
+        let dir = tempdir().unwrap();
+        let mut project = write_project_with_secrets(dir.path());
+
+        let other_key_path = dir.path().join("other.key");
+        fs::write(&other_key_path, generate_secret_key().unwrap()).unwrap();
+        if let Some(ConnectionConfig::MySql(mysql)) = project.connections.get_mut("mysql") {
+            mysql.password = SecretField::encrypt("foreign", &other_key_path).unwrap();
+        }
+        fs::write(
+            dir.path().join("project.yml"),
+            serde_yml::to_string(&project).unwrap(),
+        )
+        .unwrap();
+
+        let old_key = fs::read(dir.path().join(".secret.key")).unwrap();
+        let old_project = fs::read_to_string(dir.path().join("project.yml")).unwrap();
+
+        let result = rotate_secret_key(dir.path());
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("connections.mysql")
+        );
+
+        assert_eq!(fs::read(dir.path().join(".secret.key")).unwrap(), old_key);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("project.yml")).unwrap(),
+            old_project
+        );
+        assert!(backups(dir.path()).is_empty());
+        assert!(!dir.path().join(".secret.key.new").exists());
+    }
+
+    fn backups(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "bak"))
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rotate_secret_key_restores_key_on_failure() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        write_project_with_secrets(dir.path());
+        let old_key = fs::read(dir.path().join(".secret.key")).unwrap();
+        let old_project = fs::read_to_string(dir.path().join("project.yml")).unwrap();
+
+        // project.yml can't be replaced while a directory sits at its temporary path.
+        fs::create_dir(dir.path().join("project.yml.tmp")).unwrap();
+        let result = rotate_secret_key(dir.path());
+        assert!(result.is_err());
+
+        assert_eq!(fs::read(dir.path().join(".secret.key")).unwrap(), old_key);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("project.yml")).unwrap(),
+            old_project
+        );
+        let backups = backups(dir.path());
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read(&backups[0]).unwrap(), old_key);
+        let mode = fs::metadata(&backups[0]).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
 }
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Seeds are plain CSV files under `seeds/`; the file stem becomes the table name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedConfig {
+    pub path: PathBuf,
+}
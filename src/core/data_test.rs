@@ -0,0 +1,249 @@
+use crate::core::{
+    config::data_test::{DataTestConfig, DataTestKind, RelationshipConfig, Severity},
+    ducklake::{DuckLake, quote_ident, quote_literal},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataTestResult {
+    pub column: String,
+    #[serde(rename = "type")]
+    pub test_type: String,
+    pub passed: bool,
+    pub failing_rows: u64,
+    // `table.column` the relationship checked against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<String>,
+    #[serde(default, skip_serializing_if = "Severity::is_error")]
+    pub severity: Severity,
+}
+
+impl DataTestResult {
+    pub fn describe_failure(&self) -> String {
+        match &self.references {
+            Some(references) => format!(
+                "Relationship '{}' -> '{references}' failed: {} orphaned row(s)",
+                self.column, self.failing_rows
+            ),
+            None => format!(
+                "Data test '{}' on column '{}' failed: {} offending row(s)",
+                self.test_type, self.column, self.failing_rows
+            ),
+        }
+    }
+
+    // Failed `warn` checks are only reported.
+    pub fn fails_task(&self) -> bool {
+        !self.passed && self.severity.is_error()
+    }
+}
+
+// Each check counts the rows violating the assertion; zero means the test passed.
+fn build_check_query(table_name: &str, test: &DataTestConfig) -> String {
+    let table = quote_ident(table_name);
+    let column = quote_ident(&test.column);
+
+    match &test.kind {
+        DataTestKind::NotNull => {
+            format!("SELECT COUNT(*) FROM {table} WHERE {column} IS NULL")
+        }
+        DataTestKind::Unique => format!(
+            "SELECT COALESCE(SUM(duplicates), 0) FROM (SELECT COUNT(*) AS duplicates FROM {table} WHERE {column} IS NOT NULL GROUP BY {column} HAVING COUNT(*) > 1)"
+        ),
+        DataTestKind::AcceptedValues { values } => {
+            let values: Vec<String> = values.iter().map(|value| quote_literal(value)).collect();
+            format!(
+                "SELECT COUNT(*) FROM {table} WHERE {column} IS NOT NULL AND CAST({column} AS VARCHAR) NOT IN ({})",
+                values.join(", ")
+            )
+        }
+    }
+}
+
+pub fn run_data_tests(
+    ducklake: &DuckLake,
+    table_name: &str,
+    tests: &[DataTestConfig],
+) -> Result<Vec<DataTestResult>> {
+    tests
+        .iter()
+        .map(|test| {
+            let result = ducklake
+                .query(&build_check_query(table_name, test))
+                .with_context(|| {
+                    format!(
+                        "Failed to run data test '{}' on column '{}'",
+                        test.kind.name(),
+                        test.column
+                    )
+                })?;
+            let failing_rows = result
+                .first()
+                .and_then(|row| row.first())
+                .and_then(|count| count.parse().ok())
+                .unwrap_or_default();
+
+            Ok(DataTestResult {
+                column: test.column.clone(),
+                test_type: test.kind.name().to_string(),
+                passed: failing_rows == 0,
+                failing_rows,
+                references: None,
+                severity: Severity::Error,
+            })
+        })
+        .collect()
+}
+
+// Counts the rows whose non-null `column` has no match in the referenced table.
+fn build_relationship_query(
+    table_name: &str,
+    relationship: &RelationshipConfig,
+    referenced_table: &str,
+) -> String {
+    let table = quote_ident(table_name);
+    let column = quote_ident(&relationship.column);
+    let referenced = quote_ident(referenced_table);
+    let referenced_column = quote_ident(&relationship.references.column);
+
+    format!(
+        "SELECT COUNT(*) FROM {table} AS child ANTI JOIN {referenced} AS parent ON child.{column} = parent.{referenced_column} WHERE child.{column} IS NOT NULL"
+    )
+}
+
+// `relationships` pairs each relationship with the table its reference resolves to.
+pub fn run_relationship_tests(
+    ducklake: &DuckLake,
+    table_name: &str,
+    relationships: &[(&RelationshipConfig, &str)],
+) -> Result<Vec<DataTestResult>> {
+    relationships
+        .iter()
+        .map(|(relationship, referenced_table)| {
+            let result = ducklake
+                .query(&build_relationship_query(
+                    table_name,
+                    relationship,
+                    referenced_table,
+                ))
+                .with_context(|| {
+                    format!(
+                        "Failed to check relationship '{}' -> '{}'",
+                        relationship.column, relationship.references
+                    )
+                })?;
+            let failing_rows = result
+                .first()
+                .and_then(|row| row.first())
+                .and_then(|count| count.parse().ok())
+                .unwrap_or_default();
+
+            Ok(DataTestResult {
+                column: relationship.column.clone(),
+                test_type: "relationship".to_string(),
+                passed: failing_rows == 0,
+                failing_rows,
+                references: Some(relationship.references.to_string()),
+                severity: relationship.severity,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        adapter::test_helpers::setup_test_ducklake, config::data_test::ColumnReference,
+    };
+
+    fn data_test(column: &str, kind: DataTestKind) -> DataTestConfig {
+        DataTestConfig {
+            column: column.to_string(),
+            kind,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_data_tests() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.create_table_from_query(
+            "orders",
+            "SELECT * FROM (VALUES (1, 'paid'), (2, 'paid'), (2, 'lost'), (NULL, 'refunded'), (4, NULL)) t(id, status)",
+        )?;
+
+        let tests = vec![
+            data_test("id", DataTestKind::NotNull),
+            data_test("id", DataTestKind::Unique),
+            data_test(
+                "status",
+                DataTestKind::AcceptedValues {
+                    values: vec!["paid".to_string(), "refunded".to_string()],
+                },
+            ),
+            data_test(
+                "status",
+                DataTestKind::AcceptedValues {
+                    values: vec![
+                        "paid".to_string(),
+                        "refunded".to_string(),
+                        "lost".to_string(),
+                    ],
+                },
+            ),
+        ];
+
+        let results = run_data_tests(&ducklake, "orders", &tests)?;
+        let failing: Vec<(bool, u64)> = results
+            .iter()
+            .map(|result| (result.passed, result.failing_rows))
+            .collect();
+        assert_eq!(failing, vec![(false, 1), (false, 2), (false, 1), (true, 0)]);
+        assert_eq!(
+            results[1].describe_failure(),
+            "Data test 'unique' on column 'id' failed: 2 offending row(s)"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_relationship_tests() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.create_table_from_query("users", "SELECT * FROM (VALUES (1), (2)) t(id)")?;
+        ducklake.create_table_from_query(
+            "orders",
+            "SELECT * FROM (VALUES (10, 1), (11, 2), (12, 3), (13, NULL)) t(id, user_id)",
+        )?;
+
+        let relationship = |severity| RelationshipConfig {
+            column: "user_id".to_string(),
+            references: ColumnReference {
+                table: "users".to_string(),
+                column: "id".to_string(),
+            },
+            severity,
+        };
+        let failing = relationship(Severity::Error);
+        let warning = relationship(Severity::Warn);
+
+        let results = run_relationship_tests(
+            &ducklake,
+            "orders",
+            &[(&failing, "users"), (&warning, "users")],
+        )?;
+        assert_eq!(results[0].failing_rows, 1);
+        assert!(results[0].fails_task());
+        assert_eq!(
+            results[0].describe_failure(),
+            "Relationship 'user_id' -> 'users.id' failed: 1 orphaned row(s)"
+        );
+        assert!(!results[1].passed);
+        assert!(!results[1].fails_task());
+
+        Ok(())
+    }
+}
@@ -1,11 +1,29 @@
 use crate::core::config::{
     Config,
-    project::{DatabaseType, RemoteDatabaseConfig, S3AuthMethod, S3Config, StorageConfig},
+    project::{
+        DatabaseType, EngineConfig, ExtensionsConfig, GcsConfig, HttpConfig, RemoteDatabaseConfig,
+        S3AuthMethod, S3Config, StorageConfig,
+    },
 };
 use anyhow::{Context, Result};
-use duckdb::{DuckdbConnectionManager, types::Value};
-use r2d2::Pool;
-use std::{collections::HashMap, path::Path, sync::Arc};
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat};
+use duckdb::{
+    DuckdbConnectionManager,
+    types::{TimeUnit, Value},
+};
+use r2d2::{CustomizeConnection, Pool, PooledConnection};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use utoipa::ToSchema;
 
 use super::config::project::DatabaseConfig;
 
@@ -20,27 +38,417 @@ pub enum CatalogConfig {
     },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    fn copy_options(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "FORMAT csv, HEADER",
+            ExportFormat::Parquet => "FORMAT parquet",
+            ExportFormat::Json => "FORMAT json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct TypedResult {
+    pub columns: Vec<String>,
+    pub types: Vec<String>,
+    pub data: HashMap<String, Vec<serde_json::Value>>,
+}
+
+impl TypedResult {
+    pub fn row_count(&self) -> usize {
+        self.data.values().next().map(|v| v.len()).unwrap_or(0)
+    }
+}
+
+// SQL with `?` placeholders and the values bound to them. `inlined` carries the
+// same values as literals, for statements such as DESCRIBE that cannot bind.
+#[derive(Debug, Clone)]
+pub struct BoundSql {
+    pub sql: String,
+    pub params: Vec<Value>,
+    pub inlined: String,
+}
+
+impl BoundSql {
+    pub fn plain(sql: &str) -> Self {
+        Self {
+            sql: sql.to_string(),
+            params: Vec::new(),
+            inlined: sql.to_string(),
+        }
+    }
+
+    fn map(&self, f: impl Fn(&str) -> String) -> Self {
+        Self {
+            sql: f(&self.sql),
+            params: self.params.clone(),
+            inlined: f(&self.inlined),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DuckLakeCache {
+    cached: tokio::sync::Mutex<Option<CachedDuckLake>>,
+    builds: AtomicUsize,
+}
+
+struct CachedDuckLake {
+    database: DatabaseConfig,
+    storage: StorageConfig,
+    engine: EngineConfig,
+    extensions: ExtensionsConfig,
+    // A failed build is kept as well, so requests fail fast until the settings change
+    // or `reconnect` is called instead of each repeating a slow ATTACH.
+    ducklake: Result<Arc<DuckLake>, CatalogUnavailable>,
+}
+
+impl DuckLakeCache {
+    pub async fn get(&self, config: &tokio::sync::Mutex<Config>) -> Result<Arc<DuckLake>> {
+        // Only hold the config lock long enough to copy the settings; building a
+        // DuckLake (extensions, ATTACH) can take a while.
+        let (database, storage, engine, extensions) = {
+            let config = config.lock().await;
+            (
+                config.project.database.clone(),
+                config.project.storage.clone(),
+                config.project.engine.clone(),
+                config.project.extensions.clone(),
+            )
+        };
+
+        let mut cached = self.cached.lock().await;
+        if let Some(entry) = cached.as_ref()
+            && entry.database == database
+            && entry.storage == storage
+            && entry.engine == engine
+            && entry.extensions == extensions
+        {
+            return entry.ducklake.clone().map_err(Into::into);
+        }
+
+        let ducklake = DuckLake::from_settings(&database, &storage, &engine, &extensions)
+            .await
+            .map(Arc::new)
+            .map_err(|e| CatalogUnavailable::new(&database, &e));
+        self.builds.fetch_add(1, Ordering::Relaxed);
+        *cached = Some(CachedDuckLake {
+            database,
+            storage,
+            engine,
+            extensions,
+            ducklake: ducklake.clone(),
+        });
+
+        ducklake.map_err(Into::into)
+    }
+
+    // Forgets a failed build and tries again.
+    pub async fn reconnect(&self, config: &tokio::sync::Mutex<Config>) -> Result<Arc<DuckLake>> {
+        self.invalidate().await;
+        self.get(config).await
+    }
+
+    // Why the last build failed, while the server is running without a catalog.
+    pub async fn failure(&self) -> Option<CatalogUnavailable> {
+        self.cached
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|entry| entry.ducklake.clone().err())
+    }
+
+    // The DuckLake built so far, without building one on demand.
+    pub async fn current(&self) -> Option<Arc<DuckLake>> {
+        self.cached
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|entry| entry.ducklake.clone().ok())
+    }
+
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    pub fn builds(&self) -> usize {
+        self.builds.load(Ordering::Relaxed)
+    }
+}
+
+// A point in the catalog history: a snapshot id or a timestamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Snapshot {
+    Version(u64),
+    AsOf(String),
+}
+
+impl Snapshot {
+    fn attach_option(&self) -> String {
+        match self {
+            Snapshot::Version(version) => format!(", SNAPSHOT_VERSION {version}"),
+            Snapshot::AsOf(timestamp) => format!(", SNAPSHOT_TIME {}", quote_literal(timestamp)),
+        }
+    }
+
+    pub fn at_clause(&self) -> String {
+        match self {
+            Snapshot::Version(version) => format!("AT (VERSION => {version})"),
+            Snapshot::AsOf(timestamp) => {
+                format!(
+                    "AT (TIMESTAMP => CAST({} AS TIMESTAMPTZ))",
+                    quote_literal(timestamp)
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub snapshot_id: u64,
+    pub snapshot_time: String,
+}
+
 #[derive(Clone)]
 pub struct DuckLake {
     catalog_config: CatalogConfig,
     storage_config: StorageConfig,
+    extensions: ExtensionsConfig,
     pool: Arc<Pool<DuckdbConnectionManager>>,
     _temp_dir: Arc<tempfile::TempDir>,
 }
 
+// Returned when every pooled connection stays busy for the whole acquisition timeout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolExhausted {
+    pub pool_size: u32,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for PoolExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Server busy: all {} DuckDB connections stayed in use for {}s, try again later",
+            self.pool_size,
+            self.timeout.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for PoolExhausted {}
+
+// Returned while the DuckLake cannot be built, typically because the catalog database
+// is unreachable. `host` is only set for MySQL and PostgreSQL catalogs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogUnavailable {
+    pub catalog: String,
+    pub host: Option<String>,
+    pub error: String,
+}
+
+impl CatalogUnavailable {
+    fn new(database: &DatabaseConfig, error: &anyhow::Error) -> Self {
+        let catalog = match database.ty {
+            DatabaseType::Sqlite => "sqlite",
+            DatabaseType::Mysql => "mysql",
+            DatabaseType::Postgresql => "postgresql",
+        };
+        let host = match database.ty {
+            DatabaseType::Sqlite => None,
+            DatabaseType::Mysql | DatabaseType::Postgresql => Some(format!(
+                "{}:{}",
+                database.host.as_deref().unwrap_or("localhost"),
+                database
+                    .port
+                    .map_or_else(|| "default port".to_string(), |port| port.to_string())
+            )),
+        };
+
+        Self {
+            catalog: catalog.to_string(),
+            host,
+            // The innermost cause says what went wrong; the outer contexts only
+            // repeat which step it was.
+            error: match error.downcast_ref::<MissingExtension>() {
+                Some(missing) => missing.to_string(),
+                None => error.root_cause().to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for CatalogUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The {} catalog", self.catalog)?;
+        if let Some(host) = &self.host {
+            write!(f, " at {host}")?;
+        }
+        write!(
+            f,
+            " is unavailable: {}. Fix the database settings or retry with POST /api/project/reconnect",
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for CatalogUnavailable {}
+
+// Returned in offline mode when an extension is not in the extension directory, so
+// LOAD fails and nothing may be downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingExtension {
+    pub name: String,
+    pub directory: Option<String>,
+}
+
+impl fmt::Display for MissingExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let directory = self
+            .directory
+            .as_deref()
+            .unwrap_or("DuckDB's default directory (~/.duckdb/extensions)");
+        write!(
+            f,
+            "DuckDB extension '{}' is not installed in {directory}, and offline mode does not download it; copy {}.duckdb_extension there",
+            self.name, self.name
+        )
+    }
+}
+
+impl std::error::Error for MissingExtension {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub installed: bool,
+    pub loaded: bool,
+    pub version: Option<String>,
+    pub install_path: Option<String>,
+}
+
+// Returned when a query ran past its timeout and DuckDB was told to stop it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTimeout {
+    pub timeout: Duration,
+}
+
+impl fmt::Display for QueryTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Query timed out after {}s and was cancelled",
+            self.timeout.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for QueryTimeout {}
+
+// Interrupts the running query when dropped, unless the query finished first. The
+// handler future is dropped when the client disconnects, so this also covers that.
+struct InterruptOnDrop(Option<Box<dyn FnOnce() + Send>>);
+
+impl InterruptOnDrop {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for InterruptOnDrop {
+    fn drop(&mut self) {
+        if let Some(interrupt) = self.0.take() {
+            interrupt();
+        }
+    }
+}
+
+// Applies the engine settings to every connection the pool opens.
+#[derive(Debug)]
+struct EngineSettings {
+    memory_limit: Option<String>,
+    threads: Option<u32>,
+}
+
+impl EngineSettings {
+    fn sql(&self) -> String {
+        let mut settings = Vec::new();
+        if let Some(memory_limit) = &self.memory_limit {
+            settings.push(format!(
+                "SET memory_limit = {};",
+                quote_literal(memory_limit)
+            ));
+        }
+        if let Some(threads) = self.threads {
+            settings.push(format!("SET threads = {threads};"));
+        }
+        settings.join(" ")
+    }
+}
+
+impl CustomizeConnection<duckdb::Connection, duckdb::Error> for EngineSettings {
+    fn on_acquire(&self, connection: &mut duckdb::Connection) -> Result<(), duckdb::Error> {
+        let sql = self.sql();
+        if sql.is_empty() {
+            return Ok(());
+        }
+        connection.execute_batch(&sql)
+    }
+}
+
 impl DuckLake {
     pub async fn new(catalog_config: CatalogConfig, storage_config: StorageConfig) -> Result<Self> {
+        Self::with_engine(
+            catalog_config,
+            storage_config,
+            &EngineConfig::default(),
+            &ExtensionsConfig::default(),
+        )
+        .await
+    }
+
+    pub async fn with_engine(
+        catalog_config: CatalogConfig,
+        storage_config: StorageConfig,
+        engine: &EngineConfig,
+        extensions: &ExtensionsConfig,
+    ) -> Result<Self> {
         let temp_dir = tempfile::tempdir()?;
         let temp_db_path = temp_dir.path().join("shared.db");
 
         let manager = DuckdbConnectionManager::file(&temp_db_path)?;
         let pool = Pool::builder()
-            .max_size(num_cpus::get() as u32)
-            .build(manager)?;
+            .max_size(engine.pool_size())
+            .connection_timeout(engine.acquire_timeout())
+            .connection_customizer(Box::new(EngineSettings {
+                memory_limit: engine.memory_limit.clone(),
+                threads: engine.threads,
+            }))
+            .build(manager)
+            .context("Failed to open DuckDB connections with the engine settings")?;
 
         let instance = Self {
             catalog_config,
             storage_config,
+            extensions: extensions.clone(),
             pool: Arc::new(pool),
             _temp_dir: Arc::new(temp_dir),
         };
@@ -49,26 +457,65 @@ impl DuckLake {
         Ok(instance)
     }
 
+    // (open connections, idle connections) of the DuckDB pool.
+    pub fn pool_state(&self) -> (u32, u32) {
+        let state = self.pool.state();
+        (state.connections, state.idle_connections)
+    }
+
+    pub fn pool_size(&self) -> u32 {
+        self.pool.max_size()
+    }
+
+    // Waits up to the acquisition timeout for a free connection. Running out is
+    // reported as `PoolExhausted` so the API can answer 503 instead of 500.
+    pub fn connection(&self) -> Result<PooledConnection<DuckdbConnectionManager>> {
+        self.pool.get().map_err(|e| {
+            let state = self.pool.state();
+            if state.idle_connections == 0 && state.connections == self.pool.max_size() {
+                anyhow::Error::new(PoolExhausted {
+                    pool_size: self.pool.max_size(),
+                    timeout: self.pool.connection_timeout(),
+                })
+            } else {
+                anyhow::Error::new(e).context("Failed to get connection from pool")
+            }
+        })
+    }
+
     pub async fn from_config(config: &Config) -> Result<DuckLake> {
-        let catalog_config = match &config.project.database.ty {
+        Self::from_settings(
+            &config.project.database,
+            &config.project.storage,
+            &config.project.engine,
+            &config.project.extensions,
+        )
+        .await
+    }
+
+    pub async fn from_settings(
+        database: &DatabaseConfig,
+        storage: &StorageConfig,
+        engine: &EngineConfig,
+        extensions: &ExtensionsConfig,
+    ) -> Result<DuckLake> {
+        let catalog_config = match &database.ty {
             DatabaseType::Sqlite => CatalogConfig::Sqlite {
-                path: config
-                    .project
-                    .database
+                path: database
                     .path
                     .as_ref()
                     .expect("SQLite database path is required")
                     .clone(),
             },
             DatabaseType::Mysql => {
-                let remote_config = build_remote_database_config(&config.project.database)?;
+                let remote_config = build_remote_database_config(database)?;
                 CatalogConfig::RemoteDatabase {
                     db_type: DatabaseType::Mysql,
                     config: remote_config,
                 }
             }
             DatabaseType::Postgresql => {
-                let remote_config = build_remote_database_config(&config.project.database)?;
+                let remote_config = build_remote_database_config(database)?;
                 CatalogConfig::RemoteDatabase {
                     db_type: DatabaseType::Postgresql,
                     config: remote_config,
@@ -76,7 +523,7 @@ impl DuckLake {
             }
         };
 
-        DuckLake::new(catalog_config, config.project.storage.clone()).await
+        DuckLake::with_engine(catalog_config, storage.clone(), engine, extensions).await
     }
 
     async fn initialize(&self) -> Result<()> {
@@ -86,9 +533,71 @@ impl DuckLake {
     }
 
     async fn initialize_base(&self) -> Result<()> {
-        self.execute_batch("INSTALL ducklake; LOAD ducklake;")
-            .context("Failed to install and load extensions")?;
-        Ok(())
+        // Both settings belong to the database, so setting them once covers every
+        // pooled connection.
+        let mut settings = Vec::new();
+        if let Some(directory) = &self.extensions.directory {
+            settings.push(format!(
+                "SET extension_directory = {};",
+                quote_literal(directory)
+            ));
+        }
+        if let Some(repository) = &self.extensions.repository {
+            settings.push(format!(
+                "SET custom_extension_repository = {};",
+                quote_literal(repository)
+            ));
+        }
+        if !settings.is_empty() {
+            self.execute_batch(&settings.join(" "))
+                .context("Failed to apply the extension settings")?;
+        }
+
+        self.load_extension("ducklake")
+    }
+
+    // Installs and loads a DuckDB extension. In offline mode the extension has to be
+    // in the extension directory already and is only loaded.
+    pub fn load_extension(&self, name: &str) -> Result<()> {
+        if self.extensions.offline {
+            return self.execute_batch(&format!("LOAD {name};")).map_err(|e| {
+                e.context(MissingExtension {
+                    name: name.to_string(),
+                    directory: self.extensions.directory.clone(),
+                })
+            });
+        }
+
+        let source = self
+            .extensions
+            .repository
+            .as_deref()
+            .unwrap_or("the default extension repository");
+        self.execute_batch(&format!("INSTALL {name}; LOAD {name};"))
+            .with_context(|| {
+                format!(
+                    "Failed to install and load the {name} extension from {source}; without network access, set extensions.directory and extensions.offline in project.yml"
+                )
+            })
+    }
+
+    // The extensions DuckDB knows of, from `duckdb_extensions()`.
+    pub fn list_extensions(&self) -> Result<Vec<ExtensionInfo>> {
+        let rows = self.query(
+            "SELECT extension_name, installed, loaded, COALESCE(extension_version, ''), COALESCE(install_path, '') FROM duckdb_extensions() ORDER BY extension_name",
+        )?;
+
+        let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        Ok(rows
+            .into_iter()
+            .map(|row| ExtensionInfo {
+                name: row[0].clone(),
+                installed: row[1] == "true",
+                loaded: row[2] == "true",
+                version: optional(&row[3]),
+                install_path: optional(&row[4]),
+            })
+            .collect())
     }
 
     pub async fn configure_s3_connection(&self, s3_config: &S3Config) -> Result<()> {
@@ -96,8 +605,54 @@ impl DuckLake {
             .await
     }
 
+    pub async fn configure_gcs_connection(&self, gcs_config: &GcsConfig) -> Result<()> {
+        self.ensure_s3_extensions().await?;
+
+        let secret_sql = Self::build_gcs_secret_sql(gcs_config, false)?;
+        self.execute_batch(&secret_sql)
+            .context("Failed to create GCS secret")
+    }
+
+    pub fn configure_http_connection(&self, http_config: &HttpConfig) -> Result<()> {
+        self.load_extension("httpfs")?;
+
+        let mut options = Vec::new();
+        if let Some(auth_token) = &http_config.auth_token {
+            options.push(format!(
+                "BEARER_TOKEN {}",
+                quote_literal(auth_token.plaintext()?)
+            ));
+        }
+        if let Some(headers) = http_config.headers.as_ref().filter(|h| !h.is_empty()) {
+            let mut entries: Vec<String> = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}", quote_literal(name), quote_literal(value)))
+                .collect();
+            entries.sort();
+            options.push(format!("EXTRA_HTTP_HEADERS MAP {{{}}}", entries.join(", ")));
+        }
+        if options.is_empty() {
+            return Ok(());
+        }
+
+        // Scope the secret to the connection's URL so that several HTTP connections
+        // with different credentials can coexist.
+        let secret_name: String = http_config
+            .base_url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let sql = format!(
+            "CREATE OR REPLACE SECRET http_{secret_name} (TYPE HTTP, {}, SCOPE {});",
+            options.join(", "),
+            quote_literal(&http_config.base_url)
+        );
+        self.execute_batch(&sql)
+            .context("Failed to create HTTP secret")
+    }
+
     async fn attach(&self) -> Result<()> {
-        let (extension_sql, attach_sql) = self.catalog_sql()?;
+        let (extension, attach_sql) = self.catalog_sql()?;
 
         match &self.storage_config {
             StorageConfig::LocalFile { path } => {
@@ -107,10 +662,15 @@ impl DuckLake {
             StorageConfig::S3(_) => {
                 self.configure_s3_storage().await?;
             }
+            StorageConfig::Gcs(gcs_config) => {
+                self.ensure_s3_extensions().await?;
+                let secret_sql = Self::build_gcs_secret_sql(gcs_config, true)?;
+                self.execute_batch(&secret_sql)
+                    .context("Failed to create GCS secret")?;
+            }
         };
 
-        self.execute_batch(&extension_sql)
-            .context("Failed to install and load database extension")?;
+        self.load_extension(extension)?;
 
         self.execute_batch(&attach_sql)
             .context("Failed to attach DuckLake catalog")?;
@@ -118,67 +678,64 @@ impl DuckLake {
         Ok(())
     }
 
-    fn catalog_sql(&self) -> Result<(String, String)> {
-        match &self.catalog_config {
+    // The extension the catalog database needs and the statements attaching it.
+    fn catalog_sql(&self) -> Result<(&'static str, String)> {
+        let extension_name = match &self.catalog_config {
             CatalogConfig::Sqlite { path } => {
-                let catalog_path = Path::new(path);
-                if let Some(parent) = catalog_path.parent() {
+                if let Some(parent) = Path::new(path).parent() {
                     std::fs::create_dir_all(parent)
                         .context("Failed to create catalog directory")?;
                 }
+                "sqlite"
+            }
+            CatalogConfig::RemoteDatabase { db_type, .. } => match db_type {
+                DatabaseType::Mysql => "mysql",
+                DatabaseType::Postgresql => "postgres",
+                DatabaseType::Sqlite => {
+                    unreachable!("SQLite should not use RemoteDatabase catalog variant")
+                }
+            },
+        };
 
-                let data_path = self.get_storage_path();
+        let attach_sql = format!("{} USE db;", self.attach_sql("db", "")?);
 
-                let extension_sql = "INSTALL sqlite; LOAD sqlite;".to_string();
-                let attach_sql = format!(
-                    "ATTACH 'ducklake:sqlite:{path}' AS db (DATA_PATH '{data_path}'); USE db;"
-                );
+        Ok((extension_name, attach_sql))
+    }
 
-                Ok((extension_sql, attach_sql))
-            }
+    // `extra_options` is appended to the ATTACH option list, e.g. to pin a snapshot.
+    fn attach_sql(&self, alias: &str, extra_options: &str) -> Result<String> {
+        let data_path = self.get_storage_path();
+
+        match &self.catalog_config {
+            CatalogConfig::Sqlite { path } => Ok(format!(
+                "ATTACH 'ducklake:sqlite:{path}' AS {alias} (DATA_PATH '{data_path}'{extra_options});"
+            )),
             CatalogConfig::RemoteDatabase { db_type, config } => {
-                let data_path = self.get_storage_path();
-
-                let (extension_name, connection_string) = match db_type {
-                    DatabaseType::Mysql => {
-                        let extension = "mysql";
-                        let password = config.password.plaintext()?;
-                        let conn_str = format!(
-                            "ducklake:mysql:db={} host={} port={} user={} password={}",
-                            config.database, config.host, config.port, config.username, password
-                        );
-                        (extension, conn_str)
-                    }
-                    DatabaseType::Postgresql => {
-                        let extension = "postgres";
-                        let password = config.password.plaintext()?;
-                        let conn_str = format!(
-                            "ducklake:postgres:dbname={} host={} port={} user={} password={}",
-                            config.database, config.host, config.port, config.username, password
-                        );
-                        (extension, conn_str)
-                    }
+                let password = config.password.plaintext()?;
+                let connection_string = match db_type {
+                    DatabaseType::Mysql => format!(
+                        "ducklake:mysql:db={} host={} port={} user={} password={}",
+                        config.database, config.host, config.port, config.username, password
+                    ),
+                    DatabaseType::Postgresql => format!(
+                        "ducklake:postgres:dbname={} host={} port={} user={} password={}",
+                        config.database, config.host, config.port, config.username, password
+                    ),
                     DatabaseType::Sqlite => {
                         unreachable!("SQLite should not use RemoteDatabase catalog variant")
                     }
                 };
 
-                let extension_sql = format!("INSTALL {extension_name}; LOAD {extension_name};");
-                let attach_sql = format!(
-                    "ATTACH '{connection_string}' AS db (DATA_PATH '{data_path}', METADATA_SCHEMA '{}_metadata'); USE db;",
+                Ok(format!(
+                    "ATTACH '{connection_string}' AS {alias} (DATA_PATH '{data_path}', METADATA_SCHEMA '{}_metadata'{extra_options});",
                     config.database
-                );
-
-                Ok((extension_sql, attach_sql))
+                ))
             }
         }
     }
 
     pub fn execute_batch(&self, sql: &str) -> Result<()> {
-        let connection = self
-            .pool
-            .get()
-            .context("Failed to get connection from pool")?;
+        let connection = self.connection()?;
 
         connection
             .execute_batch(sql)
@@ -222,13 +779,69 @@ impl DuckLake {
         }
     }
 
+    pub fn to_json(value: Value) -> serde_json::Value {
+        use serde_json::{Number, Value as Json};
+
+        let float = |f: f64| Number::from_f64(f).map(Json::Number).unwrap_or(Json::Null);
+        let micros = |unit: TimeUnit, t: i64| match unit {
+            TimeUnit::Second => t * 1_000_000,
+            TimeUnit::Millisecond => t * 1_000,
+            TimeUnit::Microsecond => t,
+            TimeUnit::Nanosecond => t / 1_000,
+        };
+
+        match value {
+            Value::Null => Json::Null,
+            Value::Boolean(b) => Json::Bool(b),
+            Value::TinyInt(i) => Json::from(i),
+            Value::SmallInt(i) => Json::from(i),
+            Value::Int(i) => Json::from(i),
+            Value::BigInt(i) => Json::from(i),
+            Value::HugeInt(i) => i64::try_from(i)
+                .map(Json::from)
+                .unwrap_or_else(|_| Json::String(i.to_string())),
+            Value::UTinyInt(i) => Json::from(i),
+            Value::USmallInt(i) => Json::from(i),
+            Value::UInt(i) => Json::from(i),
+            Value::UBigInt(i) => Json::from(i),
+            Value::Float(f) => float(f64::from(f)),
+            Value::Double(f) => float(f),
+            Value::Decimal(d) => d
+                .to_string()
+                .parse::<f64>()
+                .map(float)
+                .unwrap_or_else(|_| Json::String(d.to_string())),
+            Value::Text(s) | Value::Enum(s) => Json::String(s),
+            Value::Date32(d) => NaiveDate::from_num_days_from_ce_opt(d + 719_163)
+                .map(|date| Json::String(date.format("%Y-%m-%d").to_string()))
+                .unwrap_or_else(|| Json::from(d)),
+            Value::Time64(unit, t) => {
+                let micros = micros(unit, t);
+                NaiveTime::from_num_seconds_from_midnight_opt(
+                    (micros / 1_000_000) as u32,
+                    (micros % 1_000_000) as u32 * 1_000,
+                )
+                .map(|time| Json::String(time.format("%H:%M:%S%.f").to_string()))
+                .unwrap_or_else(|| Json::from(t))
+            }
+            Value::Timestamp(unit, t) => DateTime::from_timestamp_micros(micros(unit, t))
+                .map(|ts| Json::String(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)))
+                .unwrap_or_else(|| Json::from(t)),
+            Value::List(values) | Value::Array(values) => {
+                Json::Array(values.into_iter().map(Self::to_json).collect())
+            }
+            other => Json::String(Self::to_string(other)),
+        }
+    }
+
     pub fn query(&self, sql: &str) -> Result<Vec<Vec<String>>> {
-        let connection = self
-            .pool
-            .get()
-            .context("Failed to get connection from pool")?;
+        self.query_params(sql, &[])
+    }
+
+    pub fn query_params(&self, sql: &str, params: &[Value]) -> Result<Vec<Vec<String>>> {
+        let connection = self.connection()?;
         let mut stmt = connection.prepare(sql)?;
-        let mut rows = stmt.query([])?;
+        let mut rows = stmt.query(duckdb::params_from_iter(params))?;
         let column_count = rows.as_ref().unwrap().column_count();
 
         let mut results = Vec::new();
@@ -245,10 +858,7 @@ impl DuckLake {
     }
 
     pub fn query_with_column_names(&self, sql: &str) -> Result<HashMap<String, Vec<String>>> {
-        let connection = self
-            .pool
-            .get()
-            .context("Failed to get connection from pool")?;
+        let connection = self.connection()?;
 
         let column_names = {
             let mut stmt = connection.prepare(sql)?;
@@ -276,12 +886,398 @@ impl DuckLake {
         Ok(data)
     }
 
+    pub fn query_typed(&self, sql: &str) -> Result<TypedResult> {
+        let connection = self.connection()?;
+        Self::query_typed_on(&connection, sql)
+    }
+
+    // Describes and runs the query on the same connection, so session state such as
+    // `USE` applies to both.
+    fn query_typed_on(connection: &duckdb::Connection, sql: &str) -> Result<TypedResult> {
+        Self::query_bound_on(connection, &BoundSql::plain(sql))
+    }
+
+    fn query_bound_on(connection: &duckdb::Connection, bound: &BoundSql) -> Result<TypedResult> {
+        let bound = bound.map(|sql| sql.trim().trim_end_matches(';').to_string());
+
+        let mut stmt = connection.prepare(&format!("DESCRIBE ({})", bound.inlined))?;
+        let mut rows = stmt.query([])?;
+        let mut columns: Vec<String> = Vec::new();
+        let mut types = Vec::new();
+        while let Some(row) = rows.next()? {
+            columns.push(unique_column_name(&columns, Self::to_string(row.get(0)?)));
+            types.push(Self::to_string(row.get(1)?));
+        }
+
+        let mut stmt = connection.prepare(&bound.sql)?;
+        let mut rows = stmt.query(duckdb::params_from_iter(&bound.params))?;
+
+        let mut data: HashMap<String, Vec<serde_json::Value>> = columns
+            .iter()
+            .map(|column| (column.clone(), Vec::new()))
+            .collect();
+
+        while let Some(row) = rows.next()? {
+            for (i, column_name) in columns.iter().enumerate() {
+                let value = Self::to_json(row.get(i)?);
+                data.get_mut(column_name).unwrap().push(value);
+            }
+        }
+
+        Ok(TypedResult {
+            columns,
+            types,
+            data,
+        })
+    }
+
+    pub fn query_paged(
+        &self,
+        sql: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(TypedResult, bool)> {
+        let connection = self.connection()?;
+        Self::query_paged_on(&connection, &BoundSql::plain(sql), limit, offset)
+    }
+
+    fn query_paged_on(
+        connection: &duckdb::Connection,
+        bound: &BoundSql,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(TypedResult, bool)> {
+        let paged = bound.map(|sql| {
+            let sql = sql.trim().trim_end_matches(';');
+            format!(
+                "SELECT * FROM ({sql}) AS paged LIMIT {} OFFSET {offset}",
+                limit.saturating_add(1)
+            )
+        });
+
+        let mut result = Self::query_bound_on(connection, &paged)?;
+
+        let truncated = result.row_count() > limit;
+        if truncated {
+            for values in result.data.values_mut() {
+                values.truncate(limit);
+            }
+        }
+
+        Ok((result, truncated))
+    }
+
+    // Runs `f` on a connection whose default catalog is a read-only attachment of the
+    // catalog pinned to `snapshot`.
+    fn with_snapshot<T>(
+        &self,
+        connection: &duckdb::Connection,
+        snapshot: &Snapshot,
+        f: impl FnOnce(&duckdb::Connection) -> Result<T>,
+    ) -> Result<T> {
+        let alias = Self::generate_temp_table_name("db_snapshot");
+        let attach_sql = self.attach_sql(&alias, &snapshot.attach_option())?;
+        connection
+            .execute_batch(&attach_sql)
+            .context("Failed to attach DuckLake snapshot")?;
+
+        let result = connection
+            .execute_batch(&format!("USE {alias};"))
+            .map_err(anyhow::Error::from)
+            .and_then(|_| {
+                let result = f(connection);
+                connection.execute_batch("USE db;")?;
+                result
+            });
+
+        let _ = connection.execute_batch(&format!("DETACH {alias};"));
+        result
+    }
+
+    // Runs `f` on a pooled connection from a blocking task. If `timeout` elapses, or
+    // the caller stops waiting, DuckDB is interrupted and the connection goes back to
+    // the pool once the query has unwound.
+    pub async fn run_interruptible<T: Send + 'static>(
+        &self,
+        timeout: Duration,
+        f: impl FnOnce(&duckdb::Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let connection = self.connection()?;
+        let handle = connection.interrupt_handle();
+        let guard = InterruptOnDrop(Some(Box::new(move || handle.interrupt())));
+        let task = tokio::task::spawn_blocking(move || f(&connection));
+
+        match tokio::time::timeout(timeout, task).await {
+            Ok(result) => {
+                guard.disarm();
+                result.context("Query task failed")?
+            }
+            Err(_) => Err(anyhow::Error::new(QueryTimeout { timeout })),
+        }
+    }
+
+    pub async fn query_typed_with_timeout(
+        &self,
+        sql: &str,
+        snapshot: Option<&Snapshot>,
+        timeout: Duration,
+    ) -> Result<TypedResult> {
+        self.query_bound_with_timeout(&BoundSql::plain(sql), snapshot, timeout)
+            .await
+    }
+
+    pub async fn query_bound_with_timeout(
+        &self,
+        bound: &BoundSql,
+        snapshot: Option<&Snapshot>,
+        timeout: Duration,
+    ) -> Result<TypedResult> {
+        let ducklake = self.clone();
+        let bound = bound.clone();
+        let snapshot = snapshot.cloned();
+        self.run_interruptible(timeout, move |connection| match &snapshot {
+            Some(snapshot) => ducklake.with_snapshot(connection, snapshot, |connection| {
+                Self::query_bound_on(connection, &bound)
+            }),
+            None => Self::query_bound_on(connection, &bound),
+        })
+        .await
+    }
+
+    pub async fn query_paged_with_timeout(
+        &self,
+        sql: &str,
+        limit: usize,
+        offset: usize,
+        snapshot: Option<&Snapshot>,
+        timeout: Duration,
+    ) -> Result<(TypedResult, bool)> {
+        self.query_bound_paged_with_timeout(&BoundSql::plain(sql), limit, offset, snapshot, timeout)
+            .await
+    }
+
+    pub async fn query_bound_paged_with_timeout(
+        &self,
+        bound: &BoundSql,
+        limit: usize,
+        offset: usize,
+        snapshot: Option<&Snapshot>,
+        timeout: Duration,
+    ) -> Result<(TypedResult, bool)> {
+        let ducklake = self.clone();
+        let bound = bound.clone();
+        let snapshot = snapshot.cloned();
+        self.run_interruptible(timeout, move |connection| match &snapshot {
+            Some(snapshot) => ducklake.with_snapshot(connection, snapshot, |connection| {
+                Self::query_paged_on(connection, &bound, limit, offset)
+            }),
+            None => Self::query_paged_on(connection, &bound, limit, offset),
+        })
+        .await
+    }
+
+    pub fn list_snapshots(&self, table_name: &str) -> Result<Vec<SnapshotInfo>> {
+        let table_ids = self.query_params(
+            "SELECT CAST(table_id AS VARCHAR) FROM ducklake_table_info('db') WHERE table_name = ?",
+            &[Value::Text(table_name.to_string())],
+        );
+        let table_ids: Vec<Value> = table_ids
+            .map_err(snapshot_unsupported)?
+            .into_iter()
+            .map(|row| Value::Text(row[0].clone()))
+            .collect();
+
+        // `changes` maps the kind of change to the tables it touched, named either by
+        // qualified name or by table id.
+        let mut names = vec![
+            Value::Text(table_name.to_string()),
+            Value::Text(format!("main.{table_name}")),
+        ];
+        names.extend(table_ids);
+        let placeholders = vec!["?"; names.len()].join(", ");
+
+        let results = self
+            .query_params(
+                &format!(
+                    "SELECT snapshot_id, CAST(snapshot_time AS VARCHAR) FROM ducklake_snapshots('db') WHERE len(list_intersect(flatten(map_values(changes)), [{placeholders}])) > 0 ORDER BY snapshot_id"
+                ),
+                &names,
+            )
+            .map_err(snapshot_unsupported)?;
+
+        Ok(results
+            .into_iter()
+            .filter_map(|row| {
+                Some(SnapshotInfo {
+                    snapshot_id: row.first()?.parse().ok()?,
+                    snapshot_time: row.get(1)?.clone(),
+                })
+            })
+            .collect())
+    }
+
+    pub fn rollback_table(&self, table_name: &str, snapshot: &Snapshot) -> Result<()> {
+        let table = format!("db.main.{}", quote_ident(table_name));
+        let sql = format!(
+            "CREATE OR REPLACE TABLE {table} AS SELECT * FROM {table} {};",
+            snapshot.at_clause()
+        );
+        self.execute_batch(&sql)
+            .with_context(|| format!("Failed to roll back table '{table_name}'"))
+    }
+
     pub fn create_table_from_query(&self, table_name: &str, query: &str) -> Result<()> {
         let sql = format!("CREATE OR REPLACE TABLE {table_name} AS ({query});");
         self.execute_batch(&sql)
             .with_context(|| format!("Failed to create table '{table_name}' from query: '{query}'"))
     }
 
+    pub fn insert_from_query(&self, table_name: &str, query: &str) -> Result<()> {
+        let sql = format!("INSERT INTO {table_name} BY NAME ({query});");
+        self.execute_batch(&sql).with_context(|| {
+            format!("Failed to insert into table '{table_name}' from query: '{query}'")
+        })
+    }
+
+    // Replaces the rows of `table_name` that share a `unique_key` with a row of `query`,
+    // in one transaction. Rows whose `deleted_flag` is true only delete their match.
+    pub fn merge_from_query(
+        &self,
+        table_name: &str,
+        query: &str,
+        unique_key: &[String],
+        deleted_flag: Option<&str>,
+    ) -> Result<()> {
+        let staging = Self::generate_temp_table_name("merge");
+        let table = quote_ident(table_name);
+        let matches = unique_key
+            .iter()
+            .map(|key| {
+                let key = quote_ident(key);
+                format!("{table}.{key} IS NOT DISTINCT FROM staged.{key}")
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let kept = match deleted_flag {
+            Some(flag) => format!(
+                " WHERE NOT coalesce(CAST({} AS BOOLEAN), false)",
+                quote_ident(flag)
+            ),
+            None => String::new(),
+        };
+
+        // Staged first so the source is read outside the transaction, and the
+        // transaction only ever touches the lake.
+        let connection = self.connection()?;
+        connection
+            .execute_batch(&format!("CREATE TEMP TABLE {staging} AS ({query});"))
+            .with_context(|| format!("Failed to stage rows from query: '{query}'"))?;
+        let merged = connection.execute_batch(&format!(
+            "BEGIN TRANSACTION;
+             DELETE FROM {table} WHERE EXISTS (SELECT 1 FROM {staging} AS staged WHERE {matches});
+             INSERT INTO {table} BY NAME SELECT * FROM {staging}{kept};
+             COMMIT;"
+        ));
+        if merged.is_err() {
+            let _ = connection.execute_batch("ROLLBACK;");
+        }
+        let dropped = connection.execute_batch(&format!("DROP TABLE IF EXISTS {staging};"));
+
+        merged.with_context(|| format!("Failed to merge into table '{table_name}'"))?;
+        dropped.with_context(|| format!("Failed to drop temporary table: {staging}"))
+    }
+
+    // Runs `f` on a single pooled connection inside a transaction, committing when it
+    // succeeds and rolling back otherwise. Separate `execute_batch` calls may land on
+    // different connections, so they can't share a transaction.
+    pub fn with_transaction<T>(
+        &self,
+        f: impl FnOnce(&duckdb::Connection) -> Result<T>,
+    ) -> Result<T> {
+        let connection = self.connection()?;
+        connection
+            .execute_batch("BEGIN TRANSACTION;")
+            .context("Failed to begin transaction")?;
+        let result = f(&connection).and_then(|value| {
+            connection
+                .execute_batch("COMMIT;")
+                .context("Failed to commit transaction")?;
+            Ok(value)
+        });
+        if result.is_err() {
+            let _ = connection.execute_batch("ROLLBACK;");
+        }
+        result
+    }
+
+    // Builds `query` into a staging table and swaps it in for `table_name`, so readers
+    // see either the previous table or the complete new one.
+    pub fn replace_table_from_query(&self, table_name: &str, query: &str) -> Result<()> {
+        self.replace_table_with(table_name, |staging| {
+            self.create_table_from_query(staging, query)
+        })
+    }
+
+    // `build` fills the staging table it is given; once it succeeds the staging table
+    // is renamed over `table_name` in one transaction. The staging table is dropped on
+    // any failure, leaving `table_name` untouched.
+    pub fn replace_table_with(
+        &self,
+        table_name: &str,
+        build: impl FnOnce(&str) -> Result<()>,
+    ) -> Result<()> {
+        let staging = Self::generate_temp_table_name("staging");
+        let result = build(&staging).and_then(|()| self.swap_table(&staging, table_name));
+        if result.is_err() {
+            let _ = self.drop_temp_table(&staging);
+        }
+        result
+    }
+
+    fn swap_table(&self, staging: &str, table_name: &str) -> Result<()> {
+        let drop = match self.relation_type(table_name)?.as_deref() {
+            Some("VIEW") => format!("DROP VIEW {};", quote_ident(table_name)),
+            Some(_) => format!("DROP TABLE {};", quote_ident(table_name)),
+            None => String::new(),
+        };
+        self.with_transaction(|connection| {
+            connection.execute_batch(&format!(
+                "{drop} ALTER TABLE {staging} RENAME TO {};",
+                quote_ident(table_name)
+            ))?;
+            Ok(())
+        })
+        .with_context(|| format!("Failed to replace table '{table_name}'"))
+    }
+
+    pub fn relation_type(&self, table_name: &str) -> Result<Option<String>> {
+        let result = self.query_params(
+            "SELECT table_type FROM information_schema.tables WHERE table_catalog = 'db' AND table_schema = 'main' AND table_name = ?",
+            &[Value::Text(table_name.to_string())],
+        )?;
+        Ok(result.into_iter().next().map(|row| row[0].clone()))
+    }
+
+    pub fn export_query(&self, query: &str, format: ExportFormat, path: &Path) -> Result<()> {
+        self.execute_batch(&export_sql(query, format, path))
+            .with_context(|| format!("Failed to export query result to {}", path.display()))
+    }
+
+    pub async fn export_query_with_timeout(
+        &self,
+        query: &str,
+        format: ExportFormat,
+        path: &Path,
+        timeout: Duration,
+    ) -> Result<()> {
+        let sql = export_sql(query, format, path);
+        let context = format!("Failed to export query result to {}", path.display());
+        self.run_interruptible(timeout, move |connection| {
+            connection.execute_batch(&sql).context(context)
+        })
+        .await
+    }
+
     pub fn create_table(&self, table_name: &str, columns: &[(String, String)]) -> Result<()> {
         if columns.is_empty() {
             return Err(anyhow::anyhow!(
@@ -312,10 +1308,10 @@ impl DuckLake {
     }
 
     pub fn table_exists(&self, table_name: &str) -> Result<bool> {
-        let sql = format!(
-            "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = '{table_name}'"
-        );
-        let results = self.query(&sql)?;
+        let results = self.query_params(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = ?",
+            &[Value::Text(table_name.to_string())],
+        )?;
         let exists = if let Some(row) = results.first() {
             if let Some(count_str) = row.first() {
                 count_str.parse::<i64>().unwrap_or(0) > 0
@@ -328,11 +1324,50 @@ impl DuckLake {
         Ok(exists)
     }
 
+    // Tables of the DuckLake catalog itself, excluding temp tables and attached sources.
+    pub fn list_tables(&self) -> Result<Vec<String>> {
+        let results = self.query(
+            "SELECT table_name FROM information_schema.tables WHERE table_catalog = 'db' AND table_schema = 'main' AND table_type = 'BASE TABLE' ORDER BY table_name",
+        )?;
+        Ok(results.into_iter().map(|row| row[0].clone()).collect())
+    }
+
+    // Tables and views of the DuckLake catalog with their `information_schema` type.
+    pub fn list_relations(&self) -> Result<Vec<(String, String)>> {
+        let results = self.query(
+            "SELECT table_name, table_type FROM information_schema.tables WHERE table_catalog = 'db' AND table_schema = 'main' AND table_type IN ('BASE TABLE', 'VIEW') ORDER BY table_name",
+        )?;
+        Ok(results
+            .into_iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect())
+    }
+
+    // Drops a table or view of the catalog. Returns false when there was none.
+    pub fn drop_relation(&self, name: &str) -> Result<bool> {
+        let Some((_, table_type)) = self
+            .list_relations()?
+            .into_iter()
+            .find(|(relation, _)| relation == name)
+        else {
+            return Ok(false);
+        };
+
+        let kind = if table_type == "VIEW" {
+            "VIEW"
+        } else {
+            "TABLE"
+        };
+        self.execute_batch(&format!("DROP {kind} IF EXISTS {};", quote_ident(name)))
+            .with_context(|| format!("Failed to drop '{name}'"))?;
+        Ok(true)
+    }
+
     pub fn table_schema(&self, table_name: &str) -> Result<Vec<(String, String)>> {
-        let sql = format!(
-            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = '{table_name}' ORDER BY ordinal_position"
-        );
-        let results = self.query(&sql)?;
+        let results = self.query_params(
+            "SELECT column_name, data_type FROM information_schema.columns WHERE table_catalog = 'db' AND table_schema = 'main' AND table_name = ? ORDER BY ordinal_position",
+            &[Value::Text(table_name.to_string())],
+        )?;
         let columns = results
             .into_iter()
             .filter_map(|row| {
@@ -346,14 +1381,34 @@ impl DuckLake {
         Ok(columns)
     }
 
+    // Column names and types `query` would return, without running it.
+    pub fn query_schema(&self, query: &str) -> Result<Vec<(String, String)>> {
+        let results = self
+            .query(&format!("DESCRIBE {query}"))
+            .with_context(|| format!("Failed to describe query: '{query}'"))?;
+        Ok(results
+            .into_iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect())
+    }
+
+    pub fn add_column(&self, table_name: &str, column: &str, data_type: &str) -> Result<()> {
+        let sql = format!(
+            "ALTER TABLE {} ADD COLUMN {} {data_type};",
+            quote_ident(table_name),
+            quote_ident(column)
+        );
+        self.execute_batch(&sql)
+            .with_context(|| format!("Failed to add column '{column}' to '{table_name}'"))
+    }
+
     async fn configure_s3_storage(&self) -> Result<()> {
         if let StorageConfig::S3(s3_config) = &self.storage_config {
             self.ensure_s3_extensions().await?;
 
             match &s3_config.auth_method {
                 S3AuthMethod::CredentialChain => {
-                    self.execute_batch("INSTALL aws; LOAD aws;")
-                        .context("Failed to install and load aws extension for credential chain")?;
+                    self.load_extension("aws")?;
                 }
                 S3AuthMethod::Explicit => {}
             }
@@ -426,10 +1481,50 @@ impl DuckLake {
         Ok(sql)
     }
 
+    // GCS is read through httpfs' S3-compatible XML API using HMAC keys. Secrets are
+    // scoped to the bucket so that connections to different buckets don't collide.
+    fn build_gcs_secret_sql(gcs_config: &GcsConfig, if_not_exists: bool) -> Result<String> {
+        let secret_name: String = gcs_config
+            .bucket
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        let create_clause = if if_not_exists {
+            format!("CREATE SECRET IF NOT EXISTS gcs_{secret_name}")
+        } else {
+            format!("CREATE OR REPLACE SECRET gcs_{secret_name}")
+        };
+
+        let mut sql = format!(
+            "{create_clause} (
+                TYPE GCS,
+                KEY_ID {},
+                SECRET {},
+                SCOPE {}",
+            quote_literal(&gcs_config.hmac_key_id),
+            quote_literal(gcs_config.hmac_secret.plaintext()?),
+            quote_literal(&format!("gs://{}", gcs_config.bucket))
+        );
+
+        if let Some(endpoint) = &gcs_config.endpoint_url {
+            let clean_endpoint = Self::clean_endpoint_url(endpoint);
+            sql.push_str(&format!(
+                ",\n    ENDPOINT {}",
+                quote_literal(clean_endpoint)
+            ));
+
+            if Self::is_minio_endpoint(&gcs_config.endpoint_url) {
+                sql.push_str(",\n    URL_STYLE 'path',\n    USE_SSL false");
+            }
+        }
+
+        sql.push_str("\n);");
+        Ok(sql)
+    }
+
     async fn ensure_s3_extensions(&self) -> Result<()> {
-        self.execute_batch("INSTALL httpfs; LOAD httpfs;")
-            .context("Failed to install and load httpfs extension for S3")?;
-        Ok(())
+        self.load_extension("httpfs")
     }
 
     fn is_minio_endpoint(endpoint: &Option<String>) -> bool {
@@ -455,8 +1550,7 @@ impl DuckLake {
 
         match &s3_config.auth_method {
             S3AuthMethod::CredentialChain => {
-                self.execute_batch("INSTALL aws; LOAD aws;")
-                    .context("Failed to install and load aws extension for credential chain")?;
+                self.load_extension("aws")?;
             }
             S3AuthMethod::Explicit => {}
         }
@@ -468,14 +1562,62 @@ impl DuckLake {
         Ok(())
     }
 
+    // DuckLake attaches its metadata database as `__ducklake_metadata_<alias>`; remote
+    // catalogs keep the tables in the schema named at ATTACH.
+    pub fn metadata_relation(&self, table: &str) -> String {
+        let schema = match &self.catalog_config {
+            CatalogConfig::Sqlite { .. } => "main".to_string(),
+            CatalogConfig::RemoteDatabase { config, .. } => format!("{}_metadata", config.database),
+        };
+        format!(
+            "__ducklake_metadata_db.{}.{}",
+            quote_ident(&schema),
+            quote_ident(table)
+        )
+    }
+
     fn get_storage_path(&self) -> String {
         match &self.storage_config {
             StorageConfig::LocalFile { path } => path.clone(),
             StorageConfig::S3(s3_config) => format!("s3://{}/ducklake", s3_config.bucket),
+            StorageConfig::Gcs(gcs_config) => format!("gs://{}/ducklake", gcs_config.bucket),
         }
     }
 }
 
+// Results are keyed by column name, so a repeated name (`SELECT a.id, b.id`) gets a
+// suffix instead of mixing both columns into one.
+fn unique_column_name(columns: &[String], name: String) -> String {
+    if !columns.contains(&name) {
+        return name;
+    }
+    (1..)
+        .map(|n| format!("{name}_{n}"))
+        .find(|candidate| !columns.contains(candidate))
+        .unwrap()
+}
+
+fn export_sql(query: &str, format: ExportFormat, path: &Path) -> String {
+    let query = query.trim().trim_end_matches(';');
+    format!(
+        "COPY ({query}) TO '{}' ({});",
+        path.display(),
+        format.copy_options()
+    )
+}
+
+fn snapshot_unsupported(error: anyhow::Error) -> anyhow::Error {
+    error.context("Snapshots are not supported by this DuckLake catalog")
+}
+
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+pub fn quote_literal(literal: &str) -> String {
+    format!("'{}'", literal.replace('\'', "''"))
+}
+
 fn build_remote_database_config(config: &DatabaseConfig) -> Result<RemoteDatabaseConfig> {
     match config.ty {
         DatabaseType::Mysql | DatabaseType::Postgresql => {
@@ -516,7 +1658,11 @@ fn build_remote_database_config(config: &DatabaseConfig) -> Result<RemoteDatabas
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::{adapter::test_helpers::test_encrypted_field, config::project::S3Config};
+    use crate::core::{
+        adapter::test_helpers::{setup_test_ducklake, test_encrypted_field, test_lake_configs},
+        config::{project::S3Config, secret::SecretField},
+    };
+    use serde_json::json;
 
     #[tokio::test]
     async fn test_ducklake_localfile() {
@@ -546,6 +1692,291 @@ mod tests {
         assert_eq!(results[0], vec!["1", "Alice"]);
     }
 
+    #[tokio::test]
+    async fn test_ducklake_engine_settings() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let (catalog_config, storage_config) = test_lake_configs(tempdir.path());
+        let engine = EngineConfig {
+            pool_size: Some(1),
+            acquire_timeout_secs: Some(1),
+            memory_limit: Some("512MB".to_string()),
+            threads: Some(2),
+            ..Default::default()
+        };
+
+        let ducklake = DuckLake::with_engine(
+            catalog_config,
+            storage_config,
+            &engine,
+            &ExtensionsConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(ducklake.pool_size(), 1);
+        assert_eq!(
+            ducklake.query("SELECT current_setting('threads')").unwrap(),
+            vec![vec!["2"]]
+        );
+
+        let _held = ducklake.connection().unwrap();
+        let error = ducklake.query("SELECT 1").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<PoolExhausted>(),
+            Some(&PoolExhausted {
+                pool_size: 1,
+                timeout: Duration::from_secs(1),
+            })
+        );
+        assert!(error.to_string().starts_with("Server busy"));
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_offline_extensions() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let extensions = ducklake.list_extensions().unwrap();
+        assert!(
+            extensions
+                .iter()
+                .any(|extension| extension.name == "ducklake" && extension.loaded)
+        );
+
+        let directory = tempdir
+            .path()
+            .join("extensions")
+            .to_string_lossy()
+            .to_string();
+        let offline = DuckLake {
+            extensions: ExtensionsConfig {
+                repository: None,
+                directory: Some(directory.clone()),
+                offline: true,
+            },
+            ..(*ducklake).clone()
+        };
+        // Already loaded, so LOAD succeeds without touching the network.
+        offline.load_extension("ducklake").unwrap();
+
+        let error = offline.load_extension("no_such_extension").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<MissingExtension>(),
+            Some(&MissingExtension {
+                name: "no_such_extension".to_string(),
+                directory: Some(directory.clone()),
+            })
+        );
+        assert!(error.to_string().contains(&directory));
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_query_paged() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let sql = "SELECT range AS id FROM range(10) ORDER BY id;";
+
+        let (result, truncated) = ducklake.query_paged(sql, 3, 0).unwrap();
+        assert!(truncated);
+        assert_eq!(result.data["id"], vec![json!(0), json!(1), json!(2)]);
+
+        let (result, truncated) = ducklake.query_paged(sql, 3, 8).unwrap();
+        assert!(!truncated);
+        assert_eq!(result.data["id"], vec![json!(8), json!(9)]);
+
+        let (result, truncated) = ducklake.query_paged(sql, 10, 0).unwrap();
+        assert!(!truncated);
+        assert_eq!(result.row_count(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_query_typed() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let result = ducklake
+            .query_typed(
+                "SELECT
+                    42 AS int_col,
+                    1.5::DOUBLE AS float_col,
+                    true AS bool_col,
+                    NULL::INTEGER AS null_col,
+                    TIMESTAMP '2024-01-02 03:04:05' AS ts_col,
+                    DATE '2024-01-02' AS date_col,
+                    'Alice' AS text_col",
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.columns,
+            vec![
+                "int_col",
+                "float_col",
+                "bool_col",
+                "null_col",
+                "ts_col",
+                "date_col",
+                "text_col"
+            ]
+        );
+        assert_eq!(
+            result.types,
+            vec![
+                "INTEGER",
+                "DOUBLE",
+                "BOOLEAN",
+                "INTEGER",
+                "TIMESTAMP",
+                "DATE",
+                "VARCHAR"
+            ]
+        );
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(result.data["int_col"][0], json!(42));
+        assert_eq!(result.data["float_col"][0], json!(1.5));
+        assert_eq!(result.data["bool_col"][0], json!(true));
+        assert_eq!(result.data["null_col"][0], serde_json::Value::Null);
+        assert_eq!(result.data["ts_col"][0], json!("2024-01-02T03:04:05Z"));
+        assert_eq!(result.data["date_col"][0], json!("2024-01-02"));
+        assert_eq!(result.data["text_col"][0], json!("Alice"));
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_query_typed_duplicate_columns() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let result = ducklake
+            .query_typed("SELECT 1 AS id, 2 AS id, 3 AS id_1, 'x' AS id")
+            .unwrap();
+        assert_eq!(result.columns, vec!["id", "id_1", "id_1_1", "id_2"]);
+        assert_eq!(result.data["id"], vec![json!(1)]);
+        assert_eq!(result.data["id_1"], vec![json!(2)]);
+        assert_eq!(result.data["id_1_1"], vec![json!(3)]);
+        assert_eq!(result.data["id_2"], vec![json!("x")]);
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_table_name_with_quote() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let table_name = "o'brien \"orders\"";
+        ducklake
+            .create_table_from_query(&quote_ident(table_name), "SELECT 1 AS id, 'Alice' AS name")
+            .unwrap();
+
+        assert!(ducklake.table_exists(table_name).unwrap());
+        assert!(!ducklake.table_exists("o'brien").unwrap());
+        assert_eq!(
+            ducklake.table_schema(table_name).unwrap(),
+            vec![
+                ("id".to_string(), "INTEGER".to_string()),
+                ("name".to_string(), "VARCHAR".to_string()),
+            ]
+        );
+
+        let results = ducklake
+            .query_params(
+                &format!("SELECT name FROM {} WHERE id = ?", quote_ident(table_name)),
+                &[Value::Int(1)],
+            )
+            .unwrap();
+        assert_eq!(results, vec![vec!["Alice".to_string()]]);
+    }
+
+    #[test]
+    fn test_quote_helpers() {
+        assert_eq!(quote_ident("users"), "\"users\"");
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+        assert_eq!(quote_literal("it's"), "'it''s'");
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_cache_reuses_instance() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().to_path_buf());
+        config.project.database.path = Some(
+            tempdir
+                .path()
+                .join("catalog.sqlite")
+                .to_string_lossy()
+                .to_string(),
+        );
+        config.project.storage = StorageConfig::LocalFile {
+            path: tempdir.path().join("storage").to_string_lossy().to_string(),
+        };
+        let config = tokio::sync::Mutex::new(config);
+
+        let cache = DuckLakeCache::default();
+        let first = cache.get(&config).await.unwrap();
+        let second = cache.get(&config).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.builds(), 1);
+
+        config.lock().await.project.storage = StorageConfig::LocalFile {
+            path: tempdir.path().join("other").to_string_lossy().to_string(),
+        };
+        let third = cache.get(&config).await.unwrap();
+        assert!(!Arc::ptr_eq(&second, &third));
+        assert_eq!(cache.builds(), 2);
+
+        cache.invalidate().await;
+        cache.get(&config).await.unwrap();
+        assert_eq!(cache.builds(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_transactions_and_table_swap() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+        ducklake
+            .create_table_from_query("users", "SELECT 1 AS id")
+            .unwrap();
+
+        let result = ducklake.with_transaction(|connection| -> Result<()> {
+            connection.execute_batch("INSERT INTO users VALUES (2);")?;
+            Err(anyhow::anyhow!("injected failure"))
+        });
+        assert!(result.is_err());
+        let rows = ducklake.query("SELECT COUNT(*) FROM users").unwrap();
+        assert_eq!(rows[0][0], "1");
+
+        // A failed build leaves the old table and no staging table behind.
+        let result = ducklake.replace_table_with("users", |staging| {
+            ducklake.create_table_from_query(staging, "SELECT 5 AS id")?;
+            Err(anyhow::anyhow!("injected failure"))
+        });
+        assert!(result.is_err());
+        let rows = ducklake.query("SELECT id FROM users").unwrap();
+        assert_eq!(rows, vec![vec!["1".to_string()]]);
+        assert_eq!(ducklake.list_tables().unwrap(), vec!["users".to_string()]);
+
+        ducklake
+            .replace_table_from_query("users", "SELECT 5 AS id")
+            .unwrap();
+        let rows = ducklake.query("SELECT id FROM users").unwrap();
+        assert_eq!(rows, vec![vec!["5".to_string()]]);
+        assert_eq!(ducklake.list_tables().unwrap(), vec!["users".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_ducklake_export_query() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let ducklake = setup_test_ducklake(tempdir.path()).await.unwrap();
+
+        let path = tempdir.path().join("export.csv");
+        ducklake
+            .export_query("SELECT 1 AS id, 'Alice' AS name;", ExportFormat::Csv, &path)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec!["id,name", "1,Alice"]
+        );
+    }
+
     #[tokio::test]
     async fn test_ducklake_s3() {
         use aws_config::Region;
@@ -687,6 +2118,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_gcs_secret_sql() {
+        let mut gcs_config = GcsConfig {
+            bucket: "analytics-lake".to_string(),
+            hmac_key_id: "GOOG1EXAMPLE".to_string(),
+            hmac_secret: SecretField::PlainText {
+                value: "it's-secret".to_string(),
+            },
+            endpoint_url: None,
+        };
+
+        let sql = DuckLake::build_gcs_secret_sql(&gcs_config, false).unwrap();
+        assert!(sql.contains("CREATE OR REPLACE SECRET gcs_analytics_lake"));
+        assert!(sql.contains("TYPE GCS"));
+        assert!(sql.contains("SECRET 'it''s-secret'"));
+        assert!(sql.contains("SCOPE 'gs://analytics-lake'"));
+        assert!(!sql.contains("ENDPOINT"));
+
+        gcs_config.endpoint_url = Some("http://localhost:4443".to_string());
+        let sql = DuckLake::build_gcs_secret_sql(&gcs_config, true).unwrap();
+        assert!(sql.contains("CREATE SECRET IF NOT EXISTS gcs_analytics_lake"));
+        assert!(sql.contains("ENDPOINT 'localhost:4443'"));
+        assert!(sql.contains("USE_SSL false"));
+    }
+
     #[tokio::test]
     async fn test_helper_methods() {
         assert!(DuckLake::is_minio_endpoint(&Some(
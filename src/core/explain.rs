@@ -0,0 +1,183 @@
+use crate::core::ducklake::DuckLake;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct QueryPlan {
+    pub plan: String,
+    pub analyzed: bool,
+    // Only EXPLAIN ANALYZE measures operators; listed depth-first from the root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operators: Vec<PlanOperator>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PlanOperator {
+    pub name: String,
+    pub depth: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+}
+
+// EXPLAIN ANALYZE runs the query, so it goes through the same interruptible path as
+// any other query and is cut off at `timeout`.
+pub async fn explain(
+    ducklake: &DuckLake,
+    sql: &str,
+    analyze: bool,
+    timeout: Duration,
+) -> Result<QueryPlan> {
+    let sql = sql.trim().trim_end_matches(';');
+    let statement = if analyze {
+        format!("EXPLAIN (ANALYZE, FORMAT JSON) {sql}")
+    } else {
+        format!("EXPLAIN {sql}")
+    };
+
+    let output = ducklake
+        .run_interruptible(timeout, move |connection| {
+            let mut stmt = connection.prepare(&statement)?;
+            let mut rows = stmt.query([])?;
+            // Each row is a (kind, plan) pair; plain EXPLAIN only returns the physical plan.
+            let mut plans = Vec::new();
+            while let Some(row) = rows.next()? {
+                plans.push(DuckLake::to_string(row.get(1)?));
+            }
+            Ok(plans.join("\n"))
+        })
+        .await?;
+
+    if !analyze {
+        return Ok(QueryPlan {
+            plan: output,
+            analyzed: false,
+            operators: vec![],
+        });
+    }
+
+    // Builds without JSON profiling output return the text tree instead; keep it as is.
+    let Ok(profile) = serde_json::from_str::<serde_json::Value>(&output) else {
+        return Ok(QueryPlan {
+            plan: output,
+            analyzed: true,
+            operators: vec![],
+        });
+    };
+    let operators = plan_operators(&profile);
+    Ok(QueryPlan {
+        plan: render_operators(&operators),
+        analyzed: true,
+        operators,
+    })
+}
+
+// The profile's root describes the query as a whole; operators are its children.
+fn plan_operators(profile: &serde_json::Value) -> Vec<PlanOperator> {
+    let mut operators = Vec::new();
+    for child in children(profile) {
+        collect_operators(child, 0, &mut operators);
+    }
+    operators
+}
+
+fn collect_operators(node: &serde_json::Value, depth: usize, operators: &mut Vec<PlanOperator>) {
+    // Key names changed between DuckDB releases, so accept both spellings.
+    let field = |names: &[&str]| names.iter().find_map(|name| node.get(*name));
+    let name = field(&["operator_name", "operator_type", "name"])
+        .and_then(|value| value.as_str())
+        .unwrap_or("UNKNOWN")
+        .trim()
+        .to_string();
+    let duration_ms = field(&["operator_timing", "timing"])
+        .and_then(|value| value.as_f64())
+        .map(|seconds| seconds * 1000.0);
+    let rows = field(&["operator_cardinality", "cardinality"]).and_then(|value| value.as_u64());
+
+    operators.push(PlanOperator {
+        name,
+        depth,
+        duration_ms,
+        rows,
+    });
+    for child in children(node) {
+        collect_operators(child, depth + 1, operators);
+    }
+}
+
+fn children(node: &serde_json::Value) -> &[serde_json::Value] {
+    node.get("children")
+        .and_then(|children| children.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn render_operators(operators: &[PlanOperator]) -> String {
+    operators
+        .iter()
+        .map(|operator| {
+            let mut line = format!("{}{}", "  ".repeat(operator.depth), operator.name);
+            let mut details = Vec::new();
+            if let Some(rows) = operator.rows {
+                details.push(format!("{rows} rows"));
+            }
+            if let Some(duration_ms) = operator.duration_ms {
+                details.push(format!("{duration_ms:.3} ms"));
+            }
+            if !details.is_empty() {
+                line.push_str(&format!(" ({})", details.join(", ")));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plan_operators() {
+        let profile = json!({
+            "query_name": "SELECT ...",
+            "children": [{
+                "operator_name": "PROJECTION",
+                "operator_timing": 0.5,
+                "operator_cardinality": 3,
+                "children": [{
+                    "operator_name": "HASH_JOIN",
+                    "operator_timing": 0.25,
+                    "operator_cardinality": 3,
+                    "children": [
+                        { "operator_name": "RANGE", "operator_cardinality": 10, "children": [] },
+                        { "name": "RANGE", "timing": 0.125, "cardinality": 10, "children": [] }
+                    ]
+                }]
+            }]
+        });
+
+        let operators = plan_operators(&profile);
+        assert_eq!(operators.len(), 4);
+        assert_eq!(operators[1].name, "HASH_JOIN");
+        assert_eq!(operators[1].depth, 1);
+        assert_eq!(operators[1].duration_ms, Some(250.0));
+        assert_eq!(operators[2].duration_ms, None);
+        assert_eq!(operators[3].rows, Some(10));
+
+        let plan = render_operators(&operators);
+        assert_eq!(
+            plan.lines().collect::<Vec<_>>(),
+            vec![
+                "PROJECTION (3 rows, 500.000 ms)",
+                "  HASH_JOIN (3 rows, 250.000 ms)",
+                "    RANGE (10 rows)",
+                "    RANGE (10 rows, 125.000 ms)",
+            ]
+        );
+    }
+}
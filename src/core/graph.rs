@@ -1,17 +1,94 @@
-use anyhow::Result;
+use crate::core::{
+    adapter::SchemaDiff,
+    config::Config,
+    ducklake::{DuckLake, quote_ident},
+    pipeline::Phase,
+    template,
+};
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
+    time::Instant,
 };
 use tokio::fs;
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub name: String,
     pub updated_at: Option<DateTime<Utc>>,
     pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub row_count: Option<u64>,
+    #[serde(default)]
+    pub last_duration_ms: Option<u64>,
+    #[serde(default)]
+    pub last_status: Option<Phase>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableStats {
+    pub row_count: u64,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub duplicates_dropped: u64,
+    // Set for file imports only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<FileCounts>,
+    // Set when an adapter's source no longer matched its table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_changes: Option<SchemaDiff>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCounts {
+    pub imported: u64,
+    pub skipped: u64,
+}
+
+impl TableStats {
+    // Counts the rows of a freshly built table, timing the build from `started`.
+    pub fn collect(ducklake: &DuckLake, table_name: &str, started: Instant) -> Result<Self> {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let row_count = count_rows(ducklake, table_name)?;
+
+        Ok(Self {
+            row_count,
+            duration_ms,
+            duplicates_dropped: 0,
+            files: None,
+            schema_changes: None,
+        })
+    }
+}
+
+pub fn count_rows(ducklake: &DuckLake, table_name: &str) -> Result<u64> {
+    let result = ducklake
+        .query(&format!("SELECT COUNT(*) FROM {}", quote_ident(table_name)))
+        .with_context(|| format!("Failed to count rows of table '{table_name}'"))?;
+    Ok(result
+        .first()
+        .and_then(|row| row.first())
+        .and_then(|count| count.parse().ok())
+        .unwrap_or_default())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -106,6 +183,21 @@ impl Graph {
         self.nodes.remove(name);
     }
 
+    pub fn rename_node(&mut self, from: &str, to: &str) {
+        if let Some(mut node) = self.nodes.remove(from) {
+            node.name = to.to_string();
+            self.nodes.insert(to.to_string(), node);
+        }
+
+        for node in self.nodes.values_mut() {
+            for dependency in node.dependencies.iter_mut() {
+                if dependency == from {
+                    *dependency = to.to_string();
+                }
+            }
+        }
+    }
+
     pub fn update_node(&mut self, name: &str) {
         self.reset_updated_at(name);
 
@@ -122,12 +214,24 @@ impl Graph {
     fn reset_updated_at(&mut self, name: &str) {
         if let Some(node) = self.nodes.get_mut(name) {
             node.updated_at = None;
+            node.last_error = None;
         }
     }
 
-    pub fn update(&mut self, name: &str) {
+    pub fn record_stats(&mut self, name: &str, stats: &TableStats) {
         if let Some(node) = self.nodes.get_mut(name) {
             node.updated_at = Some(Utc::now());
+            node.row_count = Some(stats.row_count);
+            node.last_duration_ms = Some(stats.duration_ms);
+            node.last_status = Some(Phase::Completed);
+            node.last_error = None;
+        }
+    }
+
+    pub fn record_failure(&mut self, name: &str, error: &str) {
+        if let Some(node) = self.nodes.get_mut(name) {
+            node.last_status = Some(Phase::Failed);
+            node.last_error = Some(error.to_string());
         }
     }
 
@@ -138,6 +242,10 @@ impl Graph {
                 name: name.to_string(),
                 updated_at: None,
                 dependencies: Vec::new(),
+                row_count: None,
+                last_duration_ms: None,
+                last_status: None,
+                last_error: None,
             },
         );
         self.update_dependencies(name, dependencies);
@@ -186,12 +294,129 @@ impl Graph {
     pub fn get_node(&self, name: &str) -> Option<&Node> {
         self.nodes.get(name)
     }
+
+    // Brings the graph back in line with the adapter, seed and model configs. Nodes whose
+    // dependencies are unchanged keep their build state; changed nodes are marked stale.
+    pub fn rebuild(&mut self, config: &Config) -> GraphDiff {
+        let mut expected: HashMap<String, Vec<String>> = config
+            .seeds
+            .keys()
+            .map(|name| (name.clone(), Vec::new()))
+            .collect();
+        for (name, adapter) in &config.adapters {
+            let dependencies = config.relationship_nodes(name, &adapter.relationships);
+            expected.insert(name.clone(), dependencies);
+        }
+        for (name, model) in &config.models {
+            let mut dependencies = template::dependencies(config, &model.sql).unwrap_or_default();
+            for node in config.relationship_nodes(name, &model.relationships) {
+                if !dependencies.contains(&node) {
+                    dependencies.push(node);
+                }
+            }
+            expected.insert(name.clone(), dependencies);
+        }
+
+        let mut diff = GraphDiff::default();
+
+        let stale: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|name| !expected.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in stale {
+            self.delete_node(&name);
+            diff.removed.push(name);
+        }
+
+        for (name, dependencies) in &expected {
+            let deps: Vec<&str> = dependencies.iter().map(|s| s.as_str()).collect();
+            match self.nodes.get(name) {
+                None => {
+                    self.create_node(name, &deps);
+                    diff.added.push(name.clone());
+                }
+                Some(node) if !same_dependencies(&node.dependencies, dependencies) => {
+                    self.update_dependencies(name, &deps);
+                    diff.changed.push(name.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        for name in &diff.changed {
+            self.update_node(name);
+        }
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    pub fn detect_cycle(&self) -> Option<Vec<String>> {
+        let mut names: Vec<&String> = self.nodes.keys().collect();
+        names.sort();
+
+        let mut visited = std::collections::HashSet::new();
+        let mut path = Vec::new();
+
+        for name in names {
+            if let Some(cycle) = self.find_cycle(name, &mut visited, &mut path) {
+                return Some(cycle);
+            }
+        }
+
+        None
+    }
+
+    fn find_cycle(
+        &self,
+        name: &str,
+        visited: &mut std::collections::HashSet<String>,
+        path: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(position) = path.iter().position(|n| n == name) {
+            let mut cycle = path[position..].to_vec();
+            cycle.push(name.to_string());
+            return Some(cycle);
+        }
+
+        if !visited.insert(name.to_string()) {
+            return None;
+        }
+
+        let node = self.nodes.get(name)?;
+
+        path.push(name.to_string());
+        for dep in &node.dependencies {
+            if let Some(cycle) = self.find_cycle(dep, visited, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+
+        None
+    }
+}
+
+fn same_dependencies(current: &[String], expected: &[String]) -> bool {
+    let mut current = current.to_vec();
+    let mut expected = expected.to_vec();
+    current.sort();
+    expected.sort();
+    current == expected
 }
 
 use sqlparser::{
-    ast::{Statement, TableFactor},
+    ast::{
+        Expr, FunctionArg, FunctionArgExpr, FunctionArguments, Ident, Select, SelectItem, SetExpr,
+        Statement, TableFactor,
+    },
     dialect::DuckDbDialect,
     parser::Parser,
+    tokenizer::{Token, Tokenizer},
 };
 
 pub fn dependent_tables(sql: &str) -> Result<Vec<String>, String> {
@@ -223,7 +448,15 @@ pub fn dependent_tables(sql: &str) -> Result<Vec<String>, String> {
 pub fn collect_table_names(table_factor: &TableFactor, tables: &mut Vec<String>) {
     match table_factor {
         TableFactor::Table { name, .. } => {
-            tables.push(name.to_string());
+            // Templates quote the tables ref() and source() resolve to.
+            let name = name.to_string();
+            match name
+                .strip_prefix('"')
+                .and_then(|name| name.strip_suffix('"'))
+            {
+                Some(unquoted) if !unquoted.contains('"') => tables.push(unquoted.to_string()),
+                _ => tables.push(name),
+            }
         }
         TableFactor::NestedJoin {
             table_with_joins, ..
@@ -237,6 +470,276 @@ pub fn collect_table_names(table_factor: &TableFactor, tables: &mut Vec<String>)
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ColumnInput {
+    // None when an unqualified column could come from more than one table.
+    pub table: Option<String>,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ColumnLineage {
+    pub output: String,
+    pub inputs: Vec<ColumnInput>,
+    // Set for `*` and `t.*`, whose columns are only known at run time.
+    pub wildcard: bool,
+}
+
+// Best-effort column lineage for a single SELECT. Expressions we do not
+// understand (subqueries, window specs, ...) simply contribute no inputs.
+pub fn column_lineage(sql: &str) -> Result<Vec<ColumnLineage>, String> {
+    let dialect = DuckDbDialect {};
+    let ast = Parser::parse_sql(&dialect, sql).map_err(|e| e.to_string())?;
+
+    let Some(Statement::Query(query)) = ast.first() else {
+        return Ok(vec![]);
+    };
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return Ok(vec![]);
+    };
+
+    let scope = TableScope::new(select);
+    let mut lineage = Vec::new();
+    for item in &select.projection {
+        match item {
+            SelectItem::UnnamedExpr(expr) => lineage.push(ColumnLineage {
+                output: expr_output_name(expr),
+                inputs: scope.inputs(expr),
+                wildcard: false,
+            }),
+            SelectItem::ExprWithAlias { expr, alias } => lineage.push(ColumnLineage {
+                output: alias.value.clone(),
+                inputs: scope.inputs(expr),
+                wildcard: false,
+            }),
+            SelectItem::Wildcard(_) => lineage.push(ColumnLineage {
+                output: "*".to_string(),
+                inputs: scope
+                    .tables
+                    .iter()
+                    .map(|(_, table)| ColumnInput {
+                        table: Some(table.clone()),
+                        column: "*".to_string(),
+                    })
+                    .collect(),
+                wildcard: true,
+            }),
+            SelectItem::QualifiedWildcard(qualifier, _) => {
+                let qualifier = qualifier.to_string();
+                lineage.push(ColumnLineage {
+                    output: format!("{qualifier}.*"),
+                    inputs: vec![ColumnInput {
+                        table: Some(scope.resolve(&qualifier).unwrap_or(qualifier)),
+                        column: "*".to_string(),
+                    }],
+                    wildcard: true,
+                });
+            }
+        }
+    }
+
+    Ok(lineage)
+}
+
+fn expr_output_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map(|ident| ident.value.clone())
+            .unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+// Tables visible to a SELECT, keyed by the name a column would use to refer to
+// them: the alias when there is one, otherwise the table name.
+struct TableScope {
+    tables: Vec<(String, String)>,
+}
+
+impl TableScope {
+    fn new(select: &Select) -> Self {
+        let mut tables = Vec::new();
+        for table in &select.from {
+            Self::collect(&table.relation, &mut tables);
+            for join in &table.joins {
+                Self::collect(&join.relation, &mut tables);
+            }
+        }
+        Self { tables }
+    }
+
+    fn collect(table_factor: &TableFactor, tables: &mut Vec<(String, String)>) {
+        match table_factor {
+            TableFactor::Table { name, alias, .. } => {
+                let table = name.to_string();
+                let reference = alias
+                    .as_ref()
+                    .map(|alias| alias.name.value.clone())
+                    .unwrap_or_else(|| table.clone());
+                tables.push((reference, table));
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => {
+                Self::collect(&table_with_joins.relation, tables);
+                for join in &table_with_joins.joins {
+                    Self::collect(&join.relation, tables);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve(&self, qualifier: &str) -> Option<String> {
+        let short_name = |table: &str| table.rsplit('.').next().unwrap_or(table).to_lowercase();
+        let qualifier = qualifier.to_lowercase();
+        self.tables
+            .iter()
+            .find(|(reference, table)| {
+                reference.to_lowercase() == qualifier
+                    || table.to_lowercase() == qualifier
+                    || short_name(table) == qualifier
+            })
+            .map(|(_, table)| table.clone())
+    }
+
+    fn column(&self, qualifier: &[Ident], column: &Ident) -> ColumnInput {
+        let table = if qualifier.is_empty() {
+            match self.tables.as_slice() {
+                [(_, table)] => Some(table.clone()),
+                _ => None,
+            }
+        } else {
+            let qualifier = qualifier
+                .iter()
+                .map(|ident| ident.value.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+            Some(self.resolve(&qualifier).unwrap_or(qualifier))
+        };
+
+        ColumnInput {
+            table,
+            column: column.value.clone(),
+        }
+    }
+
+    fn inputs(&self, expr: &Expr) -> Vec<ColumnInput> {
+        let mut inputs = Vec::new();
+        self.collect_inputs(expr, &mut inputs);
+        inputs
+    }
+
+    fn collect_inputs(&self, expr: &Expr, inputs: &mut Vec<ColumnInput>) {
+        let input = match expr {
+            Expr::Identifier(ident) => Some(self.column(&[], ident)),
+            Expr::CompoundIdentifier(idents) => idents
+                .split_last()
+                .map(|(column, qualifier)| self.column(qualifier, column)),
+            _ => None,
+        };
+        if let Some(input) = input {
+            if !inputs.contains(&input) {
+                inputs.push(input);
+            }
+            return;
+        }
+
+        match expr {
+            Expr::BinaryOp { left, right, .. } => {
+                self.collect_inputs(left, inputs);
+                self.collect_inputs(right, inputs);
+            }
+            Expr::UnaryOp { expr, .. }
+            | Expr::Nested(expr)
+            | Expr::Cast { expr, .. }
+            | Expr::IsNull(expr)
+            | Expr::IsNotNull(expr) => self.collect_inputs(expr, inputs),
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                for expr in [expr, low, high] {
+                    self.collect_inputs(expr, inputs);
+                }
+            }
+            Expr::InList { expr, list, .. } => {
+                self.collect_inputs(expr, inputs);
+                for expr in list {
+                    self.collect_inputs(expr, inputs);
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                else_result,
+                ..
+            } => {
+                if let Some(operand) = operand {
+                    self.collect_inputs(operand, inputs);
+                }
+                for when in conditions {
+                    self.collect_inputs(&when.condition, inputs);
+                    self.collect_inputs(&when.result, inputs);
+                }
+                if let Some(else_result) = else_result {
+                    self.collect_inputs(else_result, inputs);
+                }
+            }
+            Expr::Function(function) => {
+                if let FunctionArguments::List(list) = &function.args {
+                    for arg in &list.args {
+                        let arg = match arg {
+                            FunctionArg::Unnamed(arg)
+                            | FunctionArg::Named { arg, .. }
+                            | FunctionArg::ExprNamed { arg, .. } => arg,
+                        };
+                        if let FunctionArgExpr::Expr(expr) = arg {
+                            self.collect_inputs(expr, inputs);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[schema(as = SqlColumnReference)]
+pub struct ColumnReference {
+    pub identifier: String,
+    pub line: u64,
+    pub column: u64,
+}
+
+// Finds identifiers naming any of `columns` (compared case-insensitively).
+// Works on tokens, so `price_usd` and the string 'price' never match `price`.
+pub fn column_references(
+    sql: &str,
+    columns: &std::collections::HashSet<String>,
+) -> Result<Vec<ColumnReference>, String> {
+    let dialect = DuckDbDialect {};
+    let tokens = Tokenizer::new(&dialect, sql)
+        .tokenize_with_location()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tokens
+        .into_iter()
+        .filter_map(|token| match token.token {
+            Token::Word(word) if columns.contains(&word.value.to_lowercase()) => {
+                Some(ColumnReference {
+                    identifier: word.value,
+                    line: token.span.start.line,
+                    column: token.span.start.column,
+                })
+            }
+            _ => None,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,6 +826,85 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_detect_cycle_none() {
+        let mut graph = Graph::default();
+        graph.create_node("a", &[]);
+        graph.create_node("b", &["a"]);
+        graph.create_node("c", &["b", "a"]);
+        graph.create_node("d", &["missing"]);
+
+        assert_eq!(graph.detect_cycle(), None);
+    }
+
+    #[test]
+    fn test_detect_cycle_two_nodes() {
+        let mut graph = Graph::default();
+        graph.create_node("a", &["b"]);
+        graph.create_node("b", &["a"]);
+
+        assert_eq!(
+            graph.detect_cycle(),
+            Some(vec!["a".to_string(), "b".to_string(), "a".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_detect_cycle_longer() {
+        let mut graph = Graph::default();
+        graph.create_node("source", &[]);
+        graph.create_node("a", &["source", "c"]);
+        graph.create_node("b", &["a"]);
+        graph.create_node("c", &["b"]);
+
+        assert_eq!(
+            graph.detect_cycle(),
+            Some(vec![
+                "a".to_string(),
+                "c".to_string(),
+                "b".to_string(),
+                "a".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_detect_cycle_self_reference() {
+        let mut graph = Graph::default();
+        graph.create_node("a", &[]);
+        graph.create_node("self_ref", &["a", "self_ref"]);
+
+        assert_eq!(
+            graph.detect_cycle(),
+            Some(vec!["self_ref".to_string(), "self_ref".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_rename_node() {
+        let mut graph = Graph::default();
+        graph.create_node("users", &[]);
+        graph.set_current_time("users");
+        graph.create_node("active_users", &["users"]);
+        graph.create_node("report", &["active_users", "users"]);
+
+        let updated_at = graph.get_node("users").unwrap().updated_at;
+        graph.rename_node("users", "customers");
+
+        assert!(!graph.has_node("users"));
+        let renamed = graph.get_node("customers").unwrap();
+        assert_eq!(renamed.name, "customers");
+        assert_eq!(renamed.updated_at, updated_at);
+        assert_eq!(
+            graph.get_node("active_users").unwrap().dependencies,
+            vec!["customers".to_string()]
+        );
+        assert_eq!(
+            graph.get_node("report").unwrap().dependencies,
+            vec!["active_users".to_string(), "customers".to_string()]
+        );
+    }
+
     #[test]
     fn test_dependent_tables() {
         let sql = "SELECT * FROM users";
@@ -341,5 +923,82 @@ mod tests {
         let sql = "SELECT * FROM test_table";
         let tables = dependent_tables(sql).unwrap();
         assert_eq!(tables, vec!["test_table"]);
+
+        let sql = "SELECT * FROM \"test_table\" JOIN main.\"other\" USING (id)";
+        let tables = dependent_tables(sql).unwrap();
+        assert_eq!(tables, vec!["test_table", "main.\"other\""]);
+    }
+
+    fn input(table: Option<&str>, column: &str) -> ColumnInput {
+        ColumnInput {
+            table: table.map(str::to_string),
+            column: column.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_column_lineage_join_aliases() {
+        let sql = "SELECT u.id AS user_id, name, o.total * o.quantity AS revenue, \
+                   u.id + o.id, COALESCE(o.note, 'none') AS note, 1 AS one \
+                   FROM users u JOIN orders AS o ON u.id = o.user_id";
+        let lineage = column_lineage(sql).unwrap();
+
+        let outputs = lineage
+            .iter()
+            .map(|l| l.output.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            vec!["user_id", "name", "revenue", "u.id + o.id", "note", "one"]
+        );
+        assert_eq!(lineage[0].inputs, vec![input(Some("users"), "id")]);
+        // Ambiguous across the join.
+        assert_eq!(lineage[1].inputs, vec![input(None, "name")]);
+        assert_eq!(
+            lineage[2].inputs,
+            vec![
+                input(Some("orders"), "total"),
+                input(Some("orders"), "quantity")
+            ]
+        );
+        assert_eq!(
+            lineage[3].inputs,
+            vec![input(Some("users"), "id"), input(Some("orders"), "id")]
+        );
+        assert_eq!(lineage[4].inputs, vec![input(Some("orders"), "note")]);
+        assert!(lineage[5].inputs.is_empty());
+        assert!(lineage.iter().all(|l| !l.wildcard));
+    }
+
+    #[test]
+    fn test_column_lineage_wildcards() {
+        let lineage = column_lineage("SELECT * FROM events").unwrap();
+        assert_eq!(lineage.len(), 1);
+        assert!(lineage[0].wildcard);
+        assert_eq!(lineage[0].inputs, vec![input(Some("events"), "*")]);
+
+        let lineage =
+            column_lineage("SELECT e.*, CAST(u.age AS INTEGER) AS age FROM events e, users u")
+                .unwrap();
+        assert_eq!(lineage[0].output, "e.*");
+        assert_eq!(lineage[0].inputs, vec![input(Some("events"), "*")]);
+        assert_eq!(lineage[1].inputs, vec![input(Some("users"), "age")]);
+
+        // Unqualified columns resolve when there is a single table.
+        let lineage = column_lineage("SELECT amount - discount FROM sales").unwrap();
+        assert_eq!(
+            lineage[0].inputs,
+            vec![
+                input(Some("sales"), "amount"),
+                input(Some("sales"), "discount")
+            ]
+        );
+
+        assert!(column_lineage("SELECT (1").is_err());
+        assert!(
+            column_lineage("SELECT 1 UNION SELECT 2")
+                .unwrap()
+                .is_empty()
+        );
     }
 }
@@ -0,0 +1,339 @@
+use crate::core::{
+    config::Config,
+    graph::{ColumnReference, Graph, column_lineage, column_references, dependent_tables},
+    template,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AffectedResource {
+    pub name: String,
+    pub references: Vec<ColumnReference>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AffectedDashboard {
+    pub name: String,
+    pub panel: usize,
+    pub query: String,
+    // Chart fields (`x_column`, `y_column`) that name an affected column.
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ImpactReport {
+    pub models: Vec<AffectedResource>,
+    pub queries: Vec<AffectedResource>,
+    pub dashboards: Vec<AffectedDashboard>,
+}
+
+// Lowercased column names, per table, that carry the changed column.
+type Tracked = HashMap<String, HashSet<String>>;
+
+fn tracked_columns(sql: &str, tracked: &Tracked) -> HashSet<String> {
+    dependent_tables(sql)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|table| tracked.get(table))
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+fn references(sql: &str, tracked: &Tracked) -> Vec<ColumnReference> {
+    let columns = tracked_columns(sql, tracked);
+    if columns.is_empty() {
+        return vec![];
+    }
+    column_references(sql, &columns).unwrap_or_default()
+}
+
+// Output columns of `sql` that are computed from, or pass through, a tracked column.
+fn derived_columns(sql: &str, tracked: &Tracked) -> HashSet<String> {
+    let upstream = tracked_columns(sql, tracked);
+    let mut derived = HashSet::new();
+
+    for lineage in column_lineage(sql).unwrap_or_default() {
+        if lineage.wildcard {
+            for input in &lineage.inputs {
+                if let Some(columns) = input.table.as_ref().and_then(|table| tracked.get(table)) {
+                    derived.extend(columns.iter().cloned());
+                }
+            }
+            continue;
+        }
+
+        let carries = lineage.inputs.iter().any(|input| {
+            let column = input.column.to_lowercase();
+            match &input.table {
+                Some(table) => tracked
+                    .get(table)
+                    .is_some_and(|columns| columns.contains(&column)),
+                None => upstream.contains(&column),
+            }
+        });
+        if carries {
+            derived.insert(lineage.output.to_lowercase());
+        }
+    }
+
+    derived
+}
+
+fn sorted<T>(items: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut items = items.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(name, _)| *name);
+    items
+}
+
+// Follows `column` of `table` through the downstream models, then checks which
+// models, queries and dashboards refer to it or to a column derived from it.
+pub fn column_impact(config: &Config, graph: &Graph, table: &str, column: &str) -> ImpactReport {
+    let mut tracked = Tracked::from([(table.to_string(), HashSet::from([column.to_lowercase()]))]);
+    let downstream = graph.downstream(config.table_owner(table).unwrap_or(table));
+    // `ref()` and `source()` only name a table once compiled; SQL that doesn't
+    // compile is read as written.
+    let models = downstream
+        .iter()
+        .filter_map(|name| config.models.get_key_value(name))
+        .map(|(name, model)| {
+            let sql = template::compile_for_analysis(config, &model.sql)
+                .map(|compiled| compiled.sql)
+                .unwrap_or_else(|_| model.sql.clone());
+            (name, sql)
+        })
+        .collect::<Vec<_>>();
+
+    // Repeat until nothing new is derived; the graph is acyclic, so this ends.
+    loop {
+        let mut changed = false;
+        for (name, sql) in &models {
+            let derived = derived_columns(sql, &tracked);
+            let columns = tracked.entry(name.to_string()).or_default();
+            for column in derived {
+                changed |= columns.insert(column);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut report = ImpactReport::default();
+    for (name, sql) in &models {
+        let references = references(sql, &tracked);
+        if !references.is_empty() {
+            report.models.push(AffectedResource {
+                name: name.to_string(),
+                references,
+            });
+        }
+    }
+
+    let mut query_columns = HashMap::new();
+    for (name, query) in sorted(&config.queries) {
+        let references = references(&query.sql, &tracked);
+        let affected = !references.is_empty();
+        if affected {
+            report.queries.push(AffectedResource {
+                name: name.clone(),
+                references,
+            });
+        }
+        let derived = derived_columns(&query.sql, &tracked);
+        query_columns.insert(name.as_str(), (derived, affected));
+    }
+
+    for (name, dashboard) in sorted(&config.dashboards) {
+        for (index, panel) in dashboard.panels.iter().enumerate() {
+            let Some((columns, query_affected)) = query_columns.get(panel.query.as_str()) else {
+                continue;
+            };
+            let fields = [
+                ("x_column", &panel.chart.x_column),
+                ("y_column", &panel.chart.y_column),
+            ]
+            .into_iter()
+            .filter(|(_, column)| columns.contains(&column.to_lowercase()))
+            .map(|(field, _)| field.to_string())
+            .collect::<Vec<_>>();
+
+            if *query_affected || !fields.is_empty() {
+                report.dashboards.push(AffectedDashboard {
+                    name: name.clone(),
+                    panel: index,
+                    query: panel.query.clone(),
+                    fields,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{
+        dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+        model::ModelConfig,
+        query::QueryConfig,
+    };
+    use tempfile::tempdir;
+
+    fn query(sql: &str) -> QueryConfig {
+        QueryConfig {
+            description: None,
+            sql: sql.to_string(),
+            tags: vec![],
+            group: None,
+            parameters: vec![],
+        }
+    }
+
+    fn dashboard(query: &str, x_column: &str, y_column: &str) -> DashboardConfig {
+        DashboardConfig {
+            description: None,
+            panels: vec![PanelConfig::new(
+                query,
+                ChartConfig {
+                    chart_type: ChartType::Bar,
+                    x_column: x_column.to_string(),
+                    y_column: y_column.to_string(),
+                },
+            )],
+            tags: vec![],
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_column_impact() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::new(dir.path().to_path_buf());
+        config.models.insert(
+            "priced".to_string(),
+            ModelConfig {
+                sql: "SELECT id,\n  price * quantity AS revenue\nFROM products".to_string(),
+                ..Default::default()
+            },
+        );
+        config.models.insert(
+            "converted".to_string(),
+            ModelConfig {
+                sql: "SELECT id, price_usd FROM products WHERE label = 'price'".to_string(),
+                ..Default::default()
+            },
+        );
+        config.models.insert(
+            "report".to_string(),
+            ModelConfig {
+                sql: "SELECT SUM(revenue) AS total FROM priced".to_string(),
+                ..Default::default()
+            },
+        );
+        config.queries.insert(
+            "revenue_by_id".to_string(),
+            query("SELECT id, revenue FROM priced"),
+        );
+        config
+            .queries
+            .insert("everything".to_string(), query("SELECT * FROM priced"));
+        config
+            .queries
+            .insert("usd".to_string(), query("SELECT price_usd FROM converted"));
+        config.dashboards.insert(
+            "revenue_chart".to_string(),
+            dashboard("revenue_by_id", "id", "revenue"),
+        );
+        config.dashboards.insert(
+            "wildcard_chart".to_string(),
+            dashboard("everything", "id", "revenue"),
+        );
+        config.dashboards.insert(
+            "usd_chart".to_string(),
+            dashboard("usd", "price_usd", "price_usd"),
+        );
+
+        let mut graph = Graph::new(dir.path());
+        graph.create_node("products", &[]);
+        graph.create_node("priced", &["products"]);
+        graph.create_node("converted", &["products"]);
+        graph.create_node("report", &["priced"]);
+
+        let report = column_impact(&config, &graph, "products", "price");
+
+        let models = report
+            .models
+            .iter()
+            .map(|model| model.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(models, vec!["priced", "report"]);
+        assert_eq!(
+            report.models[0].references,
+            vec![ColumnReference {
+                identifier: "price".to_string(),
+                line: 2,
+                column: 3,
+            }]
+        );
+        assert_eq!(report.models[1].references[0].identifier, "revenue");
+
+        let queries = report
+            .queries
+            .iter()
+            .map(|query| query.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(queries, vec!["revenue_by_id"]);
+
+        let dashboards = report
+            .dashboards
+            .iter()
+            .map(|dashboard| (dashboard.name.as_str(), dashboard.fields.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dashboards,
+            vec![
+                ("revenue_chart", vec!["y_column".to_string()]),
+                ("wildcard_chart", vec!["y_column".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_impact_through_ref() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::new(dir.path().to_path_buf());
+        config.models.insert(
+            "priced".to_string(),
+            ModelConfig {
+                sql: "SELECT id, price * quantity AS revenue FROM products".to_string(),
+                ..Default::default()
+            },
+        );
+        config.models.insert(
+            "report".to_string(),
+            ModelConfig {
+                sql: "SELECT SUM(revenue) AS total FROM {{ ref('priced') }}".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let mut graph = Graph::new(dir.path());
+        graph.create_node("products", &[]);
+        graph.create_node("priced", &["products"]);
+        graph.create_node("report", &["priced"]);
+
+        let report = column_impact(&config, &graph, "products", "price");
+        let models = report
+            .models
+            .iter()
+            .map(|model| model.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(models, vec!["priced", "report"]);
+        assert_eq!(report.models[1].references[0].identifier, "revenue");
+    }
+}
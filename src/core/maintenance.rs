@@ -0,0 +1,184 @@
+use crate::core::{config::Config, ducklake::DuckLake};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SNAPSHOT_RETENTION_DAYS: u32 = 30;
+
+// DuckLake keeps its own metadata in a separate catalog, so these never show up in
+// `db.main`; the check makes sure cleanup can't touch them if that ever changes.
+fn is_protected(name: &str) -> bool {
+    name.starts_with("ducklake_") || name.starts_with("__ducklake")
+}
+
+// Tables and views of the catalog that no adapter, model or seed builds any more,
+// e.g. left behind by a deleted adapter or a renamed model.
+pub fn orphaned_relations(ducklake: &DuckLake, config: &Config) -> Result<Vec<String>> {
+    Ok(ducklake
+        .list_relations()?
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !is_protected(name) && !config.defines_table(name))
+        .collect())
+}
+
+// Drops the table an adapter or model built once nothing else builds it.
+pub fn drop_unowned_table(ducklake: &DuckLake, config: &Config, table: &str) -> Result<bool> {
+    if is_protected(table) || config.defines_table(table) {
+        return Ok(false);
+    }
+    ducklake.drop_relation(table)
+}
+
+// What the catalog tracks in storage. Data files include those only kept for older
+// snapshots; files pending deletion are already unreferenced but still on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub snapshots: u64,
+    pub data_files: u64,
+    pub data_bytes: u64,
+    pub pending_deletion: u64,
+}
+
+impl StorageStats {
+    pub fn collect(ducklake: &DuckLake) -> Result<Self> {
+        let snapshot = ducklake.metadata_relation("ducklake_snapshot");
+        let data_file = ducklake.metadata_relation("ducklake_data_file");
+        let scheduled = ducklake.metadata_relation("ducklake_files_scheduled_for_deletion");
+        let rows = ducklake
+            .query(&format!(
+                "SELECT \
+                 (SELECT count(*) FROM {snapshot}), \
+                 (SELECT count(*) FROM {data_file}), \
+                 (SELECT coalesce(sum(file_size_bytes), 0) FROM {data_file}), \
+                 (SELECT count(*) FROM {scheduled})"
+            ))
+            .context("Failed to read DuckLake storage statistics")?;
+
+        let value = |index: usize| -> Result<u64> {
+            let value = &rows[0][index];
+            value
+                .parse()
+                .with_context(|| format!("Unexpected storage statistic '{value}'"))
+        };
+        Ok(Self {
+            snapshots: value(0)?,
+            data_files: value(1)?,
+            data_bytes: value(2)?,
+            pending_deletion: value(3)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub dry_run: bool,
+    pub older_than_days: u32,
+    pub expired_snapshots: u64,
+    pub deleted_files: u64,
+    pub before: StorageStats,
+    pub after: StorageStats,
+    pub files_reclaimed: u64,
+    pub bytes_reclaimed: u64,
+}
+
+// Merges small adjacent files, expires snapshots older than `older_than_days` and
+// deletes the files nothing references any more. A dry run only reports which
+// snapshots would expire and which files are already waiting to be deleted.
+pub fn compact(
+    ducklake: &DuckLake,
+    older_than_days: u32,
+    dry_run: bool,
+) -> Result<CompactionReport> {
+    let before = StorageStats::collect(ducklake)?;
+    let older_than = format!("now() - INTERVAL '{older_than_days} days'");
+
+    let (expired_snapshots, deleted_files) = if dry_run {
+        let expired = ducklake.query(&format!(
+            "CALL ducklake_expire_snapshots('db', dry_run => true, older_than => {older_than})"
+        ))?;
+        let deleted = ducklake
+            .query("CALL ducklake_cleanup_old_files('db', dry_run => true, cleanup_all => true)")?;
+        (expired.len(), deleted.len())
+    } else {
+        ducklake
+            .execute_batch("CALL ducklake_merge_adjacent_files('db');")
+            .context("Failed to merge data files")?;
+        let expired = ducklake
+            .query(&format!(
+                "CALL ducklake_expire_snapshots('db', older_than => {older_than})"
+            ))
+            .context("Failed to expire snapshots")?;
+        // Expired snapshots can't be read any more, so their files can go right away.
+        let deleted = ducklake
+            .query("CALL ducklake_cleanup_old_files('db', cleanup_all => true)")
+            .context("Failed to delete old files")?;
+        (expired.len(), deleted.len())
+    };
+
+    let after = if dry_run {
+        before
+    } else {
+        StorageStats::collect(ducklake)?
+    };
+    Ok(CompactionReport {
+        dry_run,
+        older_than_days,
+        expired_snapshots: expired_snapshots as u64,
+        deleted_files: deleted_files as u64,
+        before,
+        after,
+        files_reclaimed: (before.data_files + before.pending_deletion)
+            .saturating_sub(after.data_files + after.pending_deletion),
+        bytes_reclaimed: before.data_bytes.saturating_sub(after.data_bytes),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        adapter::test_helpers::setup_test_ducklake,
+        config::model::{Materialization, ModelConfig},
+    };
+
+    #[tokio::test]
+    async fn test_orphaned_relations() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.execute_batch(
+            "CREATE TABLE users AS SELECT 1 AS id; \
+             CREATE TABLE old_users AS SELECT 1 AS id; \
+             CREATE VIEW old_view AS SELECT 1 AS id;",
+        )?;
+
+        let mut config = Config::new(tempdir.path().to_path_buf());
+        config.models.insert(
+            "users".to_string(),
+            ModelConfig {
+                description: None,
+                sql: "SELECT 1 AS id".to_string(),
+                materialization: Materialization::Table,
+                unique_key: None,
+                updated_at_column: None,
+                tests: vec![],
+                relationships: vec![],
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            },
+        );
+
+        assert_eq!(
+            orphaned_relations(&ducklake, &config)?,
+            vec!["old_users", "old_view"]
+        );
+        assert!(!drop_unowned_table(&ducklake, &config, "users")?);
+        assert!(drop_unowned_table(&ducklake, &config, "old_view")?);
+        assert!(!drop_unowned_table(&ducklake, &config, "old_view")?);
+        assert_eq!(orphaned_relations(&ducklake, &config)?, vec!["old_users"]);
+
+        Ok(())
+    }
+}
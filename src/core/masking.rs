@@ -0,0 +1,427 @@
+use crate::core::{
+    config::{
+        Config,
+        masking::{MaskMode, MaskingConfig},
+    },
+    ducklake::quote_ident,
+    graph::dependent_tables,
+};
+use ring::digest::{SHA256, digest};
+use serde_json::Value;
+use sqlparser::{
+    ast::{Expr, Ident, Query, SelectItem, SetExpr, Statement, TableFactor},
+    dialect::DuckDbDialect,
+    parser::Parser,
+    tokenizer::{Token, Tokenizer},
+};
+use std::{collections::HashMap, fmt};
+
+const MASK: &str = "***";
+const PARTIAL_VISIBLE_CHARS: usize = 4;
+
+// Masks to apply to a result, keyed by output column name.
+pub type Masks = HashMap<String, MaskMode>;
+
+// Masks follow output column names, so a query that renames a masked column or
+// derives another column from it would return the values in the clear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedReference(pub String);
+
+impl fmt::Display for MaskedReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Masked column '{}' can only be selected as is, not renamed or used in an expression",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for MaskedReference {}
+
+impl MaskMode {
+    // NULL stays NULL, so masking never hides whether a value is present.
+    pub fn apply(self, value: &Value) -> Value {
+        let text = match value {
+            Value::Null => return Value::Null,
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+
+        Value::String(match self {
+            MaskMode::Full => MASK.to_string(),
+            MaskMode::Partial => partial(&text),
+            MaskMode::Hash => hash(&text),
+        })
+    }
+
+    // The same mask as `apply`, as a DuckDB expression over `column`, for results
+    // that DuckDB writes out itself.
+    fn sql(self, column: &str) -> String {
+        let text = format!("CAST({} AS VARCHAR)", quote_ident(column));
+        match self {
+            MaskMode::Full => format!("CASE WHEN {text} IS NULL THEN NULL ELSE '{MASK}' END"),
+            MaskMode::Partial => format!(
+                "CASE WHEN {text} IS NULL THEN NULL \
+                 WHEN position('@' IN {text}) > 1 \
+                 THEN left({text}, 1) || '{MASK}' || substr({text}, position('@' IN {text})) \
+                 WHEN length({text}) <= {PARTIAL_VISIBLE_CHARS} THEN '{MASK}' \
+                 ELSE '{MASK}' || right({text}, {PARTIAL_VISIBLE_CHARS}) END"
+            ),
+            MaskMode::Hash => format!("left(sha256({text}), 16)"),
+        }
+    }
+}
+
+fn partial(text: &str) -> String {
+    if let Some((local, domain)) = text.split_once('@')
+        && let Some(first) = local.chars().next()
+    {
+        return format!("{first}{MASK}@{domain}");
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= PARTIAL_VISIBLE_CHARS {
+        return MASK.to_string();
+    }
+    let visible: String = chars[chars.len() - PARTIAL_VISIBLE_CHARS..]
+        .iter()
+        .collect();
+    format!("{MASK}{visible}")
+}
+
+fn hash(text: &str) -> String {
+    digest(&SHA256, text.as_bytes()).as_ref()[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn masking_of<'a>(config: &'a Config, table: &str) -> &'a [MaskingConfig] {
+    let Some(owner) = config.table_owner(table) else {
+        return &[];
+    };
+    if let Some(adapter) = config.adapters.get(owner) {
+        &adapter.masking
+    } else if let Some(model) = config.models.get(owner) {
+        &model.masking
+    } else {
+        &[]
+    }
+}
+
+// A column masked differently by two tables gets the stronger mask.
+fn add_masks<'a>(masks: &mut Masks, masking: impl IntoIterator<Item = &'a MaskingConfig>) {
+    for column in masking {
+        masks
+            .entry(column.column.clone())
+            .and_modify(|mask| {
+                if *mask != column.mask {
+                    *mask = MaskMode::Full;
+                }
+            })
+            .or_insert(column.mask);
+    }
+}
+
+pub fn table_masks(config: &Config, table: &str) -> Masks {
+    let mut masks = Masks::new();
+    add_masks(&mut masks, masking_of(config, table));
+    masks
+}
+
+// Output columns are matched by name against the masked columns of the tables the
+// SQL reads. When those tables can't all be resolved, e.g. behind a CTE, every mask
+// in the project applies instead.
+pub fn query_masks(config: &Config, sql: &str) -> Result<Masks, MaskedReference> {
+    let mut masks = Masks::new();
+    match dependent_tables(sql) {
+        Ok(tables) if tables.iter().all(|table| config.defines_table(table)) => {
+            for table in tables {
+                add_masks(&mut masks, masking_of(config, &table));
+            }
+        }
+        _ => {
+            let adapters = config.adapters.values().flat_map(|a| &a.masking);
+            add_masks(&mut masks, adapters);
+            add_masks(&mut masks, config.models.values().flat_map(|m| &m.masking));
+        }
+    }
+    check_references(&masks, sql)?;
+    Ok(masks)
+}
+
+// Refuses SQL whose output could carry a masked column under another name. Only
+// the columns a query returns are checked; filtering or joining on one is fine.
+fn check_references(masks: &Masks, sql: &str) -> Result<(), MaskedReference> {
+    if masks.is_empty() {
+        return Ok(());
+    }
+    match Parser::parse_sql(&DuckDbDialect {}, sql) {
+        Ok(statements) => statements.iter().try_for_each(|statement| match statement {
+            Statement::Query(query) => check_query(masks, query),
+            _ => Ok(()),
+        }),
+        // Without a parse there's no telling how a masked column is used.
+        Err(_) => refuse_mentions(masks, sql),
+    }
+}
+
+fn check_query(masks: &Masks, query: &Query) -> Result<(), MaskedReference> {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            if !cte.alias.columns.is_empty() {
+                refuse_mentions(masks, &cte.query.to_string())?;
+            }
+            check_query(masks, &cte.query)?;
+        }
+    }
+    check_body(masks, &query.body)
+}
+
+fn check_body(masks: &Masks, body: &SetExpr) -> Result<(), MaskedReference> {
+    match body {
+        SetExpr::Select(select) => {
+            for item in &select.projection {
+                check_item(masks, item)?;
+            }
+            for table in &select.from {
+                check_table(masks, &table.relation)?;
+                for join in &table.joins {
+                    check_table(masks, &join.relation)?;
+                }
+            }
+            Ok(())
+        }
+        SetExpr::Query(query) => check_query(masks, query),
+        // Later branches take their column names from the first.
+        SetExpr::SetOperation { left, right, .. } => {
+            check_body(masks, left)?;
+            refuse_mentions(masks, &right.to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_table(masks: &Masks, table: &TableFactor) -> Result<(), MaskedReference> {
+    match table {
+        TableFactor::Derived {
+            subquery, alias, ..
+        } => {
+            if alias
+                .as_ref()
+                .is_some_and(|alias| !alias.columns.is_empty())
+            {
+                refuse_mentions(masks, &subquery.to_string())?;
+            }
+            check_query(masks, subquery)
+        }
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => {
+            check_table(masks, &table_with_joins.relation)?;
+            for join in &table_with_joins.joins {
+                check_table(masks, &join.relation)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_item(masks: &Masks, item: &SelectItem) -> Result<(), MaskedReference> {
+    match item {
+        SelectItem::UnnamedExpr(Expr::Identifier(column)) => check_column(masks, column),
+        SelectItem::UnnamedExpr(Expr::CompoundIdentifier(parts)) => match parts.last() {
+            Some(column) => check_column(masks, column),
+            None => Ok(()),
+        },
+        SelectItem::Wildcard(options) | SelectItem::QualifiedWildcard(_, options) => {
+            if let Some(rename) = &options.opt_rename {
+                refuse_mentions(masks, &rename.to_string())?;
+            }
+            if let Some(replace) = &options.opt_replace {
+                refuse_mentions(masks, &replace.to_string())?;
+            }
+            Ok(())
+        }
+        item => refuse_mentions(masks, &item.to_string()),
+    }
+}
+
+// A bare column keeps its name, unless it's written in another case.
+fn check_column(masks: &Masks, column: &Ident) -> Result<(), MaskedReference> {
+    if masks.contains_key(&column.value) {
+        return Ok(());
+    }
+    refuse_mentions(masks, &column.value)
+}
+
+fn refuse_mentions(masks: &Masks, sql: &str) -> Result<(), MaskedReference> {
+    let sql = sql.to_lowercase();
+    let words: Option<Vec<String>> =
+        Tokenizer::new(&DuckDbDialect {}, &sql)
+            .tokenize()
+            .ok()
+            .map(|tokens| {
+                tokens
+                    .into_iter()
+                    .filter_map(|token| match token {
+                        Token::Word(word) => Some(word.value),
+                        _ => None,
+                    })
+                    .collect()
+            });
+    let mentioned = masks.keys().filter(|column| {
+        let column = column.to_lowercase();
+        match &words {
+            Some(words) => words.contains(&column),
+            None => sql.contains(&column),
+        }
+    });
+    match mentioned.min() {
+        Some(column) => Err(MaskedReference(column.clone())),
+        None => Ok(()),
+    }
+}
+
+pub fn mask_values(masks: &Masks, column: &str, values: &mut [Value]) {
+    if let Some(mask) = masks.get(column) {
+        for value in values {
+            *value = mask.apply(value);
+        }
+    }
+}
+
+// Wraps `sql` so the masked ones among its output `columns` come out masked.
+pub fn masked_sql(masks: &Masks, columns: &[String], sql: &str) -> String {
+    let replaced: Vec<String> = columns
+        .iter()
+        .filter_map(|column| {
+            let mask = masks.get(column)?;
+            Some(format!("{} AS {}", mask.sql(column), quote_ident(column)))
+        })
+        .collect();
+    if replaced.is_empty() {
+        return sql.to_string();
+    }
+
+    let sql = sql.trim().trim_end_matches(';');
+    format!(
+        "SELECT * REPLACE ({}) FROM ({sql}) AS masked",
+        replaced.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::model::ModelConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask_modes() {
+        let email = json!("alice@example.com");
+        assert_eq!(MaskMode::Full.apply(&email), json!("***"));
+        assert_eq!(MaskMode::Partial.apply(&email), json!("a***@example.com"));
+        assert_eq!(
+            MaskMode::Partial.apply(&json!("090-1234-5678")),
+            json!("***5678")
+        );
+        assert_eq!(MaskMode::Partial.apply(&json!(1234)), json!("***"));
+        assert_eq!(MaskMode::Full.apply(&Value::Null), Value::Null);
+
+        let hashed = MaskMode::Hash.apply(&email);
+        assert_eq!(hashed, MaskMode::Hash.apply(&json!("alice@example.com")));
+        assert_ne!(hashed, MaskMode::Hash.apply(&json!("bob@example.com")));
+        assert_eq!(hashed.as_str().unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_query_masks() {
+        let mut config = Config::new(std::env::temp_dir());
+        let users: ModelConfig =
+            serde_yml::from_str("sql: SELECT 1\nmasking:\n  - column: email\n    mask: partial\n")
+                .unwrap();
+        let orders: ModelConfig =
+            serde_yml::from_str("sql: SELECT 1\nmasking:\n  - column: email\n    mask: hash\n")
+                .unwrap();
+        config.models.insert("users".to_string(), users);
+        config.models.insert("orders".to_string(), orders);
+        config.models.insert(
+            "plain".to_string(),
+            serde_yml::from_str("sql: SELECT 1").unwrap(),
+        );
+
+        let masks = query_masks(&config, "SELECT email FROM users").unwrap();
+        assert_eq!(
+            masks,
+            Masks::from([("email".to_string(), MaskMode::Partial)])
+        );
+        assert!(
+            query_masks(&config, "SELECT email FROM plain")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            query_masks(&config, "SELECT * FROM users JOIN orders USING (email)").unwrap()["email"],
+            MaskMode::Full
+        );
+        // Tables behind a CTE can't be told apart, so every mask applies.
+        assert_eq!(
+            query_masks(&config, "WITH p AS (SELECT * FROM plain) SELECT * FROM p").unwrap()["email"],
+            MaskMode::Full
+        );
+        assert_eq!(table_masks(&config, "orders")["email"], MaskMode::Hash);
+    }
+
+    #[test]
+    fn test_query_masks_renamed_columns() {
+        let mut config = Config::new(std::env::temp_dir());
+        let users: ModelConfig =
+            serde_yml::from_str("sql: SELECT 1\nmasking:\n  - column: email\n    mask: partial\n")
+                .unwrap();
+        config.models.insert("users".to_string(), users);
+
+        for sql in [
+            "SELECT id, u.email FROM users u WHERE email LIKE '%@example.com'",
+            "SELECT * EXCLUDE (email) FROM users",
+            "SELECT email FROM (SELECT * FROM users) AS recent",
+            "SELECT id FROM users UNION ALL SELECT id FROM users",
+        ] {
+            assert!(query_masks(&config, sql).is_ok(), "{sql}");
+        }
+        for sql in [
+            "SELECT email AS contact FROM users",
+            "SELECT lower(email) FROM users",
+            "SELECT EMAIL FROM users",
+            "SELECT contact FROM (SELECT email AS contact FROM users) AS renamed",
+            "WITH r(contact) AS (SELECT email FROM users) SELECT contact FROM r",
+            "SELECT id FROM users UNION ALL SELECT email FROM users",
+            "FROM users SELECT email AS contact",
+        ] {
+            assert_eq!(
+                query_masks(&config, sql),
+                Err(MaskedReference("email".to_string())),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_masked_sql() {
+        let masks = Masks::from([("email".to_string(), MaskMode::Hash)]);
+        let columns = ["id".to_string(), "email".to_string()];
+
+        let sql = masked_sql(&masks, &columns, "SELECT id, email FROM users;");
+        assert_eq!(
+            sql,
+            "SELECT * REPLACE (left(sha256(CAST(\"email\" AS VARCHAR)), 16) AS \"email\") \
+             FROM (SELECT id, email FROM users) AS masked"
+        );
+        assert_eq!(
+            masked_sql(&masks, &columns[..1], "SELECT id FROM users"),
+            "SELECT id FROM users"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+// Metric names are part of the operational interface: dashboards and alerts
+// depend on them, so rename only with care.
+
+// Counter, labels `status` ("completed" | "failed" | "cancelled"): finished
+// pipeline runs; "failed" means at least one task failed.
+pub const PIPELINE_RUNS: &str = "duckhub_pipeline_runs_total";
+// Histogram, seconds: wall-clock time of a whole pipeline run.
+pub const PIPELINE_DURATION: &str = "duckhub_pipeline_duration_seconds";
+// Histogram, seconds, labels `task`: time spent building one adapter, model or seed.
+pub const TASK_DURATION: &str = "duckhub_task_duration_seconds";
+// Counter, labels `task`: task runs that errored or failed their data tests.
+pub const TASK_FAILURES: &str = "duckhub_task_failures_total";
+// Counter, labels `adapter`: rows written by adapter imports.
+pub const ROWS_IMPORTED: &str = "duckhub_rows_imported_total";
+// Counter, labels `method`, `path` (route template), `status`: API requests served.
+pub const HTTP_REQUESTS: &str = "duckhub_http_requests_total";
+// Histogram, seconds, labels `method`, `path`: API request latency.
+pub const HTTP_REQUEST_DURATION: &str = "duckhub_http_request_duration_seconds";
+// Gauges: DuckDB connection pool size, open connections and idle connections.
+pub const DUCKDB_POOL_MAX: &str = "duckhub_duckdb_pool_max_connections";
+pub const DUCKDB_POOL_CONNECTIONS: &str = "duckhub_duckdb_pool_connections";
+pub const DUCKDB_POOL_IDLE: &str = "duckhub_duckdb_pool_idle_connections";
+// Gauges: API queries currently running and the configured limit.
+pub const API_QUERIES_IN_FLIGHT: &str = "duckhub_api_queries_in_flight";
+pub const API_QUERIES_MAX: &str = "duckhub_api_queries_max";
+// Counter: API queries turned away with 429 because the limit stayed reached.
+pub const API_QUERIES_REJECTED: &str = "duckhub_api_queries_rejected_total";
+
+const REQUEST_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+const TASK_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0, 3600.0];
+
+// Installs the process-wide Prometheus recorder on first use; metrics recorded
+// before that are dropped.
+pub fn handle() -> &'static PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+    HANDLE.get_or_init(|| {
+        let buckets = [
+            (HTTP_REQUEST_DURATION, REQUEST_BUCKETS),
+            (PIPELINE_DURATION, TASK_BUCKETS),
+            (TASK_DURATION, TASK_BUCKETS),
+        ];
+        let handle = buckets
+            .into_iter()
+            .try_fold(PrometheusBuilder::new(), |builder, (name, buckets)| {
+                builder.set_buckets_for_metric(Matcher::Full(name.to_string()), buckets)
+            })
+            .expect("histogram buckets are not empty")
+            .install_recorder()
+            .expect("the Prometheus recorder is only installed once");
+        describe();
+        handle
+    })
+}
+
+fn describe() {
+    describe_counter!(PIPELINE_RUNS, "Finished pipeline runs by outcome");
+    describe_histogram!(
+        PIPELINE_DURATION,
+        Unit::Seconds,
+        "Duration of pipeline runs"
+    );
+    describe_histogram!(TASK_DURATION, Unit::Seconds, "Duration of pipeline tasks");
+    describe_counter!(TASK_FAILURES, "Failed pipeline tasks");
+    describe_counter!(ROWS_IMPORTED, "Rows imported by adapters");
+    describe_counter!(HTTP_REQUESTS, "API requests served");
+    describe_histogram!(
+        HTTP_REQUEST_DURATION,
+        Unit::Seconds,
+        "Latency of API requests"
+    );
+    describe_gauge!(
+        DUCKDB_POOL_MAX,
+        "Maximum size of the DuckDB connection pool"
+    );
+    describe_gauge!(DUCKDB_POOL_CONNECTIONS, "Open DuckDB pool connections");
+    describe_gauge!(DUCKDB_POOL_IDLE, "Idle DuckDB pool connections");
+    describe_gauge!(API_QUERIES_IN_FLIGHT, "API queries currently running");
+    describe_gauge!(API_QUERIES_MAX, "Maximum concurrent API queries");
+    describe_counter!(API_QUERIES_REJECTED, "API queries rejected by the limit");
+}
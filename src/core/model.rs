@@ -1,36 +1,122 @@
-use crate::core::{config::model::ModelConfig, ducklake::DuckLake};
+use crate::core::{
+    config::model::{Materialization, ModelConfig},
+    ducklake::{DuckLake, quote_ident},
+    graph::TableStats,
+};
 use anyhow::{Context, Result};
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 #[derive(Clone)]
 pub struct Model {
     config: ModelConfig,
     ducklake: Arc<DuckLake>,
+    full_refresh: bool,
 }
 
 impl Model {
     pub fn new(config: ModelConfig, ducklake: Arc<DuckLake>) -> Self {
-        Self { config, ducklake }
+        Self {
+            config,
+            ducklake,
+            full_refresh: false,
+        }
     }
 
-    pub async fn transform(&self, table_name: &str) -> Result<()> {
-        let create_table_sql = format!(
-            "CREATE OR REPLACE TABLE {} AS ({});",
-            table_name, self.config.sql
-        );
+    pub fn with_full_refresh(mut self, full_refresh: bool) -> Self {
+        self.full_refresh = full_refresh;
+        self
+    }
 
-        self.ducklake
-            .execute_batch(&create_table_sql)
-            .with_context(|| {
-                format!("Failed to execute model transformation. SQL: {create_table_sql}")
-            })?;
-        Ok(())
+    // Tables are built into a staging table and swapped in, and views and incremental
+    // updates run in a transaction, so a failed build leaves the previous relation intact.
+    pub async fn transform(&self, table_name: &str) -> Result<TableStats> {
+        let started = Instant::now();
+        let existing = self.ducklake.relation_type(table_name)?;
+        let target = quote_ident(table_name);
+
+        let result = match self.config.materialization {
+            Materialization::Table => self
+                .ducklake
+                .replace_table_from_query(table_name, &self.config.sql),
+            Materialization::View => self.execute_in_transaction(&format!(
+                "{}CREATE OR REPLACE VIEW {target} AS ({});",
+                drop_relation_sql(existing.as_deref(), "BASE TABLE", &target),
+                self.config.sql
+            )),
+            Materialization::Incremental => {
+                if self.full_refresh || existing.as_deref() != Some("BASE TABLE") {
+                    self.ducklake
+                        .replace_table_from_query(table_name, &self.config.sql)
+                } else {
+                    self.execute_in_transaction(&self.incremental_sql(&target)?)
+                }
+            }
+        };
+        result.with_context(|| {
+            format!(
+                "Failed to execute model transformation. SQL: {}",
+                self.config.sql
+            )
+        })?;
+
+        TableStats::collect(&self.ducklake, table_name, started)
+    }
+
+    // The incremental merge deletes rows before inserting their replacements, so a
+    // failure in between must not leave the deletes behind.
+    fn execute_in_transaction(&self, sql: &str) -> Result<()> {
+        self.ducklake.with_transaction(|connection| {
+            connection.execute_batch(sql)?;
+            Ok(())
+        })
+    }
+
+    // Stages rows past the watermark, keeping the latest row of each key, replaces any
+    // existing rows with the same key, then appends the staged rows.
+    fn incremental_sql(&self, target: &str) -> Result<String> {
+        let (Some(unique_key), Some(updated_at_column)) =
+            (&self.config.unique_key, &self.config.updated_at_column)
+        else {
+            return Err(anyhow::anyhow!(
+                "Incremental models require 'unique_key' and 'updated_at_column'"
+            ));
+        };
+        let unique_key = quote_ident(unique_key);
+        let updated_at_column = quote_ident(updated_at_column);
+        let staging = format!("temp_incremental_{}", uuid::Uuid::new_v4().simple());
+
+        Ok(format!(
+            "CREATE TEMP TABLE {staging} AS \
+             SELECT * FROM ({sql}) AS source \
+             WHERE (SELECT max({updated_at_column}) FROM {target}) IS NULL \
+             OR {updated_at_column} > (SELECT max({updated_at_column}) FROM {target}) \
+             QUALIFY row_number() OVER (PARTITION BY {unique_key} ORDER BY {updated_at_column} DESC) = 1; \
+             DELETE FROM {target} WHERE {unique_key} IN (SELECT {unique_key} FROM {staging}); \
+             INSERT INTO {target} BY NAME SELECT * FROM {staging}; \
+             DROP TABLE {staging};",
+            sql = self.config.sql
+        ))
+    }
+}
+
+// A model switching between view and table has to drop the old relation first.
+fn drop_relation_sql(existing: Option<&str>, conflicting: &str, target: &str) -> String {
+    match existing {
+        Some(existing) if existing == conflicting => {
+            let kind = if conflicting == "VIEW" {
+                "VIEW"
+            } else {
+                "TABLE"
+            };
+            format!("DROP {kind} {target}; ")
+        }
+        _ => String::new(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::config::project::StorageConfig;
+    use crate::core::{adapter::test_helpers::setup_test_ducklake, config::project::StorageConfig};
 
     use super::*;
 
@@ -38,6 +124,16 @@ mod tests {
         ModelConfig {
             sql: "SELECT * FROM test_table".to_string(),
             description: None,
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
         }
     }
 
@@ -58,4 +154,156 @@ mod tests {
         let model = Model::new(config, ducklake);
         assert_eq!(model.config.sql, "SELECT * FROM test_table");
     }
+
+    fn model_config(sql: &str, materialization: Materialization) -> ModelConfig {
+        ModelConfig {
+            sql: sql.to_string(),
+            description: None,
+            materialization,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        }
+    }
+
+    fn relation_type(ducklake: &Arc<DuckLake>, name: &str) -> Option<String> {
+        ducklake.relation_type(name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transform_table_and_view() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.create_table_from_query(
+            "users",
+            "SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob')) t(id, name)",
+        )?;
+
+        let sql = "SELECT * FROM users WHERE id > 1";
+        let stats = Model::new(model_config(sql, Materialization::Table), ducklake.clone())
+            .transform("active_users")
+            .await?;
+        assert_eq!(stats.row_count, 1);
+        assert_eq!(
+            relation_type(&ducklake, "active_users").as_deref(),
+            Some("BASE TABLE")
+        );
+
+        // Switching to a view replaces the table and tracks the source.
+        let stats = Model::new(model_config(sql, Materialization::View), ducklake.clone())
+            .transform("active_users")
+            .await?;
+        assert_eq!(stats.row_count, 1);
+        assert_eq!(
+            relation_type(&ducklake, "active_users").as_deref(),
+            Some("VIEW")
+        );
+
+        ducklake.create_table_from_query(
+            "users",
+            "SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol')) t(id, name)",
+        )?;
+        let result = ducklake.query("SELECT COUNT(*) FROM active_users")?;
+        assert_eq!(result[0][0], "2");
+
+        Model::new(model_config(sql, Materialization::Table), ducklake.clone())
+            .transform("active_users")
+            .await?;
+        assert_eq!(
+            relation_type(&ducklake, "active_users").as_deref(),
+            Some("BASE TABLE")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_transform_keeps_previous_table() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.create_table_from_query(
+            "users",
+            "SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob')) t(id, name)",
+        )?;
+        Model::new(
+            model_config("SELECT * FROM users", Materialization::Table),
+            ducklake.clone(),
+        )
+        .transform("users_copy")
+        .await?;
+
+        // The build fails while the staging table is being filled.
+        let sql = "SELECT id, CASE WHEN id = 2 THEN error('boom') ELSE name END AS name FROM users";
+        let result = Model::new(model_config(sql, Materialization::Table), ducklake.clone())
+            .transform("users_copy")
+            .await;
+        assert!(result.is_err());
+
+        let rows = ducklake.query("SELECT COUNT(*) FROM users_copy")?;
+        assert_eq!(rows[0][0], "2");
+        let staging = ducklake.query(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_name LIKE 'staging_%'",
+        )?;
+        assert_eq!(staging[0][0], "0");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transform_incremental() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        ducklake.create_table_from_query(
+            "events",
+            "SELECT * FROM (VALUES (1, 'a', 1), (2, 'b', 2)) t(id, value, updated_at)",
+        )?;
+
+        let config = ModelConfig {
+            unique_key: Some("id".to_string()),
+            updated_at_column: Some("updated_at".to_string()),
+            ..model_config("SELECT * FROM events", Materialization::Incremental)
+        };
+        let model = Model::new(config, ducklake.clone());
+
+        let stats = model.transform("events_latest").await?;
+        assert_eq!(stats.row_count, 2);
+
+        // Row 1 changes without a newer watermark, row 2 is updated, row 3 is new and
+        // row 4 arrives twice, of which only the latest is kept.
+        ducklake.create_table_from_query(
+            "events",
+            "SELECT * FROM (VALUES (1, 'a_changed', 1), (2, 'b2', 4), (3, 'c', 3), (4, 'd1', 5), (4, 'd2', 6)) t(id, value, updated_at)",
+        )?;
+
+        let stats = model.transform("events_latest").await?;
+        assert_eq!(stats.row_count, 4);
+
+        let rows = ducklake.query("SELECT id, value FROM events_latest ORDER BY id")?;
+        assert_eq!(
+            rows,
+            vec![
+                vec!["1".to_string(), "a".to_string()],
+                vec!["2".to_string(), "b2".to_string()],
+                vec!["3".to_string(), "c".to_string()],
+                vec!["4".to_string(), "d2".to_string()],
+            ]
+        );
+
+        // A full refresh rebuilds from scratch.
+        model
+            .clone()
+            .with_full_refresh(true)
+            .transform("events_latest")
+            .await?;
+        let rows = ducklake.query("SELECT value FROM events_latest WHERE id = 1")?;
+        assert_eq!(rows[0][0], "a_changed");
+
+        Ok(())
+    }
 }
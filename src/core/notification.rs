@@ -0,0 +1,265 @@
+use crate::core::{
+    config::{
+        notification::{EmailConfig, NotificationConfig, TlsMode},
+        project::{ProjectConfig, ServerConfig},
+    },
+    pipeline::{Phase, Pipeline},
+};
+use anyhow::{Context, Result};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use std::time::Duration;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+// What every channel delivers: a subject line and a plain-text body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub subject: String,
+    pub body: String,
+}
+
+impl Notice {
+    // None when no task failed.
+    pub fn pipeline_failed(pipeline: &Pipeline, server: &ServerConfig) -> Option<Self> {
+        let mut failed: Vec<(&str, &str)> = pipeline
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.phase() == &Phase::Failed)
+            .map(|(name, task)| {
+                (
+                    name.as_str(),
+                    task.error_message().unwrap_or("unknown error"),
+                )
+            })
+            .collect();
+        if failed.is_empty() {
+            return None;
+        }
+        failed.sort();
+
+        let mut body = format!(
+            "Pipeline {} failed: {} of {} tasks did not complete.\n\n",
+            pipeline.id,
+            failed.len(),
+            pipeline.tasks.len()
+        );
+        for (name, error) in &failed {
+            // Errors carry their whole cause chain; the first line says what went wrong.
+            let summary = error.lines().next().unwrap_or_default();
+            body.push_str(&format!("- {name}: {summary}\n"));
+        }
+        if let Some(base_url) = server.public_base_url() {
+            body.push_str("\nTask logs:\n");
+            for (name, _) in &failed {
+                body.push_str(&format!(
+                    "- {base_url}/api/pipelines/{}/tasks/{name}/logs\n",
+                    pipeline.id
+                ));
+            }
+        }
+
+        Some(Self {
+            subject: format!("[duckhub] Pipeline failed: {}", failed_names(&failed)),
+            body,
+        })
+    }
+
+    pub fn test() -> Self {
+        Self {
+            subject: "[duckhub] Test notification".to_string(),
+            body: "This is a test notification from duckhub. Pipeline failures will be reported here.\n".to_string(),
+        }
+    }
+}
+
+fn failed_names(failed: &[(&str, &str)]) -> String {
+    const SHOWN: usize = 3;
+    let names = failed
+        .iter()
+        .take(SHOWN)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    match failed.len().saturating_sub(SHOWN) {
+        0 => names,
+        more => format!("{names} and {more} more"),
+    }
+}
+
+pub async fn send(channel: &NotificationConfig, notice: &Notice) -> Result<()> {
+    match channel {
+        NotificationConfig::Email(config) => {
+            let transport = smtp_transport(config)?;
+            let message = compose_email(config, notice)?;
+            transport.send(message).await.with_context(|| {
+                format!(
+                    "Failed to send email through {}:{}",
+                    config.host,
+                    config.port()
+                )
+            })?;
+            Ok(())
+        }
+    }
+}
+
+// Sends `notice` to every channel. A channel that fails is logged and does not stop
+// the others; the results come back in channel name order.
+pub async fn send_all(project: &ProjectConfig, notice: &Notice) -> Vec<(String, Result<()>)> {
+    let mut names = project.notifications.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let mut results = Vec::new();
+    for name in names {
+        let result = send(&project.notifications[name], notice).await;
+        if let Err(error) = &result {
+            tracing::error!("Notification '{name}' failed: {error:#}");
+        }
+        results.push((name.clone(), result));
+    }
+    results
+}
+
+pub fn compose_email(config: &EmailConfig, notice: &Notice) -> Result<Message> {
+    let mailbox = |address: &str| {
+        address
+            .parse::<Mailbox>()
+            .with_context(|| format!("Invalid email address '{address}'"))
+    };
+
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from)?)
+        .subject(&notice.subject)
+        .header(ContentType::TEXT_PLAIN);
+    for to in &config.to {
+        builder = builder.to(mailbox(to)?);
+    }
+    builder
+        .body(notice.body.clone())
+        .context("Failed to build email")
+}
+
+fn smtp_transport(config: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let builder = match config.tls {
+        TlsMode::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+        TlsMode::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+            .context("Failed to set up STARTTLS")?,
+        TlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+            .context("Failed to set up TLS")?,
+    };
+    let mut builder = builder.port(config.port()).timeout(Some(SMTP_TIMEOUT));
+
+    if let Some(username) = &config.username {
+        let password = match &config.password {
+            Some(password) => password.plaintext()?.to_string(),
+            None => String::new(),
+        };
+        builder = builder.credentials(Credentials::new(username.clone(), password));
+    }
+
+    Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lettre::transport::stub::AsyncStubTransport;
+
+    fn email_config() -> EmailConfig {
+        EmailConfig {
+            host: "127.0.0.1".to_string(),
+            port: None,
+            username: None,
+            password: None,
+            from: "DuckHub <duckhub@example.com>".to_string(),
+            to: vec!["data-team@example.com".to_string()],
+            tls: TlsMode::None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failure_email() -> Result<()> {
+        let mut pipeline: Pipeline = serde_json::from_value(serde_json::json!({
+            "phase": "completed",
+            "started_at": null,
+            "completed_at": null,
+            "tasks": {
+                "orders": {
+                    "phase": "failed",
+                    "started_at": null,
+                    "completed_at": null,
+                    "error": {
+                        "message": "Failed to read 'orders.csv'\n\nCaused by:\n    No such file",
+                        "at": "2024-01-01T00:00:00Z"
+                    }
+                },
+                "users": {
+                    "phase": "completed",
+                    "started_at": null,
+                    "completed_at": null,
+                    "error": null
+                }
+            }
+        }))?;
+        pipeline.id = "20240101000000".to_string();
+        let server = ServerConfig {
+            public_url: Some("https://duckhub.example.com/".to_string()),
+            base_path: Some("/hub".to_string()),
+            ..Default::default()
+        };
+
+        let notice = Notice::pipeline_failed(&pipeline, &server).unwrap();
+        assert_eq!(notice.subject, "[duckhub] Pipeline failed: orders");
+        assert!(notice.body.contains("1 of 2 tasks did not complete"));
+        assert!(
+            notice
+                .body
+                .contains("- orders: Failed to read 'orders.csv'\n")
+        );
+        assert!(!notice.body.contains("Caused by"));
+        assert!(notice.body.contains(
+            "https://duckhub.example.com/hub/api/pipelines/20240101000000/tasks/orders/logs"
+        ));
+
+        let transport = AsyncStubTransport::new_ok();
+        transport
+            .send(compose_email(&email_config(), &notice)?)
+            .await?;
+        let messages = transport.messages().await;
+        assert_eq!(messages.len(), 1);
+        let (envelope, raw) = &messages[0];
+        assert_eq!(envelope.to()[0].to_string(), "data-team@example.com");
+        assert!(raw.contains("Subject: [duckhub] Pipeline failed: orders"));
+        assert!(raw.contains("From: DuckHub <duckhub@example.com>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_notice_without_failures() -> Result<()> {
+        let pipeline: Pipeline = serde_json::from_value(serde_json::json!({
+            "phase": "completed",
+            "started_at": null,
+            "completed_at": null,
+            "tasks": {}
+        }))?;
+        assert!(Notice::pipeline_failed(&pipeline, &ServerConfig::default()).is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_connection_failure() {
+        // Nothing listens on port 1, so the connection is refused.
+        let channel = NotificationConfig::Email(EmailConfig {
+            port: Some(1),
+            ..email_config()
+        });
+
+        let error = send(&channel, &Notice::test()).await.unwrap_err();
+        assert!(format!("{error:#}").contains("Failed to send email through 127.0.0.1:1"));
+    }
+}
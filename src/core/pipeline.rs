@@ -1,5 +1,16 @@
 use crate::core::{
-    adapter::Adapter, config::Config, ducklake::DuckLake, graph::Graph, model::Model,
+    adapter::{Adapter, ImportProgress, Progress, SchemaDiff, SchemaDrift, database::Attachments},
+    config::Config,
+    data_test::{DataTestResult, run_data_tests, run_relationship_tests},
+    ducklake::DuckLake,
+    graph::{FileCounts, Graph, TableStats},
+    metrics::{PIPELINE_DURATION, PIPELINE_RUNS, ROWS_IMPORTED, TASK_DURATION, TASK_FAILURES},
+    model::Model,
+    notification::{self, Notice},
+    run_history::RunHistory,
+    seed::Seed,
+    task_log::task_span,
+    template,
 };
 use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
@@ -7,10 +18,28 @@ use futures::future;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
+    fmt,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
-use tokio::{fs, sync::Mutex};
+use tokio::{
+    fs,
+    sync::{Mutex, Semaphore, SemaphorePermit, broadcast},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use utoipa::ToSchema;
+
+const EVENT_CAPACITY: usize = 256;
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Bumped when the file format changes incompatibly. Files written before the field
+// existed read as version 0.
+pub const PIPELINE_SCHEMA_VERSION: u32 = 1;
+// Task transitions are written at most this often while a run is in progress; phase
+// changes of the run itself are written right away.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskStatus {
@@ -18,6 +47,20 @@ pub struct TaskStatus {
     started_at: Option<DateTime<Utc>>,
     completed_at: Option<DateTime<Utc>>,
     error: Option<ErrorInfo>,
+    #[serde(default)]
+    row_count: Option<u64>,
+    #[serde(default)]
+    duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duplicates_dropped: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    files: Option<FileCounts>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_changes: Option<SchemaDiff>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    progress: Option<Progress>,
+    #[serde(default)]
+    tests: Vec<DataTestResult>,
 }
 
 impl Default for TaskStatus {
@@ -33,18 +76,54 @@ impl TaskStatus {
             phase: Phase::Waiting,
             completed_at: None,
             error: None,
+            row_count: None,
+            duration_ms: None,
+            duplicates_dropped: None,
+            files: None,
+            schema_changes: None,
+            progress: None,
+            tests: Vec::new(),
         }
     }
 
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn progress(&self) -> Option<&Progress> {
+        self.progress.as_ref()
+    }
+
+    pub fn error_message(&self) -> Option<&str> {
+        self.error.as_ref().map(|error| error.message.as_str())
+    }
+
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.started_at
+    }
+
+    // Failed tasks have no `completed_at`; they ended when the error was recorded.
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.completed_at
+            .or_else(|| self.error.as_ref().map(|error| error.at))
+    }
+
     fn start(&mut self) {
         self.phase = Phase::Running;
         self.started_at = Some(Utc::now());
     }
 
-    fn complete(&mut self) {
+    fn complete(&mut self, output: &TaskOutput) {
         self.phase = Phase::Completed;
         self.completed_at = Some(Utc::now());
         self.error = None;
+        self.row_count = Some(output.stats.row_count);
+        self.duration_ms = Some(output.stats.duration_ms);
+        self.duplicates_dropped =
+            Some(output.stats.duplicates_dropped).filter(|dropped| *dropped > 0);
+        self.files = output.stats.files;
+        self.schema_changes = output.stats.schema_changes.clone();
+        self.tests = output.tests.clone();
     }
 
     fn fail(&mut self, error_message: String) {
@@ -56,7 +135,7 @@ impl TaskStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Phase {
     Waiting,
@@ -71,32 +150,239 @@ pub struct ErrorInfo {
     at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineEvent {
+    Snapshot {
+        phase: Phase,
+        tasks: HashMap<String, TaskStatus>,
+    },
+    TaskStarted {
+        task: String,
+    },
+    TaskCompleted {
+        task: String,
+        row_count: u64,
+        duration_ms: u64,
+    },
+    TaskFailed {
+        task: String,
+        error: String,
+    },
+    TaskProgress {
+        task: String,
+        progress: Progress,
+    },
+    PipelineCompleted,
+}
+
+impl PipelineEvent {
+    // Whether nothing follows this event: the run completed, failed or was aborted.
+    pub fn ends_run(&self) -> bool {
+        matches!(
+            self,
+            Self::PipelineCompleted
+                | Self::Snapshot {
+                    phase: Phase::Completed | Phase::Failed,
+                    ..
+                }
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pipeline {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default, skip_deserializing)]
+    pub id: String,
     pub phase: Phase,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub tasks: HashMap<String, TaskStatus>,
     #[serde(skip)]
     filepath: PathBuf,
+    #[serde(skip, default = "event_channel")]
+    events: broadcast::Sender<PipelineEvent>,
+    #[serde(skip)]
+    cancel: CancellationToken,
+    // Changes not yet written to `filepath`.
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    flushed_at: Option<std::time::Instant>,
+    #[serde(skip, default = "flush_interval")]
+    flush_interval: Duration,
+}
+
+fn event_channel() -> broadcast::Sender<PipelineEvent> {
+    broadcast::channel(EVENT_CAPACITY).0
+}
+
+fn flush_interval() -> Duration {
+    FLUSH_INTERVAL
+}
+
+// A run file that could not be read. It is reported rather than skipped, so a
+// corrupted file doesn't just make the history shorter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadIssue {
+    pub file: String,
+    pub error: String,
+}
+
+// Writes to a temporary file next to `path` and renames it into place, so a crash
+// leaves either the old or the new content. Both the file and the directory are
+// synced, since the rename is only durable once the directory entry is.
+fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let temp_path = path.with_extension("json.tmp");
+    {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&temp_path, path)?;
+    // Directories can't be opened for syncing on every platform.
+    if let Ok(dir) = std::fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+// Returned when a run is triggered while another one hasn't finished.
+#[derive(Debug)]
+pub struct PipelineBusy;
+
+impl fmt::Display for PipelineBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "A pipeline is already running")
+    }
+}
+
+impl std::error::Error for PipelineBusy {}
+
+// The pipeline started most recently by this process, so that API handlers can
+// follow its progress while it runs.
+#[derive(Default)]
+pub struct ActivePipeline {
+    current: Mutex<Option<Arc<Mutex<Pipeline>>>>,
+}
+
+impl ActivePipeline {
+    pub async fn get(&self) -> Option<Arc<Mutex<Pipeline>>> {
+        self.current.lock().await.clone()
+    }
+
+    #[cfg(test)]
+    async fn set(&self, pipeline: Arc<Mutex<Pipeline>>) {
+        *self.current.lock().await = Some(pipeline);
+    }
+
+    // Registers a new run unless the current one is still waiting to start or
+    // running. The check and the registration share one lock, so two concurrent
+    // triggers can't both start a run.
+    pub async fn claim(&self, project_dir: &Path) -> Result<Arc<Mutex<Pipeline>>, PipelineBusy> {
+        let mut current = self.current.lock().await;
+        if let Some(pipeline) = current.as_ref()
+            && matches!(pipeline.lock().await.phase, Phase::Waiting | Phase::Running)
+        {
+            return Err(PipelineBusy);
+        }
+
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(project_dir)));
+        *current = Some(pipeline.clone());
+        Ok(pipeline)
+    }
+
+    pub async fn is_running(&self) -> bool {
+        match self.get().await {
+            Some(pipeline) => pipeline.lock().await.phase == Phase::Running,
+            None => false,
+        }
+    }
+
+    #[cfg(test)]
+    pub async fn mark_running(&self, project_dir: &Path) {
+        let mut pipeline = Pipeline::new(project_dir);
+        pipeline.phase = Phase::Running;
+        self.set(Arc::new(Mutex::new(pipeline))).await;
+    }
+
+    // Stops the running pipeline from starting new tasks and gives in-flight tasks
+    // `grace` to finish; whatever is still unfinished after that is marked failed.
+    pub async fn shutdown(&self, grace: Duration) -> Result<()> {
+        let Some(pipeline) = self.get().await else {
+            return Ok(());
+        };
+
+        {
+            let pipeline = pipeline.lock().await;
+            if pipeline.phase != Phase::Running {
+                return Ok(());
+            }
+            pipeline.cancel.cancel();
+        }
+
+        let deadline = tokio::time::Instant::now() + grace;
+        while tokio::time::Instant::now() < deadline {
+            if pipeline.lock().await.phase != Phase::Running {
+                return Ok(());
+            }
+            tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+        }
+
+        let mut pipeline = pipeline.lock().await;
+        if pipeline.phase == Phase::Running {
+            pipeline
+                .abort("Server shut down before the task finished")
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 impl Pipeline {
     fn new(project_dir: &Path) -> Self {
         let pipeline_dir = Self::get_pipeline_dir(project_dir);
         let now = Utc::now();
-        let filename = now.format("%Y-%m-%d-%H-%M-%S.json").to_string();
-        let path = pipeline_dir.join(filename);
+        let id = now.format("%Y-%m-%d-%H-%M-%S").to_string();
+        let path = pipeline_dir.join(format!("{id}.json"));
 
         Self {
+            schema_version: PIPELINE_SCHEMA_VERSION,
+            id,
             filepath: path,
             phase: Phase::Waiting,
             started_at: None,
             completed_at: None,
             tasks: HashMap::new(),
+            events: event_channel(),
+            cancel: CancellationToken::new(),
+            dirty: false,
+            flushed_at: None,
+            flush_interval: FLUSH_INTERVAL,
         }
     }
 
+    // Subscribing while holding the pipeline lock guarantees that no event is
+    // missed between the snapshot and the first update.
+    pub fn subscribe(&self) -> (PipelineEvent, broadcast::Receiver<PipelineEvent>) {
+        let snapshot = PipelineEvent::Snapshot {
+            phase: self.phase.clone(),
+            tasks: self.tasks.clone(),
+        };
+        (snapshot, self.events.subscribe())
+    }
+
+    fn publish(&self, event: PipelineEvent) {
+        // Sending only fails when nobody is listening.
+        let _ = self.events.send(event);
+    }
+
     fn to_datetime(path: &Path) -> Option<DateTime<Utc>> {
         if "json" != path.extension()?.to_str()? {
             return None;
@@ -138,34 +424,62 @@ impl Pipeline {
         }
 
         if let Some(path) = latest_file {
-            let content = fs::read_to_string(&path).await?;
-            let pipeline = serde_json::from_str(&content)?;
-            Ok(Some(pipeline))
+            Ok(Some(Self::load_file(path).await.map_err(|issue| {
+                anyhow::anyhow!("Cannot read pipeline run {}: {}", issue.file, issue.error)
+            })?))
         } else {
             Ok(None)
         }
     }
 
-    pub async fn load_all(project_dir: &Path) -> Result<Vec<Self>> {
+    async fn load_file(path: PathBuf) -> Result<Self, LoadIssue> {
+        let issue = |error: String| LoadIssue {
+            file: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            error,
+        };
+
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| issue(e.to_string()))?;
+        let mut pipeline: Pipeline =
+            serde_json::from_str(&content).map_err(|e| issue(e.to_string()))?;
+        if pipeline.schema_version > PIPELINE_SCHEMA_VERSION {
+            return Err(issue(format!(
+                "Written by a newer DuckHub (schema version {}, this one reads up to {PIPELINE_SCHEMA_VERSION})",
+                pipeline.schema_version
+            )));
+        }
+        pipeline.set_filepath(path);
+        Ok(pipeline)
+    }
+
+    // Every run on disk, newest first, with the files that could not be read.
+    pub async fn load_all(project_dir: &Path) -> Result<(Vec<Self>, Vec<LoadIssue>)> {
         let pipeline_dir = Self::get_pipeline_dir(project_dir);
 
         if !pipeline_dir.exists() {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), Vec::new()));
         }
 
         let mut entries = tokio::fs::read_dir(&pipeline_dir).await?;
         let mut pipelines = Vec::new();
+        let mut issues = Vec::new();
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if path.extension().and_then(|ext| ext.to_str()) == Some("json")
-                && let Ok(content) = fs::read_to_string(&path).await
-                && let Ok(mut pipeline) = serde_json::from_str::<Pipeline>(&content)
-            {
-                pipeline.filepath = path;
-                pipelines.push(pipeline);
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match Self::load_file(path).await {
+                Ok(pipeline) => pipelines.push(pipeline),
+                Err(issue) => issues.push(issue),
             }
         }
+        issues.sort_by(|a, b| a.file.cmp(&b.file));
 
         pipelines.sort_by(|a, b| {
             let time_a = Self::to_datetime(&a.filepath);
@@ -178,7 +492,7 @@ impl Pipeline {
             }
         });
 
-        Ok(pipelines)
+        Ok((pipelines, issues))
     }
 
     async fn start(&mut self, tasks: &[String]) -> Result<()> {
@@ -199,10 +513,77 @@ impl Pipeline {
         project_dir.join(".data").join("pipelines")
     }
 
+    // The inverse of `get_pipeline_dir`, from `<project>/.data/pipelines/<id>.json`.
+    fn project_dir(&self) -> Option<&Path> {
+        self.filepath.parent()?.parent()?.parent()
+    }
+
+    fn set_filepath(&mut self, path: PathBuf) {
+        self.id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+        self.filepath = path;
+    }
+
+    // Logs of a run live next to its JSON file: `<pipelines>/<id>/logs/<task>.log`.
+    pub fn task_log_path(project_dir: &Path, id: &str, task: &str) -> PathBuf {
+        Self::get_pipeline_dir(project_dir)
+            .join(id)
+            .join("logs")
+            .join(format!("{task}.log"))
+    }
+
+    fn log_path(&self, task: &str) -> PathBuf {
+        self.filepath
+            .with_extension("")
+            .join("logs")
+            .join(format!("{task}.log"))
+    }
+
+    // Runs left `running` by a process that is gone (Ctrl-C past the grace period,
+    // a crash, kill -9) are closed out so the UI doesn't show them forever.
+    pub async fn recover_interrupted(
+        project_dir: &Path,
+        started_before: DateTime<Utc>,
+    ) -> Result<usize> {
+        let mut recovered = 0;
+        let (pipelines, _) = Self::load_all(project_dir).await?;
+        for mut pipeline in pipelines {
+            if pipeline.phase == Phase::Running
+                && pipeline
+                    .started_at
+                    .is_none_or(|started_at| started_at < started_before)
+            {
+                pipeline
+                    .abort("Interrupted by a server shutdown or crash")
+                    .await?;
+                recovered += 1;
+            }
+        }
+        Ok(recovered)
+    }
+
+    async fn abort(&mut self, reason: &str) -> Result<()> {
+        for task in self.tasks.values_mut() {
+            if matches!(task.phase, Phase::Waiting | Phase::Running) {
+                task.fail(reason.to_string());
+            }
+        }
+        self.phase = Phase::Failed;
+        self.completed_at = Some(Utc::now());
+        self.save().await?;
+        self.publish(PipelineEvent::PipelineCompleted);
+        Ok(())
+    }
+
     async fn complete(&mut self) -> Result<()> {
         self.phase = Phase::Completed;
         self.completed_at = Some(Utc::now());
-        self.save().await
+        self.save().await?;
+        self.publish(PipelineEvent::PipelineCompleted);
+        Ok(())
     }
 
     async fn waiting_task(&self) -> Vec<String> {
@@ -226,55 +607,252 @@ impl Pipeline {
         if let Some(task) = self.tasks.get_mut(name) {
             task.start();
         }
-        self.save().await
+        self.mark_dirty().await?;
+        self.publish(PipelineEvent::TaskStarted {
+            task: name.to_string(),
+        });
+        Ok(())
     }
 
-    async fn complete_task(&mut self, name: &str) -> Result<()> {
+    async fn complete_task(&mut self, name: &str, output: &TaskOutput) -> Result<()> {
         if let Some(task) = self.tasks.get_mut(name) {
-            task.complete();
+            task.complete(output);
+        }
+        self.mark_dirty().await?;
+        self.publish(PipelineEvent::TaskCompleted {
+            task: name.to_string(),
+            row_count: output.stats.row_count,
+            duration_ms: output.stats.duration_ms,
+        });
+        Ok(())
+    }
+
+    // Called from inside a running import, which is synchronous, so the file is
+    // written without going through the async runtime.
+    fn record_progress(&mut self, name: &str, progress: &Progress) -> Result<()> {
+        if let Some(task) = self.tasks.get_mut(name) {
+            task.progress = Some(progress.clone());
+        }
+        self.dirty = true;
+        if self.flush_due() {
+            write_atomically(
+                &self.filepath,
+                serde_json::to_string_pretty(&self)?.as_bytes(),
+            )?;
+            self.flushed();
+        }
+        self.publish(PipelineEvent::TaskProgress {
+            task: name.to_string(),
+            progress: progress.clone(),
+        });
+        Ok(())
+    }
+
+    async fn record_tests(&mut self, name: &str, tests: Vec<DataTestResult>) -> Result<()> {
+        if let Some(task) = self.tasks.get_mut(name) {
+            task.tests = tests;
+        }
+        self.mark_dirty().await
+    }
+
+    // Saved along with the failure that follows.
+    fn record_schema_changes(&mut self, name: &str, diff: SchemaDiff) {
+        if let Some(task) = self.tasks.get_mut(name) {
+            task.schema_changes = Some(diff);
         }
-        self.save().await
     }
 
     async fn fail_task(&mut self, name: &str, error_message: String) -> Result<()> {
         if let Some(task) = self.tasks.get_mut(name) {
-            task.fail(error_message);
+            task.fail(error_message.clone());
         }
-        self.save().await
+        self.mark_dirty().await?;
+        self.publish(PipelineEvent::TaskFailed {
+            task: name.to_string(),
+            error: error_message,
+        });
+        Ok(())
     }
 
-    async fn save(&self) -> Result<()> {
+    fn flush_due(&self) -> bool {
+        self.flushed_at
+            .is_none_or(|flushed_at| flushed_at.elapsed() >= self.flush_interval)
+    }
+
+    fn flushed(&mut self) {
+        self.dirty = false;
+        self.flushed_at = Some(std::time::Instant::now());
+    }
+
+    // Records a task transition, writing it only if the last write is old enough.
+    async fn mark_dirty(&mut self) -> Result<()> {
+        self.dirty = true;
+        if self.flush_due() {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    // Writes pending task transitions.
+    pub async fn flush(&mut self) -> Result<()> {
+        if self.dirty {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    async fn save(&mut self) -> Result<()> {
         let content = serde_json::to_string_pretty(&self)?;
-        if let Some(dir) = self.filepath.parent() {
-            fs::create_dir_all(dir).await?;
+        let path = self.filepath.clone();
+        tokio::task::spawn_blocking(move || write_atomically(&path, content.as_bytes())).await??;
+        self.flushed();
+
+        // The run itself is saved; a stale history index only costs a rebuild.
+        if let Some(project_dir) = self.project_dir()
+            && let Err(error) = RunHistory::update(project_dir, self).await
+        {
+            tracing::warn!("Failed to update run history: {error:#}");
         }
-        fs::write(&self.filepath, content).await?;
         Ok(())
     }
 }
 
+// What a successful build produced: table stats plus the outcome of its data tests.
+#[derive(Debug, Clone, Default)]
+pub struct TaskOutput {
+    pub stats: TableStats,
+    pub tests: Vec<DataTestResult>,
+}
+
+// How many worker slots a task occupies while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskWeight {
+    Slots(u32),
+    // Every slot, so the task runs alone.
+    Exclusive,
+}
+
+impl Default for TaskWeight {
+    fn default() -> Self {
+        Self::Slots(1)
+    }
+}
+
 #[async_trait::async_trait]
 pub trait TaskExecutor: Send + Sync {
-    async fn run(&self, name: &str) -> Result<()>;
+    async fn run(&self, name: &str, progress: ImportProgress) -> Result<TaskOutput>;
+
+    async fn weight(&self, _name: &str) -> TaskWeight {
+        TaskWeight::default()
+    }
 }
 
 pub struct Executor {
     ducklake: Arc<DuckLake>,
     config: Arc<Mutex<Config>>,
+    full_refresh: bool,
+    // Overrides for `{{ var() }}` in model SQL, on top of the project's vars.
+    vars: HashMap<String, String>,
+    attachments: Arc<Attachments>,
+}
+
+impl Executor {
+    fn new(ducklake: Arc<DuckLake>, config: Arc<Mutex<Config>>, full_refresh: bool) -> Self {
+        Self {
+            ducklake,
+            config,
+            full_refresh,
+            vars: HashMap::new(),
+            attachments: Arc::new(Attachments::default()),
+        }
+    }
+
+    fn with_vars(mut self, vars: HashMap<String, String>) -> Self {
+        self.vars = vars;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl TaskExecutor for Executor {
-    async fn run(&self, name: &str) -> Result<()> {
+    async fn run(&self, name: &str, progress: ImportProgress) -> Result<TaskOutput> {
         let config = self.config.lock().await;
-        if let Some(adapter_config) = config.adapters.get(name) {
-            let adapter = Adapter::new(adapter_config.clone(), Arc::clone(&self.ducklake));
-            adapter.import(name, &config.project.connections).await
-        } else if let Some(model_config) = config.models.get(name) {
-            let model = Model::new(model_config.clone(), Arc::clone(&self.ducklake));
-            model.transform(name).await
+        let (table, stats, tests, relationships) =
+            if let Some(adapter_config) = config.adapters.get(name) {
+                let table = adapter_config.table_name(name);
+                let adapter = Adapter::new(
+                    config.with_format_defaults(adapter_config.clone()),
+                    Arc::clone(&self.ducklake),
+                )
+                .with_project_dir(&config.project_dir)
+                .with_full_refresh(self.full_refresh)
+                .with_attachments(&self.attachments)
+                .with_progress(progress);
+                let stats = adapter.import(table, &config.project.connections).await?;
+                metrics::counter!(ROWS_IMPORTED, "adapter" => name.to_string())
+                    .increment(stats.row_count);
+                (
+                    table,
+                    stats,
+                    adapter_config.tests.as_slice(),
+                    adapter_config.relationships.as_slice(),
+                )
+            } else if let Some(model_config) = config.models.get(name) {
+                let mut compiled = model_config.clone();
+                compiled.sql = template::compile(&config, &model_config.sql, &self.vars)?.sql;
+                let model = Model::new(compiled, Arc::clone(&self.ducklake))
+                    .with_full_refresh(self.full_refresh);
+                (
+                    name,
+                    model.transform(name).await?,
+                    model_config.tests.as_slice(),
+                    model_config.relationships.as_slice(),
+                )
+            } else if let Some(seed_config) = config.seeds.get(name) {
+                let seed = Seed::new(seed_config.clone(), Arc::clone(&self.ducklake));
+                (name, seed.load(name)?, [].as_slice(), [].as_slice())
+            } else {
+                return Err(anyhow::anyhow!("Unknown task: {}", name));
+            };
+
+        config.check_relationships(relationships)?;
+        let relationships: Vec<_> = relationships
+            .iter()
+            .filter_map(|relationship| {
+                let (_, referenced) = config.resolve_table(&relationship.references.table)?;
+                Some((relationship, referenced))
+            })
+            .collect();
+
+        let mut tests = run_data_tests(&self.ducklake, table, tests)?;
+        tests.extend(run_relationship_tests(
+            &self.ducklake,
+            table,
+            &relationships,
+        )?);
+        for test in tests
+            .iter()
+            .filter(|test| !test.passed && !test.fails_task())
+        {
+            tracing::warn!("{}", test.describe_failure());
+        }
+        Ok(TaskOutput { stats, tests })
+    }
+
+    async fn weight(&self, name: &str) -> TaskWeight {
+        let config = self.config.lock().await;
+        let (weight, exclusive) = if let Some(adapter) = config.adapters.get(name) {
+            (adapter.weight, adapter.exclusive)
+        } else if let Some(model) = config.models.get(name) {
+            (model.weight, model.exclusive)
+        } else {
+            (None, false)
+        };
+
+        if exclusive {
+            TaskWeight::Exclusive
         } else {
-            Err(anyhow::anyhow!("Unknown task: {}", name))
+            TaskWeight::Slots(weight.unwrap_or(1))
         }
     }
 }
@@ -284,32 +862,94 @@ pub struct Worker<T: TaskExecutor> {
     pipeline: Arc<Mutex<Pipeline>>,
     executor: Arc<T>,
     graph: Arc<Mutex<Graph>>,
+    cancel: CancellationToken,
+    // One permit per worker. A running task holds as many as its weight, so an
+    // exclusive task waits for every other task to finish and blocks new ones.
+    slots: Arc<Semaphore>,
+    slot_count: u32,
 }
 
 impl<T: TaskExecutor> Worker<T> {
-    fn new(pipeline: Arc<Mutex<Pipeline>>, executor: Arc<T>, graph: Arc<Mutex<Graph>>) -> Self {
+    fn new(
+        pipeline: Arc<Mutex<Pipeline>>,
+        executor: Arc<T>,
+        graph: Arc<Mutex<Graph>>,
+        cancel: CancellationToken,
+        slots: Arc<Semaphore>,
+        slot_count: u32,
+    ) -> Self {
         Self {
             pipeline,
             executor,
             graph,
+            cancel,
+            slots,
+            slot_count,
         }
     }
 
-    async fn complete_task(&self, name: &str) -> Result<()> {
+    async fn acquire_slots(&self, name: &str) -> Result<SemaphorePermit<'_>> {
+        let permits = match self.executor.weight(name).await {
+            TaskWeight::Slots(slots) => slots.clamp(1, self.slot_count),
+            TaskWeight::Exclusive => self.slot_count,
+        };
+        Ok(self.slots.acquire_many(permits).await?)
+    }
+
+    async fn complete_task(&self, name: &str, output: &TaskOutput) -> Result<()> {
         let mut pipeline = self.pipeline.lock().await;
-        pipeline.complete_task(name).await?;
+        pipeline.complete_task(name, output).await?;
         let mut graph = self.graph.lock().await;
-        graph.update(name);
+        graph.record_stats(name, &output.stats);
         graph.save().await
     }
 
+    // Best effort: an update is skipped while another worker holds the pipeline, and
+    // the next batch reports again.
+    fn progress_reporter(&self, name: &str) -> ImportProgress {
+        let pipeline = self.pipeline.clone();
+        let name = name.to_string();
+        ImportProgress::new(move |progress| {
+            if let Ok(mut pipeline) = pipeline.try_lock()
+                && let Err(error) = pipeline.record_progress(&name, progress)
+            {
+                tracing::warn!("Failed to record progress for '{name}': {error:#}");
+            }
+        })
+    }
+
+    async fn fail_tests(&self, name: &str, output: TaskOutput) -> Result<()> {
+        let failures: Vec<String> = output
+            .tests
+            .iter()
+            .filter(|test| test.fails_task())
+            .map(DataTestResult::describe_failure)
+            .collect();
+
+        self.pipeline
+            .lock()
+            .await
+            .record_tests(name, output.tests)
+            .await?;
+        self.fail_task(name, anyhow::anyhow!(failures.join("; ")))
+            .await
+    }
+
     async fn fail_task(&self, name: &str, error: Error) -> Result<()> {
         {
             let mut pipeline = self.pipeline.lock().await;
+            if let Some(drift) = error.chain().find_map(|e| e.downcast_ref::<SchemaDrift>()) {
+                pipeline.record_schema_changes(name, drift.diff.clone());
+            }
             pipeline.fail_task(name, format!("{error:?}")).await?;
         }
 
-        let downstream = self.graph.lock().await.downstream(name);
+        let downstream = {
+            let mut graph = self.graph.lock().await;
+            graph.record_failure(name, &format!("{error:#}"));
+            graph.save().await?;
+            graph.downstream(name)
+        };
         for task in downstream {
             let mut pipeline = self.pipeline.lock().await;
             pipeline
@@ -322,16 +962,58 @@ impl<T: TaskExecutor> Worker<T> {
 
     async fn run(&self) -> Result<()> {
         loop {
+            if self.cancel.is_cancelled() {
+                break;
+            }
             let Some(name) = pop_task(&self.graph, &self.pipeline).await? else {
                 break;
             };
 
-            use tokio::time::{Duration, sleep};
+            use tokio::time::sleep;
             sleep(Duration::from_secs(2)).await;
 
-            match self.executor.run(&name).await {
-                Ok(()) => {
-                    self.complete_task(&name).await?;
+            if self.cancel.is_cancelled() {
+                self.fail_task(&name, anyhow::anyhow!("Cancelled before the task ran"))
+                    .await?;
+                break;
+            }
+
+            let _slots = self.acquire_slots(&name).await?;
+            let log_path = self.pipeline.lock().await.log_path(&name);
+            let progress = self.progress_reporter(&name);
+            let started = std::time::Instant::now();
+            let result = async {
+                tracing::info!("Task started");
+                let result = self.executor.run(&name, progress).await;
+                match &result {
+                    Ok(output) => tracing::info!(
+                        row_count = output.stats.row_count,
+                        duration_ms = output.stats.duration_ms,
+                        duplicates_dropped = output.stats.duplicates_dropped,
+                        "Task finished"
+                    ),
+                    Err(error) => tracing::error!("Task failed: {error:?}"),
+                }
+                result
+            }
+            .instrument(task_span(&name, &log_path))
+            .await;
+
+            metrics::histogram!(TASK_DURATION, "task" => name.clone())
+                .record(started.elapsed().as_secs_f64());
+            if !result
+                .as_ref()
+                .is_ok_and(|output| !output.tests.iter().any(DataTestResult::fails_task))
+            {
+                metrics::counter!(TASK_FAILURES, "task" => name.clone()).increment(1);
+            }
+
+            match result {
+                Ok(output) if !output.tests.iter().any(DataTestResult::fails_task) => {
+                    self.complete_task(&name, &output).await?;
+                }
+                Ok(output) => {
+                    self.fail_tests(&name, output).await?;
                 }
                 Err(error) => {
                     self.fail_task(&name, error).await?;
@@ -342,52 +1024,130 @@ impl<T: TaskExecutor> Worker<T> {
     }
 }
 
-pub async fn run_pipeline_all(config: Arc<Mutex<Config>>, graph: Arc<Mutex<Graph>>) -> Result<()> {
-    let (tasks, pipeline, executor) = {
-        let config_guard = config.lock().await;
-        let tasks = config_guard
+pub async fn run_pipeline_all(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+    pipeline: Arc<Mutex<Pipeline>>,
+    full_refresh: bool,
+    max_workers: Option<usize>,
+    vars: HashMap<String, String>,
+) -> Result<()> {
+    let tasks = {
+        let config = config.lock().await;
+        config
             .adapters
             .keys()
-            .chain(config_guard.models.keys())
+            .chain(config.models.keys())
+            .chain(config.seeds.keys())
             .cloned()
-            .collect::<Vec<String>>();
-        let pipeline = Arc::new(Mutex::new(Pipeline::new(&config_guard.project_dir)));
-        let ducklake = Arc::new(DuckLake::from_config(&config_guard).await?);
-        drop(config_guard);
-        let executor = Arc::new(Executor {
-            ducklake,
-            config: config.clone(),
-        });
-        (tasks, pipeline, executor)
+            .collect::<Vec<String>>()
     };
-    run_pipeline(executor, graph, pipeline, &tasks).await
+    run_claimed(
+        config,
+        graph,
+        pipeline,
+        tasks,
+        full_refresh,
+        max_workers,
+        vars,
+    )
+    .await
 }
 
 pub async fn run_pipeline_node(
     config: Arc<Mutex<Config>>,
     graph: Arc<Mutex<Graph>>,
+    pipeline: Arc<Mutex<Pipeline>>,
     node_name: String,
+    full_refresh: bool,
+    max_workers: Option<usize>,
+    vars: HashMap<String, String>,
 ) -> Result<()> {
-    let (tasks, pipeline, executor) = {
-        let config_guard = config.lock().await;
-        let graph_guard = graph.lock().await;
-
+    let tasks = {
+        let graph = graph.lock().await;
         let mut visited = std::collections::HashSet::new();
         visited.insert(node_name.clone());
-        let mut upstream_tasks = graph_guard.all_upstream(&node_name, &mut visited);
-        upstream_tasks.push(node_name.clone());
-
-        let pipeline = Arc::new(Mutex::new(Pipeline::new(&config_guard.project_dir)));
-        let ducklake = Arc::new(DuckLake::from_config(&config_guard).await?);
-        drop(config_guard);
-        drop(graph_guard);
-        let executor = Arc::new(Executor {
-            ducklake,
-            config: config.clone(),
-        });
-        (upstream_tasks, pipeline, executor)
+        let mut upstream_tasks = graph.all_upstream(&node_name, &mut visited);
+        upstream_tasks.push(node_name);
+        upstream_tasks
     };
-    run_pipeline(executor, graph, pipeline, &tasks).await
+    run_claimed(
+        config,
+        graph,
+        pipeline,
+        tasks,
+        full_refresh,
+        max_workers,
+        vars,
+    )
+    .await
+}
+
+// Runs a pipeline claimed from `ActivePipeline`. A run that fails before it starts,
+// e.g. when DuckLake can't be reached, is marked failed so it stops holding the claim.
+async fn run_claimed(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+    pipeline: Arc<Mutex<Pipeline>>,
+    tasks: Vec<String>,
+    full_refresh: bool,
+    max_workers: Option<usize>,
+    vars: HashMap<String, String>,
+) -> Result<()> {
+    let result: Result<()> = async {
+        let (ducklake, max_workers) = {
+            let config = config.lock().await;
+            let ducklake = Arc::new(DuckLake::from_config(&config).await?);
+            (
+                ducklake,
+                max_workers.or(config.project.pipeline.max_workers),
+            )
+        };
+        let executor =
+            Arc::new(Executor::new(ducklake, config.clone(), full_refresh).with_vars(vars));
+        run_with_attachments(executor, graph, pipeline.clone(), &tasks, max_workers).await
+    }
+    .await;
+
+    if let Err(error) = &result {
+        let mut pipeline = pipeline.lock().await;
+        if pipeline.phase == Phase::Waiting
+            && let Err(abort_error) = pipeline.abort(&format!("{error:#}")).await
+        {
+            tracing::warn!("Failed to save the unstarted pipeline: {abort_error:#}");
+        }
+    }
+    notify_failures(&config, &pipeline).await;
+    result
+}
+
+// Failed tasks are reported to every notification channel. A channel that cannot be
+// reached is logged; it doesn't change the outcome of the run.
+async fn notify_failures(config: &Arc<Mutex<Config>>, pipeline: &Arc<Mutex<Pipeline>>) {
+    let project = config.lock().await.project.clone();
+    if project.notifications.is_empty() {
+        return;
+    }
+    let notice = Notice::pipeline_failed(&*pipeline.lock().await, &project.server);
+    if let Some(notice) = notice {
+        notification::send_all(&project, &notice).await;
+    }
+}
+
+// Runs the pipeline, then releases the external databases its imports attached,
+// whether or not the run succeeded.
+async fn run_with_attachments(
+    executor: Arc<Executor>,
+    graph: Arc<Mutex<Graph>>,
+    pipeline: Arc<Mutex<Pipeline>>,
+    tasks: &[String],
+    max_workers: Option<usize>,
+) -> Result<()> {
+    let attachments = executor.attachments.clone();
+    let result = run_pipeline(executor, graph, pipeline, tasks, max_workers).await;
+    let detached = attachments.detach_all();
+    result?;
+    detached
 }
 
 pub async fn run_pipeline<T: TaskExecutor + 'static>(
@@ -395,26 +1155,93 @@ pub async fn run_pipeline<T: TaskExecutor + 'static>(
     graph: Arc<Mutex<Graph>>,
     pipeline: Arc<Mutex<Pipeline>>,
     tasks: &[String],
+    max_workers: Option<usize>,
 ) -> Result<()> {
-    {
+    if let Some(cycle) = graph.lock().await.detect_cycle() {
+        return Err(anyhow::anyhow!(
+            "Dependency cycle detected: {}",
+            cycle.join(" -> ")
+        ));
+    }
+
+    let started = std::time::Instant::now();
+    let cancel = {
         let mut pipeline = pipeline.lock().await;
         pipeline.start(tasks).await?;
-    }
+        pipeline.cancel.clone()
+    };
 
-    let worker_count = num_cpus::get();
+    let worker_count = max_workers.unwrap_or_else(num_cpus::get).max(1);
+    let slots = Arc::new(Semaphore::new(worker_count));
     let handles = (0..worker_count).map(|_| {
-        let worker = Worker::new(pipeline.clone(), executor.clone(), graph.clone());
+        let worker = Worker::new(
+            pipeline.clone(),
+            executor.clone(),
+            graph.clone(),
+            cancel.clone(),
+            slots.clone(),
+            worker_count as u32,
+        );
 
         tokio::spawn(async move { worker.run().await })
     });
 
-    future::try_join_all(handles).await?;
+    // Writes transitions that came in faster than the flush interval, so those
+    // finishing next to a long task don't wait for it to be saved.
+    let flusher = tokio::spawn({
+        let pipeline = pipeline.clone();
+        async move {
+            loop {
+                tokio::time::sleep(FLUSH_INTERVAL).await;
+                if let Err(error) = pipeline.lock().await.flush().await {
+                    tracing::warn!("Failed to save the pipeline: {error:#}");
+                }
+            }
+        }
+    });
+    let joined = future::try_join_all(handles).await;
+    flusher.abort();
 
-    {
-        let mut pipeline = pipeline.lock().await;
-        pipeline.complete().await?;
+    let mut pipeline = pipeline.lock().await;
+    // A worker that errored or panicked may have left tasks unfinished. Aborting saves
+    // whatever the flusher had not written yet and ends the run for subscribers.
+    let failure = match joined {
+        Ok(results) => results.into_iter().find_map(Result::err),
+        Err(error) => Some(error.into()),
+    };
+    if let Some(error) = failure {
+        if let Err(abort_error) = pipeline
+            .abort(&format!("Pipeline stopped: {error:#}"))
+            .await
+        {
+            tracing::warn!("Failed to save the aborted pipeline: {abort_error:#}");
+        }
+        metrics::counter!(PIPELINE_RUNS, "status" => "failed").increment(1);
+        metrics::histogram!(PIPELINE_DURATION).record(started.elapsed().as_secs_f64());
+        return Err(error);
     }
 
+    let status = if cancel.is_cancelled() {
+        pipeline
+            .abort("Pipeline cancelled before the task ran")
+            .await?;
+        "cancelled"
+    } else {
+        pipeline.complete().await?;
+        if pipeline
+            .tasks
+            .values()
+            .any(|task| task.phase == Phase::Failed)
+        {
+            "failed"
+        } else {
+            "completed"
+        }
+    };
+
+    metrics::counter!(PIPELINE_RUNS, "status" => status).increment(1);
+    metrics::histogram!(PIPELINE_DURATION).record(started.elapsed().as_secs_f64());
+
     Ok(())
 }
 
@@ -445,8 +1272,9 @@ pub async fn pop_task(
 
 #[cfg(test)]
 mod tests {
-    use super::TaskExecutor;
-    use anyhow::Result;
+    use super::{ImportProgress, TableStats, TaskExecutor, TaskOutput, TaskWeight};
+    use anyhow::{Context, Result};
+    use std::time::Instant;
 
     #[tokio::test]
     async fn test_pipeline() -> Result<()> {
@@ -494,8 +1322,8 @@ mod tests {
         let deps = vec!["a".to_string(), "b".to_string()];
         assert!(!manager.all_deps_completed(&deps));
 
-        manager.complete_task("a").await?;
-        manager.complete_task("b").await?;
+        manager.complete_task("a", &TaskOutput::default()).await?;
+        manager.complete_task("b", &TaskOutput::default()).await?;
 
         let waiting = manager.waiting_task().await;
         assert_eq!(waiting.len(), 2);
@@ -525,6 +1353,8 @@ mod tests {
                 "d".to_string(),
             ],
             fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
         });
 
         let mut graph = Graph::default();
@@ -548,7 +1378,7 @@ mod tests {
             "d".to_string(),
         ];
 
-        run_pipeline(mock_executor, graph, pipeline.clone(), &tasks).await?;
+        run_pipeline(mock_executor, graph.clone(), pipeline.clone(), &tasks, None).await?;
 
         let pipeline_guard = pipeline.lock().await;
         assert_eq!(pipeline_guard.phase, Phase::Completed);
@@ -572,12 +1402,512 @@ mod tests {
             pipeline_guard.tasks.get("d").unwrap().phase,
             Phase::Completed
         );
+        assert_eq!(pipeline_guard.tasks.get("d").unwrap().row_count, Some(1));
+        assert_eq!(graph.lock().await.get_node("d").unwrap().row_count, Some(1));
 
         Ok(())
     }
 
+    struct PanickingExecutor;
+
+    #[async_trait::async_trait]
+    impl TaskExecutor for PanickingExecutor {
+        async fn run(&self, name: &str, _progress: ImportProgress) -> Result<TaskOutput> {
+            panic!("task {name} panicked");
+        }
+    }
+
     #[tokio::test]
-    async fn test_pipeline_failure() -> Result<()> {
+    async fn test_pipeline_worker_panic_aborts_run() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mut graph = Graph::default();
+        graph.create_node("a", &[]);
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(project_dir)));
+        let (_, mut events) = pipeline.lock().await.subscribe();
+
+        let tasks = vec!["a".to_string()];
+        let result = run_pipeline(
+            Arc::new(PanickingExecutor),
+            graph,
+            pipeline.clone(),
+            &tasks,
+            Some(1),
+        )
+        .await;
+        assert!(result.is_err());
+
+        assert_eq!(pipeline.lock().await.phase, Phase::Failed);
+        let saved = Pipeline::load_latest(project_dir).await?.unwrap();
+        assert_eq!(saved.phase, Phase::Failed);
+        assert_eq!(saved.tasks["a"].phase, Phase::Failed);
+
+        let mut ended = false;
+        while let Ok(event) = events.try_recv() {
+            ended |= event.ends_run();
+        }
+        assert!(ended);
+
+        Ok(())
+    }
+
+    fn overlaps(a: &(String, Instant, Instant), b: &(String, Instant, Instant)) -> bool {
+        a.1 < b.2 && b.1 < a.2
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_max_workers() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let tasks: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: tasks.clone(),
+            fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+
+        let mut graph = Graph::default();
+        for task in &tasks {
+            graph.create_node(task, &[]);
+        }
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+
+        run_pipeline(mock_executor.clone(), graph, pipeline, &tasks, Some(1)).await?;
+
+        let runs = mock_executor.runs();
+        assert_eq!(runs.len(), 3);
+        for pair in runs.windows(2) {
+            assert!(
+                pair[0].2 <= pair[1].1,
+                "{} overlapped {}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_exclusive_task() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let tasks: Vec<String> = ["a", "b", "heavy", "c"].map(String::from).to_vec();
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: tasks.clone(),
+            fail_tasks: vec![],
+            exclusive_tasks: vec!["heavy".to_string()],
+            runs: Default::default(),
+        });
+
+        let mut graph = Graph::default();
+        for task in &tasks {
+            graph.create_node(task, &[]);
+        }
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+
+        run_pipeline(
+            mock_executor.clone(),
+            graph,
+            pipeline.clone(),
+            &tasks,
+            Some(4),
+        )
+        .await?;
+
+        assert_eq!(pipeline.lock().await.phase, Phase::Completed);
+        let runs = mock_executor.runs();
+        let (heavy, others): (Vec<_>, Vec<_>) =
+            runs.iter().partition(|(name, _, _)| name == "heavy");
+        assert_eq!(heavy.len(), 1);
+        for other in &others {
+            assert!(!overlaps(heavy[0], other), "heavy overlapped {}", other.0);
+        }
+        // The other tasks still share the workers.
+        assert!(
+            others
+                .iter()
+                .any(|a| others.iter().any(|b| a.0 != b.0 && overlaps(a, b)))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_events() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let tasks = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: tasks.clone(),
+            fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+
+        // a-->b-->c
+        let mut graph = Graph::default();
+        graph.create_node("a", &[]);
+        graph.create_node("b", &["a"]);
+        graph.create_node("c", &["b"]);
+        let graph = Arc::new(Mutex::new(graph));
+
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        let (snapshot, mut receiver) = pipeline.lock().await.subscribe();
+        assert!(matches!(
+            snapshot,
+            PipelineEvent::Snapshot {
+                phase: Phase::Waiting,
+                ..
+            }
+        ));
+
+        run_pipeline(mock_executor, graph, pipeline.clone(), &tasks, None).await?;
+
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(serde_json::to_value(event)?);
+        }
+        let kinds: Vec<String> = events
+            .iter()
+            .map(|event| {
+                let task = event["task"].as_str().unwrap_or("pipeline");
+                format!("{}:{task}", event["type"].as_str().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "task_started:a",
+                "task_completed:a",
+                "task_started:b",
+                "task_completed:b",
+                "task_started:c",
+                "task_completed:c",
+                "pipeline_completed:pipeline",
+            ]
+        );
+        assert_eq!(events[1]["row_count"], 1);
+
+        let (snapshot, _) = pipeline.lock().await.subscribe();
+        assert!(snapshot.ends_run());
+        let snapshot = serde_json::to_value(snapshot)?;
+        assert_eq!(snapshot["phase"], "completed");
+        assert_eq!(snapshot["tasks"]["c"]["phase"], "completed");
+
+        // An aborted run is over too, so subscribers joining late stop after the snapshot.
+        let aborted = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        aborted.lock().await.start(&tasks).await?;
+        aborted.lock().await.abort("Cancelled").await?;
+        let (snapshot, _) = aborted.lock().await.subscribe();
+        assert!(snapshot.ends_run());
+
+        Ok(())
+    }
+
+    struct LoggingExecutor;
+
+    #[async_trait::async_trait]
+    impl TaskExecutor for LoggingExecutor {
+        async fn run(&self, name: &str, _progress: ImportProgress) -> Result<TaskOutput> {
+            tracing::info!("importing {name}");
+            if name == "broken" {
+                return Err(anyhow::anyhow!("Binder Error: column \"id\" not found"))
+                    .context("Failed to import table");
+            }
+            Ok(TaskOutput::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_task_logs() -> Result<()> {
+        use super::*;
+        use crate::core::task_log::{TaskLogLayer, read_task_log};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(TaskLogLayer));
+
+        let tempdir = tempfile::tempdir()?;
+        let mut graph = Graph::default();
+        graph.create_node("healthy", &[]);
+        graph.create_node("broken", &[]);
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        let tasks = vec!["healthy".to_string(), "broken".to_string()];
+
+        run_pipeline(
+            Arc::new(LoggingExecutor),
+            graph,
+            pipeline.clone(),
+            &tasks,
+            None,
+        )
+        .await?;
+
+        let id = pipeline.lock().await.id.clone();
+        let healthy = Pipeline::task_log_path(tempdir.path(), &id, "healthy");
+        let healthy = read_task_log(&healthy, None).await?.unwrap();
+        assert!(healthy.contains("importing healthy"));
+        assert!(healthy.contains("Task finished"));
+        assert!(!healthy.contains("importing broken"));
+
+        let broken = Pipeline::task_log_path(tempdir.path(), &id, "broken");
+        let broken = read_task_log(&broken, None).await?.unwrap();
+        assert!(broken.contains("importing broken"));
+        assert!(broken.contains("ERROR"));
+        assert!(broken.contains("Binder Error: column \"id\" not found"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted_pipelines() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let mut pipeline = Pipeline::new(tempdir.path());
+        pipeline
+            .start(&["a".to_string(), "b".to_string(), "c".to_string()])
+            .await?;
+        pipeline.start_task("a").await?;
+        pipeline.complete_task("a", &TaskOutput::default()).await?;
+        pipeline.start_task("b").await?;
+        // What the periodic flush writes while b runs.
+        pipeline.flush().await?;
+
+        let started_at = pipeline.started_at.unwrap();
+        let recovered = Pipeline::recover_interrupted(tempdir.path(), started_at).await?;
+        assert_eq!(recovered, 0);
+
+        let now = Utc::now() + chrono::Duration::seconds(1);
+        let recovered = Pipeline::recover_interrupted(tempdir.path(), now).await?;
+        assert_eq!(recovered, 1);
+
+        let latest = Pipeline::load_latest(tempdir.path()).await?.unwrap();
+        assert_eq!(latest.phase, Phase::Failed);
+        assert!(latest.completed_at.is_some());
+        assert_eq!(latest.tasks["a"].phase, Phase::Completed);
+        for task in ["b", "c"] {
+            let status = &latest.tasks[task];
+            assert_eq!(status.phase, Phase::Failed);
+            assert_eq!(
+                status.error.as_ref().unwrap().message,
+                "Interrupted by a server shutdown or crash"
+            );
+        }
+
+        let recovered = Pipeline::recover_interrupted(tempdir.path(), now).await?;
+        assert_eq!(recovered, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_all_reports_corrupted_files() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let mut pipeline = Pipeline::new(tempdir.path());
+        pipeline.start(&["a".to_string()]).await?;
+        pipeline.complete().await?;
+
+        let dir = Pipeline::get_pipeline_dir(tempdir.path());
+        let content = std::fs::read_to_string(&pipeline.filepath)?;
+        let saved: serde_json::Value = serde_json::from_str(&content)?;
+        assert_eq!(saved["schema_version"], PIPELINE_SCHEMA_VERSION);
+        std::fs::write(
+            dir.join("2020-01-01-00-00-00.json"),
+            &content[..content.len() / 2],
+        )?;
+        std::fs::write(
+            dir.join("2020-01-02-00-00-00.json"),
+            content.replace("\"schema_version\": 1", "\"schema_version\": 99"),
+        )?;
+
+        let (pipelines, issues) = Pipeline::load_all(tempdir.path()).await?;
+        assert_eq!(pipelines.len(), 1);
+        assert_eq!(pipelines[0].id, pipeline.id);
+        let files: Vec<&str> = issues.iter().map(|issue| issue.file.as_str()).collect();
+        assert_eq!(
+            files,
+            vec!["2020-01-01-00-00-00.json", "2020-01-02-00-00-00.json"]
+        );
+        assert!(issues[0].error.contains("EOF"));
+        assert!(issues[1].error.contains("newer"));
+
+        // Written by rename, so no temporary file is left behind.
+        let leftovers = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_batches_task_saves() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let saved_phase = |pipeline: &Pipeline, task: &str| -> Result<Phase> {
+            let content = std::fs::read_to_string(&pipeline.filepath)?;
+            let saved: Pipeline = serde_json::from_str(&content)?;
+            Ok(saved.tasks[task].phase.clone())
+        };
+
+        let mut pipeline = Pipeline::new(tempdir.path());
+        pipeline.start(&["a".to_string()]).await?;
+        pipeline.start_task("a").await?;
+        assert_eq!(pipeline.tasks["a"].phase, Phase::Running);
+        assert_eq!(saved_phase(&pipeline, "a")?, Phase::Waiting);
+
+        pipeline.flush().await?;
+        assert_eq!(saved_phase(&pipeline, "a")?, Phase::Running);
+
+        // A whole run still ends with every transition on disk.
+        let tasks: Vec<String> = (0..8).map(|i| format!("t{i}")).collect();
+        let mut graph = Graph::new(tempdir.path());
+        for task in &tasks {
+            graph.create_node(task, &[]);
+        }
+        let executor = Arc::new(MockExecutor {
+            success_tasks: tasks.clone(),
+            fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        run_pipeline(
+            executor,
+            Arc::new(Mutex::new(graph)),
+            pipeline.clone(),
+            &tasks,
+            Some(4),
+        )
+        .await?;
+
+        let pipeline = pipeline.lock().await;
+        for task in &tasks {
+            assert_eq!(saved_phase(&pipeline, task)?, Phase::Completed);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_active_pipeline_claim() -> Result<()> {
+        use super::*;
+        let tempdir = tempfile::tempdir()?;
+        let active = ActivePipeline::default();
+
+        let (first, second) =
+            tokio::join!(active.claim(tempdir.path()), active.claim(tempdir.path()));
+        let claimed = match (first, second) {
+            (Ok(pipeline), Err(PipelineBusy)) | (Err(PipelineBusy), Ok(pipeline)) => pipeline,
+            _ => panic!("exactly one concurrent claim should succeed"),
+        };
+
+        claimed.lock().await.phase = Phase::Running;
+        assert!(active.claim(tempdir.path()).await.is_err());
+
+        claimed.lock().await.phase = Phase::Failed;
+        let next = active.claim(tempdir.path()).await?;
+        assert!(Arc::ptr_eq(&active.get().await.unwrap(), &next));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_shutdown() -> Result<()> {
+        use super::*;
+
+        let tempdir = tempfile::tempdir()?;
+        let tasks = vec!["a".to_string(), "b".to_string()];
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: tasks.clone(),
+            fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+
+        let mut graph = Graph::default();
+        graph.create_node("a", &[]);
+        graph.create_node("b", &["a"]);
+        let graph = Arc::new(Mutex::new(graph));
+
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        let active = ActivePipeline::default();
+        active.set(pipeline.clone()).await;
+
+        let run = tokio::spawn({
+            let pipeline = pipeline.clone();
+            async move { run_pipeline(mock_executor, graph, pipeline, &tasks, None).await }
+        });
+        while pipeline.lock().await.phase != Phase::Running {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        active.shutdown(Duration::from_secs(10)).await?;
+        run.await??;
+
+        let pipeline = pipeline.lock().await;
+        assert_eq!(pipeline.phase, Phase::Failed);
+        assert!(
+            pipeline
+                .tasks
+                .values()
+                .all(|task| task.phase == Phase::Failed)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_metrics() -> Result<()> {
+        use super::*;
+
+        let handle = crate::core::metrics::handle();
+
+        let tempdir = tempfile::tempdir()?;
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: vec!["metrics_ok".to_string()],
+            fail_tasks: vec!["metrics_broken".to_string()],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+        let mut graph = Graph::default();
+        graph.create_node("metrics_ok", &[]);
+        graph.create_node("metrics_broken", &[]);
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(tempdir.path())));
+        let tasks = vec!["metrics_ok".to_string(), "metrics_broken".to_string()];
+
+        run_pipeline(mock_executor, graph, pipeline, &tasks, None).await?;
+
+        let scraped = handle.render();
+        assert!(scraped.contains("duckhub_pipeline_runs_total{status=\"failed\"}"));
+        assert!(scraped.contains("duckhub_pipeline_duration_seconds_count"));
+        assert!(scraped.contains("duckhub_task_duration_seconds_count{task=\"metrics_ok\"} 1"));
+        assert!(scraped.contains("duckhub_task_failures_total{task=\"metrics_broken\"} 1"));
+        assert!(!scraped.contains("duckhub_task_failures_total{task=\"metrics_ok\"}"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_failure() -> Result<()> {
         use super::*;
         use tempfile::tempdir;
 
@@ -587,6 +1917,8 @@ mod tests {
         let mock_executor = Arc::new(MockExecutor {
             success_tasks: vec!["a".to_string(), "b".to_string(), "c".to_string()],
             fail_tasks: vec!["e".to_string()],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
         });
 
         let mut graph = Graph::default();
@@ -610,7 +1942,7 @@ mod tests {
             "d".to_string(),
         ];
 
-        run_pipeline(mock_executor, graph, pipeline.clone(), &tasks).await?;
+        run_pipeline(mock_executor, graph, pipeline.clone(), &tasks, None).await?;
 
         let pipeline_guard = pipeline.lock().await;
         assert_eq!(pipeline_guard.phase, Phase::Completed);
@@ -632,21 +1964,578 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_pipeline_records_last_error() -> Result<()> {
+        use super::*;
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: vec!["a".to_string()],
+            fail_tasks: vec!["b".to_string()],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+
+        // a-->b
+        let mut graph = Graph::new(project_dir);
+        graph.create_node("a", &[]);
+        graph.create_node("b", &["a"]);
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(project_dir)));
+
+        let tasks = vec!["a".to_string(), "b".to_string()];
+        run_pipeline(mock_executor, graph, pipeline, &tasks, None).await?;
+
+        let mut saved = Graph::load(project_dir).await?;
+        let a = saved.get_node("a").unwrap();
+        assert_eq!(a.last_status, Some(Phase::Completed));
+        assert_eq!(a.last_error, None);
+        let b = saved.get_node("b").unwrap();
+        assert_eq!(b.last_status, Some(Phase::Failed));
+        assert_eq!(b.last_error.as_deref(), Some("Task b failed"));
+
+        saved.update_node("b");
+        saved.save().await?;
+
+        let saved = Graph::load(project_dir).await?;
+        let b = saved.get_node("b").unwrap();
+        assert_eq!(b.last_status, Some(Phase::Failed));
+        assert_eq!(b.last_error, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_cycle() -> Result<()> {
+        use super::*;
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mock_executor = Arc::new(MockExecutor {
+            success_tasks: vec!["a".to_string(), "b".to_string()],
+            fail_tasks: vec![],
+            exclusive_tasks: vec![],
+            runs: Default::default(),
+        });
+
+        let mut graph = Graph::default();
+        graph.create_node("a", &["b"]);
+        graph.create_node("b", &["a"]);
+
+        let graph = Arc::new(Mutex::new(graph));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(project_dir)));
+
+        let tasks = vec!["a".to_string(), "b".to_string()];
+
+        let result = run_pipeline(mock_executor, graph, pipeline.clone(), &tasks, None).await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("a -> b -> a"));
+
+        assert_eq!(pipeline.lock().await.phase, Phase::Waiting);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_model_runs_after_seeds() -> Result<()> {
+        use super::*;
+        use crate::core::config::{
+            model::{Materialization, ModelConfig},
+            project::ProjectConfig,
+        };
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mut config = Config::new(project_dir.to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        std::fs::create_dir_all(project_dir.join("seeds"))?;
+        std::fs::write(
+            project_dir.join("seeds/countries.csv"),
+            "code,name,region\nJP,Japan,APAC\nUS,United States,AMER\nFR,France,EMEA\n",
+        )?;
+        std::fs::write(
+            project_dir.join("seeds/regions.csv"),
+            "code,label\nAPAC,Asia Pacific\nEMEA,Europe\n",
+        )?;
+        config.load()?;
+
+        let model = ModelConfig {
+            description: None,
+            sql: "SELECT countries.name, regions.label FROM countries JOIN regions ON countries.region = regions.code".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        config.upsert_model("country_regions", &model)?.save()?;
+
+        let mut graph = Graph::new(project_dir);
+        graph.rebuild(&config);
+        assert_eq!(
+            graph.get_node("country_regions").unwrap().dependencies,
+            vec!["countries", "regions"]
+        );
+
+        let config = Arc::new(Mutex::new(config));
+        let graph = Arc::new(Mutex::new(graph));
+        run_pipeline_all(
+            config,
+            graph.clone(),
+            ActivePipeline::default().claim(project_dir).await?,
+            false,
+            None,
+            HashMap::new(),
+        )
+        .await?;
+
+        let pipeline = Pipeline::load_latest(project_dir).await?.unwrap();
+        let model_task = &pipeline.tasks["country_regions"];
+        assert_eq!(model_task.phase, Phase::Completed);
+        assert_eq!(model_task.row_count, Some(2));
+        for seed in ["countries", "regions"] {
+            let seed_task = &pipeline.tasks[seed];
+            assert_eq!(seed_task.phase, Phase::Completed);
+            assert!(seed_task.completed_at <= model_task.started_at);
+        }
+
+        assert_eq!(
+            graph.lock().await.get_node("countries").unwrap().row_count,
+            Some(3)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_shares_database_attachments() -> Result<()> {
+        use super::*;
+        use crate::core::config::{
+            adapter::{AdapterConfig, AdapterSource},
+            project::{ConnectionConfig, ProjectConfig},
+        };
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+        let source_path = project_dir.join("source.db");
+        rusqlite::Connection::open(&source_path)?.execute_batch(
+            "CREATE TABLE users (id INTEGER, name TEXT);
+             INSERT INTO users VALUES (1, 'alice'), (2, 'bob');
+             CREATE TABLE orders (id INTEGER, user_id INTEGER);
+             INSERT INTO orders VALUES (10, 1), (11, 1), (12, 2);",
+        )?;
+
+        let mut project = ProjectConfig::default();
+        project.connections.insert(
+            "source".to_string(),
+            ConnectionConfig::Sqlite {
+                path: source_path.to_string_lossy().to_string(),
+            },
+        );
+        let mut config = Config::new(project_dir.to_path_buf());
+        config.add_project_setting(&project)?.save()?;
+        config.load()?;
+        for table in ["users", "orders"] {
+            let adapter = AdapterConfig {
+                connection: "source".to_string(),
+                target_table: None,
+                dedupe: None,
+                description: None,
+                source: AdapterSource::Database {
+                    table_name: table.to_string(),
+                    columns: None,
+                    filter: None,
+                    incremental: None,
+                },
+                columns: vec![],
+                tests: vec![],
+                relationships: vec![],
+                on_schema_change: None,
+                tags: vec![],
+                group: None,
+                weight: None,
+                exclusive: false,
+                masking: vec![],
+            };
+            config.upsert_adapter(table, &adapter)?.save()?;
+        }
+
+        let mut graph = Graph::new(project_dir);
+        graph.rebuild(&config);
+        let ducklake = Arc::new(DuckLake::from_config(&config).await?);
+        let executor = Arc::new(Executor::new(ducklake, Arc::new(Mutex::new(config)), true));
+        let pipeline = Arc::new(Mutex::new(Pipeline::new(project_dir)));
+        let tasks = vec!["users".to_string(), "orders".to_string()];
+        run_with_attachments(
+            executor.clone(),
+            Arc::new(Mutex::new(graph)),
+            pipeline.clone(),
+            &tasks,
+            Some(2),
+        )
+        .await?;
+
+        let pipeline = pipeline.lock().await;
+        assert_eq!(pipeline.tasks["users"].row_count, Some(2));
+        assert_eq!(pipeline.tasks["orders"].row_count, Some(3));
+        assert_eq!(executor.attachments.attach_count(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_progress_saved_between_batches() -> Result<()> {
+        use super::*;
+        use crate::core::{
+            adapter::{
+                Adapter,
+                test_helpers::{create_csv_adapter_config, setup_test_ducklake},
+            },
+            config::{adapter::AdapterSource, project::ConnectionConfig},
+        };
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+        for i in 1..=5 {
+            std::fs::write(
+                project_dir.join(format!("part_{i}.csv")),
+                format!("id,amount\n{i},{}\n", i * 10),
+            )?;
+        }
+        let mut adapter_config = create_csv_adapter_config();
+        if let AdapterSource::File { file, .. } = &mut adapter_config.source {
+            file.path = "part_*.csv".to_string();
+            file.max_batch_files = Some(1);
+        }
+        let connections = HashMap::from([(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: project_dir.to_string_lossy().to_string(),
+            },
+        )]);
+
+        let ducklake = setup_test_ducklake(project_dir).await?;
+        let config = Config::new(project_dir.to_path_buf());
+        let executor = Arc::new(Executor::new(
+            ducklake.clone(),
+            Arc::new(Mutex::new(config)),
+            false,
+        ));
+        let mut pipeline = Pipeline::new(project_dir);
+        // Write every report, as if each batch took longer than the flush interval.
+        pipeline.flush_interval = Duration::ZERO;
+        let pipeline = Arc::new(Mutex::new(pipeline));
+        pipeline.lock().await.start(&["orders".to_string()]).await?;
+        let filepath = pipeline.lock().await.filepath.clone();
+        let graph = Arc::new(Mutex::new(Graph::new(project_dir)));
+        let worker = Worker::new(
+            pipeline,
+            executor,
+            graph,
+            CancellationToken::new(),
+            Arc::new(Semaphore::new(1)),
+            1,
+        );
+
+        // Read the pipeline file back after every report to see what a client polling
+        // it would have seen between batches.
+        let reporter = worker.progress_reporter("orders");
+        let saved = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let progress = ImportProgress::new({
+            let saved = saved.clone();
+            move |progress| {
+                reporter.report(progress);
+                let content = std::fs::read_to_string(&filepath).unwrap();
+                let pipeline: Pipeline = serde_json::from_str(&content).unwrap();
+                saved
+                    .lock()
+                    .unwrap()
+                    .push(pipeline.tasks["orders"].progress().cloned().unwrap());
+            }
+        });
+        Adapter::new(adapter_config, ducklake)
+            .with_progress(progress)
+            .import("orders", &connections)
+            .await?;
+
+        let saved = saved.lock().unwrap();
+        let files_done: Vec<usize> = saved.iter().map(|p| p.files_done).collect();
+        assert_eq!(files_done, vec![0, 1, 2, 3, 4, 5]);
+        assert!(saved.iter().all(|p| p.files_total == 5));
+        let bytes_done: Vec<u64> = saved.iter().map(|p| p.bytes_done.unwrap()).collect();
+        assert!(bytes_done.windows(2).all(|pair| pair[0] < pair[1]));
+        let last = saved.last().unwrap();
+        assert_eq!(last.bytes_done, last.bytes_total);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_data_tests() -> Result<()> {
+        use super::*;
+        use crate::core::config::{
+            data_test::{DataTestConfig, DataTestKind},
+            model::{Materialization, ModelConfig},
+            project::ProjectConfig,
+        };
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mut config = Config::new(project_dir.to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        std::fs::create_dir_all(project_dir.join("seeds"))?;
+        std::fs::write(
+            project_dir.join("seeds/countries.csv"),
+            "code,region\nJP,APAC\nUS,AMER\nCN,APAC\n",
+        )?;
+        config.load()?;
+
+        let model = |tests: Vec<DataTestConfig>| ModelConfig {
+            description: None,
+            sql: "SELECT * FROM countries".to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests,
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        let passing = model(vec![
+            DataTestConfig {
+                column: "code".to_string(),
+                kind: DataTestKind::Unique,
+            },
+            DataTestConfig {
+                column: "region".to_string(),
+                kind: DataTestKind::AcceptedValues {
+                    values: vec!["APAC".to_string(), "AMER".to_string()],
+                },
+            },
+        ]);
+        let failing = model(vec![
+            DataTestConfig {
+                column: "code".to_string(),
+                kind: DataTestKind::NotNull,
+            },
+            DataTestConfig {
+                column: "region".to_string(),
+                kind: DataTestKind::Unique,
+            },
+        ]);
+        config.upsert_model("checked", &passing)?.save()?;
+        config.upsert_model("duplicated", &failing)?.save()?;
+
+        let mut graph = Graph::new(project_dir);
+        graph.rebuild(&config);
+        let graph = Arc::new(Mutex::new(graph));
+        run_pipeline_all(
+            Arc::new(Mutex::new(config)),
+            graph.clone(),
+            ActivePipeline::default().claim(project_dir).await?,
+            false,
+            None,
+            HashMap::new(),
+        )
+        .await?;
+
+        let pipeline = Pipeline::load_latest(project_dir).await?.unwrap();
+
+        let checked = &pipeline.tasks["checked"];
+        assert_eq!(checked.phase, Phase::Completed);
+        assert_eq!(checked.tests.len(), 2);
+        assert!(checked.tests.iter().all(|test| test.passed));
+
+        let duplicated = &pipeline.tasks["duplicated"];
+        assert_eq!(duplicated.phase, Phase::Failed);
+        assert!(duplicated.tests[0].passed);
+        assert!(!duplicated.tests[1].passed);
+        assert_eq!(duplicated.tests[1].failing_rows, 2);
+        let message = &duplicated.error.as_ref().unwrap().message;
+        assert!(
+            message.contains("Data test 'unique' on column 'region' failed: 2 offending row(s)")
+        );
+
+        assert!(
+            graph
+                .lock()
+                .await
+                .get_node("duplicated")
+                .unwrap()
+                .updated_at
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_relationships() -> Result<()> {
+        use super::*;
+        use crate::core::config::{
+            data_test::{ColumnReference, RelationshipConfig, Severity},
+            model::{Materialization, ModelConfig},
+            project::ProjectConfig,
+        };
+        use tempfile::tempdir;
+
+        let tempdir = tempdir()?;
+        let project_dir = tempdir.path();
+
+        let mut config = Config::new(project_dir.to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        std::fs::create_dir_all(project_dir.join("seeds"))?;
+        std::fs::write(
+            project_dir.join("seeds/countries.csv"),
+            "code,region\nJP,APAC\nUS,AMER\n",
+        )?;
+        config.load()?;
+
+        // FR has no row in countries.
+        let model = |severity| ModelConfig {
+            description: None,
+            sql: "SELECT * FROM (VALUES (1, 'JP'), (2, 'FR'), (3, NULL)) t(id, country)"
+                .to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![RelationshipConfig {
+                column: "country".to_string(),
+                references: ColumnReference {
+                    table: "countries".to_string(),
+                    column: "code".to_string(),
+                },
+                severity,
+            }],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        config
+            .upsert_model("strict_orders", &model(Severity::Error))?
+            .save()?;
+        config
+            .upsert_model("lenient_orders", &model(Severity::Warn))?
+            .save()?;
+
+        let mut graph = Graph::new(project_dir);
+        graph.rebuild(&config);
+        assert_eq!(
+            graph.get_node("strict_orders").unwrap().dependencies,
+            vec!["countries".to_string()]
+        );
+        let graph = Arc::new(Mutex::new(graph));
+        run_pipeline_all(
+            Arc::new(Mutex::new(config)),
+            graph.clone(),
+            ActivePipeline::default().claim(project_dir).await?,
+            false,
+            None,
+            HashMap::new(),
+        )
+        .await?;
+
+        let pipeline = Pipeline::load_latest(project_dir).await?.unwrap();
+
+        let strict = &pipeline.tasks["strict_orders"];
+        assert_eq!(strict.phase, Phase::Failed);
+        assert_eq!(strict.tests[0].failing_rows, 1);
+        assert_eq!(
+            strict.tests[0].references.as_deref(),
+            Some("countries.code")
+        );
+        let message = &strict.error.as_ref().unwrap().message;
+        assert!(
+            message
+                .contains("Relationship 'country' -> 'countries.code' failed: 1 orphaned row(s)")
+        );
+
+        let lenient = &pipeline.tasks["lenient_orders"];
+        assert_eq!(lenient.phase, Phase::Completed);
+        assert!(!lenient.tests[0].passed);
+        assert_eq!(lenient.tests[0].severity, Severity::Warn);
+
+        Ok(())
+    }
+
     pub struct MockExecutor {
         pub success_tasks: Vec<String>,
         pub fail_tasks: Vec<String>,
+        pub exclusive_tasks: Vec<String>,
+        // When each task started and finished.
+        pub runs: std::sync::Mutex<Vec<(String, Instant, Instant)>>,
+    }
+
+    impl MockExecutor {
+        fn runs(&self) -> Vec<(String, Instant, Instant)> {
+            let mut runs = self.runs.lock().unwrap().clone();
+            runs.sort_by_key(|(_, started, _)| *started);
+            runs
+        }
     }
 
     #[async_trait::async_trait]
     impl TaskExecutor for MockExecutor {
-        async fn run(&self, name: &str) -> Result<()> {
+        async fn run(&self, name: &str, _progress: ImportProgress) -> Result<TaskOutput> {
+            let started = Instant::now();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            self.runs
+                .lock()
+                .unwrap()
+                .push((name.to_string(), started, Instant::now()));
+
             if self.success_tasks.contains(&name.to_string()) {
-                Ok(())
+                Ok(TaskOutput {
+                    stats: TableStats {
+                        row_count: 1,
+                        duration_ms: 0,
+                        duplicates_dropped: 0,
+                        files: None,
+                        schema_changes: None,
+                    },
+                    tests: vec![],
+                })
             } else if self.fail_tasks.contains(&name.to_string()) {
                 Err(anyhow::anyhow!("Task {} failed", name))
             } else {
                 Err(anyhow::anyhow!("Unknown task: {}", name))
             }
         }
+
+        async fn weight(&self, name: &str) -> TaskWeight {
+            if self.exclusive_tasks.contains(&name.to_string()) {
+                TaskWeight::Exclusive
+            } else {
+                TaskWeight::default()
+            }
+        }
     }
 }
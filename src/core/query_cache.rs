@@ -0,0 +1,206 @@
+use crate::core::{
+    config::project::CacheConfig,
+    ducklake::{Snapshot, TypedResult},
+    graph::{Graph, dependent_tables},
+};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+// Identifies a cached result. `upstream` holds the `updated_at` of every graph node
+// the SQL reads, so rebuilding any of them produces a new key and the old entry is
+// never hit again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    query: String,
+    sql_hash: u64,
+    limit: Option<usize>,
+    snapshot: Option<String>,
+    upstream: Vec<(String, Option<DateTime<Utc>>)>,
+}
+
+impl CacheKey {
+    pub fn new(
+        graph: &Graph,
+        query: &str,
+        sql: &str,
+        limit: Option<usize>,
+        snapshot: Option<&Snapshot>,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        sql.hash(&mut hasher);
+
+        // Unparseable SQL fails when it runs; until then the TTL is all that expires it.
+        let mut tables = dependent_tables(sql).unwrap_or_default();
+        tables.sort();
+        tables.dedup();
+        let upstream = tables
+            .into_iter()
+            .filter_map(|table| {
+                let updated_at = graph.get_node(&table)?.updated_at;
+                Some((table, updated_at))
+            })
+            .collect();
+
+        Self {
+            query: query.to_string(),
+            sql_hash: hasher.finish(),
+            limit,
+            snapshot: snapshot.map(|snapshot| format!("{snapshot:?}")),
+            upstream,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedResult {
+    pub result: TypedResult,
+    pub truncated: bool,
+}
+
+struct Entry {
+    value: Arc<CachedResult>,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+pub struct QueryCache {
+    entries: Mutex<HashMap<CacheKey, Entry>>,
+    clock: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl QueryCache {
+    pub fn get(&self, key: &CacheKey, config: &CacheConfig) -> Option<Arc<CachedResult>> {
+        if !config.enabled() {
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let value = match entries.get_mut(key) {
+            Some(entry) if entry.inserted_at.elapsed() < config.ttl() => {
+                entry.last_used = self.clock.fetch_add(1, Ordering::Relaxed);
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub fn insert(&self, key: CacheKey, value: CachedResult, config: &CacheConfig) {
+        if !config.enabled() || config.max_entries() == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = config.ttl();
+        entries.retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+        while entries.len() >= config.max_entries() {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                value: Arc::new(value),
+                inserted_at: Instant::now(),
+                last_used: self.clock.fetch_add(1, Ordering::Relaxed),
+            },
+        );
+    }
+
+    // Returns how many entries were dropped.
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let cleared = entries.len();
+        entries.clear();
+        cleared
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(graph: &Graph, query: &str) -> CacheKey {
+        CacheKey::new(graph, query, "SELECT * FROM orders", None, None)
+    }
+
+    fn value() -> CachedResult {
+        CachedResult {
+            result: TypedResult::default(),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let graph = Graph::new(std::path::Path::new("."));
+        let config = CacheConfig {
+            max_entries: Some(2),
+            ..Default::default()
+        };
+        let cache = QueryCache::default();
+
+        cache.insert(key(&graph, "a"), value(), &config);
+        cache.insert(key(&graph, "b"), value(), &config);
+        assert!(cache.get(&key(&graph, "a"), &config).is_some());
+        cache.insert(key(&graph, "c"), value(), &config);
+
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.get(&key(&graph, "a"), &config).is_some());
+        assert!(cache.get(&key(&graph, "b"), &config).is_none());
+        assert!(cache.get(&key(&graph, "c"), &config).is_some());
+        assert_eq!(cache.hits(), 3);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_key_follows_upstream_updates() {
+        let mut graph = Graph::new(std::path::Path::new("."));
+        graph.create_node("orders", &[]);
+        let before = key(&graph, "orders_by_day");
+
+        graph.set_current_time("orders");
+        assert_ne!(key(&graph, "orders_by_day"), before);
+    }
+}
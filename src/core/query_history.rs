@@ -0,0 +1,183 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs, sync::Mutex};
+use utoipa::ToSchema;
+
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct QueryHistoryEntry {
+    pub id: String,
+    pub sql: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub row_count: Option<usize>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl QueryHistoryEntry {
+    pub fn new(sql: &str, started_at: DateTime<Utc>, outcome: Result<usize, String>) -> Self {
+        let duration_ms = (Utc::now() - started_at).num_milliseconds().max(0) as u64;
+        let (row_count, error) = match outcome {
+            Ok(row_count) => (Some(row_count), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            sql: sql.to_string(),
+            started_at,
+            duration_ms,
+            row_count,
+            success: error.is_none(),
+            error,
+        }
+    }
+}
+
+// Entries are queued synchronously and written by a spawned flush, so the query
+// path never waits on the file. Reads flush first and always see earlier queries.
+pub struct QueryHistory {
+    capacity: usize,
+    pending: std::sync::Mutex<Vec<(PathBuf, QueryHistoryEntry)>>,
+    file: Mutex<()>,
+}
+
+impl Default for QueryHistory {
+    fn default() -> Self {
+        Self::with_capacity(MAX_HISTORY_ENTRIES)
+    }
+}
+
+impl QueryHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            pending: std::sync::Mutex::new(Vec::new()),
+            file: Mutex::new(()),
+        }
+    }
+
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".data").join("query_history.ndjson")
+    }
+
+    pub fn record(self: &Arc<Self>, project_dir: &Path, entry: QueryHistoryEntry) {
+        self.pending
+            .lock()
+            .unwrap()
+            .push((Self::path(project_dir), entry));
+
+        let history = self.clone();
+        tokio::spawn(async move {
+            if let Err(error) = history.flush().await {
+                tracing::warn!("Failed to write query history: {error:#}");
+            }
+        });
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let _file = self.file.lock().await;
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+
+        let mut paths: Vec<&PathBuf> = Vec::new();
+        for (path, _) in &pending {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        for path in paths {
+            let mut lines = read_lines(path).await?;
+            for (_, entry) in pending.iter().filter(|(p, _)| p == path) {
+                lines.push(serde_json::to_string(entry)?);
+            }
+            let start = lines.len().saturating_sub(self.capacity);
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let temp_path = path.with_extension("ndjson.tmp");
+            fs::write(&temp_path, lines[start..].join("\n") + "\n").await?;
+            fs::rename(&temp_path, path).await?;
+        }
+
+        Ok(())
+    }
+
+    // Newest first.
+    pub async fn list(&self, project_dir: &Path, limit: usize) -> Result<Vec<QueryHistoryEntry>> {
+        self.flush().await?;
+        let _file = self.file.lock().await;
+
+        let lines = read_lines(&Self::path(project_dir)).await?;
+        lines
+            .iter()
+            .rev()
+            .take(limit)
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    pub async fn get(&self, project_dir: &Path, id: &str) -> Result<Option<QueryHistoryEntry>> {
+        let entries = self.list(project_dir, self.capacity).await?;
+        Ok(entries.into_iter().find(|entry| entry.id == id))
+    }
+}
+
+async fn read_lines(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).await?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_history_keeps_newest_entries() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let history = Arc::new(QueryHistory::with_capacity(3));
+
+        for i in 0..5 {
+            let sql = format!("SELECT {i}");
+            history.record(
+                tempdir.path(),
+                QueryHistoryEntry::new(&sql, Utc::now(), Ok(1)),
+            );
+        }
+        history.record(
+            tempdir.path(),
+            QueryHistoryEntry::new("SELEC 1", Utc::now(), Err("syntax error".to_string())),
+        );
+
+        let entries = history.list(tempdir.path(), 10).await?;
+        let sqls: Vec<&str> = entries.iter().map(|entry| entry.sql.as_str()).collect();
+        assert_eq!(sqls, vec!["SELEC 1", "SELECT 4", "SELECT 3"]);
+        assert!(!entries[0].success);
+        assert_eq!(entries[0].error.as_deref(), Some("syntax error"));
+        assert_eq!(entries[1].row_count, Some(1));
+
+        let content = std::fs::read_to_string(QueryHistory::path(tempdir.path()))?;
+        assert_eq!(content.lines().count(), 3);
+
+        let found = history.get(tempdir.path(), &entries[1].id).await?;
+        assert_eq!(found, Some(entries[1].clone()));
+
+        Ok(())
+    }
+}
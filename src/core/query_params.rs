@@ -0,0 +1,260 @@
+use crate::core::{
+    config::query::{ParamDef, ParamType, QueryConfig},
+    ducklake::{BoundSql, quote_literal},
+};
+use chrono::NaiveDate;
+use duckdb::types::Value;
+use regex::Regex;
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+#[derive(Debug, PartialEq)]
+pub enum ParamError {
+    Missing(Vec<String>),
+    Unknown(Vec<String>),
+    Invalid { name: String, message: String },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(names) => {
+                write!(f, "Missing required parameters: {}", names.join(", "))
+            }
+            Self::Unknown(names) => write!(f, "Unknown parameters: {}", names.join(", ")),
+            Self::Invalid { name, message } => {
+                write!(f, "Invalid value for parameter '{name}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ParamValue {
+    Date(NaiveDate),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl ParamValue {
+    fn parse(def: &ParamDef, value: &serde_json::Value) -> Result<Self, ParamError> {
+        use serde_json::Value as Json;
+
+        let parsed = match (def.param_type, value) {
+            (ParamType::Int, Json::Number(n)) => n.as_i64().map(Self::Int),
+            (ParamType::Int, Json::String(s)) => s.trim().parse().ok().map(Self::Int),
+            (ParamType::Float, Json::Number(n)) => n.as_f64().map(Self::Float),
+            (ParamType::Float, Json::String(s)) => s
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .map(Self::Float),
+            (ParamType::Date, Json::String(s)) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                .ok()
+                .map(Self::Date),
+            (ParamType::String, Json::String(s)) => Some(Self::String(s.clone())),
+            _ => None,
+        };
+
+        parsed.ok_or_else(|| ParamError::Invalid {
+            name: def.name.clone(),
+            message: match def.param_type {
+                ParamType::Date => "expected a date as YYYY-MM-DD",
+                ParamType::Int => "expected an integer",
+                ParamType::Float => "expected a number",
+                ParamType::String => "expected a string",
+            }
+            .to_string(),
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Date(date) => {
+                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                Value::Date32((*date - epoch).num_days() as i32)
+            }
+            Self::Int(i) => Value::BigInt(*i),
+            Self::Float(f) => Value::Double(*f),
+            Self::String(s) => Value::Text(s.clone()),
+        }
+    }
+
+    // Typed like the bound value, so DESCRIBE reports the same column types.
+    fn to_literal(&self) -> String {
+        match self {
+            Self::Date(date) => format!("DATE '{date}'"),
+            Self::Int(i) => format!("CAST({i} AS BIGINT)"),
+            Self::Float(f) => format!("CAST({f:?} AS DOUBLE)"),
+            Self::String(s) => quote_literal(s),
+        }
+    }
+}
+
+// Replaces each `{{name}}` with a `?` placeholder. Values missing from `params` fall
+// back to the parameter's default.
+pub fn bind_query(
+    query: &QueryConfig,
+    params: &HashMap<String, serde_json::Value>,
+) -> Result<BoundSql, ParamError> {
+    let mut unknown: Vec<String> = params
+        .keys()
+        .filter(|name| !query.parameters.iter().any(|def| &def.name == *name))
+        .cloned()
+        .collect();
+    for captures in PLACEHOLDER.captures_iter(&query.sql) {
+        let name = &captures[1];
+        if !query.parameters.iter().any(|def| def.name == name)
+            && !unknown.iter().any(|u| u == name)
+        {
+            unknown.push(name.to_string());
+        }
+    }
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(ParamError::Unknown(unknown));
+    }
+
+    let mut values = HashMap::new();
+    let mut missing = Vec::new();
+    for def in &query.parameters {
+        match params.get(&def.name).or(def.default.as_ref()) {
+            Some(value) => {
+                values.insert(def.name.as_str(), ParamValue::parse(def, value)?);
+            }
+            None => missing.push(def.name.clone()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(ParamError::Missing(missing));
+    }
+
+    let mut bound = Vec::new();
+    let sql = PLACEHOLDER.replace_all(&query.sql, |captures: &regex::Captures| {
+        bound.push(values[&captures[1]].to_value());
+        "?".to_string()
+    });
+    let inlined = PLACEHOLDER.replace_all(&query.sql, |captures: &regex::Captures| {
+        values[&captures[1]].to_literal()
+    });
+
+    Ok(BoundSql {
+        sql: sql.into_owned(),
+        params: bound,
+        inlined: inlined.into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(
+        sql: &str,
+        parameters: &[(&str, ParamType, Option<serde_json::Value>)],
+    ) -> QueryConfig {
+        QueryConfig {
+            description: None,
+            sql: sql.to_string(),
+            tags: vec![],
+            group: None,
+            parameters: parameters
+                .iter()
+                .map(|(name, param_type, default)| ParamDef {
+                    name: name.to_string(),
+                    param_type: *param_type,
+                    default: default.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn params(value: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_bind_query_types() {
+        let query = query(
+            "SELECT * FROM orders WHERE day >= {{start}} AND qty > {{ min_qty }} AND price < {{max_price}} AND status = {{status}} AND day <= {{start}}",
+            &[
+                ("start", ParamType::Date, None),
+                ("min_qty", ParamType::Int, Some(json!(1))),
+                ("max_price", ParamType::Float, None),
+                ("status", ParamType::String, Some(json!("open"))),
+            ],
+        );
+
+        let bound = bind_query(
+            &query,
+            &params(json!({ "start": "2024-01-01", "max_price": "9.5" })),
+        )
+        .unwrap();
+        assert_eq!(
+            bound.sql,
+            "SELECT * FROM orders WHERE day >= ? AND qty > ? AND price < ? AND status = ? AND day <= ?"
+        );
+        assert_eq!(
+            bound.params,
+            vec![
+                Value::Date32(19723),
+                Value::BigInt(1),
+                Value::Double(9.5),
+                Value::Text("open".to_string()),
+                Value::Date32(19723),
+            ]
+        );
+        assert_eq!(
+            bound.inlined,
+            "SELECT * FROM orders WHERE day >= DATE '2024-01-01' AND qty > CAST(1 AS BIGINT) AND price < CAST(9.5 AS DOUBLE) AND status = 'open' AND day <= DATE '2024-01-01'"
+        );
+    }
+
+    #[test]
+    fn test_bind_query_rejects_bad_values() {
+        let query = query(
+            "SELECT {{n}}, {{d}}",
+            &[("n", ParamType::Int, None), ("d", ParamType::Date, None)],
+        );
+
+        let error = bind_query(&query, &params(json!({ "d": "2024-01-01" }))).unwrap_err();
+        assert_eq!(error, ParamError::Missing(vec!["n".to_string()]));
+
+        let error = bind_query(
+            &query,
+            &params(json!({ "n": "1; DROP TABLE x", "d": "2024-01-01" })),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ParamError::Invalid { name, .. } if name == "n"));
+
+        let error = bind_query(&query, &params(json!({ "n": 1, "d": "01/02/2024" }))).unwrap_err();
+        assert!(matches!(error, ParamError::Invalid { name, .. } if name == "d"));
+
+        let error = bind_query(
+            &query,
+            &params(json!({ "n": 1, "d": "2024-01-01", "x": 2 })),
+        )
+        .unwrap_err();
+        assert_eq!(error, ParamError::Unknown(vec!["x".to_string()]));
+    }
+
+    #[test]
+    fn test_bind_query_quotes_inlined_strings() {
+        let query = query(
+            "SELECT {{name}} AS name",
+            &[("name", ParamType::String, None)],
+        );
+
+        let bound = bind_query(&query, &params(json!({ "name": "x' OR '1'='1" }))).unwrap();
+        assert_eq!(bound.sql, "SELECT ? AS name");
+        assert_eq!(bound.params, vec![Value::Text("x' OR '1'='1".to_string())]);
+        assert_eq!(bound.inlined, "SELECT 'x'' OR ''1''=''1' AS name");
+    }
+}
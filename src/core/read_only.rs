@@ -0,0 +1,214 @@
+use sqlparser::{
+    ast::{SetExpr, Statement},
+    dialect::DuckDbDialect,
+    keywords::Keyword,
+    parser::Parser,
+    tokenizer::{Token, Tokenizer, Word},
+};
+use std::fmt;
+
+// Statements that may start with these only read. DESCRIBE, SHOW and SUMMARIZE
+// are DuckDB's ways of inspecting a table.
+const READ_KEYWORDS: [&str; 11] = [
+    "SELECT",
+    "WITH",
+    "FROM",
+    "VALUES",
+    "TABLE",
+    "DESCRIBE",
+    "DESC",
+    "SHOW",
+    "SUMMARIZE",
+    "EXPLAIN",
+    "PIVOT",
+];
+
+// A WITH can end in a write, and EXPLAIN ANALYZE runs what it explains.
+const WRITE_KEYWORDS: [&str; 16] = [
+    "INSERT", "UPDATE", "DELETE", "MERGE", "CREATE", "DROP", "ALTER", "TRUNCATE", "COPY", "ATTACH",
+    "DETACH", "PRAGMA", "SET", "INSTALL", "LOAD", "CALL",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadOnlyError {
+    MultipleStatements(usize),
+    // The leading keyword of the offending statement, e.g. "DROP".
+    Write(String),
+    Unparseable(String),
+}
+
+impl fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MultipleStatements(count) => {
+                write!(f, "Only one statement can run at a time, found {count}")
+            }
+            Self::Write(kind) => write!(
+                f,
+                "{kind} statements are not allowed; only SELECT, WITH, DESCRIBE, SHOW and EXPLAIN can run"
+            ),
+            Self::Unparseable(message) => write!(f, "Could not read the query: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadOnlyError {}
+
+// Accepts a single statement that only reads. Writes are recognized by statement
+// type rather than by trying them, so nothing runs before the check.
+pub fn check_read_only(sql: &str) -> Result<(), ReadOnlyError> {
+    match Parser::parse_sql(&DuckDbDialect {}, sql) {
+        Ok(statements) => match statements.as_slice() {
+            [] => Ok(()),
+            [statement] => check_statement(statement),
+            _ => Err(ReadOnlyError::MultipleStatements(statements.len())),
+        },
+        // DuckDB accepts more than sqlparser does (`FROM t` first, SUMMARIZE, PIVOT),
+        // so fall back to the keywords of each statement.
+        Err(_) => check_tokens(sql),
+    }
+}
+
+fn check_statement(statement: &Statement) -> Result<(), ReadOnlyError> {
+    match statement {
+        Statement::Query(query) => match query.body.as_ref() {
+            SetExpr::Select(select) if select.into.is_some() => {
+                Err(ReadOnlyError::Write("SELECT INTO".to_string()))
+            }
+            SetExpr::Select(_) => Ok(()),
+            SetExpr::Query(_)
+            | SetExpr::SetOperation { .. }
+            | SetExpr::Values(_)
+            | SetExpr::Table(_) => Ok(()),
+            body => Err(ReadOnlyError::Write(leading_keyword(&body.to_string()))),
+        },
+        Statement::Explain { statement, .. } => check_statement(statement),
+        _ => {
+            let kind = leading_keyword(&statement.to_string());
+            if ["DESCRIBE", "DESC", "SHOW", "EXPLAIN"].contains(&kind.as_str()) {
+                Ok(())
+            } else {
+                Err(ReadOnlyError::Write(kind))
+            }
+        }
+    }
+}
+
+fn check_tokens(sql: &str) -> Result<(), ReadOnlyError> {
+    let tokens = Tokenizer::new(&DuckDbDialect {}, sql)
+        .tokenize()
+        .map_err(|e| ReadOnlyError::Unparseable(e.to_string()))?;
+
+    // Unquoted words only; a quoted "update" is a column, not a statement.
+    let statements: Vec<Vec<&Word>> = tokens
+        .split(|token| *token == Token::SemiColon)
+        .map(|statement| {
+            statement
+                .iter()
+                .filter_map(|token| match token {
+                    Token::Word(word) if word.quote_style.is_none() => Some(word),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+
+    let words = match statements.as_slice() {
+        [] => return Ok(()),
+        [words] => words,
+        _ => return Err(ReadOnlyError::MultipleStatements(statements.len())),
+    };
+
+    let first = words[0].value.to_uppercase();
+    if !READ_KEYWORDS.contains(&first.as_str()) {
+        // A typo is a bad query, not an attempted write.
+        return Err(match words[0].keyword {
+            Keyword::NoKeyword => ReadOnlyError::Unparseable(format!("Unknown statement {first}")),
+            _ => ReadOnlyError::Write(first),
+        });
+    }
+    if matches!(first.as_str(), "WITH" | "EXPLAIN")
+        && let Some(write) = words
+            .iter()
+            .map(|word| word.value.to_uppercase())
+            .find(|word| WRITE_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(ReadOnlyError::Write(write));
+    }
+
+    Ok(())
+}
+
+fn leading_keyword(sql: &str) -> String {
+    sql.split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_statements() {
+        for sql in [
+            "SELECT * FROM users",
+            "select 1;",
+            "WITH recent AS (SELECT * FROM orders) SELECT count(*) FROM recent",
+            "SELECT 1 UNION ALL SELECT 2",
+            "VALUES (1), (2)",
+            "DESCRIBE users",
+            "SHOW TABLES",
+            "EXPLAIN SELECT * FROM users",
+            "FROM users SELECT id",
+            "SUMMARIZE users",
+            "",
+        ] {
+            assert_eq!(check_read_only(sql), Ok(()), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_write_statements() {
+        for (sql, kind) in [
+            ("DROP TABLE users", "DROP"),
+            ("CREATE TABLE t AS SELECT 1", "CREATE"),
+            ("ALTER TABLE users ADD COLUMN age INTEGER", "ALTER"),
+            ("INSERT INTO users VALUES (1)", "INSERT"),
+            ("UPDATE users SET name = 'x'", "UPDATE"),
+            ("DELETE FROM users", "DELETE"),
+            ("PRAGMA database_list", "PRAGMA"),
+            ("ATTACH 'other.db' AS other", "ATTACH"),
+            ("COPY users TO 'users.csv'", "COPY"),
+            ("EXPLAIN ANALYZE DELETE FROM users", "DELETE"),
+        ] {
+            assert_eq!(
+                check_read_only(sql),
+                Err(ReadOnlyError::Write(kind.to_string())),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiple_statements() {
+        assert_eq!(
+            check_read_only("SELECT 1; DROP TABLE users"),
+            Err(ReadOnlyError::MultipleStatements(2))
+        );
+        assert_eq!(
+            check_read_only("SELECT 1) AS paged; DROP TABLE users; SELECT * FROM (SELECT 1"),
+            Err(ReadOnlyError::MultipleStatements(3))
+        );
+    }
+
+    #[test]
+    fn test_unknown_statement() {
+        assert!(matches!(
+            check_read_only("SELEC 1"),
+            Err(ReadOnlyError::Unparseable(_))
+        ));
+    }
+}
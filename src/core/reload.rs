@@ -0,0 +1,186 @@
+use crate::core::{
+    config::{Config, ConfigChanges},
+    graph::{Graph, GraphDiff},
+};
+use anyhow::Result;
+use notify::{Event, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt, path::PathBuf, sync::Arc};
+use tokio::{
+    sync::{Mutex, mpsc},
+    time::{Duration, timeout},
+};
+
+// Editors save in bursts (temp file, rename, chmod); reload once they go quiet.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReloadSummary {
+    #[serde(flatten)]
+    pub changes: ConfigChanges,
+    pub graph: GraphDiff,
+}
+
+impl ReloadSummary {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.graph.is_empty()
+    }
+}
+
+impl fmt::Display for ReloadSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} changed, {} removed, {} failed to parse; graph: {} added, {} removed, {} changed",
+            self.changes.changed.len(),
+            self.changes.removed.len(),
+            self.changes.errors.len(),
+            self.graph.added.len(),
+            self.graph.removed.len(),
+            self.graph.changed.len()
+        )
+    }
+}
+
+// `None` re-reads every config file.
+pub async fn reload(
+    config: &Mutex<Config>,
+    graph: &Mutex<Graph>,
+    paths: Option<Vec<PathBuf>>,
+) -> Result<ReloadSummary> {
+    let mut config = config.lock().await;
+    let paths = paths.unwrap_or_else(|| config.config_paths());
+    let changes = config.reload_files(&paths);
+
+    let mut graph = graph.lock().await;
+    let diff = graph.rebuild(&config);
+    if !diff.is_empty() {
+        graph.save().await?;
+    }
+
+    Ok(ReloadSummary {
+        changes,
+        graph: diff,
+    })
+}
+
+// Picks up edits made outside the API. Writes made through the API are seen too,
+// but they already match the in-memory config and reload as no-ops.
+pub async fn run_config_watcher(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+) -> Result<()> {
+    let (project_dir, directories) = {
+        let config = config.lock().await;
+        (config.project_dir.clone(), config.config_directories())
+    };
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event
+            && !event.kind.is_access()
+        {
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        }
+    })?;
+    // project.yml is replaced by a rename on save, which would drop a watch on the
+    // file itself, so its directory is watched instead.
+    watcher.watch(&project_dir, RecursiveMode::NonRecursive)?;
+    let mut watched = BTreeSet::new();
+    watch_directories(&mut watcher, &directories, &mut watched);
+
+    while let Some(path) = receiver.recv().await {
+        let mut changed = BTreeSet::from([path]);
+        while let Ok(Some(path)) = timeout(DEBOUNCE, receiver.recv()).await {
+            changed.insert(path);
+        }
+        // The project directory watch reports a resource directory being created;
+        // reloading it reads every file in it, including ones written before the
+        // new watch was in place.
+        watch_directories(&mut watcher, &directories, &mut watched);
+
+        match reload(&config, &graph, Some(changed.into_iter().collect())).await {
+            Ok(summary) if summary.is_empty() => {}
+            Ok(summary) => {
+                tracing::info!("Reloaded config: {summary}");
+                for error in &summary.changes.errors {
+                    tracing::warn!(
+                        "Kept the previous version of {}: {}",
+                        error.path,
+                        error.message
+                    );
+                }
+            }
+            Err(e) => tracing::error!("Failed to reload config: {e:#}"),
+        }
+    }
+
+    Ok(())
+}
+
+// Brings the watches in line with which resource directories exist right now, so
+// one created or recreated after startup is picked up too.
+fn watch_directories(
+    watcher: &mut impl Watcher,
+    directories: &[PathBuf],
+    watched: &mut BTreeSet<PathBuf>,
+) {
+    for directory in directories {
+        if !directory.is_dir() {
+            if watched.remove(directory) {
+                let _ = watcher.unwatch(directory);
+            }
+        } else if !watched.contains(directory) {
+            match watcher.watch(directory, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    watched.insert(directory.clone());
+                }
+                Err(e) => tracing::warn!("Failed to watch {directory:?}: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::project::ProjectConfig;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_config_watcher_picks_up_new_directories() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut config = Config::new(temp_dir.path().to_path_buf());
+        config
+            .add_project_setting(&ProjectConfig::default())?
+            .save()?;
+        let config = Arc::new(Mutex::new(config));
+        let graph = Arc::new(Mutex::new(Graph::new(temp_dir.path())));
+
+        let watcher = tokio::spawn(run_config_watcher(config.clone(), graph.clone()));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // `models` did not exist when the watcher started.
+        let models = temp_dir.path().join("models");
+        std::fs::create_dir_all(&models)?;
+        std::fs::write(models.join("active_users.yml"), "sql: SELECT 1\n")?;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        std::fs::write(models.join("orders.yml"), "sql: SELECT 2\n")?;
+
+        let mut loaded = false;
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let config = config.lock().await;
+            if config.models.contains_key("active_users") && config.models.contains_key("orders") {
+                loaded = true;
+                break;
+            }
+        }
+        watcher.abort();
+        assert!(loaded);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use crate::core::pipeline::{Phase, Pipeline, TaskStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+// The pipeline files keep older runs; the index only needs a window.
+const MAX_RUNS_PER_TASK: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub pipeline_id: String,
+    pub phase: Phase,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl TaskRun {
+    fn new(pipeline_id: &str, status: &TaskStatus) -> Self {
+        let started_at = status.started_at();
+        let completed_at = status.finished_at();
+        let duration_ms = started_at
+            .zip(completed_at)
+            .and_then(|(started, completed)| {
+                (completed - started).num_milliseconds().try_into().ok()
+            });
+
+        Self {
+            pipeline_id: pipeline_id.to_string(),
+            phase: status.phase().clone(),
+            started_at,
+            completed_at,
+            duration_ms,
+            error: status.error_message().map(str::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeRuns {
+    pub runs: Vec<TaskRun>,
+    // Both are over the returned runs only.
+    pub success_rate: Option<f64>,
+    pub median_duration_ms: Option<u64>,
+}
+
+// Finished runs of every task, newest first, so a node's history doesn't mean
+// reparsing every pipeline file. Built from those files the first time it is
+// read, then kept current by `Pipeline::save`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunHistory {
+    #[serde(skip)]
+    path: PathBuf,
+    tasks: HashMap<String, Vec<TaskRun>>,
+}
+
+impl RunHistory {
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".data").join("run_history.json")
+    }
+
+    // An unreadable index is rebuilt rather than reported.
+    pub async fn load(project_dir: &Path) -> Result<Self> {
+        let path = Self::path(project_dir);
+        if let Ok(content) = fs::read_to_string(&path).await
+            && let Ok(mut history) = serde_json::from_str::<Self>(&content)
+        {
+            history.path = path;
+            return Ok(history);
+        }
+
+        let mut history = Self {
+            path,
+            tasks: HashMap::new(),
+        };
+        let (pipelines, _) = Pipeline::load_all(project_dir).await?;
+        for pipeline in pipelines {
+            history.record(&pipeline);
+        }
+        history.save().await?;
+        Ok(history)
+    }
+
+    pub async fn update(project_dir: &Path, pipeline: &Pipeline) -> Result<()> {
+        let mut history = Self::load(project_dir).await?;
+        if history.record(pipeline) {
+            history.save().await?;
+        }
+        Ok(())
+    }
+
+    async fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::write(&self.path, serde_json::to_string(self)?)
+            .await
+            .with_context(|| format!("Failed to write run history to {:?}", self.path))
+    }
+
+    // Returns whether anything changed. Tasks still waiting or running are left
+    // out until they finish.
+    fn record(&mut self, pipeline: &Pipeline) -> bool {
+        let mut changed = false;
+        for (task, status) in &pipeline.tasks {
+            if !matches!(status.phase(), Phase::Completed | Phase::Failed) {
+                continue;
+            }
+
+            let run = TaskRun::new(&pipeline.id, status);
+            let runs = self.tasks.entry(task.clone()).or_default();
+            match runs.iter_mut().find(|r| r.pipeline_id == run.pipeline_id) {
+                Some(existing) if *existing == run => continue,
+                Some(existing) => *existing = run,
+                None => runs.push(run),
+            }
+            runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+            runs.truncate(MAX_RUNS_PER_TASK);
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn runs(&self, task: &str, limit: usize) -> NodeRuns {
+        let runs: Vec<TaskRun> = self
+            .tasks
+            .get(task)
+            .map(|runs| runs.iter().take(limit).cloned().collect())
+            .unwrap_or_default();
+
+        let completed = runs
+            .iter()
+            .filter(|run| run.phase == Phase::Completed)
+            .count();
+        let success_rate = (!runs.is_empty()).then(|| completed as f64 / runs.len() as f64);
+
+        let mut durations: Vec<u64> = runs
+            .iter()
+            .filter(|run| run.phase == Phase::Completed)
+            .filter_map(|run| run.duration_ms)
+            .collect();
+        durations.sort_unstable();
+        let median_duration_ms = match durations.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(durations[len / 2]),
+            len => Some((durations[len / 2 - 1] + durations[len / 2]) / 2),
+        };
+
+        NodeRuns {
+            runs,
+            success_rate,
+            median_duration_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_pipeline(project_dir: &Path, id: &str, tasks: serde_json::Value) -> Result<()> {
+        let dir = project_dir.join(".data").join("pipelines");
+        std::fs::create_dir_all(&dir)?;
+        let pipeline = json!({
+            "phase": "completed",
+            "started_at": "2024-01-01T00:00:00Z",
+            "completed_at": "2024-01-01T00:10:00Z",
+            "tasks": tasks,
+        });
+        std::fs::write(dir.join(format!("{id}.json")), pipeline.to_string())?;
+        Ok(())
+    }
+
+    fn completed(day: u32, seconds: u32) -> serde_json::Value {
+        json!({
+            "phase": "completed",
+            "started_at": format!("2024-01-0{day}T00:00:00Z"),
+            "completed_at": format!("2024-01-0{day}T00:00:{seconds:02}Z"),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_node_runs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_pipeline(
+            dir.path(),
+            "2024-01-01-00-00-00",
+            json!({ "users": completed(1, 10), "orders": completed(1, 5) }),
+        )?;
+        write_pipeline(
+            dir.path(),
+            "2024-01-02-00-00-00",
+            json!({
+                "users": {
+                    "phase": "failed",
+                    "started_at": "2024-01-02T00:00:00Z",
+                    "completed_at": null,
+                    "error": { "message": "Source table missing", "at": "2024-01-02T00:00:03Z" },
+                },
+                "orders": { "phase": "waiting", "started_at": null, "completed_at": null },
+            }),
+        )?;
+        write_pipeline(
+            dir.path(),
+            "2024-01-03-00-00-00",
+            json!({ "users": completed(3, 30) }),
+        )?;
+
+        let history = RunHistory::load(dir.path()).await?;
+        assert!(RunHistory::path(dir.path()).exists());
+
+        let users = history.runs("users", 20);
+        let ids: Vec<&str> = users
+            .runs
+            .iter()
+            .map(|run| run.pipeline_id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "2024-01-03-00-00-00",
+                "2024-01-02-00-00-00",
+                "2024-01-01-00-00-00"
+            ]
+        );
+        assert_eq!(users.runs[1].phase, Phase::Failed);
+        assert_eq!(users.runs[1].duration_ms, Some(3_000));
+        assert_eq!(users.runs[1].error.as_deref(), Some("Source table missing"));
+        assert_eq!(users.success_rate, Some(2.0 / 3.0));
+        assert_eq!(users.median_duration_ms, Some(20_000));
+
+        // Unfinished runs are not history yet.
+        assert_eq!(history.runs("orders", 20).runs.len(), 1);
+
+        let latest = history.runs("users", 1);
+        assert_eq!(latest.success_rate, Some(1.0));
+        assert_eq!(latest.median_duration_ms, Some(30_000));
+
+        assert_eq!(history.runs("unknown", 20).success_rate, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::core::{
+    config::{
+        Config,
+        project::{CompactionSchedule, next_cron_run},
+    },
+    ducklake::DuckLake,
+    graph::Graph,
+    maintenance::compact,
+    pipeline::{ActivePipeline, Phase, Pipeline, run_pipeline_all},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tokio::{sync::Mutex, time::Duration};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub async fn run_scheduler(
+    config: Arc<Mutex<Config>>,
+    graph: Arc<Mutex<Graph>>,
+    active: Arc<ActivePipeline>,
+) -> Result<()> {
+    let mut ticks = CronTicks::default();
+    let in_flight = Arc::new(AtomicBool::new(false));
+
+    loop {
+        let schedule = config
+            .lock()
+            .await
+            .project
+            .schedule
+            .clone()
+            .filter(|schedule| schedule.enabled);
+
+        let cron = schedule.as_ref().map(|schedule| schedule.cron.as_str());
+        if ticks.due("pipeline", cron, Utc::now()) {
+            trigger_pipeline(&config, &graph, &active, &in_flight).await;
+        }
+        tokio::time::sleep(ticks.wait()).await;
+    }
+}
+
+// The next tick of a cron expression that may be edited or disabled between polls.
+#[derive(Default)]
+struct CronTicks {
+    cron: Option<String>,
+    next_run: Option<DateTime<Utc>>,
+}
+
+impl CronTicks {
+    // Whether a tick of `cron` is due at `now`; `None` disables it. An expression that
+    // does not parse never fires.
+    fn due(&mut self, name: &str, cron: Option<&str>, now: DateTime<Utc>) -> bool {
+        let Some(cron) = cron else {
+            self.cron = None;
+            self.next_run = None;
+            return false;
+        };
+
+        // Recompute the next tick whenever the cron expression is edited.
+        if self.cron.as_deref() != Some(cron) {
+            self.cron = Some(cron.to_string());
+            self.next_run = match next_cron_run(cron, now) {
+                Ok(next_run) => next_run,
+                Err(e) => {
+                    tracing::warn!("Scheduled {name} disabled: {e}");
+                    None
+                }
+            };
+        }
+
+        match self.next_run {
+            Some(scheduled_at) if scheduled_at <= now => {
+                self.next_run = next_cron_run(cron, now).unwrap_or(None);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // How long to sleep before polling again.
+    fn wait(&self) -> Duration {
+        self.next_run
+            .and_then(|next_run| (next_run - Utc::now()).to_std().ok())
+            .map(|until_next| until_next.min(POLL_INTERVAL))
+            .unwrap_or(POLL_INTERVAL)
+    }
+}
+
+// Set from the moment a scheduled run is spawned until it returns, so a tick that
+// lands before the run has saved its Running status still skips. Dropping it
+// clears the flag, even when the run panics.
+struct InFlight(Arc<AtomicBool>);
+
+impl InFlight {
+    fn claim(flag: &Arc<AtomicBool>) -> Option<Self> {
+        (!flag.swap(true, Ordering::AcqRel)).then(|| Self(flag.clone()))
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+async fn trigger_pipeline(
+    config: &Arc<Mutex<Config>>,
+    graph: &Arc<Mutex<Graph>>,
+    active: &Arc<ActivePipeline>,
+    in_flight: &Arc<AtomicBool>,
+) {
+    let Some(claim) = InFlight::claim(in_flight) else {
+        tracing::info!("Skipping scheduled pipeline run: the previous one is still in progress");
+        return;
+    };
+
+    let project_dir = config.lock().await.project_dir.clone();
+
+    match Pipeline::load_latest(&project_dir).await {
+        Ok(Some(pipeline)) if pipeline.phase == Phase::Running => {
+            tracing::info!("Skipping scheduled pipeline run: a run is still in progress");
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("Failed to load latest pipeline status: {e}");
+        }
+    }
+    // A run started from the API may not have saved its Running status yet.
+    let pipeline = match active.claim(&project_dir).await {
+        Ok(pipeline) => pipeline,
+        Err(e) => {
+            tracing::info!("Skipping scheduled pipeline run: {e}");
+            return;
+        }
+    };
+
+    let config = config.clone();
+    let graph = graph.clone();
+    tokio::spawn(async move {
+        let _claim = claim;
+        if let Err(e) = run_pipeline_all(config, graph, pipeline, false, None, HashMap::new()).await
+        {
+            tracing::error!("Scheduled pipeline run failed: {e}");
+        }
+    });
+}
+
+pub async fn run_compaction_scheduler(
+    config: Arc<Mutex<Config>>,
+    active: Arc<ActivePipeline>,
+) -> Result<()> {
+    let mut ticks = CronTicks::default();
+
+    loop {
+        let schedule = config
+            .lock()
+            .await
+            .project
+            .compaction
+            .clone()
+            .filter(|compaction| compaction.enabled);
+
+        let cron = schedule.as_ref().map(|schedule| schedule.cron.as_str());
+        if ticks.due("compaction", cron, Utc::now())
+            && let Some(schedule) = &schedule
+        {
+            // Compaction rewrites files a running pipeline may be reading or writing,
+            // so a busy tick is skipped rather than queued.
+            if active.is_running().await {
+                tracing::info!("Skipping scheduled compaction: a pipeline is running");
+            } else if let Err(e) = run_compaction(&config, schedule).await {
+                tracing::error!("Scheduled compaction failed: {e:#}");
+            }
+        }
+        tokio::time::sleep(ticks.wait()).await;
+    }
+}
+
+async fn run_compaction(config: &Arc<Mutex<Config>>, schedule: &CompactionSchedule) -> Result<()> {
+    let ducklake = DuckLake::from_config(&*config.lock().await).await?;
+    let older_than_days = schedule.older_than_days();
+    // Compaction blocks on DuckDB for as long as it rewrites files.
+    let report =
+        tokio::task::spawn_blocking(move || compact(&ducklake, older_than_days, false)).await??;
+    tracing::info!(
+        "Compacted storage: expired {} snapshots, reclaimed {} files ({} bytes)",
+        report.expired_snapshots,
+        report.files_reclaimed,
+        report.bytes_reclaimed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_flight_claim() {
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let claim = InFlight::claim(&flag).unwrap();
+        assert!(InFlight::claim(&flag).is_none());
+
+        drop(claim);
+        assert!(InFlight::claim(&flag).is_some());
+    }
+
+    #[test]
+    fn test_cron_ticks_due() {
+        use chrono::TimeZone;
+
+        let mut ticks = CronTicks::default();
+        let noon = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let next_day = Utc.with_ymd_and_hms(2024, 1, 2, 3, 0, 0).unwrap();
+
+        assert!(!ticks.due("pipeline", Some("0 3 * * *"), noon));
+        assert!(ticks.due("pipeline", Some("0 3 * * *"), next_day));
+        assert!(!ticks.due("pipeline", Some("0 3 * * *"), next_day));
+
+        // Disabling forgets the pending tick.
+        assert!(!ticks.due("pipeline", None, noon));
+        assert_eq!(ticks.next_run, None);
+        assert!(!ticks.due("pipeline", Some("not a cron"), next_day));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::core::{
+    config::seed::SeedConfig,
+    ducklake::{DuckLake, quote_ident, quote_literal},
+    graph::TableStats,
+};
+use anyhow::{Context, Result};
+use std::{sync::Arc, time::Instant};
+
+pub struct Seed {
+    config: SeedConfig,
+    ducklake: Arc<DuckLake>,
+}
+
+impl Seed {
+    pub fn new(config: SeedConfig, ducklake: Arc<DuckLake>) -> Self {
+        Self { config, ducklake }
+    }
+
+    pub fn load(&self, table_name: &str) -> Result<TableStats> {
+        let started = Instant::now();
+        let path = self.config.path.to_string_lossy();
+        let sql = format!(
+            "CREATE OR REPLACE TABLE {} AS SELECT * FROM read_csv_auto({});",
+            quote_ident(table_name),
+            quote_literal(&path)
+        );
+
+        self.ducklake
+            .execute_batch(&sql)
+            .with_context(|| format!("Failed to load seed '{}'", self.config.path.display()))?;
+
+        TableStats::collect(&self.ducklake, table_name, started)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::adapter::test_helpers::{
+        create_test_csv_data, setup_test_ducklake, write_test_file,
+    };
+
+    #[tokio::test]
+    async fn test_load_seed() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let ducklake = setup_test_ducklake(tempdir.path()).await?;
+        write_test_file(tempdir.path(), "people.csv", &create_test_csv_data())?;
+
+        let seed = Seed::new(
+            SeedConfig {
+                path: tempdir.path().join("people.csv"),
+            },
+            ducklake.clone(),
+        );
+        let stats = seed.load("people")?;
+        assert_eq!(stats.row_count, 3);
+
+        let rows = ducklake.query("SELECT name FROM people ORDER BY id")?;
+        assert_eq!(rows[1][0], "Bob");
+
+        Ok(())
+    }
+}
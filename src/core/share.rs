@@ -0,0 +1,234 @@
+use crate::core::config::write_atomic;
+use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64};
+use chrono::{DateTime, Utc};
+use ring::{
+    digest::{SHA256, digest},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Share {
+    pub id: String,
+    pub dashboard: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    token_hash: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredShare {
+    id: String,
+    dashboard: String,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    token_hash: String,
+}
+
+// Like the API token, only the SHA-256 digest of each share token is kept on
+// disk; the plaintext is returned once, when the share is created.
+pub struct Shares {
+    project_dir: PathBuf,
+    shares: Vec<Share>,
+}
+
+impl Shares {
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".data").join("shares.json")
+    }
+
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = Self::path(project_dir);
+        let shares = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read shares from {path:?}"))?;
+            let stored: Vec<StoredShare> = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse shares from {path:?}"))?;
+            stored
+                .into_iter()
+                .map(|share| {
+                    Ok(Share {
+                        token_hash: hex_decode(&share.token_hash).with_context(|| {
+                            format!("Invalid token hash for share {}", share.id)
+                        })?,
+                        id: share.id,
+                        dashboard: share.dashboard,
+                        created_at: share.created_at,
+                        expires_at: share.expires_at,
+                    })
+                })
+                .collect::<Result<_>>()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            project_dir: project_dir.to_path_buf(),
+            shares,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path(&self.project_dir);
+        let stored: Vec<StoredShare> = self
+            .shares
+            .iter()
+            .map(|share| StoredShare {
+                id: share.id.clone(),
+                dashboard: share.dashboard.clone(),
+                created_at: share.created_at,
+                expires_at: share.expires_at,
+                token_hash: hex_encode(&share.token_hash),
+            })
+            .collect();
+        write_atomic(&path, &serde_json::to_string_pretty(&stored)?)
+            .with_context(|| format!("Failed to write shares to {path:?}"))?;
+        Ok(())
+    }
+
+    // Returns the new share and its plaintext token.
+    pub fn create(
+        &mut self,
+        dashboard: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(Share, String)> {
+        let mut bytes = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| anyhow::anyhow!("Failed to generate share token"))?;
+        let token = BASE64.encode(bytes);
+
+        let share = Share {
+            id: uuid::Uuid::new_v4().to_string(),
+            dashboard: dashboard.to_string(),
+            created_at: Utc::now(),
+            expires_at,
+            token_hash: hash_token(&token),
+        };
+        self.shares.push(share.clone());
+
+        Ok((share, token))
+    }
+
+    pub fn for_dashboard(&self, dashboard: &str) -> Vec<&Share> {
+        self.shares
+            .iter()
+            .filter(|share| share.dashboard == dashboard)
+            .collect()
+    }
+
+    pub fn revoke(&mut self, dashboard: &str, id: &str) -> bool {
+        let before = self.shares.len();
+        self.shares
+            .retain(|share| !(share.dashboard == dashboard && share.id == id));
+        self.shares.len() != before
+    }
+
+    pub fn revoke_dashboard(&mut self, dashboard: &str) -> bool {
+        let before = self.shares.len();
+        self.shares.retain(|share| share.dashboard != dashboard);
+        self.shares.len() != before
+    }
+
+    pub fn rename_dashboard(&mut self, old_name: &str, new_name: &str) -> bool {
+        let mut renamed = false;
+        for share in &mut self.shares {
+            if share.dashboard == old_name {
+                share.dashboard = new_name.to_string();
+                renamed = true;
+            }
+        }
+        renamed
+    }
+
+    // Every stored hash is compared in full so the lookup time does not depend
+    // on which share, or how much of a token, matched.
+    pub fn resolve(&self, token: &str, now: DateTime<Utc>) -> Option<&Share> {
+        let hash = hash_token(token);
+        let mut found = None;
+        for share in &self.shares {
+            if constant_time_eq(&share.token_hash, &hash) {
+                found = Some(share);
+            }
+        }
+        found.filter(|share| share.expires_at.is_none_or(|expires_at| expires_at > now))
+    }
+
+    #[cfg(test)]
+    pub fn set_expires_at(&mut self, id: &str, expires_at: Option<DateTime<Utc>>) {
+        for share in &mut self.shares {
+            if share.id == id {
+                share.expires_at = expires_at;
+            }
+        }
+    }
+}
+
+fn hash_token(token: &str) -> Vec<u8> {
+    digest(&SHA256, token.as_bytes()).as_ref().to_vec()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hex_decode(hex: &str) -> Result<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        anyhow::bail!("odd number of hex digits");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&hex[i..i + 2], 16)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_shares_roundtrip() -> Result<()> {
+        let dir = tempdir()?;
+        let now = Utc::now();
+
+        let mut shares = Shares::load(dir.path())?;
+        let (kept, kept_token) = shares.create("sales", None)?;
+        let (expiring, expiring_token) = shares.create("sales", Some(now + Duration::hours(1)))?;
+        let (_, other_token) = shares.create("ops", None)?;
+        shares.save()?;
+
+        let stored = fs::read_to_string(Shares::path(dir.path()))?;
+        assert!(!stored.contains(&kept_token));
+
+        let mut shares = Shares::load(dir.path())?;
+        assert_eq!(shares.resolve(&kept_token, now), Some(&kept));
+        assert_eq!(shares.resolve(&expiring_token, now), Some(&expiring));
+        assert_eq!(
+            shares.resolve(&expiring_token, now + Duration::hours(2)),
+            None
+        );
+        assert_eq!(shares.resolve("wrong", now), None);
+        assert_eq!(shares.for_dashboard("sales").len(), 2);
+
+        assert!(shares.revoke("sales", &kept.id));
+        assert!(!shares.revoke("ops", &expiring.id));
+        assert_eq!(shares.resolve(&kept_token, now), None);
+
+        assert!(shares.revoke_dashboard("ops"));
+        assert_eq!(shares.resolve(&other_token, now), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::{
+    fmt::{Debug, Write as _},
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+use tracing::{
+    Event, Id, Span, Subscriber,
+    field::{Field, Visit},
+    span::Attributes,
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+const TASK_SPAN: &str = "task";
+const LOG_PATH_FIELD: &str = "log_path";
+pub const MAX_LOG_BYTES: usize = 4 * 1024 * 1024;
+
+// Events recorded inside this span end up in the log file at `log_path`.
+pub fn task_span(task: &str, log_path: &Path) -> Span {
+    tracing::info_span!(TASK_SPAN, task = task, log_path = %log_path.display())
+}
+
+pub async fn read_task_log(path: &Path, tail: Option<usize>) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).await?;
+    let Some(tail) = tail else {
+        return Ok(Some(content));
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(tail);
+    Ok(Some(lines[start..].join("\n")))
+}
+
+struct TaskLog {
+    path: PathBuf,
+    buffer: String,
+    truncated: bool,
+}
+
+impl TaskLog {
+    fn append(&mut self, line: &str) {
+        if self.truncated {
+            return;
+        }
+        if self.buffer.len() + line.len() + 1 > MAX_LOG_BYTES {
+            self.truncated = true;
+            return;
+        }
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+    }
+
+    fn persist(mut self) -> std::io::Result<()> {
+        if self.truncated {
+            self.buffer.push_str(&format!(
+                "... log truncated at {MAX_LOG_BYTES} bytes, later events were dropped\n"
+            ));
+        }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, self.buffer)
+    }
+}
+
+#[derive(Default)]
+struct LogPathVisitor {
+    path: Option<PathBuf>,
+}
+
+impl Visit for LogPathVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == LOG_PATH_FIELD {
+            self.path = Some(PathBuf::from(format!("{value:?}")));
+        }
+    }
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+// Routes events emitted inside a task span into that task's log buffer and
+// writes the buffer out once the span closes.
+pub struct TaskLogLayer;
+
+impl<S> Layer<S> for TaskLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != TASK_SPAN {
+            return;
+        }
+
+        let mut visitor = LogPathVisitor::default();
+        attrs.record(&mut visitor);
+        if let Some(path) = visitor.path
+            && let Some(span) = ctx.span(id)
+        {
+            span.extensions_mut().insert(TaskLog {
+                path,
+                buffer: String::new(),
+                truncated: false,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+
+        for span in scope {
+            let mut extensions = span.extensions_mut();
+            if let Some(log) = extensions.get_mut::<TaskLog>() {
+                let mut visitor = LineVisitor::default();
+                event.record(&mut visitor);
+                let metadata = event.metadata();
+                log.append(&format!(
+                    "{} {:>5} {}: {}{}",
+                    Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                    metadata.level(),
+                    metadata.target(),
+                    visitor.message,
+                    visitor.fields
+                ));
+                return;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(log) = span.extensions_mut().remove::<TaskLog>() else {
+            return;
+        };
+
+        // Logging here would re-enter the subscriber, so report on stderr instead.
+        if let Err(e) = log.persist() {
+            eprintln!("Failed to write task log: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_task_log_truncation() {
+        let mut log = TaskLog {
+            path: PathBuf::new(),
+            buffer: String::new(),
+            truncated: false,
+        };
+        let line = "x".repeat(1024 * 1024);
+        for _ in 0..5 {
+            log.append(&line);
+        }
+
+        assert!(log.truncated);
+        assert!(log.buffer.len() <= MAX_LOG_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_read_task_log_tail() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("task.log");
+
+        let subscriber = tracing_subscriber::registry().with(TaskLogLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = task_span("task", &path);
+            let _entered = span.enter();
+            tracing::info!(rows = 3, "first");
+            tracing::warn!("second");
+            tracing::info!("third");
+        });
+
+        let content = read_task_log(&path, None).await?.unwrap();
+        assert_eq!(content.lines().count(), 3);
+        assert!(content.lines().next().unwrap().ends_with("first rows=3"));
+
+        let tail = read_task_log(&path, Some(2)).await?.unwrap();
+        assert!(tail.starts_with(&content.lines().nth(1).unwrap().to_string()));
+        assert!(tail.ends_with("third"));
+
+        assert!(
+            read_task_log(&tempdir.path().join("missing.log"), None)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
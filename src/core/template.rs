@@ -0,0 +1,362 @@
+use crate::core::{config::Config, ducklake::quote_ident, graph::dependent_tables};
+use regex::Regex;
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+// `{{ name('arg', ...) }}`, where every argument is a quoted string.
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)\{\{(.*?)\}\}").unwrap());
+static CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)^\s*(\w+)\s*\((.*)\)\s*$").unwrap());
+static ARGUMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"'([^']*)'|"([^"]*)""#).unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    UnknownModel(String),
+    UnknownAdapter(String),
+    MissingVar(String),
+    // The text of a tag that isn't a ref(), source() or var() call.
+    Invalid(String),
+}
+
+impl TemplateError {
+    // The model or adapter a failed ref() or source() names.
+    pub fn missing_resource(&self) -> Option<&str> {
+        match self {
+            Self::UnknownModel(name) | Self::UnknownAdapter(name) => Some(name),
+            Self::MissingVar(_) | Self::Invalid(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownModel(name) => write!(f, "ref('{name}') names no model or seed"),
+            Self::UnknownAdapter(name) => write!(f, "source('{name}') names no adapter"),
+            Self::MissingVar(name) => write!(
+                f,
+                "var('{name}') has no value in the project's vars or the run, and no default"
+            ),
+            Self::Invalid(tag) => write!(
+                f,
+                "Unsupported template tag '{{{{{tag}}}}}'; use ref('model'), source('adapter') or var('name', 'default')"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledSql {
+    pub sql: String,
+    // Nodes named by ref() and source(), in order of first use.
+    pub dependencies: Vec<String>,
+}
+
+// Replaces `{{ ref('model') }}` and `{{ source('adapter') }}` with the table each
+// builds, and `{{ var('name', 'default') }}` with the value from `overrides`, the
+// project's `vars` or the default, in that order. SQL without tags is unchanged.
+pub fn compile(
+    config: &Config,
+    sql: &str,
+    overrides: &HashMap<String, String>,
+) -> Result<CompiledSql, TemplateError> {
+    render(config, sql, overrides, None)
+}
+
+// Like compile(), but a var() without a value becomes NULL instead of an error, so a
+// model's dependencies can be read before the run that supplies its vars. Only
+// unknown ref() and source() names and malformed tags fail.
+pub fn compile_for_analysis(config: &Config, sql: &str) -> Result<CompiledSql, TemplateError> {
+    render(config, sql, &HashMap::new(), Some("NULL"))
+}
+
+fn render(
+    config: &Config,
+    sql: &str,
+    overrides: &HashMap<String, String>,
+    placeholder: Option<&str>,
+) -> Result<CompiledSql, TemplateError> {
+    let mut compiled = CompiledSql {
+        sql: String::with_capacity(sql.len()),
+        dependencies: Vec::new(),
+    };
+    let mut rest = 0;
+
+    for tag in TAG.captures_iter(sql) {
+        let whole = tag.get(0).unwrap();
+        let inner = tag[1].trim();
+        let (function, arguments) = parse_call(inner)?;
+
+        let replacement = match (function.as_str(), arguments.as_slice()) {
+            ("ref", [name]) => {
+                if !config.models.contains_key(name) && !config.seeds.contains_key(name) {
+                    return Err(TemplateError::UnknownModel(name.clone()));
+                }
+                add_dependency(&mut compiled.dependencies, name);
+                quote_ident(name)
+            }
+            ("source", [name]) => {
+                let Some(adapter) = config.adapters.get(name) else {
+                    return Err(TemplateError::UnknownAdapter(name.clone()));
+                };
+                add_dependency(&mut compiled.dependencies, name);
+                quote_ident(adapter.table_name(name))
+            }
+            ("var", [name, default @ ..]) if default.len() <= 1 => overrides
+                .get(name)
+                .or_else(|| config.project.vars.get(name))
+                .or(default.first())
+                .cloned()
+                .or_else(|| placeholder.map(str::to_string))
+                .ok_or_else(|| TemplateError::MissingVar(name.clone()))?,
+            _ => return Err(TemplateError::Invalid(inner.to_string())),
+        };
+
+        compiled.sql.push_str(&sql[rest..whole.start()]);
+        compiled.sql.push_str(&replacement);
+        rest = whole.end();
+    }
+
+    compiled.sql.push_str(&sql[rest..]);
+    Ok(compiled)
+}
+
+fn parse_call(inner: &str) -> Result<(String, Vec<String>), TemplateError> {
+    let invalid = || TemplateError::Invalid(inner.to_string());
+    let call = CALL.captures(inner).ok_or_else(invalid)?;
+    let arguments = &call[2];
+
+    // Only quoted strings separated by commas may appear between the parentheses.
+    if ARGUMENT
+        .replace_all(arguments, "")
+        .chars()
+        .any(|c| c != ',' && !c.is_whitespace())
+    {
+        return Err(invalid());
+    }
+    let arguments = ARGUMENT
+        .captures_iter(arguments)
+        .map(|argument| {
+            argument
+                .get(1)
+                .or_else(|| argument.get(2))
+                .map_or(String::new(), |value| value.as_str().to_string())
+        })
+        .collect();
+
+    Ok((call[1].to_string(), arguments))
+}
+
+fn add_dependency(dependencies: &mut Vec<String>, name: &str) {
+    if !dependencies.iter().any(|dependency| dependency == name) {
+        dependencies.push(name.to_string());
+    }
+}
+
+// The nodes a model depends on: those named by ref() and source(), then the owners
+// of any other tables the compiled SQL reads. SQL that doesn't parse contributes
+// only its explicit references.
+pub fn dependencies(config: &Config, sql: &str) -> Result<Vec<String>, TemplateError> {
+    let compiled = compile_for_analysis(config, sql)?;
+    let mut dependencies = compiled.dependencies;
+    for node in config.table_nodes(dependent_tables(&compiled.sql).unwrap_or_default()) {
+        add_dependency(&mut dependencies, &node);
+    }
+    Ok(dependencies)
+}
+
+// What renaming a node means for the models that read it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RenamedReferences {
+    // Each model whose ref() or source() calls name the node, with its SQL rewritten
+    // to the new name.
+    pub rewritten: Vec<(String, String)>,
+    // Models that read the node's table by name, which can't be rewritten safely.
+    pub by_table: Vec<String>,
+}
+
+// `function` is "ref" for models and seeds and "source" for adapters. `table` is the
+// node's table when renaming the node renames it too.
+pub fn rename_references(
+    config: &Config,
+    function: &str,
+    from: &str,
+    to: &str,
+    table: Option<&str>,
+) -> RenamedReferences {
+    let mut renamed = RenamedReferences::default();
+    let mut names: Vec<&String> = config.models.keys().filter(|name| *name != from).collect();
+    names.sort();
+
+    for name in names {
+        let sql = &config.models[name].sql;
+        if table.is_some_and(|table| plain_tables(sql).iter().any(|t| t == table)) {
+            renamed.by_table.push(name.clone());
+            continue;
+        }
+
+        let rewritten = rename_calls(sql, function, from, to);
+        if rewritten != *sql {
+            renamed.rewritten.push((name.clone(), rewritten));
+        }
+    }
+    renamed
+}
+
+fn rename_calls(sql: &str, function: &str, from: &str, to: &str) -> String {
+    TAG.replace_all(sql, |tag: &regex::Captures| {
+        match parse_call(tag[1].trim()) {
+            Ok((name, arguments)) if name == function && arguments == [from] => {
+                format!("{{{{ {function}('{to}') }}}}")
+            }
+            _ => tag[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+// The tables `sql` reads by name rather than through a tag.
+fn plain_tables(sql: &str) -> Vec<String> {
+    dependent_tables(&TAG.replace_all(sql, "duckhub_template_tag")).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        adapter::test_helpers::create_csv_adapter_config, config::model::ModelConfig,
+    };
+
+    fn config() -> Config {
+        let mut config = Config::new(std::env::temp_dir());
+        let mut adapter = create_csv_adapter_config();
+        adapter.target_table = Some("raw_orders".to_string());
+        config.adapters.insert("orders".to_string(), adapter);
+        config.models.insert(
+            "customers".to_string(),
+            serde_yml::from_str::<ModelConfig>("sql: SELECT 1 AS id").unwrap(),
+        );
+        config
+            .project
+            .vars
+            .insert("since".to_string(), "'2024-01-01'".to_string());
+        config
+    }
+
+    #[test]
+    fn test_compile_ref_and_source() {
+        let config = config();
+        let sql = "SELECT * FROM {{ source('orders') }} o JOIN {{ref(\"customers\")}} c ON o.customer_id = c.id";
+
+        let compiled = compile(&config, sql, &HashMap::new()).unwrap();
+        assert_eq!(
+            compiled.sql,
+            "SELECT * FROM \"raw_orders\" o JOIN \"customers\" c ON o.customer_id = c.id"
+        );
+        assert_eq!(compiled.dependencies, vec!["orders", "customers"]);
+        assert_eq!(
+            dependencies(&config, sql).unwrap(),
+            vec!["orders", "customers"]
+        );
+
+        // Plain SQL passes through, and its tables still count.
+        let sql = "SELECT * FROM raw_orders";
+        assert_eq!(compile(&config, sql, &HashMap::new()).unwrap().sql, sql);
+        assert_eq!(dependencies(&config, sql).unwrap(), vec!["orders"]);
+    }
+
+    #[test]
+    fn test_compile_vars() {
+        let config = config();
+        let sql = "SELECT * FROM t WHERE day >= {{ var('since') }} LIMIT {{ var('limit', '10') }}";
+
+        let compiled = compile(&config, sql, &HashMap::new()).unwrap();
+        assert_eq!(
+            compiled.sql,
+            "SELECT * FROM t WHERE day >= '2024-01-01' LIMIT 10"
+        );
+
+        let overrides = HashMap::from([
+            ("since".to_string(), "'2025-06-01'".to_string()),
+            ("limit".to_string(), "5".to_string()),
+        ]);
+        let compiled = compile(&config, sql, &overrides).unwrap();
+        assert_eq!(
+            compiled.sql,
+            "SELECT * FROM t WHERE day >= '2025-06-01' LIMIT 5"
+        );
+
+        assert_eq!(
+            compile(&config, "SELECT {{ var('missing') }}", &HashMap::new()),
+            Err(TemplateError::MissingVar("missing".to_string()))
+        );
+
+        // Dependencies don't wait for the run that supplies the var.
+        let sql = "SELECT * FROM {{ ref('customers') }} WHERE id = {{ var('missing') }}";
+        assert_eq!(
+            compile_for_analysis(&config, sql).unwrap().sql,
+            "SELECT * FROM \"customers\" WHERE id = NULL"
+        );
+        assert_eq!(dependencies(&config, sql).unwrap(), vec!["customers"]);
+    }
+
+    #[test]
+    fn test_rename_references() {
+        let mut config = config();
+        config.models.insert(
+            "report".to_string(),
+            serde_yml::from_str::<ModelConfig>(
+                "sql: SELECT * FROM {{ ref('customers') }} JOIN {{ source('orders') }} USING (id)",
+            )
+            .unwrap(),
+        );
+        config.models.insert(
+            "legacy".to_string(),
+            serde_yml::from_str::<ModelConfig>("sql: SELECT * FROM customers").unwrap(),
+        );
+
+        let renamed = rename_references(&config, "ref", "customers", "clients", Some("customers"));
+        assert_eq!(
+            renamed.rewritten,
+            vec![(
+                "report".to_string(),
+                "SELECT * FROM {{ ref('clients') }} JOIN {{ source('orders') }} USING (id)"
+                    .to_string()
+            )]
+        );
+        assert_eq!(renamed.by_table, vec!["legacy"]);
+
+        // The adapter imports into `raw_orders`, which keeps its name.
+        let renamed = rename_references(&config, "source", "orders", "purchases", None);
+        assert_eq!(renamed.rewritten.len(), 1);
+        assert!(renamed.rewritten[0].1.contains("{{ source('purchases') }}"));
+        assert!(renamed.by_table.is_empty());
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let config = config();
+        let error =
+            compile(&config, "SELECT * FROM {{ ref('nope') }}", &HashMap::new()).unwrap_err();
+        assert_eq!(error, TemplateError::UnknownModel("nope".to_string()));
+        assert_eq!(error.missing_resource(), Some("nope"));
+
+        assert_eq!(
+            compile(
+                &config,
+                "SELECT * FROM {{ source('customers') }}",
+                &HashMap::new()
+            ),
+            Err(TemplateError::UnknownAdapter("customers".to_string()))
+        );
+        assert_eq!(
+            compile(&config, "SELECT {{ orders }}", &HashMap::new()),
+            Err(TemplateError::Invalid("orders".to_string()))
+        );
+        assert_eq!(
+            compile(&config, "SELECT * FROM {{ ref(orders) }}", &HashMap::new()),
+            Err(TemplateError::Invalid("ref(orders)".to_string()))
+        );
+    }
+}
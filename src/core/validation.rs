@@ -0,0 +1,373 @@
+use crate::core::{
+    config::{Config, data_test::RelationshipConfig},
+    graph::{Graph, dependent_tables},
+    template::compile,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    Project,
+    MissingConnection,
+    InvalidModelSql,
+    UnknownTable,
+    MissingQuery,
+    OrphanNode,
+    UnknownRelationship,
+    InvalidConfigFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub kind: FindingKind,
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Error)
+    }
+
+    fn push(&mut self, severity: Severity, kind: FindingKind, name: &str, message: String) {
+        self.findings.push(Finding {
+            severity,
+            kind,
+            name: name.to_string(),
+            message,
+        });
+    }
+}
+
+pub fn validate_project(config: &Config, graph: &Graph) -> Result<ValidationReport> {
+    let mut report = ValidationReport::default();
+
+    for warning in config.project.validate(&config.project_dir)? {
+        report.push(Severity::Warning, FindingKind::Project, "project", warning);
+    }
+
+    for (path, message) in &config.load_errors {
+        report.push(
+            Severity::Error,
+            FindingKind::InvalidConfigFile,
+            path,
+            format!("{path} could not be reloaded and its previous version is in use: {message}"),
+        );
+    }
+
+    for (name, adapter) in sorted(&config.adapters) {
+        if !config.project.connections.contains_key(&adapter.connection) {
+            report.push(
+                Severity::Error,
+                FindingKind::MissingConnection,
+                name,
+                format!(
+                    "Adapter '{name}' references missing connection '{}'",
+                    adapter.connection
+                ),
+            );
+        }
+    }
+
+    for (name, model) in sorted(&config.models) {
+        let sql = match compile(config, &model.sql, &HashMap::new()) {
+            Ok(compiled) => compiled.sql,
+            Err(e) => {
+                report.push(
+                    Severity::Error,
+                    FindingKind::InvalidModelSql,
+                    name,
+                    format!("Model '{name}' has an invalid template: {e}"),
+                );
+                continue;
+            }
+        };
+        let tables = match dependent_tables(&sql) {
+            Ok(tables) => tables,
+            Err(e) => {
+                report.push(
+                    Severity::Error,
+                    FindingKind::InvalidModelSql,
+                    name,
+                    format!("Model '{name}' has invalid SQL: {e}"),
+                );
+                continue;
+            }
+        };
+
+        for table in tables {
+            if !config.defines_table(&table) {
+                report.push(
+                    Severity::Warning,
+                    FindingKind::UnknownTable,
+                    name,
+                    format!(
+                        "Model '{name}' references '{table}', which is not an adapter, model or seed"
+                    ),
+                );
+            }
+        }
+    }
+
+    let mut related: Vec<(&String, &Vec<RelationshipConfig>)> = config
+        .adapters
+        .iter()
+        .map(|(name, adapter)| (name, &adapter.relationships))
+        .chain(
+            config
+                .models
+                .iter()
+                .map(|(name, model)| (name, &model.relationships)),
+        )
+        .collect();
+    related.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, relationships) in related {
+        for relationship in relationships {
+            if config
+                .resolve_table(&relationship.references.table)
+                .is_none()
+            {
+                report.push(
+                    Severity::Error,
+                    FindingKind::UnknownRelationship,
+                    name,
+                    format!(
+                        "'{name}' relates column '{}' to '{}', which is not an adapter, model or seed",
+                        relationship.column, relationship.references
+                    ),
+                );
+            }
+        }
+    }
+
+    for (name, dashboard) in sorted(&config.dashboards) {
+        for query in dashboard.queries() {
+            if !config.queries.contains_key(query) {
+                report.push(
+                    Severity::Error,
+                    FindingKind::MissingQuery,
+                    name,
+                    format!("Dashboard '{name}' references missing query '{query}'"),
+                );
+            }
+        }
+    }
+
+    for (name, _) in sorted(&graph.nodes) {
+        if !config.defines_node(name) {
+            report.push(
+                Severity::Warning,
+                FindingKind::OrphanNode,
+                name,
+                format!("Graph node '{name}' has no adapter, model or seed configuration"),
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+fn sorted<T>(map: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries: Vec<(&String, &T)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{
+        adapter::{AdapterConfig, AdapterSource},
+        dashboard::{ChartConfig, ChartType, DashboardConfig, PanelConfig},
+        data_test::ColumnReference,
+        model::{Materialization, ModelConfig},
+        project::ConnectionConfig,
+    };
+
+    fn broken_project(project_dir: &std::path::Path) -> (Config, Graph) {
+        let mut config = Config::new(project_dir.to_path_buf());
+
+        config.project.connections.insert(
+            "local".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: project_dir.to_string_lossy().to_string(),
+            },
+        );
+
+        let adapter = |connection: &str| AdapterConfig {
+            connection: connection.to_string(),
+            target_table: None,
+            dedupe: None,
+            description: None,
+            source: AdapterSource::Database {
+                table_name: "users".to_string(),
+                columns: None,
+                filter: None,
+                incremental: None,
+            },
+            columns: vec![],
+            tests: vec![],
+            relationships: vec![],
+            on_schema_change: None,
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        config
+            .adapters
+            .insert("users".to_string(), adapter("local"));
+        config
+            .adapters
+            .insert("orders".to_string(), adapter("missing_connection"));
+        config.adapters.get_mut("users").unwrap().relationships = vec![RelationshipConfig {
+            column: "team_id".to_string(),
+            references: ColumnReference {
+                table: "teams".to_string(),
+                column: "id".to_string(),
+            },
+            severity: Default::default(),
+        }];
+
+        let model = |sql: &str| ModelConfig {
+            description: None,
+            sql: sql.to_string(),
+            materialization: Materialization::Table,
+            unique_key: None,
+            updated_at_column: None,
+            tests: vec![],
+            relationships: vec![],
+            tags: vec![],
+            group: None,
+            weight: None,
+            exclusive: false,
+            masking: vec![],
+        };
+        config.models.insert(
+            "active_users".to_string(),
+            model("SELECT * FROM users WHERE active"),
+        );
+        config
+            .models
+            .insert("broken".to_string(), model("SELEC * FORM users"));
+        config
+            .models
+            .insert("dangling".to_string(), model("SELECT * FROM ghosts"));
+
+        config.dashboards.insert(
+            "sales".to_string(),
+            DashboardConfig {
+                description: None,
+                panels: vec![PanelConfig::new(
+                    "missing_query",
+                    ChartConfig {
+                        chart_type: ChartType::Bar,
+                        x_column: "x".to_string(),
+                        y_column: "y".to_string(),
+                    },
+                )],
+                tags: vec![],
+                group: None,
+            },
+        );
+
+        let mut graph = Graph::new(project_dir);
+        graph.create_node("users", &[]);
+        graph.create_node("orders", &[]);
+        graph.create_node("active_users", &["users"]);
+        graph.create_node("leftover", &[]);
+
+        (config, graph)
+    }
+
+    fn find<'a>(report: &'a ValidationReport, kind: FindingKind) -> Vec<&'a Finding> {
+        report
+            .findings
+            .iter()
+            .filter(|finding| finding.kind == kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_project_detects_problems() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config, graph) = broken_project(tempdir.path());
+
+        let report = validate_project(&config, &graph).unwrap();
+        assert!(report.has_errors());
+
+        let missing_connection = find(&report, FindingKind::MissingConnection);
+        assert_eq!(missing_connection.len(), 1);
+        assert_eq!(missing_connection[0].name, "orders");
+        assert_eq!(missing_connection[0].severity, Severity::Error);
+
+        let invalid_sql = find(&report, FindingKind::InvalidModelSql);
+        assert_eq!(invalid_sql.len(), 1);
+        assert_eq!(invalid_sql[0].name, "broken");
+
+        let unknown_table = find(&report, FindingKind::UnknownTable);
+        assert_eq!(unknown_table.len(), 1);
+        assert_eq!(unknown_table[0].name, "dangling");
+        assert!(unknown_table[0].message.contains("ghosts"));
+
+        let missing_query = find(&report, FindingKind::MissingQuery);
+        assert_eq!(missing_query.len(), 1);
+        assert_eq!(missing_query[0].name, "sales");
+
+        let unknown_relationship = find(&report, FindingKind::UnknownRelationship);
+        assert_eq!(unknown_relationship.len(), 1);
+        assert_eq!(unknown_relationship[0].name, "users");
+        assert!(unknown_relationship[0].message.contains("teams.id"));
+
+        let orphans = find(&report, FindingKind::OrphanNode);
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].name, "leftover");
+    }
+
+    #[test]
+    fn test_validate_project_includes_project_warnings() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().to_path_buf());
+        config.project.connections.insert(
+            "missing_dir".to_string(),
+            ConnectionConfig::LocalFile {
+                base_path: "does/not/exist".to_string(),
+            },
+        );
+
+        let report = validate_project(&config, &Graph::new(tempdir.path())).unwrap();
+
+        let project = find(&report, FindingKind::Project);
+        assert_eq!(project.len(), 1);
+        assert_eq!(project[0].severity, Severity::Warning);
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn test_validate_project_clean() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config = Config::new(tempdir.path().to_path_buf());
+
+        let report = validate_project(&config, &Graph::new(tempdir.path())).unwrap();
+        assert!(report.findings.is_empty());
+    }
+}
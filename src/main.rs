@@ -1,10 +1,11 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use commands::{
-    new::{create_gitignore, create_secret_key},
-    samples::create_samples,
+    query::{DEFAULT_MAX_WIDTH, OutputFormat, execute_query},
+    templates::Template,
 };
-use core::config::{Config, project::ProjectConfig};
+use core::config::{Config, ENV_VAR};
+use std::path::PathBuf;
 
 pub mod api;
 pub mod commands;
@@ -14,19 +15,35 @@ pub mod error_handle;
 pub mod test_helpers;
 pub mod ui;
 
-use tracing_subscriber::EnvFilter;
+use core::task_log::TaskLogLayer;
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 fn setup_tracing() {
     #[cfg(debug_assertions)]
-    {
-        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"));
-        tracing_subscriber::fmt().with_env_filter(filter).init();
-    }
-
+    let default_filter = "debug";
     #[cfg(not(debug_assertions))]
-    {
-        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
-        tracing_subscriber::fmt().with_env_filter(filter).init();
+    let default_filter = "warn";
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+
+    // Task logs keep info-level events even when the console is quieter.
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(filter))
+        .with(TaskLogLayer.with_filter(LevelFilter::INFO))
+        .init();
+}
+
+fn select_env(flag: &Option<String>) -> Option<String> {
+    flag.clone().or_else(|| std::env::var(ENV_VAR).ok())
+}
+
+fn parse_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected NAME=VALUE, got '{value}'")),
     }
 }
 
@@ -41,11 +58,110 @@ struct Cli {
 enum Commands {
     New {
         project_name: String,
+        #[arg(
+            long,
+            value_enum,
+            default_value = "sample",
+            help = "What to put in the new project"
+        )]
+        template: Template,
+    },
+    #[command(about = "Set up an existing project directory, e.g. a fresh checkout")]
+    Init {
+        #[arg(default_value = ".", help = "Project directory")]
+        path: PathBuf,
     },
     Start {
         project_name: String,
         #[arg(short, long, default_value = "3015")]
         port: u16,
+        #[arg(
+            long,
+            default_value = "localhost",
+            help = "Interface to bind the servers to"
+        )]
+        host: String,
+        #[arg(
+            long,
+            help = "Serve the API without requiring a token (local development only)"
+        )]
+        no_auth: bool,
+        #[arg(
+            long,
+            default_value = "30",
+            help = "Seconds to wait for running pipeline tasks on shutdown"
+        )]
+        shutdown_grace: u64,
+        #[arg(
+            long,
+            help = "Apply the project.<ENV>.yml overlay (defaults to $DUCKHUB_ENV)"
+        )]
+        env: Option<String>,
+    },
+    RotateKey {
+        project_name: String,
+    },
+    #[command(about = "Issue an admin API token, which may request unmasked results")]
+    AdminToken {
+        project_name: String,
+    },
+    #[command(about = "Export the project definitions as a portable .tar.gz bundle")]
+    Export {
+        project_name: String,
+        #[arg(long, help = "Bundle file to write (defaults to <project>.tar.gz)")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Import a bundle created by `duckhub export` into a project")]
+    Import {
+        project_name: String,
+        bundle: PathBuf,
+        #[arg(long, help = "Replace definitions that already exist in the project")]
+        overwrite: bool,
+    },
+    #[command(about = "Run the pipeline without starting the server")]
+    Run {
+        project_name: String,
+        #[arg(long, help = "Run only this node and its upstream dependencies")]
+        node: Option<String>,
+        #[arg(long, help = "Load incrementally instead of doing a full refresh")]
+        incremental: bool,
+        #[arg(long, help = "Print task events as newline-delimited JSON")]
+        json: bool,
+        #[arg(
+            long,
+            help = "Number of tasks to run in parallel (defaults to CPU count)"
+        )]
+        max_workers: Option<usize>,
+        #[arg(
+            long,
+            help = "Apply the project.<ENV>.yml overlay (defaults to $DUCKHUB_ENV)"
+        )]
+        env: Option<String>,
+        #[arg(
+            long = "var",
+            value_name = "NAME=VALUE",
+            value_parser = parse_var,
+            help = "Set a value for {{ var('NAME') }} in model SQL; may be repeated"
+        )]
+        vars: Vec<(String, String)>,
+    },
+    #[command(about = "Run SQL against the project's lakehouse")]
+    Query {
+        project_name: String,
+        #[arg(required_unless_present = "file", help = "SQL to run")]
+        sql: Option<String>,
+        #[arg(long, conflicts_with = "sql", help = "Read the SQL from a file")]
+        file: Option<PathBuf>,
+        #[arg(long, value_enum, default_value = "table", help = "Output format")]
+        format: OutputFormat,
+        #[arg(long, help = "Write the results to a file instead of stdout")]
+        output: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value_t = DEFAULT_MAX_WIDTH,
+            help = "Truncate values longer than this in table output"
+        )]
+        max_width: usize,
     },
 }
 
@@ -54,38 +170,116 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     let result = match &cli.command {
-        Commands::New { project_name } => {
-            let project_dir = std::env::current_dir()?.join(project_name);
-
-            if project_dir.exists() {
-                return Err(anyhow::anyhow!(
-                    "Directory '{}' already exists",
-                    project_dir.display()
-                ));
-            }
-
-            std::fs::create_dir_all(&project_dir)?;
-
-            let mut config = Config::new(project_dir.clone());
-            config
-                .add_project_setting(&ProjectConfig::default())?
-                .save()?;
-
-            create_gitignore(&project_dir)?;
-            create_secret_key(&project_dir)?;
-            create_samples(&mut config).await?;
-
-            println!("✓ Project '{project_name}' created successfully");
-            println!("  Run 'duckhub start {project_name}' to open the project");
-            Ok(())
+        Commands::New {
+            project_name,
+            template,
+        } => commands::new::execute_new(project_name, *template).await,
+        Commands::Init { path } => {
+            let project_dir = std::env::current_dir()?.join(path);
+            commands::init::execute_init(&project_dir).await
         }
-        Commands::Start { project_name, port } => {
+        Commands::Start {
+            project_name,
+            port,
+            host,
+            no_auth,
+            shutdown_grace,
+            env,
+        } => {
             let project_dir = std::env::current_dir()?.join(project_name);
-            let config = Config::new(project_dir);
+            let config = Config::new(project_dir).with_env(select_env(env));
 
             setup_tracing();
 
-            commands::start::execute_start(config, *port).await
+            commands::start::execute_start(
+                config,
+                host,
+                *port,
+                *no_auth,
+                std::time::Duration::from_secs(*shutdown_grace),
+            )
+            .await
+        }
+        Commands::RotateKey { project_name } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            commands::rotate_key::execute_rotate_key(&project_dir)
+        }
+        Commands::AdminToken { project_name } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            commands::admin_token::execute_admin_token(&project_dir)
+        }
+        Commands::Export {
+            project_name,
+            output,
+        } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            let output = output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(format!("{project_name}.tar.gz")));
+            commands::bundle::execute_export(&project_dir, &output)
+        }
+        Commands::Import {
+            project_name,
+            bundle,
+            overwrite,
+        } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            commands::bundle::execute_import(&project_dir, bundle, *overwrite).await
+        }
+        Commands::Run {
+            project_name,
+            node,
+            incremental,
+            json,
+            max_workers,
+            env,
+            vars,
+        } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            let options = commands::run::RunOptions {
+                node: node.clone(),
+                incremental: *incremental,
+                json: *json,
+                max_workers: *max_workers,
+                env: select_env(env),
+                vars: vars.iter().cloned().collect(),
+            };
+            match commands::run::execute_run(&project_dir, options, &mut std::io::stdout()).await {
+                Ok(true) => Ok(()),
+                Ok(false) => std::process::exit(1),
+                Err(err) => Err(err),
+            }
+        }
+        Commands::Query {
+            project_name,
+            sql,
+            file,
+            format,
+            output,
+            max_width,
+        } => {
+            let project_dir = std::env::current_dir()?.join(project_name);
+            let sql = match (sql, file) {
+                (Some(sql), _) => sql.clone(),
+                (None, Some(file)) => std::fs::read_to_string(file)?,
+                (None, None) => unreachable!("clap requires either SQL or --file"),
+            };
+            match output {
+                Some(path) => {
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                    execute_query(&project_dir, &sql, *format, *max_width, &mut file).await
+                }
+                None => {
+                    execute_query(
+                        &project_dir,
+                        &sql,
+                        *format,
+                        *max_width,
+                        &mut std::io::stdout(),
+                    )
+                    .await
+                }
+            }
         }
     };
 
@@ -1,6 +1,15 @@
-use crate::core::{
-    config::{Config, project::ProjectConfig},
-    graph::Graph,
+use crate::{
+    api::QueryLimiter,
+    commands::new::{create_gitignore, create_secret_key},
+    core::{
+        audit::AuditLog,
+        config::{Config, project::ProjectConfig},
+        ducklake::DuckLakeCache,
+        graph::Graph,
+        pipeline::ActivePipeline,
+        query_cache::QueryCache,
+        query_history::QueryHistory,
+    },
 };
 use axum::{Extension, Router};
 use axum_test::TestServer;
@@ -12,6 +21,12 @@ pub struct TestManager {
     temp_dir: TempDir,
     config: Arc<Mutex<Config>>,
     graph: Arc<Mutex<Graph>>,
+    ducklakes: Arc<DuckLakeCache>,
+    active: Arc<ActivePipeline>,
+    query_cache: Arc<QueryCache>,
+    query_history: Arc<QueryHistory>,
+    audit: Arc<AuditLog>,
+    limiter: Arc<QueryLimiter>,
 }
 
 impl TestManager {
@@ -35,6 +50,12 @@ impl TestManager {
             temp_dir,
             config: Arc::new(Mutex::new(config)),
             graph: Arc::new(Mutex::new(Graph::new(&project_dir))),
+            ducklakes: Arc::new(DuckLakeCache::default()),
+            active: Arc::new(ActivePipeline::default()),
+            query_cache: Arc::new(QueryCache::default()),
+            query_history: Arc::new(QueryHistory::default()),
+            audit: Arc::new(AuditLog::default()),
+            limiter: Arc::new(QueryLimiter::default()),
         }
     }
 
@@ -50,13 +71,31 @@ impl TestManager {
         self.graph.lock().await
     }
 
+    pub fn ducklakes(&self) -> &DuckLakeCache {
+        &self.ducklakes
+    }
+
+    pub fn active(&self) -> &ActivePipeline {
+        &self.active
+    }
+
+    pub fn query_cache(&self) -> &QueryCache {
+        &self.query_cache
+    }
+
     pub fn setup_server<F>(&self, routes: F) -> TestServer
     where
         F: FnOnce() -> Router,
     {
         let app = routes()
             .layer(Extension(self.config.clone()))
-            .layer(Extension(self.graph.clone()));
+            .layer(Extension(self.graph.clone()))
+            .layer(Extension(self.ducklakes.clone()))
+            .layer(Extension(self.active.clone()))
+            .layer(Extension(self.query_cache.clone()))
+            .layer(Extension(self.query_history.clone()))
+            .layer(Extension(self.audit.clone()))
+            .layer(Extension(self.limiter.clone()));
 
         TestServer::new(app).unwrap()
     }
@@ -67,3 +106,15 @@ impl Default for TestManager {
         Self::new()
     }
 }
+
+// Writes `project` to a new project directory, along with its .gitignore and secret
+// key, and loads it.
+pub fn create_project(project_dir: &Path, project: &ProjectConfig) -> anyhow::Result<Config> {
+    std::fs::create_dir_all(project_dir)?;
+    let mut config = Config::new(project_dir.to_path_buf());
+    config.add_project_setting(project)?.save()?;
+    create_gitignore(project_dir)?;
+    create_secret_key(project_dir)?;
+    config.load()?;
+    Ok(config)
+}
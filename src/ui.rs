@@ -82,16 +82,22 @@ mod production_server {
         }
     }
 
-    pub async fn start() -> Result<()> {
+    pub async fn start(host: &str, base_path: &str) -> Result<()> {
         println!("Starting UI production server on port 8015...");
 
-        let app = Router::new().fallback(static_handler);
+        // Behind a reverse proxy the assets live under the same prefix as the API.
+        let assets = Router::new().fallback(static_handler);
+        let app = if base_path.is_empty() {
+            assets
+        } else {
+            Router::new().nest(base_path, assets)
+        };
 
-        let listener = TcpListener::bind("localhost:8015")
+        let listener = TcpListener::bind((host, 8015))
             .await
-            .context("Failed to bind UI server to localhost:8015")?;
+            .with_context(|| format!("Failed to bind UI server to {host}:8015"))?;
 
-        println!("✓ UI production server started on http://localhost:8015");
+        println!("✓ UI production server started on http://{host}:8015{base_path}/");
 
         axum::serve(listener, app)
             .await
@@ -101,14 +107,15 @@ mod production_server {
     }
 }
 
-pub async fn start_ui_server() -> Result<()> {
+pub async fn start_ui_server(host: &str, base_path: &str) -> Result<()> {
     #[cfg(debug_assertions)]
     {
+        let _ = (host, base_path);
         debug_server::start().await
     }
 
     #[cfg(not(debug_assertions))]
     {
-        production_server::start().await
+        production_server::start(host, base_path).await
     }
 }
@@ -1,17 +1,23 @@
 import { z } from 'zod';
-const API_BASE_URL = 'http://localhost:3015';
+// Separately hosted frontends point at the backend (including any base path) at build time.
+const API_BASE_URL = import.meta.env.VITE_API_BASE_URL ?? 'http://localhost:3015';
 
 const AdapterSummarySchema = z.object({
   name: z.string(),
   description: z.string().nullable().optional(),
   connection: z.string(),
   source_type: z.string(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
 });
 
 const FileConfigSchema = z.object({
   path: z.string(),
   compression: z.string().nullable().optional(),
   max_batch_size: z.string().nullable().optional(),
+  max_batch_files: z.number().int().positive().nullable().optional(),
+  incremental: z.boolean().optional(),
+  import_mode: z.enum(['full', 'modified', 'content']).optional(),
 });
 
 const FormatConfigSchema = z.object({
@@ -19,6 +25,20 @@ const FormatConfigSchema = z.object({
   delimiter: z.string().nullable().optional(),
   null_value: z.string().nullable().optional(),
   has_header: z.boolean().nullable().optional(),
+  sheet: z.string().nullable().optional(),
+  columns_spec: z
+    .array(z.object({ name: z.string(), start: z.number(), len: z.number() }))
+    .optional(),
+  quote: z.string().optional(),
+  escape: z.string().optional(),
+  date_format: z.string().optional(),
+  timestamp_format: z.string().optional(),
+});
+
+const IncrementalConfigSchema = z.object({
+  cursor_column: z.string(),
+  unique_key: z.array(z.string()),
+  deleted_flag: z.string().optional(),
 });
 
 const AdapterSourceSchema = z.object({
@@ -26,12 +46,16 @@ const AdapterSourceSchema = z.object({
   file: FileConfigSchema.optional(),
   format: FormatConfigSchema.optional(),
   table_name: z.string().optional(),
+  incremental: IncrementalConfigSchema.optional(),
 });
 
 const ColumnConfigSchema = z.object({
   name: z.string(),
   type: z.string(),
   description: z.string().nullable().optional(),
+  rename: z.string().nullable().optional(),
+  enforce: z.boolean().optional(),
+  lenient: z.boolean().optional(),
 });
 
 const ColumnInfoSchema = z.object({
@@ -39,22 +63,93 @@ const ColumnInfoSchema = z.object({
   data_type: z.string(),
 });
 
+const InferenceReportSchema = z.object({
+  sampled_rows: z.number(),
+  findings: z.array(
+    z.object({
+      column: z.string(),
+      inferred_type: z.string(),
+      issue: z.enum(['leading_zeros', 'date_as_text', 'mostly_null']),
+      message: z.string(),
+      suggestion: ColumnConfigSchema.nullable(),
+    }),
+  ),
+  suggested_columns: z.array(ColumnConfigSchema),
+});
+
+export type InferenceReport = z.infer<typeof InferenceReportSchema>;
+
+const SchemaWithInferenceSchema = z.object({
+  columns: z.array(ColumnInfoSchema),
+  inference: InferenceReportSchema.nullable(),
+});
+
+export type SchemaWithInference = z.infer<typeof SchemaWithInferenceSchema>;
+
+const DataTestSchema = z.object({
+  column: z.string(),
+  type: z.enum(['not_null', 'unique', 'accepted_values']),
+  values: z.array(z.string()).optional(),
+});
+
+const RelationshipSchema = z.object({
+  column: z.string(),
+  references: z.object({ table: z.string(), column: z.string() }),
+  severity: z.enum(['error', 'warn']).optional(),
+});
+
+const MaskingSchema = z.object({
+  column: z.string(),
+  mask: z.enum(['full', 'partial', 'hash']),
+});
+
+const DedupeConfigSchema = z.object({
+  distinct: z.boolean().optional(),
+  unique_key: z.array(z.string()).optional(),
+  order_by: z.string().nullable().optional(),
+});
+
 const AdapterConfigSchema = z.object({
   connection: z.string(),
+  target_table: z.string().nullable().optional(),
+  dedupe: DedupeConfigSchema.nullable().optional(),
   description: z.string().nullable().optional(),
   source: AdapterSourceSchema,
   columns: z.array(ColumnConfigSchema),
+  tests: z.array(DataTestSchema).optional(),
+  relationships: z.array(RelationshipSchema).optional(),
+  masking: z.array(MaskingSchema).optional(),
+  on_schema_change: z
+    .enum(['fail', 'append_new_columns', 'ignore'])
+    .nullable()
+    .optional(),
+  weight: z.number().nullable().optional(),
+  exclusive: z.boolean().optional(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
 });
 
 const ModelSummarySchema = z.object({
   name: z.string(),
   description: z.string().nullable().optional(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
 });
 
 const ModelConfigSchema = z.object({
   sql: z.string(),
   description: z.string().nullable().optional(),
   depends: z.array(z.string()).optional(),
+  materialization: z.enum(['table', 'view', 'incremental']).optional(),
+  unique_key: z.string().nullable().optional(),
+  updated_at_column: z.string().nullable().optional(),
+  tests: z.array(DataTestSchema).optional(),
+  relationships: z.array(RelationshipSchema).optional(),
+  masking: z.array(MaskingSchema).optional(),
+  weight: z.number().nullable().optional(),
+  exclusive: z.boolean().optional(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
 });
 
 const ConnectionSummarySchema = z.object({
@@ -79,6 +174,8 @@ const MySqlConnectionConfigSchema = z.object({
   password: z.union([
     z.object({ type: z.literal('plain'), value: z.string() }),
     z.object({ type: z.literal('encrypted'), value: z.string() }),
+    z.object({ type: z.literal('env'), name: z.string() }),
+    z.object({ type: z.literal('file'), path: z.string() }),
   ]),
 });
 
@@ -90,6 +187,8 @@ const PostgreSqlConnectionConfigSchema = z.object({
   password: z.union([
     z.object({ type: z.literal('plain'), value: z.string() }),
     z.object({ type: z.literal('encrypted'), value: z.string() }),
+    z.object({ type: z.literal('env'), name: z.string() }),
+    z.object({ type: z.literal('file'), path: z.string() }),
   ]),
 });
 
@@ -103,6 +202,8 @@ const S3ConnectionConfigSchema = z.object({
     .union([
       z.object({ type: z.literal('plain'), value: z.string() }),
       z.object({ type: z.literal('encrypted'), value: z.string() }),
+      z.object({ type: z.literal('env'), name: z.string() }),
+      z.object({ type: z.literal('file'), path: z.string() }),
     ])
     .nullable()
     .optional(),
@@ -145,11 +246,22 @@ const ConnectionConfigSchema = z.discriminatedUnion('type', [
 const QuerySummarySchema = z.object({
   name: z.string(),
   description: z.string().nullable().optional(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
+});
+
+const ParamDefSchema = z.object({
+  name: z.string(),
+  type: z.enum(['date', 'int', 'float', 'string']),
+  default: z.union([z.string(), z.number()]).nullable().optional(),
 });
 
 const QueryConfigSchema = z.object({
   description: z.string().nullable().optional(),
   sql: z.string(),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
+  parameters: z.array(ParamDefSchema).optional(),
 });
 
 // Connection test API用の型定義
@@ -183,21 +295,84 @@ export type TestConnectionConfig =
       path_style_access: boolean;
     };
 
+const CapabilityCheckSchema = z.object({
+  status: z.enum(['ok', 'denied', 'failed', 'skipped']),
+  message: z.string().optional(),
+});
+
+const ConnectionTestResultSchema = z.object({
+  success: z.boolean(),
+  checks: z.record(z.string(), CapabilityCheckSchema),
+});
+
+export type CapabilityCheck = z.infer<typeof CapabilityCheckSchema>;
+export type ConnectionTestResult = z.infer<typeof ConnectionTestResultSchema>;
+
 const DashboardSummarySchema = z.object({
   name: z.string(),
   description: z.string().nullable().optional(),
-  query_name: z.string().optional(),
-  chart_type: z.string().optional(),
+  panels: z.array(
+    z.object({
+      title: z.string().nullable().optional(),
+      query: z.string(),
+      chart_type: z.string(),
+    }),
+  ),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
 });
 
-const DashboardConfigSchema = z.object({
-  description: z.string().nullable().optional(),
+const PanelConfigSchema = z.object({
+  title: z.string().nullable().optional(),
   query: z.string(),
   chart: z.object({
-    type: z.enum(['line', 'bar']),
+    type: z.enum(['line', 'bar', 'pie', 'area', 'scatter']),
     x_column: z.string(),
     y_column: z.string(),
   }),
+  position: z
+    .object({
+      x: z.number(),
+      y: z.number(),
+      w: z.number(),
+      h: z.number(),
+    })
+    .optional(),
+});
+
+const DashboardConfigSchema = z.object({
+  description: z.string().nullable().optional(),
+  panels: z.array(PanelConfigSchema),
+  tags: z.array(z.string()).optional(),
+  group: z.string().nullable().optional(),
+});
+
+const ShareSchema = z.object({
+  id: z.string(),
+  dashboard: z.string(),
+  created_at: z.string(),
+  expires_at: z.string().nullable(),
+});
+
+const CreatedShareSchema = ShareSchema.extend({
+  token: z.string(),
+});
+
+const ProgressSchema = z.object({
+  files_done: z.number(),
+  files_total: z.number(),
+  bytes_done: z.number().optional(),
+  bytes_total: z.number().optional(),
+});
+
+const SchemaDiffSchema = z.object({
+  added: z
+    .array(z.object({ name: z.string(), data_type: z.string() }))
+    .optional(),
+  removed: z.array(z.string()).optional(),
+  retyped: z
+    .array(z.object({ name: z.string(), from: z.string(), to: z.string() }))
+    .optional(),
 });
 
 const TaskStatusSchema = z.object({
@@ -211,10 +386,27 @@ const TaskStatusSchema = z.object({
     })
     .nullable()
     .optional(),
+  duplicates_dropped: z.number().optional(),
+  files: z.object({ imported: z.number(), skipped: z.number() }).optional(),
+  schema_changes: SchemaDiffSchema.optional(),
+  tests: z
+    .array(
+      z.object({
+        column: z.string(),
+        type: z.string(),
+        passed: z.boolean(),
+        failing_rows: z.number(),
+        references: z.string().optional(),
+        severity: z.enum(['error', 'warn']).optional(),
+      }),
+    )
+    .optional(),
+  progress: ProgressSchema.optional(),
 });
 
 const PipelineSchema = z
   .object({
+    id: z.string().optional(),
     phase: z.string(),
     started_at: z.string().nullable().optional(),
     completed_at: z.string().nullable().optional(),
@@ -222,29 +414,102 @@ const PipelineSchema = z
   })
   .nullable();
 
+const PipelineListSchema = z.object({
+  pipelines: z.array(PipelineSchema),
+  issues: z.array(z.object({ file: z.string(), error: z.string() })),
+});
+
+export type PipelineList = z.infer<typeof PipelineListSchema>;
+
+const PipelineEventSchema = z.discriminatedUnion('type', [
+  z.object({
+    type: z.literal('snapshot'),
+    phase: z.string(),
+    tasks: z.record(z.string(), TaskStatusSchema),
+  }),
+  z.object({ type: z.literal('task_started'), task: z.string() }),
+  z.object({
+    type: z.literal('task_progress'),
+    task: z.string(),
+    progress: ProgressSchema,
+  }),
+  z.object({
+    type: z.literal('task_completed'),
+    task: z.string(),
+    row_count: z.number(),
+    duration_ms: z.number(),
+  }),
+  z.object({ type: z.literal('task_failed'), task: z.string(), error: z.string() }),
+  z.object({ type: z.literal('pipeline_completed') }),
+]);
+
+const LastRunSchema = z.object({
+  status: z.string().nullable(),
+  error: z.string().nullable(),
+  updated_at: z.string().nullable(),
+  row_count: z.number().nullable(),
+  duration_ms: z.number().nullable(),
+});
+
+const AdapterResponseSchema = AdapterConfigSchema.extend({
+  last_run: LastRunSchema.nullable(),
+});
+
+const ModelResponseSchema = ModelConfigSchema.extend({
+  last_run: LastRunSchema.nullable(),
+});
+
 const GraphNodeSchema = z.object({
   name: z.string(),
+  kind: z.enum(['adapter', 'model', 'seed']).nullable(),
   updated_at: z.string().nullable(),
   dependencies: z.array(z.string()),
+  phase: z.string().nullable(),
+  last_status: z.string().nullable(),
+  last_error: z.string().nullable(),
+  orphan: z.boolean(),
+});
+
+const GraphEdgeSchema = z.object({
+  from: z.string(),
+  to: z.string(),
 });
 
 const GraphDataSchema = z.object({
-  nodes: z.record(z.string(), GraphNodeSchema),
+  nodes: z.array(GraphNodeSchema),
+  edges: z.array(GraphEdgeSchema),
+});
+
+const NodeRunsSchema = z.object({
+  runs: z.array(
+    z.object({
+      pipeline_id: z.string(),
+      phase: z.string(),
+      started_at: z.string().nullable(),
+      completed_at: z.string().nullable(),
+      duration_ms: z.number().nullable(),
+      error: z.string().nullable(),
+    }),
+  ),
+  success_rate: z.number().nullable(),
+  median_duration_ms: z.number().nullable(),
 });
 
 export type GraphNode = z.infer<typeof GraphNodeSchema>;
-export type GraphEdge = {
-  from: string;
-  to: string;
-};
+export type GraphEdge = z.infer<typeof GraphEdgeSchema>;
+export type NodeRuns = z.infer<typeof NodeRunsSchema>;
 
 export type AdapterSummary = z.infer<typeof AdapterSummarySchema>;
 export type AdapterConfig = z.infer<typeof AdapterConfigSchema>;
+export type AdapterResponse = z.infer<typeof AdapterResponseSchema>;
 export type AdapterSource = z.infer<typeof AdapterSourceSchema>;
 export type ColumnInfo = z.infer<typeof ColumnInfoSchema>;
+export type DataTest = z.infer<typeof DataTestSchema>;
 
 export type ModelSummary = z.infer<typeof ModelSummarySchema>;
 export type ModelConfig = z.infer<typeof ModelConfigSchema>;
+export type ModelResponse = z.infer<typeof ModelResponseSchema>;
+export type LastRun = z.infer<typeof LastRunSchema>;
 
 export type ConnectionSummary = z.infer<typeof ConnectionSummarySchema>;
 export type LocalFileConnection = z.infer<typeof LocalFileConnectionSchema>;
@@ -256,35 +521,77 @@ export type ConnectionConfig = z.infer<typeof ConnectionConfigSchema>;
 
 export type QuerySummary = z.infer<typeof QuerySummarySchema>;
 export type QueryConfig = z.infer<typeof QueryConfigSchema>;
+export type ParamDef = z.infer<typeof ParamDefSchema>;
 
 export type DashboardSummary = z.infer<typeof DashboardSummarySchema>;
 export type DashboardConfig = z.infer<typeof DashboardConfigSchema>;
+export type PanelConfig = z.infer<typeof PanelConfigSchema>;
+export type Share = z.infer<typeof ShareSchema>;
+export type CreatedShare = z.infer<typeof CreatedShareSchema>;
 
 export type Pipeline = z.infer<typeof PipelineSchema>;
 export type TaskStatus = z.infer<typeof TaskStatusSchema>;
+export type PipelineEvent = z.infer<typeof PipelineEventSchema>;
 export type GraphData = z.infer<typeof GraphDataSchema>;
 
 export class ApiError extends Error {
-  constructor(message: string) {
+  constructor(
+    message: string,
+    public code?: string,
+    public details: Record<string, unknown> = {},
+  ) {
     super(message);
   }
 }
 
+const TOKEN_STORAGE_KEY = 'duckhub_api_token';
+
+// `duckhub start` opens the UI with `#token=...` when it generates a new token.
+function apiToken(): string | null {
+  if (typeof window === 'undefined') {
+    return null;
+  }
+  const match = window.location.hash.match(/token=([^&]+)/);
+  if (match) {
+    localStorage.setItem(TOKEN_STORAGE_KEY, match[1]);
+    history.replaceState(null, '', window.location.pathname + window.location.search);
+  }
+  return localStorage.getItem(TOKEN_STORAGE_KEY);
+}
+
 async function apiRequest(
   endpoint: string,
   options: RequestInit = {},
+  retry = true,
 ): Promise<Response> {
   const url = `${API_BASE_URL}${endpoint}`;
-  const response = await fetch(url, options);
+  const token = apiToken();
+  const headers = new Headers(options.headers);
+  if (token) {
+    headers.set('Authorization', `Bearer ${token}`);
+  }
+  const response = await fetch(url, { ...options, headers });
+
+  if (response.status === 401 && retry) {
+    const entered = window.prompt('Enter the DuckHub API token');
+    if (entered) {
+      localStorage.setItem(TOKEN_STORAGE_KEY, entered.trim());
+      return apiRequest(endpoint, options, false);
+    }
+  }
 
   if (!response.ok) {
     let errorMessage = `HTTP ${response.status}: ${response.statusText}`;
+    let errorCode: string | undefined;
+    let errorDetails: Record<string, unknown> = {};
 
     try {
       const contentType = response.headers.get('content-type');
       if (contentType && contentType.includes('application/json')) {
         const error = await response.json();
         errorMessage = error.message || errorMessage;
+        errorCode = error.code;
+        errorDetails = error.details ?? {};
       } else {
         const text = await response.text();
         errorMessage = text || errorMessage;
@@ -293,15 +600,49 @@ async function apiRequest(
       console.warn('Failed to parse error response:', parseError);
     }
 
-    throw new ApiError(errorMessage);
+    throw new ApiError(errorMessage, errorCode, errorDetails);
   }
 
   return response;
 }
 
+const ResolvedAdapterSchema = z.object({
+  name: z.string(),
+  config: AdapterConfigSchema,
+  // Secrets held in the config come back as "***".
+  connection: z.object({ type: z.string(), config: z.unknown() }),
+  location: z.string(),
+  target_table: z.string(),
+  source_table: z.string().optional(),
+  pattern: z.string().optional(),
+  format: FormatConfigSchema.optional(),
+  files: z
+    .object({
+      files: z.array(z.string()),
+      total: z.number(),
+      error: z.string().optional(),
+    })
+    .optional(),
+});
+
+export type ResolvedAdapter = z.infer<typeof ResolvedAdapterSchema>;
+
+export interface LabelFilter {
+  tag?: string;
+  group?: string;
+}
+
+function labelQuery(filter: LabelFilter = {}): string {
+  const params = new URLSearchParams();
+  if (filter.tag) params.set('tag', filter.tag);
+  if (filter.group) params.set('group', filter.group);
+  const query = params.toString();
+  return query ? `?${query}` : '';
+}
+
 const adapters = {
-  async list(): Promise<AdapterSummary[]> {
-    const response = await apiRequest('/api/adapters');
+  async list(filter?: LabelFilter): Promise<AdapterSummary[]> {
+    const response = await apiRequest(`/api/adapters${labelQuery(filter)}`);
     const data = await response.json();
     try {
       return z.array(AdapterSummarySchema).parse(data);
@@ -311,10 +652,16 @@ const adapters = {
     }
   },
 
-  async get(name: string): Promise<AdapterConfig> {
+  async get(name: string): Promise<AdapterResponse> {
     const response = await apiRequest(`/api/adapters/${name}`);
     const data = await response.json();
-    return AdapterConfigSchema.parse(data);
+    return AdapterResponseSchema.parse(data);
+  },
+
+  async resolved(name: string): Promise<ResolvedAdapter> {
+    const response = await apiRequest(`/api/adapters/${name}/resolved`);
+    const data = await response.json();
+    return ResolvedAdapterSchema.parse(data);
   },
 
   async create(data: { name: string; config: AdapterConfig }): Promise<void> {
@@ -333,12 +680,21 @@ const adapters = {
     });
   },
 
-  async delete(name: string): Promise<void> {
-    await apiRequest(`/api/adapters/${name}`, {
+  async delete(name: string, dropTable = false): Promise<void> {
+    const query = dropTable ? '?drop_table=true' : '';
+    await apiRequest(`/api/adapters/${name}${query}`, {
       method: 'DELETE',
     });
   },
 
+  async rename(name: string, newName: string): Promise<void> {
+    await apiRequest(`/api/adapters/${name}/rename`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ new_name: newName }),
+    });
+  },
+
   async getSchema(request: {
     connection: string;
     source: AdapterSource;
@@ -352,6 +708,20 @@ const adapters = {
     return z.array(ColumnInfoSchema).parse(data);
   },
 
+  async getSchemaWithInference(request: {
+    connection: string;
+    source: AdapterSource;
+    sample_rows?: number;
+  }): Promise<SchemaWithInference> {
+    const response = await apiRequest('/api/adapters/get-schema', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ ...request, inference: true }),
+    });
+    const data = await response.json();
+    return SchemaWithInferenceSchema.parse(data);
+  },
+
   async testSchema(request: {
     connection: string;
     source: AdapterSource;
@@ -366,16 +736,16 @@ const adapters = {
 };
 
 const models = {
-  async list(): Promise<ModelSummary[]> {
-    const response = await apiRequest('/api/models');
+  async list(filter?: LabelFilter): Promise<ModelSummary[]> {
+    const response = await apiRequest(`/api/models${labelQuery(filter)}`);
     const data = await response.json();
     return z.array(ModelSummarySchema).parse(data);
   },
 
-  async get(name: string): Promise<ModelConfig> {
+  async get(name: string): Promise<ModelResponse> {
     const response = await apiRequest(`/api/models/${name}`);
     const data = await response.json();
-    return ModelConfigSchema.parse(data);
+    return ModelResponseSchema.parse(data);
   },
 
   async create(data: { name: string; config: ModelConfig }): Promise<void> {
@@ -394,11 +764,20 @@ const models = {
     });
   },
 
-  async delete(name: string): Promise<void> {
-    await apiRequest(`/api/models/${name}`, {
+  async delete(name: string, dropTable = false): Promise<void> {
+    const query = dropTable ? '?drop_table=true' : '';
+    await apiRequest(`/api/models/${name}${query}`, {
       method: 'DELETE',
     });
   },
+
+  async rename(name: string, newName: string): Promise<void> {
+    await apiRequest(`/api/models/${name}/rename`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ new_name: newName }),
+    });
+  },
 };
 
 const connections = {
@@ -449,12 +828,17 @@ const connections = {
     });
   },
 
-  async test(config: TestConnectionConfig): Promise<void> {
-    await apiRequest('/api/connections/test', {
+  async test(
+    config: TestConnectionConfig,
+    options: { table?: string; name?: string } = {},
+  ): Promise<ConnectionTestResult> {
+    const response = await apiRequest('/api/connections/test', {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify(config),
+      body: JSON.stringify({ ...config, ...options }),
     });
+    const data = await response.json();
+    return ConnectionTestResultSchema.parse(data);
   },
 };
 
@@ -463,19 +847,59 @@ const QueryListResponseSchema = z.object({
 });
 
 export interface QueryResult {
-  data: Record<string, string[]>;
+  columns: string[];
+  types: string[];
+  data: Record<string, unknown[]>;
   row_count: number;
   column_count: number;
+  truncated: boolean;
 }
 
+const QueryPlanSchema = z.object({
+  plan: z.string(),
+  analyzed: z.boolean(),
+  operators: z
+    .array(
+      z.object({
+        name: z.string(),
+        depth: z.number(),
+        duration_ms: z.number().optional(),
+        rows: z.number().optional(),
+      }),
+    )
+    .optional(),
+});
+
+export type QueryPlan = z.infer<typeof QueryPlanSchema>;
+
+const QueryHistoryEntrySchema = z.object({
+  id: z.string(),
+  sql: z.string(),
+  started_at: z.string(),
+  duration_ms: z.number(),
+  row_count: z.number().nullable(),
+  success: z.boolean(),
+  error: z.string().nullable(),
+});
+
+export type QueryHistoryEntry = z.infer<typeof QueryHistoryEntrySchema>;
+
+const SaveQueryResponseSchema = z.object({
+  warnings: z.array(z.string()),
+});
+
+export type SaveQueryResponse = z.infer<typeof SaveQueryResponseSchema>;
+
 const queries = {
-  async list(): Promise<QuerySummary[]> {
-    const response = await apiRequest('/api/queries');
+  async list(filter?: LabelFilter): Promise<QuerySummary[]> {
+    const response = await apiRequest(`/api/queries${labelQuery(filter)}`);
     const data = await response.json();
     const listResponse = QueryListResponseSchema.parse(data);
     return Object.entries(listResponse.queries).map(([name, config]) => ({
       name,
       description: config.description,
+      tags: config.tags,
+      group: config.group,
     }));
   },
 
@@ -489,8 +913,8 @@ const queries = {
     name: string;
     description?: string;
     sql: string;
-  }): Promise<void> {
-    await apiRequest('/api/queries', {
+  }): Promise<SaveQueryResponse> {
+    const response = await apiRequest('/api/queries', {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
@@ -501,6 +925,7 @@ const queries = {
         },
       }),
     });
+    return SaveQueryResponseSchema.parse(await response.json());
   },
 
   async update(
@@ -509,16 +934,31 @@ const queries = {
       name: string;
       description?: string;
       sql: string;
+      parameters?: ParamDef[];
     },
-  ): Promise<void> {
-    await apiRequest(`/api/queries/${name}`, {
+  ): Promise<SaveQueryResponse> {
+    const response = await apiRequest(`/api/queries/${name}`, {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
         description: data.description,
         sql: data.sql,
+        parameters: data.parameters,
       }),
     });
+    return SaveQueryResponseSchema.parse(await response.json());
+  },
+
+  async run(
+    name: string,
+    params: Record<string, string | number> = {},
+  ): Promise<QueryResult> {
+    const response = await apiRequest(`/api/queries/${name}/run`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ params }),
+    });
+    return response.json();
   },
 
   async delete(name: string): Promise<void> {
@@ -527,19 +967,55 @@ const queries = {
     });
   },
 
-  async execute(sql: string): Promise<QueryResult> {
+  async rename(name: string, newName: string): Promise<void> {
+    await apiRequest(`/api/queries/${name}/rename`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ new_name: newName }),
+    });
+  },
+
+  // `allowWrites` only takes effect when the project sets `server.allow_writes`.
+  async execute(sql: string, allowWrites = false): Promise<QueryResult> {
     const response = await apiRequest('/api/query', {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ sql }),
+      body: JSON.stringify({
+        sql,
+        timeout_secs: timeoutSecs,
+        allow_writes: allowWrites,
+      }),
+    });
+    return response.json();
+  },
+
+  async explain(sql: string, analyze = false): Promise<QueryPlan> {
+    const response = await apiRequest('/api/query/explain', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ sql, analyze, timeout_secs: timeoutSecs }),
+    });
+    const data = await response.json();
+    return QueryPlanSchema.parse(data);
+  },
+
+  async history(limit = 50): Promise<QueryHistoryEntry[]> {
+    const response = await apiRequest(`/api/query/history?limit=${limit}`);
+    const data = await response.json();
+    return z.array(QueryHistoryEntrySchema).parse(data);
+  },
+
+  async replay(id: string): Promise<QueryResult> {
+    const response = await apiRequest(`/api/query/history/${id}/replay`, {
+      method: 'POST',
     });
     return response.json();
   },
 };
 
 const dashboards = {
-  async list(): Promise<DashboardSummary[]> {
-    const response = await apiRequest('/api/dashboards');
+  async list(filter?: LabelFilter): Promise<DashboardSummary[]> {
+    const response = await apiRequest(`/api/dashboards${labelQuery(filter)}`);
     const data = await response.json();
     return z.array(DashboardSummarySchema).parse(data);
   },
@@ -550,8 +1026,17 @@ const dashboards = {
     return DashboardConfigSchema.parse(data);
   },
 
-  async getData(name: string): Promise<{ labels: object[]; values: object[] }> {
-    const response = await apiRequest(`/api/dashboards/${name}/data`);
+  async getPanelData(
+    name: string,
+    index: number,
+  ): Promise<{
+    labels: object[];
+    values: object[];
+    points?: { x: number; y: number }[];
+  }> {
+    const response = await apiRequest(
+      `/api/dashboards/${name}/panels/${index}/data`,
+    );
     return response.json();
   },
 
@@ -576,13 +1061,43 @@ const dashboards = {
       method: 'DELETE',
     });
   },
+
+  async rename(name: string, newName: string): Promise<void> {
+    await apiRequest(`/api/dashboards/${name}/rename`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ new_name: newName }),
+    });
+  },
+
+  async listShares(name: string): Promise<Share[]> {
+    const response = await apiRequest(`/api/dashboards/${name}/share`);
+    const data = await response.json();
+    return z.array(ShareSchema).parse(data);
+  },
+
+  async share(name: string, expiresAt?: string): Promise<CreatedShare> {
+    const response = await apiRequest(`/api/dashboards/${name}/share`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ expires_at: expiresAt ?? null }),
+    });
+    const data = await response.json();
+    return CreatedShareSchema.parse(data);
+  },
+
+  async revokeShare(name: string, id: string): Promise<void> {
+    await apiRequest(`/api/dashboards/${name}/share/${id}`, {
+      method: 'DELETE',
+    });
+  },
 };
 
 const pipeline = {
-  async listPipelines(): Promise<Pipeline[]> {
+  async listPipelines(): Promise<PipelineList> {
     const response = await apiRequest('/api/pipelines');
     const data = await response.json();
-    return data;
+    return PipelineListSchema.parse(data);
   },
 
   async getStatus(): Promise<Pipeline> {
@@ -591,23 +1106,64 @@ const pipeline = {
     return PipelineSchema.parse(data);
   },
 
+  async getTaskLog(id: string, task: string, tail?: number): Promise<string> {
+    const query = tail ? `?tail=${tail}` : '';
+    const response = await apiRequest(
+      `/api/pipelines/${encodeURIComponent(id)}/tasks/${encodeURIComponent(task)}/logs${query}`
+    );
+    return response.text();
+  },
+
+  subscribe(onEvent: (event: PipelineEvent) => void): EventSource {
+    const token = apiToken();
+    const query = token ? `?access_token=${encodeURIComponent(token)}` : '';
+    const source = new EventSource(`${API_BASE_URL}/api/pipelines/current/events${query}`);
+    source.onmessage = (message) => {
+      const event = PipelineEventSchema.parse(JSON.parse(message.data));
+      onEvent(event);
+      if (event.type === 'pipeline_completed') {
+        source.close();
+      }
+    };
+    return source;
+  },
+
   async getGraph(): Promise<GraphData> {
     const response = await apiRequest('/api/graph');
     const data = await response.json();
     return GraphDataSchema.parse(data);
   },
 
-  async run(): Promise<void> {
-    await apiRequest('/api/pipeline/run', {
+  async getNodeRuns(name: string, limit?: number): Promise<NodeRuns> {
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await apiRequest(
+      `/api/nodes/${encodeURIComponent(name)}/runs${query}`,
+    );
+    return NodeRunsSchema.parse(await response.json());
+  },
+
+  async run(
+    fullRefresh = false,
+    vars?: Record<string, string>,
+  ): Promise<void> {
+    const query = fullRefresh ? '?full_refresh=true' : '';
+    await apiRequest(`/api/pipeline/run${query}`, {
       method: 'POST',
+      ...(vars && {
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ vars }),
+      }),
     });
   },
 
-  async runNode(nodeName: string): Promise<void> {
+  async runNode(
+    nodeName: string,
+    vars?: Record<string, string>,
+  ): Promise<void> {
     await apiRequest('/api/pipeline/run-node', {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ node_name: nodeName }),
+      body: JSON.stringify({ node_name: nodeName, vars }),
     });
   },
 
@@ -618,6 +1174,197 @@ const pipeline = {
   },
 };
 
+export interface ValidationFinding {
+  severity: 'error' | 'warning';
+  kind:
+    | 'project'
+    | 'missing_connection'
+    | 'invalid_model_sql'
+    | 'unknown_table'
+    | 'missing_query'
+    | 'orphan_node'
+    | 'unknown_relationship'
+    | 'invalid_config_file';
+  name: string;
+  message: string;
+}
+
+const ReloadSummarySchema = z.object({
+  changed: z.array(z.string()),
+  removed: z.array(z.string()),
+  errors: z.array(z.object({ path: z.string(), message: z.string() })),
+  graph: z.object({
+    added: z.array(z.string()),
+    removed: z.array(z.string()),
+    changed: z.array(z.string()),
+  }),
+});
+
+export type ReloadSummary = z.infer<typeof ReloadSummarySchema>;
+
+const project = {
+  async validate(): Promise<{ findings: ValidationFinding[] }> {
+    const response = await apiRequest('/api/project/validate');
+    return response.json();
+  },
+
+  async reload(): Promise<ReloadSummary> {
+    const response = await apiRequest('/api/project/reload', {
+      method: 'POST',
+    });
+    return ReloadSummarySchema.parse(await response.json());
+  },
+
+  async reconnect(): Promise<{ connected: boolean }> {
+    const response = await apiRequest('/api/project/reconnect', {
+      method: 'POST',
+    });
+    return z.object({ connected: z.boolean() }).parse(await response.json());
+  },
+
+  async getSettings(): Promise<{ storage: unknown; database: unknown }> {
+    const response = await apiRequest('/api/project/settings');
+    return response.json();
+  },
+
+  async updateSettings(settings: {
+    storage: unknown;
+    database: unknown;
+  }): Promise<void> {
+    await apiRequest('/api/project/settings', {
+      method: 'PUT',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(settings),
+    });
+  },
+};
+
+const CleanupResponseSchema = z.object({
+  tables: z.array(z.string()),
+  applied: z.boolean(),
+});
+
+export type CleanupResponse = z.infer<typeof CleanupResponseSchema>;
+
+const StorageStatsSchema = z.object({
+  snapshots: z.number(),
+  data_files: z.number(),
+  data_bytes: z.number(),
+  pending_deletion: z.number(),
+});
+
+const CompactionReportSchema = z.object({
+  dry_run: z.boolean(),
+  older_than_days: z.number(),
+  expired_snapshots: z.number(),
+  deleted_files: z.number(),
+  before: StorageStatsSchema,
+  after: StorageStatsSchema,
+  files_reclaimed: z.number(),
+  bytes_reclaimed: z.number(),
+});
+
+export type CompactionReport = z.infer<typeof CompactionReportSchema>;
+
+const maintenance = {
+  async cleanup(apply = false): Promise<CleanupResponse> {
+    const response = await apiRequest('/api/maintenance/cleanup', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ apply }),
+    });
+    const data = await response.json();
+    return CleanupResponseSchema.parse(data);
+  },
+
+  async compact(
+    olderThanDays: number,
+    dryRun = false,
+  ): Promise<CompactionReport> {
+    const response = await apiRequest('/api/maintenance/compact', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        older_than_days: olderThanDays,
+        dry_run: dryRun,
+      }),
+    });
+    const data = await response.json();
+    return CompactionReportSchema.parse(data);
+  },
+};
+
+export type BulkOperation =
+  | { kind: 'model'; op: 'upsert'; name: string; config: ModelConfig }
+  | { kind: 'adapter'; op: 'upsert'; name: string; config: AdapterConfig }
+  | { kind: 'model' | 'adapter'; op: 'delete'; name: string };
+
+const BulkItemResultSchema = z.object({
+  kind: z.enum(['model', 'adapter']),
+  op: z.enum(['upsert', 'delete']),
+  name: z.string(),
+  status: z.enum(['applied', 'valid', 'failed']),
+  code: z.string().optional(),
+  message: z.string().optional(),
+});
+
+export type BulkItemResult = z.infer<typeof BulkItemResultSchema>;
+
+// A rejected batch throws an ApiError whose `details.results` parses with
+// `parseBulkResults`.
+export function parseBulkResults(details: Record<string, unknown>) {
+  return z.array(BulkItemResultSchema).parse(details.results ?? []);
+}
+
+async function bulk(operations: BulkOperation[]): Promise<BulkItemResult[]> {
+  const response = await apiRequest('/api/bulk', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(operations),
+  });
+  const data = await response.json();
+  return z.array(BulkItemResultSchema).parse(data.results);
+}
+
+const AuditEntrySchema = z.object({
+  id: z.string(),
+  timestamp: z.string(),
+  resource: z.string(),
+  operation: z.enum(['create', 'update', 'delete']),
+  before: z.string().nullable(),
+  after: z.string().nullable(),
+  principal: z.string().nullable(),
+});
+
+const AuditDiffSchema = z.object({
+  id: z.string(),
+  resource: z.string(),
+  operation: z.enum(['create', 'update', 'delete']),
+  diff: z.string(),
+});
+
+export type AuditEntry = z.infer<typeof AuditEntrySchema>;
+export type AuditDiff = z.infer<typeof AuditDiffSchema>;
+
+const audit = {
+  // `resource` is either one resource, e.g. `models/orders`, or a kind.
+  async list(resource?: string, limit = 50): Promise<AuditEntry[]> {
+    const params = new URLSearchParams({ limit: String(limit) });
+    if (resource) {
+      params.set('resource', resource);
+    }
+    const response = await apiRequest(`/api/audit?${params}`);
+    const data = await response.json();
+    return z.array(AuditEntrySchema).parse(data);
+  },
+
+  async diff(id: string): Promise<AuditDiff> {
+    const response = await apiRequest(`/api/audit/${id}/diff`);
+    const data = await response.json();
+    return AuditDiffSchema.parse(data);
+  },
+};
+
 export const api = {
   adapters,
   models,
@@ -625,6 +1372,10 @@ export const api = {
   queries,
   dashboards,
   pipeline,
+  project,
+  maintenance,
+  bulk,
+  audit,
 } as const;
 
 export default api;